@@ -2,9 +2,34 @@
 fn scan_path(path: String) -> Result<Vec<jozin_core::Sidecar>, String> {
   jozin_core::api::scan_path(&path).map_err(|e| e.to_string())
 }
+#[tauri::command]
+fn scan_with_progress(window: tauri::Window, path: String) -> Result<jozin_core::ScanResult, String> {
+  use jozin_core::util::progress_tee::tee_progress;
+  use jozin_core::util::progress_throttle::throttled_progress;
+  use jozin_core::ProgressEvent;
+
+  // Progress goes to the frontend (throttled for redraws) and to the stderr log
+  let tauri_emit_fn = move |event: ProgressEvent| {
+    let _ = window.emit("scan-progress", format!("{:?}", event));
+  };
+  let stderr_log_fn = |event: ProgressEvent| eprintln!("{:?}", event);
+  let progress = tee_progress(throttled_progress(tauri_emit_fn, 30.0), stderr_log_fn);
+  let config = jozin_core::ScanConfig::default();
+  jozin_core::scan_path(std::path::Path::new(&path), &config, Some(&progress)).map_err(|e| e.to_string())
+}
+// "Undo last scan": each scanned file's `backup_path` is the `.bak1` to put back
+#[tauri::command]
+fn restore_from_backup(sidecar_path: String) -> Result<jozin_core::util::backup_restore::RestoredFrom, String> {
+  jozin_core::util::backup_restore::restore_from_backup(std::path::Path::new(&sidecar_path), 1).map_err(|e| e.to_string())
+}
+#[tauri::command]
+fn suggest_tags(path: String, mode: jozin_core::tags::TagMode, min_score: f32) -> Result<Vec<jozin_core::Tag>, String> {
+  let sidecar = jozin_core::scan::read_sidecar(std::path::Path::new(&path)).map_err(|e| e.to_string())?;
+  jozin_core::tags::suggest_tags(&sidecar, mode, min_score).map_err(|e| e.to_string())
+}
 fn main() {
   tauri::Builder::default()
-    .invoke_handler(tauri::generate_handler![scan_path])
+    .invoke_handler(tauri::generate_handler![scan_path, scan_with_progress, restore_from_backup, suggest_tags])
     .run(tauri::generate_context!())
     .expect("error while running tauri application");
 }
@@ -0,0 +1,55 @@
+#!/bin/bash
+# Benchmark regression check for Jožin
+# Compares the bench_harness suite against a stored criterion baseline and
+# fails if any benchmark got more than 10% slower.
+#
+# Save a baseline (e.g. on main):  ./bench_regression.sh --save main
+# Check against it (e.g. in CI):   ./bench_regression.sh main
+
+set -e  # Exit on error
+
+THRESHOLD="0.10"
+FEATURES="test-helpers,thumbs"
+CRITERION_DIR="target/criterion"
+
+if [ "$1" = "--save" ]; then
+    BASELINE="${2:-main}"
+    echo "💾 Saving benchmark baseline '$BASELINE'..."
+    cargo bench -p jozin-core --features "$FEATURES" --bench bench_harness -- --save-baseline "$BASELINE"
+    exit 0
+fi
+
+BASELINE="${1:-main}"
+
+if ! find "$CRITERION_DIR" -type d -name "$BASELINE" 2>/dev/null | grep -q .; then
+    echo "❌ No baseline '$BASELINE' in $CRITERION_DIR (run: $0 --save $BASELINE)"
+    exit 1
+fi
+
+echo "⏱️  Running benchmarks against baseline '$BASELINE'..."
+cargo bench -p jozin-core --features "$FEATURES" --bench bench_harness -- --baseline "$BASELINE"
+echo ""
+
+# criterion writes the relative change of each benchmark's mean to
+# <group>/<benchmark>/change/estimates.json
+echo "🔍 Checking for regressions > ${THRESHOLD}..."
+python3 - "$CRITERION_DIR" "$THRESHOLD" <<'PY'
+import json, pathlib, sys
+
+root, threshold = pathlib.Path(sys.argv[1]), float(sys.argv[2])
+regressions = []
+for estimates in sorted(root.glob("**/change/estimates.json")):
+    change = json.loads(estimates.read_text())["mean"]["point_estimate"]
+    name = estimates.parent.parent.relative_to(root)
+    print(f"   {change:+7.1%}  {name}")
+    if change > threshold:
+        regressions.append(name)
+
+if regressions:
+    print(f"\n❌ {len(regressions)} benchmark(s) regressed by more than {threshold:.0%}:")
+    for name in regressions:
+        print(f"   {name}")
+    sys.exit(1)
+PY
+
+echo "   ✅ No regressions"
@@ -17,9 +17,17 @@
 //! See `TASK+PHASE_PLAN.md` for complete parameter specifications.
 
 use clap::{Args, Parser, Subcommand, ValueEnum};
-use jozin_core::{JozinError, Result, OperationResponse};
+use jozin_core::util::checksum_journal::ChecksumJournal;
+use jozin_core::util::fsync_policy::FsyncPolicy;
+use jozin_core::util::glob_expander::{expand_braces, split_pattern_list};
+use jozin_core::util::library_config::LibraryConfig;
+use jozin_core::util::library_lock::LibraryLock;
+use jozin_core::util::progress_file::ProgressFileWriter;
+use jozin_core::util::report_builder::{ReportBuilder, ReportFormat};
+use jozin_core::util::sidecar_index::SidecarIndex;
+use jozin_core::{JozinError, Result, OperationResponse, ScanAction, ScanConfig};
 use serde::Serialize;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::exit;
 use time::OffsetDateTime;
 
@@ -50,6 +58,21 @@ fn determine_output_format(json_flag: bool) -> OutputFormat {
     }
 }
 
+/// Milliseconds between two instants, for report footers
+fn duration_ms(start: OffsetDateTime, end: OffsetDateTime) -> u64 {
+    (end - start).whole_milliseconds().max(0) as u64
+}
+
+/// EXIF quality indicator, e.g. `EXIF: 87% date ✓ | 34% GPS ✓ | 95% camera ✓`
+fn exif_coverage_line(coverage: &jozin_core::scan::ExifCoverage) -> String {
+    format!(
+        "EXIF: {}% date ✓ | {}% GPS ✓ | {}% camera ✓",
+        coverage.percent(coverage.has_datetime),
+        coverage.percent(coverage.has_gps),
+        coverage.percent(coverage.has_camera)
+    )
+}
+
 // ============================================================================
 // Value Enums for Type-Safe Options
 // ============================================================================
@@ -77,6 +100,122 @@ impl HashMode {
     }
 }
 
+/// Content hash algorithm for scan module
+#[derive(Clone, Copy, Debug, ValueEnum, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum HashAlgorithmArg {
+    /// BLAKE3 (default, fastest)
+    Blake3,
+    /// SHA-256 (comparable with exiftool and other tools)
+    Sha256,
+}
+
+impl From<HashAlgorithmArg> for jozin_core::util::digest::HashAlgorithm {
+    fn from(arg: HashAlgorithmArg) -> Self {
+        match arg {
+            HashAlgorithmArg::Blake3 => Self::Blake3,
+            HashAlgorithmArg::Sha256 => Self::Sha256,
+        }
+    }
+}
+
+/// Symlink handling for scan and verify traversal
+#[derive(Clone, Copy, Debug, ValueEnum, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum SymlinkArg {
+    /// Follow symlinks (default); loops are detected and reported
+    Follow,
+    /// Don't follow symlinks; symlinked files are skipped
+    Skip,
+    /// Fail on the first symlink
+    Error,
+}
+
+impl From<SymlinkArg> for jozin_core::util::symlink::SymlinkStrategy {
+    fn from(arg: SymlinkArg) -> Self {
+        match arg {
+            SymlinkArg::Follow => Self::Follow,
+            SymlinkArg::Skip => Self::Skip,
+            SymlinkArg::Error => Self::Error,
+        }
+    }
+}
+
+/// Order of files in scan output
+#[derive(Clone, Copy, Debug, ValueEnum, Serialize)]
+#[serde(rename_all = "kebab-case")]
+enum SortArg {
+    /// By path
+    Path,
+    /// By capture time, oldest first
+    DateOldest,
+    /// By capture time, newest first
+    DateNewest,
+    /// By file size, smallest first
+    SizeSmallest,
+    /// By file size, largest first
+    SizeLargest,
+}
+
+impl From<SortArg> for jozin_core::util::sidecar_sorter::SortOrder {
+    fn from(arg: SortArg) -> Self {
+        match arg {
+            SortArg::Path => Self::PathAlphabetical,
+            SortArg::DateOldest => Self::DateOldestFirst,
+            SortArg::DateNewest => Self::DateNewestFirst,
+            SortArg::SizeSmallest => Self::SizeSmallestFirst,
+            SortArg::SizeLargest => Self::SizeLargestFirst,
+        }
+    }
+}
+
+/// Order in which `scan` processes directories
+#[derive(Clone, Copy, Debug, ValueEnum, Serialize)]
+#[serde(rename_all = "kebab-case")]
+#[allow(clippy::enum_variant_names)] // the names are the option values
+enum TraversalOrderArg {
+    /// As the directory walk yields files
+    DepthFirst,
+    /// Directory by directory, shallower directories first
+    BreadthFirst,
+    /// Directory by directory, largest total size first
+    LargestFirst,
+}
+
+impl From<TraversalOrderArg> for jozin_core::util::traversal_order::TraversalOrder {
+    fn from(arg: TraversalOrderArg) -> Self {
+        match arg {
+            TraversalOrderArg::DepthFirst => Self::DepthFirst,
+            TraversalOrderArg::BreadthFirst => Self::BreadthFirst,
+            TraversalOrderArg::LargestFirst => Self::LargestFirst,
+        }
+    }
+}
+
+/// Merge strategy for `faces --import-embeddings`
+#[derive(Clone, Copy, Debug, ValueEnum, Serialize)]
+#[serde(rename_all = "kebab-case")]
+enum MergeStrategyArg {
+    /// Overwrite existing persons with the imported embedding
+    Replace,
+    /// Keep existing persons unchanged
+    KeepExisting,
+    /// Average existing and imported embeddings (weighted by sample count)
+    Average,
+}
+
+/// Redaction method for `faces --anonymise-batch`
+#[derive(Clone, Copy, Debug, ValueEnum, Serialize)]
+#[serde(rename_all = "kebab-case")]
+enum AnonymiseMethodArg {
+    /// Replace faces with blocks of --block-size pixels
+    Pixelate,
+    /// Gaussian blur
+    Blur,
+    /// Solid black rectangle
+    Fill,
+}
+
 /// Tagging mode for tags module
 #[derive(Clone, Debug, ValueEnum, Serialize)]
 #[serde(rename_all = "lowercase")]
@@ -107,6 +246,11 @@ enum ThumbFormat {
     Jpg,
     /// WebP format
     Webp,
+    /// JPEG XL format (not supported yet)
+    Jxl,
+    /// JPEG XL, lossless transcode of JPEG sources (not supported yet)
+    #[serde(rename = "jxl-from-jpeg")]
+    JxlFromJpeg,
 }
 
 impl ThumbFormat {
@@ -114,10 +258,58 @@ impl ThumbFormat {
         match self {
             ThumbFormat::Jpg => "jpg",
             ThumbFormat::Webp => "webp",
+            ThumbFormat::Jxl => "jxl",
+            ThumbFormat::JxlFromJpeg => "jxl-from-jpeg",
+        }
+    }
+}
+
+/// Tone mapping operator for HDR sources in the thumbs module
+#[derive(Clone, Debug, ValueEnum, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum ThumbTonemap {
+    /// No tone mapping
+    None,
+    /// Reinhard curve (default)
+    Reinhard,
+    /// Hable's filmic curve
+    Filmic,
+}
+
+impl ThumbTonemap {
+    fn as_str(&self) -> &'static str {
+        match self {
+            ThumbTonemap::None => "none",
+            ThumbTonemap::Reinhard => "reinhard",
+            ThumbTonemap::Filmic => "filmic",
         }
     }
 }
 
+/// Source application format for the import command
+#[derive(Clone, Debug, ValueEnum, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum ImportFormat {
+    /// Adobe Lightroom Classic catalog (.lrcat)
+    Lightroom,
+    /// digiKam database (digikam4.db)
+    Digikam,
+}
+
+/// Output format for the export command
+#[derive(Clone, Debug, ValueEnum, Serialize)]
+#[serde(rename_all = "kebab-case")]
+enum ExportFormat {
+    /// schema.org ImageObject JSON-LD array
+    JsonLd,
+    /// RSS 2.0 feed of the newest photos
+    Rss,
+    /// Tag labels written into the IPTC keywords of the photos (modifies originals)
+    Iptc,
+    /// SQLite database of the sidecar index for SQL queries
+    Sqlite,
+}
+
 // ============================================================================
 // Top-Level CLI Structure
 // ============================================================================
@@ -167,6 +359,20 @@ enum Cmd {
     Verify(VerifyArgs),
     /// Migrate sidecars between schema versions
     Migrate(MigrateArgs),
+    /// Import tags from other photo applications into sidecars
+    Import(ImportArgs),
+    /// Summarise a library and compare with earlier snapshots
+    Stats(StatsArgs),
+    /// Export sidecar metadata for publishing
+    Export(ExportArgs),
+    /// Edit individual sidecar fields addressed by JSON Pointer
+    Patch(PatchArgs),
+    /// Show the differences between two sidecars as a unified diff
+    Diff(DiffArgs),
+    /// Correct the capture time of photos taken with a wrong camera clock
+    Correct(CorrectArgs),
+    /// Show which optional external tools are installed
+    Capabilities(CapabilitiesArgs),
 }
 
 // ============================================================================
@@ -195,11 +401,20 @@ enum Cmd {
                   # Exclude hidden directories\n  \
                   jozin scan ~/Photos --exclude \"**/.*/**\"\n\n  \
                   # Limit parallelism\n  \
-                  jozin scan ~/Photos --max-threads 4"
+                  jozin scan ~/Photos --max-threads 4\n\n  \
+                  # Scan photos inside .zip archives (requires the archives feature)\n  \
+                  jozin scan ~/Archive --recursive --scan-archives\n\n  \
+                  # Name sidecars IMG_1234.JPG.jozin.json (saved in .jozin/config.json)\n  \
+                  jozin scan ~/Site/content --recursive --sidecar-suffix .jozin.json\n\n  \
+                  # Keep a record of a long scan in case it crashes\n  \
+                  jozin scan ~/Photos --recursive --progress-log scan.jsonl\n\n  \
+                  # Save space with gzipped sidecars (IMG_1234.JPG.json.gz)\n  \
+                  jozin scan ~/Photos --recursive --compress-sidecars"
 )]
 struct ScanArgs {
-    /// File or directory path to scan
-    path: PathBuf,
+    /// File or directory paths to scan (several roots are scanned as one operation)
+    #[arg(required = true, value_name = "PATH")]
+    paths: Vec<PathBuf>,
 
     /// Enable recursive directory traversal
     #[arg(short = 'r', long)]
@@ -225,6 +440,94 @@ struct ScanArgs {
     #[arg(long)]
     hash_mode: Option<HashMode>,
 
+    /// Content hash algorithm recorded in sidecars (default: blake3)
+    #[arg(long, value_name = "ALGORITHM")]
+    hash_algorithm: Option<HashAlgorithmArg>,
+
+    /// Sidecar file name suffix (default: .json; saved for the library in .jozin/config.json)
+    #[arg(long, value_name = "SUFFIX")]
+    sidecar_suffix: Option<String>,
+
+    /// Sidecar file name prefix, e.g. "." for hidden sidecars (saved in .jozin/config.json)
+    #[arg(long, value_name = "PREFIX")]
+    sidecar_prefix: Option<String>,
+
+    /// How to treat symlinks: follow, skip, or error (default: follow)
+    #[arg(long, value_name = "STRATEGY")]
+    symlinks: Option<SymlinkArg>,
+
+    /// Camera timezone for EXIF capture times without OffsetTimeOriginal (e.g., +05:30)
+    #[arg(long, value_name = "OFFSET", default_value = "+00:00", value_parser = parse_timezone)]
+    assume_timezone: time::UtcOffset,
+
+    /// Maximum read throughput in bytes per second, shared by all threads (e.g., 500k, 50M)
+    #[arg(long, value_name = "BYTES_PER_SEC", value_parser = parse_io_limit)]
+    io_limit: Option<jozin_core::util::io_budget::IoBudget>,
+
+    /// Append file hashes to a checksum journal (default: ~/.jozin/checksums.jsonl)
+    #[arg(long, value_name = "PATH", num_args = 0..=1)]
+    journal: Option<Option<PathBuf>>,
+
+    /// Append progress events to a JSONL file, e.g. to see how far a crashed scan got
+    #[arg(long, value_name = "PATH")]
+    progress_log: Option<PathBuf>,
+
+    /// Don't reduce threads, I/O rate and fsyncs for libraries on network shares (SMB, NFS)
+    #[arg(long)]
+    force_local_io: bool,
+
+    /// Don't sync sidecars to disk before renaming them into place (faster on HDDs, less crash-safe)
+    #[arg(long)]
+    no_fsync: bool,
+
+    /// Carry faces, tags and thumbnails over to renamed files (matched by hash to sidecars whose photo is gone)
+    #[arg(long)]
+    detect_renames: bool,
+
+    /// Replace sidecars written by a newer Jožin (newer schema) instead of leaving them alone
+    #[arg(long)]
+    force_overwrite_newer: bool,
+
+    /// Write sidecars gzipped as <image>.json.gz (replacing uncompressed ones)
+    #[arg(long)]
+    compress_sidecars: bool,
+
+    /// Order of files in the output: path, date-oldest, date-newest, size-smallest, size-largest
+    #[arg(long, value_name = "ORDER")]
+    sort: Option<SortArg>,
+
+    /// Order in which directories are scanned: depth-first, breadth-first (one album at a time), largest-first
+    #[arg(long, value_name = "ORDER")]
+    traversal_order: Option<TraversalOrderArg>,
+
+    /// Stop and report partial results once resident memory exceeds this many MiB
+    #[arg(long, value_name = "MB")]
+    memory_limit: Option<u64>,
+
+    /// Comma-separated MIME types to scan, detected from file contents (e.g., "image/jpeg,image/*")
+    #[arg(long, value_name = "MIME")]
+    content_type: Option<String>,
+
+    /// Store the full raw EXIF (all IFDs, max 50 KiB) in the sidecar's image.exif_json
+    #[arg(long)]
+    include_raw_exif: bool,
+
+    /// Fail files whose JPEG/PNG/TIFF structure is broken before parsing them (reads whole JPEGs)
+    #[arg(long)]
+    validate_encoding: bool,
+
+    /// Link Apple Live Photo stills to their paired .MOV video in the sidecar
+    #[arg(long)]
+    link_live_photos: bool,
+
+    /// Scan images inside .zip archives without extracting them (archives feature)
+    #[arg(long)]
+    scan_archives: bool,
+
+    /// Extract archives to this directory and scan the extracted files
+    #[arg(long, value_name = "DIR", requires = "scan_archives")]
+    extract_archives: Option<PathBuf>,
+
     /// Output JSON format (default: auto-detect based on TTY)
     #[arg(long)]
     json: bool,
@@ -257,6 +560,8 @@ struct ScanArgs {
                   jozin cleanup ~/Photos --only-sidecars\n\n  \
                   # Remove only thumbnails\n  \
                   jozin cleanup ~/Photos --only-thumbnails --recursive\n\n  \
+                  # Remove thumbnails and their entries in the sidecars\n  \
+                  jozin cleanup ~/Photos --only-thumbnails --recursive --update-sidecars\n\n  \
                   # Remove only backups\n  \
                   jozin cleanup ~/Photos --only-backups\n\n  \
                   # Remove cache directories\n  \
@@ -290,6 +595,10 @@ struct CleanupArgs {
     #[arg(long, conflicts_with_all = ["only_sidecars", "only_thumbnails", "only_backups"])]
     only_cache: bool,
 
+    /// Remove deleted thumbnails from the sidecars that list them
+    #[arg(long, conflicts_with_all = ["only_sidecars", "only_backups", "only_cache"])]
+    update_sidecars: bool,
+
     /// Output JSON format (default: auto-detect based on TTY)
     #[arg(long)]
     json: bool,
@@ -319,11 +628,22 @@ struct CleanupArgs {
                   # Identify faces against known persons\n  \
                   jozin faces ~/Photos --identify\n\n  \
                   # Train on new person\n  \
-                  jozin faces ~/Photos --train '{\"person\":\"John\",\"images\":[\"john1.jpg\",\"john2.jpg\"]}'"
+                  jozin faces ~/Photos --train '{\"person\":\"John\",\"images\":[\"john1.jpg\",\"john2.jpg\"]}'\n\n  \
+                  # Review detected faces as a grid image\n  \
+                  jozin faces ~/Photos --recursive --contact-sheet --output contacts.jpg --face-size 128\n\n  \
+                  # Move trained persons to another device\n  \
+                  jozin faces ~/Photos --export-embeddings persons.json\n  \
+                  jozin faces /nas/Photos --import-embeddings persons.json --merge-strategy average\n\n  \
+                  # Copy a library for public sharing with faces pixelated\n  \
+                  jozin faces --anonymise-batch --src ~/Photos --dst ~/Public --method pixelate --block-size 20\n\n  \
+                  # List the face data stored per person, then erase one person\n  \
+                  jozin faces ~/Photos --recursive --privacy-report --output report.json\n  \
+                  jozin faces ~/Photos --recursive --delete-person 'John'"
 )]
 struct FacesArgs {
     /// File or directory path to process
-    path: PathBuf,
+    #[arg(required_unless_present = "anonymise_batch")]
+    path: Option<PathBuf>,
 
     /// Enable recursive directory traversal
     #[arg(short = 'r', long)]
@@ -337,6 +657,18 @@ struct FacesArgs {
     #[arg(long)]
     identify: bool,
 
+    /// Extract 5-point facial landmarks (used to align faces before embedding)
+    #[arg(long)]
+    landmarks: bool,
+
+    /// Estimate the age of faces scoring at least --min-score (personal data)
+    #[arg(long)]
+    estimate_age: bool,
+
+    /// Age estimation model identifier (e.g., "ssrnet-1.0"), separate from --model
+    #[arg(long, value_name = "MODEL", requires = "estimate_age")]
+    age_model: Option<String>,
+
     /// JSON string with training data: {"person": "name", "images": ["path1", "path2"]}
     #[arg(long, value_name = "JSON")]
     train: Option<String>,
@@ -345,6 +677,78 @@ struct FacesArgs {
     #[arg(long, value_name = "SCORE", value_parser = parse_score)]
     min_score: Option<f32>,
 
+    /// Render faces recorded in existing sidecars as a grid image (requires --output)
+    #[arg(long, requires = "output")]
+    contact_sheet: bool,
+
+    /// Output image path for --contact-sheet (e.g., "contacts.jpg"), or report file for --privacy-report
+    #[arg(long, value_name = "FILE")]
+    output: Option<PathBuf>,
+
+    /// Contact sheet cell size in pixels (default: 128)
+    #[arg(long, value_name = "PX", value_parser = clap::value_parser!(u32).range(16..=1024))]
+    face_size: Option<u32>,
+
+    /// Contact sheet columns (default: 10)
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..=100))]
+    grid_cols: Option<u32>,
+
+    /// Only include faces of this person on the contact sheet
+    #[arg(long, value_name = "NAME")]
+    person: Option<String>,
+
+    /// Export the library's trained persons to a file (.json = portable JSON, otherwise binary)
+    #[arg(long, value_name = "FILE", conflicts_with = "import_embeddings")]
+    export_embeddings: Option<PathBuf>,
+
+    /// Merge trained persons from an exported file into the library
+    #[arg(long, value_name = "FILE")]
+    import_embeddings: Option<PathBuf>,
+
+    /// How --import-embeddings treats persons that already exist (default: keep-existing)
+    #[arg(long, value_name = "STRATEGY", requires = "import_embeddings")]
+    merge_strategy: Option<MergeStrategyArg>,
+
+    /// Copy a library with all recorded faces redacted and removed from sidecars
+    #[arg(long, requires_all = ["src", "dst"], conflicts_with = "path")]
+    anonymise_batch: bool,
+
+    /// Source library for --anonymise-batch
+    #[arg(long, value_name = "DIR", requires = "anonymise_batch")]
+    src: Option<PathBuf>,
+
+    /// Destination directory for --anonymise-batch (must not be inside --src)
+    #[arg(long, value_name = "DIR", requires = "anonymise_batch")]
+    dst: Option<PathBuf>,
+
+    /// Redaction method for --anonymise-batch (default: pixelate)
+    #[arg(long, value_name = "METHOD", requires = "anonymise_batch")]
+    method: Option<AnonymiseMethodArg>,
+
+    /// Pixelation block size in pixels (default: 16)
+    #[arg(long, value_name = "PX", value_parser = clap::value_parser!(u32).range(2..=256), requires = "anonymise_batch")]
+    block_size: Option<u32>,
+
+    /// Name a face cluster: set the person of all its faces (requires --name)
+    #[arg(long, value_name = "ID", requires = "name")]
+    assign_cluster: Option<String>,
+
+    /// Person name for --assign-cluster
+    #[arg(long, value_name = "NAME", requires = "assign_cluster")]
+    name: Option<String>,
+
+    /// Remove all face cluster assignments (person names are kept)
+    #[arg(long, conflicts_with = "assign_cluster")]
+    clear_clusters: bool,
+
+    /// Inventory the face data stored per person (written to --output if given)
+    #[arg(long, conflicts_with_all = ["contact_sheet", "delete_person"])]
+    privacy_report: bool,
+
+    /// Erase a person: remove their name from all faces, sidecar backups and trained persons
+    #[arg(long, value_name = "NAME", conflicts_with_all = ["train", "assign_cluster", "clear_clusters"])]
+    delete_person: Option<String>,
+
     /// Print intended actions without writing files
     #[arg(long)]
     dry_run: bool,
@@ -382,7 +786,11 @@ struct FacesArgs {
                   # Rules-only (fast, no ML)\n  \
                   jozin tags ~/Photos --mode rules\n\n  \
                   # Append to existing tags\n  \
-                  jozin tags ~/Photos --append"
+                  jozin tags ~/Photos --append\n\n  \
+                  # Preview suggested tags without writing\n  \
+                  jozin tags ~/Photos --mode rules --suggest-only\n\n  \
+                  # Show the tag tree (scene > outdoor > beach)\n  \
+                  jozin tags ~/Photos --recursive --hierarchy"
 )]
 struct TagsArgs {
     /// File or directory path to process
@@ -408,6 +816,14 @@ struct TagsArgs {
     #[arg(long)]
     append: bool,
 
+    /// Print suggested tags for existing sidecars and exit without writing
+    #[arg(long)]
+    suggest_only: bool,
+
+    /// Print the tree of hierarchical tags (scene/outdoor/beach) with counts
+    #[arg(long, conflicts_with = "suggest_only")]
+    hierarchy: bool,
+
     /// Print intended actions without writing files
     #[arg(long)]
     dry_run: bool,
@@ -439,7 +855,13 @@ struct TagsArgs {
                   # High quality JPEG thumbnails\n  \
                   jozin thumbs ~/Photos --quality 95\n\n  \
                   # Overwrite existing thumbnails\n  \
-                  jozin thumbs ~/Photos --overwrite"
+                  jozin thumbs ~/Photos --overwrite\n\n  \
+                  # Record each thumbnail's dominant colour (\"#3A7BD5\") for palettes\n  \
+                  jozin thumbs ~/Photos --dominant-color\n\n  \
+                  # Tone map HDR photos (gain maps) with the filmic curve\n  \
+                  jozin thumbs ~/Photos --hdr-tonemap filmic\n\n  \
+                  # Looping GIF previews of animated GIFs, at most 200 KB each\n  \
+                  jozin thumbs ~/Photos --animated --sizes 256 --max-gif-size 200000"
 )]
 struct ThumbsArgs {
     /// File or directory path to process
@@ -461,10 +883,30 @@ struct ThumbsArgs {
     #[arg(long, value_name = "QUALITY", value_parser = parse_quality)]
     quality: Option<u8>,
 
+    /// Seconds into a video to take the thumbnail frame from (default: 1.0, falls back to 0)
+    #[arg(long, value_name = "SEC", value_parser = parse_video_time)]
+    video_thumbnail_time: Option<f32>,
+
     /// Overwrite existing thumbnails
     #[arg(long)]
     overwrite: bool,
 
+    /// Record each thumbnail's dominant colour as a hex CSS colour (one extra pass over its pixels)
+    #[arg(long)]
+    dominant_color: bool,
+
+    /// Tone mapping for HDR images: none, reinhard or filmic (default: reinhard)
+    #[arg(long, value_name = "OPERATOR")]
+    hdr_tonemap: Option<ThumbTonemap>,
+
+    /// Write a looping GIF preview of multi-frame images (animated GIFs; HEIC bursts need the heic feature)
+    #[arg(long)]
+    animated: bool,
+
+    /// Largest animated preview in bytes; frames are dropped, then the size reduced to fit
+    #[arg(long, value_name = "BYTES", requires = "animated")]
+    max_gif_size: Option<u64>,
+
     /// Print intended actions without writing files
     #[arg(long)]
     dry_run: bool,
@@ -492,9 +934,14 @@ struct ThumbsArgs {
     about = "Verify sidecar integrity and detect staleness",
     long_about = "Validates JSON schema structure, checks required fields presence,\n\
                   detects stale sidecars (schema version mismatch), compares pipeline signatures,\n\
-                  verifies file hash consistency, and suggests actions: noop, rescan, migrate.\n\n\
+                  verifies file hash consistency, and suggests actions: noop, rescan, migrate, upgrade_jozin.\n\n\
                   Use --fix to attempt auto-repair of minor issues.\n\
-                  Use --strict to treat warnings as errors.",
+                  Use --strict to treat warnings as errors.\n\
+                  Use --allow-newer-schema to accept sidecars written by a newer Jožin.\n\
+                  Use --check-thumbnails to open every thumbnail a sidecar references\n\
+                  (with --fix, broken thumbnails are regenerated).\n\
+                  Use --check-encoding to decode every photo in full (CPU-intensive); with --fix\n\
+                  and --quarantine-corrupt, corrupt photos move to ~/.jozin/quarantine/.",
     after_help = "EXAMPLES:\n  \
                   # Verify all sidecars\n  \
                   jozin verify ~/Photos --recursive\n\n  \
@@ -502,6 +949,14 @@ struct ThumbsArgs {
                   jozin verify ~/Photos --fix\n\n  \
                   # Strict mode (warnings = errors)\n  \
                   jozin verify ~/Photos --strict\n\n  \
+                  # Check thumbnails and regenerate broken ones\n  \
+                  jozin verify ~/Photos --recursive --check-thumbnails --fix\n\n  \
+                  # Find photos with corrupt image data and quarantine them\n  \
+                  jozin verify ~/Photos --recursive --check-encoding --fix --quarantine-corrupt\n\n  \
+                  # Rewrite source paths recorded in non-canonical form\n  \
+                  jozin verify ~/Photos --recursive --fix --normalise-paths\n\n  \
+                  # Enforce custom rules from a plugin\n  \
+                  jozin verify ~/Photos --recursive --plugin ./libcustom_validator.so\n\n  \
                   # Override pipeline signature\n  \
                   jozin verify ~/Photos --pipeline-signature '{\"schema_version\":\"1.0.0\"}'"
 )]
@@ -517,14 +972,70 @@ struct VerifyArgs {
     #[arg(long)]
     fix: bool,
 
+    /// With --fix, rewrite non-canonical source paths (relative, backslashes, ..)
+    #[arg(long, requires = "fix")]
+    normalise_paths: bool,
+
     /// Treat warnings as errors
     #[arg(long)]
     strict: bool,
 
-    /// Override pipeline signature for comparison (JSON string)
+    /// Don't warn about sidecars written by a newer Jožin (schema or producer version)
+    #[arg(long)]
+    allow_newer_schema: bool,
+
+    /// Check that referenced thumbnails exist and are valid images (extra I/O)
+    #[arg(long)]
+    check_thumbnails: bool,
+
+    /// Re-hash source images with each sidecar's recorded algorithm (reads every image)
+    #[arg(long)]
+    check_hashes: bool,
+
+    /// Check that image.format matches each source image's magic bytes
+    #[arg(long)]
+    check_formats: bool,
+
+    /// Report sidecars holding personal data such as estimated face ages
+    #[arg(long)]
+    check_privacy: bool,
+
+    /// Decode every source image in full to find corrupt image data (CPU-intensive, requires thumbs or faces feature)
+    #[arg(long)]
+    check_encoding: bool,
+
+    /// With --check-encoding and --fix, move corrupt photos and their sidecars to ~/.jozin/quarantine/
+    #[arg(long, requires_all = ["check_encoding", "fix"])]
+    quarantine_corrupt: bool,
+
+    /// Check the structure of every source image (JPEG markers, PNG header CRC, TIFF offsets) without decoding it
+    #[arg(long)]
+    validate_encoding: bool,
+
+    /// How to treat symlinks when looking for sidecars: follow, skip, or error (default: follow)
+    #[arg(long, value_name = "STRATEGY")]
+    symlinks: Option<SymlinkArg>,
+
+    /// Load a validation plugin from a shared library (repeatable, requires plugins feature)
+    #[arg(long = "plugin", value_name = "PATH")]
+    plugins: Vec<PathBuf>,
+
+    /// Report sidecars whose pipeline signature is incompatible with this one (JSON string)
     #[arg(long, value_name = "JSON")]
     pipeline_signature: Option<String>,
 
+    /// Like --pipeline-signature, with the signature of this Jožin binary
+    #[arg(long, conflicts_with = "pipeline_signature")]
+    auto_reference: bool,
+
+    /// Replace corrupt sidecars with their newest valid backup (.bak1 to .bak3)
+    #[arg(long)]
+    restore_from_backup: bool,
+
+    /// With --restore-from-backup, only show which backups would be restored
+    #[arg(long, requires = "restore_from_backup")]
+    dry_run: bool,
+
     /// Output JSON format (default: auto-detect based on TTY)
     #[arg(long)]
     json: bool,
@@ -555,31 +1066,58 @@ struct MigrateArgs {
     /// Print intended actions without writing files
     dry_run: bool,
 
+    /// Only count sidecars that would be migrated (requires --dry-run)
+    count: bool,
+
     /// Create .bakN backup files (default: true, use --no-backup to disable)
     backup: bool,
 
+    /// Show field-level diffs of a random sample of sidecars without writing
+    preview: bool,
+
+    /// Show diffs of all affected sidecars (implies --preview)
+    preview_all: bool,
+
+    /// Number of sidecars to diff in preview mode
+    sample: usize,
+
+    /// Migrate the Nth backup (.bakN) instead and promote it to the sidecar
+    from_backup: Option<u8>,
+
+    /// Gzip all sidecars in place (.json → .json.gz) instead of migrating
+    compress: bool,
+
+    /// Decompress all gzipped sidecars in place (.json.gz → .json) instead of migrating
+    decompress: bool,
+
+    /// Print preview diffs without ANSI colours
+    no_color: bool,
+
     /// Output JSON format (default: auto-detect based on TTY)
     json: bool,
 }
 
 impl clap::FromArgMatches for MigrateArgs {
     fn from_arg_matches(matches: &clap::ArgMatches) -> std::result::Result<Self, clap::Error> {
-        // Determine backup value: --backup sets true, --no-backup sets false, default is true
-        let backup = if matches.get_flag("no_backup") {
-            false
-        } else if matches.get_flag("backup") {
-            true
-        } else {
-            true // default
-        };
+        // Determine backup value: --no-backup sets false; --backup and the default are both true
+        let backup = !matches.get_flag("no_backup");
 
         Ok(Self {
             path: matches.get_one::<PathBuf>("path").expect("required").clone(),
             recursive: matches.get_flag("recursive"),
             from: matches.get_one::<String>("from").cloned(),
-            to: matches.get_one::<String>("to").expect("required").clone(),
+            // Required unless --compress/--decompress
+            to: matches.get_one::<String>("to").cloned().unwrap_or_default(),
             dry_run: matches.get_flag("dry_run"),
+            count: matches.get_flag("count"),
             backup,
+            preview: matches.get_flag("preview") || matches.get_flag("preview_all"),
+            preview_all: matches.get_flag("preview_all"),
+            sample: matches.get_one::<usize>("sample").copied().unwrap_or(MIGRATE_PREVIEW_SAMPLE),
+            from_backup: matches.get_one::<u8>("from_backup").copied(),
+            compress: matches.get_flag("compress"),
+            decompress: matches.get_flag("decompress"),
+            no_color: matches.get_flag("no_color"),
             json: matches.get_flag("json"),
         })
     }
@@ -600,12 +1138,36 @@ impl clap::FromArgMatches for MigrateArgs {
         if matches.contains_id("dry_run") {
             self.dry_run = matches.get_flag("dry_run");
         }
+        if matches.contains_id("count") {
+            self.count = matches.get_flag("count");
+        }
         // Update backup based on which flag was provided
         if matches.contains_id("no_backup") {
             self.backup = !matches.get_flag("no_backup");
         } else if matches.contains_id("backup") {
             self.backup = matches.get_flag("backup");
         }
+        if matches.contains_id("preview_all") {
+            self.preview_all = matches.get_flag("preview_all");
+        }
+        if matches.contains_id("preview") {
+            self.preview = matches.get_flag("preview") || self.preview_all;
+        }
+        if let Some(sample) = matches.get_one::<usize>("sample") {
+            self.sample = *sample;
+        }
+        if matches.contains_id("from_backup") {
+            self.from_backup = matches.get_one::<u8>("from_backup").copied();
+        }
+        if matches.contains_id("compress") {
+            self.compress = matches.get_flag("compress");
+        }
+        if matches.contains_id("decompress") {
+            self.decompress = matches.get_flag("decompress");
+        }
+        if matches.contains_id("no_color") {
+            self.no_color = matches.get_flag("no_color");
+        }
         if matches.contains_id("json") {
             self.json = matches.get_flag("json");
         }
@@ -621,6 +1183,12 @@ impl clap::Args for MigrateArgs {
                          Creates backup rotation (.bak1, .bak2, .bak3). Uses atomic writes to prevent corruption.\n\
                          Idempotent (safe to run multiple times).\n\n\
                          Use --dry-run to preview changes without writing.\n\
+                         Use --dry-run --count to only count sidecars that would change.\n\
+                         Use --preview to show unified diffs of a random sample of sidecars.\n\
+                         Use --from-backup to redo a bad migration from the .bak1 backups; the\n\
+                         replaced sidecars are kept as .bak2.\n\
+                         Use --compress / --decompress to switch sidecars between .json and gzipped\n\
+                         .json.gz (with their backups) instead of migrating.\n\
                          Use --no-backup to skip creating backup files.")
             .after_help("EXAMPLES:\n  \
                          # Migrate to version 2.0.0 (auto-detect source)\n  \
@@ -629,8 +1197,16 @@ impl clap::Args for MigrateArgs {
                          jozin migrate ~/Photos --from 1.0.0 --to 2.0.0\n\n  \
                          # Dry run to preview changes\n  \
                          jozin migrate ~/Photos --to 2.0.0 --dry-run\n\n  \
+                         # Quickly count sidecars that would be migrated\n  \
+                         jozin migrate ~/Photos --to 2.0.0 --dry-run --count --recursive\n\n  \
+                         # Show what would change in 5 random sidecars\n  \
+                         jozin migrate ~/Photos --to 2.0.0 --preview --sample 5\n\n  \
+                         # Redo a migration from the pre-migration backups\n  \
+                         jozin migrate ~/Photos --from-backup --to 2.0.0 --recursive\n\n  \
                          # Migrate without backups\n  \
-                         jozin migrate ~/Photos --to 2.0.0 --no-backup")
+                         jozin migrate ~/Photos --to 2.0.0 --no-backup\n\n  \
+                         # Gzip all sidecars to save space\n  \
+                         jozin migrate ~/Photos --compress --recursive")
             .arg(clap::Arg::new("path")
                 .required(true)
                 .value_name("PATH")
@@ -648,12 +1224,18 @@ impl clap::Args for MigrateArgs {
             .arg(clap::Arg::new("to")
                 .long("to")
                 .value_name("VERSION")
-                .required(true)
-                .help("Target schema version (required)"))
+                .required_unless_present_any(["compress", "decompress"])
+                .help("Target schema version (required unless --compress/--decompress)"))
             .arg(clap::Arg::new("dry_run")
                 .long("dry-run")
                 .action(clap::ArgAction::SetTrue)
                 .help("Print intended actions without writing files"))
+            .arg(clap::Arg::new("count")
+                .long("count")
+                .action(clap::ArgAction::SetTrue)
+                .requires("dry_run")
+                .conflicts_with_all(["preview", "preview_all"])
+                .help("Only count sidecars that would be migrated (reads the first 200 bytes of each)"))
             .arg(clap::Arg::new("backup")
                 .long("backup")
                 .action(clap::ArgAction::SetTrue)
@@ -664,6 +1246,43 @@ impl clap::Args for MigrateArgs {
                 .action(clap::ArgAction::SetTrue)
                 .overrides_with_all(["backup", "no_backup"])
                 .help("Skip creating .bakN backup files"))
+            .arg(clap::Arg::new("preview")
+                .long("preview")
+                .action(clap::ArgAction::SetTrue)
+                .help("Show field-level diffs of a random sample of sidecars without writing"))
+            .arg(clap::Arg::new("preview_all")
+                .long("preview-all")
+                .action(clap::ArgAction::SetTrue)
+                .conflicts_with("sample")
+                .help("Show diffs of all affected sidecars (paged on a terminal)"))
+            .arg(clap::Arg::new("sample")
+                .long("sample")
+                .value_name("N")
+                .value_parser(clap::value_parser!(usize))
+                .requires("preview")
+                .help("Number of sidecars to diff in preview mode [default: 5]"))
+            .arg(clap::Arg::new("from_backup")
+                .long("from-backup")
+                .value_name("N")
+                .num_args(0..=1)
+                .default_missing_value("1")
+                .value_parser(clap::value_parser!(u8).range(1..=2))
+                .conflicts_with_all(["preview", "preview_all", "count"])
+                .help("Migrate backup .bakN (default: .bak1) and promote it to the sidecar"))
+            .arg(clap::Arg::new("compress")
+                .long("compress")
+                .action(clap::ArgAction::SetTrue)
+                .conflicts_with_all(["decompress", "to", "from", "preview", "preview_all", "count", "from_backup"])
+                .help("Gzip all sidecars in place (.json → .json.gz) instead of migrating"))
+            .arg(clap::Arg::new("decompress")
+                .long("decompress")
+                .action(clap::ArgAction::SetTrue)
+                .conflicts_with_all(["to", "from", "preview", "preview_all", "count", "from_backup"])
+                .help("Decompress all gzipped sidecars in place (.json.gz → .json) instead of migrating"))
+            .arg(clap::Arg::new("no_color")
+                .long("no-color")
+                .action(clap::ArgAction::SetTrue)
+                .help("Print preview diffs without colours (additions are green, removals red)"))
             .arg(clap::Arg::new("json")
                 .long("json")
                 .action(clap::ArgAction::SetTrue)
@@ -676,84 +1295,456 @@ impl clap::Args for MigrateArgs {
 }
 
 // ============================================================================
-// Helper Functions
+// Import Subcommand
 // ============================================================================
 
-/// Returns the default max_threads value: min(2×CPU, 8)
+/// Import tags from other photo applications into sidecars
 ///
-/// This implements the default parallelism strategy from TASK+PHASE_PLAN.md.
-fn default_max_threads() -> u16 {
-    std::cmp::min(num_cpus::get() * 2, 8) as u16
-}
+/// Reads keywords, ratings and labels from another application's metadata store
+/// and appends them as user tags to existing sidecars. Photos must be scanned first.
+#[derive(Args)]
+#[command(
+    about = "Import tags from other photo applications into sidecars",
+    long_about = "Reads keywords, ratings and labels from another application's metadata store\n\
+                  and appends them as user tags to existing sidecars (existing tags are kept).\n\
+                  Photos without a sidecar are reported and skipped; run 'jozin scan' first.\n\n\
+                  Lightroom import requires the 'lightroom' cargo feature;\n\
+                  digiKam import requires the 'digikam' cargo feature.",
+    after_help = "EXAMPLES:\n  \
+                  # Import Lightroom keywords, ratings and color labels\n  \
+                  jozin import --format lightroom --catalog ~/Lightroom.lrcat --path ~/Photos\n\n  \
+                  # Preview without writing sidecars\n  \
+                  jozin import --format lightroom --catalog ~/Lightroom.lrcat --path ~/Photos --dry-run\n\n  \
+                  # Import digiKam tags (People/Alice -> people/alice), ratings and comments\n  \
+                  jozin import --format digikam --db ~/.local/share/digikam/digikam4.db --path ~/Pictures"
+)]
+struct ImportArgs {
+    /// Source application format
+    #[arg(long)]
+    format: ImportFormat,
 
-/// Custom value parser for clap to parse comma-separated sizes
-fn parse_sizes_for_clap(sizes_str: &str) -> std::result::Result<Vec<u32>, String> {
-    sizes_str
-        .split(',')
-        .map(|s| {
-            let trimmed = s.trim();
-            let val = trimmed.parse::<u32>().map_err(|_| {
-                format!("Invalid size value: '{}' - must be a positive integer", trimmed)
-            })?;
-            if val == 0 {
-                return Err("size values must be positive integers (> 0)".to_string());
-            }
-            Ok(val)
-        })
-        .collect()
-}
+    /// Path to the source catalog (e.g., Lightroom .lrcat file or digiKam digikam4.db)
+    #[arg(long, visible_alias = "db", value_name = "FILE")]
+    catalog: PathBuf,
 
-/// Custom value parser for score validation (0.0-1.0)
-fn parse_score(s: &str) -> std::result::Result<f32, String> {
-    let score: f32 = s.parse().map_err(|_| "not a valid number")?;
-    if (0.0..=1.0).contains(&score) {
-        Ok(score)
-    } else {
-        Err("score must be between 0.0 and 1.0".to_string())
-    }
-}
+    /// Photo library root; only photos under this path are imported
+    #[arg(long, value_name = "PATH")]
+    path: PathBuf,
 
-/// Custom value parser for thread count (must be > 0)
-fn parse_threads(s: &str) -> std::result::Result<u16, String> {
-    let threads: u16 = s.parse().map_err(|_| "not a valid number")?;
-    if threads > 0 {
-        Ok(threads)
-    } else {
-        Err("max_threads must be greater than 0".to_string())
-    }
-}
+    /// Print intended actions without writing files
+    #[arg(long)]
+    dry_run: bool,
 
-/// Custom value parser for quality (1-100)
-fn parse_quality(s: &str) -> std::result::Result<u8, String> {
-    let quality: u8 = s.parse().map_err(|_| "not a valid number")?;
-    if (1..=100).contains(&quality) {
-        Ok(quality)
-    } else {
-        Err("quality must be between 1 and 100".to_string())
-    }
+    /// Output JSON format (default: auto-detect based on TTY)
+    #[arg(long)]
+    json: bool,
 }
 
-/// Parses comma-separated patterns into a vector of strings
-fn parse_patterns(patterns_str: &str) -> Vec<String> {
-    patterns_str
-        .split(',')
-        .map(|s| s.trim().to_string())
-        .collect()
+// ============================================================================
+// Stats Subcommand
+// ============================================================================
+
+/// Summarise a library and compare with earlier snapshots
+///
+/// Counts photos, bytes, cameras, tags, faces and geotagged photos from sidecars.
+/// Snapshots saved with --save can be compared with later runs using --compare.
+#[derive(Args)]
+#[command(
+    about = "Summarise a library and compare with earlier snapshots",
+    long_about = "Counts photos, bytes, cameras, tags, faces and geotagged photos from sidecars.\n\
+                  Snapshots saved with --save can be compared with later runs using --compare.\n\n\
+                  Photos without a sidecar are not counted; run 'jozin scan' first.",
+    after_help = "EXAMPLES:\n  \
+                  # Show library stats\n  \
+                  jozin stats ~/Photos --recursive\n\n  \
+                  # Save a monthly snapshot\n  \
+                  jozin stats ~/Photos --recursive --save ~/stats_2025-01.json\n\n  \
+                  # Show what changed since the snapshot\n  \
+                  jozin stats ~/Photos --recursive --compare ~/stats_2025-01.json"
+)]
+struct StatsArgs {
+    /// Library directory
+    path: PathBuf,
+
+    /// Enable recursive directory traversal
+    #[arg(short, long)]
+    recursive: bool,
+
+    /// Save the stats snapshot to a JSON file
+    #[arg(long, value_name = "FILE")]
+    save: Option<PathBuf>,
+
+    /// Compare with a previously saved snapshot
+    #[arg(long, value_name = "FILE")]
+    compare: Option<PathBuf>,
+
+    /// Output JSON format (default: auto-detect based on TTY)
+    #[arg(long)]
+    json: bool,
 }
 
 // ============================================================================
-// Validation Functions
+// Export Subcommand
 // ============================================================================
-//
-// NOTE: Most validation is now handled by clap's value_parser attributes.
-// These functions only handle complex validation that can't be expressed
-// declaratively in clap (e.g., JSON validation, non-empty patterns).
 
-/// Validates scan command arguments
+/// Export sidecar metadata for publishing
 ///
-/// Clap handles: max_threads > 0, hash_mode enum validation
-/// This function handles: non-empty glob patterns
-fn validate_scan_args(args: &ScanArgs) -> Result<()> {
+/// Renders the sidecars of a library in a format for other consumers, e.g.
+/// schema.org JSON-LD structured data for web galleries.
+#[derive(Args)]
+#[command(
+    about = "Export sidecar metadata for publishing",
+    long_about = "Renders the sidecars of a library in a format for other consumers.\n\n\
+                  json-ld: a JSON array of schema.org ImageObjects for web galleries\n\
+                  (search engine rich results). Content URLs are --base-url plus each\n\
+                  photo's path relative to the library root.\n\n\
+                  rss: an RSS 2.0 feed of the newest photos (by capture time), with\n\
+                  the smallest thumbnail as enclosure and image URLs as for json-ld.\n\n\
+                  iptc: writes tag labels into the IPTC keywords of each JPEG/TIFF\n\
+                  photo with exiftool, replacing existing keywords (--iptc-append adds\n\
+                  to them). This modifies the originals and requires\n\
+                  --allow-modify-originals; --base-url and --output are not used.\n\n\
+                  sqlite: an SQLite database with images, tags, faces and thumbnails\n\
+                  tables for SQL queries (requires the 'sqlite' cargo feature).\n\n\
+                  Photos without a sidecar are not exported; run 'jozin scan' first.",
+    after_help = "EXAMPLES:\n  \
+                  # Export structured data for a web gallery\n  \
+                  jozin export ~/Photos --recursive --format json-ld \\\n    \
+                  --base-url https://example.com/photos --output gallery.jsonld\n\n  \
+                  # Publish the 50 newest photos as a feed\n  \
+                  jozin export ~/Photos --recursive --format rss --output feed.xml \\\n    \
+                  --base-url https://example.com/photos --max-items 50\n\n  \
+                  # Embed your own tags as IPTC keywords for an asset manager\n  \
+                  jozin export ~/Photos --recursive --format iptc --only-user-tags \\\n    \
+                  --allow-modify-originals\n\n  \
+                  # Query the library with SQL\n  \
+                  jozin export ~/Photos --recursive --format sqlite --output library.db"
+)]
+struct ExportArgs {
+    /// Library directory (default: current directory)
+    #[arg(default_value = ".")]
+    path: PathBuf,
+
+    /// Export format
+    #[arg(long)]
+    format: ExportFormat,
+
+    /// URL the library directory is published at (json-ld, rss)
+    #[arg(long, value_name = "URL")]
+    base_url: Option<String>,
+
+    /// Maximum number of feed items, newest first (rss)
+    #[arg(long, value_name = "N", default_value_t = jozin_core::export::rss::DEFAULT_MAX_ITEMS)]
+    max_items: usize,
+
+    /// Feed title (rss; default: the library directory name)
+    #[arg(long)]
+    title: Option<String>,
+
+    /// File to write the export to (json-ld, rss, sqlite)
+    #[arg(short, long, value_name = "FILE")]
+    output: Option<PathBuf>,
+
+    /// Allow writing into the original photos (iptc)
+    #[arg(long)]
+    allow_modify_originals: bool,
+
+    /// Write only tags added by the user (iptc)
+    #[arg(long)]
+    only_user_tags: bool,
+
+    /// Add to the existing IPTC keywords instead of replacing them (iptc)
+    #[arg(long)]
+    iptc_append: bool,
+
+    /// Enable recursive directory traversal
+    #[arg(short, long)]
+    recursive: bool,
+
+    /// Output JSON format (default: auto-detect based on TTY)
+    #[arg(long)]
+    json: bool,
+}
+
+// ============================================================================
+// Patch Subcommand
+// ============================================================================
+
+/// Edit individual sidecar fields addressed by JSON Pointer
+///
+/// Changes single values without rewriting fields this build doesn't know.
+#[derive(Args)]
+#[command(
+    about = "Edit individual sidecar fields addressed by JSON Pointer",
+    long_about = "Edits a sidecar in place. Locations are JSON Pointers (RFC 6901), values\n\
+                  are JSON. The result must still be a valid sidecar; it is written\n\
+                  atomically and the previous version is kept as a .bak1 backup.\n\n\
+                  Edits are applied in this order: --set, --append, --add-tag, --remove-value,\n\
+                  --remove.",
+    after_help = "EXAMPLES:\n  \
+                  # Set the camera make\n  \
+                  jozin patch IMG_1234.JPG --set /image/camera_make '\"Canon\"'\n\n  \
+                  # Name a face and add a tag\n  \
+                  jozin patch IMG_1234.JPG --set /faces/0/person '\"Alice\"' \\\n    \
+                  --append /tags '{\"label\": \"family\", \"source\": \"user\"}'\n\n  \
+                  # Add tags as label:score:source (score and source optional)\n  \
+                  jozin patch IMG_1234.JPG --add-tag vacation --add-tag sunset:0.89:ml"
+)]
+struct PatchArgs {
+    /// Image or sidecar file to edit
+    path: PathBuf,
+
+    /// Set the value at POINTER (repeatable)
+    #[arg(long, num_args = 2, value_names = ["POINTER", "JSON"], action = clap::ArgAction::Append)]
+    set: Vec<String>,
+
+    /// Append a value to the array at POINTER (repeatable)
+    #[arg(long, num_args = 2, value_names = ["POINTER", "JSON"], action = clap::ArgAction::Append)]
+    append: Vec<String>,
+
+    /// Remove elements equal to a value from the array at POINTER (repeatable)
+    #[arg(long, num_args = 2, value_names = ["POINTER", "JSON"], action = clap::ArgAction::Append)]
+    remove_value: Vec<String>,
+
+    /// Append a tag given as LABEL[:SCORE[:SOURCE]] (source defaults to user; repeatable)
+    #[arg(long, value_name = "TAG")]
+    add_tag: Vec<jozin_core::Tag>,
+
+    /// Remove the value at POINTER (repeatable)
+    #[arg(long, value_name = "POINTER")]
+    remove: Vec<String>,
+
+    /// Output JSON format (default: auto-detect based on TTY)
+    #[arg(long)]
+    json: bool,
+}
+
+// ============================================================================
+// Diff Subcommand
+// ============================================================================
+
+/// Show the differences between two sidecars as a unified diff
+///
+/// Compares two sidecars (or backups) field by field, like `git diff`.
+#[derive(Args)]
+#[command(
+    about = "Show the differences between two sidecars as a unified diff",
+    long_about = "Compares two sidecars and prints a git diff-style unified diff: changed\n\
+                  fields as -/+ lines under @@ hunks named after their JSON path, with\n\
+                  unchanged fields around them as context. Either path may be an image (its\n\
+                  sidecar is used), a sidecar, a .bakN backup or any JSON file.",
+    after_help = "EXAMPLES:\n  \
+                  # What changed since the last backup\n  \
+                  jozin diff IMG_1234.JPG.json.bak1 IMG_1234.JPG\n\n  \
+                  # Compare the metadata of two photos without colours\n  \
+                  jozin diff IMG_1234.JPG IMG_1235.JPG --no-color\n\n  \
+                  # One line of context around each change\n  \
+                  jozin diff old.json new.json --context 1"
+)]
+struct DiffArgs {
+    /// Old image, sidecar or JSON file
+    old: PathBuf,
+
+    /// New image, sidecar or JSON file
+    new: PathBuf,
+
+    /// Unchanged lines shown around each change (default: 3)
+    #[arg(short = 'U', long, value_name = "LINES")]
+    context: Option<usize>,
+
+    /// Print the diff without ANSI colours (otherwise additions are green and removals red)
+    #[arg(long)]
+    no_color: bool,
+
+    /// Output JSON format (default: auto-detect based on TTY)
+    #[arg(long)]
+    json: bool,
+}
+
+// ============================================================================
+// Correct Subcommand
+// ============================================================================
+
+/// Correct the capture time of photos taken with a wrong camera clock
+///
+/// Shifts `DateTimeOriginal` in the photos and their sidecars.
+#[derive(Args)]
+#[command(
+    about = "Correct the capture time of photos taken with a wrong camera clock",
+    long_about = "Shifts the capture time of every photo with a recorded capture time by\n\
+                  --datetime-offset: in the photo's EXIF DateTimeOriginal (written with\n\
+                  exiftool, which must be installed) and in image.datetime_original of its\n\
+                  sidecar. This modifies the original photos and requires\n\
+                  --allow-modify-originals; --dry-run only previews the corrected times.",
+    after_help = "EXAMPLES:\n  \
+                  # Preview: the camera was an hour behind\n  \
+                  jozin correct --datetime-offset +01:00:00 --path ~/Photos --recursive --dry-run\n\n  \
+                  # Apply it\n  \
+                  jozin correct --datetime-offset +01:00:00 --path ~/Photos --recursive --allow-modify-originals\n\n  \
+                  # The clock ran 30 minutes fast\n  \
+                  jozin correct --datetime-offset -00:30 --path trip/ --allow-modify-originals"
+)]
+struct CorrectArgs {
+    /// Image or directory to correct
+    #[arg(long, value_name = "PATH")]
+    path: PathBuf,
+
+    /// Time to add to each capture time (±H:MM[:SS], e.g. +01:00:00 or -00:30)
+    #[arg(long, value_name = "OFFSET", allow_hyphen_values = true)]
+    datetime_offset: String,
+
+    /// Correct photos in subdirectories too
+    #[arg(short, long)]
+    recursive: bool,
+
+    /// Show the corrected capture times without modifying anything
+    #[arg(long)]
+    dry_run: bool,
+
+    /// Allow writing into the original photos
+    #[arg(long)]
+    allow_modify_originals: bool,
+
+    /// Output JSON format (default: auto-detect based on TTY)
+    #[arg(long)]
+    json: bool,
+}
+
+// ============================================================================
+// Capabilities Subcommand
+// ============================================================================
+
+/// Show which optional external tools are installed
+///
+/// Checks for ffprobe, exiftool and ffmpeg in PATH and reports their versions.
+#[derive(Args)]
+#[command(
+    about = "Show which optional external tools are installed",
+    long_about = "Checks for ffprobe, exiftool and ffmpeg in PATH and reports their versions\n\
+                  and the features that need them. Commands needing a missing tool fail\n\
+                  before starting, with an install hint.",
+    after_help = "EXAMPLES:\n  \
+                  # Show available tools\n  \
+                  jozin capabilities"
+)]
+struct CapabilitiesArgs {
+    /// Output JSON format (default: auto-detect based on TTY)
+    #[arg(long)]
+    json: bool,
+}
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+/// Returns the default max_threads value: min(2×CPU, 8)
+///
+/// This implements the default parallelism strategy from TASK+PHASE_PLAN.md.
+fn default_max_threads() -> u16 {
+    std::cmp::min(num_cpus::get() * 2, 8) as u16
+}
+
+/// Custom value parser for clap to parse comma-separated sizes
+fn parse_sizes_for_clap(sizes_str: &str) -> std::result::Result<Vec<u32>, String> {
+    sizes_str
+        .split(',')
+        .map(|s| {
+            let trimmed = s.trim();
+            let val = trimmed.parse::<u32>().map_err(|_| {
+                format!("Invalid size value: '{}' - must be a positive integer", trimmed)
+            })?;
+            if val == 0 {
+                return Err("size values must be positive integers (> 0)".to_string());
+            }
+            Ok(val)
+        })
+        .collect()
+}
+
+/// Custom value parser for score validation (0.0-1.0)
+fn parse_score(s: &str) -> std::result::Result<f32, String> {
+    let score: f32 = s.parse().map_err(|_| "not a valid number")?;
+    if (0.0..=1.0).contains(&score) {
+        Ok(score)
+    } else {
+        Err("score must be between 0.0 and 1.0".to_string())
+    }
+}
+
+/// Custom value parser for thread count (must be > 0)
+fn parse_threads(s: &str) -> std::result::Result<u16, String> {
+    let threads: u16 = s.parse().map_err(|_| "not a valid number")?;
+    if threads > 0 {
+        Ok(threads)
+    } else {
+        Err("max_threads must be greater than 0".to_string())
+    }
+}
+
+/// Maximum faces per contact sheet page (further faces go to additional pages)
+#[cfg(feature = "faces")]
+const CONTACT_SHEET_MAX_FACES: usize = 200;
+
+/// Disclaimer printed to stderr by `faces --estimate-age`
+const AGE_PRIVACY_NOTICE: &str = "Age estimates are personal data and often wrong by several years. \
+They are stored in sidecars next to your photos; run `jozin verify --check-privacy` to find them \
+before sharing a library.";
+
+/// Default number of sidecars diffed by `migrate --preview`
+const MIGRATE_PREVIEW_SAMPLE: usize = 5;
+
+/// Custom value parser for quality (1-100)
+fn parse_quality(s: &str) -> std::result::Result<u8, String> {
+    let quality: u8 = s.parse().map_err(|_| "not a valid number")?;
+    if (1..=100).contains(&quality) {
+        Ok(quality)
+    } else {
+        Err("quality must be between 1 and 100".to_string())
+    }
+}
+
+/// Custom value parser for video thumbnail time (seconds, >= 0)
+fn parse_video_time(s: &str) -> std::result::Result<f32, String> {
+    let time: f32 = s.parse().map_err(|_| "not a valid number")?;
+    if time.is_finite() && time >= 0.0 {
+        Ok(time)
+    } else {
+        Err("video thumbnail time must be a non-negative number of seconds".to_string())
+    }
+}
+
+/// Custom value parser for UTC offsets (+HH:MM / -HH:MM)
+fn parse_timezone(s: &str) -> std::result::Result<time::UtcOffset, String> {
+    jozin_core::util::timezone::parse_utc_offset(s).map_err(|e| e.to_string())
+}
+
+/// Custom value parser for I/O limits (bytes per second, e.g. 50M)
+fn parse_io_limit(s: &str) -> std::result::Result<jozin_core::util::io_budget::IoBudget, String> {
+    s.parse().map_err(|e: jozin_core::JozinError| e.to_string())
+}
+
+/// Parses comma-separated patterns into a vector of strings, expanding
+/// `{a,b}` brace alternatives (commas inside braces don't separate patterns)
+fn parse_patterns(patterns_str: &str) -> Vec<String> {
+    split_pattern_list(patterns_str)
+        .iter()
+        .flat_map(|pattern| expand_braces(pattern))
+        .collect()
+}
+
+// ============================================================================
+// Validation Functions
+// ============================================================================
+//
+// NOTE: Most validation is now handled by clap's value_parser attributes.
+// These functions only handle complex validation that can't be expressed
+// declaratively in clap (e.g., JSON validation, non-empty patterns).
+
+/// Validates scan command arguments
+///
+/// Clap handles: max_threads > 0, hash_mode enum validation
+/// This function handles: non-empty glob patterns
+fn validate_scan_args(args: &ScanArgs) -> Result<()> {
     // Validate glob patterns are non-empty
     if let Some(ref patterns) = args.include {
         if patterns.trim().is_empty() {
@@ -807,19 +1798,24 @@ fn validate_tags_args(_args: &TagsArgs) -> Result<()> {
 /// Validates thumbs command arguments
 ///
 /// Clap handles: quality range (1-100), sizes validation, format enum, max_threads > 0
-/// No additional validation needed.
-fn validate_thumbs_args(_args: &ThumbsArgs) -> Result<()> {
+/// This function handles: rejecting JPEG XL (no encoder bundled yet)
+fn validate_thumbs_args(args: &ThumbsArgs) -> Result<()> {
+    if matches!(args.format, Some(ThumbFormat::Jxl | ThumbFormat::JxlFromJpeg)) {
+        return Err(JozinError::NotSupported {
+            feature: "JPEG XL thumbnails (no encoder bundled yet)".to_string(),
+        });
+    }
     Ok(())
 }
 
 /// Validates verify command arguments
 ///
 /// Enforces parameter constraints:
-/// - pipeline_signature must be valid JSON
+/// - pipeline_signature must be a valid PipelineSignature JSON object
 fn validate_verify_args(args: &VerifyArgs) -> Result<()> {
     // Validate pipeline_signature JSON
     if let Some(ref sig_json) = args.pipeline_signature {
-        serde_json::from_str::<serde_json::Value>(sig_json).map_err(|e| {
+        serde_json::from_str::<jozin_core::PipelineSignature>(sig_json).map_err(|e| {
             JozinError::UserError {
                 message: format!("Invalid pipeline_signature JSON: {}", e),
             }
@@ -829,12 +1825,119 @@ fn validate_verify_args(args: &VerifyArgs) -> Result<()> {
     Ok(())
 }
 
+/// Validates import command arguments
+///
+/// Enforces parameter constraints:
+/// - path must be an existing directory
+fn validate_import_args(args: &ImportArgs) -> Result<()> {
+    if !args.path.is_dir() {
+        return Err(JozinError::UserError {
+            message: format!("Path not found or not a directory: {}", args.path.display()),
+        });
+    }
+    Ok(())
+}
+
+/// Validates correct command arguments
+///
+/// Clap handles: required path and offset
+/// This function handles: existing path, offset format, explicit opt-in
+/// unless --dry-run
+fn validate_correct_args(args: &CorrectArgs) -> Result<()> {
+    if !args.path.exists() {
+        return Err(JozinError::UserError {
+            message: format!("Path not found: {}", args.path.display()),
+        });
+    }
+    jozin_core::util::exif_write::parse_datetime_offset(&args.datetime_offset)?;
+    if !args.dry_run && !args.allow_modify_originals {
+        return Err(JozinError::UserError {
+            message: "correct writes into the original photos; pass --allow-modify-originals (or preview with --dry-run)"
+                .to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Validates export command arguments
+///
+/// Clap handles: format enum validation
+/// This function handles: existing library path, http(s) base URL and output
+/// file for json-ld/rss, explicit opt-in for iptc
+fn validate_export_args(args: &ExportArgs) -> Result<()> {
+    if !args.path.exists() {
+        return Err(JozinError::UserError {
+            message: format!("Path not found: {}", args.path.display()),
+        });
+    }
+    if let ExportFormat::Iptc = args.format {
+        if !args.allow_modify_originals {
+            return Err(JozinError::UserError {
+                message: "iptc export writes into the original photos; pass --allow-modify-originals".to_string(),
+            });
+        }
+        return Ok(());
+    }
+    if let ExportFormat::Sqlite = args.format {
+        if args.output.is_none() {
+            return Err(JozinError::UserError {
+                message: "--output is required for sqlite".to_string(),
+            });
+        }
+        return Ok(());
+    }
+    let Some(base_url) = &args.base_url else {
+        return Err(JozinError::UserError {
+            message: "--base-url is required for json-ld and rss".to_string(),
+        });
+    };
+    if !(base_url.starts_with("http://") || base_url.starts_with("https://")) {
+        return Err(JozinError::UserError {
+            message: format!("base URL must start with http:// or https://: {}", base_url),
+        });
+    }
+    if args.output.is_none() {
+        return Err(JozinError::UserError {
+            message: "--output is required for json-ld and rss".to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Validates patch command arguments
+///
+/// Clap handles: POINTER/JSON pairs
+/// This function handles: existing file, at least one edit
+fn validate_patch_args(args: &PatchArgs) -> Result<()> {
+    if !args.path.is_file() {
+        return Err(JozinError::UserError {
+            message: format!("File not found: {}", args.path.display()),
+        });
+    }
+    if args.set.is_empty()
+        && args.append.is_empty()
+        && args.add_tag.is_empty()
+        && args.remove_value.is_empty()
+        && args.remove.is_empty()
+    {
+        return Err(JozinError::UserError {
+            message: "Nothing to patch: use --set, --append, --add-tag, --remove-value or --remove".to_string(),
+        });
+    }
+    Ok(())
+}
+
 /// Validates migrate command arguments
 ///
 /// Enforces parameter constraints:
 /// - to is required and non-empty
 /// - from and to are valid semver strings (basic check)
 fn validate_migrate_args(args: &MigrateArgs) -> Result<()> {
+    // No versions involved
+    if args.compress || args.decompress {
+        return Ok(());
+    }
+
     // Validate 'to' is non-empty
     if args.to.trim().is_empty() {
         return Err(JozinError::UserError {
@@ -885,8 +1988,13 @@ fn validate_migrate_args(args: &MigrateArgs) -> Result<()> {
 /// * `event` - The progress event to print
 fn print_progress(base_path: &std::path::Path, event: jozin_core::ProgressEvent) {
     match event {
-        jozin_core::ProgressEvent::FileStarted { .. } => {
-            // Don't print anything on start, wait for completion
+        jozin_core::ProgressEvent::BatchStarted { total_files, estimated_duration_ms, .. } => {
+            if let Some(ms) = estimated_duration_ms.filter(|ms| *ms >= 1000) {
+                println!("Scanning {} files (estimated {}s)", total_files, ms.div_ceil(1000));
+            }
+        }
+        jozin_core::ProgressEvent::FileStarted { .. } | jozin_core::ProgressEvent::BatchCompleted { .. } => {
+            // Don't print anything on start, wait for completion; the summary follows the batch
         }
         jozin_core::ProgressEvent::FileCompleted { path, success, error, .. } => {
             // Calculate relative path for cleaner display
@@ -904,6 +2012,30 @@ fn print_progress(base_path: &std::path::Path, event: jozin_core::ProgressEvent)
     }
 }
 
+/// Prints a `─── Album: 2024/Summer ───` line for a directory, relative to
+/// `base_path` (`.` for the base itself).
+fn print_album_separator(base_path: &std::path::Path, album: &std::path::Path) {
+    let relative = album.strip_prefix(base_path).unwrap_or(album);
+    let name = if relative.as_os_str().is_empty() { ".".to_string() } else { relative.display().to_string() };
+    println!("─── Album: {} ───", name);
+}
+
+/// Formats a byte count with binary units (e.g., "12.3 MiB").
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", value, UNITS[unit])
+}
+
 // ============================================================================
 // Command Handlers (Phase 1 Stubs)
 // ============================================================================
@@ -920,10 +2052,20 @@ struct StubResponse {
     dry_run: Option<bool>,
 }
 
-/// Handles scan command
+/// Locks the library containing `path` for a command that writes sidecars.
 ///
-/// Phase 1: Implements file and directory scanning with hash computation.
-/// Phase 2: Supports both human-readable progress and JSON output modes.
+/// Dry runs and missing paths (reported by the command itself) take no lock.
+fn lock_library(path: &Path, dry_run: bool) -> Result<Option<LibraryLock>> {
+    if dry_run || !path.exists() {
+        return Ok(None);
+    }
+    LibraryLock::acquire(path).map(Some)
+}
+
+/// Handles scan command
+///
+/// Phase 1: Implements file and directory scanning with hash computation.
+/// Phase 2: Supports both human-readable progress and JSON output modes.
 fn handle_scan(args: ScanArgs) -> Result<()> {
     let start = OffsetDateTime::now_utc();
 
@@ -935,41 +2077,121 @@ fn handle_scan(args: ScanArgs) -> Result<()> {
     let max_threads = args.max_threads.unwrap_or_else(default_max_threads);
 
     // Get hash_mode with default to "file"
-    let hash_mode = args.hash_mode.as_ref().map(|m| m.as_str()).or(Some("file"));
+    let hash_mode = args.hash_mode.as_ref().map(|m| m.as_str()).unwrap_or("file");
+
+    // `--journal` without a path uses the default journal in the home directory
+    let journal = match args.journal.clone() {
+        Some(None) => Some(ChecksumJournal::default_path().ok_or_else(|| JozinError::UserError {
+            message: "Cannot locate home directory for the default journal; pass --journal <PATH>".to_string(),
+        })?),
+        Some(Some(path)) => Some(path),
+        None => None,
+    };
+
+    let mut config = ScanConfig {
+        recursive: args.recursive,
+        include,
+        exclude,
+        dry_run: args.dry_run,
+        max_threads,
+        hash_mode: Some(hash_mode.to_string()),
+        content_type_filter: args.content_type.as_ref().map(|s| parse_patterns(s)),
+        include_raw_exif: args.include_raw_exif,
+        link_live_photos: args.link_live_photos,
+        failure_policy: jozin_core::scan::FailurePolicy::default(),
+        scan_archives: args.scan_archives,
+        extract_archives: args.extract_archives.clone(),
+        hash_algorithm: args.hash_algorithm.map(Into::into).unwrap_or_default(),
+        sidecar_suffix: args
+            .sidecar_suffix
+            .clone()
+            .unwrap_or_else(|| jozin_core::util::library_config::DEFAULT_SIDECAR_SUFFIX.to_string()),
+        sidecar_prefix: args.sidecar_prefix.clone(),
+        symlinks: args.symlinks.map(Into::into).unwrap_or_default(),
+        assume_timezone: args.assume_timezone,
+        io_budget: args.io_limit,
+        journal,
+        hash_cache: None,
+        fsync_policy: if args.no_fsync { FsyncPolicy::Never } else { FsyncPolicy::Auto },
+        force_local_io: args.force_local_io,
+        output_sort_order: args.sort.map(Into::into).unwrap_or_default(),
+        memory_limit_mb: args.memory_limit,
+        sidecar_index: None,
+        traversal_order: args.traversal_order.map(Into::into).unwrap_or_default(),
+        force_overwrite_newer: args.force_overwrite_newer,
+        validate_encoding: args.validate_encoding,
+        compress_sidecars: args.compress_sidecars,
+    };
+
+    // One writing process per library; held until the scan returns
+    let _locks = args
+        .paths
+        .iter()
+        .map(|root| lock_library(root, args.dry_run))
+        .collect::<Result<Vec<_>>>()?;
+
+    // Index the existing sidecars before the scan writes new ones
+    if args.detect_renames {
+        let index = SidecarIndex::default();
+        for root in &args.paths {
+            let naming = if args.sidecar_suffix.is_some() || args.sidecar_prefix.is_some() {
+                config.library_config()
+            } else {
+                LibraryConfig::discover(root)
+            };
+            index.add_root(root, args.recursive, &naming)?;
+        }
+        config.sidecar_index = Some(std::sync::Arc::new(index));
+    }
 
     // Determine output format
     let output_format = determine_output_format(args.json);
 
-    // Call scan_path with appropriate callback based on output format
+    // Several roots are scanned together (one batch, deduplicated files)
+    let roots: Vec<&std::path::Path> = args.paths.iter().map(PathBuf::as_path).collect();
+    let scan = |callback: Option<&dyn Fn(jozin_core::ProgressEvent)>| match roots.as_slice() {
+        [path] => jozin_core::scan_path(path, &config, callback),
+        _ => jozin_core::scan::scan_paths(&roots, &config, callback),
+    };
+
+    let progress_log = match &args.progress_log {
+        Some(path) => Some(ProgressFileWriter::new(path)?.into_callback()),
+        None => None,
+    };
+
+    // Call scan with appropriate callback based on output format
     let result = match output_format {
         OutputFormat::Human => {
-            // Clone path for closure
-            let base_path = args.path.clone();
-
-            // Call scan_path with progress callback
-            jozin_core::scan_path(
-                &args.path,
-                args.recursive,
-                include.as_deref(),
-                exclude.as_deref(),
-                args.dry_run,
-                max_threads,
-                hash_mode,
-                Some(&|event| print_progress(&base_path, event)),
-            )?
+            // Paths are shown relative to a single root, in full otherwise
+            let base_path = if roots.len() == 1 { args.paths[0].clone() } else { PathBuf::new() };
+
+            // Breadth-first scans announce each album as they enter it
+            let current_album = std::cell::RefCell::new(None::<PathBuf>);
+            let announce_albums = matches!(args.traversal_order, Some(TraversalOrderArg::BreadthFirst));
+
+            // Scan with progress callback
+            scan(Some(&|event| {
+                if let Some(log) = &progress_log {
+                    log(event.clone());
+                }
+                if let jozin_core::ProgressEvent::FileCompleted { path, .. } = &event {
+                    let album = std::path::Path::new(path).parent().map(std::path::Path::to_path_buf);
+                    if announce_albums && *current_album.borrow() != album {
+                        if let Some(album) = &album {
+                            print_album_separator(&base_path, album);
+                        }
+                        *current_album.borrow_mut() = album;
+                    }
+                }
+                print_progress(&base_path, event)
+            }))?
         }
         OutputFormat::Json => {
-            // Call scan_path without callback (silent mode)
-            jozin_core::scan_path(
-                &args.path,
-                args.recursive,
-                include.as_deref(),
-                exclude.as_deref(),
-                args.dry_run,
-                max_threads,
-                hash_mode,
-                None,
-            )?
+            // Scan without printing progress (silent mode)
+            match &progress_log {
+                Some(log) => scan(Some(log))?,
+                None => scan(None)?,
+            }
         }
     };
 
@@ -984,6 +2206,32 @@ fn handle_scan(args: ScanArgs) -> Result<()> {
             println!("  Successful: {}", result.successful);
             println!("  Failed: {}", result.failed);
             println!("  Skipped: {}", result.skipped);
+            if result.conflicts > 0 {
+                println!("  Conflicts: {} (sidecars from a newer Jožin were left unchanged; upgrade Jožin)", result.conflicts);
+                for file in &result.scanned_files {
+                    if let ScanAction::Conflict { existing_schema, scanner_schema } = &file.action {
+                        println!("    \x1b[33m⚠\x1b[0m {} (schema {} > {})", file.path, existing_schema, scanner_schema);
+                    }
+                }
+            }
+            if result.exif_coverage.total > 0 {
+                println!("  {}", exif_coverage_line(&result.exif_coverage));
+            }
+            if result.truncated {
+                println!("  Truncated: memory limit reached, not all files were scanned");
+            }
+            if !result.orphaned_sidecars.is_empty() {
+                println!("  Sidecars left behind by renamed files: {}", result.orphaned_sidecars.len());
+                for sidecar in &result.orphaned_sidecars {
+                    println!("    {}", sidecar);
+                }
+            }
+            if !result.cross_root_duplicates.is_empty() {
+                println!("  Duplicated across roots: {}", result.cross_root_duplicates.len());
+                for group in &result.cross_root_duplicates {
+                    println!("    {}", group.paths.join(", "));
+                }
+            }
         }
         OutputFormat::Json => {
             // Print JSON to stdout
@@ -1015,6 +2263,7 @@ fn handle_cleanup(args: CleanupArgs) -> Result<()> {
         // Default: remove all
         jozin_core::CleanupOptions::all()
     };
+    let options = jozin_core::CleanupOptions { update_sidecars: args.update_sidecars, ..options };
 
     // Determine output format
     let output_format = determine_output_format(args.json);
@@ -1054,9 +2303,38 @@ fn handle_cleanup(args: CleanupArgs) -> Result<()> {
             // Print summary
             let duration_secs = (end - start).whole_milliseconds() as f64 / 1000.0;
             println!("\nDeleted {} files ({} bytes) in {:.2}s", result.total_files, result.total_bytes, duration_secs);
+            let breakdown: Vec<String> = [
+                (jozin_core::FileType::Sidecar, "Sidecars"),
+                (jozin_core::FileType::Thumbnail, "Thumbnails"),
+                (jozin_core::FileType::Backup, "Backups"),
+                (jozin_core::FileType::Cache, "Cache"),
+            ]
+            .iter()
+            .filter_map(|(file_type, label)| {
+                result
+                    .bytes_by_type
+                    .get(file_type)
+                    .map(|bytes| format!("{}: {}", label, format_bytes(*bytes)))
+            })
+            .collect();
+            if !breakdown.is_empty() {
+                println!("  {}", breakdown.join(" | "));
+            }
             if result.failed > 0 {
                 println!("  Failed: {}", result.failed);
             }
+            if !result.updated_sidecars.is_empty() {
+                println!("  Removed deleted thumbnails from {} sidecars", result.updated_sidecars.len());
+            }
+            if !result.would_orphan_thumbnail_refs.is_empty() {
+                eprintln!(
+                    "Warning: {} sidecars list deleted thumbnails (use --update-sidecars to remove them):",
+                    result.would_orphan_thumbnail_refs.len()
+                );
+                for sidecar in &result.would_orphan_thumbnail_refs {
+                    eprintln!("  {}", sidecar);
+                }
+            }
         }
         OutputFormat::Json => {
             // Print JSON to stdout
@@ -1073,16 +2351,42 @@ fn handle_cleanup(args: CleanupArgs) -> Result<()> {
 /// Phase 1 stub: prints parsed parameters as JSON.
 /// Phase 2+: will call jozin_core::faces functions.
 fn handle_faces(args: FacesArgs) -> Result<()> {
+    if args.anonymise_batch {
+        return handle_anonymise_batch(args);
+    }
+    if args.contact_sheet {
+        return handle_contact_sheet(args);
+    }
+    if args.assign_cluster.is_some() || args.clear_clusters {
+        return handle_clusters(args);
+    }
+    if args.privacy_report {
+        return handle_privacy_report(args);
+    }
+    if args.delete_person.is_some() {
+        return handle_delete_person(args);
+    }
+    if args.export_embeddings.is_some() || args.import_embeddings.is_some() {
+        return handle_embeddings_transfer(args);
+    }
+
     let start = OffsetDateTime::now_utc();
 
+    if args.estimate_age {
+        eprintln!("Privacy notice: {}", AGE_PRIVACY_NOTICE);
+    }
+
     let module = if args.dry_run { "DRY RUN: faces".to_string() } else { "faces".to_string() };
     let data = StubResponse {
         module,
         parameters: serde_json::json!({
-            "path": args.path.display().to_string(),
+            "path": args.path.unwrap_or_default().display().to_string(),
             "recursive": args.recursive,
             "model": args.model,
             "identify": args.identify,
+            "landmarks": args.landmarks,
+            "estimate_age": args.estimate_age,
+            "age_model": args.age_model,
             "train": args.train,
             "min_score": args.min_score.unwrap_or(0.8),
             "max_threads": args.max_threads.unwrap_or_else(default_max_threads),
@@ -1098,11 +2402,304 @@ fn handle_faces(args: FacesArgs) -> Result<()> {
     Ok(())
 }
 
+/// Handles `faces --contact-sheet`
+///
+/// Loads existing sidecars under the path and renders their faces into a grid
+/// image. Requires the `faces` feature.
+#[cfg(feature = "faces")]
+fn handle_contact_sheet(args: FacesArgs) -> Result<()> {
+    let start = OffsetDateTime::now_utc();
+
+    // `requires = "output"` guarantees presence
+    let output = args.output.unwrap_or_default();
+    // Required unless --anonymise-batch
+    let path = args.path.unwrap_or_default();
+    let sidecars = jozin_core::scan::load_sidecars(&path, args.recursive)?;
+    let result = jozin_core::faces::generate_contact_sheet(
+        &sidecars,
+        args.person.as_deref(),
+        CONTACT_SHEET_MAX_FACES,
+        args.grid_cols.unwrap_or(10),
+        args.face_size.unwrap_or(128),
+        &output,
+    )?;
+
+    let end = OffsetDateTime::now_utc();
+    let response = OperationResponse::new(result, start, end)?;
+    println!("{}", serde_json::to_string_pretty(&response)?);
+
+    Ok(())
+}
+
+#[cfg(not(feature = "faces"))]
+fn handle_contact_sheet(_args: FacesArgs) -> Result<()> {
+    Err(JozinError::NotSupported {
+        feature: "faces (rebuild with --features faces)".to_string(),
+    })
+}
+
+/// Handles `faces --assign-cluster` / `--clear-clusters`
+///
+/// Updates the faces in the sidecars under the path. Requires the `faces`
+/// feature.
+#[cfg(feature = "faces")]
+fn handle_clusters(args: FacesArgs) -> Result<()> {
+    let start = OffsetDateTime::now_utc();
+
+    // Required unless --anonymise-batch
+    let path = args.path.unwrap_or_default();
+    let result = match (&args.assign_cluster, &args.name) {
+        (Some(cluster_id), Some(name)) => {
+            jozin_core::faces::assign_cluster(&path, args.recursive, cluster_id, name, args.dry_run)?
+        }
+        _ => jozin_core::faces::clear_clusters(&path, args.recursive, args.dry_run)?,
+    };
+
+    let end = OffsetDateTime::now_utc();
+
+    match determine_output_format(args.json) {
+        OutputFormat::Human => {
+            let prefix = if args.dry_run { "DRY RUN: " } else { "" };
+            println!("{}Updated {} faces in {} sidecars", prefix, result.faces_updated, result.sidecars_updated);
+        }
+        OutputFormat::Json => {
+            let response = OperationResponse::new(result, start, end)?;
+            println!("{}", serde_json::to_string_pretty(&response)?);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(not(feature = "faces"))]
+fn handle_clusters(_args: FacesArgs) -> Result<()> {
+    Err(JozinError::NotSupported {
+        feature: "faces (rebuild with --features faces)".to_string(),
+    })
+}
+
+/// Handles `faces --privacy-report`
+///
+/// Writes the report to `--output` if given, otherwise prints it. Requires
+/// the `faces` feature.
+#[cfg(feature = "faces")]
+fn handle_privacy_report(args: FacesArgs) -> Result<()> {
+    let start = OffsetDateTime::now_utc();
+
+    // Required unless --anonymise-batch
+    let path = args.path.unwrap_or_default();
+    let report = jozin_core::faces::privacy_report(&path, args.recursive)?;
+
+    let end = OffsetDateTime::now_utc();
+
+    if let Some(output) = &args.output {
+        std::fs::write(output, serde_json::to_string_pretty(&report)?)?;
+    }
+    match (determine_output_format(args.json), &args.output) {
+        (OutputFormat::Human, Some(output)) => {
+            println!("Wrote privacy report for {} persons to {}", report.person_inventory.len(), output.display());
+        }
+        (OutputFormat::Human, None) => {
+            let mut persons: Vec<_> = report.person_inventory.values().collect();
+            persons.sort_by(|a, b| a.name.cmp(&b.name));
+            let persons: Vec<String> = persons
+                .into_iter()
+                .map(|person| {
+                    format!(
+                        "{}: {} faces in {} photos ({} to {})",
+                        person.name,
+                        person.face_count,
+                        person.photo_paths.len(),
+                        person.first_seen,
+                        person.last_seen
+                    )
+                })
+                .collect();
+            let text = ReportBuilder::new("Privacy report", ReportFormat::Human)
+                .section("Persons", persons.len())
+                .detail(&persons)
+                .section("Unknown faces", report.unknown_faces)
+                .section("Embedding hashes", report.embedding_hashes)
+                .timing(duration_ms(start, end))
+                .build();
+            println!("{}", text);
+        }
+        (OutputFormat::Json, _) => {
+            let response = OperationResponse::new(report, start, end)?;
+            println!("{}", serde_json::to_string_pretty(&response)?);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(not(feature = "faces"))]
+fn handle_privacy_report(_args: FacesArgs) -> Result<()> {
+    Err(JozinError::NotSupported {
+        feature: "faces (rebuild with --features faces)".to_string(),
+    })
+}
+
+/// Handles `faces --delete-person`
+///
+/// Requires the `faces` feature.
+#[cfg(feature = "faces")]
+fn handle_delete_person(args: FacesArgs) -> Result<()> {
+    let start = OffsetDateTime::now_utc();
+
+    // Required unless --anonymise-batch
+    let path = args.path.unwrap_or_default();
+    let person = args.delete_person.unwrap_or_default();
+    let result = jozin_core::faces::delete_person(&path, args.recursive, &person, args.dry_run)?;
+
+    let end = OffsetDateTime::now_utc();
+
+    match determine_output_format(args.json) {
+        OutputFormat::Human => {
+            let prefix = if args.dry_run { "DRY RUN: " } else { "" };
+            println!(
+                "{}Removed {} from {} faces in {} sidecars ({} backups)",
+                prefix, person, result.faces_updated, result.sidecars_updated, result.backups_scrubbed
+            );
+            if result.removed_from_database {
+                println!("{}Removed {} from trained persons", prefix, person);
+            }
+        }
+        OutputFormat::Json => {
+            let response = OperationResponse::new(result, start, end)?;
+            println!("{}", serde_json::to_string_pretty(&response)?);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(not(feature = "faces"))]
+fn handle_delete_person(_args: FacesArgs) -> Result<()> {
+    Err(JozinError::NotSupported {
+        feature: "faces (rebuild with --features faces)".to_string(),
+    })
+}
+
+/// Handles `faces --anonymise-batch`
+///
+/// Mirrors `--src` into `--dst` with faces redacted. Requires the `faces`
+/// feature.
+#[cfg(feature = "faces")]
+fn handle_anonymise_batch(args: FacesArgs) -> Result<()> {
+    use jozin_core::faces::{anonymise_batch, AnonymiseMethod};
+
+    let start = OffsetDateTime::now_utc();
+
+    // `requires_all` guarantees presence
+    let (src, dst) = (args.src.unwrap_or_default(), args.dst.unwrap_or_default());
+    let method = match args.method.unwrap_or(AnonymiseMethodArg::Pixelate) {
+        AnonymiseMethodArg::Pixelate => AnonymiseMethod::Pixelate {
+            block_size: args.block_size.unwrap_or(16),
+        },
+        AnonymiseMethodArg::Blur => AnonymiseMethod::Blur { sigma: 8.0 },
+        AnonymiseMethodArg::Fill => AnonymiseMethod::Fill,
+    };
+    let max_threads = args.max_threads.unwrap_or_else(default_max_threads);
+
+    let output_format = determine_output_format(args.json);
+    let result = match output_format {
+        OutputFormat::Human => anonymise_batch(
+            &src,
+            &dst,
+            method,
+            args.recursive,
+            max_threads,
+            Some(&|event| print_progress(&src, event)),
+        )?,
+        OutputFormat::Json => anonymise_batch(&src, &dst, method, args.recursive, max_threads, None)?,
+    };
+
+    let end = OffsetDateTime::now_utc();
+
+    match output_format {
+        OutputFormat::Human => {
+            println!("\nProcessed {} images", result.processed);
+            println!("  Anonymised: {} ({} faces)", result.anonymised, result.faces_redacted);
+            println!("  Copied unchanged: {}", result.copied_unchanged);
+            println!("  Failed: {}", result.failed);
+        }
+        OutputFormat::Json => {
+            let response = OperationResponse::new(result, start, end)?;
+            println!("{}", serde_json::to_string_pretty(&response)?);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(not(feature = "faces"))]
+fn handle_anonymise_batch(_args: FacesArgs) -> Result<()> {
+    Err(JozinError::NotSupported {
+        feature: "faces (rebuild with --features faces)".to_string(),
+    })
+}
+
+/// Handles `faces --export-embeddings` / `--import-embeddings`
+///
+/// The library's person database lives at `<path>/.jozin/persons.bin`.
+/// Requires the `faces` feature.
+#[cfg(feature = "faces")]
+fn handle_embeddings_transfer(args: FacesArgs) -> Result<()> {
+    use jozin_core::faces::{export_embeddings, import_embeddings, EmbeddingFormat, MergeStrategy, PersonDatabase};
+
+    let start = OffsetDateTime::now_utc();
+    // Required unless --anonymise-batch
+    let path = args.path.unwrap_or_default();
+    let mut db = PersonDatabase::load(&path)?;
+
+    let data = if let Some(output) = &args.export_embeddings {
+        export_embeddings(&db, output, EmbeddingFormat::from_path(output))?;
+        serde_json::json!({
+            "exported": db.persons.len(),
+            "output_path": output.display().to_string(),
+        })
+    } else {
+        // Guarded by the caller: one of the two is set
+        let input = args.import_embeddings.unwrap_or_default();
+        let strategy = match args.merge_strategy.unwrap_or(MergeStrategyArg::KeepExisting) {
+            MergeStrategyArg::Replace => MergeStrategy::Replace,
+            MergeStrategyArg::KeepExisting => MergeStrategy::KeepExisting,
+            MergeStrategyArg::Average => MergeStrategy::Average,
+        };
+        let result = import_embeddings(&mut db, &input, strategy)?;
+        if !args.dry_run {
+            db.save(&path)?;
+        }
+        serde_json::to_value(result)?
+    };
+
+    let end = OffsetDateTime::now_utc();
+    let response = OperationResponse::new(data, start, end)?;
+    println!("{}", serde_json::to_string_pretty(&response)?);
+
+    Ok(())
+}
+
+#[cfg(not(feature = "faces"))]
+fn handle_embeddings_transfer(_args: FacesArgs) -> Result<()> {
+    Err(JozinError::NotSupported {
+        feature: "faces (rebuild with --features faces)".to_string(),
+    })
+}
+
 /// Handles tags command
 ///
-/// Phase 1 stub: prints parsed parameters as JSON.
-/// Phase 2+: will call jozin_core::tags functions.
+/// `--suggest-only` calls jozin_core::tags::suggest_tags.
+/// Otherwise a Phase 1 stub that prints parsed parameters as JSON.
 fn handle_tags(args: TagsArgs) -> Result<()> {
+    if args.suggest_only {
+        return handle_suggest_tags(args);
+    }
+    if args.hierarchy {
+        return handle_tag_hierarchy(args);
+    }
+
     let start = OffsetDateTime::now_utc();
 
     let module = if args.dry_run { "DRY RUN: tags".to_string() } else { "tags".to_string() };
@@ -1127,6 +2724,106 @@ fn handle_tags(args: TagsArgs) -> Result<()> {
     Ok(())
 }
 
+/// Suggested tags for one image (`tags --suggest-only` output)
+#[cfg(feature = "tags")]
+#[derive(Serialize)]
+struct TagSuggestion {
+    path: String,
+    tags: Vec<jozin_core::Tag>,
+}
+
+/// Handles `tags --suggest-only`
+///
+/// Loads existing sidecars under the path and prints the tags that would be
+/// added. Never writes. Requires the `tags` feature.
+#[cfg(feature = "tags")]
+fn handle_suggest_tags(args: TagsArgs) -> Result<()> {
+    use jozin_core::tags::{suggest_tags, TagMode as CoreTagMode};
+
+    let start = OffsetDateTime::now_utc();
+
+    let mode = match args.mode.unwrap_or(TagMode::Both) {
+        TagMode::Ml => CoreTagMode::Ml,
+        TagMode::Rules => CoreTagMode::Rules,
+        TagMode::Both => CoreTagMode::Both,
+    };
+    let min_score = args.min_score.unwrap_or(0.6);
+
+    let mut suggestions = Vec::new();
+    for sidecar in jozin_core::scan::load_sidecars(&args.path, args.recursive)? {
+        let tags = suggest_tags(&sidecar, mode, min_score)?;
+        suggestions.push(TagSuggestion {
+            path: sidecar.source.file_path,
+            tags,
+        });
+    }
+
+    let end = OffsetDateTime::now_utc();
+    match determine_output_format(args.json) {
+        OutputFormat::Human => {
+            for suggestion in &suggestions {
+                let labels: Vec<&str> = suggestion.tags.iter().map(|t| t.label.as_str()).collect();
+                let labels = if labels.is_empty() { "(none)".to_string() } else { labels.join(", ") };
+                println!("{}: {}", suggestion.path, labels);
+            }
+        }
+        OutputFormat::Json => {
+            let response = OperationResponse::new(suggestions, start, end)?;
+            println!("{}", serde_json::to_string_pretty(&response)?);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(not(feature = "tags"))]
+fn handle_suggest_tags(_args: TagsArgs) -> Result<()> {
+    Err(JozinError::NotSupported {
+        feature: "tags (rebuild with --features tags)".to_string(),
+    })
+}
+
+/// Handles `tags --hierarchy`
+///
+/// Builds the tag tree of the sidecars under the path and prints it indented
+/// (one level per `/`), with the number of tags at or below each node.
+#[cfg(feature = "tags")]
+fn handle_tag_hierarchy(args: TagsArgs) -> Result<()> {
+    use jozin_core::tags::{HierarchyNode, TagHierarchy};
+
+    fn print_node(node: &HierarchyNode, depth: usize) {
+        println!("{}{} ({})", "  ".repeat(depth), node.name(), node.count);
+        for child in &node.children {
+            print_node(child, depth + 1);
+        }
+    }
+
+    let start = OffsetDateTime::now_utc();
+    let hierarchy = TagHierarchy::build(&jozin_core::scan::load_sidecars(&args.path, args.recursive)?);
+
+    let end = OffsetDateTime::now_utc();
+    match determine_output_format(args.json) {
+        OutputFormat::Human => {
+            for node in &hierarchy.root.children {
+                print_node(node, 0);
+            }
+        }
+        OutputFormat::Json => {
+            let response = OperationResponse::new(hierarchy, start, end)?;
+            println!("{}", serde_json::to_string_pretty(&response)?);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(not(feature = "tags"))]
+fn handle_tag_hierarchy(_args: TagsArgs) -> Result<()> {
+    Err(JozinError::NotSupported {
+        feature: "tags (rebuild with --features tags)".to_string(),
+    })
+}
+
 /// Handles thumbs command
 ///
 /// Phase 1 stub: prints parsed parameters as JSON.
@@ -1143,7 +2840,12 @@ fn handle_thumbs(args: ThumbsArgs) -> Result<()> {
             "sizes": args.sizes.unwrap_or_else(|| vec![512]),
             "format": args.format.as_ref().map(|f| f.as_str()).unwrap_or("jpg"),
             "quality": args.quality.unwrap_or(85),
+            "video_thumbnail_time": args.video_thumbnail_time.unwrap_or(1.0),
             "overwrite": args.overwrite,
+            "dominant_color": args.dominant_color,
+            "hdr_tonemap": args.hdr_tonemap.as_ref().map(|t| t.as_str()).unwrap_or("reinhard"),
+            "animated": args.animated,
+            "max_gif_size": args.max_gif_size,
             "max_threads": args.max_threads.unwrap_or_else(default_max_threads),
         }),
         dry_run: if args.dry_run { Some(true) } else { None },
@@ -1159,9 +2861,26 @@ fn handle_thumbs(args: ThumbsArgs) -> Result<()> {
 
 /// Handles verify command
 ///
-/// Phase 1 stub: prints parsed parameters as JSON.
-/// Phase 2+: will call jozin_core::verify functions.
+/// `--check-thumbnails` / `--check-hashes` / `--check-formats` /
+/// `--check-privacy` / `--check-encoding` / `--validate-encoding` / `--pipeline-signature` /
+/// `--auto-reference` / `--plugin` call jozin_core::verify::verify_path_with_plugins.
+/// Otherwise a Phase 1 stub that prints parsed parameters as JSON.
 fn handle_verify(args: VerifyArgs) -> Result<()> {
+    if args.check_thumbnails
+        || args.check_hashes
+        || args.check_formats
+        || args.check_privacy
+        || args.check_encoding
+        || args.validate_encoding
+        || args.pipeline_signature.is_some()
+        || args.auto_reference
+        || args.normalise_paths
+        || args.restore_from_backup
+        || !args.plugins.is_empty()
+    {
+        return handle_verify_checks(args);
+    }
+
     let start = OffsetDateTime::now_utc();
 
     let data = StubResponse {
@@ -1171,6 +2890,7 @@ fn handle_verify(args: VerifyArgs) -> Result<()> {
             "recursive": args.recursive,
             "fix": args.fix,
             "strict": args.strict,
+            "allow_newer_schema": args.allow_newer_schema,
             "pipeline_signature": args.pipeline_signature,
         }),
         dry_run: None,
@@ -1184,39 +2904,848 @@ fn handle_verify(args: VerifyArgs) -> Result<()> {
     Ok(())
 }
 
-/// Handles migrate command
+/// Handles `verify --check-thumbnails` / `--check-hashes` / `--check-formats` /
+/// `--check-privacy` / `--check-encoding` / `--validate-encoding` / `--pipeline-signature` /
+/// `--auto-reference` / `--normalise-paths` / `--restore-from-backup` / `--plugin`
 ///
-/// Phase 1 stub: prints parsed parameters as JSON.
-/// Phase 2+: will call jozin_core::migrate functions.
-fn handle_migrate(args: MigrateArgs) -> Result<()> {
+/// Prints one line per sidecar problem and plugin warning, library warnings,
+/// then a summary.
+fn handle_verify_checks(args: VerifyArgs) -> Result<()> {
+    use jozin_core::verify::{verify_path_with_plugins, ValidationSource, VerifyOptions};
+
     let start = OffsetDateTime::now_utc();
 
-    let module = if args.dry_run { "DRY RUN: migrate".to_string() } else { "migrate".to_string() };
-    let data = StubResponse {
-        module,
-        parameters: serde_json::json!({
-            "path": args.path.display().to_string(),
-            "recursive": args.recursive,
-            "from": args.from,
-            "to": args.to,
-            "backup": args.backup,
-        }),
-        dry_run: if args.dry_run { Some(true) } else { None },
+    let options = VerifyOptions {
+        check_thumbnails: args.check_thumbnails,
+        check_hashes: args.check_hashes,
+        check_formats: args.check_formats,
+        check_privacy: args.check_privacy,
+        reference_signature: match &args.pipeline_signature {
+            // Validated in validate_verify_args
+            Some(json) => Some(serde_json::from_str(json)?),
+            None if args.auto_reference => Some(jozin_core::PipelineSignature::current()),
+            None => None,
+        },
+        fix: args.fix,
+        normalise_paths: args.normalise_paths,
+        symlinks: args.symlinks.map(Into::into).unwrap_or_default(),
+        allow_newer_schema: args.allow_newer_schema,
+        strict: args.strict,
+        restore_from_backup: args.restore_from_backup,
+        dry_run: args.dry_run,
+        check_encoding: args.check_encoding,
+        quarantine_corrupt: args.quarantine_corrupt,
+        quarantine_dir: None,
+        validate_encoding: args.validate_encoding,
     };
+    let plugins = load_plugins(&args.plugins)?;
+    let writes = args.fix || args.restore_from_backup || args.quarantine_corrupt;
+    let _lock = lock_library(&args.path, args.dry_run || !writes)?;
+    let result = verify_path_with_plugins(&args.path, args.recursive, &options, &plugins)?;
 
     let end = OffsetDateTime::now_utc();
-    let response = OperationResponse::new(data, start, end)?;
+    match determine_output_format(args.json) {
+        OutputFormat::Human => {
+            let mut issues = Vec::new();
+            for sidecar in &result.sidecars {
+                issues.extend(
+                    sidecar
+                        .statuses
+                        .iter()
+                        .filter_map(describe_verify_status)
+                        .map(|issue| format!("{}: {}", sidecar.sidecar_path, issue)),
+                );
+                issues.extend(sidecar.warnings.iter().map(|warning| match &warning.source {
+                    ValidationSource::Plugin { name } => {
+                        format!("{}: [{}] {}", sidecar.sidecar_path, name, warning.message)
+                    }
+                    ValidationSource::Builtin => format!("{}: {}", sidecar.sidecar_path, warning.message),
+                }));
+            }
 
-    println!("{}", serde_json::to_string_pretty(&response)?);
+            let mut report = ReportBuilder::new("Verify", ReportFormat::Human)
+                .section("Sidecars", result.total_sidecars)
+                .section("OK", result.ok)
+                .section("With issues", result.with_issues)
+                .detail(&issues);
+            if args.fix {
+                report = report
+                    .section("Thumbnails to regenerate", result.regeneration_queue.len())
+                    .section("Regenerated", result.regenerated);
+            }
+            if args.normalise_paths {
+                report = report.section("Normalised source paths", result.paths_normalised);
+            }
+            if result.labels_normalised > 0 {
+                report = report.section("Normalised tag labels", result.labels_normalised);
+            }
+            if !result.rescan_queue.is_empty() {
+                report = report
+                    .section("Need a rescan (hash algorithm changed)", result.rescan_queue.len())
+                    .detail(&result.rescan_queue);
+            }
+            if !result.restored.is_empty() {
+                let restored: Vec<String> =
+                    result.restored.iter().map(|r| r.backup_path.display().to_string()).collect();
+                report = report.section("Restored from backup", restored.len()).detail(&restored);
+            }
+            if !result.restore_candidates.is_empty() {
+                report = report
+                    .section("Would restore from backup", result.restore_candidates.len())
+                    .detail(&result.restore_candidates);
+            }
+            if !result.quarantined.is_empty() {
+                let quarantined: Vec<String> = result
+                    .quarantined
+                    .iter()
+                    .map(|e| format!("{} -> {}", e.original_path.display(), e.quarantined_path.display()))
+                    .collect();
+                report = report.section("Quarantined", quarantined.len()).detail(&quarantined);
+            }
+            for warning in &result.warnings {
+                report = report.warning(&warning.message);
+            }
+            println!("{}", report.timing(duration_ms(start, end)).build());
+        }
+        OutputFormat::Json => {
+            let response = OperationResponse::new(result, start, end)?;
+            println!("{}", serde_json::to_string_pretty(&response)?);
+        }
+    }
 
     Ok(())
 }
 
-// ============================================================================
-// Main Entry Point
-// ============================================================================
+/// One-line description of a `verify` finding; `None` for `Ok`.
+fn describe_verify_status(status: &jozin_core::verify::VerifyStatus) -> Option<String> {
+    use jozin_core::verify::VerifyStatus;
 
-/// Executes the requested command with validation and error handling
+    Some(match status {
+        VerifyStatus::Ok => return None,
+        VerifyStatus::CorruptJson { error } => format!("corrupt sidecar: {}", error),
+        VerifyStatus::MissingThumbnail { expected_path, size } => {
+            format!("missing {}px thumbnail {}", size, expected_path)
+        }
+        VerifyStatus::CorruptThumbnail { path, error } => format!("corrupt thumbnail {}: {}", path, error),
+        VerifyStatus::InvalidLandmarks { face_index } => format!("face {} has landmarks outside 0-1", face_index),
+        VerifyStatus::HashMismatch { algorithm, .. } => format!("{} hash mismatch (image changed)", algorithm),
+        VerifyStatus::FormatMismatch { recorded, detected } => {
+            format!("recorded format {} but file is {}", recorded, detected)
+        }
+        VerifyStatus::NewerSchema { sidecar_version, supported_version } => format!(
+            "schema {} is newer than supported {} (upgrade Jožin)",
+            sidecar_version, supported_version
+        ),
+        VerifyStatus::NewerProducer { producer_version, running_version } => format!(
+            "written by Jožin {}, running {} (upgrade Jožin)",
+            producer_version, running_version
+        ),
+        VerifyStatus::PrivacySensitiveData { fields } => format!("personal data in {}", fields.join(", ")),
+        VerifyStatus::StaleSignature { fields, .. } => {
+            let fields: Vec<String> = fields.iter().map(ToString::to_string).collect();
+            format!("stale pipeline signature ({} differ)", fields.join(", "))
+        }
+        VerifyStatus::CorruptImageData { error } => format!("corrupt image data: {}", error),
+        VerifyStatus::InvalidEncoding { format, error } => format!("invalid {} encoding: {}", format, error),
+        VerifyStatus::HdrWithoutTonemap { path } => format!(
+            "HDR thumbnail {} not tone mapped (regenerate with jozin thumbs --overwrite)",
+            path
+        ),
+    })
+}
+
+/// Loads `verify --plugin` libraries
+#[cfg(feature = "plugins")]
+fn load_plugins(paths: &[PathBuf]) -> Result<Vec<Box<dyn jozin_core::verify::ValidationPlugin>>> {
+    paths
+        .iter()
+        .map(|path| jozin_core::verify::PluginLoader::load(path))
+        .collect()
+}
+
+#[cfg(not(feature = "plugins"))]
+fn load_plugins(paths: &[PathBuf]) -> Result<Vec<Box<dyn jozin_core::verify::ValidationPlugin>>> {
+    if paths.is_empty() {
+        return Ok(Vec::new());
+    }
+    Err(JozinError::NotSupported {
+        feature: "plugins (rebuild with --features plugins)".to_string(),
+    })
+}
+
+/// Handles migrate command
+///
+/// `--preview` / `--preview-all` call jozin_core::migrate::preview_migration and
+/// `--from-backup` calls jozin_core::migrate::migrate_path.
+/// Otherwise a Phase 1 stub that prints parsed parameters as JSON.
+fn handle_migrate(args: MigrateArgs) -> Result<()> {
+    if args.compress || args.decompress {
+        return handle_migrate_compression(args);
+    }
+    if args.preview {
+        return handle_migrate_preview(args);
+    }
+    if args.count {
+        return handle_migrate_count(args);
+    }
+    if args.from_backup.is_some() {
+        return handle_migrate_from_backup(args);
+    }
+
+    let start = OffsetDateTime::now_utc();
+
+    let module = if args.dry_run { "DRY RUN: migrate".to_string() } else { "migrate".to_string() };
+    let data = StubResponse {
+        module,
+        parameters: serde_json::json!({
+            "path": args.path.display().to_string(),
+            "recursive": args.recursive,
+            "from": args.from,
+            "to": args.to,
+            "backup": args.backup,
+        }),
+        dry_run: if args.dry_run { Some(true) } else { None },
+    };
+
+    let end = OffsetDateTime::now_utc();
+    let response = OperationResponse::new(data, start, end)?;
+
+    println!("{}", serde_json::to_string_pretty(&response)?);
+
+    Ok(())
+}
+
+/// Handles `migrate --compress` / `--decompress`: converts sidecars in place.
+fn handle_migrate_compression(args: MigrateArgs) -> Result<()> {
+    let start = OffsetDateTime::now_utc();
+    let _lock = lock_library(&args.path, args.dry_run)?;
+    let result = jozin_core::util::compression::convert_sidecars(&args.path, args.recursive, args.compress, args.dry_run)?;
+    let end = OffsetDateTime::now_utc();
+
+    match determine_output_format(args.json) {
+        OutputFormat::Human => {
+            let prefix = if args.dry_run { "DRY RUN: " } else { "" };
+            let verb = if args.compress { "Compressed" } else { "Decompressed" };
+            println!("{}{}: {}", prefix, verb, result.converted);
+            println!("Already done: {}", result.already_converted);
+            println!("Skipped (both forms exist): {}", result.skipped);
+            println!("Size: {} → {} bytes", result.bytes_before, result.bytes_after);
+        }
+        OutputFormat::Json => {
+            let response = OperationResponse::new(result, start, end)?;
+            println!("{}", serde_json::to_string_pretty(&response)?);
+        }
+    }
+
+    Ok(())
+}
+
+/// Handles `migrate --preview`: diffs sidecars in memory, never writes.
+fn handle_migrate_preview(args: MigrateArgs) -> Result<()> {
+    let start = OffsetDateTime::now_utc();
+
+    let sample = if args.preview_all { None } else { Some(args.sample) };
+    let preview = jozin_core::migrate::preview_migration(&args.path, args.recursive, &args.to, sample)?;
+
+    let end = OffsetDateTime::now_utc();
+    match determine_output_format(args.json) {
+        OutputFormat::Human => {
+            let text = format_migration_preview(&preview, !args.no_color);
+            if args.preview_all {
+                print_paged(&text);
+            } else {
+                print!("{}", text);
+            }
+        }
+        OutputFormat::Json => {
+            let response = OperationResponse::new(preview, start, end)?;
+            println!("{}", serde_json::to_string_pretty(&response)?);
+        }
+    }
+
+    Ok(())
+}
+
+/// Handles `migrate --dry-run --count`: reads sidecar headers only.
+fn handle_migrate_count(args: MigrateArgs) -> Result<()> {
+    let start = OffsetDateTime::now_utc();
+    let counts = jozin_core::migrate::count_pending(&args.path, &args.to, args.recursive)?;
+    let end = OffsetDateTime::now_utc();
+
+    match determine_output_format(args.json) {
+        OutputFormat::Human => {
+            println!("Would migrate:   {}", counts.would_migrate);
+            println!("Already current: {}", counts.already_current);
+            println!("Unreadable:      {}", counts.unreadable);
+        }
+        OutputFormat::Json => {
+            let response = OperationResponse::new(counts, start, end)?;
+            println!("{}", serde_json::to_string_pretty(&response)?);
+        }
+    }
+
+    Ok(())
+}
+
+/// Handles `migrate --from-backup`: migrates `.bakN` and promotes it.
+fn handle_migrate_from_backup(args: MigrateArgs) -> Result<()> {
+    use jozin_core::migrate::{MigrateOptions, MigrateStatus, SourcePreference};
+
+    let start = OffsetDateTime::now_utc();
+    let _lock = lock_library(&args.path, args.dry_run)?;
+    let source_preference = match args.from_backup {
+        Some(2) => SourcePreference::Backup2,
+        _ => SourcePreference::Backup1,
+    };
+    let options = MigrateOptions {
+        dry_run: args.dry_run,
+        backup: args.backup,
+    };
+    let result = jozin_core::migrate::migrate_path(&args.path, &args.to, args.recursive, source_preference, &options)?;
+    let end = OffsetDateTime::now_utc();
+
+    match determine_output_format(args.json) {
+        OutputFormat::Human => {
+            let prefix = if args.dry_run { "DRY RUN: " } else { "" };
+            for file in &result.files {
+                match &file.status {
+                    MigrateStatus::Migrated { from_version } => println!(
+                        "{}{} ← {} ({} → {})",
+                        prefix,
+                        file.sidecar_path.display(),
+                        file.source_path.display(),
+                        from_version,
+                        result.to_version
+                    ),
+                    MigrateStatus::NoBackupAvailable => {
+                        eprintln!("Warning: no backup for {}", file.sidecar_path.display())
+                    }
+                    MigrateStatus::Failed { error } => {
+                        eprintln!("Warning: failed to migrate {}: {}", file.source_path.display(), error)
+                    }
+                    MigrateStatus::AlreadyCurrent => {}
+                }
+            }
+            println!("Migrated:            {}", result.migrated);
+            println!("No backup available: {}", result.no_backup_available);
+            println!("Failed:              {}", result.failed);
+        }
+        OutputFormat::Json => {
+            let response = OperationResponse::new(result, start, end)?;
+            println!("{}", serde_json::to_string_pretty(&response)?);
+        }
+    }
+
+    Ok(())
+}
+
+/// Renders a migration preview as a unified diff per sidecar (see
+/// `jozin_core::util::diff_json`), coloured unless `color` is false.
+fn format_migration_preview(preview: &jozin_core::migrate::MigrationPreview, color: bool) -> String {
+    use jozin_core::util::diff_json::{colorize_diff, json_unified_diff_labeled, DEFAULT_CONTEXT_LINES};
+    use std::fmt::Write;
+
+    let mut out = String::new();
+    for diff in &preview.samples {
+        let path = diff.sidecar_path.display();
+        let text = json_unified_diff_labeled(
+            &diff.old,
+            &diff.new,
+            &format!("{} ({})", path, diff.from_version),
+            &format!("{} ({})", path, preview.to_version),
+            DEFAULT_CONTEXT_LINES,
+        );
+        out.push_str(&if color { colorize_diff(&text) } else { text });
+        out.push('\n');
+    }
+
+    let _ = writeln!(
+        out,
+        "Migration would affect {} sidecars, changing {:.1} fields per file on average",
+        preview.affected_sidecars,
+        preview.average_changes_per_file()
+    );
+    if preview.unreadable > 0 {
+        let _ = writeln!(out, "Skipped {} unreadable sidecars", preview.unreadable);
+    }
+    out
+}
+
+/// Prints text through `$PAGER` (default `less -FRX`) when stdout is a terminal.
+///
+/// Falls back to plain printing if the pager cannot be started.
+fn print_paged(text: &str) {
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    if atty::is(atty::Stream::Stdout) {
+        let pager = std::env::var("PAGER").unwrap_or_else(|_| "less -FRX".to_string());
+        let mut parts = pager.split_whitespace();
+        if let Some(program) = parts.next() {
+            if let Ok(mut child) = Command::new(program).args(parts).stdin(Stdio::piped()).spawn() {
+                if let Some(mut stdin) = child.stdin.take() {
+                    let _ = stdin.write_all(text.as_bytes());
+                }
+                let _ = child.wait();
+                return;
+            }
+        }
+    }
+    print!("{}", text);
+}
+
+/// Handles import command
+///
+/// Reads tags from the source catalog and merges them into existing sidecars.
+fn handle_import(args: ImportArgs) -> Result<()> {
+    let start = OffsetDateTime::now_utc();
+
+    let entries = match args.format {
+        ImportFormat::Lightroom => read_lightroom_catalog(&args)?,
+        ImportFormat::Digikam => read_digikam_database(&args)?,
+    };
+    let result = jozin_core::interop::merge_imported_tags(&entries, args.dry_run)?;
+
+    let end = OffsetDateTime::now_utc();
+    match determine_output_format(args.json) {
+        OutputFormat::Human => {
+            let prefix = if args.dry_run { "DRY RUN: " } else { "" };
+            println!("{}Imported tags for {} images", prefix, result.total_images);
+            println!("  Updated: {} ({} tags added)", result.updated, result.tags_added);
+            println!("  Unchanged: {}", result.unchanged);
+            println!("  Missing sidecar: {}", result.missing_sidecar);
+        }
+        OutputFormat::Json => {
+            let response = OperationResponse::new(result, start, end)?;
+            println!("{}", serde_json::to_string_pretty(&response)?);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "lightroom")]
+fn read_lightroom_catalog(args: &ImportArgs) -> Result<Vec<(PathBuf, Vec<jozin_core::Tag>)>> {
+    jozin_core::interop::lr_catalog::import_lightroom_keywords(&args.catalog, &args.path)
+}
+
+#[cfg(not(feature = "lightroom"))]
+fn read_lightroom_catalog(_args: &ImportArgs) -> Result<Vec<(PathBuf, Vec<jozin_core::Tag>)>> {
+    Err(JozinError::NotSupported {
+        feature: "lightroom (rebuild with --features lightroom)".to_string(),
+    })
+}
+
+#[cfg(feature = "digikam")]
+fn read_digikam_database(args: &ImportArgs) -> Result<Vec<(PathBuf, Vec<jozin_core::Tag>)>> {
+    let entries = jozin_core::interop::digikam::import_digikam_metadata(&args.catalog, &args.path)?;
+    Ok(entries
+        .into_iter()
+        .map(|entry| {
+            let tags = entry.to_tags();
+            (entry.image_path, tags)
+        })
+        .filter(|(_, tags)| !tags.is_empty())
+        .collect())
+}
+
+#[cfg(not(feature = "digikam"))]
+fn read_digikam_database(_args: &ImportArgs) -> Result<Vec<(PathBuf, Vec<jozin_core::Tag>)>> {
+    Err(JozinError::NotSupported {
+        feature: "digikam (rebuild with --features digikam)".to_string(),
+    })
+}
+
+/// Handles `stats`: collects, optionally saves and compares snapshots.
+fn handle_stats(args: StatsArgs) -> Result<()> {
+    use jozin_core::stats::LibraryStats;
+
+    let start = OffsetDateTime::now_utc();
+
+    let stats = LibraryStats::collect(&args.path, args.recursive)?;
+    let delta = match &args.compare {
+        Some(old_path) => Some(LibraryStats::delta(&LibraryStats::load(old_path)?, &stats)),
+        None => None,
+    };
+    if let Some(save_path) = &args.save {
+        stats.save(save_path)?;
+    }
+
+    let end = OffsetDateTime::now_utc();
+    match determine_output_format(args.json) {
+        OutputFormat::Human => {
+            let mut report = ReportBuilder::new("Library statistics", ReportFormat::Human)
+                .section("Photos", stats.total_files as usize)
+                .detail(&[format!("{} bytes", stats.total_bytes)])
+                .section("Cameras", stats.cameras.len())
+                .section("Tags", stats.tag_frequency.len())
+                .section("Faces", stats.faces as usize)
+                .section("Geotagged", stats.geotagged as usize);
+            let coverage = &stats.exif_coverage;
+            if coverage.total > 0 {
+                report = report.section("Complete EXIF", coverage.has_all).detail(&[
+                    exif_coverage_line(coverage),
+                    format!("{} photos without date, GPS or camera", coverage.missing_all),
+                ]);
+            }
+            if let Some(delta) = &delta {
+                let mut changes = vec![
+                    format!("Photos: {:+} ({:+} bytes)", delta.new_files, delta.bytes_delta),
+                    format!("Faces: {:+}", delta.face_delta),
+                    format!("Geotagged: {:+}", delta.geotagged_delta),
+                ];
+                changes.extend(delta.new_cameras.iter().map(|camera| format!("New camera: {}", camera)));
+                changes.extend(delta.removed_cameras.iter().map(|camera| format!("Removed camera: {}", camera)));
+                let mut tags: Vec<_> = delta.tag_frequency_delta.iter().collect();
+                tags.sort();
+                changes.extend(tags.into_iter().map(|(label, change)| format!("Tag {}: {:+}", label, change)));
+                report = report.section("Changes since snapshot", changes.len()).detail(&changes);
+            }
+            println!("{}", report.timing(duration_ms(start, end)).build());
+        }
+        OutputFormat::Json => {
+            let data = serde_json::json!({ "stats": stats, "delta": delta });
+            let response = OperationResponse::new(data, start, end)?;
+            println!("{}", serde_json::to_string_pretty(&response)?);
+        }
+    }
+
+    Ok(())
+}
+
+/// Handles `export`: renders all sidecars and writes them to `--output`.
+fn handle_export(args: ExportArgs) -> Result<()> {
+    let start = OffsetDateTime::now_utc();
+
+    if let ExportFormat::Iptc = args.format {
+        return handle_export_iptc(&args, start);
+    }
+    if let ExportFormat::Sqlite = args.format {
+        return handle_export_sqlite(&args, start);
+    }
+    // Checked by validate_export_args
+    let base_url = args.base_url.clone().unwrap_or_default();
+    let output = args.output.clone().unwrap_or_default();
+
+    let (contents, count) = match args.format {
+        ExportFormat::JsonLd => {
+            let exported = jozin_core::export::jsonld::export_json_ld(&args.path, &base_url, args.recursive)?;
+            let count = exported.as_array().map_or(0, Vec::len);
+            (serde_json::to_string_pretty(&exported)?, count)
+        }
+        ExportFormat::Rss => {
+            use jozin_core::export::rss::{export_rss, RssFeedConfig};
+
+            let library_name = args.path.canonicalize().ok().and_then(|path| {
+                path.file_name().map(|name| name.to_string_lossy().into_owned())
+            });
+            let config = RssFeedConfig {
+                title: args.title.clone().or(library_name).unwrap_or_else(|| RssFeedConfig::default().title),
+                link: base_url.clone(),
+                base_image_url: base_url.clone(),
+                max_items: args.max_items,
+                ..RssFeedConfig::default()
+            };
+            let feed = export_rss(&args.path, &config, args.recursive)?;
+            let count = feed.matches("<item>").count();
+            (feed, count)
+        }
+        ExportFormat::Iptc | ExportFormat::Sqlite => unreachable!("handled above"),
+    };
+    std::fs::write(&output, contents)?;
+
+    let end = OffsetDateTime::now_utc();
+    match determine_output_format(args.json) {
+        OutputFormat::Human => {
+            println!("Exported {} photos to {}", count, output.display());
+        }
+        OutputFormat::Json => {
+            let data = serde_json::json!({ "format": args.format, "exported": count, "output": output });
+            let response = OperationResponse::new(data, start, end)?;
+            println!("{}", serde_json::to_string_pretty(&response)?);
+        }
+    }
+
+    Ok(())
+}
+
+/// Handles `export --format iptc`: writes tag labels into the photos.
+fn handle_export_iptc(args: &ExportArgs, start: OffsetDateTime) -> Result<()> {
+    use jozin_core::interop::iptc::{export_library_to_iptc, IptcWriteOptions};
+
+    let options = IptcWriteOptions {
+        only_user_tags: args.only_user_tags,
+        append: args.iptc_append,
+    };
+    let results = export_library_to_iptc(&args.path, args.recursive, &options)?;
+
+    let end = OffsetDateTime::now_utc();
+    match determine_output_format(args.json) {
+        OutputFormat::Human => {
+            for result in &results {
+                println!("{}: {}", result.image_path.display(), result.keywords.join(", "));
+                if !result.stderr.is_empty() {
+                    eprintln!("Warning: {}", result.stderr);
+                }
+            }
+            println!("Wrote IPTC keywords of {} photos", results.len());
+        }
+        OutputFormat::Json => {
+            let response = OperationResponse::new(results, start, end)?;
+            println!("{}", serde_json::to_string_pretty(&response)?);
+        }
+    }
+
+    Ok(())
+}
+
+/// Handles `export --format sqlite`: writes the sidecar index to a database.
+#[cfg(feature = "sqlite")]
+fn handle_export_sqlite(args: &ExportArgs, start: OffsetDateTime) -> Result<()> {
+    // Checked by validate_export_args
+    let output = args.output.clone().unwrap_or_default();
+    let sidecars = jozin_core::scan::load_sidecars(&args.path, args.recursive)?;
+    let result = jozin_core::export::sqlite::export_to_sqlite(&sidecars, &output)?;
+
+    let end = OffsetDateTime::now_utc();
+    match determine_output_format(args.json) {
+        OutputFormat::Human => {
+            println!(
+                "Exported {} photos ({} tags, {} faces, {} thumbnails) to {}",
+                result.images,
+                result.tags,
+                result.faces,
+                result.thumbnails,
+                result.db_path.display()
+            );
+        }
+        OutputFormat::Json => {
+            let response = OperationResponse::new(result, start, end)?;
+            println!("{}", serde_json::to_string_pretty(&response)?);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(not(feature = "sqlite"))]
+fn handle_export_sqlite(_args: &ExportArgs, _start: OffsetDateTime) -> Result<()> {
+    Err(JozinError::NotSupported {
+        feature: "sqlite (rebuild with --features sqlite)".to_string(),
+    })
+}
+
+/// Handles patch command
+///
+/// Calls jozin_core::util::sidecar_patch::patch_sidecar on the sidecar of the
+/// given image (or the given sidecar itself).
+fn handle_patch(args: PatchArgs) -> Result<()> {
+    use jozin_core::util::sidecar_patch::{patch_sidecar, PatchOp, SidecarPatch};
+
+    let start = OffsetDateTime::now_utc();
+
+    let naming = LibraryConfig::discover(&args.path);
+    let sidecar_path = if naming.is_sidecar(&args.path) { args.path.clone() } else { naming.sidecar_path(&args.path) };
+
+    let mut patches = Vec::new();
+    for (op, pairs) in [
+        (PatchOp::Set, &args.set),
+        (PatchOp::ArrayAppend, &args.append),
+        (PatchOp::ArrayRemoveByValue, &args.remove_value),
+    ] {
+        for pair in pairs.chunks(2) {
+            let value = serde_json::from_str(&pair[1]).map_err(|e| JozinError::UserError {
+                message: format!("Invalid JSON value for {}: {} (strings need quotes, e.g. '\"Canon\"')", pair[0], e),
+            })?;
+            patches.push(SidecarPatch::new(pair[0].clone(), op, value));
+        }
+        if op == PatchOp::ArrayAppend {
+            for tag in &args.add_tag {
+                patches.push(SidecarPatch::new("/tags", PatchOp::ArrayAppend, serde_json::to_value(tag)?));
+            }
+        }
+    }
+    for pointer in &args.remove {
+        patches.push(SidecarPatch::new(pointer.clone(), PatchOp::Remove, serde_json::Value::Null));
+    }
+
+    patch_sidecar(&sidecar_path, &patches)?;
+
+    let end = OffsetDateTime::now_utc();
+    match determine_output_format(args.json) {
+        OutputFormat::Human => {
+            println!("Applied {} edits to {}", patches.len(), sidecar_path.display());
+        }
+        OutputFormat::Json => {
+            let data = serde_json::json!({ "sidecar_path": sidecar_path, "patches": patches });
+            let response = OperationResponse::new(data, start, end)?;
+            println!("{}", serde_json::to_string_pretty(&response)?);
+        }
+    }
+
+    Ok(())
+}
+
+/// Handles diff command
+///
+/// Renders the two sidecars with jozin_core::util::diff_json; JSON output
+/// carries the uncoloured diff and the field-level changes.
+fn handle_diff(args: DiffArgs) -> Result<()> {
+    use jozin_core::util::diff_json::{
+        colorize_diff, json_field_changes, json_unified_diff_labeled, DEFAULT_CONTEXT_LINES,
+    };
+
+    let start = OffsetDateTime::now_utc();
+
+    let (old_path, new_path) = (diff_input_path(&args.old), diff_input_path(&args.new));
+    let read = |path: &Path| -> Result<serde_json::Value> {
+        let text = jozin_core::util::compression::read_sidecar_file(path)?;
+        serde_json::from_str(&text).map_err(|e| JozinError::ValidationError {
+            message: format!("{} is not valid JSON: {}", path.display(), e),
+        })
+    };
+    let (old, new) = (read(&old_path)?, read(&new_path)?);
+    let context = args.context.unwrap_or(DEFAULT_CONTEXT_LINES);
+    let diff = json_unified_diff_labeled(
+        &old,
+        &new,
+        &old_path.display().to_string(),
+        &new_path.display().to_string(),
+        context,
+    );
+
+    let end = OffsetDateTime::now_utc();
+    match determine_output_format(args.json) {
+        OutputFormat::Human => {
+            if diff.is_empty() {
+                println!("No differences");
+            } else if args.no_color {
+                print!("{}", diff);
+            } else {
+                print!("{}", colorize_diff(&diff));
+            }
+        }
+        OutputFormat::Json => {
+            let data = serde_json::json!({
+                "old": old_path,
+                "new": new_path,
+                "changes": json_field_changes(&old, &new),
+                "diff": diff,
+            });
+            let response = OperationResponse::new(data, start, end)?;
+            println!("{}", serde_json::to_string_pretty(&response)?);
+        }
+    }
+
+    Ok(())
+}
+
+/// The file `jozin diff` reads for `path`: JSON files (sidecars, backups)
+/// as given, the sidecar (plain or gzipped) for anything else.
+fn diff_input_path(path: &Path) -> PathBuf {
+    let naming = LibraryConfig::discover(path);
+    let is_json = path.file_name().is_some_and(|name| name.to_string_lossy().contains(".json"));
+    if is_json || naming.is_sidecar(path) {
+        path.to_path_buf()
+    } else {
+        jozin_core::util::compression::existing_sidecar_path(&naming.sidecar_path(path))
+    }
+}
+
+/// Handles `correct`: shifts capture times in the photos and their sidecars,
+/// or previews the shift with `--dry-run`.
+fn handle_correct(args: CorrectArgs) -> Result<()> {
+    use jozin_core::util::exif_write::{apply_datetime_correction, parse_datetime_offset, plan_datetime_corrections};
+
+    let start = OffsetDateTime::now_utc();
+    // Validated in validate_correct_args
+    let offset = parse_datetime_offset(&args.datetime_offset)?;
+    let _lock = lock_library(&args.path, args.dry_run)?;
+    let corrections = plan_datetime_corrections(&args.path, args.recursive, offset)?;
+    let mut results = Vec::new();
+    if !args.dry_run {
+        for correction in &corrections {
+            results.push(apply_datetime_correction(correction)?);
+        }
+    }
+
+    let end = OffsetDateTime::now_utc();
+    match determine_output_format(args.json) {
+        OutputFormat::Human => {
+            let prefix = if args.dry_run { "DRY RUN: " } else { "" };
+            for correction in &corrections {
+                println!(
+                    "{}{}: {} -> {}",
+                    prefix,
+                    correction.image_path.display(),
+                    correction.original,
+                    correction.corrected
+                );
+            }
+            for result in results.iter().filter(|result| !result.stderr.is_empty()) {
+                eprintln!("Warning: {}: {}", result.image_path.display(), result.stderr);
+            }
+            let verb = if args.dry_run { "Would correct" } else { "Corrected" };
+            println!("{}{} capture times by {}", prefix, verb, args.datetime_offset);
+        }
+        OutputFormat::Json => {
+            let data = serde_json::json!({
+                "offset": args.datetime_offset,
+                "dry_run": args.dry_run,
+                "corrections": corrections,
+                "written": results.len(),
+            });
+            let response = OperationResponse::new(data, start, end)?;
+            println!("{}", serde_json::to_string_pretty(&response)?);
+        }
+    }
+
+    Ok(())
+}
+
+/// Handles capabilities command: checks external tools concurrently.
+fn handle_capabilities(args: CapabilitiesArgs) -> Result<()> {
+    use jozin_core::util::capability_check::CapabilityChecker;
+
+    let start = OffsetDateTime::now_utc();
+    let results: std::collections::BTreeMap<_, _> = CapabilityChecker::new().check_all().into_iter().collect();
+    let end = OffsetDateTime::now_utc();
+
+    match determine_output_format(args.json) {
+        OutputFormat::Human => {
+            for (tool, result) in &results {
+                let needed_for = match *tool {
+                    "ffprobe" => "video metadata",
+                    "exiftool" => "export --format iptc",
+                    "ffmpeg" => "video thumbnails",
+                    _ => "",
+                };
+                match &result.path {
+                    Some(path) if result.available => println!(
+                        "\x1b[32m✓\x1b[0m {:<9} {:<10} {} ({})",
+                        tool,
+                        result.version.as_deref().unwrap_or("unknown"),
+                        path.display(),
+                        needed_for
+                    ),
+                    _ => println!("\x1b[31m✗\x1b[0m {:<9} {:<10} not found ({} unavailable)", tool, "-", needed_for),
+                }
+            }
+        }
+        OutputFormat::Json => {
+            let response = OperationResponse::new(results, start, end)?;
+            println!("{}", serde_json::to_string_pretty(&response)?);
+        }
+    }
+
+    Ok(())
+}
+
+// ============================================================================
+// Main Entry Point
+// ============================================================================
+
+/// Executes the requested command with validation and error handling
 ///
 /// Routes each command to its validation function and handler.
 /// Returns JozinError with appropriate exit code on failure.
@@ -1250,6 +3779,25 @@ fn run_command(cmd: Cmd) -> Result<()> {
             validate_migrate_args(&args)?;
             handle_migrate(args)
         }
+        Cmd::Import(args) => {
+            validate_import_args(&args)?;
+            handle_import(args)
+        }
+        Cmd::Stats(args) => handle_stats(args),
+        Cmd::Export(args) => {
+            validate_export_args(&args)?;
+            handle_export(args)
+        }
+        Cmd::Patch(args) => {
+            validate_patch_args(&args)?;
+            handle_patch(args)
+        }
+        Cmd::Diff(args) => handle_diff(args),
+        Cmd::Correct(args) => {
+            validate_correct_args(&args)?;
+            handle_correct(args)
+        }
+        Cmd::Capabilities(args) => handle_capabilities(args),
     }
 }
 
@@ -0,0 +1,166 @@
+//! Performance regression suite
+//!
+//! One criterion binary covering the hot paths of a scan, so a single run
+//! can be compared against a stored baseline:
+//!
+//! | Group                 | Inputs                                   |
+//! |-----------------------|------------------------------------------|
+//! | `blake3`              | 1 KB, 1 MB, 10 MB, 100 MB synthetic files |
+//! | `scan_file`           | A synthetic JPEG with full EXIF          |
+//! | `scan_directory`      | 100, 1,000, 10,000 synthetic JPEGs (dry run) |
+//! | `thumbnails`          | 256 px and 512 px, JPEG and WebP (`thumbs` feature) |
+//!
+//! Fixtures are generated with [`create_synthetic_jpeg()`], hence the
+//! `test-helpers` feature:
+//!
+//! ```text
+//! cargo bench -p jozin-core --features test-helpers,thumbs --bench bench_harness -- --save-baseline main
+//! scripts/bench_regression.sh main   # fails on a > 10% regression
+//! ```
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use jozin_core::hash::compute_blake3_hash;
+use jozin_core::scan::{scan_file, scan_path, ScanConfig};
+use jozin_core::util::test_helpers::{create_synthetic_jpeg, SyntheticExif};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+const HASH_SIZES: [(&str, usize); 4] = [
+    ("1kb", 1024),
+    ("1mb", 1024 * 1024),
+    ("10mb", 10 * 1024 * 1024),
+    ("100mb", 100 * 1024 * 1024),
+];
+
+const DIRECTORY_SIZES: [usize; 3] = [100, 1_000, 10_000];
+
+/// Keeps directories small enough that listing them isn't what is measured.
+const FILES_PER_DIR: usize = 500;
+
+const FULL_EXIF: SyntheticExif<'static> = SyntheticExif {
+    camera_make: Some("Canon"),
+    camera_model: Some("EOS R5"),
+    datetime_original: Some("2024:06:01 12:00:00"),
+    gps: Some((50.0755, 14.4378)),
+    width: 1024,
+    height: 768,
+    thumbnail: None,
+};
+
+/// Fixtures shared by all benchmark groups, removed when dropped.
+struct BenchmarkSuite {
+    dir: TempDir,
+}
+
+impl BenchmarkSuite {
+    /// Runs every benchmark group.
+    fn run(c: &mut Criterion) {
+        let suite = BenchmarkSuite {
+            dir: TempDir::new().unwrap(),
+        };
+        suite.bench_hash(c);
+        suite.bench_scan_file(c);
+        suite.bench_scan_directory(c);
+        #[cfg(feature = "thumbs")]
+        suite.bench_thumbnails(c);
+    }
+
+    fn bench_hash(&self, c: &mut Criterion) {
+        let mut group = c.benchmark_group("blake3");
+        group.sample_size(10);
+        for (name, size) in HASH_SIZES {
+            let path = self.synthetic_file(&format!("{}.raw", name), size);
+            group.throughput(Throughput::Bytes(size as u64));
+            group.bench_with_input(BenchmarkId::from_parameter(name), &path, |b, path| {
+                b.iter(|| compute_blake3_hash(path).unwrap())
+            });
+            std::fs::remove_file(path).unwrap();
+        }
+        group.finish();
+    }
+
+    fn bench_scan_file(&self, c: &mut Criterion) {
+        let path = create_synthetic_jpeg(self.dir.path(), "full_exif.jpg", FULL_EXIF);
+        let mut group = c.benchmark_group("scan_file");
+        group.throughput(Throughput::Bytes(file_size(&path)));
+        group.bench_function("full_exif", |b| b.iter(|| scan_file(&path, true).unwrap()));
+        group.finish();
+    }
+
+    fn bench_scan_directory(&self, c: &mut Criterion) {
+        let config = ScanConfig {
+            recursive: true,
+            dry_run: true,
+            ..ScanConfig::default()
+        };
+        let mut group = c.benchmark_group("scan_directory");
+        group.sample_size(10);
+        for count in DIRECTORY_SIZES {
+            let root = self.dir.path().join(format!("library_{}", count));
+            let bytes = synthetic_library(&root, count);
+            group.throughput(Throughput::Bytes(bytes));
+            group.bench_with_input(BenchmarkId::from_parameter(count), &root, |b, root| {
+                b.iter(|| scan_path(root, &config, None).unwrap())
+            });
+        }
+        group.finish();
+    }
+
+    #[cfg(feature = "thumbs")]
+    fn bench_thumbnails(&self, c: &mut Criterion) {
+        use jozin_core::thumbs::{write_thumbnails, ThumbnailFormat, DEFAULT_JPEG_QUALITY};
+
+        let path = create_synthetic_jpeg(self.dir.path(), "thumb_source.jpg", FULL_EXIF);
+        let image = image::open(&path).unwrap();
+        let out_dir = self.dir.path().join("thumbs");
+        std::fs::create_dir_all(&out_dir).unwrap();
+
+        let mut group = c.benchmark_group("thumbnails");
+        group.throughput(Throughput::Bytes(file_size(&path)));
+        for format in [ThumbnailFormat::Jpg, ThumbnailFormat::Webp] {
+            for size in [256, 512] {
+                let id = BenchmarkId::new(format.extension(), size);
+                group.bench_function(id, |b| {
+                    b.iter(|| write_thumbnails(&image, "thumb", &[size], format, DEFAULT_JPEG_QUALITY, &out_dir).unwrap())
+                });
+            }
+        }
+        group.finish();
+    }
+
+    /// Writes `size` bytes of deterministic, non-repeating-enough content.
+    fn synthetic_file(&self, name: &str, size: usize) -> PathBuf {
+        let path = self.dir.path().join(name);
+        let mut file = std::fs::File::create(&path).unwrap();
+        let block: Vec<u8> = (0..size.min(1024 * 1024) as u32).map(|i| (i.wrapping_mul(2654435761) >> 24) as u8).collect();
+        for _ in 0..size / block.len() {
+            file.write_all(&block).unwrap();
+        }
+        file.sync_all().unwrap();
+        path
+    }
+}
+
+/// Creates `count` small JPEGs with EXIF under `root`; returns their total size.
+fn synthetic_library(root: &Path, count: usize) -> u64 {
+    let exif = SyntheticExif {
+        width: 16,
+        height: 16,
+        ..FULL_EXIF
+    };
+    (0..count)
+        .map(|i| {
+            let album = root.join(format!("album_{:03}", i / FILES_PER_DIR));
+            std::fs::create_dir_all(&album).unwrap();
+            file_size(&create_synthetic_jpeg(&album, &format!("IMG_{:05}.jpg", i), exif))
+        })
+        .sum()
+}
+
+fn file_size(path: &Path) -> u64 {
+    std::fs::metadata(path).unwrap().len()
+}
+
+criterion_group!(benches, BenchmarkSuite::run);
+criterion_main!(benches);
@@ -0,0 +1,38 @@
+//! Hashing throughput benchmarks
+//!
+//! Compares sequential and chunked BLAKE3 hashing on a 100 MB synthetic file.
+//! Run with `cargo bench -p jozin-core --features parallel-hash --bench hash`
+//! to measure multi-threaded chunk hashing.
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use jozin_core::hash::{compute_blake3_hash, compute_hash_parallel, DEFAULT_PARALLEL_CHUNK_BYTES};
+use std::io::Write;
+
+const FILE_SIZE: usize = 100 * 1024 * 1024;
+
+fn bench_hash(c: &mut Criterion) {
+    let dir = tempfile::TempDir::new().unwrap();
+    let path = dir.path().join("synthetic.raw");
+
+    // Deterministic, non-repeating-enough content so the hash does real work
+    let mut file = std::fs::File::create(&path).unwrap();
+    let block: Vec<u8> = (0..1024 * 1024u32).map(|i| (i.wrapping_mul(2654435761) >> 24) as u8).collect();
+    for _ in 0..FILE_SIZE / block.len() {
+        file.write_all(&block).unwrap();
+    }
+    file.sync_all().unwrap();
+
+    let mut group = c.benchmark_group("blake3_100mb");
+    group.throughput(Throughput::Bytes(FILE_SIZE as u64));
+    group.sample_size(10);
+
+    group.bench_function("sequential", |b| b.iter(|| compute_blake3_hash(&path).unwrap()));
+    group.bench_function("parallel_4mb_chunks", |b| {
+        b.iter(|| compute_hash_parallel(&path, DEFAULT_PARALLEL_CHUNK_BYTES).unwrap())
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_hash);
+criterion_main!(benches);
@@ -0,0 +1,65 @@
+//! Directory traversal benchmarks
+//!
+//! Compares the sequential walker with [`ParallelWalker`] on a synthetic
+//! library of 10,000 files, doing the per-entry work of a scan (stat plus a
+//! magic-byte read). Run with `cargo bench -p jozin-core --bench walker`.
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use jozin_core::util::parallel_walker::ParallelWalker;
+use jozin_core::util::symlink::{walker, SymlinkStrategy};
+use rayon::prelude::*;
+use std::io::Read;
+use std::path::Path;
+
+const FILE_COUNT: usize = 10_000;
+const FILES_PER_DIR: usize = 500;
+
+/// Stats a file and reads its first bytes, as content sniffing does.
+fn touch_entry(path: &Path) -> bool {
+    if !path.is_file() {
+        return false;
+    }
+    let mut header = [0u8; 16];
+    std::fs::File::open(path).and_then(|mut file| file.read(&mut header)).is_ok()
+}
+
+fn bench_walker(c: &mut Criterion) {
+    let dir = tempfile::TempDir::new().unwrap();
+    for i in 0..FILE_COUNT {
+        let album = dir.path().join(format!("album_{:03}", i / FILES_PER_DIR));
+        if i % FILES_PER_DIR == 0 {
+            std::fs::create_dir(&album).unwrap();
+        }
+        std::fs::write(album.join(format!("IMG_{:05}.jpg", i)), [0xFF, 0xD8, 0xFF, 0xE0]).unwrap();
+    }
+    let threads = std::thread::available_parallelism().map_or(4, |n| n.get()).min(16) as u16;
+
+    let mut group = c.benchmark_group("walk_10k_files");
+    group.throughput(Throughput::Elements(FILE_COUNT as u64));
+    group.sample_size(10);
+
+    group.bench_function("sequential", |b| {
+        b.iter(|| {
+            walker(dir.path(), true, SymlinkStrategy::Follow)
+                .into_iter()
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| touch_entry(entry.path()))
+                .count()
+        })
+    });
+    group.bench_function("parallel", |b| {
+        b.iter(|| {
+            ParallelWalker::new(dir.path(), true)
+                .max_threads(threads)
+                .par_iter()
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| touch_entry(entry.path()))
+                .count()
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_walker);
+criterion_main!(benches);
@@ -0,0 +1,53 @@
+//! Extension plugin example
+//!
+//! Shows how a third-party tool can attach its own metadata to Jožin sidecars
+//! through `Sidecar::extensions`, without any schema changes upstream.
+//!
+//! Usage:
+//!
+//! ```text
+//! cargo run -p jozin-core --example extension_plugin -- /photos/IMG_1234.JPG
+//! ```
+//!
+//! The example scans the photo (writing `IMG_1234.JPG.json`), stores a rating
+//! and a review flag under the plugin's namespace, and writes the sidecar back.
+//! Rescanning the photo keeps the plugin's data.
+
+use jozin_core::scan::scan_file;
+use jozin_core::{JozinError, Result};
+use std::path::PathBuf;
+
+/// Namespace owned by this plugin (reverse-DNS keeps it unique).
+const NAMESPACE: &str = "com.example.ratings-plugin";
+
+fn main() -> Result<()> {
+    let path = std::env::args()
+        .nth(1)
+        .map(PathBuf::from)
+        .ok_or_else(|| JozinError::UserError {
+            message: "usage: extension_plugin <image>".to_string(),
+        })?;
+
+    // Scan (or rescan) the photo; existing extensions are carried over
+    let mut sidecar = scan_file(&path, false)?.sidecar;
+
+    let previous = sidecar
+        .get_extension(NAMESPACE, "rating")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0);
+
+    sidecar.set_extension(NAMESPACE, "rating", serde_json::json!((previous + 1).min(5)));
+    sidecar.set_extension(NAMESPACE, "needs_review", serde_json::json!(false));
+
+    // Plugins write the sidecar the same way Jožin does: tmp file, then rename
+    let sidecar_path = PathBuf::from(format!("{}.json", path.display()));
+    let tmp_path = PathBuf::from(format!("{}.json.tmp", path.display()));
+    std::fs::write(&tmp_path, serde_json::to_string_pretty(&sidecar)?)?;
+    std::fs::rename(&tmp_path, &sidecar_path)?;
+
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&sidecar.extensions[NAMESPACE])?
+    );
+    Ok(())
+}
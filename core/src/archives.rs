@@ -0,0 +1,390 @@
+//! Archives module - Scanning images inside zip archives (`archives` feature)
+//!
+//! Old photo collections are often kept as `.zip` files. With
+//! [`ScanConfig::scan_archives`](crate::scan::ScanConfig) set, directory scans
+//! treat each archive as a folder of images:
+//!
+//! - **In memory** (default): every image entry is read from the archive
+//!   without extracting anything to disk. The sidecar is written next to the
+//!   archive.
+//! - **Extracted** (`extract_archives`): entries are unpacked to
+//!   `<dir>/<archive name>/` first and scanned like regular files.
+//!
+//! ## Archive Entry Sidecars
+//!
+//! | Entry                     | `source.file_path`               | Sidecar                              |
+//! |---------------------------|----------------------------------|--------------------------------------|
+//! | `photo.jpg`               | `/photos/old.zip/photo.jpg`      | `/photos/old.zip__photo.jpg.json`    |
+//! | `2009/beach.jpg`          | `/photos/old.zip/2009/beach.jpg` | `/photos/old.zip__2009__beach.jpg.json` |
+//!
+//! `file_hash_b3` is the BLAKE3 hash of the entry's *compressed* bytes, so
+//! hashing never needs to inflate the entry (`hash_algorithm` is ignored).
+//! Entries in subdirectories of the archive are only scanned with
+//! `recursive`. Only image extensions are considered; include/exclude
+//! patterns and content-type filters apply to the archive file itself, not
+//! its entries.
+//!
+//! `.tar.gz` archives are not supported yet.
+
+use crate::exif::read_raw_exif_from_bytes;
+use crate::scan::{build_sidecar, is_image_file, write_sidecar_with, ScanAction, ScanConfig, ScanResult, ScannedFile};
+use crate::{ImageInfo, JozinError, Result, SourceInfo};
+use std::fs::{self, File};
+use std::io::{self, BufReader, Read};
+use std::path::{Path, PathBuf};
+use time::OffsetDateTime;
+use zip::ZipArchive;
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+/// Separator between the archive name and the entry path in sidecar names.
+pub const ARCHIVE_SIDECAR_SEPARATOR: &str = "__";
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Returns `true` if the path has a `.zip` extension (case-insensitive).
+pub fn is_zip_file(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("zip"))
+}
+
+/// Returns the path whose `<path>.json` is the sidecar of an archive entry.
+///
+/// `archive_sidecar_key("/photos/old.zip", "2009/beach.jpg")` is
+/// `/photos/old.zip__2009__beach.jpg`.
+pub fn archive_sidecar_key(archive: &Path, entry_name: &str) -> PathBuf {
+    let archive_name = archive.file_name().unwrap_or_default().to_string_lossy();
+    let flattened = entry_name
+        .trim_matches('/')
+        .replace('/', ARCHIVE_SIDECAR_SEPARATOR);
+    archive.with_file_name(format!(
+        "{}{}{}",
+        archive_name, ARCHIVE_SIDECAR_SEPARATOR, flattened
+    ))
+}
+
+/// Scans the image entries of a zip archive in memory.
+///
+/// Writes one sidecar per image entry next to the archive (unless
+/// `config.dry_run`). Failures of individual entries are recorded as
+/// `ScanAction::Failed`.
+///
+/// # Arguments
+///
+/// * `archive` - Path to the `.zip` file
+/// * `config` - Scan options (`recursive`, `dry_run`, `include_raw_exif`)
+///
+/// # Errors
+///
+/// Returns `JozinError::IoError` if the archive cannot be opened or is not a
+/// valid zip file.
+///
+/// # Example
+///
+/// ```no_run
+/// use jozin_core::archives::scan_zip_archive;
+/// use jozin_core::scan::ScanConfig;
+/// use std::path::Path;
+///
+/// let files = scan_zip_archive(Path::new("/photos/old.zip"), &ScanConfig::default())?;
+/// println!("{} entries scanned", files.len());
+/// # Ok::<(), jozin_core::JozinError>(())
+/// ```
+pub fn scan_zip_archive(archive: &Path, config: &ScanConfig) -> Result<Vec<ScannedFile>> {
+    let mut zip = open_archive(archive)?;
+    let archive_modified_at = format_time(OffsetDateTime::from(fs::metadata(archive)?.modified()?))?;
+
+    let mut scanned = Vec::new();
+    for index in 0..zip.len() {
+        let name = match zip.by_index_raw(index) {
+            Ok(entry) if is_scannable_entry(entry.name(), entry.is_dir(), config.recursive) => {
+                entry.name().to_string()
+            }
+            Ok(_) => continue,
+            Err(e) => {
+                scanned.push(failed_entry(archive, &format!("#{}", index), &e.to_string()));
+                continue;
+            }
+        };
+
+        match scan_entry(&mut zip, index, archive, &name, &archive_modified_at, config) {
+            Ok(file) => scanned.push(file),
+            Err(e) => scanned.push(failed_entry(archive, &name, &e.to_string())),
+        }
+    }
+
+    Ok(scanned)
+}
+
+/// Extracts the image entries of a zip archive to `<dest_dir>/<archive name>/`.
+///
+/// Entry paths that would escape the destination (`..`, absolute paths) are
+/// skipped.
+///
+/// # Arguments
+///
+/// * `archive` - Path to the `.zip` file
+/// * `dest_dir` - Directory to extract into (created if missing)
+/// * `recursive` - Also extract entries in subdirectories of the archive
+///
+/// # Returns
+///
+/// Paths of the extracted image files.
+///
+/// # Errors
+///
+/// Returns `JozinError::IoError` if the archive cannot be read or a file
+/// cannot be written.
+pub fn extract_zip_archive(archive: &Path, dest_dir: &Path, recursive: bool) -> Result<Vec<PathBuf>> {
+    let mut zip = open_archive(archive)?;
+    let target_root = dest_dir.join(archive.file_name().unwrap_or_default());
+
+    let mut extracted = Vec::new();
+    for index in 0..zip.len() {
+        let mut entry = zip.by_index(index).map_err(zip_error)?;
+        if !is_scannable_entry(entry.name(), entry.is_dir(), recursive) {
+            continue;
+        }
+        let relative = match entry.enclosed_name() {
+            Some(relative) => relative,
+            None => {
+                eprintln!("Warning: Skipping unsafe archive entry {}", entry.name());
+                continue;
+            }
+        };
+
+        let target = target_root.join(relative);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        io::copy(&mut entry, &mut File::create(&target)?)?;
+        extracted.push(target);
+    }
+
+    Ok(extracted)
+}
+
+// ============================================================================
+// Internal Helpers
+// ============================================================================
+
+fn open_archive(archive: &Path) -> Result<ZipArchive<BufReader<File>>> {
+    ZipArchive::new(BufReader::new(File::open(archive)?)).map_err(|e| JozinError::IoError {
+        message: format!("Failed to open archive {}: {}", archive.display(), e),
+    })
+}
+
+fn zip_error(e: zip::result::ZipError) -> JozinError {
+    JozinError::IoError { message: e.to_string() }
+}
+
+/// Image entries only; nested entries only when `recursive`.
+fn is_scannable_entry(name: &str, is_dir: bool, recursive: bool) -> bool {
+    !is_dir && (recursive || !name.contains('/')) && is_image_file(Path::new(name))
+}
+
+/// Scans one entry and writes its sidecar next to the archive.
+fn scan_entry(
+    zip: &mut ZipArchive<BufReader<File>>,
+    index: usize,
+    archive: &Path,
+    name: &str,
+    archive_modified_at: &str,
+    config: &ScanConfig,
+) -> Result<ScannedFile> {
+    // Hash the compressed bytes as stored in the archive
+    let (file_hash_b3, file_size_bytes, file_modified_at) = {
+        let mut raw = zip.by_index_raw(index).map_err(zip_error)?;
+        let modified_at = raw
+            .last_modified()
+            .and_then(|dt| zip_datetime_to_rfc3339(&dt))
+            .unwrap_or_else(|| archive_modified_at.to_string());
+        let size = raw.size();
+        let mut hasher = blake3::Hasher::new();
+        io::copy(&mut raw, &mut hasher)?;
+        (hasher.finalize().to_hex().to_string(), size, modified_at)
+    };
+
+    let image = if config.include_raw_exif {
+        let mut data = Vec::new();
+        zip.by_index(index).map_err(zip_error)?.read_to_end(&mut data)?;
+        read_raw_exif_from_bytes(&data, name).map(|raw| ImageInfo {
+            exif_json: Some(raw),
+            ..ImageInfo::default()
+        })
+    } else {
+        None
+    };
+
+    let display_path = archive.join(name).display().to_string();
+    let sidecar_key = archive_sidecar_key(archive, name);
+    let source = SourceInfo {
+        file_path: display_path.clone(),
+        file_size_bytes,
+        file_hash_b3: file_hash_b3.clone(),
+        file_hash_sha256: None,
+        file_modified_at,
+        ingest_timestamp: String::new(),
+        live_photo_path: None,
+        previous_paths: Vec::new(),
+        hardlink_of: None,
+    };
+    let naming = config.library_config();
+    let sidecar = build_sidecar(&sidecar_key, source, image, &naming)?;
+
+    let sidecar_path = if config.dry_run {
+        None
+    } else {
+        write_sidecar_with(&sidecar_key, &sidecar, &naming)?;
+        Some(naming.sidecar_path(&sidecar_key).display().to_string())
+    };
+
+    Ok(ScannedFile {
+        path: display_path,
+        action: if config.dry_run { ScanAction::Skipped } else { ScanAction::Written },
+        sidecar_path,
+        error: None,
+        hash: Some(file_hash_b3),
+        size_bytes: Some(file_size_bytes),
+        backup_path: None,
+    })
+}
+
+fn failed_entry(archive: &Path, name: &str, error: &str) -> ScannedFile {
+    ScannedFile {
+        path: archive.join(name).display().to_string(),
+        action: ScanAction::Failed,
+        sidecar_path: None,
+        error: Some(error.to_string()),
+        hash: None,
+        size_bytes: None,
+        backup_path: None,
+    }
+}
+
+/// Zip timestamps carry no time zone; they are recorded as UTC.
+fn zip_datetime_to_rfc3339(dt: &zip::DateTime) -> Option<String> {
+    let month = time::Month::try_from(dt.month()).ok()?;
+    let date = time::Date::from_calendar_date(i32::from(dt.year()), month, dt.day()).ok()?;
+    let time = time::Time::from_hms(dt.hour(), dt.minute(), dt.second()).ok()?;
+    format_time(time::PrimitiveDateTime::new(date, time).assume_utc()).ok()
+}
+
+fn format_time(timestamp: OffsetDateTime) -> Result<String> {
+    timestamp
+        .format(&time::format_description::well_known::Rfc3339)
+        .map_err(|e| JozinError::InternalError {
+            message: format!("Failed to format timestamp: {}", e),
+        })
+}
+
+/// Adds an archive found during a directory scan to the scan.
+///
+/// With `config.extract_archives`, extracted images are appended to
+/// `candidates` for regular scanning; otherwise the archive is scanned in
+/// memory and its entries are recorded in `result`.
+pub(crate) fn queue_archive(
+    archive: &Path,
+    config: &ScanConfig,
+    result: &mut ScanResult,
+    candidates: &mut Vec<PathBuf>,
+) {
+    let scanned = match &config.extract_archives {
+        Some(dest_dir) => match extract_zip_archive(archive, dest_dir, config.recursive) {
+            Ok(extracted) => {
+                result.total_files += extracted.len();
+                candidates.extend(extracted);
+                return;
+            }
+            Err(e) => vec![failed_entry(archive, "", &e.to_string())],
+        },
+        None => scan_zip_archive(archive, config)
+            .unwrap_or_else(|e| vec![failed_entry(archive, "", &e.to_string())]),
+    };
+
+    for file in scanned {
+        result.total_files += 1;
+        match file.action {
+            ScanAction::Written | ScanAction::Updated | ScanAction::Renamed { .. } => result.successful += 1,
+            ScanAction::Skipped => result.skipped += 1,
+            ScanAction::Failed => result.failed += 1,
+            ScanAction::Conflict { .. } => result.conflicts += 1,
+        }
+        result.scanned_files.push(file);
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scan::{read_sidecar, scan_path};
+    use std::io::Write;
+    use tempfile::TempDir;
+    use zip::write::SimpleFileOptions;
+
+    fn create_zip(path: &Path, entries: &[(&str, &[u8])]) {
+        let mut writer = zip::ZipWriter::new(File::create(path).unwrap());
+        for (name, data) in entries {
+            if name.ends_with('/') {
+                writer.add_directory(*name, SimpleFileOptions::default()).unwrap();
+            } else {
+                writer.start_file(*name, SimpleFileOptions::default()).unwrap();
+                writer.write_all(data).unwrap();
+            }
+        }
+        writer.finish().unwrap();
+    }
+
+    #[test]
+    fn test_scan_zip_archive_in_memory() {
+        let temp_dir = TempDir::new().unwrap();
+        let archive = temp_dir.path().join("old.zip");
+        create_zip(
+            &archive,
+            &[("photo.jpg", b"image one"), ("notes.txt", b"text"), ("2009/", b""), ("2009/beach.jpg", b"image two")],
+        );
+
+        let config = ScanConfig { scan_archives: true, ..ScanConfig::default() };
+        let result = scan_path(temp_dir.path(), &config, None).unwrap();
+        assert_eq!(result.successful, 1, "nested entry needs recursive");
+
+        let sidecar = read_sidecar(&temp_dir.path().join("old.zip__photo.jpg")).unwrap();
+        assert_eq!(sidecar.source.file_path, archive.join("photo.jpg").display().to_string());
+        assert_eq!(sidecar.source.file_size_bytes, 9);
+
+        let recursive = ScanConfig { recursive: true, ..config };
+        let result = scan_path(temp_dir.path(), &recursive, None).unwrap();
+        assert_eq!(result.successful, 2);
+        assert!(temp_dir.path().join("old.zip__2009__beach.jpg.json").exists());
+        assert!(!temp_dir.path().join("photo.jpg").exists(), "nothing extracted");
+    }
+
+    #[test]
+    fn test_scan_zip_archive_extracted() {
+        let temp_dir = TempDir::new().unwrap();
+        let library = temp_dir.path().join("library");
+        let extract_dir = temp_dir.path().join("extracted");
+        fs::create_dir(&library).unwrap();
+        create_zip(&library.join("old.zip"), &[("photo.jpg", b"image one"), ("../evil.jpg", b"x")]);
+
+        let config = ScanConfig {
+            recursive: true,
+            scan_archives: true,
+            extract_archives: Some(extract_dir.clone()),
+            ..ScanConfig::default()
+        };
+        let result = scan_path(&library, &config, None).unwrap();
+
+        assert_eq!(result.successful, 1);
+        assert!(extract_dir.join("old.zip/photo.jpg.json").exists());
+        assert!(!temp_dir.path().join("evil.jpg").exists());
+    }
+}
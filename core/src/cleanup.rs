@@ -1,8 +1,9 @@
 //! Cleanup module - Remove Jožin-generated files
 //!
 //! This module provides safe removal of files generated by Jožin:
-//! - JSON sidecar files (`*.json`)
-//! - Backup files (`*.json.bak1`, `*.json.bak2`, `*.json.bak3`)
+//! - JSON sidecar files (`*.json`, or the library's configured sidecar naming),
+//!   also gzipped (`*.json.gz`)
+//! - Backup files (`*.json.bak1`, `*.json.bak2`, `*.json.bak3`, and `*.json.gz.bakN`)
 //! - Thumbnail files (`*_<size>.jpg`, `*_<size>.webp`)
 //! - Cache directories (`.jozin/`)
 //!
@@ -14,6 +15,15 @@
 //! - **Detailed reporting**: Every deletion is logged with file type and size
 //! - **Error resilience**: Failed deletions are reported but don't stop the operation
 //!
+//! ## Thumbnail References
+//!
+//! Sidecars list their thumbnails in `thumbnails`. Deleting thumbnails but
+//! not the sidecars leaves those entries pointing at missing files, so
+//! sidecars under the cleaned path that reference a thumbnail about to be
+//! deleted are reported in [`CleanupResult::would_orphan_thumbnail_refs`].
+//! With [`CleanupOptions::update_sidecars`], the stale entries are removed
+//! from them after the deletion.
+//!
 //! ## Usage
 //!
 //! ```no_run
@@ -37,10 +47,16 @@
 //! # Ok::<(), jozin_core::JozinError>(())
 //! ```
 
+use crate::scan::{find_sidecar_files_with, parse_sidecar};
+use crate::util::compression::{read_sidecar_file, uncompressed_path};
+use crate::util::deferred_write::DeferredSidecarWriter;
+use crate::util::library_config::LibraryConfig;
+use crate::util::symlink::SymlinkStrategy;
 use crate::{JozinError, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 
 // ============================================================================
@@ -65,6 +81,9 @@ pub struct CleanupOptions {
     pub backups: bool,
     /// Delete cache directories (.jozin/)
     pub cache: bool,
+    /// Remove entries of deleted thumbnails from the `thumbnails` of
+    /// sidecars that are kept
+    pub update_sidecars: bool,
 }
 
 impl CleanupOptions {
@@ -75,6 +94,7 @@ impl CleanupOptions {
             thumbnails: true,
             backups: true,
             cache: true,
+            update_sidecars: false,
         }
     }
 
@@ -85,6 +105,7 @@ impl CleanupOptions {
             thumbnails: false,
             backups: false,
             cache: false,
+            update_sidecars: false,
         }
     }
 
@@ -95,6 +116,7 @@ impl CleanupOptions {
             thumbnails: true,
             backups: false,
             cache: false,
+            update_sidecars: false,
         }
     }
 
@@ -105,6 +127,7 @@ impl CleanupOptions {
             thumbnails: false,
             backups: true,
             cache: false,
+            update_sidecars: false,
         }
     }
 
@@ -115,6 +138,7 @@ impl CleanupOptions {
             thumbnails: false,
             backups: false,
             cache: true,
+            update_sidecars: false,
         }
     }
 }
@@ -131,8 +155,40 @@ pub struct CleanupResult {
     pub total_files: usize,
     /// Total size of deleted files in bytes
     pub total_bytes: u64,
+    /// Deleted bytes per file type (sums to `total_bytes`)
+    pub bytes_by_type: HashMap<FileType, u64>,
     /// Number of files that failed to delete
     pub failed: usize,
+    /// Kept sidecars whose `thumbnails` reference deleted (or, in a dry run,
+    /// to be deleted) thumbnails; advisory unless
+    /// [`CleanupOptions::update_sidecars`] is set
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub would_orphan_thumbnail_refs: Vec<String>,
+    /// Sidecars the stale thumbnail entries were removed from
+    /// ([`CleanupOptions::update_sidecars`])
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub updated_sidecars: Vec<String>,
+}
+
+impl CleanupResult {
+    fn new() -> Self {
+        CleanupResult {
+            deleted_files: Vec::new(),
+            total_files: 0,
+            total_bytes: 0,
+            bytes_by_type: HashMap::new(),
+            failed: 0,
+            would_orphan_thumbnail_refs: Vec::new(),
+            updated_sidecars: Vec::new(),
+        }
+    }
+
+    /// Counts one file towards the totals and the per-type breakdown.
+    fn count_file(&mut self, file_type: FileType, size: u64) {
+        self.total_files += 1;
+        self.total_bytes += size;
+        *self.bytes_by_type.entry(file_type).or_insert(0) += size;
+    }
 }
 
 /// Information about a single deleted file.
@@ -150,7 +206,7 @@ pub struct DeletedFile {
 }
 
 /// Type of Jožin-generated file.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum FileType {
     /// JSON sidecar file (*.json)
@@ -186,6 +242,7 @@ pub enum FileType {
 /// - List of deleted (or would-be deleted) files
 /// - Total count and size
 /// - Number of failed deletions
+/// - Sidecars referencing deleted thumbnails (see the module docs)
 ///
 /// # Errors
 ///
@@ -235,20 +292,31 @@ pub fn cleanup_path(
         });
     }
 
-    // Handle single file
-    if path.is_file() {
-        return cleanup_single_file(path, options, dry_run);
-    }
+    // Sidecar naming of the library (see util::library_config)
+    let naming = LibraryConfig::discover(path);
 
-    // Handle directory
-    if path.is_dir() {
-        return cleanup_directory(path, recursive, options, dry_run, progress_callback);
-    }
+    // Sidecars that will reference deleted thumbnails, found before deleting
+    let thumbnail_refs = if options.thumbnails && !options.sidecars {
+        find_thumbnail_refs(path, recursive, &naming)?
+    } else {
+        Vec::new()
+    };
 
-    // Path exists but is neither file nor directory
-    Err(JozinError::ValidationError {
-        message: format!("Path is neither a file nor a directory: {}", path.display()),
-    })
+    let mut result = if path.is_file() {
+        // Handle single file
+        cleanup_single_file(path, options, dry_run, &naming)?
+    } else if path.is_dir() {
+        // Handle directory
+        cleanup_directory(path, recursive, options, dry_run, &naming, progress_callback)?
+    } else {
+        // Path exists but is neither file nor directory
+        return Err(JozinError::ValidationError {
+            message: format!("Path is neither a file nor a directory: {}", path.display()),
+        });
+    };
+
+    update_thumbnail_refs(&mut result, thumbnail_refs, options, dry_run, &naming);
+    Ok(result)
 }
 
 // ============================================================================
@@ -260,20 +328,15 @@ fn cleanup_single_file(
     file_path: &Path,
     options: CleanupOptions,
     dry_run: bool,
+    naming: &LibraryConfig,
 ) -> Result<CleanupResult> {
-    let mut result = CleanupResult {
-        deleted_files: Vec::new(),
-        total_files: 0,
-        total_bytes: 0,
-        failed: 0,
-    };
+    let mut result = CleanupResult::new();
 
-    if let Some(file_type) = classify_file(file_path) {
+    if let Some(file_type) = classify_file(file_path, naming) {
         if should_delete(file_type, options) {
             let size = fs::metadata(file_path).ok().map(|m| m.len()).unwrap_or(0);
 
-            result.total_files = 1;
-            result.total_bytes = size;
+            result.count_file(file_type, size);
 
             if !dry_run {
                 match fs::remove_file(file_path) {
@@ -315,14 +378,10 @@ fn cleanup_directory(
     recursive: bool,
     options: CleanupOptions,
     dry_run: bool,
+    naming: &LibraryConfig,
     progress_callback: Option<&dyn Fn(crate::ProgressEvent)>,
 ) -> Result<CleanupResult> {
-    let mut result = CleanupResult {
-        deleted_files: Vec::new(),
-        total_files: 0,
-        total_bytes: 0,
-        failed: 0,
-    };
+    let mut result = CleanupResult::new();
 
     // Configure directory walker
     let walker = if recursive {
@@ -332,7 +391,8 @@ fn cleanup_directory(
     };
 
     // Iterate through directory entries
-    for entry in walker {
+    let mut entries = walker.into_iter();
+    while let Some(entry) = entries.next() {
         // Handle walkdir errors (permission denied, etc.)
         let entry = match entry {
             Ok(e) => e,
@@ -350,6 +410,8 @@ fn cleanup_directory(
             if let Err(e) = delete_cache_directory(path, dry_run, &mut result) {
                 eprintln!("Warning: Failed to delete cache directory {}: {}", path.display(), e);
             }
+            // Its files are already counted (and still present in dry-run mode)
+            entries.skip_current_dir();
             continue;
         }
 
@@ -359,12 +421,11 @@ fn cleanup_directory(
         }
 
         // Classify and potentially delete file
-        if let Some(file_type) = classify_file(path) {
+        if let Some(file_type) = classify_file(path, naming) {
             if should_delete(file_type, options) {
                 let size = fs::metadata(path).ok().map(|m| m.len()).unwrap_or(0);
 
-                result.total_files += 1;
-                result.total_bytes += size;
+                result.count_file(file_type, size);
 
                 // Emit FileStarted event
                 if let Some(callback) = progress_callback {
@@ -438,11 +499,115 @@ fn cleanup_directory(
     Ok(result)
 }
 
+/// Finds sidecars whose `thumbnails` reference a thumbnail file that a
+/// thumbnail cleanup of `path` deletes.
+///
+/// Sidecars are looked for where the thumbnails are: under `path`, or next
+/// to it if it is a file. Unreadable sidecars are skipped.
+///
+/// Returns each sidecar path with the locations of its referenced thumbnails.
+fn find_thumbnail_refs(path: &Path, recursive: bool, naming: &LibraryConfig) -> Result<Vec<(PathBuf, HashSet<PathBuf>)>> {
+    let root = path.canonicalize()?;
+    let (search_root, search_recursive) = if path.is_file() {
+        (root.parent().unwrap_or(&root).to_path_buf(), false)
+    } else {
+        (root.clone(), recursive)
+    };
+    // Same scope as the deletion walk
+    let is_deleted = |location: &Path| {
+        let in_scope = if path.is_file() {
+            location == root
+        } else if recursive {
+            location.starts_with(&root)
+        } else {
+            location.parent() == Some(root.as_path())
+        };
+        in_scope && location.is_file() && is_thumbnail_file(location)
+    };
+
+    let mut refs = Vec::new();
+    for sidecar_path in find_sidecar_files_with(&search_root, search_recursive, naming, SymlinkStrategy::default())? {
+        let Ok(sidecar) = read_sidecar_file(&sidecar_path).and_then(|json| parse_sidecar(&json)) else {
+            continue;
+        };
+        let deleted: HashSet<PathBuf> = sidecar
+            .thumbnails
+            .iter()
+            .filter_map(|thumb| thumbnail_location(&sidecar_path, &thumb.path))
+            .filter(|location| is_deleted(location))
+            .collect();
+        if !deleted.is_empty() {
+            refs.push((sidecar_path, deleted));
+        }
+    }
+    Ok(refs)
+}
+
+/// Reports the sidecars found by [`find_thumbnail_refs()`] and, with
+/// [`CleanupOptions::update_sidecars`], removes the entries of thumbnails that
+/// were deleted from them.
+fn update_thumbnail_refs(
+    result: &mut CleanupResult,
+    thumbnail_refs: Vec<(PathBuf, HashSet<PathBuf>)>,
+    options: CleanupOptions,
+    dry_run: bool,
+    naming: &LibraryConfig,
+) {
+    for (sidecar_path, thumbnails) in thumbnail_refs {
+        let display = sidecar_path.display().to_string();
+        // Thumbnails that failed to delete are still there
+        let deleted: HashSet<&PathBuf> = thumbnails
+            .iter()
+            .filter(|location| dry_run || !location.exists())
+            .collect();
+        if deleted.is_empty() {
+            continue;
+        }
+        if dry_run || !options.update_sidecars {
+            result.would_orphan_thumbnail_refs.push(display);
+            continue;
+        }
+
+        let Some(image_path) = naming.image_path(&sidecar_path) else {
+            result.would_orphan_thumbnail_refs.push(display);
+            continue;
+        };
+        let updated = DeferredSidecarWriter::transaction(&image_path, |sidecar| {
+            sidecar.thumbnails.retain(|thumb| {
+                thumbnail_location(&sidecar_path, &thumb.path).is_none_or(|location| !deleted.contains(&location))
+            });
+            Ok(())
+        });
+        match updated {
+            Ok(()) => result.updated_sidecars.push(display),
+            Err(e) => {
+                eprintln!("Warning: Failed to update thumbnails of {}: {}", display, e);
+                result.would_orphan_thumbnail_refs.push(display);
+            }
+        }
+    }
+}
+
+/// Canonical location of a thumbnail referenced by a sidecar: relative paths
+/// are resolved against the sidecar's directory. Only the directory has to
+/// exist, so deleted thumbnails keep their location.
+fn thumbnail_location(sidecar_path: &Path, thumbnail: &str) -> Option<PathBuf> {
+    let path = sidecar_path.parent().unwrap_or(Path::new("")).join(thumbnail);
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    Some(dir.canonicalize().ok()?.join(path.file_name()?))
+}
+
 /// Classifies a file as a Jožin-generated type or None if it's not a Jožin file.
-fn classify_file(path: &Path) -> Option<FileType> {
-    if is_sidecar_file(path) {
+///
+/// Sidecars and backups in the library's configured naming are recognised in
+/// addition to the default `<image>.json` pattern.
+fn classify_file(path: &Path, naming: &LibraryConfig) -> Option<FileType> {
+    if is_sidecar_file(path) || naming.is_sidecar(path) {
         Some(FileType::Sidecar)
-    } else if is_backup_file(path) {
+    } else if is_backup_file(path) || is_named_backup_file(path, naming) {
         Some(FileType::Backup)
     } else if is_thumbnail_file(path) {
         Some(FileType::Thumbnail)
@@ -465,10 +630,12 @@ fn should_delete(file_type: FileType, options: CleanupOptions) -> bool {
 
 /// Checks if a file is a JSON sidecar file.
 ///
-/// Pattern: `IMG_1234.JPG.json`
+/// Pattern: `IMG_1234.JPG.json` (or gzipped, `IMG_1234.JPG.json.gz`)
 /// Must end with `.json` and the stem must contain a dot (indicating an image file extension).
 /// This avoids deleting standalone JSON files like `config.json` or `document.json`.
 fn is_sidecar_file(path: &Path) -> bool {
+    let path = &uncompressed_path(path);
+
     // Must end with .json
     if path.extension().and_then(|e| e.to_str()) != Some("json") {
         return false;
@@ -498,15 +665,35 @@ fn is_sidecar_file(path: &Path) -> bool {
 /// Checks if a file is a backup file.
 ///
 /// Pattern: `IMG_1234.JPG.json.bak1`, `IMG_1234.JPG.json.bak2`, `IMG_1234.JPG.json.bak3`
+/// (`IMG_1234.JPG.json.gz.bakN` for gzipped sidecars)
 fn is_backup_file(path: &Path) -> bool {
     let file_name = match path.file_name().and_then(|n| n.to_str()) {
         Some(name) => name,
         None => return false,
     };
 
-    file_name.ends_with(".json.bak1")
-        || file_name.ends_with(".json.bak2")
-        || file_name.ends_with(".json.bak3")
+    ["bak1", "bak2", "bak3"].iter().any(|bak| {
+        file_name
+            .strip_suffix(bak)
+            .and_then(|name| name.strip_suffix('.'))
+            .is_some_and(|sidecar_name| sidecar_name.ends_with(".json") || sidecar_name.ends_with(".json.gz"))
+    })
+}
+
+/// Checks if a file is a backup of a sidecar in the configured naming
+/// (e.g. `IMG_1234.JPG.jozin.json.bak1`).
+fn is_named_backup_file(path: &Path, naming: &LibraryConfig) -> bool {
+    let file_name = match path.file_name().and_then(|n| n.to_str()) {
+        Some(name) => name,
+        None => return false,
+    };
+
+    ["bak1", "bak2", "bak3"].iter().any(|bak| {
+        file_name
+            .strip_suffix(bak)
+            .and_then(|name| name.strip_suffix('.'))
+            .is_some_and(|sidecar_name| naming.is_sidecar(Path::new(sidecar_name)))
+    })
 }
 
 /// Checks if a file is a thumbnail file.
@@ -557,7 +744,7 @@ fn is_thumbnail_file(path: &Path) -> bool {
 
         // Parse and check if it's in reasonable thumbnail size range (32-4096)
         if let Ok(size) = after_underscore.parse::<u32>() {
-            size >= 32 && size <= 4096
+            (32..=4096).contains(&size)
         } else {
             false
         }
@@ -598,8 +785,7 @@ fn delete_cache_directory(
         let entry = entry?;
         if entry.path().is_file() {
             let size = fs::metadata(entry.path()).ok().map(|m| m.len()).unwrap_or(0);
-            result.total_files += 1;
-            result.total_bytes += size;
+            result.count_file(FileType::Cache, size);
 
             result.deleted_files.push(DeletedFile {
                 path: entry.path().display().to_string(),
@@ -643,6 +829,8 @@ mod tests {
         assert!(is_sidecar_file(Path::new("IMG_1234.JPG.json")));
         assert!(is_sidecar_file(Path::new("/path/to/photo.png.json")));
         assert!(!is_sidecar_file(Path::new("IMG_1234.JPG.json.bak1")));
+        assert!(is_sidecar_file(Path::new("IMG_1234.JPG.json.gz")));
+        assert!(!is_sidecar_file(Path::new("IMG_1234.JPG.json.gz.bak1")));
         assert!(!is_sidecar_file(Path::new("IMG_1234.JPG")));
         assert!(!is_sidecar_file(Path::new("document.json")));  // This is a sidecar too
     }
@@ -652,6 +840,7 @@ mod tests {
         assert!(is_backup_file(Path::new("IMG_1234.JPG.json.bak1")));
         assert!(is_backup_file(Path::new("IMG_1234.JPG.json.bak2")));
         assert!(is_backup_file(Path::new("IMG_1234.JPG.json.bak3")));
+        assert!(is_backup_file(Path::new("IMG_1234.JPG.json.gz.bak2")));
         assert!(!is_backup_file(Path::new("IMG_1234.JPG.json")));
         assert!(!is_backup_file(Path::new("IMG_1234.JPG.json.bak")));
         assert!(!is_backup_file(Path::new("IMG_1234.JPG")));
@@ -804,4 +993,73 @@ mod tests {
         assert!(result.is_err());
         assert!(matches!(result.unwrap_err(), JozinError::IoError { .. }));
     }
+
+    #[test]
+    fn test_cleanup_bytes_by_type_sums_to_total() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+
+        create_test_file(root, "image.jpg", b"original image");
+        create_test_file(root, "image.jpg.json", b"sidecar");
+        create_test_file(root, "image.jpg.json.bak1", b"backup1");
+        create_test_file(root, "image.jpg.json.bak2", b"backup22");
+        create_test_file(root, "image_256.jpg", b"thumbnail");
+        create_test_file(root, ".jozin/cache.db", b"cache");
+
+        let result = cleanup_path(root, true, CleanupOptions::all(), true, None).unwrap();
+
+        assert_eq!(result.bytes_by_type[&FileType::Sidecar], 7);
+        assert_eq!(result.bytes_by_type[&FileType::Backup], 15);
+        assert_eq!(result.bytes_by_type[&FileType::Thumbnail], 9);
+        assert_eq!(result.bytes_by_type[&FileType::Cache], 5);
+        assert_eq!(result.bytes_by_type.values().sum::<u64>(), result.total_bytes);
+
+        let json = serde_json::to_value(&result).unwrap();
+        assert_eq!(json["bytes_by_type"]["backup"], 15);
+    }
+
+    #[test]
+    fn test_cleanup_thumbnails_reports_and_updates_sidecar_refs() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let photo = create_test_file(root, "IMG_0001.jpg", b"image");
+        let mut sidecar = crate::scan::scan_file(&photo, true).unwrap().sidecar;
+        let thumb = |path: String, size| crate::ThumbnailInfo {
+            path,
+            size,
+            format: "jpg".to_string(),
+            source_timestamp_sec: None,
+            dominant_color: None,
+            from_exif: false,
+            tonemap: None,
+        };
+        let small = create_test_file(root, "IMG_0001_128.jpg", b"thumb");
+        create_test_file(root, "IMG_0001_256.jpg", b"thumb");
+        let nested = create_test_file(root, "thumbs/IMG_0001_512.jpg", b"thumb");
+        sidecar.thumbnails = vec![
+            thumb(small.display().to_string(), 128),
+            thumb("IMG_0001_256.jpg".to_string(), 256),
+            thumb(nested.display().to_string(), 512),
+        ];
+        crate::scan::write_sidecar(&photo, &sidecar).unwrap();
+
+        // Advisory only: the sidecar is reported but left alone
+        let result = cleanup_path(root, false, CleanupOptions::thumbnails_only(), true, None).unwrap();
+        assert_eq!(result.would_orphan_thumbnail_refs.len(), 1);
+        assert!(result.would_orphan_thumbnail_refs[0].ends_with("IMG_0001.jpg.json"));
+        assert!(result.updated_sidecars.is_empty());
+
+        // Not recursive, so only the thumbnails next to the photo go
+        let options = CleanupOptions { update_sidecars: true, ..CleanupOptions::thumbnails_only() };
+        let result = cleanup_path(root, false, options, false, None).unwrap();
+        assert_eq!(result.total_files, 2);
+        assert!(result.would_orphan_thumbnail_refs.is_empty());
+        assert_eq!(result.updated_sidecars.len(), 1);
+        let kept = crate::scan::read_sidecar(&photo).unwrap().thumbnails;
+        assert_eq!(kept.iter().map(|t| t.size).collect::<Vec<_>>(), vec![512]);
+
+        // Sidecars deleted along with the thumbnails are not reported
+        let result = cleanup_path(root, true, CleanupOptions::all(), true, None).unwrap();
+        assert!(result.would_orphan_thumbnail_refs.is_empty());
+    }
 }
@@ -0,0 +1,742 @@
+//! EXIF module - Reading EXIF metadata from image files
+//!
+//! This module wraps the `kamadak-exif` crate and converts its output into the
+//! shapes stored in sidecars.
+//!
+//! ## Raw EXIF Appendix
+//!
+//! [`read_raw_exif()`] flattens every EXIF tag from every IFD into a single JSON
+//! object, keyed as `<IFD>.<TagName>`:
+//! - `IFD0.Make` - primary image TIFF tags
+//! - `IFD1.Compression` - thumbnail image TIFF tags
+//! - `ExifIFD.FocalLength` - Exif sub-IFD tags
+//! - `GPSIFD.GPSLatitude` - GPS sub-IFD tags
+//! - `InteropIFD.InteroperabilityIndex` - Interoperability sub-IFD tags
+//!
+//! The appendix is capped at [`RAW_EXIF_MAX_BYTES`]; larger blocks are
+//! truncated and marked with `"_truncated": true`.
+//!
+//! ## Capture Time
+//!
+//! [`read_capture_time()`] reads `DateTimeOriginal` and converts it to RFC3339
+//! using `OffsetTimeOriginal`, or an assumed offset for cameras that do not
+//! record one (see [`crate::util::timezone`]).
+//!
+//! ## GPS
+//!
+//! [`read_gps()`] converts `GPSLatitude`/`GPSLongitude` degree-minute-second
+//! rationals to signed decimal degrees. Cameras differ in the details:
+//!
+//! - Missing `GPSLatitudeRef`/`GPSLongitudeRef` is read as North/East
+//! - Seconds may be omitted or `0/1`
+//! - A zero denominator makes the coordinate invalid (ignored)
+//! - `GPSAltitudeRef = 1` means below sea level (negative altitude)
+//! - `(0, 0)` is a placeholder written by cameras without a GPS fix and is
+//!   not stored in sidecars
+//!
+//! Out-of-range coordinates are stored as read; `verify` reports them.
+//!
+//! ## HDR
+//!
+//! [`detect_hdr()`] looks for the markers HDR captures carry next to their
+//! EXIF: Apple's `apple_hdr_headroom`, `HDR_META`/`GainMap` boxes (and the
+//! `hdrgainmap` auxiliary image) in HEIC, Adobe/Google `hdrgm:` gain map XMP
+//! and Android's `android.hdrDynamicMetadata` in the MP (multi-picture) data.
+//! These live in maker notes, XMP and container boxes rather than named EXIF
+//! tags, so the first [`HDR_SCAN_BYTES`] of the file are searched as bytes.
+//!
+//! ## Usage
+//!
+//! ```no_run
+//! use jozin_core::exif::read_raw_exif;
+//! use std::path::Path;
+//!
+//! if let Some(raw) = read_raw_exif(Path::new("/photos/IMG_1234.JPG"))? {
+//!     println!("Focal length: {}", raw["ExifIFD.FocalLength"]);
+//! }
+//! # Ok::<(), jozin_core::JozinError>(())
+//! ```
+
+use crate::util::camera::normalise_image_camera;
+use crate::util::io_budget::{IoBudget, RateLimitedReader};
+use crate::util::timezone::resolve_capture_time;
+use crate::{ImageInfo, Result};
+use exif::{Context, Exif, Field, In, Rational, Tag, Value};
+use serde_json::{Map, Value as JsonValue};
+use std::fs::File;
+use std::io::{BufRead, BufReader, Cursor, Read, Seek};
+use std::path::Path;
+use time::UtcOffset;
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+/// Maximum serialized size of the raw EXIF appendix (50 KiB).
+pub const RAW_EXIF_MAX_BYTES: usize = 50 * 1024;
+
+/// Maximum number of bytes of an `Undefined` value kept as hex (e.g., maker notes).
+const UNDEFINED_MAX_BYTES: usize = 256;
+
+/// Bytes at the start of a file searched for HDR markers (256 KiB).
+pub const HDR_SCAN_BYTES: u64 = 256 * 1024;
+
+/// Byte strings that mark an HDR capture (see [`detect_hdr()`]).
+const HDR_MARKERS: &[&[u8]] = &[
+    b"apple_hdr_headroom",
+    b"HDR_META",
+    b"GainMap",
+    b"hdrgainmap",
+    b"hdrgm:Version",
+    b"android.hdrDynamicMetadata",
+];
+
+// ============================================================================
+// Public Types
+// ============================================================================
+
+/// A GPS position read from EXIF.
+///
+/// # Fields
+///
+/// - `latitude`: Decimal degrees, negative for South
+/// - `longitude`: Decimal degrees, negative for West
+/// - `altitude_m`: Meters above sea level, negative below
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GpsPosition {
+    pub latitude: f64,
+    pub longitude: f64,
+    pub altitude_m: Option<f64>,
+}
+
+impl GpsPosition {
+    /// Whether this is the `(0, 0)` placeholder of a camera without a GPS fix.
+    pub fn is_placeholder(&self) -> bool {
+        self.latitude == 0.0 && self.longitude == 0.0
+    }
+
+    /// Whether latitude is within ±90° and longitude within ±180°.
+    pub fn is_in_range(&self) -> bool {
+        is_valid_coordinate(self.latitude, self.longitude)
+    }
+}
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Reads all EXIF tags from a file as a flat JSON object.
+///
+/// # Arguments
+///
+/// * `file_path` - Image file to read
+///
+/// # Returns
+///
+/// - `Ok(Some(object))` with `<IFD>.<TagName>` keys
+/// - `Ok(None)` if the file contains no (parsable) EXIF data
+///
+/// # Errors
+///
+/// Returns `JozinError::IoError` if the file cannot be opened.
+pub fn read_raw_exif(file_path: &Path) -> Result<Option<JsonValue>> {
+    let file = File::open(file_path)?;
+    let mut reader = BufReader::new(file);
+    Ok(raw_exif_from_reader(&mut reader, &file_path.display().to_string()))
+}
+
+/// Reads all EXIF tags from an in-memory image as a flat JSON object.
+///
+/// Same as [`read_raw_exif()`] for data that is not a file on disk (e.g., an
+/// entry of a zip archive).
+///
+/// # Arguments
+///
+/// * `data` - Image file contents
+/// * `label` - Name of the image, used in the truncation warning
+///
+/// # Returns
+///
+/// `None` if the data contains no (parsable) EXIF data.
+pub fn read_raw_exif_from_bytes(data: &[u8], label: &str) -> Option<JsonValue> {
+    raw_exif_from_reader(&mut Cursor::new(data), label)
+}
+
+/// Reads the named EXIF fields Jožin stores in `ImageInfo`.
+///
+/// Fills `datetime_original` (see [`read_capture_time()`]), the GPS
+/// fields (see [`read_gps()`]), `camera_make`, `camera_model` (normalised,
+/// with the EXIF values in the `_raw` fields if they differ; see
+/// [`crate::util::camera`]), `width` and `height`
+/// (`PixelXDimension`/`PixelYDimension`), `color_space`, `lens_model` and
+/// `hdr` (see [`detect_hdr()`]); GPS placeholders at `(0, 0)` are left out.
+///
+/// # Arguments
+///
+/// * `file_path` - Image file to read
+/// * `assumed` - Camera UTC offset, used when `OffsetTimeOriginal` is absent
+/// * `io_budget` - Optional read throughput limit (see [`IoBudget`])
+///
+/// # Returns
+///
+/// `Ok(None)` if the file has no EXIF or none of the named fields.
+///
+/// # Errors
+///
+/// Returns `JozinError::IoError` if the file cannot be opened.
+pub fn read_image_info(
+    file_path: &Path,
+    assumed: UtcOffset,
+    io_budget: Option<IoBudget>,
+) -> Result<Option<ImageInfo>> {
+    let Some(exif) = read_exif(file_path, io_budget)? else {
+        return Ok(None);
+    };
+
+    let datetime_original = ascii_field(&exif, Tag::DateTimeOriginal).and_then(|datetime| {
+        let offset = ascii_field(&exif, Tag::OffsetTimeOriginal);
+        resolve_capture_time(&datetime, offset.as_deref(), assumed).ok()
+    });
+    let gps = gps_from_exif(&exif).filter(|gps| !gps.is_placeholder());
+    let color_space = color_space_from_exif(&exif);
+    let trimmed = |tag| {
+        ascii_field(&exif, tag)
+            .map(|value| value.trim_matches(|c: char| c.is_whitespace() || c == '\0').to_string())
+            .filter(|value| !value.is_empty())
+    };
+    let lens_model = trimmed(Tag::LensModel);
+    let camera_make = trimmed(Tag::Make);
+    let camera_model = trimmed(Tag::Model);
+    let dimension = |tag| exif.get_field(tag, In::PRIMARY).and_then(|field| field.value.get_uint(0));
+    let (width, height) = (dimension(Tag::PixelXDimension), dimension(Tag::PixelYDimension));
+    let hdr = detect_hdr(file_path)?;
+    if datetime_original.is_none()
+        && gps.is_none()
+        && color_space.is_none()
+        && lens_model.is_none()
+        && camera_make.is_none()
+        && camera_model.is_none()
+        && width.is_none()
+        && height.is_none()
+        && hdr.is_none()
+    {
+        return Ok(None);
+    }
+
+    let mut image = ImageInfo {
+        datetime_original,
+        gps_latitude: gps.map(|g| g.latitude),
+        gps_longitude: gps.map(|g| g.longitude),
+        gps_altitude_m: gps.and_then(|g| g.altitude_m),
+        camera_make,
+        camera_model,
+        width,
+        height,
+        color_space,
+        lens_model,
+        hdr,
+        ..ImageInfo::default()
+    };
+    normalise_image_camera(&mut image);
+    Ok(Some(image))
+}
+
+/// Detects whether an image is an HDR capture (gain map or HDR tone metadata).
+///
+/// Searches the first [`HDR_SCAN_BYTES`] of the file for the markers listed
+/// in the module docs. Markers are only ever added by HDR pipelines, so their
+/// absence proves nothing: the result is `Some(true)` or `None`, never
+/// `Some(false)`.
+///
+/// # Errors
+///
+/// Returns `JozinError::IoError` if the file cannot be read.
+pub fn detect_hdr(file_path: &Path) -> Result<Option<bool>> {
+    let mut head = Vec::new();
+    File::open(file_path)?.take(HDR_SCAN_BYTES).read_to_end(&mut head)?;
+    let found = HDR_MARKERS
+        .iter()
+        .any(|marker| head.windows(marker.len()).any(|window| window == *marker));
+    Ok(found.then_some(true))
+}
+
+/// Reads the capture time (`DateTimeOriginal`) of an image as RFC3339.
+///
+/// # Arguments
+///
+/// * `file_path` - Image file to read
+/// * `assumed` - Camera UTC offset, used when `OffsetTimeOriginal` is absent
+///
+/// # Returns
+///
+/// `Ok(None)` if the file has no EXIF, no `DateTimeOriginal`, or an invalid one.
+///
+/// # Errors
+///
+/// Returns `JozinError::IoError` if the file cannot be opened.
+pub fn read_capture_time(file_path: &Path, assumed: UtcOffset) -> Result<Option<String>> {
+    Ok(read_image_info(file_path, assumed, None)?.and_then(|info| info.datetime_original))
+}
+
+/// Reads the GPS position of an image.
+///
+/// The position is returned as recorded, including out-of-range values
+/// (reported by `verify`) and `(0, 0)` placeholders (see
+/// [`GpsPosition::is_placeholder()`]).
+///
+/// # Returns
+///
+/// `Ok(None)` if the file has no EXIF or no valid `GPSLatitude`/`GPSLongitude`.
+///
+/// # Errors
+///
+/// Returns `JozinError::IoError` if the file cannot be opened.
+pub fn read_gps(file_path: &Path) -> Result<Option<GpsPosition>> {
+    Ok(read_exif(file_path, None)?.and_then(|exif| gps_from_exif(&exif)))
+}
+
+/// Whether latitude is within ±90° and longitude within ±180°.
+pub fn is_valid_coordinate(latitude: f64, longitude: f64) -> bool {
+    (-90.0..=90.0).contains(&latitude) && (-180.0..=180.0).contains(&longitude)
+}
+
+// ============================================================================
+// Internal Helpers
+// ============================================================================
+
+/// Parses the EXIF of a file; `None` if it has no (parsable) EXIF.
+fn read_exif(file_path: &Path, io_budget: Option<IoBudget>) -> Result<Option<Exif>> {
+    let file = File::open(file_path)?;
+    let parsed = match io_budget {
+        Some(budget) => exif::Reader::new().read_from_container(&mut BufReader::new(RateLimitedReader::new(file, budget))),
+        None => exif::Reader::new().read_from_container(&mut BufReader::new(file)),
+    };
+    Ok(parsed.ok())
+}
+
+/// Returns the first string of an ASCII field of the primary image.
+fn ascii_field(exif: &Exif, tag: Tag) -> Option<String> {
+    match &exif.get_field(tag, In::PRIMARY)?.value {
+        Value::Ascii(values) => values.first().map(|v| String::from_utf8_lossy(v).into_owned()),
+        _ => None,
+    }
+}
+
+/// Names the EXIF `ColorSpace` value (`1` sRGB, `2` Adobe RGB,
+/// `0xFFFF` uncalibrated).
+fn color_space_from_exif(exif: &Exif) -> Option<String> {
+    let name = match exif.get_field(Tag::ColorSpace, In::PRIMARY)?.value.get_uint(0)? {
+        1 => "sRGB",
+        2 => "Adobe RGB",
+        0xFFFF => "Uncalibrated",
+        _ => return None,
+    };
+    Some(name.to_string())
+}
+
+/// Reads latitude, longitude and altitude from the GPS IFD.
+fn gps_from_exif(exif: &Exif) -> Option<GpsPosition> {
+    let coordinate = |tag: Tag, ref_tag: Tag, negative_ref: u8| {
+        let Value::Rational(dms) = &exif.get_field(tag, In::PRIMARY)?.value else {
+            return None;
+        };
+        let degrees = dms_to_degrees(dms)?;
+        // A missing reference means North / East
+        let negative = ascii_field(exif, ref_tag)
+            .and_then(|r| r.trim().bytes().next())
+            .is_some_and(|r| r.eq_ignore_ascii_case(&negative_ref));
+        Some(if negative { -degrees } else { degrees })
+    };
+
+    let latitude = coordinate(Tag::GPSLatitude, Tag::GPSLatitudeRef, b'S')?;
+    let longitude = coordinate(Tag::GPSLongitude, Tag::GPSLongitudeRef, b'W')?;
+
+    let altitude_m = match &exif.get_field(Tag::GPSAltitude, In::PRIMARY).map(|f| &f.value) {
+        Some(Value::Rational(altitude)) => altitude.first().and_then(|r| rational_to_f64(r.num, r.denom)).map(|meters| {
+            let below_sea_level = matches!(
+                exif.get_field(Tag::GPSAltitudeRef, In::PRIMARY).map(|f| &f.value),
+                Some(Value::Byte(bytes)) if bytes.first() == Some(&1)
+            );
+            if below_sea_level { -meters } else { meters }
+        }),
+        _ => None,
+    };
+
+    Some(GpsPosition { latitude, longitude, altitude_m })
+}
+
+/// Converts `[degrees, minutes, seconds]` rationals to decimal degrees.
+///
+/// Seconds (or minutes and seconds) may be omitted. Returns `None` for an
+/// empty list or a zero denominator.
+fn dms_to_degrees(dms: &[Rational]) -> Option<f64> {
+    if dms.is_empty() || dms.len() > 3 {
+        return None;
+    }
+    let mut degrees = 0.0;
+    for (r, divisor) in dms.iter().zip([1.0, 60.0, 3600.0]) {
+        degrees += rational_to_f64(r.num, r.denom)? / divisor;
+    }
+    Some(degrees)
+}
+
+/// `num / denom`, or `None` if `denom` is zero.
+fn rational_to_f64(num: u32, denom: u32) -> Option<f64> {
+    (denom != 0).then(|| num as f64 / denom as f64)
+}
+
+/// Parses EXIF from a container and flattens it (see [`read_raw_exif()`]).
+fn raw_exif_from_reader<R: BufRead + Seek>(reader: &mut R, label: &str) -> Option<JsonValue> {
+    // Missing or malformed EXIF is common and not an error for the scan
+    let exif = exif::Reader::new().read_from_container(reader).ok()?;
+
+    let map = flatten_fields(exif.fields());
+    if map.is_empty() {
+        return None;
+    }
+
+    let (map, truncated) = truncate_to_limit(map, RAW_EXIF_MAX_BYTES);
+    if truncated {
+        eprintln!(
+            "Warning: Raw EXIF for {} exceeds {} KiB, truncated",
+            label,
+            RAW_EXIF_MAX_BYTES / 1024
+        );
+    }
+
+    Some(JsonValue::Object(map))
+}
+
+/// Flattens EXIF fields into `<IFD>.<TagName>` keys.
+fn flatten_fields<'a>(fields: impl Iterator<Item = &'a Field>) -> Map<String, JsonValue> {
+    let mut map = Map::new();
+
+    for field in fields {
+        let key = format!("{}.{}", ifd_prefix(field), field.tag);
+        if let Some(value) = value_to_json(&field.value) {
+            map.insert(key, value);
+        }
+    }
+
+    map
+}
+
+/// Returns the namespace for a field, based on its tag context and IFD.
+fn ifd_prefix(field: &Field) -> &'static str {
+    match field.tag.context() {
+        Context::Exif => "ExifIFD",
+        Context::Gps => "GPSIFD",
+        Context::Interop => "InteropIFD",
+        Context::Tiff if field.ifd_num == In::THUMBNAIL => "IFD1",
+        _ => "IFD0",
+    }
+}
+
+/// Converts an EXIF value to JSON.
+///
+/// Single-element vectors become scalars; rationals become floats; ASCII becomes
+/// strings; undefined bytes become a (length-capped) hex string.
+fn value_to_json(value: &Value) -> Option<JsonValue> {
+    fn collapse(mut values: Vec<JsonValue>) -> Option<JsonValue> {
+        match values.len() {
+            0 => None,
+            1 => values.pop(),
+            _ => Some(JsonValue::Array(values)),
+        }
+    }
+
+    fn float(v: f64) -> JsonValue {
+        serde_json::Number::from_f64(v)
+            .map(JsonValue::Number)
+            .unwrap_or(JsonValue::Null)
+    }
+
+    match value {
+        Value::Byte(v) => collapse(v.iter().map(|&x| x.into()).collect()),
+        Value::Short(v) => collapse(v.iter().map(|&x| x.into()).collect()),
+        Value::Long(v) => collapse(v.iter().map(|&x| x.into()).collect()),
+        Value::SByte(v) => collapse(v.iter().map(|&x| x.into()).collect()),
+        Value::SShort(v) => collapse(v.iter().map(|&x| x.into()).collect()),
+        Value::SLong(v) => collapse(v.iter().map(|&x| x.into()).collect()),
+        Value::Rational(v) => collapse(v.iter().map(|r| float(r.to_f64())).collect()),
+        Value::SRational(v) => collapse(v.iter().map(|r| float(r.to_f64())).collect()),
+        Value::Float(v) => collapse(v.iter().map(|&x| float(x as f64)).collect()),
+        Value::Double(v) => collapse(v.iter().map(|&x| float(x)).collect()),
+        Value::Ascii(v) => collapse(
+            v.iter()
+                .map(|s| JsonValue::String(String::from_utf8_lossy(s).trim_end().to_string()))
+                .collect(),
+        ),
+        Value::Undefined(bytes, _) => {
+            let hex: String = bytes
+                .iter()
+                .take(UNDEFINED_MAX_BYTES)
+                .map(|b| format!("{:02x}", b))
+                .collect();
+            Some(JsonValue::String(hex))
+        }
+        _ => None,
+    }
+}
+
+/// Drops entries from the end until the serialized object fits in `max_bytes`.
+///
+/// Returns the (possibly shortened) map and whether truncation happened. A
+/// truncated map carries a `"_truncated": true` marker.
+fn truncate_to_limit(
+    map: Map<String, JsonValue>,
+    max_bytes: usize,
+) -> (Map<String, JsonValue>, bool) {
+    let full_size = serde_json::to_string(&map).map(|s| s.len()).unwrap_or(0);
+    if full_size <= max_bytes {
+        return (map, false);
+    }
+
+    // Reserve room for braces and the marker entry
+    let budget = max_bytes.saturating_sub(r#"{"_truncated":true}"#.len());
+    let mut kept = Map::new();
+    let mut used = 0;
+
+    for (key, value) in map {
+        let entry_size = serde_json::to_string(&key).map(|s| s.len()).unwrap_or(0)
+            + serde_json::to_string(&value).map(|s| s.len()).unwrap_or(0)
+            + 2; // ':' and ','
+        if used + entry_size > budget {
+            break;
+        }
+        used += entry_size;
+        kept.insert(key, value);
+    }
+
+    kept.insert("_truncated".to_string(), JsonValue::Bool(true));
+    (kept, true)
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_value_to_json_scalars_and_arrays() {
+        assert_eq!(value_to_json(&Value::Short(vec![2])), Some(JsonValue::from(2)));
+        assert_eq!(
+            value_to_json(&Value::Ascii(vec![b"Canon".to_vec()])),
+            Some(JsonValue::from("Canon"))
+        );
+        assert_eq!(
+            value_to_json(&Value::Rational(vec![Rational { num: 35, denom: 1 }])),
+            Some(JsonValue::from(35.0))
+        );
+        assert_eq!(
+            value_to_json(&Value::Long(vec![1, 2])),
+            Some(serde_json::json!([1, 2]))
+        );
+        assert_eq!(value_to_json(&Value::Short(vec![])), None);
+    }
+
+    #[test]
+    fn test_truncate_to_limit() {
+        let mut map = Map::new();
+        for i in 0..100 {
+            map.insert(format!("ExifIFD.Tag{}", i), JsonValue::from("x".repeat(100)));
+        }
+
+        let (small, truncated) = truncate_to_limit(map.clone(), 1024);
+        assert!(truncated);
+        assert_eq!(small["_truncated"], JsonValue::Bool(true));
+        assert!(serde_json::to_string(&small).unwrap().len() <= 1024);
+
+        let (full, truncated) = truncate_to_limit(map, usize::MAX);
+        assert!(!truncated);
+        assert_eq!(full.len(), 100);
+    }
+
+    /// Builds a minimal JPEG whose APP1 segment carries the given EXIF fields.
+    fn jpeg_with_exif(fields: &[Field]) -> Vec<u8> {
+        let mut writer = exif::experimental::Writer::new();
+        for field in fields {
+            writer.push_field(field);
+        }
+        let mut tiff = std::io::Cursor::new(Vec::new());
+        writer.write(&mut tiff, false).unwrap();
+        let tiff = tiff.into_inner();
+
+        let mut jpeg = vec![0xFF, 0xD8, 0xFF, 0xE1];
+        jpeg.extend_from_slice(&((tiff.len() + 8) as u16).to_be_bytes());
+        jpeg.extend_from_slice(b"Exif\0\0");
+        jpeg.extend_from_slice(&tiff);
+        jpeg.extend_from_slice(&[0xFF, 0xD9]);
+        jpeg
+    }
+
+    #[test]
+    fn test_read_raw_exif_flattens_ifds() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("exif.jpg");
+        let fields = [
+            Field {
+                tag: exif::Tag::Make,
+                ifd_num: In::PRIMARY,
+                value: Value::Ascii(vec![b"Canon".to_vec()]),
+            },
+            Field {
+                tag: exif::Tag::ExposureProgram,
+                ifd_num: In::PRIMARY,
+                value: Value::Short(vec![2]),
+            },
+        ];
+        std::fs::write(&path, jpeg_with_exif(&fields)).unwrap();
+
+        let raw = read_raw_exif(&path).unwrap().unwrap();
+        assert_eq!(raw["IFD0.Make"], JsonValue::from("Canon"));
+        assert_eq!(raw["ExifIFD.ExposureProgram"], JsonValue::from(2));
+    }
+
+    #[test]
+    fn test_read_capture_time_prefers_recorded_offset() {
+        let temp_dir = TempDir::new().unwrap();
+        let datetime = Field {
+            tag: Tag::DateTimeOriginal,
+            ifd_num: In::PRIMARY,
+            value: Value::Ascii(vec![b"2025:06:15 14:30:00".to_vec()]),
+        };
+        let offset = Field {
+            tag: Tag::OffsetTimeOriginal,
+            ifd_num: In::PRIMARY,
+            value: Value::Ascii(vec![b"-08:00".to_vec()]),
+        };
+        let assumed = UtcOffset::from_hms(5, 30, 0).unwrap();
+
+        let local = temp_dir.path().join("local.jpg");
+        std::fs::write(&local, jpeg_with_exif(std::slice::from_ref(&datetime))).unwrap();
+        assert_eq!(
+            read_capture_time(&local, assumed).unwrap().as_deref(),
+            Some("2025-06-15T14:30:00+05:30")
+        );
+
+        let recorded = temp_dir.path().join("recorded.jpg");
+        std::fs::write(&recorded, jpeg_with_exif(&[datetime, offset])).unwrap();
+        assert_eq!(
+            read_capture_time(&recorded, assumed).unwrap().as_deref(),
+            Some("2025-06-15T14:30:00-08:00")
+        );
+    }
+
+    fn rationals(values: &[(u32, u32)]) -> Vec<Rational> {
+        values.iter().map(|&(num, denom)| Rational { num, denom }).collect()
+    }
+
+    fn gps_field(tag: Tag, value: Value) -> Field {
+        Field { tag, ifd_num: In::PRIMARY, value }
+    }
+
+    #[test]
+    fn test_read_gps_refs_and_altitude() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("gps.jpg");
+        let fields = [
+            gps_field(Tag::GPSLatitude, Value::Rational(rationals(&[(33, 1), (51, 1), (0, 1)]))),
+            gps_field(Tag::GPSLatitudeRef, Value::Ascii(vec![b"S".to_vec()])),
+            gps_field(Tag::GPSLongitude, Value::Rational(rationals(&[(151, 1), (12, 1), (3600, 100)]))),
+            gps_field(Tag::GPSAltitude, Value::Rational(rationals(&[(125, 10)]))),
+            gps_field(Tag::GPSAltitudeRef, Value::Byte(vec![1])),
+        ];
+        std::fs::write(&path, jpeg_with_exif(&fields)).unwrap();
+
+        let gps = read_gps(&path).unwrap().unwrap();
+        assert!((gps.latitude + 33.85).abs() < 1e-9);
+        // No GPSLongitudeRef: East
+        assert!((gps.longitude - 151.21).abs() < 1e-9);
+        assert_eq!(gps.altitude_m, Some(-12.5));
+        assert!(gps.is_in_range());
+
+        let info = read_image_info(&path, UtcOffset::UTC, Some(IoBudget::new(50_000_000))).unwrap().unwrap();
+        assert_eq!(info.gps_altitude_m, Some(-12.5));
+    }
+
+    #[test]
+    fn test_gps_placeholder_not_stored() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("nofix.jpg");
+        let fields = [
+            gps_field(Tag::GPSLatitude, Value::Rational(rationals(&[(0, 1), (0, 1), (0, 1)]))),
+            gps_field(Tag::GPSLongitude, Value::Rational(rationals(&[(0, 1), (0, 1), (0, 1)]))),
+        ];
+        std::fs::write(&path, jpeg_with_exif(&fields)).unwrap();
+
+        assert!(read_gps(&path).unwrap().unwrap().is_placeholder());
+        assert!(read_image_info(&path, UtcOffset::UTC, None).unwrap().is_none());
+        assert!(!is_valid_coordinate(90.5, 0.0));
+    }
+
+    #[test]
+    fn test_detect_hdr_markers() {
+        let temp_dir = TempDir::new().unwrap();
+        let make = Field { tag: Tag::Make, ifd_num: In::PRIMARY, value: Value::Ascii(vec![b"Apple".to_vec()]) };
+
+        let sdr = temp_dir.path().join("sdr.jpg");
+        std::fs::write(&sdr, jpeg_with_exif(std::slice::from_ref(&make))).unwrap();
+        assert_eq!(detect_hdr(&sdr).unwrap(), None);
+        assert_eq!(read_image_info(&sdr, UtcOffset::UTC, None).unwrap().unwrap().hdr, None);
+
+        // A gain map XMP packet after the EXIF block
+        let hdr = temp_dir.path().join("hdr.jpg");
+        let mut bytes = jpeg_with_exif(&[make]);
+        bytes.extend_from_slice(b"<x:xmpmeta hdrgm:Version=\"1.0\"/>");
+        std::fs::write(&hdr, bytes).unwrap();
+        assert_eq!(detect_hdr(&hdr).unwrap(), Some(true));
+        assert_eq!(read_image_info(&hdr, UtcOffset::UTC, None).unwrap().unwrap().hdr, Some(true));
+
+        let android = temp_dir.path().join("android.jpg");
+        std::fs::write(&android, b"MPF\0android.hdrDynamicMetadata").unwrap();
+        assert_eq!(detect_hdr(&android).unwrap(), Some(true));
+    }
+
+    proptest! {
+        #[test]
+        fn prop_dms_matches_formula(deg in 0u32..=180, min in 0u32..60, sec in 0u32..60) {
+            let degrees = dms_to_degrees(&rationals(&[(deg, 1), (min, 1), (sec, 1)])).unwrap();
+            let expected = deg as f64 + min as f64 / 60.0 + sec as f64 / 3600.0;
+            prop_assert!((degrees - expected).abs() < 1e-9);
+            prop_assert!(degrees >= deg as f64 && degrees < deg as f64 + 1.0);
+        }
+
+        #[test]
+        fn prop_omitted_seconds_equal_zero_seconds(deg in 0u32..=180, min in 0u32..60) {
+            prop_assert_eq!(
+                dms_to_degrees(&rationals(&[(deg, 1), (min, 1)])),
+                dms_to_degrees(&rationals(&[(deg, 1), (min, 1), (0, 1)]))
+            );
+        }
+
+        #[test]
+        fn prop_scaled_rationals_are_equal(deg in 0u32..=180, min in 0u32..60, sec in 0u32..6000, k in 1u32..1000) {
+            let plain = dms_to_degrees(&rationals(&[(deg, 1), (min, 1), (sec, 100)])).unwrap();
+            let scaled = dms_to_degrees(&rationals(&[(deg * k, k), (min * k, k), (sec * k, 100 * k)])).unwrap();
+            prop_assert!((plain - scaled).abs() < 1e-9);
+        }
+
+        #[test]
+        fn prop_zero_denominator_is_invalid(values in proptest::collection::vec((any::<u32>(), any::<u32>()), 1..=3), zero_at in 0usize..3) {
+            let mut values = values;
+            let index = zero_at % values.len();
+            values[index].1 = 0;
+            prop_assert_eq!(dms_to_degrees(&rationals(&values)), None);
+        }
+    }
+
+    #[test]
+    fn test_read_raw_exif_without_exif() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("plain.jpg");
+        std::fs::write(&path, b"not really a jpeg").unwrap();
+
+        assert!(read_raw_exif(&path).unwrap().is_none());
+    }
+}
@@ -0,0 +1,266 @@
+//! JSON-LD export (schema.org `ImageObject`)
+//!
+//! Websites embed JSON-LD structured data so search engines can show photos
+//! as rich results. [`to_json_ld()`] renders one sidecar as an
+//! [`ImageObject`](https://schema.org/ImageObject); [`export_json_ld()`]
+//! renders all sidecars of a library as a JSON array.
+//!
+//! ## Field Mapping
+//!
+//! | JSON-LD       | Sidecar                                                   |
+//! |---------------|-----------------------------------------------------------|
+//! | `name`        | File name of `source.file_path`                           |
+//! | `contentUrl`  | `base_url` + path relative to the library root            |
+//! | `uploadDate`  | `image.datetime_original`                                 |
+//! | `width`, `height` | `image.width`, `image.height`                         |
+//! | `keywords`    | Tag labels, comma-separated                               |
+//! | `author`      | `image.camera_make` (placeholder until photographer metadata exists) |
+//! | `thumbnail`   | Smallest entry of `thumbnails`                            |
+//! | `geo`         | `GeoCoordinates` from `image.gps_*`                       |
+//!
+//! Fields without a source value are omitted.
+//!
+//! ## Usage
+//!
+//! ```no_run
+//! use jozin_core::export::jsonld::export_json_ld;
+//! use std::path::Path;
+//!
+//! let gallery = export_json_ld(Path::new("/photos"), "https://example.com/photos", true)?;
+//! std::fs::write("gallery.jsonld", serde_json::to_string_pretty(&gallery)?)?;
+//! # Ok::<(), jozin_core::JozinError>(())
+//! ```
+
+use crate::scan::load_sidecars;
+use crate::{Result, Sidecar};
+use serde_json::{json, Map, Value};
+use std::path::{Component, Path};
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Renders a sidecar as a schema.org `ImageObject`.
+///
+/// Without a library root to relate to, `contentUrl` is `base_url` plus the
+/// file name; use [`export_json_ld()`] to keep subdirectories in the URLs.
+///
+/// # Errors
+///
+/// Currently infallible; returns `Result` so mapping errors can be reported
+/// as fields are added.
+///
+/// # Example
+///
+/// ```no_run
+/// use jozin_core::export::jsonld::to_json_ld;
+/// use jozin_core::scan::read_sidecar;
+/// use std::path::Path;
+///
+/// let sidecar = read_sidecar(Path::new("/photos/IMG_1234.JPG"))?;
+/// let object = to_json_ld(&sidecar, "https://example.com/photos")?;
+/// assert_eq!(object["@type"], "ImageObject");
+/// # Ok::<(), jozin_core::JozinError>(())
+/// ```
+pub fn to_json_ld(sidecar: &Sidecar, base_url: &str) -> Result<Value> {
+    Ok(image_object(sidecar, base_url, Path::new(&file_name(sidecar))))
+}
+
+/// Renders all sidecars under `root` as a JSON array of `ImageObject`s.
+///
+/// Content URLs keep the photo's path relative to `root`; photos recorded
+/// outside `root` fall back to their file name.
+///
+/// # Arguments
+///
+/// * `root` - Library directory (or a single image or sidecar)
+/// * `base_url` - URL the library root is published at
+/// * `recursive` - Include subdirectories
+///
+/// # Errors
+///
+/// Returns `JozinError::IoError` if `root` does not exist.
+pub fn export_json_ld(root: &Path, base_url: &str, recursive: bool) -> Result<Value> {
+    let root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+    let root = if root.is_file() {
+        root.parent().map(Path::to_path_buf).unwrap_or_default()
+    } else {
+        root
+    };
+
+    let objects = load_sidecars(&root, recursive)?
+        .iter()
+        .map(|sidecar| {
+            let file_path = Path::new(&sidecar.source.file_path);
+            match file_path.strip_prefix(&root) {
+                Ok(relative) => image_object(sidecar, base_url, relative),
+                Err(_) => image_object(sidecar, base_url, Path::new(&file_name(sidecar))),
+            }
+        })
+        .collect();
+    Ok(Value::Array(objects))
+}
+
+// ============================================================================
+// Internal Helpers
+// ============================================================================
+
+/// Builds the `ImageObject` for a photo published at `relative_path`.
+fn image_object(sidecar: &Sidecar, base_url: &str, relative_path: &Path) -> Value {
+    let mut object = Map::new();
+    object.insert("@context".to_string(), json!("https://schema.org"));
+    object.insert("@type".to_string(), json!("ImageObject"));
+    object.insert("name".to_string(), json!(file_name(sidecar)));
+    object.insert("contentUrl".to_string(), json!(join_url(base_url, relative_path)));
+
+    if let Some(image) = &sidecar.image {
+        if let Some(datetime) = &image.datetime_original {
+            object.insert("uploadDate".to_string(), json!(datetime));
+        }
+        if let Some(width) = image.width {
+            object.insert("width".to_string(), json!(width));
+        }
+        if let Some(height) = image.height {
+            object.insert("height".to_string(), json!(height));
+        }
+        if let Some(make) = &image.camera_make {
+            object.insert("author".to_string(), json!({ "@type": "Organization", "name": make }));
+        }
+        if let (Some(latitude), Some(longitude)) = (image.gps_latitude, image.gps_longitude) {
+            let mut geo = json!({ "@type": "GeoCoordinates", "latitude": latitude, "longitude": longitude });
+            if let Some(altitude) = image.gps_altitude_m {
+                geo["elevation"] = json!(altitude);
+            }
+            object.insert("geo".to_string(), geo);
+        }
+    }
+
+    if !sidecar.tags.is_empty() {
+        let keywords: Vec<&str> = sidecar.tags.iter().map(|tag| tag.label.as_str()).collect();
+        object.insert("keywords".to_string(), json!(keywords.join(", ")));
+    }
+
+    // Thumbnails are stored next to the original
+    if let Some(thumbnail) = sidecar.thumbnails.iter().min_by_key(|t| t.size) {
+        if let Some(name) = Path::new(&thumbnail.path).file_name() {
+            let thumbnail_path = relative_path.with_file_name(name);
+            object.insert(
+                "thumbnail".to_string(),
+                json!({ "@type": "ImageObject", "contentUrl": join_url(base_url, &thumbnail_path) }),
+            );
+        }
+    }
+
+    Value::Object(object)
+}
+
+/// File name of the sidecar's photo.
+fn file_name(sidecar: &Sidecar) -> String {
+    let path = Path::new(&sidecar.source.file_path);
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| sidecar.source.file_path.clone())
+}
+
+/// Appends a relative path to `base_url`, percent-encoding each segment.
+fn join_url(base_url: &str, relative_path: &Path) -> String {
+    let segments: Vec<String> = relative_path
+        .components()
+        .filter_map(|component| match component {
+            Component::Normal(segment) => Some(encode_segment(&segment.to_string_lossy())),
+            _ => None,
+        })
+        .collect();
+    format!("{}/{}", base_url.trim_end_matches('/'), segments.join("/"))
+}
+
+/// Percent-encodes everything but RFC 3986 unreserved characters.
+fn encode_segment(segment: &str) -> String {
+    let mut encoded = String::with_capacity(segment.len());
+    for byte in segment.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    encoded
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scan::scan_file;
+    use crate::{ImageInfo, Tag, TagSource, ThumbnailInfo};
+    use tempfile::TempDir;
+
+    fn tag(label: &str) -> Tag {
+        Tag {
+            label: label.to_string(),
+            score: None,
+            source: TagSource::User,
+        }
+    }
+
+    #[test]
+    fn test_to_json_ld_maps_fields() {
+        let temp_dir = TempDir::new().unwrap();
+        let photo = temp_dir.path().join("IMG 0001.jpg");
+        std::fs::write(&photo, b"image").unwrap();
+        let mut sidecar = scan_file(&photo, true).unwrap().sidecar;
+        sidecar.image = Some(ImageInfo {
+            width: Some(4000),
+            height: Some(3000),
+            datetime_original: Some("2024-06-01T12:00:00+02:00".to_string()),
+            camera_make: Some("Canon".to_string()),
+            gps_latitude: Some(50.08),
+            gps_longitude: Some(14.42),
+            ..ImageInfo::default()
+        });
+        sidecar.tags = vec![tag("beach"), tag("sunset")];
+        for size in [512, 256] {
+            sidecar.thumbnails.push(ThumbnailInfo {
+                path: temp_dir.path().join(format!("IMG 0001_{}.jpg", size)).display().to_string(),
+                size,
+                format: "jpg".to_string(),
+                source_timestamp_sec: None,
+                dominant_color: None,
+                from_exif: false,
+                tonemap: None,
+            });
+        }
+
+        let object = to_json_ld(&sidecar, "https://example.com/photos/").unwrap();
+        assert_eq!(object["@context"], "https://schema.org");
+        assert_eq!(object["name"], "IMG 0001.jpg");
+        assert_eq!(object["contentUrl"], "https://example.com/photos/IMG%200001.jpg");
+        assert_eq!(object["uploadDate"], "2024-06-01T12:00:00+02:00");
+        assert_eq!(object["width"], 4000);
+        assert_eq!(object["keywords"], "beach, sunset");
+        assert_eq!(object["author"]["name"], "Canon");
+        assert_eq!(object["geo"]["@type"], "GeoCoordinates");
+        assert_eq!(object["geo"]["latitude"], 50.08);
+        assert_eq!(object["thumbnail"]["contentUrl"], "https://example.com/photos/IMG%200001_256.jpg");
+    }
+
+    #[test]
+    fn test_export_json_ld_keeps_subdirectories() {
+        let temp_dir = TempDir::new().unwrap();
+        let album = temp_dir.path().join("2024");
+        std::fs::create_dir(&album).unwrap();
+        let photo = album.join("a.jpg");
+        std::fs::write(&photo, b"image").unwrap();
+        scan_file(&photo, false).unwrap();
+
+        let gallery = export_json_ld(temp_dir.path(), "https://example.com", true).unwrap();
+        let objects = gallery.as_array().unwrap();
+        assert_eq!(objects.len(), 1);
+        assert_eq!(objects[0]["contentUrl"], "https://example.com/2024/a.jpg");
+        assert!(objects[0].get("geo").is_none());
+        assert!(objects[0].get("keywords").is_none());
+    }
+}
@@ -0,0 +1,16 @@
+//! Export module - Publishing sidecar metadata in other formats
+//!
+//! Exporters read existing sidecars and render them for consumers outside
+//! Jožin (websites, feed readers, other tools). Sidecars and originals are
+//! never modified.
+//!
+//! ## Submodules
+//!
+//! - **jsonld** - schema.org `ImageObject` JSON-LD for web publishing
+//! - **rss** - RSS 2.0 feed of the newest photos
+//! - **sqlite** - SQLite database of the sidecar index for SQL queries (`sqlite` feature)
+
+pub mod jsonld;
+pub mod rss;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
@@ -0,0 +1,390 @@
+//! RSS 2.0 feed of recent photos
+//!
+//! Photo blogs and family sites often publish updates as a feed.
+//! [`generate_rss_feed()`] renders sidecars as an
+//! [RSS 2.0](https://www.rssboard.org/rss-specification) channel, newest
+//! photo first, with [Media RSS](https://www.rssboard.org/media-rss) entries
+//! for the full images.
+//!
+//! ## Item Mapping
+//!
+//! | RSS                | Sidecar                                                 |
+//! |--------------------|---------------------------------------------------------|
+//! | `title`            | File name of `source.file_path`                         |
+//! | `link`, `media:content` | `base_image_url` + path of the photo               |
+//! | `guid`             | `source.file_hash_b3` (not a permalink)                 |
+//! | `pubDate`          | `image.datetime_original`, or `created_at` without one  |
+//! | `description`      | First tag label                                         |
+//! | `enclosure`        | Smallest entry of `thumbnails`                          |
+//!
+//! Elements without a source value are omitted.
+//!
+//! ## Usage
+//!
+//! ```no_run
+//! use jozin_core::export::rss::{export_rss, RssFeedConfig};
+//! use std::path::Path;
+//!
+//! let config = RssFeedConfig {
+//!     title: "Family photos".to_string(),
+//!     link: "https://example.com".to_string(),
+//!     base_image_url: "https://example.com/photos".to_string(),
+//!     ..RssFeedConfig::default()
+//! };
+//! std::fs::write("feed.xml", export_rss(Path::new("/photos"), &config, true)?)?;
+//! # Ok::<(), jozin_core::JozinError>(())
+//! ```
+
+use crate::scan::load_sidecars;
+use crate::util::mime::extension_to_mime;
+use crate::{Result, Sidecar};
+use std::cmp::Reverse;
+use std::path::{Component, Path, PathBuf};
+use time::format_description::well_known::{Rfc2822, Rfc3339};
+use time::OffsetDateTime;
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+/// Default number of items in a feed.
+pub const DEFAULT_MAX_ITEMS: usize = 50;
+
+/// Namespace of the `media:` elements.
+const MEDIA_RSS_NAMESPACE: &str = "http://search.yahoo.com/mrss/";
+
+// ============================================================================
+// Public Types
+// ============================================================================
+
+/// Channel settings of an RSS feed.
+///
+/// # Fields
+///
+/// - `title`: Channel title
+/// - `link`: URL of the website the feed belongs to
+/// - `description`: Channel description
+/// - `base_image_url`: URL the photos (and their thumbnails) are published at
+/// - `max_items`: Maximum number of items, newest first (default 50)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RssFeedConfig {
+    pub title: String,
+    pub link: String,
+    pub description: String,
+    pub base_image_url: String,
+    pub max_items: usize,
+}
+
+impl Default for RssFeedConfig {
+    fn default() -> Self {
+        Self {
+            title: "Photos".to_string(),
+            link: String::new(),
+            description: "Recently added photos".to_string(),
+            base_image_url: String::new(),
+            max_items: DEFAULT_MAX_ITEMS,
+        }
+    }
+}
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Renders sidecars as an RSS 2.0 feed, newest first.
+///
+/// Without a library root to relate to, image URLs are `base_image_url`
+/// plus the file name; use [`export_rss()`] to keep subdirectories in the
+/// URLs.
+///
+/// # Errors
+///
+/// Currently infallible; returns `Result` so rendering errors can be
+/// reported as elements are added.
+///
+/// # Example
+///
+/// ```no_run
+/// use jozin_core::export::rss::{generate_rss_feed, RssFeedConfig};
+/// use jozin_core::scan::read_sidecar;
+/// use std::path::Path;
+///
+/// let sidecar = read_sidecar(Path::new("/photos/IMG_1234.JPG"))?;
+/// let feed = generate_rss_feed(&[sidecar], RssFeedConfig::default())?;
+/// assert!(feed.contains("<rss version=\"2.0\""));
+/// # Ok::<(), jozin_core::JozinError>(())
+/// ```
+pub fn generate_rss_feed(sidecars: &[Sidecar], config: RssFeedConfig) -> Result<String> {
+    let items = sidecars.iter().map(|sidecar| (sidecar, PathBuf::from(file_name(sidecar)))).collect();
+    Ok(render_feed(items, &config))
+}
+
+/// Renders the sidecars under `root` as an RSS 2.0 feed.
+///
+/// Image URLs keep the photo's path relative to `root`; photos recorded
+/// outside `root` fall back to their file name.
+///
+/// # Arguments
+///
+/// * `root` - Library directory (or a single image or sidecar)
+/// * `config` - Channel settings
+/// * `recursive` - Include subdirectories
+///
+/// # Errors
+///
+/// Returns `JozinError::IoError` if `root` does not exist.
+pub fn export_rss(root: &Path, config: &RssFeedConfig, recursive: bool) -> Result<String> {
+    let root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+    let root = if root.is_file() {
+        root.parent().map(Path::to_path_buf).unwrap_or_default()
+    } else {
+        root
+    };
+
+    let sidecars = load_sidecars(&root, recursive)?;
+    let items = sidecars
+        .iter()
+        .map(|sidecar| match Path::new(&sidecar.source.file_path).strip_prefix(&root) {
+            Ok(relative) => (sidecar, relative.to_path_buf()),
+            Err(_) => (sidecar, PathBuf::from(file_name(sidecar))),
+        })
+        .collect();
+    Ok(render_feed(items, config))
+}
+
+// ============================================================================
+// Internal Helpers
+// ============================================================================
+
+/// Renders the channel with the newest `max_items` of `items` (each a
+/// sidecar and the photo's path relative to `base_image_url`).
+fn render_feed(mut items: Vec<(&Sidecar, PathBuf)>, config: &RssFeedConfig) -> String {
+    // Newest first; undated photos last, by path for a stable order
+    items.sort_by_key(|(sidecar, path)| (Reverse(publication_date(sidecar)), path.clone()));
+    items.truncate(config.max_items);
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(&format!("<rss version=\"2.0\" xmlns:media=\"{}\">\n", MEDIA_RSS_NAMESPACE));
+    xml.push_str("  <channel>\n");
+    push_element(&mut xml, 4, "title", &config.title);
+    push_element(&mut xml, 4, "link", &config.link);
+    push_element(&mut xml, 4, "description", &config.description);
+    push_element(&mut xml, 4, "generator", &format!("Jožin {}", env!("CARGO_PKG_VERSION")));
+    for (sidecar, relative_path) in &items {
+        render_item(&mut xml, sidecar, relative_path, &config.base_image_url);
+    }
+    xml.push_str("  </channel>\n</rss>\n");
+    xml
+}
+
+/// Appends one `<item>`.
+fn render_item(xml: &mut String, sidecar: &Sidecar, relative_path: &Path, base_url: &str) {
+    let image_url = join_url(base_url, relative_path);
+
+    xml.push_str("    <item>\n");
+    push_element(xml, 6, "title", &file_name(sidecar));
+    push_element(xml, 6, "link", &image_url);
+    xml.push_str(&format!(
+        "      <guid isPermaLink=\"false\">{}</guid>\n",
+        escape_xml(&sidecar.source.file_hash_b3)
+    ));
+    if let Some(date) = publication_date(sidecar).and_then(|date| date.format(&Rfc2822).ok()) {
+        push_element(xml, 6, "pubDate", &date);
+    }
+    if let Some(tag) = sidecar.tags.first() {
+        push_element(xml, 6, "description", &tag.label);
+    }
+
+    // Thumbnails are stored next to the original
+    if let Some(thumbnail) = sidecar.thumbnails.iter().min_by_key(|t| t.size) {
+        if let Some(name) = Path::new(&thumbnail.path).file_name() {
+            let url = join_url(base_url, &relative_path.with_file_name(name));
+            let length = std::fs::metadata(&thumbnail.path).map_or(0, |metadata| metadata.len());
+            xml.push_str(&format!(
+                "      <enclosure url=\"{}\" length=\"{}\" type=\"{}\"/>\n",
+                escape_xml(&url),
+                length,
+                mime_of(Path::new(name))
+            ));
+        }
+    }
+
+    let mut media = format!("      <media:content url=\"{}\" medium=\"image\"", escape_xml(&image_url));
+    media.push_str(&format!(" type=\"{}\"", mime_of(relative_path)));
+    if let Some(image) = &sidecar.image {
+        if let (Some(width), Some(height)) = (image.width, image.height) {
+            media.push_str(&format!(" width=\"{}\" height=\"{}\"", width, height));
+        }
+    }
+    xml.push_str(&media);
+    xml.push_str("/>\n");
+    xml.push_str("    </item>\n");
+}
+
+/// Capture time of the photo, or the sidecar's creation time without one.
+///
+/// Capture times recorded without a UTC offset are taken as UTC.
+fn publication_date(sidecar: &Sidecar) -> Option<OffsetDateTime> {
+    let parse = |value: &str| {
+        OffsetDateTime::parse(value, &Rfc3339)
+            .or_else(|_| OffsetDateTime::parse(&format!("{}Z", value), &Rfc3339))
+            .ok()
+    };
+    sidecar
+        .image
+        .as_ref()
+        .and_then(|image| image.datetime_original.as_deref())
+        .and_then(parse)
+        .or_else(|| parse(&sidecar.created_at))
+}
+
+/// MIME type of a file by extension (`application/octet-stream` if unknown).
+fn mime_of(path: &Path) -> &'static str {
+    path.extension()
+        .and_then(|ext| extension_to_mime(&ext.to_string_lossy()))
+        .unwrap_or("application/octet-stream")
+}
+
+/// Appends `<name>text</name>` on its own line, indented by `indent` spaces.
+fn push_element(xml: &mut String, indent: usize, name: &str, text: &str) {
+    xml.push_str(&format!("{:indent$}<{name}>{}</{name}>\n", "", escape_xml(text), indent = indent, name = name));
+}
+
+/// Escapes the XML special characters of text and attribute values.
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// File name of the sidecar's photo.
+fn file_name(sidecar: &Sidecar) -> String {
+    let path = Path::new(&sidecar.source.file_path);
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| sidecar.source.file_path.clone())
+}
+
+/// Appends a relative path to `base_url`, percent-encoding each segment.
+fn join_url(base_url: &str, relative_path: &Path) -> String {
+    let segments: Vec<String> = relative_path
+        .components()
+        .filter_map(|component| match component {
+            Component::Normal(segment) => Some(encode_segment(&segment.to_string_lossy())),
+            _ => None,
+        })
+        .collect();
+    format!("{}/{}", base_url.trim_end_matches('/'), segments.join("/"))
+}
+
+/// Percent-encodes everything but RFC 3986 unreserved characters.
+fn encode_segment(segment: &str) -> String {
+    let mut encoded = String::with_capacity(segment.len());
+    for byte in segment.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    encoded
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scan::scan_file;
+    use crate::{ImageInfo, Tag, ThumbnailInfo};
+    use tempfile::TempDir;
+
+    fn sidecar_at(dir: &Path, name: &str, datetime_original: Option<&str>) -> Sidecar {
+        let photo = dir.join(name);
+        std::fs::write(&photo, name.as_bytes()).unwrap();
+        let mut sidecar = scan_file(&photo, true).unwrap().sidecar;
+        sidecar.image = Some(ImageInfo {
+            datetime_original: datetime_original.map(str::to_string),
+            ..ImageInfo::default()
+        });
+        sidecar
+    }
+
+    #[test]
+    fn test_generate_rss_feed_items() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut beach = sidecar_at(temp_dir.path(), "beach & sun.jpg", Some("2024-06-01T12:00:00+02:00"));
+        beach.tags = vec![Tag::from_label("sunset"), Tag::from_label("beach")];
+        beach.image.as_mut().unwrap().width = Some(4000);
+        beach.image.as_mut().unwrap().height = Some(3000);
+        let thumbnail = temp_dir.path().join("beach & sun_256.jpg");
+        std::fs::write(&thumbnail, b"thumb").unwrap();
+        beach.thumbnails.push(ThumbnailInfo {
+            path: thumbnail.display().to_string(),
+            size: 256,
+            format: "jpg".to_string(),
+            source_timestamp_sec: None,
+            dominant_color: None,
+            from_exif: false,
+            tonemap: None,
+        });
+        let older = sidecar_at(temp_dir.path(), "older.jpg", Some("2023-01-01T08:00:00Z"));
+        let undated = sidecar_at(temp_dir.path(), "undated.png", None);
+
+        let config = RssFeedConfig {
+            title: "Family <photos>".to_string(),
+            link: "https://example.com".to_string(),
+            base_image_url: "https://example.com/photos/".to_string(),
+            ..RssFeedConfig::default()
+        };
+        let feed = generate_rss_feed(&[older, undated.clone(), beach], config.clone()).unwrap();
+
+        assert!(feed.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<rss version=\"2.0\""));
+        assert!(feed.contains("<title>Family &lt;photos&gt;</title>"));
+        assert_eq!(feed.matches("<item>").count(), 3);
+        assert!(feed.contains("<title>beach &amp; sun.jpg</title>"));
+        assert!(feed.contains("<pubDate>Sat, 01 Jun 2024 12:00:00 +0200</pubDate>"));
+        assert!(feed.contains("<description>sunset</description>"));
+        assert!(feed.contains(
+            "<enclosure url=\"https://example.com/photos/beach%20%26%20sun_256.jpg\" length=\"5\" type=\"image/jpeg\"/>"
+        ));
+        assert!(feed.contains(
+            "<media:content url=\"https://example.com/photos/beach%20%26%20sun.jpg\" medium=\"image\" type=\"image/jpeg\" width=\"4000\" height=\"3000\"/>"
+        ));
+
+        // Newest first; the undated photo falls back to the sidecar's created_at
+        let position = |name: &str| feed.find(&format!("<title>{}</title>", name)).unwrap();
+        assert!(position("undated.png") < position("beach &amp; sun.jpg"));
+        assert!(position("beach &amp; sun.jpg") < position("older.jpg"));
+
+        let limited = generate_rss_feed(&[undated], RssFeedConfig { max_items: 0, ..config }).unwrap();
+        assert!(!limited.contains("<item>"));
+    }
+
+    #[test]
+    fn test_export_rss_keeps_subdirectories() {
+        let temp_dir = TempDir::new().unwrap();
+        let album = temp_dir.path().join("2024");
+        std::fs::create_dir(&album).unwrap();
+        let photo = album.join("a.jpg");
+        std::fs::write(&photo, b"image").unwrap();
+        scan_file(&photo, false).unwrap();
+
+        let config = RssFeedConfig { base_image_url: "https://example.com".to_string(), ..RssFeedConfig::default() };
+        let feed = export_rss(temp_dir.path(), &config, true).unwrap();
+        assert!(feed.contains("<link>https://example.com/2024/a.jpg</link>"));
+        assert!(!feed.contains("<enclosure"));
+        assert!(feed.contains("<pubDate>"));
+    }
+}
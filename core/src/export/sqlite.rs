@@ -0,0 +1,371 @@
+//! SQLite database of the sidecar index
+//!
+//! For questions the CLI doesn't answer, [`export_to_sqlite()`] writes the
+//! sidecars of a library into an SQLite database that any SQL client can
+//! query:
+//!
+//! ```sql
+//! SELECT camera_model, COUNT(*) AS cnt FROM images
+//! WHERE year = 2024 GROUP BY camera_model ORDER BY cnt DESC;
+//! ```
+//!
+//! ## Schema
+//!
+//! | Table        | Columns                                                          |
+//! |--------------|------------------------------------------------------------------|
+//! | `images`     | `id`, `file_path`, `file_hash`, `file_size`, `schema_version`, `created_at`, `updated_at`, `width`, `height`, `format`, `orientation`, `datetime_original`, `year`, `camera_make`, `camera_model`, `gps_lat`, `gps_lon`, `score`, `color_space` |
+//! | `tags`       | `image_id`, `label`, `score`, `source`                           |
+//! | `faces`      | `image_id`, `bbox_x`, `bbox_y`, `bbox_w`, `bbox_h`, `score`, `person`, `cluster_id` |
+//! | `thumbnails` | `image_id`, `path`, `size`, `format`, `width`, `height`          |
+//!
+//! - `file_hash` is the BLAKE3 hash (SHA-256 for sidecars scanned with it)
+//! - `year` is the year of `datetime_original`
+//! - `score` is the star rating of a `rating:N` tag (e.g. imported from
+//!   Lightroom or digiKam)
+//! - Thumbnail `width`/`height` follow from `size` and the image dimensions
+//!   (NULL when those are unknown)
+//!
+//! `file_hash`, `datetime_original`, `camera_make` and `tags.label` are
+//! indexed. The database is built next to `db_path` and renamed over it when
+//! complete, so an existing export is replaced only by a finished one.
+
+use crate::util::atomic_rename::atomic_rename;
+use crate::{JozinError, Result, Sidecar};
+use rusqlite::{params, Connection, Transaction};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+/// Tables and indices of an export.
+const SCHEMA: &str = "
+CREATE TABLE images (
+    id INTEGER PRIMARY KEY,
+    file_path TEXT NOT NULL,
+    file_hash TEXT NOT NULL,
+    file_size INTEGER NOT NULL,
+    schema_version TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    width INTEGER,
+    height INTEGER,
+    format TEXT,
+    orientation INTEGER,
+    datetime_original TEXT,
+    year INTEGER,
+    camera_make TEXT,
+    camera_model TEXT,
+    gps_lat REAL,
+    gps_lon REAL,
+    score INTEGER,
+    color_space TEXT
+);
+CREATE TABLE tags (
+    image_id INTEGER NOT NULL REFERENCES images(id),
+    label TEXT NOT NULL,
+    score REAL,
+    source TEXT NOT NULL
+);
+CREATE TABLE faces (
+    image_id INTEGER NOT NULL REFERENCES images(id),
+    bbox_x REAL NOT NULL,
+    bbox_y REAL NOT NULL,
+    bbox_w REAL NOT NULL,
+    bbox_h REAL NOT NULL,
+    score REAL NOT NULL,
+    person TEXT,
+    cluster_id TEXT
+);
+CREATE TABLE thumbnails (
+    image_id INTEGER NOT NULL REFERENCES images(id),
+    path TEXT NOT NULL,
+    size INTEGER NOT NULL,
+    format TEXT NOT NULL,
+    width INTEGER,
+    height INTEGER
+);
+CREATE INDEX idx_images_file_hash ON images(file_hash);
+CREATE INDEX idx_images_datetime_original ON images(datetime_original);
+CREATE INDEX idx_images_camera_make ON images(camera_make);
+CREATE INDEX idx_tags_label ON tags(label);
+";
+
+// ============================================================================
+// Public Types
+// ============================================================================
+
+/// Result of [`export_to_sqlite()`].
+///
+/// # Fields
+///
+/// - `db_path`: The written database
+/// - `images`, `tags`, `faces`, `thumbnails`: Rows written per table
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SqliteExportResult {
+    pub db_path: PathBuf,
+    pub images: usize,
+    pub tags: usize,
+    pub faces: usize,
+    pub thumbnails: usize,
+}
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Writes sidecars into a new SQLite database at `db_path`, replacing any
+/// existing file.
+///
+/// # Arguments
+///
+/// * `sidecars` - Sidecars to export (e.g. from [`crate::scan::load_sidecars()`])
+/// * `db_path` - Database file to write
+///
+/// # Errors
+///
+/// Returns `JozinError::IoError` if the database cannot be written.
+///
+/// # Example
+///
+/// ```no_run
+/// use jozin_core::export::sqlite::export_to_sqlite;
+/// use jozin_core::scan::load_sidecars;
+/// use std::path::Path;
+///
+/// let sidecars = load_sidecars(Path::new("/photos"), true)?;
+/// let result = export_to_sqlite(&sidecars, Path::new("library.db"))?;
+/// println!("{} images, {} tags", result.images, result.tags);
+/// # Ok::<(), jozin_core::JozinError>(())
+/// ```
+pub fn export_to_sqlite(sidecars: &[Sidecar], db_path: &Path) -> Result<SqliteExportResult> {
+    let mut tmp_name = db_path.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(".tmp");
+    let tmp_path = db_path.with_file_name(tmp_name);
+    let _ = fs::remove_file(&tmp_path);
+
+    match write_database(sidecars, &tmp_path) {
+        Ok(mut result) => {
+            atomic_rename(&tmp_path, db_path)?;
+            result.db_path = db_path.to_path_buf();
+            Ok(result)
+        }
+        Err(e) => {
+            let _ = fs::remove_file(&tmp_path);
+            Err(database_error(db_path, e))
+        }
+    }
+}
+
+// ============================================================================
+// Internal Helpers
+// ============================================================================
+
+fn write_database(sidecars: &[Sidecar], path: &Path) -> rusqlite::Result<SqliteExportResult> {
+    let mut conn = Connection::open(path)?;
+    let tx = conn.transaction()?;
+    tx.execute_batch(SCHEMA)?;
+
+    let mut result = SqliteExportResult {
+        db_path: path.to_path_buf(),
+        images: 0,
+        tags: 0,
+        faces: 0,
+        thumbnails: 0,
+    };
+    for sidecar in sidecars {
+        insert_sidecar(&tx, sidecar, &mut result)?;
+    }
+    tx.commit()?;
+    Ok(result)
+}
+
+/// Inserts one image with its tags, faces and thumbnails.
+fn insert_sidecar(tx: &Transaction, sidecar: &Sidecar, result: &mut SqliteExportResult) -> rusqlite::Result<()> {
+    let source = &sidecar.source;
+    let image = sidecar.image.clone().unwrap_or_default();
+    let file_hash = match (&source.file_hash_sha256, source.file_hash_b3.is_empty()) {
+        (Some(sha256), true) => sha256.as_str(),
+        _ => source.file_hash_b3.as_str(),
+    };
+    let year = image.datetime_original.as_deref().and_then(|d| d.get(..4)).and_then(|y| y.parse::<i32>().ok());
+
+    tx.execute(
+        "INSERT INTO images (file_path, file_hash, file_size, schema_version, created_at, updated_at, width, height,
+             format, orientation, datetime_original, year, camera_make, camera_model, gps_lat, gps_lon, score, color_space)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18)",
+        params![
+            source.file_path,
+            file_hash,
+            source.file_size_bytes as i64,
+            sidecar.schema_version,
+            sidecar.created_at,
+            sidecar.updated_at,
+            image.width,
+            image.height,
+            image.format,
+            image.orientation,
+            image.datetime_original,
+            year,
+            image.camera_make,
+            image.camera_model,
+            image.gps_latitude,
+            image.gps_longitude,
+            rating(sidecar),
+            image.color_space,
+        ],
+    )?;
+    let image_id = tx.last_insert_rowid();
+    result.images += 1;
+
+    for tag in &sidecar.tags {
+        tx.execute(
+            "INSERT INTO tags (image_id, label, score, source) VALUES (?1, ?2, ?3, ?4)",
+            params![image_id, tag.label, tag.score, tag.source.as_str()],
+        )?;
+        result.tags += 1;
+    }
+    for face in &sidecar.faces {
+        let [x, y, w, h] = face.bbox;
+        tx.execute(
+            "INSERT INTO faces (image_id, bbox_x, bbox_y, bbox_w, bbox_h, score, person, cluster_id)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![image_id, x, y, w, h, face.score, face.person, face.cluster_id],
+        )?;
+        result.faces += 1;
+    }
+    for thumbnail in &sidecar.thumbnails {
+        let dimensions = match (image.width, image.height) {
+            (Some(width), Some(height)) if width > 0 && height > 0 => {
+                Some(thumbnail_dimensions(thumbnail.size, width, height))
+            }
+            _ => None,
+        };
+        tx.execute(
+            "INSERT INTO thumbnails (image_id, path, size, format, width, height) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                image_id,
+                thumbnail.path,
+                thumbnail.size,
+                thumbnail.format,
+                dimensions.map(|d| d.0),
+                dimensions.map(|d| d.1)
+            ],
+        )?;
+        result.thumbnails += 1;
+    }
+    Ok(())
+}
+
+/// Star rating from a `rating:N` tag (1-5).
+fn rating(sidecar: &Sidecar) -> Option<u8> {
+    sidecar
+        .tags
+        .iter()
+        .find_map(|tag| tag.label.strip_prefix("rating:")?.parse::<u8>().ok())
+        .filter(|rating| (1..=5).contains(rating))
+}
+
+/// Thumbnail dimensions for an image of `width` × `height`: the longest edge
+/// fitted to `size`, aspect ratio kept, never upscaled (the rule the
+/// thumbnail writers use).
+fn thumbnail_dimensions(size: u32, width: u32, height: u32) -> (u32, u32) {
+    let (long, short) = (width.max(height), width.min(height));
+    if long <= size {
+        return (width, height);
+    }
+    let scaled_short = ((u64::from(short) * u64::from(size) + u64::from(long) / 2) / u64::from(long)).max(1) as u32;
+    if width >= height {
+        (size, scaled_short)
+    } else {
+        (scaled_short, size)
+    }
+}
+
+fn database_error(db_path: &Path, err: rusqlite::Error) -> JozinError {
+    JozinError::IoError {
+        message: format!("Failed to write SQLite database {}: {}", db_path.display(), err),
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scan::{read_sidecar, scan_file};
+    use crate::{FaceDetection, Tag, ThumbnailInfo};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_export_to_sqlite() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut sidecars = Vec::new();
+        for (name, make, date) in [
+            ("a.jpg", "Canon", "2024-05-01T10:00:00Z"),
+            ("b.jpg", "Canon", "2024-06-01T10:00:00Z"),
+            ("c.jpg", "Apple", "2023-01-01T10:00:00Z"),
+        ] {
+            let photo = temp_dir.path().join(name);
+            fs::write(&photo, name).unwrap();
+            scan_file(&photo, false).unwrap();
+            let mut sidecar = read_sidecar(&photo).unwrap();
+            let image = sidecar.image.get_or_insert_with(Default::default);
+            image.camera_make = Some(make.to_string());
+            image.datetime_original = Some(date.to_string());
+            (image.width, image.height) = (Some(4000), Some(3000));
+            sidecars.push(sidecar);
+        }
+        sidecars[0].tags = vec![Tag::from_label("beach"), Tag::from_label("rating:4")];
+        sidecars[0].faces = vec![FaceDetection {
+            bbox: [0.1, 0.2, 0.3, 0.4],
+            score: 0.9,
+            embedding_hash: None,
+            person: Some("Alice".to_string()),
+            landmarks: None,
+            age_estimate: None,
+            cluster_id: None,
+        }];
+        sidecars[0].thumbnails = vec![ThumbnailInfo {
+            path: "a_256.webp".to_string(),
+            size: 256,
+            format: "webp".to_string(),
+            source_timestamp_sec: None,
+            dominant_color: None,
+            from_exif: false,
+            tonemap: None,
+        }];
+
+        let db_path = temp_dir.path().join("library.db");
+        fs::write(&db_path, "stale").unwrap();
+        let result = export_to_sqlite(&sidecars, &db_path).unwrap();
+        assert_eq!((result.images, result.tags, result.faces, result.thumbnails), (3, 2, 1, 1));
+
+        let conn = Connection::open(&db_path).unwrap();
+        let mut stmt = conn
+            .prepare("SELECT camera_make, COUNT(*) FROM images WHERE year = 2024 GROUP BY camera_make")
+            .unwrap();
+        let rows: Vec<(String, i64)> = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?))).unwrap().map(|r| r.unwrap()).collect();
+        assert_eq!(rows, vec![("Canon".to_string(), 2)]);
+
+        let (person, score): (String, Option<i64>) = conn
+            .query_row(
+                "SELECT f.person, i.score FROM faces f JOIN images i ON i.id = f.image_id",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!((person.as_str(), score), ("Alice", Some(4)));
+        let size: (i64, i64) = conn.query_row("SELECT width, height FROM thumbnails", [], |row| Ok((row.get(0)?, row.get(1)?))).unwrap();
+        assert_eq!(size, (256, 192));
+        let indices: i64 = conn
+            .query_row("SELECT COUNT(*) FROM sqlite_master WHERE type = 'index' AND name LIKE 'idx_%'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(indices, 4);
+        assert!(!temp_dir.path().join("library.db.tmp").exists());
+    }
+}
@@ -8,9 +8,75 @@
 //!
 //! ## Status
 //!
-//! This is a **Phase 2+ stub module**. The actual implementation is planned
-//! but not yet available. This file exists to satisfy the feature flag
-//! structure and prevent compilation warnings.
+//! Face detection itself is not implemented yet. Faces already recorded in
+//! sidecars can be reviewed with [`generate_contact_sheet()`], which renders a
+//! grid of face crops for labeling.
+//!
+//! ## Alignment
+//!
+//! Embedding models expect faces aligned to a canonical pose. When a detection
+//! carries 5-point [`FaceDetection::landmarks`], [`alignment_transform()`]
+//! computes the similarity transform (rotation, uniform scale, translation)
+//! that maps them onto [`ARCFACE_LANDMARK_TEMPLATE`]; the embedding step warps
+//! the face crop with it.
+//!
+//! ## Person Database Portability
+//!
+//! Known persons (name + reference embedding) live in a [`PersonDatabase`]
+//! stored at `<library>/.jozin/persons.bin`. [`export_embeddings()`] writes it
+//! to a portable file and [`import_embeddings()`] merges such a file into
+//! another library, so a face model trained on one device can be reused on
+//! another without re-training.
+//!
+//! | [`EmbeddingFormat`] | Encoding                                              |
+//! |---------------------|-------------------------------------------------------|
+//! | `Binary`            | bincode (compact)                                     |
+//! | `Json`              | JSON; each embedding is base64 of little-endian `f32`s |
+//!
+//! ## Age Estimation
+//!
+//! Once detection runs, `jozin faces --estimate-age` fills
+//! [`FaceDetection::age_estimate`] for faces scoring at least `--min-score`,
+//! using an age estimation model (e.g., SSR-Net or AgeNet ONNX, chosen with
+//! `--age-model`) separate from the detection model.
+//! Estimated ages are personal data: the CLI prints a privacy notice to
+//! stderr when they are requested, and `jozin verify --check-privacy` reports
+//! sidecars containing them. [`has_face_in_age_range()`] selects photos by
+//! estimated age.
+//!
+//! ## Anonymisation
+//!
+//! [`anonymise_faces()`] redacts the faces recorded for a photo
+//! ([`AnonymiseMethod`]: pixelate, blur or fill) and writes the result
+//! upright, without EXIF. [`anonymise_batch()`] prepares a whole library for
+//! public sharing: it mirrors `src_root` into `dst_root`, redacting photos
+//! with faces, copying the others unchanged, and writing their sidecars with
+//! `faces` emptied so no person names leak.
+//!
+//! ## Face Clusters
+//!
+//! Unknown faces that look alike can be grouped into clusters, recorded per
+//! face as [`FaceDetection::cluster_id`]. Naming a cluster once with
+//! [`assign_cluster()`] names all of its faces across the library;
+//! [`clear_clusters()`] removes the assignments before re-clustering.
+//! `jozin verify` warns about clusters that have no name yet.
+//!
+//! ## Privacy Report and Erasure
+//!
+//! Person names attached to faces are biometric personal data.
+//! [`privacy_report()`] inventories them per person (face count, photos,
+//! first/last seen) for access requests, and [`delete_person()`] erases a
+//! person from the sidecars, their backups and the [`PersonDatabase`]
+//! (`jozin faces --privacy-report` / `--delete-person`).
+//!
+//! ## Face Pipeline
+//!
+//! Detection, embedding and identification share one [`FacePipeline`], which
+//! loads the detection and embedding models once and is reused for every
+//! image. [`detect_and_process()`] keeps one pipeline per thread, so rayon
+//! workers never share a model session. Running the models needs an ONNX
+//! runtime that is not bundled yet: the pipeline validates its models and
+//! decodes the image, then returns `JozinError::NotSupported`.
 //!
 //! ## Future Implementation
 //!
@@ -19,6 +85,1968 @@
 //! - `identify_faces()` - Match faces against known persons
 //! - `train_model()` - Train on labeled face data
 
-// Placeholder to prevent "unused module" warnings
-#[allow(dead_code)]
-const _PLACEHOLDER: &str = "Phase 2+ implementation pending";
+use crate::scan::{find_sidecar_files_with, is_image_file, parse_sidecar};
+use crate::util::compression::{encode_sidecar, existing_sidecar_path, is_compressed_path, read_sidecar_file};
+use crate::util::fsync_policy::FsyncPolicy;
+use crate::util::library_config::LibraryConfig;
+use crate::util::orientation::apply_orientation;
+use crate::util::path_normalise::normalise_sidecar_path;
+use crate::util::symlink::{walker, SymlinkStrategy};
+use crate::util::unicode_normalise::normalise_person_name;
+use crate::{FaceDetection, JozinError, ProgressEvent, Result, Sidecar, Timestamp};
+use image::{DynamicImage, Rgb, RgbImage};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use base64::Engine;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::thread;
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+/// Reference landmark positions (pixels) in a 112x112 aligned face crop, as
+/// used by ArcFace models. Order matches [`FaceDetection::landmarks`].
+pub const ARCFACE_LANDMARK_TEMPLATE: [[f32; 2]; 5] = [
+    [38.2946, 51.6963],
+    [73.5318, 51.5014],
+    [56.0252, 71.7366],
+    [41.5493, 92.3655],
+    [70.7299, 92.2041],
+];
+
+/// Side length (pixels) of the crop [`ARCFACE_LANDMARK_TEMPLATE`] refers to.
+pub const ARCFACE_CROP_SIZE: u32 = 112;
+
+/// Location of a library's [`PersonDatabase`], relative to the library root.
+pub const PERSON_DATABASE_FILE: &str = ".jozin/persons.bin";
+
+// ============================================================================
+// Public Types
+// ============================================================================
+
+/// Result of [`generate_contact_sheet()`].
+///
+/// # Fields
+///
+/// - `total_faces`: Number of faces placed on the sheet(s)
+/// - `output_path`: Path of the first page
+/// - `page_count`: Number of pages written (`<stem>-page<N>.<ext>` after the first)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContactSheetResult {
+    pub total_faces: usize,
+    pub output_path: String,
+    pub page_count: usize,
+}
+
+/// A known person and their reference face embedding.
+///
+/// # Fields
+///
+/// - `name`: Person name (unique within a database, compared case-sensitively
+///   after [`normalise_person_name()`])
+/// - `embedding`: Reference embedding (mean of the training samples)
+/// - `sample_count`: Number of face samples averaged into `embedding`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PersonEmbedding {
+    pub name: String,
+    pub embedding: Vec<f32>,
+    pub sample_count: u32,
+}
+
+/// Trained persons used by `jozin faces --identify`.
+///
+/// # Fields
+///
+/// - `model`: Embedding model the vectors were produced with (e.g., "arcface-1.4")
+/// - `persons`: Known persons
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PersonDatabase {
+    pub model: Option<String>,
+    pub persons: Vec<PersonEmbedding>,
+}
+
+impl PersonDatabase {
+    /// Loads the database of a library (empty if none has been trained yet).
+    ///
+    /// # Errors
+    ///
+    /// - `JozinError::IoError` if the file exists but cannot be read
+    /// - `JozinError::ValidationError` if the file is corrupt
+    pub fn load(library_root: &Path) -> Result<Self> {
+        let path = library_root.join(PERSON_DATABASE_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        decode_database(&fs::read(&path)?)
+    }
+
+    /// Saves the database to `<library_root>/.jozin/persons.bin`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `JozinError::IoError` if the file cannot be written.
+    pub fn save(&self, library_root: &Path) -> Result<()> {
+        let path = library_root.join(PERSON_DATABASE_FILE);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, encode_database(self, EmbeddingFormat::Binary)?)?;
+        Ok(())
+    }
+
+    /// Adds a person, normalising the name (see
+    /// [`normalise_person_name()`]). A person with the same name is replaced
+    /// and returned.
+    pub fn add_person(&mut self, mut person: PersonEmbedding) -> Option<PersonEmbedding> {
+        person.name = normalise_person_name(&person.name);
+        match self.persons.iter_mut().find(|p| p.name == person.name) {
+            Some(existing) => Some(std::mem::replace(existing, person)),
+            None => {
+                self.persons.push(person);
+                None
+            }
+        }
+    }
+
+    /// Removes a person by name (normalised, see [`normalise_person_name()`])
+    /// and returns it.
+    pub fn remove_person(&mut self, name: &str) -> Option<PersonEmbedding> {
+        let name = normalise_person_name(name);
+        let index = self.persons.iter().position(|p| p.name == name)?;
+        Some(self.persons.remove(index))
+    }
+}
+
+/// File format for [`export_embeddings()`].
+///
+/// # Variants
+///
+/// - `Binary`: bincode, compact but Jožin-specific
+/// - `Json`: JSON with base64-encoded embeddings, readable by other tools
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EmbeddingFormat {
+    Binary,
+    Json,
+}
+
+impl EmbeddingFormat {
+    /// `Json` for `.json` paths, `Binary` otherwise.
+    pub fn from_path(path: &Path) -> Self {
+        if path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("json")) {
+            EmbeddingFormat::Json
+        } else {
+            EmbeddingFormat::Binary
+        }
+    }
+}
+
+/// How [`import_embeddings()`] handles a person already in the database.
+///
+/// # Variants
+///
+/// - `Replace`: Use the imported embedding
+/// - `KeepExisting`: Ignore the imported embedding
+/// - `Average`: Average both, weighted by `sample_count`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MergeStrategy {
+    Replace,
+    KeepExisting,
+    Average,
+}
+
+/// Result of [`import_embeddings()`].
+///
+/// # Fields
+///
+/// - `added`: Persons that were not in the database
+/// - `replaced`: Existing persons overwritten (`Replace`)
+/// - `kept`: Existing persons left unchanged (`KeepExisting`)
+/// - `averaged`: Existing persons merged (`Average`)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImportResult {
+    pub added: usize,
+    pub replaced: usize,
+    pub kept: usize,
+    pub averaged: usize,
+}
+
+/// How [`anonymise_faces()`] hides a face.
+///
+/// # Variants
+///
+/// - `Pixelate`: Replace the face with blocks of `block_size` pixels, each
+///   filled with its average colour
+/// - `Blur`: Gaussian blur with standard deviation `sigma` (pixels)
+/// - `Fill`: Solid black rectangle
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum AnonymiseMethod {
+    Pixelate { block_size: u32 },
+    Blur { sigma: f32 },
+    Fill,
+}
+
+/// Result of [`anonymise_batch()`].
+///
+/// # Fields
+///
+/// - `processed`: Images found under the source root
+/// - `anonymised`: Images written with faces redacted
+/// - `faces_redacted`: Faces redacted across those images
+/// - `copied_unchanged`: Images without recorded faces, copied as-is
+/// - `failed`: Images that could not be processed (reported as warnings)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AnonymiseResult {
+    pub processed: usize,
+    pub anonymised: usize,
+    pub faces_redacted: usize,
+    pub copied_unchanged: usize,
+    pub failed: usize,
+}
+
+/// Result of [`assign_cluster()`] and [`clear_clusters()`].
+///
+/// # Fields
+///
+/// - `sidecars_updated`: Sidecars with at least one changed face
+/// - `faces_updated`: Faces changed across those sidecars
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClusterUpdateResult {
+    pub sidecars_updated: usize,
+    pub faces_updated: usize,
+}
+
+/// Personal data stored about one named person (see [`privacy_report()`]).
+///
+/// # Fields
+///
+/// - `name`: Person name as recorded in the sidecars
+/// - `face_count`: Faces labelled with this name
+/// - `photo_paths`: Photos containing those faces (sorted)
+/// - `first_seen`: Earliest `created_at` of those sidecars
+/// - `last_seen`: Latest `updated_at` of those sidecars
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PersonInventory {
+    pub name: String,
+    pub face_count: usize,
+    pub photo_paths: Vec<String>,
+    pub first_seen: Timestamp,
+    pub last_seen: Timestamp,
+}
+
+/// Inventory of the biometric data stored in a library's sidecars, returned
+/// by [`privacy_report()`].
+///
+/// # Fields
+///
+/// - `person_inventory`: Named persons, keyed by name
+/// - `unknown_faces`: Faces without a person name
+/// - `embedding_hashes`: Faces carrying an embedding hash (named or not)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PrivacyReport {
+    pub person_inventory: HashMap<String, PersonInventory>,
+    pub unknown_faces: usize,
+    pub embedding_hashes: usize,
+}
+
+/// Result of [`delete_person()`].
+///
+/// # Fields
+///
+/// - `sidecars_updated`: Sidecars with at least one face of the person
+/// - `faces_updated`: Faces whose person name was removed
+/// - `backups_scrubbed`: Sidecar backups (`.bak1`-`.bak3`) the name was removed from
+/// - `removed_from_database`: Whether the person was removed from the [`PersonDatabase`]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PersonDeletionResult {
+    pub sidecars_updated: usize,
+    pub faces_updated: usize,
+    pub backups_scrubbed: usize,
+    pub removed_from_database: bool,
+}
+
+/// Models and thresholds for a [`FacePipeline`].
+///
+/// # Fields
+///
+/// - `detect_model`: Face detection model (ONNX)
+/// - `embed_model`: Face embedding model (ONNX, e.g. ArcFace)
+/// - `min_detect_score`: Drop detections scoring below this (0-1)
+/// - `min_identify_score`: Minimum cosine similarity for a person match (0-1)
+/// - `generate_landmarks`: Keep the 5-point landmarks in the results
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FacePipelineConfig {
+    pub detect_model: PathBuf,
+    pub embed_model: PathBuf,
+    pub min_detect_score: f32,
+    pub min_identify_score: f32,
+    pub generate_landmarks: bool,
+}
+
+impl FacePipelineConfig {
+    /// Config for the given models with the default thresholds (0.8 for
+    /// detection, 0.6 for identification) and no landmarks.
+    pub fn new(detect_model: impl Into<PathBuf>, embed_model: impl Into<PathBuf>) -> Self {
+        Self {
+            detect_model: detect_model.into(),
+            embed_model: embed_model.into(),
+            min_detect_score: DEFAULT_MIN_DETECT_SCORE,
+            min_identify_score: DEFAULT_MIN_IDENTIFY_SCORE,
+            generate_landmarks: false,
+        }
+    }
+}
+
+/// Detection and embedding models loaded once, reused across images.
+///
+/// A pipeline is not shared between threads; [`detect_and_process()`] keeps
+/// one per thread.
+#[derive(Debug)]
+pub struct FacePipeline {
+    config: FacePipelineConfig,
+    sessions: (ModelSession, ModelSession),
+}
+
+impl FacePipeline {
+    /// Loads the detection and embedding models of `config`.
+    ///
+    /// # Errors
+    ///
+    /// - `JozinError::UserError` if a threshold is outside 0-1
+    /// - `JozinError::IoError` if a model file cannot be read
+    pub fn new(config: &FacePipelineConfig) -> Result<FacePipeline> {
+        for (name, score) in [
+            ("min_detect_score", config.min_detect_score),
+            ("min_identify_score", config.min_identify_score),
+        ] {
+            if !(0.0..=1.0).contains(&score) {
+                return Err(JozinError::UserError {
+                    message: format!("{} must be between 0 and 1, got {}", name, score),
+                });
+            }
+        }
+        Ok(FacePipeline {
+            config: config.clone(),
+            sessions: (ModelSession::load(&config.detect_model)?, ModelSession::load(&config.embed_model)?),
+        })
+    }
+
+    /// Detects the faces in an image, embeds them and, with a database,
+    /// identifies them.
+    ///
+    /// Each returned face scores at least `min_detect_score`, carries the
+    /// BLAKE3 hash of its embedding, and is named after the closest person in
+    /// `db` whose similarity reaches `min_identify_score`.
+    ///
+    /// # Errors
+    ///
+    /// - `JozinError::IoError` if the image cannot be decoded
+    /// - `JozinError::NotSupported` until an ONNX runtime is available
+    pub fn process(&self, image_path: &Path, db: Option<&PersonDatabase>) -> Result<Vec<FaceDetection>> {
+        let image = image::open(image_path).map_err(|e| JozinError::IoError {
+            message: format!("Failed to decode {}: {}", image_path.display(), e),
+        })?;
+
+        let mut faces = self.sessions.0.detect(&image)?;
+        faces.retain(|face| face.score >= self.config.min_detect_score);
+        for face in &mut faces {
+            let embedding = self.sessions.1.embed(&image, face)?;
+            face.embedding_hash = Some(embedding_hash(&embedding));
+            face.person = db.and_then(|db| identify_embedding(&embedding, db, self.config.min_identify_score));
+            if !self.config.generate_landmarks {
+                face.landmarks = None;
+            }
+        }
+        Ok(faces)
+    }
+}
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+/// Default [`FacePipelineConfig::min_detect_score`].
+const DEFAULT_MIN_DETECT_SCORE: f32 = 0.8;
+
+/// Default [`FacePipelineConfig::min_identify_score`].
+const DEFAULT_MIN_IDENTIFY_SCORE: f32 = 0.6;
+
+/// Background color between and behind face cells.
+const SHEET_BACKGROUND: Rgb<u8> = Rgb([32, 32, 32]);
+
+/// Gap between face cells in pixels.
+const CELL_PADDING: u32 = 4;
+
+/// Smallest face cell that gets a label overlay; smaller cells are too cramped.
+const MIN_LABELED_FACE_SIZE: u32 = 48;
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Renders the faces recorded in sidecars as a grid image for review.
+///
+/// For each [`FaceDetection`] (optionally only those of one person), the face
+/// region is cropped from the source photo after applying its EXIF orientation,
+/// resized to `face_size × face_size`, and placed in a grid of `grid_cols`
+/// columns. Cells of at least 48 px get a label strip with the person name
+/// (or `?` if unidentified) and the detection score.
+///
+/// When there are more than `max_faces` faces, additional pages are written as
+/// `<stem>-page2.<ext>`, `<stem>-page3.<ext>`, ... next to `output_path`.
+/// Faces whose source photo cannot be read are skipped with a warning.
+///
+/// # Arguments
+///
+/// * `sidecars` - Sidecars whose `faces` should be rendered
+/// * `person_filter` - Only include faces of this person (case-insensitive)
+/// * `max_faces` - Maximum faces per page
+/// * `grid_cols` - Number of columns
+/// * `face_size` - Edge length of each face cell in pixels
+/// * `output_path` - Output image (format from extension, e.g. `.jpg`, `.png`)
+///
+/// # Errors
+///
+/// - `JozinError::UserError` if `max_faces`, `grid_cols` or `face_size` is zero
+/// - `JozinError::ValidationError` if no faces match
+/// - `JozinError::IoError` if the sheet cannot be written
+///
+/// # Example
+///
+/// ```no_run
+/// use jozin_core::faces::generate_contact_sheet;
+/// use jozin_core::scan::load_sidecars;
+/// use std::path::Path;
+///
+/// let sidecars = load_sidecars(Path::new("/photos"), true)?;
+/// let result = generate_contact_sheet(&sidecars, None, 100, 10, 128, Path::new("contacts.jpg"))?;
+/// println!("{} faces on {} page(s)", result.total_faces, result.page_count);
+/// # Ok::<(), jozin_core::JozinError>(())
+/// ```
+pub fn generate_contact_sheet(
+    sidecars: &[Sidecar],
+    person_filter: Option<&str>,
+    max_faces: usize,
+    grid_cols: u32,
+    face_size: u32,
+    output_path: &Path,
+) -> Result<ContactSheetResult> {
+    if max_faces == 0 || grid_cols == 0 || face_size == 0 {
+        return Err(JozinError::UserError {
+            message: "max_faces, grid_cols and face_size must be greater than 0".to_string(),
+        });
+    }
+
+    // Render all matching faces, decoding each source photo only once
+    let mut decoded: HashMap<&str, Option<DynamicImage>> = HashMap::new();
+    let mut cells = Vec::new();
+
+    for sidecar in sidecars {
+        let faces: Vec<&FaceDetection> = sidecar
+            .faces
+            .iter()
+            .filter(|face| matches_person(face, person_filter))
+            .collect();
+        if faces.is_empty() {
+            continue;
+        }
+
+        let source = sidecar.source.file_path.as_str();
+        let image = decoded.entry(source).or_insert_with(|| {
+            let orientation = sidecar.image.as_ref().and_then(|i| i.orientation);
+            match image::open(source) {
+                Ok(image) => Some(apply_orientation(image, orientation)),
+                Err(e) => {
+                    eprintln!("Warning: Skipping faces of {}: {}", source, e);
+                    None
+                }
+            }
+        });
+
+        if let Some(image) = image {
+            for face in faces {
+                cells.push(render_face_cell(image, face, face_size));
+            }
+        }
+    }
+
+    if cells.is_empty() {
+        return Err(JozinError::ValidationError {
+            message: match person_filter {
+                Some(person) => format!("No faces found for person '{}'", person),
+                None => "No faces found in the provided sidecars".to_string(),
+            },
+        });
+    }
+
+    let total_faces = cells.len();
+    let mut page_count = 0;
+
+    for (page_index, page_cells) in cells.chunks(max_faces).enumerate() {
+        let sheet = compose_grid(page_cells, grid_cols, face_size);
+        let path = page_path(output_path, page_index);
+        DynamicImage::ImageRgb8(sheet)
+            .save(&path)
+            .map_err(|e| JozinError::IoError {
+                message: format!("Failed to write contact sheet {}: {}", path.display(), e),
+            })?;
+        page_count += 1;
+    }
+
+    Ok(ContactSheetResult {
+        total_faces,
+        output_path: output_path.display().to_string(),
+        page_count,
+    })
+}
+
+/// Computes the affine transform that aligns a face to the ArcFace template.
+///
+/// Least-squares similarity fit (Umeyama) from the landmarks, converted to
+/// pixels of the source image, onto [`ARCFACE_LANDMARK_TEMPLATE`] scaled to
+/// `crop_size`.
+///
+/// # Arguments
+///
+/// * `landmarks` - Normalized landmarks from [`FaceDetection::landmarks`]
+/// * `image_width`, `image_height` - Source image dimensions in pixels
+/// * `crop_size` - Side length of the aligned output crop
+///
+/// # Returns
+///
+/// Row-major 2x3 matrix `[a, -b, tx, b, a, ty]` mapping source pixels to crop
+/// pixels, or `None` if all landmarks coincide (no usable geometry).
+pub fn alignment_transform(
+    landmarks: &[[f32; 2]; 5],
+    image_width: u32,
+    image_height: u32,
+    crop_size: u32,
+) -> Option<[f32; 6]> {
+    let scale = crop_size as f32 / ARCFACE_CROP_SIZE as f32;
+    let src = landmarks.map(|[x, y]| [x * image_width as f32, y * image_height as f32]);
+    let dst = ARCFACE_LANDMARK_TEMPLATE.map(|[x, y]| [x * scale, y * scale]);
+
+    let mean = |points: &[[f32; 2]; 5]| {
+        let (sx, sy) = points.iter().fold((0.0, 0.0), |(sx, sy), [x, y]| (sx + x, sy + y));
+        [sx / 5.0, sy / 5.0]
+    };
+    let (src_mean, dst_mean) = (mean(&src), mean(&dst));
+
+    let (mut dot, mut cross, mut norm) = (0.0f32, 0.0f32, 0.0f32);
+    for (s, d) in src.iter().zip(dst.iter()) {
+        let (sx, sy) = (s[0] - src_mean[0], s[1] - src_mean[1]);
+        let (dx, dy) = (d[0] - dst_mean[0], d[1] - dst_mean[1]);
+        dot += sx * dx + sy * dy;
+        cross += sx * dy - sy * dx;
+        norm += sx * sx + sy * sy;
+    }
+    if norm <= f32::EPSILON {
+        return None;
+    }
+
+    let (a, b) = (dot / norm, cross / norm);
+    let tx = dst_mean[0] - (a * src_mean[0] - b * src_mean[1]);
+    let ty = dst_mean[1] - (b * src_mean[0] + a * src_mean[1]);
+    Some([a, -b, tx, b, a, ty])
+}
+
+/// Writes a person database to a portable file.
+///
+/// # Arguments
+///
+/// * `db` - Database to export
+/// * `output_path` - File to write (overwritten)
+/// * `format` - Encoding (see [`EmbeddingFormat`])
+///
+/// # Errors
+///
+/// - `JozinError::IoError` if the file cannot be written
+/// - `JozinError::InternalError` if encoding fails
+///
+/// # Example
+///
+/// ```no_run
+/// use jozin_core::faces::{export_embeddings, EmbeddingFormat, PersonDatabase};
+/// use std::path::Path;
+///
+/// let db = PersonDatabase::load(Path::new("/photos"))?;
+/// export_embeddings(&db, Path::new("persons.json"), EmbeddingFormat::Json)?;
+/// # Ok::<(), jozin_core::JozinError>(())
+/// ```
+pub fn export_embeddings(db: &PersonDatabase, output_path: &Path, format: EmbeddingFormat) -> Result<()> {
+    fs::write(output_path, encode_database(db, format)?)?;
+    Ok(())
+}
+
+/// Merges an exported person database into `db`.
+///
+/// The file format (binary or JSON) is detected from its contents. `db` is
+/// only modified if the whole file could be read.
+///
+/// # Arguments
+///
+/// * `db` - Database to merge into
+/// * `input_path` - File written by [`export_embeddings()`]
+/// * `merge_strategy` - What to do with persons already in `db`
+///
+/// # Errors
+///
+/// - `JozinError::IoError` if the file cannot be read
+/// - `JozinError::ValidationError` if the file is corrupt, or `Average` meets
+///   embeddings of different lengths (e.g., from another model)
+///
+/// # Example
+///
+/// ```no_run
+/// use jozin_core::faces::{import_embeddings, MergeStrategy, PersonDatabase};
+/// use std::path::Path;
+///
+/// let mut db = PersonDatabase::load(Path::new("/nas/photos"))?;
+/// let result = import_embeddings(&mut db, Path::new("persons.json"), MergeStrategy::Average)?;
+/// db.save(Path::new("/nas/photos"))?;
+/// println!("{} added, {} averaged", result.added, result.averaged);
+/// # Ok::<(), jozin_core::JozinError>(())
+/// ```
+pub fn import_embeddings(
+    db: &mut PersonDatabase,
+    input_path: &Path,
+    merge_strategy: MergeStrategy,
+) -> Result<ImportResult> {
+    let imported = decode_database(&fs::read(input_path)?)?;
+
+    let mut merged = db.clone();
+    let mut result = ImportResult::default();
+    for mut person in imported.persons {
+        person.name = normalise_person_name(&person.name);
+        let Some(existing) = merged.persons.iter_mut().find(|p| p.name == person.name) else {
+            merged.add_person(person);
+            result.added += 1;
+            continue;
+        };
+
+        match merge_strategy {
+            MergeStrategy::Replace => {
+                *existing = person;
+                result.replaced += 1;
+            }
+            MergeStrategy::KeepExisting => result.kept += 1,
+            MergeStrategy::Average => {
+                average_into(existing, &person)?;
+                result.averaged += 1;
+            }
+        }
+    }
+
+    if merged.model.is_none() {
+        merged.model = imported.model;
+    }
+    *db = merged;
+    Ok(result)
+}
+
+/// Whether any face of a sidecar has an estimated age within a range.
+///
+/// Faces without [`FaceDetection::age_estimate`] never match. Both bounds
+/// are inclusive; a missing bound is open.
+///
+/// # Example
+///
+/// ```no_run
+/// use jozin_core::faces::has_face_in_age_range;
+/// use jozin_core::scan::load_sidecars;
+/// use std::path::Path;
+///
+/// let sidecars = load_sidecars(Path::new("/photos"), true)?;
+/// let young_adults = sidecars.iter().filter(|s| has_face_in_age_range(s, Some(18.0), Some(30.0))).count();
+/// # Ok::<(), jozin_core::JozinError>(())
+/// ```
+pub fn has_face_in_age_range(sidecar: &Sidecar, min_age: Option<f32>, max_age: Option<f32>) -> bool {
+    sidecar.faces.iter().filter_map(|face| face.age_estimate).any(|age| {
+        min_age.is_none_or(|min| age >= min) && max_age.is_none_or(|max| age <= max)
+    })
+}
+
+/// Redacts faces in a photo and writes the result to `output_path`.
+///
+/// `faces` are bounding boxes relative to the photo after its EXIF
+/// `orientation` is applied, as recorded in sidecars. The output is written
+/// upright in the format of its extension and carries no EXIF.
+///
+/// # Returns
+///
+/// The number of faces redacted (faces with an empty bounding box are skipped).
+///
+/// # Errors
+///
+/// - `JozinError::UserError` if a pixelate block size is zero
+/// - `JozinError::IoError` if the photo cannot be decoded or the output written
+///
+/// # Example
+///
+/// ```no_run
+/// use jozin_core::faces::{anonymise_faces, AnonymiseMethod};
+/// use jozin_core::scan::read_sidecar;
+/// use std::path::Path;
+///
+/// let photo = Path::new("/photos/IMG_1234.JPG");
+/// let sidecar = read_sidecar(photo)?;
+/// let orientation = sidecar.image.as_ref().and_then(|i| i.orientation);
+/// let method = AnonymiseMethod::Pixelate { block_size: 16 };
+/// anonymise_faces(photo, &sidecar.faces, orientation, method, Path::new("/public/IMG_1234.JPG"))?;
+/// # Ok::<(), jozin_core::JozinError>(())
+/// ```
+pub fn anonymise_faces(
+    image_path: &Path,
+    faces: &[FaceDetection],
+    orientation: Option<u8>,
+    method: AnonymiseMethod,
+    output_path: &Path,
+) -> Result<usize> {
+    if method == (AnonymiseMethod::Pixelate { block_size: 0 }) {
+        return Err(JozinError::UserError {
+            message: "Pixelate block size must be greater than 0".to_string(),
+        });
+    }
+
+    let image = image::open(image_path).map_err(|e| JozinError::IoError {
+        message: format!("Failed to decode {}: {}", image_path.display(), e),
+    })?;
+    let mut image = apply_orientation(image, orientation).to_rgb8();
+
+    let mut redacted = 0;
+    for face in faces {
+        if let Some(region) = face_region(&image, face) {
+            redact_region(&mut image, region, method);
+            redacted += 1;
+        }
+    }
+
+    image.save(output_path).map_err(|e| JozinError::IoError {
+        message: format!("Failed to write {}: {}", output_path.display(), e),
+    })?;
+    Ok(redacted)
+}
+
+/// Mirrors a photo library with all recorded faces redacted.
+///
+/// Every image under `src_root` is written to the same relative path under
+/// `dst_root`: photos whose sidecar records faces via [`anonymise_faces()`],
+/// all others copied byte for byte. Each sidecar is copied next to its image
+/// with `faces` emptied and `source.file_path` pointing at the copy; the
+/// library's sidecar naming is carried over. Other files are not copied.
+///
+/// Images are processed on up to `max_threads` threads; progress events are
+/// delivered on the calling thread. Images that fail are reported as
+/// warnings and counted in [`AnonymiseResult::failed`].
+///
+/// # Errors
+///
+/// - `JozinError::UserError` if `src_root` is not a directory, `dst_root` is
+///   inside `src_root`, or the pixelate block size is zero
+/// - `JozinError::IoError` if `dst_root` cannot be created
+///
+/// # Example
+///
+/// ```no_run
+/// use jozin_core::faces::{anonymise_batch, AnonymiseMethod};
+/// use std::path::Path;
+///
+/// let method = AnonymiseMethod::Pixelate { block_size: 20 };
+/// let result = anonymise_batch(Path::new("/photos"), Path::new("/public"), method, true, 4, None)?;
+/// println!("{} faces redacted in {} photos", result.faces_redacted, result.anonymised);
+/// # Ok::<(), jozin_core::JozinError>(())
+/// ```
+pub fn anonymise_batch(
+    src_root: &Path,
+    dst_root: &Path,
+    method: AnonymiseMethod,
+    recursive: bool,
+    max_threads: u16,
+    progress_callback: Option<&dyn Fn(ProgressEvent)>,
+) -> Result<AnonymiseResult> {
+    if !src_root.is_dir() {
+        return Err(JozinError::UserError {
+            message: format!("Source is not a directory: {}", src_root.display()),
+        });
+    }
+    if method == (AnonymiseMethod::Pixelate { block_size: 0 }) {
+        return Err(JozinError::UserError {
+            message: "Pixelate block size must be greater than 0".to_string(),
+        });
+    }
+    fs::create_dir_all(dst_root)?;
+    let (src_root, dst_root) = (src_root.canonicalize()?, dst_root.canonicalize()?);
+    if dst_root.starts_with(&src_root) {
+        return Err(JozinError::UserError {
+            message: format!("Destination must not be inside the source: {}", dst_root.display()),
+        });
+    }
+
+    let naming = LibraryConfig::discover(&src_root);
+    if !naming.is_default_naming() {
+        naming.save(&dst_root)?;
+    }
+
+    let mut images = Vec::new();
+    for entry in walker(&src_root, recursive, SymlinkStrategy::Follow) {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(e) => {
+                eprintln!("Warning: Failed to access entry: {}", e);
+                continue;
+            }
+        };
+        let path = entry.path();
+        if path.is_file() && is_image_file(path) && !naming.is_sidecar(path) {
+            images.push(path.to_path_buf());
+        }
+    }
+
+    if let Some(callback) = progress_callback {
+        callback(ProgressEvent::BatchStarted {
+            total_files: images.len(),
+            total_bytes: images.iter().filter_map(|path| fs::metadata(path).ok()).map(|m| m.len()).sum(),
+            estimated_duration_ms: None,
+            traversal_order: crate::util::traversal_order::TraversalOrder::DepthFirst.to_string(),
+        });
+    }
+
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(usize::from(max_threads.max(1)))
+        .build()
+        .map_err(|e| JozinError::InternalError {
+            message: format!("Failed to start anonymise thread pool: {}", e),
+        })?;
+    let (sender, receiver) = mpsc::channel();
+    let anonymise_one = |sender: &mut mpsc::Sender<ProgressEvent>, image: &PathBuf| {
+        let _ = sender.send(ProgressEvent::FileStarted { path: image.display().to_string() });
+        let outcome = anonymise_library_image(image, &src_root, &dst_root, &naming, method);
+        let _ = sender.send(ProgressEvent::FileCompleted {
+            path: image.display().to_string(),
+            success: outcome.is_ok(),
+            error: outcome.as_ref().err().map(|e| e.to_string()),
+            size_bytes: None,
+        });
+        outcome
+    };
+    let outcomes = thread::scope(|scope| {
+        let worker = scope.spawn(|| pool.install(|| images.par_iter().map_with(sender, anonymise_one).collect::<Vec<_>>()));
+        // Ends once the workers have dropped every sender
+        for event in receiver {
+            if let Some(callback) = progress_callback {
+                callback(event);
+            }
+        }
+        worker.join()
+    })
+    .map_err(|_| JozinError::InternalError {
+        message: "Anonymise worker panicked".to_string(),
+    })?;
+
+    let mut result = AnonymiseResult {
+        processed: images.len(),
+        ..AnonymiseResult::default()
+    };
+    for (image, outcome) in images.iter().zip(outcomes) {
+        match outcome {
+            Ok(Some(faces)) => {
+                result.anonymised += 1;
+                result.faces_redacted += faces;
+            }
+            Ok(None) => result.copied_unchanged += 1,
+            Err(e) => {
+                eprintln!("Warning: Failed to anonymise {}: {}", image.display(), e);
+                result.failed += 1;
+            }
+        }
+    }
+    if let Some(callback) = progress_callback {
+        callback(ProgressEvent::BatchCompleted {
+            successful: result.anonymised + result.copied_unchanged,
+            failed: result.failed,
+            skipped: 0,
+        });
+    }
+    Ok(result)
+}
+
+/// Names a face cluster: sets `person` on every face with `cluster_id` in
+/// the sidecars under `root`.
+///
+/// # Arguments
+///
+/// * `root` - Library directory (or a single image or sidecar)
+/// * `recursive` - Include subdirectories
+/// * `cluster_id` - Cluster to name (see [`FaceDetection::cluster_id`])
+/// * `person` - Person name to assign (normalised, see [`normalise_person_name()`])
+/// * `dry_run` - Count the faces without writing sidecars
+///
+/// # Errors
+///
+/// - `JozinError::IoError` if `root` does not exist or a sidecar cannot be written
+/// - `JozinError::ValidationError` if a sidecar cannot be parsed
+///
+/// # Example
+///
+/// ```no_run
+/// use jozin_core::faces::assign_cluster;
+/// use std::path::Path;
+///
+/// let result = assign_cluster(Path::new("/photos"), true, "5f0c6d1e-8a3b-4c2d-9e7f-1a2b3c4d5e6f", "Alice", false)?;
+/// println!("Named {} faces", result.faces_updated);
+/// # Ok::<(), jozin_core::JozinError>(())
+/// ```
+pub fn assign_cluster(
+    root: &Path,
+    recursive: bool,
+    cluster_id: &str,
+    person: &str,
+    dry_run: bool,
+) -> Result<ClusterUpdateResult> {
+    let person = normalise_person_name(person);
+    let person = person.as_str();
+    update_faces(root, recursive, dry_run, |face| {
+        if face.cluster_id.as_deref() != Some(cluster_id) || face.person.as_deref() == Some(person) {
+            return false;
+        }
+        face.person = Some(person.to_string());
+        true
+    })
+}
+
+/// Removes the cluster assignment of every face in the sidecars under `root`,
+/// e.g. before re-clustering with a different threshold. Person names are
+/// kept.
+///
+/// # Errors
+///
+/// Same as [`assign_cluster()`].
+pub fn clear_clusters(root: &Path, recursive: bool, dry_run: bool) -> Result<ClusterUpdateResult> {
+    update_faces(root, recursive, dry_run, |face| face.cluster_id.take().is_some())
+}
+
+/// Lists the personal data recorded about faces in the sidecars under `root`:
+/// every named person with the photos they appear in, plus the number of
+/// unnamed faces and stored embedding hashes. Meant as a data inventory for
+/// privacy requests (GDPR/CCPA); [`delete_person()`] erases a person.
+///
+/// # Arguments
+///
+/// * `root` - Library root (or a single directory)
+/// * `recursive` - Include subdirectories
+///
+/// # Errors
+///
+/// - `JozinError::IoError` if `root` does not exist or a sidecar cannot be read
+/// - `JozinError::ValidationError` if a sidecar cannot be parsed
+///
+/// # Example
+///
+/// ```no_run
+/// use jozin_core::faces::privacy_report;
+/// use std::path::Path;
+///
+/// let report = privacy_report(Path::new("/photos"), true)?;
+/// for person in report.person_inventory.values() {
+///     println!("{}: {} faces in {} photos", person.name, person.face_count, person.photo_paths.len());
+/// }
+/// # Ok::<(), jozin_core::JozinError>(())
+/// ```
+pub fn privacy_report(root: &Path, recursive: bool) -> Result<PrivacyReport> {
+    let naming = LibraryConfig::discover(root);
+    let mut report = PrivacyReport::default();
+    for sidecar_path in find_sidecar_files_with(root, recursive, &naming, SymlinkStrategy::default())? {
+        let sidecar = parse_sidecar(&read_sidecar_file(&sidecar_path)?)?;
+        let photo_path = naming.image_path(&sidecar_path).unwrap_or(sidecar_path).display().to_string();
+        for face in &sidecar.faces {
+            if face.embedding_hash.is_some() {
+                report.embedding_hashes += 1;
+            }
+            let Some(name) = &face.person else {
+                report.unknown_faces += 1;
+                continue;
+            };
+            let person = report.person_inventory.entry(name.clone()).or_insert_with(|| PersonInventory {
+                name: name.clone(),
+                first_seen: sidecar.created_at.clone(),
+                last_seen: sidecar.updated_at.clone(),
+                ..PersonInventory::default()
+            });
+            person.face_count += 1;
+            if !person.photo_paths.contains(&photo_path) {
+                person.photo_paths.push(photo_path.clone());
+            }
+            // RFC 3339 UTC timestamps order lexicographically
+            if sidecar.created_at < person.first_seen {
+                person.first_seen = sidecar.created_at.clone();
+            }
+            if sidecar.updated_at > person.last_seen {
+                person.last_seen = sidecar.updated_at.clone();
+            }
+        }
+    }
+    for person in report.person_inventory.values_mut() {
+        person.photo_paths.sort();
+    }
+    Ok(report)
+}
+
+/// Erases a person from the library ("right to erasure"): removes the name
+/// from every face labelled with it, from the sidecar backups (`.bak1`-`.bak3`,
+/// which would otherwise keep the name) and from the library's
+/// [`PersonDatabase`]. The faces themselves are kept as unknown faces.
+///
+/// # Arguments
+///
+/// * `root` - Library root (its [`PersonDatabase`] is updated)
+/// * `recursive` - Include subdirectories
+/// * `person` - Person name (normalised, see [`normalise_person_name()`])
+/// * `dry_run` - Count what would be erased without writing files
+///
+/// # Errors
+///
+/// - `JozinError::IoError` if `root` does not exist or a file cannot be written
+/// - `JozinError::ValidationError` if a sidecar or the person database cannot be parsed
+///
+/// # Example
+///
+/// ```no_run
+/// use jozin_core::faces::delete_person;
+/// use std::path::Path;
+///
+/// let result = delete_person(Path::new("/photos"), true, "Alice", false)?;
+/// println!("Erased {} faces", result.faces_updated);
+/// # Ok::<(), jozin_core::JozinError>(())
+/// ```
+pub fn delete_person(root: &Path, recursive: bool, person: &str, dry_run: bool) -> Result<PersonDeletionResult> {
+    let person = normalise_person_name(person);
+    let updated = update_faces(root, recursive, dry_run, |face| {
+        if face.person.as_deref() != Some(person.as_str()) {
+            return false;
+        }
+        face.person = None;
+        true
+    })?;
+
+    // Backups are scrubbed after the update, which rotated the named sidecars into them
+    let naming = LibraryConfig::discover(root);
+    let mut backups_scrubbed = 0;
+    for sidecar_path in find_sidecar_files_with(root, recursive, &naming, SymlinkStrategy::default())? {
+        for k in 1..=3 {
+            let backup_path = crate::scan::append_to_file_name(&sidecar_path, &format!(".bak{}", k));
+            let compressed = is_compressed_path(&sidecar_path);
+            if backup_path.is_file() && scrub_person_from_backup(&backup_path, compressed, &person, dry_run)? {
+                backups_scrubbed += 1;
+            }
+        }
+    }
+
+    let mut db = PersonDatabase::load(root)?;
+    let removed_from_database = db.remove_person(&person).is_some();
+    if removed_from_database && !dry_run {
+        db.save(root)?;
+    }
+
+    Ok(PersonDeletionResult {
+        sidecars_updated: updated.sidecars_updated,
+        faces_updated: updated.faces_updated,
+        backups_scrubbed,
+        removed_from_database,
+    })
+}
+
+/// Detects, embeds and identifies the faces in an image.
+///
+/// The models are loaded on first use in each thread and the [`FacePipeline`]
+/// is kept for later calls with the same config, so a parallel scan loads
+/// them once per rayon thread rather than once per image.
+///
+/// # Arguments
+///
+/// * `image_path` - Image to process
+/// * `config` - Models and thresholds
+/// * `db` - Known persons to identify faces against (optional)
+///
+/// # Errors
+///
+/// See [`FacePipeline::new()`] and [`FacePipeline::process()`].
+///
+/// # Example
+///
+/// ```no_run
+/// use jozin_core::faces::{detect_and_process, FacePipelineConfig, PersonDatabase};
+/// use std::path::Path;
+///
+/// let db = PersonDatabase::load(Path::new("/photos"))?;
+/// let config = FacePipelineConfig::new("models/retinaface.onnx", "models/arcface.onnx");
+/// for face in detect_and_process(Path::new("/photos/IMG_1234.JPG"), config, Some(&db))? {
+///     println!("{:?} {:.2}", face.person, face.score);
+/// }
+/// # Ok::<(), jozin_core::JozinError>(())
+/// ```
+pub fn detect_and_process(
+    image_path: &Path,
+    config: FacePipelineConfig,
+    db: Option<&PersonDatabase>,
+) -> Result<Vec<FaceDetection>> {
+    thread_local! {
+        static PIPELINE: RefCell<Option<FacePipeline>> = const { RefCell::new(None) };
+    }
+
+    PIPELINE.with(|cell| {
+        let mut pipeline = cell.borrow_mut();
+        if pipeline.as_ref().is_none_or(|p| p.config != config) {
+            *pipeline = Some(FacePipeline::new(&config)?);
+        }
+        pipeline.as_ref().expect("pipeline initialised above").process(image_path, db)
+    })
+}
+
+// ============================================================================
+// Internal Helpers
+// ============================================================================
+
+/// A loaded ONNX model.
+///
+/// Only the model file is checked for now; inference returns
+/// `JozinError::NotSupported` until an ONNX runtime is bundled.
+#[derive(Debug)]
+struct ModelSession {
+    model_path: PathBuf,
+}
+
+impl ModelSession {
+    fn load(model_path: &Path) -> Result<Self> {
+        let metadata = fs::metadata(model_path).map_err(|e| JozinError::IoError {
+            message: format!("Failed to load model {}: {}", model_path.display(), e),
+        })?;
+        if !metadata.is_file() {
+            return Err(JozinError::IoError {
+                message: format!("Failed to load model {}: not a file", model_path.display()),
+            });
+        }
+        Ok(Self { model_path: model_path.to_path_buf() })
+    }
+
+    /// Runs face detection on `image`.
+    fn detect(&self, image: &DynamicImage) -> Result<Vec<FaceDetection>> {
+        let _ = image;
+        Err(self.not_supported("face detection"))
+    }
+
+    /// Computes the embedding of the face cropped from `image`.
+    fn embed(&self, image: &DynamicImage, face: &FaceDetection) -> Result<Vec<f32>> {
+        let _ = (image, face);
+        Err(self.not_supported("face embedding"))
+    }
+
+    fn not_supported(&self, task: &str) -> JozinError {
+        JozinError::NotSupported {
+            feature: format!("{} with {} (no ONNX runtime available yet)", task, self.model_path.display()),
+        }
+    }
+}
+
+/// BLAKE3 hash of an embedding's little-endian `f32`s, for
+/// [`FaceDetection::embedding_hash`].
+fn embedding_hash(embedding: &[f32]) -> String {
+    let mut hasher = blake3::Hasher::new();
+    for value in embedding {
+        hasher.update(&value.to_le_bytes());
+    }
+    hasher.finalize().to_hex().to_string()
+}
+
+/// Name of the person in `db` most similar to `embedding` (cosine
+/// similarity), if it reaches `min_score`.
+fn identify_embedding(embedding: &[f32], db: &PersonDatabase, min_score: f32) -> Option<String> {
+    let norm = |v: &[f32]| v.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let embedding_norm = norm(embedding);
+    db.persons
+        .iter()
+        .filter(|person| person.embedding.len() == embedding.len())
+        .filter_map(|person| {
+            let denominator = embedding_norm * norm(&person.embedding);
+            if denominator == 0.0 {
+                return None;
+            }
+            let dot: f32 = embedding.iter().zip(&person.embedding).map(|(a, b)| a * b).sum();
+            Some((dot / denominator, person))
+        })
+        .filter(|(similarity, _)| *similarity >= min_score)
+        .max_by(|(a, _), (b, _)| a.total_cmp(b))
+        .map(|(_, person)| person.name.clone())
+}
+
+/// Applies `update` to every face of the sidecars under `root` and writes
+/// back the sidecars where it returned `true` for any face.
+fn update_faces(
+    root: &Path,
+    recursive: bool,
+    dry_run: bool,
+    update: impl Fn(&mut FaceDetection) -> bool,
+) -> Result<ClusterUpdateResult> {
+    let naming = LibraryConfig::discover(root);
+    let mut result = ClusterUpdateResult::default();
+    for sidecar_path in find_sidecar_files_with(root, recursive, &naming, SymlinkStrategy::default())? {
+        let mut sidecar = parse_sidecar(&read_sidecar_file(&sidecar_path)?)?;
+        let updated = sidecar.faces.iter_mut().map(&update).filter(|changed| *changed).count();
+        if updated == 0 {
+            continue;
+        }
+        result.sidecars_updated += 1;
+        result.faces_updated += updated;
+        if !dry_run {
+            crate::scan::write_sidecar_json(&sidecar_path, &serde_json::to_string_pretty(&sidecar)?, FsyncPolicy::Always)?;
+        }
+    }
+    Ok(result)
+}
+
+/// Removes `person` from the faces of a sidecar backup (gzipped if
+/// `compressed`). Backups may predate the current schema, so they are edited
+/// as plain JSON. Returns whether the backup contained the name.
+fn scrub_person_from_backup(backup_path: &Path, compressed: bool, person: &str, dry_run: bool) -> Result<bool> {
+    let mut backup: serde_json::Value = serde_json::from_str(&read_sidecar_file(backup_path)?)?;
+    let Some(faces) = backup.get_mut("faces").and_then(serde_json::Value::as_array_mut) else {
+        return Ok(false);
+    };
+    let mut found = false;
+    for face in faces {
+        let Some(face) = face.as_object_mut() else { continue };
+        if face.get("person").and_then(serde_json::Value::as_str) == Some(person) {
+            face.remove("person");
+            found = true;
+        }
+    }
+    if found && !dry_run {
+        fs::write(backup_path, encode_sidecar(&serde_json::to_string_pretty(&backup)?, compressed)?)?;
+    }
+    Ok(found)
+}
+
+/// Writes one image of [`anonymise_batch()`] and its sidecar to the
+/// destination library.
+///
+/// Returns the number of redacted faces, or `None` if the image was copied
+/// unchanged.
+fn anonymise_library_image(
+    image: &Path,
+    src_root: &Path,
+    dst_root: &Path,
+    naming: &LibraryConfig,
+    method: AnonymiseMethod,
+) -> Result<Option<usize>> {
+    let relative = image.strip_prefix(src_root).map_err(|e| JozinError::InternalError {
+        message: e.to_string(),
+    })?;
+    let output = dst_root.join(relative);
+    if let Some(parent) = output.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let sidecar_path = existing_sidecar_path(&naming.sidecar_path(image));
+    let mut sidecar = if sidecar_path.is_file() {
+        Some(crate::scan::parse_sidecar(&read_sidecar_file(&sidecar_path)?)?)
+    } else {
+        None
+    };
+
+    let redacted = match sidecar.as_mut() {
+        Some(sidecar) if !sidecar.faces.is_empty() => {
+            let orientation = sidecar.image.as_ref().and_then(|i| i.orientation);
+            let redacted = anonymise_faces(image, &sidecar.faces, orientation, method, &output)?;
+            // The copy is written upright
+            if let Some(info) = sidecar.image.as_mut().filter(|info| info.orientation.is_some()) {
+                info.orientation = Some(1);
+            }
+            Some(redacted)
+        }
+        _ => {
+            fs::copy(image, &output)?;
+            None
+        }
+    };
+
+    if let Some(mut sidecar) = sidecar {
+        sidecar.faces.clear();
+        sidecar.source.file_path = normalise_sidecar_path(&output.display().to_string(), dst_root);
+        let json = serde_json::to_string_pretty(&sidecar)?;
+        crate::scan::write_sidecar_json(&naming.sidecar_path(&output), &json, FsyncPolicy::Always)?;
+    }
+    Ok(redacted)
+}
+
+/// Pixel rectangle `(x, y, width, height)` of a face, clamped to the image;
+/// `None` if it is empty.
+fn face_region(image: &RgbImage, face: &FaceDetection) -> Option<(u32, u32, u32, u32)> {
+    let (width, height) = (image.width() as f32, image.height() as f32);
+    let [bx, by, bw, bh] = face.bbox;
+    let x0 = (bx.clamp(0.0, 1.0) * width).floor() as u32;
+    let y0 = (by.clamp(0.0, 1.0) * height).floor() as u32;
+    let x1 = ((bx + bw).clamp(0.0, 1.0) * width).ceil() as u32;
+    let y1 = ((by + bh).clamp(0.0, 1.0) * height).ceil() as u32;
+    (x1 > x0 && y1 > y0).then_some((x0, y0, x1 - x0, y1 - y0))
+}
+
+/// Applies an anonymisation method to a pixel rectangle.
+fn redact_region(image: &mut RgbImage, (x, y, width, height): (u32, u32, u32, u32), method: AnonymiseMethod) {
+    match method {
+        AnonymiseMethod::Fill => {
+            for py in y..y + height {
+                for px in x..x + width {
+                    image.put_pixel(px, py, Rgb([0, 0, 0]));
+                }
+            }
+        }
+        AnonymiseMethod::Blur { sigma } => {
+            let region = image::imageops::crop_imm(image, x, y, width, height).to_image();
+            let blurred = image::imageops::blur(&region, sigma);
+            image::imageops::replace(image, &blurred, i64::from(x), i64::from(y));
+        }
+        AnonymiseMethod::Pixelate { block_size } => {
+            for block_y in (y..y + height).step_by(block_size as usize) {
+                for block_x in (x..x + width).step_by(block_size as usize) {
+                    let block_w = block_size.min(x + width - block_x);
+                    let block_h = block_size.min(y + height - block_y);
+                    let mut sum = [0u64; 3];
+                    for py in block_y..block_y + block_h {
+                        for px in block_x..block_x + block_w {
+                            for (total, channel) in sum.iter_mut().zip(image.get_pixel(px, py).0) {
+                                *total += u64::from(channel);
+                            }
+                        }
+                    }
+                    let count = u64::from(block_w * block_h);
+                    let average = Rgb(sum.map(|total| (total / count) as u8));
+                    for py in block_y..block_y + block_h {
+                        for px in block_x..block_x + block_w {
+                            image.put_pixel(px, py, average);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// JSON export layout: embeddings as base64 strings instead of float arrays.
+#[derive(Serialize, Deserialize)]
+struct JsonPersonDatabase {
+    model: Option<String>,
+    persons: Vec<JsonPersonEmbedding>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct JsonPersonEmbedding {
+    name: String,
+    /// Base64 of the embedding as little-endian `f32`s
+    embedding: String,
+    sample_count: u32,
+}
+
+fn encode_database(db: &PersonDatabase, format: EmbeddingFormat) -> Result<Vec<u8>> {
+    match format {
+        EmbeddingFormat::Binary => bincode::serialize(db).map_err(|e| JozinError::InternalError {
+            message: format!("Failed to encode person database: {}", e),
+        }),
+        EmbeddingFormat::Json => {
+            let json = JsonPersonDatabase {
+                model: db.model.clone(),
+                persons: db
+                    .persons
+                    .iter()
+                    .map(|person| JsonPersonEmbedding {
+                        name: person.name.clone(),
+                        embedding: base64::engine::general_purpose::STANDARD.encode(
+                            person.embedding.iter().flat_map(|v| v.to_le_bytes()).collect::<Vec<u8>>(),
+                        ),
+                        sample_count: person.sample_count,
+                    })
+                    .collect(),
+            };
+            Ok(serde_json::to_vec_pretty(&json)?)
+        }
+    }
+}
+
+/// Decodes either format; JSON files start with `{`.
+fn decode_database(data: &[u8]) -> Result<PersonDatabase> {
+    let invalid = |e: String| JozinError::ValidationError {
+        message: format!("Invalid person database: {}", e),
+    };
+
+    if data.iter().find(|b| !b.is_ascii_whitespace()) != Some(&b'{') {
+        return bincode::deserialize(data).map_err(|e| invalid(e.to_string()));
+    }
+
+    let json: JsonPersonDatabase = serde_json::from_slice(data).map_err(|e| invalid(e.to_string()))?;
+    let persons = json
+        .persons
+        .into_iter()
+        .map(|person| {
+            let bytes = base64::engine::general_purpose::STANDARD
+                .decode(&person.embedding)
+                .map_err(|e| invalid(format!("{}: {}", person.name, e)))?;
+            if bytes.len() % 4 != 0 {
+                return Err(invalid(format!("{}: embedding is not a list of f32", person.name)));
+            }
+            Ok(PersonEmbedding {
+                name: person.name,
+                embedding: bytes
+                    .chunks_exact(4)
+                    .map(|c| f32::from_le_bytes([c[0], c[1], c[2], c[3]]))
+                    .collect(),
+                sample_count: person.sample_count,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(PersonDatabase { model: json.model, persons })
+}
+
+/// Averages `other` into `existing`, weighted by sample counts.
+fn average_into(existing: &mut PersonEmbedding, other: &PersonEmbedding) -> Result<()> {
+    if existing.embedding.len() != other.embedding.len() {
+        return Err(JozinError::ValidationError {
+            message: format!(
+                "Cannot average embeddings of {} ({} vs {} dimensions)",
+                existing.name,
+                existing.embedding.len(),
+                other.embedding.len()
+            ),
+        });
+    }
+
+    let (a, b) = (existing.sample_count.max(1) as f32, other.sample_count.max(1) as f32);
+    for (value, other_value) in existing.embedding.iter_mut().zip(&other.embedding) {
+        *value = (*value * a + other_value * b) / (a + b);
+    }
+    existing.sample_count = existing.sample_count.max(1) + other.sample_count.max(1);
+    Ok(())
+}
+
+fn matches_person(face: &FaceDetection, person_filter: Option<&str>) -> bool {
+    match person_filter {
+        None => true,
+        Some(filter) => face
+            .person
+            .as_deref()
+            .is_some_and(|person| person.eq_ignore_ascii_case(filter)),
+    }
+}
+
+/// Crops one face (normalized bbox) and renders it as a square labeled cell.
+fn render_face_cell(image: &DynamicImage, face: &FaceDetection, face_size: u32) -> RgbImage {
+    let (width, height) = (image.width() as f32, image.height() as f32);
+    let [bx, by, bw, bh] = face.bbox.map(|v| v.clamp(0.0, 1.0));
+
+    let x = (bx * width) as u32;
+    let y = (by * height) as u32;
+    let w = ((bw * width) as u32).clamp(1, image.width().saturating_sub(x).max(1));
+    let h = ((bh * height) as u32).clamp(1, image.height().saturating_sub(y).max(1));
+
+    let mut cell = image
+        .crop_imm(x.min(image.width().saturating_sub(1)), y.min(image.height().saturating_sub(1)), w, h)
+        .resize_exact(face_size, face_size, image::imageops::FilterType::Triangle)
+        .to_rgb8();
+
+    if face_size >= MIN_LABELED_FACE_SIZE {
+        let label = format!(
+            "{} {:.2}",
+            face.person.as_deref().unwrap_or("?"),
+            face.score
+        );
+        draw_label(&mut cell, &label);
+    }
+
+    cell
+}
+
+/// Arranges equally sized cells in a grid with padding.
+fn compose_grid(cells: &[RgbImage], grid_cols: u32, face_size: u32) -> RgbImage {
+    let cols = grid_cols.min(cells.len() as u32).max(1);
+    let rows = (cells.len() as u32).div_ceil(cols);
+    let stride = face_size + CELL_PADDING;
+
+    let mut sheet = RgbImage::from_pixel(
+        cols * stride + CELL_PADDING,
+        rows * stride + CELL_PADDING,
+        SHEET_BACKGROUND,
+    );
+
+    for (i, cell) in cells.iter().enumerate() {
+        let col = i as u32 % cols;
+        let row = i as u32 / cols;
+        image::imageops::replace(
+            &mut sheet,
+            cell,
+            (CELL_PADDING + col * stride) as i64,
+            (CELL_PADDING + row * stride) as i64,
+        );
+    }
+
+    sheet
+}
+
+/// Returns the output path for a page: the given path for the first page,
+/// `<stem>-page<N>.<ext>` for later ones.
+fn page_path(output_path: &Path, page_index: usize) -> PathBuf {
+    if page_index == 0 {
+        return output_path.to_path_buf();
+    }
+    let stem = output_path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| "contacts".to_string());
+    let name = match output_path.extension() {
+        Some(ext) => format!("{}-page{}.{}", stem, page_index + 1, ext.to_string_lossy()),
+        None => format!("{}-page{}", stem, page_index + 1),
+    };
+    output_path.with_file_name(name)
+}
+
+// ============================================================================
+// Label Rendering
+// ============================================================================
+
+/// Draws `text` in a dark strip along the bottom of the cell using a built-in
+/// 3×5 pixel font (uppercase letters, digits, `.`, `-`, `?`), scaled 2×.
+fn draw_label(cell: &mut RgbImage, text: &str) {
+    const SCALE: u32 = 2;
+    const GLYPH_W: u32 = 3;
+    const GLYPH_H: u32 = 5;
+    const STRIP_H: u32 = GLYPH_H * SCALE + 4;
+
+    let (width, height) = cell.dimensions();
+    if height < STRIP_H {
+        return;
+    }
+
+    // Darken the strip so text stays readable on any face
+    for y in height - STRIP_H..height {
+        for x in 0..width {
+            let p = cell.get_pixel_mut(x, y);
+            p.0 = p.0.map(|c| c / 4);
+        }
+    }
+
+    let advance = (GLYPH_W + 1) * SCALE;
+    let max_chars = ((width.saturating_sub(4)) / advance) as usize;
+    let top = height - STRIP_H + 2;
+
+    for (i, ch) in text.chars().take(max_chars).enumerate() {
+        let glyph = glyph_bits(ch);
+        let left = 2 + i as u32 * advance;
+        for row in 0..GLYPH_H {
+            for col in 0..GLYPH_W {
+                let bit = 14 - (row * GLYPH_W + col);
+                if glyph & (1 << bit) == 0 {
+                    continue;
+                }
+                for dy in 0..SCALE {
+                    for dx in 0..SCALE {
+                        cell.put_pixel(left + col * SCALE + dx, top + row * SCALE + dy, Rgb([255, 255, 255]));
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Returns the 3×5 bitmap (15 bits, row-major, MSB first) for a character.
+fn glyph_bits(ch: char) -> u16 {
+    match ch.to_ascii_uppercase() {
+        '0' => 0b111_101_101_101_111,
+        '1' => 0b010_110_010_010_111,
+        '2' => 0b111_001_111_100_111,
+        '3' => 0b111_001_111_001_111,
+        '4' => 0b101_101_111_001_001,
+        '5' => 0b111_100_111_001_111,
+        '6' => 0b111_100_111_101_111,
+        '7' => 0b111_001_001_001_001,
+        '8' => 0b111_101_111_101_111,
+        '9' => 0b111_101_111_001_111,
+        'A' => 0b010_101_111_101_101,
+        'B' => 0b110_101_110_101_110,
+        'C' => 0b011_100_100_100_011,
+        'D' => 0b110_101_101_101_110,
+        'E' => 0b111_100_110_100_111,
+        'F' => 0b111_100_110_100_100,
+        'G' => 0b011_100_101_101_011,
+        'H' => 0b101_101_111_101_101,
+        'I' => 0b111_010_010_010_111,
+        'J' => 0b001_001_001_101_010,
+        'K' => 0b101_101_110_101_101,
+        'L' => 0b100_100_100_100_111,
+        'M' => 0b101_111_111_101_101,
+        'N' => 0b110_101_101_101_101,
+        'O' => 0b010_101_101_101_010,
+        'P' => 0b110_101_110_100_100,
+        'Q' => 0b010_101_101_110_011,
+        'R' => 0b110_101_110_101_101,
+        'S' => 0b011_100_010_001_110,
+        'T' => 0b111_010_010_010_010,
+        'U' => 0b101_101_101_101_111,
+        'V' => 0b101_101_101_101_010,
+        'W' => 0b101_101_111_111_101,
+        'X' => 0b101_101_010_101_101,
+        'Y' => 0b101_101_010_010_010,
+        'Z' => 0b111_001_010_100_111,
+        '.' => 0b000_000_000_000_010,
+        '-' => 0b000_000_111_000_000,
+        ' ' => 0,
+        _ => 0b111_001_011_000_010, // '?'
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scan::scan_file;
+    use tempfile::TempDir;
+
+    fn face(bbox: [f32; 4], person: Option<&str>) -> FaceDetection {
+        FaceDetection {
+            bbox,
+            score: 0.9,
+            embedding_hash: None,
+            person: person.map(String::from),
+            landmarks: None,
+            age_estimate: None,
+            cluster_id: None,
+        }
+    }
+
+    fn sidecar_with_faces(dir: &Path, faces: Vec<FaceDetection>) -> Sidecar {
+        let photo = dir.join("group.png");
+        DynamicImage::new_rgb8(200, 100).save(&photo).unwrap();
+        let mut sidecar = scan_file(&photo, true).unwrap().sidecar;
+        sidecar.faces = faces;
+        sidecar
+    }
+
+    #[test]
+    fn test_generate_contact_sheet_grid_and_pages() {
+        let temp_dir = TempDir::new().unwrap();
+        let sidecar = sidecar_with_faces(
+            temp_dir.path(),
+            vec![
+                face([0.0, 0.0, 0.5, 0.5], Some("Alice")),
+                face([0.5, 0.5, 0.5, 0.5], Some("Bob")),
+                face([0.9, 0.9, 0.5, 0.5], None), // extends past the edge
+            ],
+        );
+        let output = temp_dir.path().join("contacts.png");
+
+        let result = generate_contact_sheet(&[sidecar], None, 2, 2, 64, &output).unwrap();
+        assert_eq!(result.total_faces, 3);
+        assert_eq!(result.page_count, 2);
+
+        let first = image::open(&output).unwrap();
+        assert_eq!(first.width(), 2 * (64 + CELL_PADDING) + CELL_PADDING);
+        assert!(temp_dir.path().join("contacts-page2.png").exists());
+    }
+
+    #[test]
+    fn test_generate_contact_sheet_person_filter() {
+        let temp_dir = TempDir::new().unwrap();
+        let sidecar = sidecar_with_faces(
+            temp_dir.path(),
+            vec![face([0.0, 0.0, 0.5, 0.5], Some("Alice")), face([0.5, 0.0, 0.5, 0.5], None)],
+        );
+        let output = temp_dir.path().join("alice.png");
+
+        let result =
+            generate_contact_sheet(std::slice::from_ref(&sidecar), Some("alice"), 10, 4, 32, &output).unwrap();
+        assert_eq!(result.total_faces, 1);
+
+        let missing = generate_contact_sheet(&[sidecar], Some("Carol"), 10, 4, 32, &output);
+        assert!(matches!(missing, Err(JozinError::ValidationError { .. })));
+    }
+
+    #[test]
+    fn test_alignment_transform_maps_landmarks_onto_template() {
+        // Template itself, placed in a 224x224 image at twice the scale
+        let landmarks = ARCFACE_LANDMARK_TEMPLATE.map(|[x, y]| [x / 112.0, y / 112.0]);
+        let m = alignment_transform(&landmarks, 224, 224, 112).unwrap();
+
+        for ([x, y], [tx, ty]) in landmarks.iter().zip(ARCFACE_LANDMARK_TEMPLATE.iter()) {
+            let (px, py) = (x * 224.0, y * 224.0);
+            let mapped = [m[0] * px + m[1] * py + m[2], m[3] * px + m[4] * py + m[5]];
+            assert!((mapped[0] - tx).abs() < 1e-3 && (mapped[1] - ty).abs() < 1e-3);
+        }
+        assert!((m[0] - 0.5).abs() < 1e-4 && m[3].abs() < 1e-4);
+
+        assert!(alignment_transform(&[[0.5, 0.5]; 5], 100, 100, 112).is_none());
+    }
+
+    fn person(name: &str, embedding: Vec<f32>, sample_count: u32) -> PersonEmbedding {
+        PersonEmbedding { name: name.to_string(), embedding, sample_count }
+    }
+
+    #[test]
+    fn test_export_import_embeddings_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let db = PersonDatabase {
+            model: Some("arcface-1.4".to_string()),
+            persons: vec![person("Alice", vec![0.5, -1.25, 3.0], 4), person("Bob", vec![1.0, 0.0, 0.0], 1)],
+        };
+
+        for file in ["persons.json", "persons.bin"] {
+            let path = temp_dir.path().join(file);
+            export_embeddings(&db, &path, EmbeddingFormat::from_path(&path)).unwrap();
+
+            let mut imported = PersonDatabase::default();
+            let result = import_embeddings(&mut imported, &path, MergeStrategy::KeepExisting).unwrap();
+            assert_eq!(result.added, 2);
+            assert_eq!(imported, db);
+        }
+
+        let json = fs::read_to_string(temp_dir.path().join("persons.json")).unwrap();
+        assert!(json.contains("\"embedding\": \""), "embeddings are base64 strings");
+
+        db.save(temp_dir.path()).unwrap();
+        assert_eq!(PersonDatabase::load(temp_dir.path()).unwrap(), db);
+    }
+
+    #[test]
+    fn test_add_person_normalises_name() {
+        let person = |name: &str, sample_count| PersonEmbedding { name: name.to_string(), embedding: vec![1.0], sample_count };
+        let mut db = PersonDatabase::default();
+        assert_eq!(db.add_person(person(" Zoe\u{308}  Smith", 1)), None);
+        assert_eq!(db.persons[0].name, "Zoë Smith");
+        let replaced = db.add_person(person("Zoë Smith", 2)).unwrap();
+        assert_eq!((replaced.sample_count, db.persons.len(), db.persons[0].sample_count), (1, 1, 2));
+    }
+
+    #[test]
+    fn test_import_embeddings_merge_strategies() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("persons.json");
+        let incoming = PersonDatabase {
+            model: None,
+            persons: vec![person("Alice", vec![3.0, 3.0], 1), person("Carol", vec![1.0, 1.0], 1)],
+        };
+        export_embeddings(&incoming, &path, EmbeddingFormat::Json).unwrap();
+        let existing = PersonDatabase { model: None, persons: vec![person("Alice", vec![0.0, 0.0], 2)] };
+
+        let mut db = existing.clone();
+        let result = import_embeddings(&mut db, &path, MergeStrategy::KeepExisting).unwrap();
+        assert_eq!((result.added, result.kept), (1, 1));
+        assert_eq!(db.persons[0].embedding, vec![0.0, 0.0]);
+
+        let mut db = existing.clone();
+        import_embeddings(&mut db, &path, MergeStrategy::Replace).unwrap();
+        assert_eq!(db.persons[0].embedding, vec![3.0, 3.0]);
+
+        let mut db = existing.clone();
+        let result = import_embeddings(&mut db, &path, MergeStrategy::Average).unwrap();
+        assert_eq!(result.averaged, 1);
+        assert_eq!(db.persons[0].embedding, vec![1.0, 1.0]);
+        assert_eq!(db.persons[0].sample_count, 3);
+
+        // Mismatched dimensions leave the database untouched
+        let mut db = PersonDatabase { model: None, persons: vec![person("Alice", vec![0.0], 1)] };
+        let before = db.clone();
+        assert!(import_embeddings(&mut db, &path, MergeStrategy::Average).is_err());
+        assert_eq!(db, before);
+    }
+
+    #[test]
+    fn test_has_face_in_age_range() {
+        let temp_dir = TempDir::new().unwrap();
+        let aged = |age| FaceDetection { age_estimate: age, ..face([0.0, 0.0, 0.5, 0.5], None) };
+        let sidecar = sidecar_with_faces(temp_dir.path(), vec![aged(None), aged(Some(24.5)), aged(Some(61.0))]);
+
+        assert!(has_face_in_age_range(&sidecar, Some(18.0), Some(30.0)));
+        assert!(has_face_in_age_range(&sidecar, Some(60.0), None));
+        assert!(!has_face_in_age_range(&sidecar, Some(31.0), Some(59.0)));
+        assert!(!has_face_in_age_range(&sidecar_with_faces(temp_dir.path(), vec![aged(None)]), None, None));
+    }
+
+    #[test]
+    fn test_redact_region_methods() {
+        let mut image = RgbImage::from_fn(4, 2, |x, _| Rgb([x as u8 * 10, 0, 0]));
+        redact_region(&mut image, (0, 0, 4, 2), AnonymiseMethod::Pixelate { block_size: 2 });
+        assert_eq!(image.get_pixel(0, 1), &Rgb([5, 0, 0]));
+        assert_eq!(image.get_pixel(3, 0), &Rgb([25, 0, 0]));
+
+        redact_region(&mut image, (1, 0, 2, 1), AnonymiseMethod::Fill);
+        assert_eq!(image.get_pixel(1, 0), &Rgb([0, 0, 0]));
+        assert_eq!(image.get_pixel(0, 0), &Rgb([5, 0, 0]));
+
+        assert_eq!(face_region(&image, &face([0.5, 0.5, 0.0, 0.5], None)), None);
+        assert_eq!(face_region(&image, &face([0.75, 0.0, 0.5, 1.0], None)), Some((3, 0, 1, 2)));
+    }
+
+    #[test]
+    fn test_anonymise_batch() {
+        let temp_dir = TempDir::new().unwrap();
+        let (src, dst) = (temp_dir.path().join("photos"), temp_dir.path().join("public"));
+        fs::create_dir_all(src.join("album")).unwrap();
+
+        let with_faces = src.join("album/group.png");
+        RgbImage::from_pixel(20, 10, Rgb([200, 200, 200])).save(&with_faces).unwrap();
+        let mut sidecar = scan_file(&with_faces, false).unwrap().sidecar;
+        sidecar.faces = vec![face([0.0, 0.0, 0.5, 1.0], Some("Alice")), face([0.5, 0.0, 0.5, 1.0], None)];
+        crate::scan::write_sidecar(&with_faces, &sidecar).unwrap();
+        let without_faces = src.join("landscape.png");
+        RgbImage::from_pixel(8, 8, Rgb([0, 100, 0])).save(&without_faces).unwrap();
+        scan_file(&without_faces, false).unwrap();
+
+        let result = anonymise_batch(&src, &dst, AnonymiseMethod::Fill, true, 2, None).unwrap();
+        assert_eq!(
+            result,
+            AnonymiseResult { processed: 2, anonymised: 1, faces_redacted: 2, copied_unchanged: 1, failed: 0 }
+        );
+
+        let redacted = image::open(dst.join("album/group.png")).unwrap().to_rgb8();
+        assert_eq!(redacted.get_pixel(15, 5), &Rgb([0, 0, 0]));
+        assert_eq!(fs::read(dst.join("landscape.png")).unwrap(), fs::read(&without_faces).unwrap());
+
+        let copied = crate::scan::read_sidecar(&dst.join("album/group.png")).unwrap();
+        assert!(copied.faces.is_empty());
+        assert!(copied.source.file_path.contains("public"));
+        assert!(!fs::read_to_string(dst.join("album/group.png.json")).unwrap().contains("Alice"));
+        // The source library is untouched
+        assert_eq!(crate::scan::read_sidecar(&with_faces).unwrap().faces.len(), 2);
+
+        let nested = anonymise_batch(&src, &src.join("out"), AnonymiseMethod::Fill, true, 1, None);
+        assert!(matches!(nested, Err(JozinError::UserError { .. })));
+    }
+
+    #[test]
+    fn test_assign_and_clear_clusters() {
+        let temp_dir = TempDir::new().unwrap();
+        let clustered = |cluster: &str| FaceDetection { cluster_id: Some(cluster.to_string()), ..face([0.0; 4], None) };
+        for (name, faces) in [
+            ("a.png", vec![clustered("c1"), clustered("c2")]),
+            ("b.png", vec![clustered("c1"), face([0.0; 4], Some("Bob"))]),
+        ] {
+            let photo = temp_dir.path().join(name);
+            RgbImage::new(4, 4).save(&photo).unwrap();
+            let mut sidecar = scan_file(&photo, false).unwrap().sidecar;
+            sidecar.faces = faces;
+            crate::scan::write_sidecar(&photo, &sidecar).unwrap();
+        }
+
+        let dry = assign_cluster(temp_dir.path(), false, "c1", "Alice", true).unwrap();
+        assert_eq!(dry, ClusterUpdateResult { sidecars_updated: 2, faces_updated: 2 });
+        assert_eq!(crate::scan::read_sidecar(&temp_dir.path().join("a.png")).unwrap().faces[0].person, None);
+
+        assign_cluster(temp_dir.path(), false, "c1", "Alice", false).unwrap();
+        let a = crate::scan::read_sidecar(&temp_dir.path().join("a.png")).unwrap();
+        assert_eq!(a.faces[0].person.as_deref(), Some("Alice"));
+        assert_eq!(a.faces[1].person, None);
+        // Already named: nothing to do
+        assert_eq!(assign_cluster(temp_dir.path(), false, "c1", "Alice", false).unwrap().faces_updated, 0);
+
+        let cleared = clear_clusters(temp_dir.path(), false, false).unwrap();
+        assert_eq!(cleared, ClusterUpdateResult { sidecars_updated: 2, faces_updated: 3 });
+        let b = crate::scan::read_sidecar(&temp_dir.path().join("b.png")).unwrap();
+        assert!(b.faces.iter().all(|face| face.cluster_id.is_none()));
+        assert_eq!(b.faces[0].person.as_deref(), Some("Alice"));
+    }
+
+    #[test]
+    fn test_privacy_report_and_delete_person() {
+        let temp_dir = TempDir::new().unwrap();
+        let hashed = |person: Option<&str>| FaceDetection { embedding_hash: Some("ab12".to_string()), ..face([0.0; 4], person) };
+        for (name, faces) in [
+            ("a.png", vec![face([0.0; 4], Some("Alice")), hashed(Some("Bob"))]),
+            ("b.png", vec![hashed(Some("Alice")), face([0.0; 4], None)]),
+            ("c.png", vec![face([0.0; 4], None)]),
+        ] {
+            let photo = temp_dir.path().join(name);
+            RgbImage::new(4, 4).save(&photo).unwrap();
+            let mut sidecar = scan_file(&photo, false).unwrap().sidecar;
+            sidecar.faces = faces;
+            crate::scan::write_sidecar(&photo, &sidecar).unwrap();
+        }
+        let mut db = PersonDatabase::default();
+        for name in ["Alice", "Bob"] {
+            db.add_person(PersonEmbedding { name: name.to_string(), embedding: vec![1.0, 0.0], sample_count: 1 });
+        }
+        db.save(temp_dir.path()).unwrap();
+
+        let report = privacy_report(temp_dir.path(), false).unwrap();
+        assert_eq!((report.unknown_faces, report.embedding_hashes), (2, 2));
+        let alice = &report.person_inventory["Alice"];
+        assert_eq!(alice.face_count, 2);
+        let photos: Vec<_> = alice.photo_paths.iter().map(|path| Path::new(path).file_name().unwrap()).collect();
+        assert_eq!(photos, ["a.png", "b.png"]);
+        assert!(alice.first_seen <= alice.last_seen);
+        assert_eq!(report.person_inventory["Bob"].photo_paths.len(), 1);
+
+        let dry = delete_person(temp_dir.path(), false, "Alice", true).unwrap();
+        assert_eq!((dry.sidecars_updated, dry.faces_updated), (2, 2));
+        assert!(privacy_report(temp_dir.path(), false).unwrap().person_inventory.contains_key("Alice"));
+
+        let result = delete_person(temp_dir.path(), false, "Alice", false).unwrap();
+        assert_eq!((result.sidecars_updated, result.faces_updated), (2, 2));
+        assert!(result.backups_scrubbed >= 2);
+        assert!(result.removed_from_database);
+
+        // No trace of the name in sidecars, backups or the person database
+        let report = privacy_report(temp_dir.path(), false).unwrap();
+        assert!(!report.person_inventory.contains_key("Alice"));
+        assert_eq!(report.unknown_faces, 4);
+        for entry in walkdir::WalkDir::new(temp_dir.path()).into_iter().filter_map(|entry| entry.ok()) {
+            if entry.file_type().is_file() {
+                let bytes = fs::read(entry.path()).unwrap();
+                assert!(!bytes.windows(5).any(|window| window == b"Alice"), "{}", entry.path().display());
+            }
+        }
+        let db = PersonDatabase::load(temp_dir.path()).unwrap();
+        assert_eq!(db.persons.iter().map(|p| p.name.as_str()).collect::<Vec<_>>(), ["Bob"]);
+        let a = crate::scan::read_sidecar(&temp_dir.path().join("a.png")).unwrap();
+        assert_eq!(a.faces[1].person.as_deref(), Some("Bob"));
+    }
+
+    #[test]
+    fn test_face_pipeline_loads_models_once() {
+        let temp_dir = TempDir::new().unwrap();
+        let detect_model = temp_dir.path().join("detect.onnx");
+        let embed_model = temp_dir.path().join("embed.onnx");
+        fs::write(&detect_model, b"onnx").unwrap();
+        let photo = temp_dir.path().join("photo.png");
+        RgbImage::from_pixel(4, 4, Rgb([200, 150, 120])).save(&photo).unwrap();
+
+        let config = FacePipelineConfig::new(&detect_model, &embed_model);
+        assert!(matches!(FacePipeline::new(&config), Err(JozinError::IoError { .. })));
+        fs::write(&embed_model, b"onnx").unwrap();
+        let invalid = FacePipelineConfig { min_detect_score: 1.5, ..config.clone() };
+        assert!(matches!(FacePipeline::new(&invalid), Err(JozinError::UserError { .. })));
+
+        let pipeline = FacePipeline::new(&config).unwrap();
+        assert!(matches!(pipeline.process(&temp_dir.path().join("missing.png"), None), Err(JozinError::IoError { .. })));
+        assert!(matches!(pipeline.process(&photo, None), Err(JozinError::NotSupported { .. })));
+        assert!(matches!(detect_and_process(&photo, config, None), Err(JozinError::NotSupported { .. })));
+    }
+
+    #[test]
+    fn test_identify_embedding_picks_closest_person() {
+        let mut db = PersonDatabase::default();
+        for (name, embedding) in [("Alice", vec![1.0, 0.0, 0.0]), ("Bob", vec![0.0, 1.0, 0.0])] {
+            db.add_person(PersonEmbedding { name: name.to_string(), embedding, sample_count: 1 });
+        }
+
+        assert_eq!(identify_embedding(&[0.9, 0.1, 0.0], &db, 0.6), Some("Alice".to_string()));
+        assert_eq!(identify_embedding(&[0.2, 0.8, 0.1], &db, 0.6), Some("Bob".to_string()));
+        assert_eq!(identify_embedding(&[0.0, 0.0, 1.0], &db, 0.6), None);
+        assert_eq!(identify_embedding(&[1.0, 0.0], &db, 0.0), None);
+        assert_eq!(embedding_hash(&[1.0, 0.0]).len(), 64);
+    }
+}
@@ -0,0 +1,221 @@
+//! Hash module - BLAKE3 file hashing
+//!
+//! This module computes the content hashes stored in `SourceInfo::file_hash_b3`.
+//!
+//! ## Strategies
+//!
+//! - [`compute_blake3_hash()`] - Sequential streaming hash (8 KiB buffer)
+//! - [`compute_hash_parallel()`] - Hashes large files in chunks, using all CPU
+//!   cores per chunk when the `parallel-hash` feature is enabled
+//!
+//! Both produce identical output for the same input; the parallel variant only
+//! changes how fast the hash is computed on large files (e.g., 100 MB RAW).
+//!
+//! ## Feature Flags
+//!
+//! - `parallel-hash` - Enables `blake3/rayon` so each chunk is hashed with
+//!   `Hasher::update_rayon`. Without it, chunks are hashed on the calling thread.
+
+use crate::util::io_budget::{IoBudget, RateLimitedReader};
+use crate::Result;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+/// Default chunk size for [`compute_hash_parallel()`] (4 MiB).
+///
+/// Files smaller than this are hashed sequentially; thread pool overhead would
+/// outweigh the gain.
+pub const DEFAULT_PARALLEL_CHUNK_BYTES: u64 = 4 * 1024 * 1024;
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Computes BLAKE3 hash of a file.
+///
+/// Reads the entire file and computes its hash using the BLAKE3 algorithm.
+/// Returns the hash as a lowercase hexadecimal string.
+///
+/// # Errors
+///
+/// Returns `JozinError::IoError` if the file cannot be read.
+pub fn compute_blake3_hash(file_path: &Path) -> Result<String> {
+    blake3_from_reader(File::open(file_path)?)
+}
+
+/// Computes BLAKE3 hash of a file, reading at most `budget` bytes per second.
+///
+/// Same result as [`compute_blake3_hash()`]; used by `scan` when an I/O
+/// limit is set.
+///
+/// # Errors
+///
+/// Returns `JozinError::IoError` if the file cannot be read.
+pub fn compute_blake3_hash_limited(file_path: &Path, budget: IoBudget) -> Result<String> {
+    blake3_from_reader(RateLimitedReader::new(File::open(file_path)?, budget))
+}
+
+/// Computes BLAKE3 hash of a file, parallelising within large chunks.
+///
+/// The file is read in chunks of `chunk_size_bytes` and each chunk is fed to
+/// `blake3::Hasher::update_rayon`, which splits it across the rayon thread pool.
+/// Files smaller than `chunk_size_bytes` fall back to [`compute_blake3_hash()`].
+///
+/// Without the `parallel-hash` feature, chunks are hashed on the calling thread.
+///
+/// # Arguments
+///
+/// * `file_path` - File to hash
+/// * `chunk_size_bytes` - Read size per chunk (e.g., [`DEFAULT_PARALLEL_CHUNK_BYTES`])
+///
+/// # Returns
+///
+/// Lowercase hexadecimal BLAKE3 hash, identical to [`compute_blake3_hash()`].
+///
+/// # Errors
+///
+/// Returns `JozinError::IoError` if the file cannot be read.
+///
+/// # Example
+///
+/// ```no_run
+/// use jozin_core::hash::{compute_hash_parallel, DEFAULT_PARALLEL_CHUNK_BYTES};
+/// use std::path::Path;
+///
+/// let hash = compute_hash_parallel(Path::new("/photos/IMG_1234.CR2"), DEFAULT_PARALLEL_CHUNK_BYTES)?;
+/// println!("BLAKE3: {}", hash);
+/// # Ok::<(), jozin_core::JozinError>(())
+/// ```
+pub fn compute_hash_parallel(file_path: &Path, chunk_size_bytes: u64) -> Result<String> {
+    let chunk_size = chunk_size_bytes.max(1);
+    let file_size = std::fs::metadata(file_path)?.len();
+
+    if file_size < chunk_size {
+        return compute_blake3_hash(file_path);
+    }
+
+    let mut file = File::open(file_path)?;
+    let mut hasher = blake3::Hasher::new();
+    let mut buffer = vec![0u8; chunk_size as usize];
+
+    loop {
+        let filled = read_full(&mut file, &mut buffer)?;
+        if filled == 0 {
+            break;
+        }
+        update_chunk(&mut hasher, &buffer[..filled]);
+        if filled < buffer.len() {
+            break;
+        }
+    }
+
+    Ok(hasher.finalize().to_hex().to_string())
+}
+
+// ============================================================================
+// Internal Helpers
+// ============================================================================
+
+/// Hashes everything `reader` yields with BLAKE3.
+fn blake3_from_reader<R: Read>(mut reader: R) -> Result<String> {
+    let mut hasher = blake3::Hasher::new();
+    let mut buffer = [0u8; 8192];
+
+    loop {
+        let bytes_read = reader.read(&mut buffer)?;
+        if bytes_read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..bytes_read]);
+    }
+
+    let hash = hasher.finalize();
+    Ok(hash.to_hex().to_string())
+}
+
+/// Fills `buffer` as far as possible, returning the number of bytes read.
+///
+/// Unlike a single `read`, this only returns a short count at end of file.
+fn read_full(file: &mut File, buffer: &mut [u8]) -> Result<usize> {
+    let mut filled = 0;
+    while filled < buffer.len() {
+        let n = file.read(&mut buffer[filled..])?;
+        if n == 0 {
+            break;
+        }
+        filled += n;
+    }
+    Ok(filled)
+}
+
+#[cfg(feature = "parallel-hash")]
+fn update_chunk(hasher: &mut blake3::Hasher, chunk: &[u8]) {
+    hasher.update_rayon(chunk);
+}
+
+#[cfg(not(feature = "parallel-hash"))]
+fn update_chunk(hasher: &mut blake3::Hasher, chunk: &[u8]) {
+    hasher.update(chunk);
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+    use tempfile::TempDir;
+
+    fn write_temp(dir: &TempDir, data: &[u8]) -> std::path::PathBuf {
+        let path = dir.path().join("data.bin");
+        std::fs::write(&path, data).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_compute_blake3_hash_known_value() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = write_temp(&temp_dir, b"");
+        assert_eq!(
+            compute_blake3_hash(&path).unwrap(),
+            blake3::hash(b"").to_hex().to_string()
+        );
+    }
+
+    #[test]
+    fn test_compute_hash_parallel_multi_chunk() {
+        let temp_dir = TempDir::new().unwrap();
+        let data: Vec<u8> = (0..100_000u32).map(|i| (i % 251) as u8).collect();
+        let path = write_temp(&temp_dir, &data);
+
+        // 100_000 bytes in 4 KiB chunks: several full chunks plus a partial one
+        assert_eq!(
+            compute_hash_parallel(&path, 4096).unwrap(),
+            compute_blake3_hash(&path).unwrap()
+        );
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(32))]
+
+        #[test]
+        fn prop_parallel_matches_sequential(
+            data in proptest::collection::vec(any::<u8>(), 0..20_000),
+            chunk in 1u64..8192,
+        ) {
+            let temp_dir = TempDir::new().unwrap();
+            let path = write_temp(&temp_dir, &data);
+            prop_assert_eq!(
+                compute_hash_parallel(&path, chunk).unwrap(),
+                compute_blake3_hash(&path).unwrap()
+            );
+        }
+    }
+}
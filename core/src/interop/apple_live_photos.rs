@@ -0,0 +1,564 @@
+//! Apple Live Photo pairing
+//!
+//! An iPhone Live Photo is a still image (`IMG_1234.HEIC` or `.JPG`) plus a
+//! short QuickTime video (`IMG_1234.MOV`). Both carry the same content
+//! identifier UUID: in the still image's Apple maker note and in the video's
+//! `com.apple.quicktime.content.identifier` metadata key.
+//!
+//! ## Detection Strategy
+//!
+//! 1. **Stem matching** - a still and a `.MOV` with the same file stem in the
+//!    same directory form a pair. If both files carry a content identifier and
+//!    the identifiers differ, the pair is rejected.
+//! 2. **UUID matching** - remaining stills and videos in the same directory are
+//!    paired by identical content identifier (handles renamed files).
+//!
+//! Content identifiers are read from where Apple stores them: the
+//! `ContentIdentifier` tag (`0x0011`) of the `Apple iOS` maker note in the
+//! still image's EXIF, and the `moov/meta` `keys`/`ilst` atoms of the video.
+//! Only atom headers are read on the way to `moov`, so the media data of a
+//! large video is skipped, not scanned.
+
+use crate::{JozinError, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+/// Extensions of Live Photo still images (lowercase).
+const STILL_EXTENSIONS: &[&str] = &["heic", "heif", "jpg", "jpeg"];
+
+/// Extensions of Live Photo motion videos (lowercase).
+const MOTION_EXTENSIONS: &[&str] = &["mov"];
+
+/// QuickTime metadata key of the Live Photo content identifier.
+const CONTENT_IDENTIFIER_KEY: &[u8] = b"com.apple.quicktime.content.identifier";
+
+/// Header of the Apple maker note; the IFD follows at [`APPLE_MAKER_NOTE_IFD`].
+const APPLE_MAKER_NOTE_HEADER: &[u8] = b"Apple iOS\0";
+
+/// Offset of the maker note IFD (after the header, a version and `MM`/`II`).
+const APPLE_MAKER_NOTE_IFD: usize = 14;
+
+/// Maker note tag holding the content identifier.
+const APPLE_CONTENT_IDENTIFIER_TAG: u16 = 0x0011;
+
+/// `keys`/`ilst` atoms are a few KB; larger ones are not read.
+const MAX_METADATA_ATOM_BYTES: u64 = 1024 * 1024;
+
+// ============================================================================
+// Public Types
+// ============================================================================
+
+/// A still image and its Live Photo motion video.
+///
+/// # Fields
+///
+/// - `still_path`: The still image (`.HEIC`/`.JPG`)
+/// - `motion_path`: The paired video (`.MOV`)
+/// - `uuid`: Shared Apple content identifier, if found in both files
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LivePhotoPair {
+    pub still_path: PathBuf,
+    pub motion_path: PathBuf,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uuid: Option<String>,
+}
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Finds Live Photo pairs in a directory.
+///
+/// # Arguments
+///
+/// * `dir` - Directory to search
+/// * `recursive` - Also search subdirectories (pairs never span directories)
+///
+/// # Returns
+///
+/// Pairs sorted by still image path.
+///
+/// # Errors
+///
+/// Returns `JozinError::IoError` if `dir` is not a directory.
+///
+/// # Example
+///
+/// ```no_run
+/// use jozin_core::interop::apple_live_photos::find_live_photo_pairs;
+/// use std::path::Path;
+///
+/// for pair in find_live_photo_pairs(Path::new("/photos/iPhone"), true)? {
+///     println!("{} <-> {}", pair.still_path.display(), pair.motion_path.display());
+/// }
+/// # Ok::<(), jozin_core::JozinError>(())
+/// ```
+pub fn find_live_photo_pairs(dir: &Path, recursive: bool) -> Result<Vec<LivePhotoPair>> {
+    if !dir.is_dir() {
+        return Err(JozinError::IoError {
+            message: format!("Directory not found: {}", dir.display()),
+        });
+    }
+
+    let walker = if recursive {
+        WalkDir::new(dir)
+    } else {
+        WalkDir::new(dir).max_depth(1)
+    };
+
+    // Group candidates by parent directory
+    let mut stills: HashMap<PathBuf, Vec<PathBuf>> = HashMap::new();
+    let mut motions: HashMap<PathBuf, Vec<PathBuf>> = HashMap::new();
+    for entry in walker.into_iter().filter_map(|e| e.ok()) {
+        let path = entry.path();
+        if !path.is_file() {
+            continue;
+        }
+        let parent = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        if has_extension(path, STILL_EXTENSIONS) {
+            stills.entry(parent).or_default().push(path.to_path_buf());
+        } else if has_extension(path, MOTION_EXTENSIONS) {
+            motions.entry(parent).or_default().push(path.to_path_buf());
+        }
+    }
+
+    let mut pairs = Vec::new();
+    for (parent, dir_stills) in stills {
+        if let Some(dir_motions) = motions.remove(&parent) {
+            pairs.extend(pair_directory(dir_stills, dir_motions));
+        }
+    }
+
+    pairs.sort_by(|a, b| a.still_path.cmp(&b.still_path));
+    Ok(pairs)
+}
+
+/// Returns the Live Photo video for a still image, if there is one.
+///
+/// Looks for a `.MOV`/`.mov` next to `still_path` with the same stem and
+/// rejects it if both files carry differing content identifiers. Used by
+/// `scan --link-live-photos` to fill `SourceInfo::live_photo_path`.
+pub fn find_motion_companion(still_path: &Path) -> Option<PathBuf> {
+    if !has_extension(still_path, STILL_EXTENSIONS) {
+        return None;
+    }
+
+    ["MOV", "mov"]
+        .iter()
+        .map(|ext| still_path.with_extension(ext))
+        .find(|candidate| candidate.is_file())
+        .filter(|motion| {
+            !matches!(
+                (read_content_identifier(still_path), read_content_identifier(motion)),
+                (Some(a), Some(b)) if a != b
+            )
+        })
+}
+
+/// Reads the Apple content identifier (UUID) of a Live Photo file.
+///
+/// `.MOV` files are read from the QuickTime `com.apple.quicktime.content.identifier`
+/// metadata key, still images from the Apple maker note `ContentIdentifier`.
+/// Returns the UUID uppercased, or `None` if the file has none or cannot be
+/// read.
+pub fn read_content_identifier(path: &Path) -> Option<String> {
+    let mut reader = BufReader::new(File::open(path).ok()?);
+    if has_extension(path, MOTION_EXTENSIONS) {
+        quicktime_content_identifier(&mut reader)
+    } else {
+        let exif = exif::Reader::new().read_from_container(&mut reader).ok()?;
+        match &exif.get_field(exif::Tag::MakerNote, exif::In::PRIMARY)?.value {
+            exif::Value::Undefined(note, _) => maker_note_content_identifier(note),
+            _ => None,
+        }
+    }
+}
+
+// ============================================================================
+// Internal Helpers
+// ============================================================================
+
+/// Pairs stills and videos of one directory: by stem first, then by UUID.
+fn pair_directory(stills: Vec<PathBuf>, motions: Vec<PathBuf>) -> Vec<LivePhotoPair> {
+    let mut pairs = Vec::new();
+    let mut unpaired_stills = Vec::new();
+    let mut motions: Vec<Option<PathBuf>> = motions.into_iter().map(Some).collect();
+
+    // 1. Stem matching (case-insensitive)
+    for still in stills {
+        let stem = stem_lower(&still);
+        let slot = motions
+            .iter_mut()
+            .find(|m| m.as_ref().is_some_and(|m| stem_lower(m) == stem));
+
+        match slot {
+            Some(slot) => {
+                let motion = slot.as_ref().unwrap();
+                let still_uuid = read_content_identifier(&still);
+                let motion_uuid = read_content_identifier(motion);
+                match (still_uuid, motion_uuid) {
+                    (Some(a), Some(b)) if a != b => unpaired_stills.push(still),
+                    (Some(a), Some(_)) => pairs.push(LivePhotoPair {
+                        still_path: still,
+                        motion_path: slot.take().unwrap(),
+                        uuid: Some(a),
+                    }),
+                    _ => pairs.push(LivePhotoPair {
+                        still_path: still,
+                        motion_path: slot.take().unwrap(),
+                        uuid: None,
+                    }),
+                }
+            }
+            None => unpaired_stills.push(still),
+        }
+    }
+
+    // 2. UUID matching for the rest
+    let mut motion_uuids: HashMap<String, PathBuf> = motions
+        .into_iter()
+        .flatten()
+        .filter_map(|m| read_content_identifier(&m).map(|uuid| (uuid, m)))
+        .collect();
+
+    for still in unpaired_stills {
+        if let Some(uuid) = read_content_identifier(&still) {
+            if let Some(motion) = motion_uuids.remove(&uuid) {
+                pairs.push(LivePhotoPair {
+                    still_path: still,
+                    motion_path: motion,
+                    uuid: Some(uuid),
+                });
+            }
+        }
+    }
+
+    pairs
+}
+
+fn has_extension(path: &Path, extensions: &[&str]) -> bool {
+    path.extension()
+        .map(|ext| extensions.contains(&ext.to_string_lossy().to_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+fn stem_lower(path: &Path) -> String {
+    path.file_stem()
+        .map(|s| s.to_string_lossy().to_lowercase())
+        .unwrap_or_default()
+}
+
+/// Reads the content identifier from the `moov/meta` atom of a QuickTime file.
+fn quicktime_content_identifier<R: Read + Seek>(reader: &mut R) -> Option<String> {
+    let file_end = reader.seek(SeekFrom::End(0)).ok()?;
+    let moov = find_atom(reader, 0..file_end, b"moov")?;
+    let mut meta = find_atom(reader, moov, b"meta")?;
+
+    // QuickTime `meta` holds atoms directly; the ISO variant has version and
+    // flags (zero) first
+    reader.seek(SeekFrom::Start(meta.start)).ok()?;
+    let mut first = [0u8; 4];
+    reader.read_exact(&mut first).ok()?;
+    if first == [0; 4] {
+        meta.start += 4;
+    }
+
+    let keys = find_atom(reader, meta.clone(), b"keys")?;
+    let keys = read_atom_payload(reader, keys)?;
+    let ilst = find_atom(reader, meta, b"ilst")?;
+    let ilst = read_atom_payload(reader, ilst)?;
+
+    // `keys`: version/flags and entry count, then one `[size][namespace][name]`
+    // entry per key; `ilst` items are named by the 1-based key index
+    let index = atoms(keys.get(8..)?)
+        .position(|(_, name)| name == CONTENT_IDENTIFIER_KEY)
+        .map(|i| i as u32 + 1)?;
+    let (_, item) = atoms(&ilst).find(|(kind, _)| u32::from_be_bytes(*kind) == index)?;
+
+    // `data`: type and locale, then the UTF-8 value
+    let (_, data) = atoms(item).find(|(kind, _)| kind == b"data")?;
+    uuid_from_bytes(data.get(8..)?)
+}
+
+/// Returns the payload range of the first `kind` atom in `range`.
+fn find_atom<R: Read + Seek>(reader: &mut R, range: Range<u64>, kind: &[u8; 4]) -> Option<Range<u64>> {
+    let mut pos = range.start;
+    while pos + 8 <= range.end {
+        reader.seek(SeekFrom::Start(pos)).ok()?;
+        let mut header = [0u8; 8];
+        reader.read_exact(&mut header).ok()?;
+        let (header_len, size) = match u32::from_be_bytes(header[..4].try_into().unwrap()) {
+            // 64-bit size follows the type
+            1 => {
+                let mut large = [0u8; 8];
+                reader.read_exact(&mut large).ok()?;
+                (16, u64::from_be_bytes(large))
+            }
+            // Extends to the end of the enclosing atom
+            0 => (8, range.end - pos),
+            size => (8, size as u64),
+        };
+        if size < header_len || pos + size > range.end {
+            return None;
+        }
+        if &header[4..] == kind {
+            return Some(pos + header_len..pos + size);
+        }
+        pos += size;
+    }
+    None
+}
+
+/// Reads an atom payload of at most [`MAX_METADATA_ATOM_BYTES`].
+fn read_atom_payload<R: Read + Seek>(reader: &mut R, range: Range<u64>) -> Option<Vec<u8>> {
+    let len = range.end - range.start;
+    if len > MAX_METADATA_ATOM_BYTES {
+        return None;
+    }
+    reader.seek(SeekFrom::Start(range.start)).ok()?;
+    let mut payload = vec![0u8; len as usize];
+    reader.read_exact(&mut payload).ok()?;
+    Some(payload)
+}
+
+/// Iterates the `(type, payload)` of consecutive 32-bit sized atoms in `data`.
+fn atoms(data: &[u8]) -> impl Iterator<Item = ([u8; 4], &[u8])> {
+    let mut rest = data;
+    std::iter::from_fn(move || {
+        let size = u32::from_be_bytes(rest.get(..4)?.try_into().ok()?) as usize;
+        if size < 8 || size > rest.len() {
+            return None;
+        }
+        let kind = rest[4..8].try_into().ok()?;
+        let payload = &rest[8..size];
+        rest = &rest[size..];
+        Some((kind, payload))
+    })
+}
+
+/// Reads the `ContentIdentifier` tag of an Apple maker note.
+///
+/// The maker note is a TIFF IFD after a 14-byte header; value offsets are
+/// relative to the start of the maker note.
+fn maker_note_content_identifier(note: &[u8]) -> Option<String> {
+    if !note.starts_with(APPLE_MAKER_NOTE_HEADER) {
+        return None;
+    }
+    let big_endian = match note.get(12..14)? {
+        b"MM" => true,
+        b"II" => false,
+        _ => return None,
+    };
+    let u16_at = |offset: usize| {
+        let bytes = note.get(offset..offset + 2)?.try_into().ok()?;
+        Some(if big_endian { u16::from_be_bytes(bytes) } else { u16::from_le_bytes(bytes) })
+    };
+    let u32_at = |offset: usize| {
+        let bytes = note.get(offset..offset + 4)?.try_into().ok()?;
+        Some(if big_endian { u32::from_be_bytes(bytes) } else { u32::from_le_bytes(bytes) })
+    };
+
+    let count = u16_at(APPLE_MAKER_NOTE_IFD)? as usize;
+    (0..count).find_map(|i| {
+        let entry = APPLE_MAKER_NOTE_IFD + 2 + i * 12;
+        // ASCII (type 2) values longer than four bytes are stored at an offset
+        if u16_at(entry)? != APPLE_CONTENT_IDENTIFIER_TAG || u16_at(entry + 2)? != 2 {
+            return None;
+        }
+        let len = u32_at(entry + 4)? as usize;
+        let start = if len <= 4 { entry + 8 } else { u32_at(entry + 8)? as usize };
+        uuid_from_bytes(note.get(start..start.checked_add(len)?)?)
+    })
+}
+
+/// Parses a NUL-terminated `XXXXXXXX-XXXX-XXXX-XXXX-XXXXXXXXXXXX` UUID,
+/// uppercased.
+fn uuid_from_bytes(bytes: &[u8]) -> Option<String> {
+    const DASHES: [usize; 4] = [8, 13, 18, 23];
+
+    let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    let uuid = &bytes[..end];
+    let valid = uuid.len() == 36
+        && uuid.iter().enumerate().all(|(i, &b)| {
+            if DASHES.contains(&i) {
+                b == b'-'
+            } else {
+                b.is_ascii_hexdigit()
+            }
+        });
+    valid.then(|| String::from_utf8_lossy(uuid).to_uppercase())
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use exif::experimental::Writer;
+    use exif::{Field, In, Tag, Value};
+    use std::io::Cursor;
+    use tempfile::TempDir;
+
+    const UUID_A: &str = "1F2E3D4C-5B6A-4978-8695-A4B3C2D1E0F9";
+    const UUID_B: &str = "00000000-1111-2222-3333-444444444444";
+    const UUID_C: &str = "ABCDEF01-2345-6789-ABCD-EF0123456789";
+
+    /// An atom of `kind` around `payload`.
+    fn atom(kind: &[u8; 4], payload: &[u8]) -> Vec<u8> {
+        let mut atom = ((payload.len() + 8) as u32).to_be_bytes().to_vec();
+        atom.extend_from_slice(kind);
+        atom.extend_from_slice(payload);
+        atom
+    }
+
+    /// A QuickTime file with `uuid` in `moov/meta`, after media data that
+    /// contains a different UUID.
+    fn quicktime(uuid: Option<&str>) -> Vec<u8> {
+        let mut data = atom(b"ftyp", b"qt  \0\0\0\0qt  ");
+        data.extend(atom(b"mdat", format!("....{UUID_B}....").as_bytes()));
+
+        let mut meta = atom(b"hdlr", &[0; 25]);
+        if let Some(uuid) = uuid {
+            let mut keys = vec![0, 0, 0, 0, 0, 0, 0, 2];
+            keys.extend(atom(b"mdta", b"com.apple.quicktime.make"));
+            keys.extend(atom(b"mdta", CONTENT_IDENTIFIER_KEY));
+            let value = |text: &str| atom(b"data", &[&[0, 0, 0, 1, 0, 0, 0, 0], text.as_bytes()].concat());
+            let ilst = [atom(&1u32.to_be_bytes(), &value("Apple")), atom(&2u32.to_be_bytes(), &value(uuid))].concat();
+            meta.extend(atom(b"keys", &keys));
+            meta.extend(atom(b"ilst", &ilst));
+        }
+        data.extend(atom(b"moov", &atom(b"meta", &meta)));
+        data
+    }
+
+    /// A JPEG whose EXIF has an Apple maker note with `uuid` as
+    /// `ContentIdentifier`, after a burst UUID (tag `0x000b`).
+    fn jpeg(uuid: Option<&str>) -> Vec<u8> {
+        let mut fields = vec![Field {
+            tag: Tag::Make,
+            ifd_num: In::PRIMARY,
+            value: Value::Ascii(vec![b"Apple".to_vec()]),
+        }];
+        if let Some(uuid) = uuid {
+            let entries = [(0x000b, UUID_B), (APPLE_CONTENT_IDENTIFIER_TAG, uuid)];
+            let mut note = b"Apple iOS\0\0\x01MM".to_vec();
+            note.extend((entries.len() as u16).to_be_bytes());
+            let mut values = Vec::new();
+            let values_start = note.len() + entries.len() * 12 + 4;
+            for (tag, text) in entries {
+                note.extend(u16::to_be_bytes(tag));
+                note.extend(2u16.to_be_bytes());
+                note.extend(((text.len() + 1) as u32).to_be_bytes());
+                note.extend(((values_start + values.len()) as u32).to_be_bytes());
+                values.extend(text.as_bytes());
+                values.push(0);
+            }
+            note.extend([0; 4]);
+            note.extend(values);
+            fields.push(Field {
+                tag: Tag::MakerNote,
+                ifd_num: In::PRIMARY,
+                value: Value::Undefined(note, 0),
+            });
+        }
+
+        let mut writer = Writer::new();
+        for field in &fields {
+            writer.push_field(field);
+        }
+        let mut tiff = Cursor::new(Vec::new());
+        writer.write(&mut tiff, false).unwrap();
+        let tiff = tiff.into_inner();
+
+        let mut data = vec![0xFF, 0xD8, 0xFF, 0xE1];
+        data.extend(((tiff.len() + 8) as u16).to_be_bytes());
+        data.extend_from_slice(b"Exif\0\0");
+        data.extend(tiff);
+        data.extend([0xFF, 0xD9]);
+        data
+    }
+
+    fn write(dir: &Path, name: &str, uuid: Option<&str>) -> PathBuf {
+        let path = dir.join(name);
+        let data = if has_extension(&path, MOTION_EXTENSIONS) {
+            quicktime(uuid)
+        } else {
+            jpeg(uuid)
+        };
+        std::fs::write(&path, data).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_find_live_photo_pairs_by_stem_and_uuid() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+
+        // Stem match without identifiers
+        write(dir, "IMG_0001.HEIC", None);
+        write(dir, "IMG_0001.MOV", None);
+        // Stem match with conflicting identifiers is rejected
+        write(dir, "IMG_0002.HEIC", Some(UUID_A));
+        write(dir, "IMG_0002.MOV", Some(UUID_B));
+        // Renamed video paired by identifier
+        write(dir, "IMG_0003.JPG", Some(UUID_C));
+        write(dir, "renamed.mov", Some(UUID_C));
+        // Lonely still
+        write(dir, "IMG_0004.HEIC", None);
+
+        let pairs = find_live_photo_pairs(dir, false).unwrap();
+        assert_eq!(pairs.len(), 2);
+
+        assert_eq!(pairs[0].still_path, dir.join("IMG_0001.HEIC"));
+        assert_eq!(pairs[0].motion_path, dir.join("IMG_0001.MOV"));
+        assert_eq!(pairs[0].uuid, None);
+
+        assert_eq!(pairs[1].still_path, dir.join("IMG_0003.JPG"));
+        assert_eq!(pairs[1].motion_path, dir.join("renamed.mov"));
+        assert_eq!(pairs[1].uuid.as_deref(), Some(UUID_C));
+    }
+
+    #[test]
+    fn test_find_motion_companion() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        let still = write(dir, "IMG_0001.HEIC", Some(UUID_A));
+        write(dir, "IMG_0001.MOV", Some(&UUID_A.to_lowercase()));
+        let conflicting = write(dir, "IMG_0002.HEIC", Some(UUID_A));
+        write(dir, "IMG_0002.MOV", Some(UUID_B));
+
+        assert_eq!(find_motion_companion(&still), Some(dir.join("IMG_0001.MOV")));
+        assert_eq!(find_motion_companion(&conflicting), None);
+        assert_eq!(find_motion_companion(&dir.join("IMG_0001.MOV")), None);
+    }
+
+    #[test]
+    fn test_read_content_identifier() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+
+        // Other UUIDs in the file (media data, burst UUID) are not picked up
+        assert_eq!(read_content_identifier(&write(dir, "a.mov", Some(UUID_A))), Some(UUID_A.to_string()));
+        assert_eq!(read_content_identifier(&write(dir, "a.jpg", Some(UUID_A))), Some(UUID_A.to_string()));
+        assert_eq!(read_content_identifier(&write(dir, "b.mov", None)), None);
+        assert_eq!(read_content_identifier(&write(dir, "b.jpg", None)), None);
+    }
+
+    #[test]
+    fn test_uuid_from_bytes() {
+        assert_eq!(uuid_from_bytes(b"1f2e3d4c-5b6a-4978-8695-a4b3c2d1e0f9\0"), Some(UUID_A.to_string()));
+        assert_eq!(uuid_from_bytes(b"1F2E3D4C-5B6A-4978-8695-A4B3C2D1E0F"), None);
+        assert_eq!(uuid_from_bytes(b"xx1F2E3D4C-5B6A-4978-8695-A4B3C2D1E0F9"), None);
+    }
+}
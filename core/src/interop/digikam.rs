@@ -0,0 +1,353 @@
+//! digiKam database import
+//!
+//! Reads tags, ratings, comments and capture dates from a digiKam database
+//! (`digikam4.db`, SQLite, usually in `~/.local/share/digikam/` or the
+//! collection root). The database is opened read-only and is never modified.
+//!
+//! ## Tag Mapping
+//!
+//! | digiKam                    | Jožin tag        |
+//! |----------------------------|------------------|
+//! | Tag "Alice" under "People" | `people/alice`   |
+//! | Rating ★★★★                | `rating:4`       |
+//! | Image comment              | `comment`        |
+//!
+//! Hierarchical tags keep digiKam's `/` separator and are lowercased.
+//! digiKam's internal tags (`_Digikam_Internal_Tags_`) are skipped. All tags
+//! use `TagSource::User` with no score.
+//!
+//! ## Database Tables Used
+//!
+//! - `AlbumRoots` / `Albums` / `Images` - file location
+//! - `Tags` / `ImageTags` - tag tree and assignments
+//! - `ImageInformation` - rating and creation date
+//! - `ImageComments` - captions and comments
+//!
+//! Album roots on removable volumes are resolved as if the volume were
+//! mounted at `/` unless the root identifier carries an explicit `path=`.
+
+use crate::{JozinError, Result, Tag};
+use rusqlite::{Connection, OpenFlags};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+/// Root of digiKam's internal bookkeeping tags (color labels, pick labels, ...).
+const INTERNAL_TAGS_ROOT: &str = "_Digikam_Internal_Tags_";
+
+/// Maximum tag tree depth followed (guards against cycles in damaged databases).
+const MAX_TAG_DEPTH: usize = 64;
+
+// ============================================================================
+// Public Types
+// ============================================================================
+
+/// Metadata of one photo read from a digiKam database.
+///
+/// # Fields
+///
+/// - `image_path`: Absolute path of the photo
+/// - `tags`: Hierarchical tags (e.g. `people/alice`), lowercased
+/// - `rating`: Star rating 0-5 (`None` if unset)
+/// - `comment`: Image comment or caption
+/// - `datetime`: Creation date as stored by digiKam (e.g. `2020-06-15T10:30:00`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DigiKamImportEntry {
+    pub image_path: PathBuf,
+    pub tags: Vec<Tag>,
+    pub rating: Option<u8>,
+    pub comment: Option<String>,
+    pub datetime: Option<String>,
+}
+
+impl DigiKamImportEntry {
+    /// All imported metadata as Jožin tags: `tags`, then `rating:N` (1-5)
+    /// and `comment` if present.
+    pub fn to_tags(&self) -> Vec<Tag> {
+        let mut tags = self.tags.clone();
+        if let Some(rating) = self.rating.filter(|r| (1..=5).contains(r)) {
+            tags.push(user_tag(format!("rating:{}", rating)));
+        }
+        if self.comment.is_some() {
+            tags.push(user_tag("comment".to_string()));
+        }
+        tags
+    }
+}
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Reads digiKam tags, ratings, comments and dates.
+///
+/// # Arguments
+///
+/// * `db_path` - Path to `digikam4.db`
+/// * `photos_root` - Only photos under this directory are returned
+///
+/// # Returns
+///
+/// One entry per photo that has at least one tag, rating, comment or date.
+///
+/// # Errors
+///
+/// - `JozinError::IoError` if the database does not exist
+/// - `JozinError::ValidationError` if the file is not a readable digiKam database
+///
+/// # Example
+///
+/// ```no_run
+/// use jozin_core::interop::digikam::import_digikam_metadata;
+/// use std::path::Path;
+///
+/// let entries = import_digikam_metadata(
+///     Path::new("/home/me/.local/share/digikam/digikam4.db"),
+///     Path::new("/home/me/Pictures"),
+/// )?;
+/// for entry in entries {
+///     println!("{}: {} tags", entry.image_path.display(), entry.to_tags().len());
+/// }
+/// # Ok::<(), jozin_core::JozinError>(())
+/// ```
+pub fn import_digikam_metadata(db_path: &Path, photos_root: &Path) -> Result<Vec<DigiKamImportEntry>> {
+    if !db_path.is_file() {
+        return Err(JozinError::IoError {
+            message: format!("digiKam database not found: {}", db_path.display()),
+        });
+    }
+
+    let conn = Connection::open_with_flags(db_path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .map_err(|e| database_error(db_path, e))?;
+
+    let tag_paths = read_tag_paths(&conn).map_err(|e| database_error(db_path, e))?;
+    let image_tags = read_image_tags(&conn, &tag_paths).map_err(|e| database_error(db_path, e))?;
+    let comments = read_comments(&conn).map_err(|e| database_error(db_path, e))?;
+    let images = read_images(&conn).map_err(|e| database_error(db_path, e))?;
+
+    let mut entries = Vec::new();
+    for image in images {
+        if !image.path.starts_with(photos_root) {
+            continue;
+        }
+
+        let entry = DigiKamImportEntry {
+            image_path: image.path,
+            tags: image_tags
+                .get(&image.id)
+                .map(|labels| labels.iter().cloned().map(user_tag).collect())
+                .unwrap_or_default(),
+            rating: image.rating.and_then(|r| u8::try_from(r).ok()).filter(|r| *r <= 5),
+            comment: comments.get(&image.id).cloned(),
+            datetime: image.datetime,
+        };
+        if !entry.tags.is_empty() || entry.rating.is_some() || entry.comment.is_some() || entry.datetime.is_some() {
+            entries.push(entry);
+        }
+    }
+
+    Ok(entries)
+}
+
+// ============================================================================
+// Internal Helpers
+// ============================================================================
+
+/// A photo row from the database.
+struct DigiKamImage {
+    id: i64,
+    path: PathBuf,
+    rating: Option<i64>,
+    datetime: Option<String>,
+}
+
+fn read_images(conn: &Connection) -> rusqlite::Result<Vec<DigiKamImage>> {
+    let mut stmt = conn.prepare(
+        "SELECT i.id, r.identifier, r.specificPath, a.relativePath, i.name,
+                info.rating, info.creationDate
+         FROM Images i
+         JOIN Albums a ON i.album = a.id
+         JOIN AlbumRoots r ON a.albumRoot = r.id
+         LEFT JOIN ImageInformation info ON info.imageid = i.id",
+    )?;
+
+    let rows = stmt.query_map([], |row| {
+        let identifier: String = row.get::<_, Option<String>>(1)?.unwrap_or_default();
+        let specific_path: String = row.get::<_, Option<String>>(2)?.unwrap_or_default();
+        let relative_path: String = row.get::<_, Option<String>>(3)?.unwrap_or_default();
+        let name: String = row.get(4)?;
+
+        Ok(DigiKamImage {
+            id: row.get(0)?,
+            path: album_root_path(&identifier, &specific_path)
+                .join(relative_path.trim_start_matches('/'))
+                .join(name),
+            // digiKam stores "no rating" as -1
+            rating: row.get::<_, Option<i64>>(5)?.filter(|r| *r >= 0),
+            datetime: row.get::<_, Option<String>>(6)?.filter(|d| !d.is_empty()),
+        })
+    })?;
+
+    rows.collect()
+}
+
+/// Returns the full `parent/child` path of every tag, lowercased.
+///
+/// Internal tags are left out.
+fn read_tag_paths(conn: &Connection) -> rusqlite::Result<HashMap<i64, String>> {
+    let mut stmt = conn.prepare("SELECT id, pid, name FROM Tags")?;
+    let rows = stmt.query_map([], |row| {
+        Ok((row.get::<_, i64>(0)?, (row.get::<_, Option<i64>>(1)?.unwrap_or(0), row.get::<_, String>(2)?)))
+    })?;
+    let tags: HashMap<i64, (i64, String)> = rows.collect::<rusqlite::Result<_>>()?;
+
+    let mut paths = HashMap::new();
+    for &id in tags.keys() {
+        let mut names = Vec::new();
+        let mut current = id;
+        while let Some((parent, name)) = tags.get(&current) {
+            names.push(name.as_str());
+            if *parent == 0 || names.len() > MAX_TAG_DEPTH {
+                break;
+            }
+            current = *parent;
+        }
+        names.reverse();
+        if names.first().is_some_and(|root| *root == INTERNAL_TAGS_ROOT) {
+            continue;
+        }
+        paths.insert(id, names.join("/").to_lowercase());
+    }
+
+    Ok(paths)
+}
+
+/// Returns tag paths per image id.
+fn read_image_tags(conn: &Connection, tag_paths: &HashMap<i64, String>) -> rusqlite::Result<HashMap<i64, Vec<String>>> {
+    let mut stmt = conn.prepare("SELECT imageid, tagid FROM ImageTags ORDER BY imageid, tagid")?;
+    let rows = stmt.query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?)))?;
+
+    let mut image_tags: HashMap<i64, Vec<String>> = HashMap::new();
+    for row in rows {
+        let (image, tag) = row?;
+        if let Some(path) = tag_paths.get(&tag) {
+            image_tags.entry(image).or_default().push(path.clone());
+        }
+    }
+
+    Ok(image_tags)
+}
+
+/// Returns the first non-empty comment per image id.
+fn read_comments(conn: &Connection) -> rusqlite::Result<HashMap<i64, String>> {
+    let mut stmt = conn.prepare("SELECT imageid, comment FROM ImageComments ORDER BY id")?;
+    let rows = stmt.query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, Option<String>>(1)?)))?;
+
+    let mut comments = HashMap::new();
+    for row in rows {
+        if let (image, Some(comment)) = row? {
+            let comment = comment.trim();
+            if !comment.is_empty() {
+                comments.entry(image).or_insert_with(|| comment.to_string());
+            }
+        }
+    }
+
+    Ok(comments)
+}
+
+/// Resolves an album root from its `volumeid:?...` identifier and specific path.
+fn album_root_path(identifier: &str, specific_path: &str) -> PathBuf {
+    let mount_point = identifier
+        .split(['?', '&'])
+        .find_map(|param| param.strip_prefix("path="))
+        .unwrap_or("/");
+    PathBuf::from(mount_point).join(specific_path.trim_start_matches('/'))
+}
+
+fn user_tag(label: String) -> Tag {
+    Tag::from_label(&label)
+}
+
+fn database_error(db_path: &Path, err: rusqlite::Error) -> JozinError {
+    JozinError::ValidationError {
+        message: format!("Failed to read digiKam database {}: {}", db_path.display(), err),
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// Creates a database with the subset of digiKam's schema that is read.
+    fn create_database(path: &Path, root: &Path) {
+        let conn = Connection::open(path).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE AlbumRoots (id INTEGER PRIMARY KEY, label TEXT, identifier TEXT, specificPath TEXT);
+             CREATE TABLE Albums (id INTEGER PRIMARY KEY, albumRoot INTEGER, relativePath TEXT);
+             CREATE TABLE Images (id INTEGER PRIMARY KEY, album INTEGER, name TEXT);
+             CREATE TABLE Tags (id INTEGER PRIMARY KEY, pid INTEGER, name TEXT);
+             CREATE TABLE ImageTags (imageid INTEGER, tagid INTEGER);
+             CREATE TABLE ImageInformation (imageid INTEGER PRIMARY KEY, rating INTEGER, creationDate TEXT);
+             CREATE TABLE ImageComments (id INTEGER PRIMARY KEY, imageid INTEGER, type INTEGER, comment TEXT);",
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO AlbumRoots VALUES (1, 'Pictures', ?1, '/'), (2, 'Other', 'volumeid:?path=/elsewhere', '/')",
+            [format!("volumeid:?path={}", root.display())],
+        )
+        .unwrap();
+        conn.execute_batch(
+            "INSERT INTO Albums VALUES (10, 1, '/2020/Summer'), (11, 2, '/');
+             INSERT INTO Images VALUES (100, 10, 'IMG_0001.JPG'), (101, 10, 'IMG_0002.JPG'), (102, 11, 'IMG_0003.JPG');
+             INSERT INTO Tags VALUES (1, 0, 'People'), (2, 1, 'Alice'), (3, 0, '_Digikam_Internal_Tags_'), (4, 3, 'Color Label Red');
+             INSERT INTO ImageTags VALUES (100, 2), (100, 4), (102, 2);
+             INSERT INTO ImageInformation VALUES (100, 4, '2020-06-15T10:30:00'), (101, -1, NULL), (102, 3, NULL);
+             INSERT INTO ImageComments VALUES (1, 100, 1, 'At the lake'), (2, 101, 1, '  ');",
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_import_digikam_metadata() {
+        let temp_dir = TempDir::new().unwrap();
+        let db = temp_dir.path().join("digikam4.db");
+        create_database(&db, temp_dir.path());
+
+        let entries = import_digikam_metadata(&db, temp_dir.path()).unwrap();
+
+        // IMG_0002 has nothing to import; IMG_0003 is outside photos_root
+        assert_eq!(entries.len(), 1);
+        let entry = &entries[0];
+        assert_eq!(entry.image_path, temp_dir.path().join("2020/Summer/IMG_0001.JPG"));
+        assert_eq!(entry.rating, Some(4));
+        assert_eq!(entry.comment.as_deref(), Some("At the lake"));
+        assert_eq!(entry.datetime.as_deref(), Some("2020-06-15T10:30:00"));
+
+        let labels: Vec<String> = entry.to_tags().into_iter().map(|t| t.label).collect();
+        assert_eq!(labels, vec!["people/alice", "rating:4", "comment"]);
+    }
+
+    #[test]
+    fn test_import_digikam_invalid_database() {
+        let temp_dir = TempDir::new().unwrap();
+        let db = temp_dir.path().join("empty.db");
+        std::fs::write(&db, b"").unwrap();
+
+        let result = import_digikam_metadata(&db, temp_dir.path());
+        assert!(matches!(result, Err(JozinError::ValidationError { .. })));
+
+        let missing = import_digikam_metadata(&temp_dir.path().join("nope.db"), temp_dir.path());
+        assert!(matches!(missing, Err(JozinError::IoError { .. })));
+        assert_eq!(album_root_path("volumeid:?uuid=abcd", "/home/me/Pictures"), Path::new("/home/me/Pictures"));
+    }
+}
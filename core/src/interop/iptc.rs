@@ -0,0 +1,255 @@
+//! Writing tags into the IPTC block of photos
+//!
+//! Some asset management systems read only metadata embedded in the image,
+//! not sidecars. [`export_tags_to_iptc()`] writes a sidecar's tag labels into
+//! the photo's `IPTC:Keywords` using [`exiftool`](https://exiftool.org) as a
+//! subprocess (it must be in `PATH`).
+//!
+//! **This modifies the original file**, which Jožin otherwise never does;
+//! the CLI requires `--allow-modify-originals`. exiftool rewrites the file
+//! in place (`-overwrite_original`), without keeping a backup copy.
+//!
+//! | Option          | Effect                                                  |
+//! |-----------------|---------------------------------------------------------|
+//! | default         | Existing keywords are replaced by all tag labels        |
+//! | `append`        | Tag labels are added to the existing keywords           |
+//! | `only_user_tags`| Only `TagSource::User` labels are written               |
+//!
+//! Only JPEG and TIFF files have an IPTC block exiftool can write.
+//!
+//! ## Usage
+//!
+//! ```no_run
+//! use jozin_core::interop::iptc::export_tags_to_iptc;
+//! use jozin_core::scan::read_sidecar;
+//! use std::path::Path;
+//!
+//! let photo = Path::new("/photos/IMG_1234.JPG");
+//! let result = export_tags_to_iptc(&read_sidecar(photo)?, photo)?;
+//! println!("{:?} -> {}", result.keywords, result.stdout);
+//! # Ok::<(), jozin_core::JozinError>(())
+//! ```
+
+use crate::scan::load_sidecars;
+use crate::util::capability_check::CapabilityChecker;
+use crate::{JozinError, Result, Sidecar, TagSource};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+/// File extensions (lowercase) with a writable IPTC block.
+const IPTC_EXTENSIONS: &[&str] = &["jpg", "jpeg", "tif", "tiff"];
+
+// ============================================================================
+// Public Types
+// ============================================================================
+
+/// Options for writing IPTC keywords.
+///
+/// # Fields
+///
+/// - `only_user_tags`: Write only `TagSource::User` labels (default: all)
+/// - `append`: Add to the existing keywords instead of replacing them
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IptcWriteOptions {
+    pub only_user_tags: bool,
+    pub append: bool,
+}
+
+/// Result of writing the keywords of one photo.
+///
+/// # Fields
+///
+/// - `image_path`: The photo that was modified
+/// - `keywords`: The labels passed to exiftool, in tag order
+/// - `stdout`: exiftool's output (e.g. `"1 image files updated"`)
+/// - `stderr`: exiftool's warnings
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IptcWriteResult {
+    pub image_path: PathBuf,
+    pub keywords: Vec<String>,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Replaces the IPTC keywords of `image_path` with the tag labels of
+/// `sidecar` (all sources).
+///
+/// # Errors
+///
+/// See [`export_tags_to_iptc_with()`].
+pub fn export_tags_to_iptc(sidecar: &Sidecar, image_path: &Path) -> Result<IptcWriteResult> {
+    export_tags_to_iptc_with(sidecar, image_path, &IptcWriteOptions::default())
+}
+
+/// Writes the tag labels of `sidecar` into the IPTC keywords of
+/// `image_path`, as set by `options`.
+///
+/// Duplicate labels are written once. Replacing with no labels clears the
+/// keywords; appending no labels leaves the file untouched.
+///
+/// # Errors
+///
+/// - `JozinError::NotSupported` if the file is not a JPEG or TIFF, or
+///   exiftool is not installed
+/// - `JozinError::IoError` if exiftool cannot be run or fails (its error
+///   output is included)
+pub fn export_tags_to_iptc_with(
+    sidecar: &Sidecar,
+    image_path: &Path,
+    options: &IptcWriteOptions,
+) -> Result<IptcWriteResult> {
+    let extension = image_path.extension().map(|ext| ext.to_string_lossy().to_lowercase()).unwrap_or_default();
+    if !IPTC_EXTENSIONS.contains(&extension.as_str()) {
+        return Err(JozinError::NotSupported {
+            feature: format!("IPTC keywords in {} (only JPEG and TIFF)", image_path.display()),
+        });
+    }
+
+    let keywords = keywords(sidecar, options.only_user_tags);
+    if options.append && keywords.is_empty() {
+        return Ok(IptcWriteResult {
+            image_path: image_path.to_path_buf(),
+            keywords,
+            stdout: String::new(),
+            stderr: String::new(),
+        });
+    }
+
+    let exiftool = CapabilityChecker::new().require("exiftool")?;
+    let output = Command::new(exiftool)
+        .args(exiftool_args(&keywords, options.append))
+        .arg(image_path)
+        .stdin(Stdio::null())
+        .output()?;
+
+    let (stdout, stderr) = (
+        String::from_utf8_lossy(&output.stdout).trim().to_string(),
+        String::from_utf8_lossy(&output.stderr).trim().to_string(),
+    );
+    if !output.status.success() {
+        return Err(JozinError::IoError {
+            message: format!("exiftool failed on {}: {}", image_path.display(), stderr),
+        });
+    }
+
+    Ok(IptcWriteResult {
+        image_path: image_path.to_path_buf(),
+        keywords,
+        stdout,
+        stderr,
+    })
+}
+
+/// Writes the IPTC keywords of every photo with a sidecar under `root`.
+///
+/// Photos are taken from `source.file_path` of each sidecar. Processing
+/// stops at the first failure.
+///
+/// # Errors
+///
+/// - `JozinError::NotSupported` if there are photos and exiftool is not
+///   installed (checked before any photo is touched)
+/// - `JozinError::IoError` if `root` does not exist
+/// - As [`export_tags_to_iptc_with()`] for each photo
+pub fn export_library_to_iptc(root: &Path, recursive: bool, options: &IptcWriteOptions) -> Result<Vec<IptcWriteResult>> {
+    let sidecars = load_sidecars(root, recursive)?;
+    if !sidecars.is_empty() {
+        CapabilityChecker::new().require("exiftool")?;
+    }
+    sidecars
+        .iter()
+        .map(|sidecar| export_tags_to_iptc_with(sidecar, Path::new(&sidecar.source.file_path), options))
+        .collect()
+}
+
+// ============================================================================
+// Internal Helpers
+// ============================================================================
+
+/// Distinct tag labels to write, in tag order.
+fn keywords(sidecar: &Sidecar, only_user_tags: bool) -> Vec<String> {
+    let mut keywords: Vec<String> = Vec::new();
+    for tag in &sidecar.tags {
+        if (!only_user_tags || tag.source == TagSource::User) && !keywords.contains(&tag.label) {
+            keywords.push(tag.label.clone());
+        }
+    }
+    keywords
+}
+
+/// exiftool arguments (before the file name) writing `keywords`.
+///
+/// Replacing starts with `-IPTC:Keywords=`, which clears the existing
+/// keywords; every label is then added with `+=`.
+fn exiftool_args(keywords: &[String], append: bool) -> Vec<String> {
+    let mut args = vec!["-charset".to_string(), "iptc=UTF8".to_string(), "-overwrite_original".to_string()];
+    if !append {
+        args.push("-IPTC:Keywords=".to_string());
+    }
+    args.extend(keywords.iter().map(|keyword| format!("-IPTC:Keywords+={}", keyword)));
+    args
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scan::scan_file;
+    use crate::Tag;
+    use tempfile::TempDir;
+
+    fn sidecar_with_tags(dir: &Path) -> Sidecar {
+        let photo = dir.join("a.jpg");
+        std::fs::write(&photo, b"image").unwrap();
+        let mut sidecar = scan_file(&photo, true).unwrap().sidecar;
+        sidecar.tags = vec![
+            Tag::from_label("beach"),
+            Tag { source: TagSource::Ml, score: Some(0.9), ..Tag::from_label("sunset") },
+            Tag { source: TagSource::Rules, ..Tag::from_label("beach") },
+        ];
+        sidecar
+    }
+
+    #[test]
+    fn test_keywords_and_arguments() {
+        let temp_dir = TempDir::new().unwrap();
+        let sidecar = sidecar_with_tags(temp_dir.path());
+        assert_eq!(keywords(&sidecar, false), ["beach", "sunset"]);
+        assert_eq!(keywords(&sidecar, true), ["beach"]);
+
+        let labels = keywords(&sidecar, false);
+        let replace = exiftool_args(&labels, false);
+        assert_eq!(
+            replace[3..],
+            ["-IPTC:Keywords=", "-IPTC:Keywords+=beach", "-IPTC:Keywords+=sunset"]
+        );
+        assert_eq!(exiftool_args(&labels, true)[3..], ["-IPTC:Keywords+=beach", "-IPTC:Keywords+=sunset"]);
+    }
+
+    #[test]
+    fn test_unsupported_formats_and_empty_append() {
+        let temp_dir = TempDir::new().unwrap();
+        let sidecar = sidecar_with_tags(temp_dir.path());
+        let err = export_tags_to_iptc(&sidecar, &temp_dir.path().join("a.png")).unwrap_err();
+        assert!(matches!(err, JozinError::NotSupported { .. }));
+
+        // Nothing to append: exiftool is not run
+        let mut untagged = sidecar.clone();
+        untagged.tags.clear();
+        let options = IptcWriteOptions { append: true, ..IptcWriteOptions::default() };
+        let result = export_tags_to_iptc_with(&untagged, &temp_dir.path().join("a.jpg"), &options).unwrap();
+        assert!(result.keywords.is_empty() && result.stdout.is_empty());
+    }
+}
@@ -0,0 +1,258 @@
+//! Lightroom Classic catalog import
+//!
+//! Reads keywords, star ratings and color labels from an Adobe Lightroom
+//! Classic catalog (`.lrcat`, an SQLite database) and converts them to Jožin
+//! tags. The catalog is opened read-only and is never modified.
+//!
+//! ## Tag Mapping
+//!
+//! | Lightroom            | Jožin tag      |
+//! |----------------------|----------------|
+//! | Keyword "Beach"      | `beach`        |
+//! | Rating ★★★★★         | `rating:5`     |
+//! | Color label "Red"    | `color:red`    |
+//!
+//! All tags use `TagSource::User` with no score.
+//!
+//! ## Catalog Tables Used
+//!
+//! - `Adobe_images` - one row per photo (rating, color label)
+//! - `AgLibraryFile` / `AgLibraryFolder` / `AgLibraryRootFolder` - file location
+//! - `AgLibraryKeywordImage` / `AgLibraryKeyword` - keyword assignments
+
+use crate::{JozinError, Result, Tag};
+use rusqlite::{Connection, OpenFlags};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Color labels Lightroom assigns by default (lowercased).
+const COLOR_LABELS: &[&str] = &["red", "yellow", "green", "blue", "purple"];
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Reads Lightroom keywords, ratings and color labels as Jožin tags.
+///
+/// # Arguments
+///
+/// * `catalog_path` - Path to the `.lrcat` file
+/// * `photos_root` - Only photos under this directory are returned
+///
+/// # Returns
+///
+/// `(absolute_image_path, tags)` pairs, one per photo that has at least one
+/// keyword, rating or color label.
+///
+/// # Errors
+///
+/// - `JozinError::IoError` if the catalog does not exist
+/// - `JozinError::ValidationError` if the file is not a readable Lightroom catalog
+///
+/// # Example
+///
+/// ```no_run
+/// use jozin_core::interop::lr_catalog::import_lightroom_keywords;
+/// use std::path::Path;
+///
+/// let entries = import_lightroom_keywords(
+///     Path::new("/Users/me/Pictures/Lightroom/Lightroom Catalog.lrcat"),
+///     Path::new("/Users/me/Pictures"),
+/// )?;
+/// for (path, tags) in entries {
+///     println!("{}: {} tags", path.display(), tags.len());
+/// }
+/// # Ok::<(), jozin_core::JozinError>(())
+/// ```
+pub fn import_lightroom_keywords(
+    catalog_path: &Path,
+    photos_root: &Path,
+) -> Result<Vec<(PathBuf, Vec<Tag>)>> {
+    if !catalog_path.is_file() {
+        return Err(JozinError::IoError {
+            message: format!("Catalog not found: {}", catalog_path.display()),
+        });
+    }
+
+    let conn = Connection::open_with_flags(catalog_path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .map_err(|e| catalog_error(catalog_path, e))?;
+
+    let keywords = read_keywords(&conn).map_err(|e| catalog_error(catalog_path, e))?;
+    let images = read_images(&conn).map_err(|e| catalog_error(catalog_path, e))?;
+
+    let mut entries = Vec::new();
+    for image in images {
+        if !image.path.starts_with(photos_root) {
+            continue;
+        }
+
+        let mut tags: Vec<Tag> = keywords
+            .get(&image.id)
+            .map(|names| names.iter().map(|name| user_tag(name.to_lowercase())).collect())
+            .unwrap_or_default();
+
+        if let Some(rating) = image.rating.filter(|r| (1..=5).contains(r)) {
+            tags.push(user_tag(format!("rating:{}", rating)));
+        }
+        if let Some(color) = image.color_label.as_deref().and_then(normalise_color_label) {
+            tags.push(user_tag(format!("color:{}", color)));
+        }
+
+        if !tags.is_empty() {
+            entries.push((image.path, tags));
+        }
+    }
+
+    Ok(entries)
+}
+
+// ============================================================================
+// Internal Helpers
+// ============================================================================
+
+/// A photo row from the catalog.
+struct CatalogImage {
+    id: i64,
+    path: PathBuf,
+    rating: Option<i64>,
+    color_label: Option<String>,
+}
+
+fn read_images(conn: &Connection) -> rusqlite::Result<Vec<CatalogImage>> {
+    let mut stmt = conn.prepare(
+        "SELECT i.id_local, rf.absolutePath, fo.pathFromRoot, f.baseName, f.extension,
+                i.rating, i.colorLabels
+         FROM Adobe_images i
+         JOIN AgLibraryFile f ON i.rootFile = f.id_local
+         JOIN AgLibraryFolder fo ON f.folder = fo.id_local
+         JOIN AgLibraryRootFolder rf ON fo.rootFolder = rf.id_local",
+    )?;
+
+    let rows = stmt.query_map([], |row| {
+        let root: String = row.get(1)?;
+        let folder: String = row.get::<_, Option<String>>(2)?.unwrap_or_default();
+        let base: String = row.get(3)?;
+        let ext: String = row.get::<_, Option<String>>(4)?.unwrap_or_default();
+        let file_name = if ext.is_empty() { base } else { format!("{}.{}", base, ext) };
+
+        Ok(CatalogImage {
+            id: row.get(0)?,
+            // Lightroom stores directories with a trailing '/'
+            path: PathBuf::from(format!("{}{}{}", root, folder, file_name)),
+            rating: row.get::<_, Option<f64>>(5)?.map(|r| r.round() as i64),
+            color_label: row.get(6)?,
+        })
+    })?;
+
+    rows.collect()
+}
+
+/// Returns keyword names per image id.
+fn read_keywords(conn: &Connection) -> rusqlite::Result<HashMap<i64, Vec<String>>> {
+    let mut stmt = conn.prepare(
+        "SELECT ki.image, k.name
+         FROM AgLibraryKeywordImage ki
+         JOIN AgLibraryKeyword k ON ki.tag = k.id_local
+         WHERE k.name IS NOT NULL",
+    )?;
+
+    let mut keywords: HashMap<i64, Vec<String>> = HashMap::new();
+    let rows = stmt.query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))?;
+    for row in rows {
+        let (image, name) = row?;
+        keywords.entry(image).or_default().push(name);
+    }
+
+    Ok(keywords)
+}
+
+/// Maps a Lightroom color label to one of [`COLOR_LABELS`], if it is one.
+fn normalise_color_label(label: &str) -> Option<&'static str> {
+    let lower = label.trim().to_lowercase();
+    COLOR_LABELS.iter().copied().find(|c| *c == lower)
+}
+
+fn user_tag(label: String) -> Tag {
+    Tag::from_label(&label)
+}
+
+fn catalog_error(catalog_path: &Path, err: rusqlite::Error) -> JozinError {
+    JozinError::ValidationError {
+        message: format!(
+            "Failed to read Lightroom catalog {}: {}",
+            catalog_path.display(),
+            err
+        ),
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TagSource;
+    use tempfile::TempDir;
+
+    /// Creates a catalog with the subset of Lightroom's schema that is read.
+    fn create_catalog(path: &Path, root: &Path) {
+        let conn = Connection::open(path).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE AgLibraryRootFolder (id_local INTEGER PRIMARY KEY, absolutePath TEXT);
+             CREATE TABLE AgLibraryFolder (id_local INTEGER PRIMARY KEY, rootFolder INTEGER, pathFromRoot TEXT);
+             CREATE TABLE AgLibraryFile (id_local INTEGER PRIMARY KEY, folder INTEGER, baseName TEXT, extension TEXT);
+             CREATE TABLE Adobe_images (id_local INTEGER PRIMARY KEY, rootFile INTEGER, rating REAL, colorLabels TEXT);
+             CREATE TABLE AgLibraryKeyword (id_local INTEGER PRIMARY KEY, name TEXT);
+             CREATE TABLE AgLibraryKeywordImage (id_local INTEGER PRIMARY KEY, image INTEGER, tag INTEGER);",
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO AgLibraryRootFolder VALUES (1, ?1), (2, '/elsewhere/')",
+            [format!("{}/", root.display())],
+        )
+        .unwrap();
+        conn.execute_batch(
+            "INSERT INTO AgLibraryFolder VALUES (10, 1, '2020/'), (11, 2, '');
+             INSERT INTO AgLibraryFile VALUES (100, 10, 'IMG_0001', 'JPG'), (101, 10, 'IMG_0002', 'JPG'),
+                                              (102, 11, 'IMG_0003', 'JPG');
+             INSERT INTO Adobe_images VALUES (1000, 100, 5.0, 'Red'), (1001, 101, NULL, ''),
+                                             (1002, 102, 3.0, NULL);
+             INSERT INTO AgLibraryKeyword VALUES (1, 'Beach'), (2, NULL);
+             INSERT INTO AgLibraryKeywordImage VALUES (1, 1000, 1), (2, 1001, 2), (3, 1002, 1);",
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_import_lightroom_keywords() {
+        let temp_dir = TempDir::new().unwrap();
+        let catalog = temp_dir.path().join("Test.lrcat");
+        create_catalog(&catalog, temp_dir.path());
+
+        let entries = import_lightroom_keywords(&catalog, temp_dir.path()).unwrap();
+
+        // IMG_0002 has nothing to import; IMG_0003 is outside photos_root
+        assert_eq!(entries.len(), 1);
+        let (path, tags) = &entries[0];
+        assert_eq!(path, &temp_dir.path().join("2020").join("IMG_0001.JPG"));
+
+        let labels: Vec<&str> = tags.iter().map(|t| t.label.as_str()).collect();
+        assert_eq!(labels, vec!["beach", "rating:5", "color:red"]);
+        assert!(tags.iter().all(|t| t.source == TagSource::User && t.score.is_none()));
+    }
+
+    #[test]
+    fn test_import_lightroom_keywords_invalid_catalog() {
+        let temp_dir = TempDir::new().unwrap();
+        let catalog = temp_dir.path().join("empty.lrcat");
+        std::fs::write(&catalog, b"").unwrap();
+
+        let result = import_lightroom_keywords(&catalog, temp_dir.path());
+        assert!(matches!(result, Err(JozinError::ValidationError { .. })));
+
+        let missing = import_lightroom_keywords(&temp_dir.path().join("nope.lrcat"), temp_dir.path());
+        assert!(matches!(missing, Err(JozinError::IoError { .. })));
+    }
+}
@@ -0,0 +1,139 @@
+//! Interop module - Exchanging metadata with other photo tools
+//!
+//! Importers read another application's metadata store and return plain
+//! `(image path, tags)` pairs; [`merge_imported_tags()`] then writes them into
+//! Jožin sidecars. Exporters go the other way and write sidecar metadata
+//! into another tool's format.
+//!
+//! ## Submodules
+//!
+//! - **lr_catalog** - Adobe Lightroom Classic `.lrcat` catalogs (`lightroom` feature)
+//! - **digikam** - digiKam `digikam4.db` databases (`digikam` feature)
+//! - **apple_live_photos** - Pairing iPhone Live Photo stills with their videos
+//! - **iptc** - Writing tag labels into the IPTC keywords of JPEG/TIFF originals (via exiftool)
+
+pub mod apple_live_photos;
+pub mod iptc;
+#[cfg(feature = "lightroom")]
+pub mod lr_catalog;
+#[cfg(feature = "digikam")]
+pub mod digikam;
+
+use crate::scan::{read_sidecar, write_sidecar};
+use crate::{Result, Tag};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+// ============================================================================
+// Public Types
+// ============================================================================
+
+/// Result of merging imported tags into sidecars.
+///
+/// # Fields
+///
+/// - `total_images`: Number of images reported by the importer
+/// - `updated`: Sidecars that received at least one new tag
+/// - `unchanged`: Sidecars that already had all imported tags
+/// - `missing_sidecar`: Images without a sidecar (run `jozin scan` first)
+/// - `tags_added`: Total number of tags added across all sidecars
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ImportResult {
+    pub total_images: usize,
+    pub updated: usize,
+    pub unchanged: usize,
+    pub missing_sidecar: usize,
+    pub tags_added: usize,
+}
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Merges imported tags into the existing sidecars of the given images.
+///
+/// Tags are appended (existing tags are preserved, duplicate labels skipped).
+/// Images without a readable sidecar are counted in `missing_sidecar`; sidecars
+/// are never created here.
+///
+/// # Arguments
+///
+/// * `entries` - `(image path, tags)` pairs from an importer
+/// * `dry_run` - Count what would change without writing sidecars
+///
+/// # Errors
+///
+/// Returns `JozinError::IoError` if an updated sidecar cannot be written.
+pub fn merge_imported_tags(entries: &[(PathBuf, Vec<Tag>)], dry_run: bool) -> Result<ImportResult> {
+    let mut result = ImportResult {
+        total_images: entries.len(),
+        ..ImportResult::default()
+    };
+
+    for (image_path, tags) in entries {
+        let mut sidecar = match read_sidecar(image_path) {
+            Ok(sidecar) => sidecar,
+            Err(_) => {
+                result.missing_sidecar += 1;
+                continue;
+            }
+        };
+
+        let added = sidecar.merge_tags(tags.clone(), true);
+        if added == 0 {
+            result.unchanged += 1;
+            continue;
+        }
+
+        if !dry_run {
+            write_sidecar(image_path, &sidecar)?;
+        }
+        result.updated += 1;
+        result.tags_added += added;
+    }
+
+    Ok(result)
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scan::scan_file;
+    use crate::TagSource;
+    use tempfile::TempDir;
+
+    fn user_tag(label: &str) -> Tag {
+        Tag {
+            label: label.to_string(),
+            score: None,
+            source: TagSource::User,
+        }
+    }
+
+    #[test]
+    fn test_merge_imported_tags_appends_and_skips_duplicates() {
+        let temp_dir = TempDir::new().unwrap();
+        let photo = temp_dir.path().join("a.jpg");
+        std::fs::write(&photo, b"image").unwrap();
+        scan_file(&photo, false).unwrap();
+        let orphan = temp_dir.path().join("no_sidecar.jpg");
+
+        let entries = vec![
+            (photo.clone(), vec![user_tag("beach"), user_tag("rating:5")]),
+            (orphan, vec![user_tag("beach")]),
+        ];
+        let result = merge_imported_tags(&entries, false).unwrap();
+        assert_eq!(result.updated, 1);
+        assert_eq!(result.tags_added, 2);
+        assert_eq!(result.missing_sidecar, 1);
+
+        // Importing again adds nothing
+        let result = merge_imported_tags(&entries[..1], false).unwrap();
+        assert_eq!(result.unchanged, 1);
+        assert_eq!(read_sidecar(&photo).unwrap().tags.len(), 2);
+    }
+}
@@ -27,6 +27,7 @@
 
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::str::FromStr;
 use time::OffsetDateTime;
 
 // Module declarations
@@ -34,9 +35,15 @@ pub mod scan;
 pub mod verify;
 pub mod migrate;
 pub mod cleanup;
+pub mod stats;
+pub mod exif;
+pub mod hash;
+pub mod interop;
+pub mod export;
+pub mod util;
 
 // Re-export commonly used types for convenience
-pub use scan::{scan_file, scan_path, ScanAction, ScanResult, ScannedFile};
+pub use scan::{scan_file, scan_path, ScanAction, ScanConfig, ScanResult, ScannedFile, SidecarWriteResult, WriteAction};
 pub use cleanup::{cleanup_path, CleanupOptions, CleanupResult, DeletedFile, FileType};
 
 // Phase 2+ modules (feature-gated)
@@ -46,6 +53,8 @@ pub mod faces;
 pub mod tags;
 #[cfg(feature = "thumbs")]
 pub mod thumbs;
+#[cfg(feature = "archives")]
+pub mod archives;
 
 // ============================================================================
 // Type Aliases
@@ -99,6 +108,9 @@ pub type Timestamp = String;
 ///
 /// let callback = |event: ProgressEvent| {
 ///     match event {
+///         ProgressEvent::BatchStarted { total_files, estimated_duration_ms, .. } => {
+///             println!("Scanning {} files (~{} ms)", total_files, estimated_duration_ms.unwrap_or(0));
+///         }
 ///         ProgressEvent::FileStarted { path } => {
 ///             println!("Processing: {}", path);
 ///         }
@@ -109,15 +121,35 @@ pub type Timestamp = String;
 ///                 println!("{} ... ✗ {}", path, error.as_deref().unwrap_or("error"));
 ///             }
 ///         }
+///         ProgressEvent::BatchCompleted { successful, failed, .. } => {
+///             println!("Done: {} ok, {} failed", successful, failed);
+///         }
 ///     }
 /// };
 ///
 /// // Pass callback to core function
-/// // scan_path(&Path::new("./photos"), true, None, None, false, 4, None, Some(&callback))?;
+/// // scan_path(&Path::new("./photos"), &ScanConfig::default(), Some(&callback))?;
 /// # Ok::<(), jozin_core::JozinError>(())
 /// ```
-#[derive(Debug, Clone)]
+///
+/// Events serialize as JSON objects tagged with `"event"` (e.g.
+/// `{"event": "file_started", "path": "..."}`), the format of progress logs
+/// (see `util::progress_file`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
 pub enum ProgressEvent {
+    /// Batch processing is about to start (emitted once, before any file)
+    BatchStarted {
+        /// Number of files that will be processed
+        total_files: usize,
+        /// Combined size of those files in bytes
+        total_bytes: u64,
+        /// Estimated duration in milliseconds (see `util::size_estimate`)
+        estimated_duration_ms: Option<u64>,
+        /// Order in which the files are processed, e.g. `"breadth-first"`
+        /// (see `util::traversal_order`)
+        traversal_order: String,
+    },
     /// File processing started
     FileStarted {
         /// Path to the file being processed
@@ -134,6 +166,15 @@ pub enum ProgressEvent {
         /// File size in bytes (if available)
         size_bytes: Option<u64>,
     },
+    /// Batch processing finished (emitted once, after the last file)
+    BatchCompleted {
+        /// Files processed successfully
+        successful: usize,
+        /// Files that failed
+        failed: usize,
+        /// Files skipped (filtered out or unchanged)
+        skipped: usize,
+    },
 }
 
 // ============================================================================
@@ -151,6 +192,8 @@ pub enum ProgressEvent {
 /// - `started_at`: RFC3339 timestamp when the operation began
 /// - `finished_at`: RFC3339 timestamp when the operation completed
 /// - `duration_ms`: Duration in milliseconds (automatically calculated)
+/// - `duration_iso`: The same duration in ISO 8601 format, e.g. `"PT5S"`
+///   (only with the `iso-duration` feature)
 /// - `data`: The actual operation result
 ///
 /// # Example
@@ -175,6 +218,7 @@ pub enum ProgressEvent {
 ///   "started_at": "2025-01-15T14:30:00Z",
 ///   "finished_at": "2025-01-15T14:30:05Z",
 ///   "duration_ms": 5000,
+///   "duration_iso": "PT5S",
 ///   "data": ["file1.jpg", "file2.jpg"]
 /// }
 /// ```
@@ -189,6 +233,11 @@ pub struct OperationResponse<T> {
     /// Duration of the operation in milliseconds
     pub duration_ms: u64,
 
+    /// Duration of the operation as an ISO 8601 duration (`iso-duration`
+    /// feature, see [`util::iso_duration`])
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_iso: Option<String>,
+
     /// The actual operation result
     pub data: T,
 }
@@ -246,6 +295,7 @@ impl<T> OperationResponse<T> {
             started_at: started_at_str,
             finished_at: finished_at_str,
             duration_ms,
+            duration_iso: cfg!(feature = "iso-duration").then(|| util::iso_duration::format_iso_duration(duration_ms)),
             data,
         })
     }
@@ -265,6 +315,9 @@ impl<T> OperationResponse<T> {
 /// - Exit code 3: Validation error (schema mismatch, corrupt sidecar)
 /// - Exit code 4: Internal error (unexpected panics, logic errors)
 ///
+/// `NotSupported` also maps to exit code 1: the request is valid, but this build
+/// (or this machine) lacks the feature or external tool needed to fulfil it.
+///
 /// # Serialization
 ///
 /// This type implements `Serialize` and `Deserialize` for JSON error responses.
@@ -356,6 +409,18 @@ pub enum JozinError {
         /// Error message describing the internal failure
         message: String,
     },
+
+    /// Not supported (exit code 1) - Feature or external tool is unavailable
+    ///
+    /// Use this for:
+    /// - Cargo features that were not compiled in (e.g., `videos`)
+    /// - Required external tools missing from PATH (e.g., `ffmpeg`)
+    /// - Input formats the current build cannot process
+    #[serde(rename = "not_supported")]
+    NotSupported {
+        /// Name of the unavailable feature or tool
+        feature: String,
+    },
 }
 
 impl fmt::Display for JozinError {
@@ -365,6 +430,7 @@ impl fmt::Display for JozinError {
             JozinError::IoError { message } => write!(f, "I/O error: {}", message),
             JozinError::ValidationError { message } => write!(f, "Validation error: {}", message),
             JozinError::InternalError { message } => write!(f, "Internal error: {}", message),
+            JozinError::NotSupported { feature } => write!(f, "Not supported: {}", feature),
         }
     }
 }
@@ -395,6 +461,31 @@ impl From<walkdir::Error> for JozinError {
     }
 }
 
+/// Maps image decoding/encoding failures: unreadable files are I/O errors,
+/// unsupported formats `NotSupported`, corrupt data a validation error.
+#[cfg(any(feature = "faces", feature = "thumbs"))]
+impl From<image::ImageError> for JozinError {
+    fn from(err: image::ImageError) -> Self {
+        use image::ImageError;
+        match err {
+            ImageError::IoError(e) => JozinError::IoError { message: e.to_string() },
+            ImageError::Unsupported(e) => JozinError::NotSupported { feature: e.to_string() },
+            ImageError::Decoding(e) => JozinError::ValidationError {
+                message: format!("Image decode error: {}", e),
+            },
+            ImageError::Encoding(e) => JozinError::InternalError {
+                message: format!("Image encode error: {}", e),
+            },
+            ImageError::Limits(e) => JozinError::InternalError {
+                message: format!("Image exceeds memory limits (out of memory): {}", e),
+            },
+            ImageError::Parameter(e) => JozinError::InternalError {
+                message: format!("Image parameter error: {}", e),
+            },
+        }
+    }
+}
+
 impl JozinError {
     /// Returns the appropriate exit code for this error.
     ///
@@ -403,9 +494,10 @@ impl JozinError {
     /// - IoError → 2
     /// - ValidationError → 3
     /// - InternalError → 4
+    /// - NotSupported → 1 (the user asked for something this build cannot do)
     pub fn exit_code(&self) -> i32 {
         match self {
-            JozinError::UserError { .. } => 1,
+            JozinError::UserError { .. } | JozinError::NotSupported { .. } => 1,
             JozinError::IoError { .. } => 2,
             JozinError::ValidationError { .. } => 3,
             JozinError::InternalError { .. } => 4,
@@ -476,11 +568,32 @@ pub struct PipelineSignature {
 }
 
 impl PipelineSignature {
+    /// Signature of sidecars written by the running Jožin binary: the schema
+    /// version `scan` writes, this crate's version, the default hash
+    /// algorithm, no models, and the current time as `created_at`.
+    ///
+    /// Used by `jozin verify --auto-reference` to find sidecars that a rescan
+    /// with this binary would produce differently.
+    pub fn current() -> Self {
+        PipelineSignature {
+            schema_version: scan::SIDECAR_SCHEMA_VERSION.to_string(),
+            producer_version: env!("CARGO_PKG_VERSION").to_string(),
+            hash_algorithm: util::digest::HashAlgorithm::default().as_str().to_string(),
+            face_model: None,
+            tag_model: None,
+            created_at: OffsetDateTime::now_utc()
+                .format(&time::format_description::well_known::Rfc3339)
+                .unwrap_or_default(),
+        }
+    }
+
     /// Checks if two pipeline signatures are compatible.
     ///
     /// Signatures are compatible if they have the same `schema_version` and
-    /// `hash_algorithm`. Different producer versions or model versions are
-    /// considered compatible if the core schema hasn't changed.
+    /// `hash_algorithm`, and don't name different face or tag models.
+    /// Different producer versions are considered compatible if the core
+    /// schema hasn't changed, and a model only one side has run is no
+    /// conflict (see [`PipelineSignature::incompatible_fields()`]).
     ///
     /// This is used by the verify module to determine if a sidecar needs
     /// rescanning due to fundamental pipeline changes.
@@ -511,8 +624,68 @@ impl PipelineSignature {
     /// assert!(sig1.is_compatible_with(&sig2)); // Compatible despite version difference
     /// ```
     pub fn is_compatible_with(&self, other: &PipelineSignature) -> bool {
-        self.schema_version == other.schema_version
-            && self.hash_algorithm == other.hash_algorithm
+        self.incompatible_fields(other).is_empty()
+    }
+
+    /// Lists the fields that make two signatures incompatible (empty if they
+    /// are compatible).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jozin_core::{IncompatibleField, PipelineSignature};
+    ///
+    /// let stored = PipelineSignature { face_model: Some("arcface-1.4".to_string()), ..PipelineSignature::current() };
+    /// let reference = PipelineSignature { face_model: Some("arcface-2.0".to_string()), ..PipelineSignature::current() };
+    /// assert_eq!(stored.incompatible_fields(&reference), vec![IncompatibleField::FaceModel]);
+    /// ```
+    pub fn incompatible_fields(&self, other: &PipelineSignature) -> Vec<IncompatibleField> {
+        let models_differ = |a: &Option<String>, b: &Option<String>| matches!((a, b), (Some(a), Some(b)) if a != b);
+
+        let mut fields = Vec::new();
+        if self.schema_version != other.schema_version {
+            fields.push(IncompatibleField::SchemaVersion);
+        }
+        if self.hash_algorithm != other.hash_algorithm {
+            fields.push(IncompatibleField::HashAlgorithm);
+        }
+        if models_differ(&self.face_model, &other.face_model) {
+            fields.push(IncompatibleField::FaceModel);
+        }
+        if models_differ(&self.tag_model, &other.tag_model) {
+            fields.push(IncompatibleField::TagModel);
+        }
+        fields
+    }
+}
+
+/// A [`PipelineSignature`] field that differs between two incompatible
+/// signatures.
+///
+/// # Variants
+///
+/// - `SchemaVersion`: Different sidecar schema (migrate or rescan)
+/// - `HashAlgorithm`: Hashes can't be compared (rescan)
+/// - `FaceModel`: Faces were detected with another model
+/// - `TagModel`: Tags were assigned by another model
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IncompatibleField {
+    SchemaVersion,
+    HashAlgorithm,
+    FaceModel,
+    TagModel,
+}
+
+impl fmt::Display for IncompatibleField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            IncompatibleField::SchemaVersion => "schema_version",
+            IncompatibleField::HashAlgorithm => "hash_algorithm",
+            IncompatibleField::FaceModel => "face_model",
+            IncompatibleField::TagModel => "tag_model",
+        };
+        f.write_str(name)
     }
 }
 
@@ -531,6 +704,9 @@ impl PipelineSignature {
 /// - `score`: Detection confidence score (0-1)
 /// - `embedding_hash`: Optional hash of face embedding vector for privacy
 /// - `person`: Optional identified person name (if `--identify` was used)
+/// - `landmarks`: Optional 5-point facial landmarks (if `--landmarks` was used)
+/// - `age_estimate`: Optional estimated age in years (if `--estimate-age` was used)
+/// - `cluster_id`: Optional face cluster the face was grouped into
 ///
 /// # Example
 ///
@@ -563,6 +739,26 @@ pub struct FaceDetection {
     /// None if face was detected but not identified.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub person: Option<String>,
+
+    /// Optional 5-point facial landmarks as `[x, y]` normalized to 0-1 range.
+    /// Order: left eye, right eye, nose tip, left mouth corner, right mouth corner.
+    /// Populated by detection models that output landmarks (`--landmarks`);
+    /// used to align face crops before embedding generation.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub landmarks: Option<[[f32; 2]; 5]>,
+
+    /// Optional estimated age in years from a separate age estimation model.
+    /// Populated only with `--estimate-age` and only for faces scoring at
+    /// least `--min-score`. Personal data: `jozin verify --check-privacy`
+    /// flags sidecars that contain it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub age_estimate: Option<f32>,
+
+    /// Optional ID (a UUID string) of the cluster of similar unknown faces
+    /// this face belongs to. Naming a cluster (`jozin faces --assign-cluster`)
+    /// sets `person` on all of its faces; `--clear-clusters` resets it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cluster_id: Option<String>,
 }
 
 /// Tag source type indicating how a tag was assigned.
@@ -612,7 +808,8 @@ pub enum TagSource {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Tag {
     /// Tag label text (e.g., "sunset", "beach", "portrait").
-    /// Should be lowercase for consistency.
+    /// Stored normalised (NFC, lowercase, single spaces; see
+    /// [`util::unicode_normalise::normalise_label()`]).
     pub label: String,
 
     /// Optional confidence score (0-1) for ML-assigned tags.
@@ -626,6 +823,103 @@ pub struct Tag {
     pub source: TagSource,
 }
 
+impl TagSource {
+    /// Name used in JSON and in the `label:score:source` tag syntax.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TagSource::Ml => "ml",
+            TagSource::Rules => "rules",
+            TagSource::User => "user",
+        }
+    }
+}
+
+impl fmt::Display for TagSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for TagSource {
+    type Err = JozinError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "ml" => Ok(TagSource::Ml),
+            "rules" => Ok(TagSource::Rules),
+            "user" => Ok(TagSource::User),
+            other => Err(JozinError::ValidationError {
+                message: format!("Unknown tag source '{}' (expected ml, rules or user)", other),
+            }),
+        }
+    }
+}
+
+impl Tag {
+    /// Creates a user tag without a score; the label is normalised.
+    pub fn from_label(label: &str) -> Tag {
+        Tag {
+            label: util::unicode_normalise::normalise_label(label),
+            score: None,
+            source: TagSource::User,
+        }
+    }
+}
+
+/// Formats a tag as `label:score:source`, leaving the score empty if there
+/// is none (`"sunset:0.89:ml"`, `"vacation::user"`).
+impl fmt::Display for Tag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:", self.label)?;
+        if let Some(score) = self.score {
+            write!(f, "{}", score)?;
+        }
+        write!(f, ":{}", self.source)
+    }
+}
+
+/// Parses `label[:score[:source]]`, the inverse of `Display`.
+///
+/// An empty or missing score is `None`; a missing source is `user`. Labels
+/// cannot contain `:` and are normalised.
+///
+/// # Example
+///
+/// ```
+/// use jozin_core::{Tag, TagSource};
+///
+/// let tag: Tag = "sunset:0.89:ml".parse()?;
+/// assert_eq!((tag.score, tag.source), (Some(0.89), TagSource::Ml));
+/// assert_eq!("vacation".parse::<Tag>()?.to_string(), "vacation::user");
+/// # Ok::<(), jozin_core::JozinError>(())
+/// ```
+impl FromStr for Tag {
+    type Err = JozinError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = |reason: &str| JozinError::ValidationError {
+            message: format!("Invalid tag '{}': {}", s, reason),
+        };
+        let mut parts = s.splitn(3, ':');
+        let label = util::unicode_normalise::normalise_label(parts.next().unwrap_or_default());
+        if label.is_empty() {
+            return Err(invalid("label is empty"));
+        }
+        let score = match parts.next().map(str::trim) {
+            None | Some("") => None,
+            Some(score) => match score.parse::<f32>() {
+                Ok(score) if (0.0..=1.0).contains(&score) => Some(score),
+                _ => return Err(invalid("score must be a number between 0 and 1")),
+            },
+        };
+        let source = match parts.next() {
+            Some(source) => source.trim().parse()?,
+            None => TagSource::User,
+        };
+        Ok(Tag { label, score, source })
+    }
+}
+
 /// Thumbnail file information.
 ///
 /// This struct is populated by the thumbs module (Phase 2+) and records the
@@ -635,7 +929,15 @@ pub struct Tag {
 ///
 /// - `path`: Relative or absolute path to thumbnail file
 /// - `size`: Thumbnail size in pixels (e.g., 256, 512)
-/// - `format`: Image format ("jpg" or "webp")
+/// - `format`: Image format: "jpg" or "webp" in schema 1.0.0, the MIME type
+///   ("image/jpeg" or "image/webp") from schema 2.0.0 on
+/// - `source_timestamp_sec`: Frame timestamp for video thumbnails (optional)
+/// - `dominant_color`: Average colour of the thumbnail as `"#RRGGBB"`
+///   (optional, `jozin thumbs --dominant-color`)
+/// - `from_exif`: The thumbnail was taken from the thumbnail embedded in the
+///   source's EXIF instead of decoding the full image
+/// - `tonemap`: Tone mapping applied to an HDR source ("reinhard" or
+///   "filmic"; optional)
 ///
 /// # Example
 ///
@@ -655,9 +957,60 @@ pub struct ThumbnailInfo {
     /// Controlled by `--sizes` parameter in thumbs module.
     pub size: u32,
 
-    /// Image format: "jpg" or "webp".
+    /// Image format: "jpg" or "webp" (schema 1.0.0), or its MIME type (2.0.0).
     /// Controlled by `--format` parameter in thumbs module.
     pub format: String,
+
+    /// Timestamp (seconds into the video) of the frame this thumbnail was taken from.
+    /// Only set for video thumbnails; None for still images.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_timestamp_sec: Option<f32>,
+
+    /// Dominant colour of the thumbnail pixels as a CSS hex colour (e.g., "#3A7BD5").
+    /// Only computed with `jozin thumbs --dominant-color`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dominant_color: Option<String>,
+
+    /// True when the thumbnail came from the JPEG embedded in the source's EXIF
+    /// (small sizes only); omitted when false.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub from_exif: bool,
+
+    /// Tone mapping operator applied because the source is HDR ("reinhard" or
+    /// "filmic"). None for SDR sources and thumbnails made with `--hdr-tonemap none`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tonemap: Option<String>,
+}
+
+impl ThumbnailInfo {
+    /// Euclidean RGB distance (0-441) between `dominant_color` and the hex
+    /// colour `target` (`"#3A7BD5"` or `"3A7BD5"`).
+    ///
+    /// Returns `None` if there is no dominant colour or either colour is not
+    /// valid hex.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jozin_core::ThumbnailInfo;
+    ///
+    /// let thumb = ThumbnailInfo {
+    ///     path: "IMG_1234_256.jpg".to_string(),
+    ///     size: 256,
+    ///     format: "jpg".to_string(),
+    ///     source_timestamp_sec: None,
+    ///     dominant_color: Some("#3A7BD5".to_string()),
+    ///     from_exif: false,
+    ///     tonemap: None,
+    /// };
+    /// assert!(thumb.dominant_color_distance("#3A7BE0").unwrap() <= 20.0);
+    /// ```
+    pub fn dominant_color_distance(&self, target: &str) -> Option<f64> {
+        let own = parse_hex_color(self.dominant_color.as_deref()?)?;
+        let target = parse_hex_color(target)?;
+        let squared: f64 = own.iter().zip(target).map(|(a, b)| (f64::from(*a) - f64::from(b)).powi(2)).sum();
+        Some(squared.sqrt())
+    }
 }
 
 // ============================================================================
@@ -681,6 +1034,7 @@ pub struct ThumbnailInfo {
 /// - `faces` section: Face detection results (Phase 2+)
 /// - `tags` section: ML and user-assigned labels (Phase 2+)
 /// - `thumbnails` section: Generated thumbnail information (Phase 2+)
+/// - `extensions` section: Third-party tool metadata, namespaced per tool (optional)
 ///
 /// # Example JSON
 ///
@@ -700,7 +1054,8 @@ pub struct ThumbnailInfo {
 ///     "file_path": "/photos/2020/IMG_1234.JPG",
 ///     "file_size_bytes": 2048576,
 ///     "file_hash_b3": "a3f2c1d4e5f6...",
-///     "file_modified_at": "2020-06-15T10:30:00Z"
+///     "file_modified_at": "2020-06-15T10:30:00Z",
+///     "ingest_timestamp": "2025-01-15T14:30:00Z"
 ///   },
 ///   "image": {
 ///     "width": 4032,
@@ -715,7 +1070,10 @@ pub struct ThumbnailInfo {
 ///   },
 ///   "faces": [],
 ///   "tags": [],
-///   "thumbnails": []
+///   "thumbnails": [],
+///   "extensions": {
+///     "com.example.myapp": { "rating": 4 }
+///   }
 /// }
 /// ```
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -755,6 +1113,299 @@ pub struct Sidecar {
     /// Generated thumbnails (populated by thumbs module, Phase 2+)
     #[serde(default)]
     pub thumbnails: Vec<ThumbnailInfo>,
+
+    /// Third-party tool metadata, keyed by namespace then key
+    /// (e.g., `{"com.example.lrplugin": {"rating": 4}}`).
+    /// Opaque to Jožin: never validated, and carried over when a sidecar is rewritten.
+    #[serde(default, skip_serializing_if = "serde_json::Map::is_empty")]
+    pub extensions: serde_json::Map<String, serde_json::Value>,
+}
+
+impl Sidecar {
+    /// Stores a third-party value under `extensions[namespace][key]`.
+    ///
+    /// Namespaces should be unique to the tool writing them (reverse-DNS style
+    /// such as `"com.example.myapp"` is recommended). If `extensions[namespace]`
+    /// exists but is not an object, it is replaced.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let mut sidecar: jozin_core::Sidecar = serde_json::from_str(r#"{
+    /// #     "schema_version": "1.0.0", "producer_version": "0.1.0",
+    /// #     "created_at": "2024-01-01T00:00:00Z", "updated_at": "2024-01-01T00:00:00Z",
+    /// #     "pipeline_signature": {"schema_version": "1.0.0", "producer_version": "0.1.0",
+    /// #         "hash_algorithm": "blake3", "created_at": "2024-01-01T00:00:00Z"},
+    /// #     "source": {"file_path": "photo.jpg", "file_size_bytes": 0,
+    /// #         "file_modified_at": "2024-01-01T00:00:00Z"}
+    /// # }"#)?;
+    /// sidecar.set_extension("com.example.myapp", "rating", serde_json::json!(4));
+    /// assert_eq!(
+    ///     sidecar.get_extension("com.example.myapp", "rating"),
+    ///     Some(&serde_json::json!(4))
+    /// );
+    /// # Ok::<(), serde_json::Error>(())
+    /// ```
+    pub fn set_extension(&mut self, namespace: &str, key: &str, value: serde_json::Value) {
+        let entry = self
+            .extensions
+            .entry(namespace.to_string())
+            .or_insert_with(|| serde_json::Value::Object(serde_json::Map::new()));
+
+        if !entry.is_object() {
+            *entry = serde_json::Value::Object(serde_json::Map::new());
+        }
+
+        if let serde_json::Value::Object(map) = entry {
+            map.insert(key.to_string(), value);
+        }
+    }
+
+    /// Returns the value stored under `extensions[namespace][key]`, if any.
+    pub fn get_extension(&self, namespace: &str, key: &str) -> Option<&serde_json::Value> {
+        self.extensions.get(namespace)?.get(key)
+    }
+
+    /// Merges tags into this sidecar.
+    ///
+    /// - `append = true`: existing tags are kept; new tags are added unless a tag
+    ///   with the same label (case-insensitive) already exists
+    /// - `append = false`: existing tags are replaced
+    ///
+    /// Labels are normalised first (see [`util::unicode_normalise`]).
+    ///
+    /// Returns the number of tags added.
+    pub fn merge_tags(&mut self, tags: Vec<Tag>, append: bool) -> usize {
+        let tags = tags.into_iter().map(|tag| Tag {
+            label: util::unicode_normalise::normalise_label(&tag.label),
+            ..tag
+        });
+        if !append {
+            self.tags = tags.collect();
+            self.update_summary_counts();
+            return self.tags.len();
+        }
+
+        let mut added = 0;
+        for tag in tags {
+            let exists = self
+                .tags
+                .iter()
+                .any(|t| t.label.eq_ignore_ascii_case(&tag.label));
+            if !exists {
+                self.tags.push(tag);
+                added += 1;
+            }
+        }
+        self.update_summary_counts();
+        added
+    }
+
+    /// Updates `ImageInfo::faces_count` and `ImageInfo::tag_count` from the
+    /// `faces` and `tags` arrays.
+    ///
+    /// Does nothing for sidecars without an `image` section. Call after
+    /// changing `faces` or `tags` directly; [`Sidecar::merge_tags()`] calls it
+    /// itself.
+    pub fn update_summary_counts(&mut self) {
+        let (faces, tags) = (self.faces.len() as u32, self.tags.len() as u32);
+        if let Some(image) = self.image.as_mut() {
+            image.faces_count = Some(faces);
+            image.tag_count = Some(tags);
+        }
+    }
+
+    /// Checks the sidecar for internal consistency.
+    ///
+    /// - `schema_version` is supported and matches `pipeline_signature.schema_version`
+    /// - `source.file_path` is set, as is a content hash (BLAKE3 or SHA-256)
+    /// - `created_at` and `updated_at` are RFC3339
+    /// - Face scores are within 0-1
+    /// - `image.faces_count` / `image.tag_count`, if set, match `faces` / `tags`
+    ///
+    /// # Errors
+    ///
+    /// Returns `JozinError::ValidationError` listing every problem found.
+    pub fn validate(&self) -> Result<()> {
+        use time::format_description::well_known::Rfc3339;
+
+        let mut problems = Vec::new();
+        if !migrate::SUPPORTED_SCHEMA_VERSIONS.contains(&self.schema_version.as_str()) {
+            problems.push(format!("unsupported schema_version '{}'", self.schema_version));
+        }
+        if self.pipeline_signature.schema_version != self.schema_version {
+            problems.push(format!(
+                "pipeline_signature.schema_version '{}' does not match schema_version '{}'",
+                self.pipeline_signature.schema_version, self.schema_version
+            ));
+        }
+        if self.source.file_path.is_empty() {
+            problems.push("source.file_path is empty".to_string());
+        }
+        if self.source.file_hash_b3.is_empty() && self.source.file_hash_sha256.is_none() {
+            problems.push("source has no content hash".to_string());
+        }
+        for (field, value) in [("created_at", &self.created_at), ("updated_at", &self.updated_at)] {
+            if time::OffsetDateTime::parse(value, &Rfc3339).is_err() {
+                problems.push(format!("{} '{}' is not RFC3339", field, value));
+            }
+        }
+        if let Some(face) = self.faces.iter().find(|f| !(0.0..=1.0).contains(&f.score)) {
+            problems.push(format!("face score {} is outside 0-1", face.score));
+        }
+        if let Some(image) = &self.image {
+            if image.faces_count.is_some_and(|n| n as usize != self.faces.len()) {
+                problems.push("image.faces_count does not match faces".to_string());
+            }
+            if image.tag_count.is_some_and(|n| n as usize != self.tags.len()) {
+                problems.push("image.tag_count does not match tags".to_string());
+            }
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(JozinError::ValidationError {
+                message: format!("Invalid sidecar: {}", problems.join("; ")),
+            })
+        }
+    }
+
+    /// Compares two sidecars field by field.
+    ///
+    /// Both sidecars are compared in their serialized JSON form. Nested objects
+    /// are descended into, so a change is reported at the deepest differing key
+    /// (e.g., `pipeline_signature.schema_version`). Arrays (faces, tags,
+    /// thumbnails) are compared as a whole and reported at the array's path.
+    ///
+    /// # Returns
+    ///
+    /// Changes sorted by path; empty if the sidecars serialize identically.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let old: jozin_core::Sidecar = serde_json::from_str(r#"{
+    /// #     "schema_version": "1.0.0", "producer_version": "0.1.0",
+    /// #     "created_at": "2024-01-01T00:00:00Z", "updated_at": "2024-01-01T00:00:00Z",
+    /// #     "pipeline_signature": {"schema_version": "1.0.0", "producer_version": "0.1.0",
+    /// #         "hash_algorithm": "blake3", "created_at": "2024-01-01T00:00:00Z"},
+    /// #     "source": {"file_path": "photo.jpg", "file_size_bytes": 0,
+    /// #         "file_modified_at": "2024-01-01T00:00:00Z"}
+    /// # }"#)?;
+    /// let mut new = old.clone();
+    /// new.schema_version = "2.0.0".to_string();
+    /// let changes = jozin_core::Sidecar::diff(&old, &new);
+    /// assert_eq!(changes[0].path, "schema_version");
+    /// # Ok::<(), serde_json::Error>(())
+    /// ```
+    pub fn diff(old: &Sidecar, new: &Sidecar) -> Vec<FieldChange> {
+        let old = serde_json::to_value(old).unwrap_or_default();
+        let new = serde_json::to_value(new).unwrap_or_default();
+        let mut changes = Vec::new();
+        diff_values("", Some(&old), Some(&new), &mut changes);
+        changes
+    }
+
+    /// Metadata completeness score in 0.0-1.0, for ranking a library by quality.
+    ///
+    /// | Present                                  | Weight |
+    /// |------------------------------------------|--------|
+    /// | `image.datetime_original`                | 0.15   |
+    /// | `image.gps_latitude` and `gps_longitude` | 0.15   |
+    /// | `image.camera_make`                      | 0.10   |
+    /// | `image.camera_model`                     | 0.10   |
+    /// | At least one tag                         | 0.15   |
+    /// | At least one face                        | 0.10   |
+    /// | At least one thumbnail                   | 0.10   |
+    /// | `image.width` and `image.height`         | 0.10   |
+    /// | `image.orientation`                      | 0.05   |
+    ///
+    /// A sidecar with every field present scores exactly 1.0.
+    pub fn score(&self) -> f32 {
+        // Weights in hundredths so that a complete sidecar sums to exactly 100
+        let mut points = 0u32;
+        if let Some(image) = &self.image {
+            if image.datetime_original.is_some() {
+                points += 15;
+            }
+            if image.gps_latitude.is_some() && image.gps_longitude.is_some() {
+                points += 15;
+            }
+            if image.camera_make.is_some() {
+                points += 10;
+            }
+            if image.camera_model.is_some() {
+                points += 10;
+            }
+            if image.width.is_some() && image.height.is_some() {
+                points += 10;
+            }
+            if image.orientation.is_some() {
+                points += 5;
+            }
+        }
+        if !self.tags.is_empty() {
+            points += 15;
+        }
+        if !self.faces.is_empty() {
+            points += 10;
+        }
+        if !self.thumbnails.is_empty() {
+            points += 10;
+        }
+        points as f32 / 100.0
+    }
+}
+
+/// A single field difference between two sidecars (see [`Sidecar::diff()`]).
+///
+/// # Fields
+///
+/// - `path`: Dotted JSON path of the field (e.g., `"image.camera_make"`)
+/// - `old`: Previous value, `None` if the field was added
+/// - `new`: New value, `None` if the field was removed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldChange {
+    pub path: String,
+    pub old: Option<serde_json::Value>,
+    pub new: Option<serde_json::Value>,
+}
+
+/// Parses `"#RRGGBB"` (the `#` is optional, case-insensitive).
+fn parse_hex_color(color: &str) -> Option<[u8; 3]> {
+    let hex = color.strip_prefix('#').unwrap_or(color);
+    if hex.len() != 6 || !hex.is_ascii() {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some([channel(0)?, channel(2)?, channel(4)?])
+}
+
+/// Recursively collects differences between two JSON values.
+fn diff_values(
+    path: &str,
+    old: Option<&serde_json::Value>,
+    new: Option<&serde_json::Value>,
+    changes: &mut Vec<FieldChange>,
+) {
+    use serde_json::Value;
+
+    if let (Some(Value::Object(old_map)), Some(Value::Object(new_map))) = (old, new) {
+        let mut keys: Vec<&String> = old_map.keys().chain(new_map.keys()).collect();
+        keys.sort();
+        keys.dedup();
+        for key in keys {
+            let child = if path.is_empty() { key.clone() } else { format!("{}.{}", path, key) };
+            diff_values(&child, old_map.get(key), new_map.get(key), changes);
+        }
+    } else if old != new {
+        changes.push(FieldChange {
+            path: path.to_string(),
+            old: old.cloned(),
+            new: new.cloned(),
+        });
+    }
 }
 
 /// Original file information section of sidecar.
@@ -766,8 +1417,13 @@ pub struct Sidecar {
 ///
 /// - `file_path`: Path to original file (relative or absolute)
 /// - `file_size_bytes`: File size in bytes
-/// - `file_hash_b3`: BLAKE3 hash in hexadecimal format
+/// - `file_hash_b3`: BLAKE3 hash in hexadecimal format (omitted for SHA-256 scans)
+/// - `file_hash_sha256`: SHA-256 hash in hexadecimal format (SHA-256 scans only)
 /// - `file_modified_at`: File system modification timestamp (RFC3339)
+/// - `ingest_timestamp`: When Jožin first scanned the file (RFC3339)
+/// - `live_photo_path`: Paired Live Photo video (optional)
+/// - `previous_paths`: Earlier paths of a renamed file, oldest first
+/// - `hardlink_of`: Canonical copy this file was hardlinked to by deduplication
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SourceInfo {
     /// Path to original photo file (relative or absolute).
@@ -780,11 +1436,58 @@ pub struct SourceInfo {
 
     /// BLAKE3 hash of file contents in hexadecimal format.
     /// Used for duplicate detection and integrity verification.
+    /// Empty (and omitted) when the library was scanned with SHA-256.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub file_hash_b3: String,
 
+    /// SHA-256 hash of file contents in hexadecimal format.
+    /// Only set by `scan --hash-algorithm sha256` (see [`util::digest`]).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_hash_sha256: Option<String>,
+
     /// File system modification timestamp (RFC3339).
     /// Used to detect if file has changed since last scan.
     pub file_modified_at: Timestamp,
+
+    /// When Jožin first scanned this file (RFC3339).
+    /// Set on the first scan and carried over by every rescan, so it survives
+    /// copies and syncs that reset `file_modified_at`. Sidecars written before
+    /// this field existed fall back to `created_at` when read.
+    #[serde(default)]
+    pub ingest_timestamp: Timestamp,
+
+    /// Path to the paired Live Photo video (`.MOV`) for Apple Live Photo stills.
+    /// Only set by `scan --link-live-photos`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub live_photo_path: Option<String>,
+
+    /// Earlier `file_path` values, oldest first.
+    /// Appended when a scan with a sidecar index recognises a renamed file
+    /// (see [`util::sidecar_index`]); carried over by every rescan.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub previous_paths: Vec<String>,
+
+    /// Path of the canonical file this one is a hardlink of.
+    /// Set when deduplication replaced the file with a hardlink (see
+    /// [`util::hardlink`]); carried over by every rescan.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hardlink_of: Option<String>,
+}
+
+impl SourceInfo {
+    /// Algorithm of the recorded content hash (SHA-256 if `file_hash_sha256` is set).
+    pub fn hash_algorithm(&self) -> util::digest::HashAlgorithm {
+        if self.file_hash_sha256.is_some() {
+            util::digest::HashAlgorithm::Sha256
+        } else {
+            util::digest::HashAlgorithm::Blake3
+        }
+    }
+
+    /// The recorded content hash, whichever algorithm produced it.
+    pub fn file_hash(&self) -> &str {
+        self.file_hash_sha256.as_deref().unwrap_or(&self.file_hash_b3)
+    }
 }
 
 /// EXIF and image metadata section of sidecar.
@@ -793,7 +1496,7 @@ pub struct SourceInfo {
 /// All fields are optional since not all images have complete metadata.
 ///
 /// Populated by the scan module in Phase 1+.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ImageInfo {
     /// Image width in pixels. None if not available or not yet extracted.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -818,14 +1521,26 @@ pub struct ImageInfo {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub datetime_original: Option<String>,
 
-    /// Camera manufacturer from EXIF Make tag (e.g., "Apple", "Canon").
+    /// Camera manufacturer from EXIF Make tag, normalised (e.g., "Apple", "Canon"
+    /// for "Canon Inc."; see `util::camera`).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub camera_make: Option<String>,
 
-    /// Camera model from EXIF Model tag (e.g., "iPhone 12", "EOS 5D Mark IV").
+    /// Camera model from EXIF Model tag without the make (e.g., "iPhone 12",
+    /// "EOS 5D Mark IV" for "Canon EOS 5D Mark IV").
     #[serde(skip_serializing_if = "Option::is_none")]
     pub camera_model: Option<String>,
 
+    /// EXIF Make as written by the camera, when it differs from `camera_make`
+    /// (schema 2.0.0).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub camera_make_raw: Option<String>,
+
+    /// EXIF Model as written by the camera, when it differs from `camera_model`
+    /// (schema 2.0.0).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub camera_model_raw: Option<String>,
+
     /// GPS latitude from EXIF GPSLatitude tag (decimal degrees).
     /// Positive values indicate North, negative indicate South.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -835,5 +1550,172 @@ pub struct ImageInfo {
     /// Positive values indicate East, negative indicate West.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub gps_longitude: Option<f64>,
+
+    /// GPS altitude from EXIF GPSAltitude/GPSAltitudeRef (meters).
+    /// Negative values indicate below sea level.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gps_altitude_m: Option<f64>,
+
+    /// Full raw EXIF as a flat object keyed `<IFD>.<TagName>` (e.g., `"ExifIFD.FocalLength"`).
+    /// Only populated with `scan --include-raw-exif`; capped at 50 KiB.
+    /// Treated as an opaque appendix: verify does not validate its content.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exif_json: Option<serde_json::Value>,
+
+    /// Color space from EXIF ColorSpace tag ("sRGB", "Adobe RGB", "Uncalibrated"),
+    /// or the embedded ICC profile's description (e.g. "Display P3") when EXIF
+    /// names none or "Uncalibrated" (see `util::icc_profile`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color_space: Option<String>,
+
+    /// Lens model from EXIF LensModel tag (e.g., "EF24-70mm f/2.8L II USM").
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lens_model: Option<String>,
+
+    /// Number of entries in `Sidecar::faces`, denormalised for fast filtering.
+    /// Kept in sync by [`Sidecar::update_summary_counts()`]; verify warns on drift.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub faces_count: Option<u32>,
+
+    /// Number of entries in `Sidecar::tags`, denormalised for fast filtering.
+    /// Kept in sync by [`Sidecar::update_summary_counts()`]; verify warns on drift.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag_count: Option<u32>,
+
+    /// True for HDR captures (gain map or HDR tone metadata, see
+    /// [`exif::detect_hdr()`]); None when no HDR marker was found.
+    /// Thumbnails of HDR images are tone mapped for SDR displays.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hdr: Option<bool>,
 }
 
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scan::scan_file;
+    use tempfile::TempDir;
+
+    fn scanned_sidecar() -> Sidecar {
+        let temp_dir = TempDir::new().unwrap();
+        let photo = temp_dir.path().join("IMG_0001.jpg");
+        std::fs::write(&photo, b"image").unwrap();
+        scan_file(&photo, false).unwrap().sidecar
+    }
+
+    #[test]
+    fn test_score_boundaries() {
+        let mut sidecar = scanned_sidecar();
+        sidecar.image = None;
+        sidecar.tags.clear();
+        sidecar.faces.clear();
+        sidecar.thumbnails.clear();
+        assert_eq!(sidecar.score(), 0.0);
+
+        sidecar.image = Some(ImageInfo {
+            datetime_original: Some("2024-06-01T12:00:00Z".to_string()),
+            gps_latitude: Some(50.08),
+            gps_longitude: Some(14.42),
+            camera_make: Some("Apple".to_string()),
+            camera_model: Some("iPhone 12".to_string()),
+            width: Some(4032),
+            height: Some(3024),
+            orientation: Some(1),
+            ..ImageInfo::default()
+        });
+        // Latitude alone does not count as a GPS location
+        sidecar.image.as_mut().unwrap().gps_longitude = None;
+        assert_eq!(sidecar.score(), 0.5);
+        sidecar.image.as_mut().unwrap().gps_longitude = Some(14.42);
+
+        sidecar.tags.push(Tag {
+            label: "portfolio".to_string(),
+            score: None,
+            source: TagSource::User,
+        });
+        sidecar.faces.push(FaceDetection {
+            bbox: [0.1, 0.1, 0.2, 0.2],
+            score: 0.9,
+            embedding_hash: None,
+            person: None,
+            landmarks: None,
+            age_estimate: None,
+            cluster_id: None,
+        });
+        sidecar.thumbnails.push(ThumbnailInfo {
+            path: "IMG_0001_256.jpg".to_string(),
+            size: 256,
+            format: "jpeg".to_string(),
+            source_timestamp_sec: None,
+            dominant_color: None,
+            from_exif: false,
+            tonemap: None,
+        });
+        assert_eq!(sidecar.score(), 1.0);
+    }
+
+    #[test]
+    fn test_tag_display_from_str_round_trip() {
+        let tags = [
+            Tag { label: "sunset".to_string(), score: Some(0.89), source: TagSource::Ml },
+            Tag { label: "vacation".to_string(), score: None, source: TagSource::User },
+            Tag { label: "blurry".to_string(), score: Some(1.0), source: TagSource::Rules },
+        ];
+        for tag in tags {
+            let parsed: Tag = tag.to_string().parse().unwrap();
+            assert_eq!((parsed.label, parsed.score, parsed.source), (tag.label, tag.score, tag.source));
+        }
+        assert_eq!(Tag { score: Some(0.89), source: TagSource::Ml, ..Tag::from_label("sunset") }.to_string(), "sunset:0.89:ml");
+        assert_eq!(Tag::from_label("vacation").to_string(), "vacation::user");
+
+        let short: Tag = "beach:0.5".parse().unwrap();
+        assert_eq!((short.score, short.source), (Some(0.5), TagSource::User));
+        for invalid in ["", ":0.5:ml", "a:high:ml", "a:1.5:ml", "a::robot", "a:0.5:ml:extra"] {
+            assert!(matches!(invalid.parse::<Tag>(), Err(JozinError::ValidationError { .. })), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_operation_response_iso_duration() {
+        let start = OffsetDateTime::now_utc();
+        let end = start + time::Duration::milliseconds(90_061_500);
+        let response = OperationResponse::new((), start, end).unwrap();
+        assert_eq!(response.duration_ms, 90_061_500);
+
+        let json = serde_json::to_value(&response).unwrap();
+        if cfg!(feature = "iso-duration") {
+            assert_eq!(json["duration_iso"], "P1DT1H1M1.5S");
+            let parsed = util::iso_duration::parse_iso_duration(response.duration_iso.as_deref().unwrap()).unwrap();
+            assert_eq!(parsed, response.duration_ms);
+        } else {
+            assert!(json.get("duration_iso").is_none());
+        }
+    }
+
+    #[cfg(any(feature = "faces", feature = "thumbs"))]
+    #[test]
+    fn test_image_error_conversion() {
+        use image::error::{DecodingError, ImageFormatHint, LimitError, LimitErrorKind, UnsupportedError, UnsupportedErrorKind};
+        use image::ImageError;
+
+        let io = ImageError::IoError(std::io::Error::new(std::io::ErrorKind::NotFound, "gone"));
+        assert!(matches!(JozinError::from(io), JozinError::IoError { ref message } if message == "gone"));
+
+        let unsupported = ImageError::Unsupported(UnsupportedError::from_format_and_kind(
+            ImageFormatHint::Name("HEIC".into()),
+            UnsupportedErrorKind::Format(ImageFormatHint::Name("HEIC".into())),
+        ));
+        assert!(matches!(JozinError::from(unsupported), JozinError::NotSupported { .. }));
+
+        let decoding = ImageError::Decoding(DecodingError::new(ImageFormatHint::Unknown, "truncated"));
+        let err = JozinError::from(decoding);
+        assert!(matches!(err, JozinError::ValidationError { ref message } if message.starts_with("Image decode error")));
+        assert_eq!(err.exit_code(), 3);
+
+        let limits = ImageError::Limits(LimitError::from_kind(LimitErrorKind::InsufficientMemory));
+        assert!(matches!(JozinError::from(limits), JozinError::InternalError { ref message } if message.contains("out of memory")));
+    }
+}
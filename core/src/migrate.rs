@@ -6,4 +6,837 @@
 //! - Creating backup files during migration
 //! - Handling migration failures gracefully
 //!
-//! Phase 1 stub - full implementation to be added in subsequent phases.
+//! ## Migration Path
+//!
+//! | From    | To      | Step                      | Changes                                                 |
+//! |---------|---------|---------------------------|---------------------------------------------------------|
+//! | `1.0.0` | `2.0.0` | [`v1_to_v2`]              | `color_space`, `lens_model`, `faces_count`, `tag_count`, thumbnail MIME types, versions, `updated_at` |
+//!
+//! Steps are looked up in a [`MigrationRegistry`] and applied in memory by
+//! [`migrate_sidecar()`]; third-party `extensions` are always carried over
+//! unchanged.
+//!
+//! ## Preview
+//!
+//! [`preview_migration()`] applies the migration to every sidecar in memory and
+//! returns per-file [`FieldChange`] lists without writing anything
+//! (`jozin migrate --preview`).
+//!
+//! ## Quick Count
+//!
+//! [`count_pending()`] skips JSON parsing entirely: it reads only the first
+//! [`COUNT_HEADER_LEN`] bytes of each sidecar, where `schema_version` appears
+//! as the first field of pretty-printed sidecars (`jozin migrate --dry-run --count`).
+//!
+//! ## Writing
+//!
+//! [`migrate_path()`] writes migrated sidecars atomically, keeping the
+//! previous version as `.bak1` (rotating `.bak1` → `.bak2` → `.bak3`).
+//! With [`SourcePreference::Backup1`] it migrates `.bak1` instead and
+//! promotes the result to the current sidecar; the replaced sidecar becomes
+//! `.bak2` and older backups move up, so the backup that was migrated stays
+//! `.bak1` (`jozin migrate --from-backup`). This repairs sidecars damaged by
+//! a faulty migration.
+//!
+//! | Before          | After `--from-backup` |
+//! |-----------------|-----------------------|
+//! | `.json` (bad)   | `.json` (`.bak1` migrated) |
+//! | `.bak1` (good)  | `.bak1` (good)        |
+//! | `.bak2`         | `.bak2` (bad)         |
+//! | `.bak3`         | `.bak3` (old `.bak2`) |
+
+use crate::scan::{append_to_file_name, find_sidecar_files, parse_sidecar, write_sidecar_json};
+use crate::util::atomic_rename::atomic_rename;
+use crate::util::backup_restore::MAX_BACKUP_DEPTH;
+use crate::util::compression::{encode_sidecar, is_compressed_path, read_sidecar_file};
+use crate::util::fsync_policy::FsyncPolicy;
+use crate::{FieldChange, JozinError, Result, Sidecar};
+use flate2::read::GzDecoder;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::RandomState;
+use std::fs::{self, File};
+use std::hash::BuildHasher;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+pub mod v1_to_v2;
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+/// Schema versions this build can read and migrate to, oldest first.
+pub const SUPPORTED_SCHEMA_VERSIONS: &[&str] = &["1.0.0", "2.0.0"];
+
+/// Newest schema version this build understands (last of [`SUPPORTED_SCHEMA_VERSIONS`]).
+pub const CURRENT_SCHEMA_VERSION: &str = "2.0.0";
+
+/// Bytes read from each sidecar by [`count_pending()`].
+pub const COUNT_HEADER_LEN: u64 = 200;
+
+// ============================================================================
+// Public Types
+// ============================================================================
+
+/// Field changes a migration would make to one sidecar.
+///
+/// # Fields
+///
+/// - `sidecar_path`: Path to the sidecar JSON file
+/// - `from_version`: Schema version currently on disk
+/// - `changes`: Fields that would be added, changed or removed
+/// - `old`, `new`: The sidecar before and after the migration, as JSON (for
+///   [`crate::util::diff_json`]; not serialized)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SidecarDiff {
+    pub sidecar_path: PathBuf,
+    pub from_version: String,
+    pub changes: Vec<FieldChange>,
+    #[serde(skip)]
+    pub old: serde_json::Value,
+    #[serde(skip)]
+    pub new: serde_json::Value,
+}
+
+/// Result of previewing a migration (see [`preview_migration()`]).
+///
+/// # Fields
+///
+/// - `to_version`: Target schema version
+/// - `total_sidecars`: Sidecars found and parsed
+/// - `affected_sidecars`: Sidecars the migration would change
+/// - `total_field_changes`: Field changes summed over all affected sidecars
+/// - `unreadable`: Sidecar files that could not be parsed (skipped)
+/// - `samples`: Diffs of the sampled (or all) affected sidecars
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MigrationPreview {
+    pub to_version: String,
+    pub total_sidecars: usize,
+    pub affected_sidecars: usize,
+    pub total_field_changes: usize,
+    pub unreadable: usize,
+    pub samples: Vec<SidecarDiff>,
+}
+
+impl MigrationPreview {
+    /// Average number of changed fields per affected sidecar (0.0 if none).
+    pub fn average_changes_per_file(&self) -> f64 {
+        if self.affected_sidecars == 0 {
+            0.0
+        } else {
+            self.total_field_changes as f64 / self.affected_sidecars as f64
+        }
+    }
+}
+
+/// A single schema upgrade, applied in place.
+///
+/// The flag tells the step whether the photo at `source.file_path` can be
+/// read (e.g. to fill new EXIF fields).
+pub type MigrationFn = fn(&mut Sidecar, bool) -> Result<()>;
+
+/// A registered upgrade from one schema version to the next.
+#[derive(Debug, Clone, Copy)]
+pub struct MigrationStep {
+    pub from: &'static str,
+    pub to: &'static str,
+    pub apply: MigrationFn,
+}
+
+/// The upgrade steps between [`SUPPORTED_SCHEMA_VERSIONS`].
+///
+/// `MigrationRegistry::default()` contains every step shipped with this
+/// build.
+#[derive(Debug, Clone)]
+pub struct MigrationRegistry {
+    steps: Vec<MigrationStep>,
+}
+
+impl Default for MigrationRegistry {
+    fn default() -> Self {
+        let mut registry = Self { steps: Vec::new() };
+        registry.register(MigrationStep {
+            from: "1.0.0",
+            to: "2.0.0",
+            apply: v1_to_v2::migrate_v1_to_v2,
+        });
+        registry
+    }
+}
+
+/// Result of a quick count (see [`count_pending()`]).
+///
+/// # Fields
+///
+/// - `would_migrate`: Sidecars whose `schema_version` differs from the target
+/// - `already_current`: Sidecars already at the target version
+/// - `unreadable`: Sidecars that could not be read or have no `schema_version` in their header
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MigrateCounts {
+    pub would_migrate: usize,
+    pub already_current: usize,
+    pub unreadable: usize,
+}
+
+/// Which copy of each sidecar [`migrate_path()`] migrates.
+///
+/// # Variants
+///
+/// - `Current`: The sidecar itself
+/// - `Backup1`: Its newest backup (`.bak1`), promoted to the current sidecar
+/// - `Backup2`: Its second backup (`.bak2`), promoted to the current sidecar
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SourcePreference {
+    #[default]
+    Current,
+    Backup1,
+    Backup2,
+}
+
+impl SourcePreference {
+    /// Backup number of the source (`None` for the current sidecar).
+    fn backup_number(self) -> Option<u8> {
+        match self {
+            SourcePreference::Current => None,
+            SourcePreference::Backup1 => Some(1),
+            SourcePreference::Backup2 => Some(2),
+        }
+    }
+}
+
+/// Options for [`migrate_path()`].
+///
+/// # Fields
+///
+/// - `dry_run`: Migrate in memory only, don't write anything
+/// - `backup`: Keep the replaced sidecar as a `.bakN` backup (default: true)
+#[derive(Debug, Clone)]
+pub struct MigrateOptions {
+    pub dry_run: bool,
+    pub backup: bool,
+}
+
+impl Default for MigrateOptions {
+    fn default() -> Self {
+        Self { dry_run: false, backup: true }
+    }
+}
+
+/// What [`migrate_path()`] did with one sidecar.
+///
+/// # Variants
+///
+/// - `Migrated`: Written (or with `dry_run`, would be written) at the target
+///   version; `from_version` is the version of the source copy
+/// - `AlreadyCurrent`: The sidecar is already at the target version
+/// - `NoBackupAvailable`: The backup selected by the [`SourcePreference`]
+///   doesn't exist
+/// - `Failed`: The source could not be read, migrated or written
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "status")]
+pub enum MigrateStatus {
+    Migrated { from_version: String },
+    AlreadyCurrent,
+    NoBackupAvailable,
+    Failed { error: String },
+}
+
+/// Outcome of migrating one sidecar.
+///
+/// # Fields
+///
+/// - `sidecar_path`: The current sidecar
+/// - `source_path`: The copy that was migrated (the sidecar or a backup)
+/// - `status`: What happened
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MigrateFileResult {
+    pub sidecar_path: PathBuf,
+    pub source_path: PathBuf,
+    #[serde(flatten)]
+    pub status: MigrateStatus,
+}
+
+/// Result of [`migrate_path()`].
+///
+/// # Fields
+///
+/// - `to_version`: Target schema version
+/// - `source_preference`: Which copy of each sidecar was migrated
+/// - `migrated`, `already_current`, `no_backup_available`, `failed`: Counts
+///   per [`MigrateStatus`]
+/// - `files`: Per-sidecar outcomes, in path order
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MigrateResult {
+    pub to_version: String,
+    pub source_preference: SourcePreference,
+    pub migrated: usize,
+    pub already_current: usize,
+    pub no_backup_available: usize,
+    pub failed: usize,
+    pub files: Vec<MigrateFileResult>,
+}
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Migrates a sidecar to `to_version` in memory.
+///
+/// Returns an unchanged clone if the sidecar is already at `to_version`.
+///
+/// # Errors
+///
+/// Returns `JozinError::ValidationError` if either version is not in
+/// [`SUPPORTED_SCHEMA_VERSIONS`] or `to_version` is older than the sidecar
+/// (downgrades are not supported).
+///
+/// # Example
+///
+/// ```no_run
+/// use jozin_core::migrate::migrate_sidecar;
+/// use jozin_core::scan::read_sidecar;
+/// use std::path::Path;
+///
+/// let old = read_sidecar(Path::new("/photos/IMG_1234.JPG"))?;
+/// let new = migrate_sidecar(&old, "2.0.0")?;
+/// assert_eq!(new.schema_version, "2.0.0");
+/// # Ok::<(), jozin_core::JozinError>(())
+/// ```
+pub fn migrate_sidecar(sidecar: &Sidecar, to_version: &str) -> Result<Sidecar> {
+    let source_file_available = Path::new(&sidecar.source.file_path).is_file();
+    MigrationRegistry::default().migrate(sidecar, to_version, source_file_available)
+}
+
+impl MigrationRegistry {
+    /// Adds a step, replacing any step registered for the same `from` version.
+    pub fn register(&mut self, step: MigrationStep) {
+        self.steps.retain(|s| s.from != step.from);
+        self.steps.push(step);
+    }
+
+    /// Returns the step upgrading from `version`, if any.
+    pub fn step_from(&self, version: &str) -> Option<&MigrationStep> {
+        self.steps.iter().find(|s| s.from == version)
+    }
+
+    /// Migrates a sidecar to `to_version` in memory, one step at a time.
+    ///
+    /// Returns an unchanged clone if the sidecar is already at `to_version`.
+    ///
+    /// # Errors
+    ///
+    /// - `JozinError::ValidationError` if either version is unsupported or
+    ///   `to_version` is older than the sidecar
+    /// - `JozinError::InternalError` if a step between them is not registered
+    /// - Any error of a step
+    pub fn migrate(&self, sidecar: &Sidecar, to_version: &str, source_file_available: bool) -> Result<Sidecar> {
+        let from = version_index(&sidecar.schema_version)?;
+        let to = version_index(to_version)?;
+
+        if to < from {
+            return Err(JozinError::ValidationError {
+                message: format!(
+                    "Cannot downgrade sidecar from {} to {}",
+                    sidecar.schema_version, to_version
+                ),
+            });
+        }
+
+        let mut migrated = sidecar.clone();
+        while migrated.schema_version != to_version {
+            let step = self.step_from(&migrated.schema_version).ok_or_else(|| JozinError::InternalError {
+                message: format!("No migration step registered from {}", migrated.schema_version),
+            })?;
+            (step.apply)(&mut migrated, source_file_available)?;
+            if migrated.schema_version != step.to {
+                return Err(JozinError::InternalError {
+                    message: format!("Migration {} -> {} left schema_version at {}", step.from, step.to, migrated.schema_version),
+                });
+            }
+        }
+
+        Ok(migrated)
+    }
+}
+
+/// Previews a migration without writing any files.
+///
+/// Every sidecar under `path` is parsed, migrated in memory and compared with
+/// [`Sidecar::diff()`]. Summary counts cover all sidecars; diffs are returned
+/// for a random sample of the affected ones.
+///
+/// # Arguments
+///
+/// * `path` - Sidecar file, image file, or directory
+/// * `recursive` - Descend into subdirectories
+/// * `to_version` - Target schema version
+/// * `sample` - Maximum number of diffs to return (`None` returns all)
+///
+/// # Errors
+///
+/// - `JozinError::IoError` if `path` does not exist
+/// - `JozinError::ValidationError` if `to_version` is not supported
+///
+/// # Example
+///
+/// ```no_run
+/// use jozin_core::migrate::preview_migration;
+/// use std::path::Path;
+///
+/// let preview = preview_migration(Path::new("/photos"), true, "2.0.0", Some(5))?;
+/// println!(
+///     "Migration would affect {} sidecars, changing {:.1} fields per file on average",
+///     preview.affected_sidecars,
+///     preview.average_changes_per_file()
+/// );
+/// # Ok::<(), jozin_core::JozinError>(())
+/// ```
+pub fn preview_migration(
+    path: &Path,
+    recursive: bool,
+    to_version: &str,
+    sample: Option<usize>,
+) -> Result<MigrationPreview> {
+    version_index(to_version)?;
+
+    let mut preview = MigrationPreview {
+        to_version: to_version.to_string(),
+        ..MigrationPreview::default()
+    };
+
+    let mut diffs = Vec::new();
+    for sidecar_path in find_sidecar_files(path, recursive)? {
+        let sidecar = match read_sidecar_file(&sidecar_path).and_then(|json| parse_sidecar(&json))
+        {
+            Ok(sidecar) => sidecar,
+            Err(_) => {
+                preview.unreadable += 1;
+                continue;
+            }
+        };
+        preview.total_sidecars += 1;
+
+        let migrated = match migrate_sidecar(&sidecar, to_version) {
+            Ok(migrated) => migrated,
+            Err(_) => {
+                preview.unreadable += 1;
+                continue;
+            }
+        };
+
+        let changes = Sidecar::diff(&sidecar, &migrated);
+        if changes.is_empty() {
+            continue;
+        }
+
+        preview.affected_sidecars += 1;
+        preview.total_field_changes += changes.len();
+        diffs.push(SidecarDiff {
+            sidecar_path,
+            old: serde_json::to_value(&sidecar)?,
+            new: serde_json::to_value(&migrated)?,
+            from_version: sidecar.schema_version,
+            changes,
+        });
+    }
+
+    preview.samples = match sample {
+        Some(n) => random_sample(diffs, n),
+        None => diffs,
+    };
+
+    Ok(preview)
+}
+
+/// Counts sidecars that a migration to `to_version` would change, without
+/// parsing them.
+///
+/// Reads only the first [`COUNT_HEADER_LEN`] bytes of each sidecar and
+/// compares its `schema_version` string with `to_version`. Sidecars whose
+/// header does not contain `schema_version` (hand-edited or compact JSON with
+/// large leading fields) are counted as unreadable; use
+/// [`preview_migration()`] for an exact result.
+///
+/// # Arguments
+///
+/// * `root` - Sidecar, image file or directory to count
+/// * `to_version` - Target schema version
+/// * `recursive` - Descend into subdirectories
+///
+/// # Errors
+///
+/// - `JozinError::IoError` if `root` does not exist
+/// - `JozinError::ValidationError` if `to_version` is not supported
+///
+/// # Example
+///
+/// ```no_run
+/// use jozin_core::migrate::count_pending;
+/// use std::path::Path;
+///
+/// let counts = count_pending(Path::new("/photos"), "2.0.0", true)?;
+/// println!("{} sidecars would be migrated", counts.would_migrate);
+/// # Ok::<(), jozin_core::JozinError>(())
+/// ```
+pub fn count_pending(root: &Path, to_version: &str, recursive: bool) -> Result<MigrateCounts> {
+    version_index(to_version)?;
+
+    let mut counts = MigrateCounts::default();
+    for sidecar_path in find_sidecar_files(root, recursive)? {
+        match read_header_version(&sidecar_path) {
+            Some(version) if version == to_version => counts.already_current += 1,
+            Some(_) => counts.would_migrate += 1,
+            None => counts.unreadable += 1,
+        }
+    }
+    Ok(counts)
+}
+
+/// Migrates every sidecar under `path` to `to_version` and writes it.
+///
+/// `source_preference` picks the copy that is migrated: the sidecar itself,
+/// or one of its backups, which is then promoted to the current sidecar
+/// (see the module documentation for how backups move). Failures are
+/// recorded per sidecar; the other sidecars are still migrated.
+///
+/// # Arguments
+///
+/// * `path` - Sidecar file, image file, or directory
+/// * `to_version` - Target schema version
+/// * `recursive` - Descend into subdirectories
+/// * `source_preference` - Copy of each sidecar to migrate
+/// * `options` - Dry run and backup settings
+///
+/// # Errors
+///
+/// - `JozinError::IoError` if `path` does not exist
+/// - `JozinError::ValidationError` if `to_version` is not supported
+///
+/// # Example
+///
+/// ```no_run
+/// use jozin_core::migrate::{migrate_path, MigrateOptions, SourcePreference};
+/// use std::path::Path;
+///
+/// // Redo a migration from the pre-migration backups
+/// let result = migrate_path(Path::new("/photos"), "2.0.0", true, SourcePreference::Backup1, &MigrateOptions::default())?;
+/// println!("{} migrated, {} without backup", result.migrated, result.no_backup_available);
+/// # Ok::<(), jozin_core::JozinError>(())
+/// ```
+pub fn migrate_path(
+    path: &Path,
+    to_version: &str,
+    recursive: bool,
+    source_preference: SourcePreference,
+    options: &MigrateOptions,
+) -> Result<MigrateResult> {
+    version_index(to_version)?;
+
+    let mut result = MigrateResult {
+        to_version: to_version.to_string(),
+        source_preference,
+        migrated: 0,
+        already_current: 0,
+        no_backup_available: 0,
+        failed: 0,
+        files: Vec::new(),
+    };
+    for sidecar_path in find_sidecar_files(path, recursive)? {
+        let source_path = match source_preference.backup_number() {
+            Some(n) => append_to_file_name(&sidecar_path, &format!(".bak{}", n)),
+            None => sidecar_path.clone(),
+        };
+        let status = if source_path.is_file() {
+            migrate_file(&sidecar_path, &source_path, to_version, source_preference, options)
+                .unwrap_or_else(|e| MigrateStatus::Failed { error: e.to_string() })
+        } else {
+            MigrateStatus::NoBackupAvailable
+        };
+        match &status {
+            MigrateStatus::Migrated { .. } => result.migrated += 1,
+            MigrateStatus::AlreadyCurrent => result.already_current += 1,
+            MigrateStatus::NoBackupAvailable => result.no_backup_available += 1,
+            MigrateStatus::Failed { .. } => result.failed += 1,
+        }
+        result.files.push(MigrateFileResult { sidecar_path, source_path, status });
+    }
+    Ok(result)
+}
+
+/// Parses a `MAJOR.MINOR.PATCH` version for ordering.
+///
+/// Pre-release and build suffixes (`-beta.1`, `+abc`) are ignored, so
+/// `2.0.0-beta` compares equal to `2.0.0`.
+///
+/// # Returns
+///
+/// `None` if `version` is not three dot-separated numbers.
+///
+/// # Example
+///
+/// ```
+/// use jozin_core::migrate::parse_schema_version;
+///
+/// assert!(parse_schema_version("1.10.0") > parse_schema_version("1.9.3"));
+/// assert_eq!(parse_schema_version("latest"), None);
+/// ```
+pub fn parse_schema_version(version: &str) -> Option<(u64, u64, u64)> {
+    let core = version.trim().split(['-', '+']).next()?;
+    let mut parts = core.split('.').map(|p| p.parse::<u64>().ok());
+    let parsed = (parts.next()??, parts.next()??, parts.next()??);
+    parts.next().is_none().then_some(parsed)
+}
+
+// ============================================================================
+// Internal Helpers
+// ============================================================================
+
+/// Reads `schema_version` from the first [`COUNT_HEADER_LEN`] bytes of a
+/// sidecar (decompressed bytes for gzipped sidecars).
+fn read_header_version(sidecar_path: &Path) -> Option<String> {
+    let mut header = Vec::with_capacity(COUNT_HEADER_LEN as usize);
+    let file = File::open(sidecar_path).ok()?;
+    if is_compressed_path(sidecar_path) {
+        GzDecoder::new(file).take(COUNT_HEADER_LEN).read_to_end(&mut header).ok()?;
+    } else {
+        file.take(COUNT_HEADER_LEN).read_to_end(&mut header).ok()?;
+    }
+    extract_schema_version(&header)
+}
+
+/// Extracts the value of `"schema_version": "..."` from a JSON prefix.
+fn extract_schema_version(header: &[u8]) -> Option<String> {
+    const KEY: &[u8] = b"\"schema_version\"";
+
+    let start = header.windows(KEY.len()).position(|w| w == KEY)? + KEY.len();
+    let rest = header[start..].trim_ascii_start().strip_prefix(b":")?;
+    let rest = rest.trim_ascii_start().strip_prefix(b"\"")?;
+    let end = rest.iter().position(|&b| b == b'"')?;
+    String::from_utf8(rest[..end].to_vec()).ok()
+}
+
+/// Migrates `source_path` (the sidecar or one of its backups) and writes the
+/// result to `sidecar_path`.
+///
+/// A current sidecar already at `to_version` is left alone; a backup is
+/// promoted even then, since replacing the current sidecar is the point.
+fn migrate_file(
+    sidecar_path: &Path,
+    source_path: &Path,
+    to_version: &str,
+    source_preference: SourcePreference,
+    options: &MigrateOptions,
+) -> Result<MigrateStatus> {
+    let source = parse_sidecar(&read_sidecar_file(source_path)?)?;
+    let backup_number = source_preference.backup_number();
+    if backup_number.is_none() && source.schema_version == to_version {
+        return Ok(MigrateStatus::AlreadyCurrent);
+    }
+    let migrated = migrate_sidecar(&source, to_version)?;
+    if options.dry_run {
+        return Ok(MigrateStatus::Migrated { from_version: source.schema_version });
+    }
+
+    let json = serde_json::to_string_pretty(&migrated)?;
+    match backup_number {
+        None if options.backup => write_sidecar_json(sidecar_path, &json, FsyncPolicy::Always)?,
+        None => write_replacing(sidecar_path, &json)?,
+        Some(n) => {
+            // The replaced sidecar goes right above the promoted backup
+            if options.backup && n < MAX_BACKUP_DEPTH {
+                let backup = |k: u8| append_to_file_name(sidecar_path, &format!(".bak{}", k));
+                for k in (n + 1..MAX_BACKUP_DEPTH).rev() {
+                    if backup(k).exists() {
+                        fs::rename(backup(k), backup(k + 1))?;
+                    }
+                }
+                fs::copy(sidecar_path, backup(n + 1))?;
+            }
+            write_replacing(sidecar_path, &json)?;
+        }
+    }
+    Ok(MigrateStatus::Migrated { from_version: source.schema_version })
+}
+
+/// Replaces `sidecar_path` atomically, without rotating backups.
+fn write_replacing(sidecar_path: &Path, json: &str) -> Result<()> {
+    let tmp_path = append_to_file_name(sidecar_path, ".tmp");
+    let mut tmp_file = File::create(&tmp_path)?;
+    tmp_file.write_all(&encode_sidecar(json, is_compressed_path(sidecar_path))?)?;
+    tmp_file.sync_all()?;
+    atomic_rename(&tmp_path, sidecar_path)
+}
+
+/// Returns the position of `version` in [`SUPPORTED_SCHEMA_VERSIONS`].
+fn version_index(version: &str) -> Result<usize> {
+    SUPPORTED_SCHEMA_VERSIONS
+        .iter()
+        .position(|v| *v == version)
+        .ok_or_else(|| JozinError::ValidationError {
+            message: format!(
+                "Unsupported schema version '{}' (supported: {})",
+                version,
+                SUPPORTED_SCHEMA_VERSIONS.join(", ")
+            ),
+        })
+}
+
+/// Picks up to `n` items at random, keeping their original order.
+///
+/// Uses the randomly seeded std hasher as the source of randomness.
+fn random_sample(items: Vec<SidecarDiff>, n: usize) -> Vec<SidecarDiff> {
+    if items.len() <= n {
+        return items;
+    }
+
+    let state = RandomState::new();
+    let mut keyed: Vec<(u64, usize, SidecarDiff)> = items
+        .into_iter()
+        .enumerate()
+        .map(|(i, item)| (state.hash_one(&item.sidecar_path), i, item))
+        .collect();
+    keyed.sort_by_key(|(key, _, _)| *key);
+    keyed.truncate(n);
+    keyed.sort_by_key(|(_, i, _)| *i);
+    keyed.into_iter().map(|(_, _, item)| item).collect()
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scan::{read_sidecar, scan_file};
+    use tempfile::TempDir;
+
+    fn scanned_library(count: usize) -> TempDir {
+        let temp_dir = TempDir::new().unwrap();
+        for i in 0..count {
+            let photo = temp_dir.path().join(format!("IMG_{:04}.jpg", i));
+            fs::write(&photo, format!("image {}", i)).unwrap();
+            scan_file(&photo, false).unwrap();
+        }
+        temp_dir
+    }
+
+    #[test]
+    fn test_migrate_sidecar_1_0_0_to_2_0_0() {
+        let temp_dir = scanned_library(1);
+        let photo = temp_dir.path().join("IMG_0000.jpg");
+        let mut old = read_sidecar(&photo).unwrap();
+        old.set_extension("com.example.app", "rating", serde_json::json!(4));
+
+        let new = migrate_sidecar(&old, "2.0.0").unwrap();
+        assert_eq!(new.schema_version, "2.0.0");
+        assert_eq!(new.pipeline_signature.schema_version, "2.0.0");
+        assert_eq!(new.get_extension("com.example.app", "rating"), Some(&serde_json::json!(4)));
+
+        let paths: Vec<String> = Sidecar::diff(&old, &new).into_iter().map(|c| c.path).collect();
+        assert!(paths.contains(&"schema_version".to_string()));
+        assert!(paths.contains(&"pipeline_signature.schema_version".to_string()));
+
+        // Already current: no changes; downgrade and unknown versions rejected
+        assert!(Sidecar::diff(&new, &migrate_sidecar(&new, "2.0.0").unwrap()).is_empty());
+        assert!(matches!(migrate_sidecar(&new, "1.0.0"), Err(JozinError::ValidationError { .. })));
+        assert!(matches!(migrate_sidecar(&old, "9.0.0"), Err(JozinError::ValidationError { .. })));
+    }
+
+    #[test]
+    fn test_preview_migration_samples_without_writing() {
+        let temp_dir = scanned_library(4);
+        let sidecar_path = temp_dir.path().join("IMG_0000.jpg.json");
+        let before = fs::read_to_string(&sidecar_path).unwrap();
+
+        let preview = preview_migration(temp_dir.path(), false, "2.0.0", Some(2)).unwrap();
+        assert_eq!(preview.total_sidecars, 4);
+        assert_eq!(preview.affected_sidecars, 4);
+        assert_eq!(preview.samples.len(), 2);
+        assert!(preview.average_changes_per_file() >= 2.0);
+        assert_eq!(preview.samples[0].from_version, "1.0.0");
+
+        let all = preview_migration(temp_dir.path(), false, "2.0.0", None).unwrap();
+        assert_eq!(all.samples.len(), 4);
+
+        // Nothing written
+        assert_eq!(fs::read_to_string(&sidecar_path).unwrap(), before);
+    }
+
+    #[test]
+    fn test_parse_schema_version_ordering() {
+        assert_eq!(parse_schema_version("2.0.0"), Some((2, 0, 0)));
+        assert_eq!(parse_schema_version("0.2.0-beta.1"), Some((0, 2, 0)));
+        assert!(parse_schema_version("1.10.0") > parse_schema_version("1.9.0"));
+        assert_eq!(parse_schema_version(SUPPORTED_SCHEMA_VERSIONS.last().unwrap()), parse_schema_version(CURRENT_SCHEMA_VERSION));
+        assert_eq!(parse_schema_version("1.0"), None);
+        assert_eq!(parse_schema_version("1.0.0.0"), None);
+    }
+
+    #[test]
+    fn test_count_pending_reads_header_only() {
+        let temp_dir = scanned_library(3);
+        let current = temp_dir.path().join("IMG_0001.jpg.json");
+        let migrated = migrate_sidecar(&read_sidecar(&temp_dir.path().join("IMG_0001.jpg")).unwrap(), "2.0.0").unwrap();
+        fs::write(&current, serde_json::to_string_pretty(&migrated).unwrap()).unwrap();
+        fs::write(temp_dir.path().join("IMG_0002.jpg.json"), "{ not json").unwrap();
+
+        let counts = count_pending(temp_dir.path(), "2.0.0", false).unwrap();
+        assert_eq!(counts, MigrateCounts { would_migrate: 1, already_current: 1, unreadable: 1 });
+        assert!(matches!(count_pending(temp_dir.path(), "9.0.0", false), Err(JozinError::ValidationError { .. })));
+
+        assert_eq!(extract_schema_version(b"{\"schema_version\" : \"1.0.0\","), Some("1.0.0".to_string()));
+        assert_eq!(extract_schema_version(b"{\"schema_version\": \"1.0"), None);
+    }
+
+    #[test]
+    fn test_migrate_path_from_backup_repairs_bad_migration() {
+        let temp_dir = scanned_library(1);
+        let photo = temp_dir.path().join("IMG_0000.jpg");
+        let sidecar_path = temp_dir.path().join("IMG_0000.jpg.json");
+        let bak1 = append_to_file_name(&sidecar_path, ".bak1");
+        let bak2 = append_to_file_name(&sidecar_path, ".bak2");
+
+        // Good 1.0.0 sidecar in .bak1, a 2.0.0 migration that lost the extension as .json
+        let mut good = read_sidecar(&photo).unwrap();
+        good.set_extension("com.example.app", "rating", serde_json::json!(4));
+        fs::write(&bak1, serde_json::to_string_pretty(&good).unwrap()).unwrap();
+        let buggy = serde_json::to_string_pretty(&migrate_sidecar(&read_sidecar(&photo).unwrap(), "2.0.0").unwrap()).unwrap();
+        fs::write(&sidecar_path, &buggy).unwrap();
+
+        let options = MigrateOptions::default();
+        let dry = migrate_path(temp_dir.path(), "2.0.0", false, SourcePreference::Backup1, &MigrateOptions { dry_run: true, ..options.clone() }).unwrap();
+        assert_eq!(dry.migrated, 1);
+        assert_eq!(fs::read_to_string(&sidecar_path).unwrap(), buggy);
+
+        let result = migrate_path(temp_dir.path(), "2.0.0", false, SourcePreference::Backup1, &options).unwrap();
+        assert_eq!(result.migrated, 1);
+        assert_eq!(result.files[0].source_path, bak1);
+        assert_eq!(result.files[0].status, MigrateStatus::Migrated { from_version: "1.0.0".to_string() });
+
+        let repaired = read_sidecar(&photo).unwrap();
+        assert_eq!(repaired.schema_version, "2.0.0");
+        assert_eq!(repaired.get_extension("com.example.app", "rating"), Some(&serde_json::json!(4)));
+        assert_eq!(parse_sidecar(&fs::read_to_string(&bak1).unwrap()).unwrap().schema_version, "1.0.0");
+        assert_eq!(fs::read_to_string(&bak2).unwrap(), buggy);
+    }
+
+    #[test]
+    fn test_migrate_path_reports_missing_backup() {
+        let temp_dir = scanned_library(2);
+        let result = migrate_path(temp_dir.path(), "2.0.0", false, SourcePreference::Backup2, &MigrateOptions::default()).unwrap();
+        assert_eq!(result.no_backup_available, 2);
+        assert_eq!(result.migrated, 0);
+        assert!(result.files.iter().all(|f| f.status == MigrateStatus::NoBackupAvailable));
+
+        // The current sidecars migrate in place, keeping the 1.0.0 copy as .bak1
+        let result = migrate_path(temp_dir.path(), "2.0.0", false, SourcePreference::Current, &MigrateOptions::default()).unwrap();
+        assert_eq!(result.migrated, 2);
+        let photo = temp_dir.path().join("IMG_0000.jpg");
+        assert_eq!(read_sidecar(&photo).unwrap().schema_version, "2.0.0");
+        assert!(temp_dir.path().join("IMG_0000.jpg.json.bak1").exists());
+        let again = migrate_path(temp_dir.path(), "2.0.0", false, SourcePreference::Current, &MigrateOptions::default()).unwrap();
+        assert_eq!(again.already_current, 2);
+    }
+}
@@ -0,0 +1,198 @@
+//! Schema migration `1.0.0` → `2.0.0`
+//!
+//! | Field                                | Change                                          |
+//! |--------------------------------------|-------------------------------------------------|
+//! | `image.color_space`                  | Added from EXIF `ColorSpace` (source file only) |
+//! | `image.lens_model`                   | Added from EXIF `LensModel` (source file only)  |
+//! | `image.faces_count`, `image.tag_count` | Added from `faces` / `tags`                   |
+//! | `image.camera_make`, `image.camera_model` | Normalised (see [`crate::util::camera`]); the originals move to `camera_make_raw` / `camera_model_raw` |
+//! | `thumbnails[].format`                | Extension → MIME type (`"jpg"` → `"image/jpeg"`) |
+//! | `schema_version`, `pipeline_signature.schema_version` | `"2.0.0"`                      |
+//! | `updated_at`                         | Now                                             |
+//!
+//! The EXIF fields can only be filled while the photo is still at
+//! `source.file_path`; otherwise they stay unset and are picked up by the next
+//! scan.
+
+use crate::exif::read_image_info;
+use crate::util::camera::normalise_image_camera;
+use crate::util::mime::extension_to_mime;
+use crate::{ImageInfo, JozinError, Result, Sidecar};
+use std::path::Path;
+use time::{OffsetDateTime, UtcOffset};
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Upgrades a `1.0.0` sidecar to `2.0.0` in place.
+///
+/// # Arguments
+///
+/// * `sidecar` - Sidecar at schema `1.0.0`
+/// * `source_file_available` - Read `color_space` and `lens_model` from the
+///   photo at `source.file_path`
+///
+/// # Errors
+///
+/// - `JozinError::ValidationError` if the sidecar is not at `1.0.0`
+/// - `JozinError::InternalError` if the timestamp cannot be formatted
+///
+/// # Example
+///
+/// ```no_run
+/// use jozin_core::migrate::v1_to_v2::migrate_v1_to_v2;
+/// use jozin_core::scan::read_sidecar;
+/// use std::path::Path;
+///
+/// let mut sidecar = read_sidecar(Path::new("/photos/IMG_1234.JPG"))?;
+/// migrate_v1_to_v2(&mut sidecar, true)?;
+/// sidecar.validate()?;
+/// # Ok::<(), jozin_core::JozinError>(())
+/// ```
+pub fn migrate_v1_to_v2(sidecar: &mut Sidecar, source_file_available: bool) -> Result<()> {
+    if sidecar.schema_version != "1.0.0" {
+        return Err(JozinError::ValidationError {
+            message: format!("Expected a 1.0.0 sidecar, found {}", sidecar.schema_version),
+        });
+    }
+
+    if source_file_available {
+        // Unreadable files are treated like missing ones
+        let exif = read_image_info(Path::new(&sidecar.source.file_path), UtcOffset::UTC, None)
+            .ok()
+            .flatten();
+        if let Some(exif) = exif.filter(|e| e.color_space.is_some() || e.lens_model.is_some()) {
+            let image = sidecar.image.get_or_insert_with(ImageInfo::default);
+            image.color_space = image.color_space.take().or(exif.color_space);
+            image.lens_model = image.lens_model.take().or(exif.lens_model);
+        }
+    }
+    if let Some(image) = &mut sidecar.image {
+        normalise_image_camera(image);
+    }
+    sidecar.update_summary_counts();
+
+    // Unknown formats are kept as they are rather than dropped
+    for thumbnail in &mut sidecar.thumbnails {
+        if let Some(mime) = extension_to_mime(&thumbnail.format) {
+            thumbnail.format = mime.to_string();
+        }
+    }
+
+    sidecar.schema_version = "2.0.0".to_string();
+    sidecar.pipeline_signature.schema_version = "2.0.0".to_string();
+    sidecar.updated_at = OffsetDateTime::now_utc()
+        .format(&time::format_description::well_known::Rfc3339)
+        .map_err(|e| JozinError::InternalError {
+            message: format!("Failed to format updated_at: {}", e),
+        })?;
+    Ok(())
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scan::parse_sidecar;
+    use exif::{Field, In, Tag, Value};
+    use tempfile::TempDir;
+
+    fn fixture_v1(photo: &Path) -> Sidecar {
+        let json = format!(
+            r#"{{
+  "schema_version": "1.0.0",
+  "producer_version": "0.1.0",
+  "created_at": "2024-03-01T10:00:00Z",
+  "updated_at": "2024-03-01T10:00:00Z",
+  "pipeline_signature": {{
+    "schema_version": "1.0.0",
+    "producer_version": "0.1.0",
+    "hash_algorithm": "blake3",
+    "created_at": "2024-03-01T10:00:00Z"
+  }},
+  "source": {{
+    "file_path": {},
+    "file_size_bytes": 1024,
+    "file_hash_b3": "af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262",
+    "file_modified_at": "2024-02-28T09:00:00Z"
+  }},
+  "image": {{ "format": "JPEG", "camera_make": "Canon Inc.", "camera_model": "Canon EOS R5" }},
+  "faces": [{{ "bbox": [0.1, 0.1, 0.2, 0.2], "score": 0.9 }}],
+  "tags": [],
+  "thumbnails": [
+    {{ "path": "IMG_0001_256.jpg", "size": 256, "format": "jpg" }},
+    {{ "path": "IMG_0001_512.webp", "size": 512, "format": "webp" }}
+  ]
+}}"#,
+            serde_json::to_string(&photo.display().to_string()).unwrap()
+        );
+        parse_sidecar(&json).unwrap()
+    }
+
+    fn jpeg_with_lens(path: &Path) {
+        let mut writer = exif::experimental::Writer::new();
+        let fields = [
+            Field { tag: Tag::ColorSpace, ifd_num: In::PRIMARY, value: Value::Short(vec![1]) },
+            Field { tag: Tag::LensModel, ifd_num: In::PRIMARY, value: Value::Ascii(vec![b"RF24-70mm F2.8 L".to_vec()]) },
+        ];
+        for field in &fields {
+            writer.push_field(field);
+        }
+        let mut tiff = std::io::Cursor::new(Vec::new());
+        writer.write(&mut tiff, false).unwrap();
+        let tiff = tiff.into_inner();
+
+        let mut jpeg = vec![0xFF, 0xD8, 0xFF, 0xE1];
+        jpeg.extend_from_slice(&((tiff.len() + 8) as u16).to_be_bytes());
+        jpeg.extend_from_slice(b"Exif\0\0");
+        jpeg.extend_from_slice(&tiff);
+        jpeg.extend_from_slice(&[0xFF, 0xD9]);
+        std::fs::write(path, jpeg).unwrap();
+    }
+
+    #[test]
+    fn test_migrate_fixture_with_source_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let photo = temp_dir.path().join("IMG_0001.jpg");
+        jpeg_with_lens(&photo);
+
+        let mut sidecar = fixture_v1(&photo);
+        assert!(sidecar.validate().is_ok());
+        migrate_v1_to_v2(&mut sidecar, true).unwrap();
+
+        assert_eq!(sidecar.schema_version, "2.0.0");
+        assert_eq!(sidecar.pipeline_signature.schema_version, "2.0.0");
+        assert_ne!(sidecar.updated_at, "2024-03-01T10:00:00Z");
+        let image = sidecar.image.as_ref().unwrap();
+        assert_eq!(image.color_space.as_deref(), Some("sRGB"));
+        assert_eq!(image.lens_model.as_deref(), Some("RF24-70mm F2.8 L"));
+        assert_eq!(image.faces_count, Some(1));
+        assert_eq!(image.format.as_deref(), Some("JPEG"));
+        sidecar.validate().unwrap();
+
+        assert!(migrate_v1_to_v2(&mut sidecar, true).is_err());
+    }
+
+    #[test]
+    fn test_migrate_fixture_without_source_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let photo = temp_dir.path().join("IMG_0001.jpg");
+        jpeg_with_lens(&photo);
+
+        let mut sidecar = fixture_v1(&photo);
+        migrate_v1_to_v2(&mut sidecar, false).unwrap();
+        let image = sidecar.image.as_ref().unwrap();
+        assert_eq!(image.color_space, None);
+        assert_eq!(image.lens_model, None);
+        assert_eq!(image.faces_count, Some(1));
+        assert_eq!((image.camera_make.as_deref(), image.camera_model.as_deref()), (Some("Canon"), Some("EOS R5")));
+        assert_eq!(image.camera_make_raw.as_deref(), Some("Canon Inc."));
+        let formats: Vec<&str> = sidecar.thumbnails.iter().map(|t| t.format.as_str()).collect();
+        assert_eq!(formats, ["image/jpeg", "image/webp"]);
+        sidecar.validate().unwrap();
+    }
+}
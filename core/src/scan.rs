@@ -15,64 +15,87 @@
 //! - Atomic sidecar writing with backup rotation
 //! - Directory traversal with recursive option
 //! - Glob pattern filtering (include/exclude)
-//! - Sequential processing (parallelism in Phase 2)
-//! - Basic error handling
-//!
-//! Future phases will add:
-//! - EXIF parsing (Phase 1+)
-//! - Parallel processing with rayon (Phase 2)
-//! - Progress reporting (Phase 2)
+//! - Parallel processing of directory files with rayon (`max_threads`)
+//! - Per-file error handling with a configurable [`FailurePolicy`]
+//! - Progress reporting via [`crate::ProgressEvent`] callbacks
+//! - Images inside `.zip` archives (`scan_archives`, `archives` feature)
 //!
 //! ## Usage
 //!
 //! For most use cases, use [`scan_path()`] which handles both files and directories:
 //!
 //! ```no_run
-//! use jozin_core::scan::scan_path;
+//! use jozin_core::scan::{scan_path, ScanConfig};
 //! use std::path::Path;
 //!
 //! // Scan a single file
-//! let result = scan_path(
-//!     Path::new("/photos/IMG_1234.JPG"),
-//!     false,  // recursive
-//!     None,   // include patterns
-//!     None,   // exclude patterns
-//!     false,  // dry_run
-//!     4,      // max_threads (unused in Phase 1)
-//!     Some("file"), // hash_mode
-//!     None,   // progress_callback
-//! )?;
+//! let result = scan_path(Path::new("/photos/IMG_1234.JPG"), &ScanConfig::default(), None)?;
 //! println!("Scanned {} files", result.successful);
 //!
 //! // Scan a directory recursively with filtering
-//! let result = scan_path(
-//!     Path::new("/photos"),
-//!     true,   // recursive
-//!     Some(&[String::from("*.jpg"), String::from("*.png")]),
-//!     Some(&[String::from("**/.jozin/**")]),
-//!     false,  // dry_run
-//!     8,      // max_threads
-//!     Some("file"),
-//!     None,   // progress_callback
-//! )?;
+//! let config = ScanConfig {
+//!     recursive: true,
+//!     include: Some(vec![String::from("*.jpg"), String::from("*.png")]),
+//!     exclude: Some(vec![String::from("**/.jozin/**")]),
+//!     max_threads: 8,
+//!     hash_mode: Some(String::from("file")),
+//!     ..ScanConfig::default()
+//! };
+//! let result = scan_path(Path::new("/photos"), &config, None)?;
 //! # Ok::<(), jozin_core::JozinError>(())
 //! ```
 //!
 //! For low-level single-file operations, use [`scan_file()`] directly.
 
-use crate::{JozinError, PipelineSignature, Result, Sidecar, SourceInfo};
-use globset::{Glob, GlobSetBuilder};
+use crate::hash::{compute_blake3_hash, compute_blake3_hash_limited, compute_hash_parallel, DEFAULT_PARALLEL_CHUNK_BYTES};
+use crate::interop::apple_live_photos::find_motion_companion;
+use crate::migrate::{parse_schema_version, CURRENT_SCHEMA_VERSION};
+use crate::util::atomic_rename::atomic_rename;
+use crate::util::checksum_journal::{ChecksumJournal, JournalEntry};
+use crate::util::compression::{
+    compressed_path, convert_sidecar_file, encode_sidecar, existing_sidecar_path, is_compressed_path, read_sidecar_file,
+};
+use crate::util::digest::{compute_sha256_hash, compute_sha256_hash_limited, HashAlgorithm};
+use crate::util::encoding_detect::{expected_image_format, validate_image_encoding};
+use crate::util::fsync_policy::FsyncPolicy;
+use crate::util::glob_path_case::{build_case_aware_glob_matcher, CaseAwareGlobMatcher};
+use crate::util::hash_cache::{HashCache, HashCacheEntry};
+use crate::util::icc_profile::extract_icc_profile;
+use crate::util::image_format::detect_format_from_magic_bytes;
+use crate::util::io_budget::IoBudget;
+use crate::util::library_config::{LibraryConfig, DEFAULT_SIDECAR_SUFFIX};
+use crate::util::memory_limit::{MemoryGuard, MEMORY_CHECK_INTERVAL};
+use crate::util::network_drive::{
+    detect_filesystem_type, FilesystemType, NETWORK_IO_BUDGET_BYTES_PER_SEC, NETWORK_MAX_THREADS,
+};
+use crate::util::parallel_walker::ParallelWalker;
+use crate::util::path_normalise::normalise_sidecar_path;
+use crate::util::platform;
+use crate::util::sidecar_index::SidecarIndex;
+use crate::util::sidecar_sorter::{sort_scanned_files, SortOrder};
+use crate::util::size_estimate::estimate_scan_duration;
+use crate::util::symlink::{check_symlink, walker, SymlinkStrategy};
+use crate::util::traversal_order::{order_files, TraversalOrder};
+use crate::{ImageInfo, JozinError, PipelineSignature, Result, Sidecar, SourceInfo};
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::fs::{self, File};
-use std::io::{Read, Write};
+use std::io::Write;
+use std::panic::{self, AssertUnwindSafe};
 use std::path::{Path, PathBuf};
-use time::OffsetDateTime;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc, Mutex, MutexGuard};
+use time::{OffsetDateTime, UtcOffset};
 use walkdir::WalkDir;
 
 // ============================================================================
 // Constants
 // ============================================================================
 
+/// Schema version of the sidecars `scan` writes (`jozin migrate` upgrades
+/// them to [`crate::migrate::CURRENT_SCHEMA_VERSION`]).
+pub const SIDECAR_SCHEMA_VERSION: &str = "1.0.0";
+
 /// Supported image file extensions (lowercase).
 ///
 /// This list covers common image formats per TASK+PHASE_PLAN.md line 149.
@@ -90,6 +113,26 @@ const SUPPORTED_EXTENSIONS: &[&str] = &[
     "webp",
 ];
 
+/// MIME types (as reported by magic-byte detection) of the formats in
+/// [`SUPPORTED_EXTENSIONS`].
+///
+/// Used by the `content_type_filter` scan option to document which detected
+/// types Jožin can process. Mapping to extensions:
+/// - image/jpeg: jpg, jpeg
+/// - image/png: png
+/// - image/heif: heic, heif
+/// - image/tiff: tiff, tif, and TIFF-based RAW (nef, arw, dng)
+/// - image/x-canon-cr2: cr2
+/// - image/webp: webp
+pub const SUPPORTED_MIME_TYPES: &[&str] = &[
+    "image/jpeg",
+    "image/png",
+    "image/heif",
+    "image/tiff",
+    "image/x-canon-cr2",
+    "image/webp",
+];
+
 // ============================================================================
 // Public Types
 // ============================================================================
@@ -107,6 +150,15 @@ const SUPPORTED_EXTENSIONS: &[&str] = &[
 /// - `successful`: Number of files successfully scanned with sidecars written
 /// - `failed`: Number of files that failed to scan (errors)
 /// - `skipped`: Number of files skipped by filters or dry_run
+/// - `conflicts`: Number of files left alone because their sidecar has a
+///   newer schema (see [`ScanAction::Conflict`])
+/// - `truncated`: The scan stopped early at `memory_limit_mb`; files not
+///   scanned are missing from `scanned_files`
+/// - `cross_root_duplicates`: Identical files found under more than one root
+///   ([`scan_paths()`] only)
+/// - `orphaned_sidecars`: Sidecars left at the old path of renamed files
+///   (see [`ScanAction::Renamed`])
+/// - `exif_coverage`: How complete the EXIF data of the scanned files is
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScanResult {
     pub scanned_files: Vec<ScannedFile>,
@@ -114,6 +166,56 @@ pub struct ScanResult {
     pub successful: usize,
     pub failed: usize,
     pub skipped: usize,
+    #[serde(default)]
+    pub conflicts: usize,
+    #[serde(default)]
+    pub truncated: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub cross_root_duplicates: Vec<DuplicateGroup>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub orphaned_sidecars: Vec<String>,
+    #[serde(default)]
+    pub exif_coverage: ExifCoverage,
+}
+
+/// How many photos carry each kind of EXIF metadata.
+///
+/// Counted from the `image` of each sidecar: the ones written by a scan
+/// ([`ScanResult::exif_coverage`]) or a library's ([`LibraryStats`]).
+/// Failed and filtered-out files are not counted.
+///
+/// # Fields
+///
+/// - `has_datetime`: Photos with `datetime_original`
+/// - `has_gps`: Photos with both `gps_latitude` and `gps_longitude`
+/// - `has_camera`: Photos with `camera_make` or `camera_model`
+/// - `has_all`: Photos with a capture time, GPS location, camera make and
+///   camera model
+/// - `missing_all`: Photos with none of the above (including photos without
+///   `image`)
+/// - `total`: Photos counted
+///
+/// [`LibraryStats`]: crate::stats::LibraryStats
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExifCoverage {
+    pub has_datetime: usize,
+    pub has_gps: usize,
+    pub has_camera: usize,
+    pub has_all: usize,
+    pub missing_all: usize,
+    pub total: usize,
+}
+
+/// Files with the same content hash.
+///
+/// # Fields
+///
+/// - `hash`: Content hash shared by the files
+/// - `paths`: The files, in scan result order
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DuplicateGroup {
+    pub hash: String,
+    pub paths: Vec<String>,
 }
 
 /// Detailed information about a single scanned file.
@@ -129,6 +231,9 @@ pub struct ScanResult {
 /// - `error`: Error message (only if action is Failed)
 /// - `hash`: BLAKE3 hash of file contents (only if successful)
 /// - `size_bytes`: File size in bytes (only if successful)
+/// - `backup_path`: The `.bak1` the previous sidecar was rotated to (only if
+///   action is Updated or Renamed); restore it with
+///   [`crate::util::backup_restore::restore_from_backup()`] to undo the scan
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScannedFile {
     pub path: String,
@@ -141,6 +246,8 @@ pub struct ScannedFile {
     pub hash: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub size_bytes: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backup_path: Option<String>,
 }
 
 /// Action taken when scanning a file.
@@ -149,15 +256,334 @@ pub struct ScannedFile {
 ///
 /// # Variants
 ///
-/// - `Written`: Sidecar was successfully created or updated
+/// - `Written`: Sidecar was successfully created
+/// - `Updated`: An existing sidecar was rewritten (the previous one was
+///   rotated to `.bak1`)
 /// - `Skipped`: File was skipped (dry_run mode or filtered out)
 /// - `Failed`: Scan failed with an error
+/// - `Renamed`: Sidecar was written with the metadata of a renamed file's old
+///   sidecar (see [`ScanConfig`]'s `sidecar_index`); `previous_path` is the
+///   file's old path
+/// - `Conflict`: The existing sidecar has a schema newer than this build
+///   understands (`existing_schema` > `scanner_schema`); it was left unchanged
+///   (see [`ScanConfig`]'s `force_overwrite_newer`)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum ScanAction {
     Written,
+    Updated,
     Skipped,
     Failed,
+    Renamed { previous_path: String },
+    Conflict { existing_schema: String, scanner_schema: String },
+}
+
+/// What [`scan_file()`] did on disk.
+///
+/// # Variants
+///
+/// - `Created`: A new sidecar was written
+/// - `Updated`: An existing sidecar was replaced; the previous one was rotated
+///   to `.bak1`
+/// - `DryRunPreview`: Nothing was written (`dry_run`)
+/// - `Unchanged`: The existing sidecar has a newer schema and was left alone
+///   (see [`ScanAction::Conflict`])
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WriteAction {
+    Created,
+    Updated,
+    DryRunPreview,
+    Unchanged,
+}
+
+/// Result of scanning a single file with [`scan_file()`].
+///
+/// # Fields
+///
+/// - `sidecar`: The generated sidecar (the existing one if `action` is
+///   `Unchanged`)
+/// - `action`: What happened on disk
+/// - `sidecar_path`: Where the sidecar is (or would be, for a dry run)
+/// - `backup_path`: The `.bak1` the previous sidecar was rotated to (only if
+///   `action` is `Updated`)
+/// - `previous_schema_version`: Schema of the sidecar that existed before the
+///   scan, if any
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SidecarWriteResult {
+    pub sidecar: Sidecar,
+    pub action: WriteAction,
+    pub sidecar_path: PathBuf,
+    pub backup_path: Option<PathBuf>,
+    pub previous_schema_version: Option<String>,
+}
+
+/// Controls when a directory scan gives up instead of recording failures.
+///
+/// # Fields
+///
+/// - `abort_on_internal`: Abort the scan on a `JozinError::InternalError` or a
+///   panic while scanning a file (a bug, not a problem with the file). The
+///   error is returned after in-flight files finish.
+/// - `max_file_failures`: Abort once more than this many files have failed
+///   (`None` = never abort on file failures)
+///
+/// # Example
+///
+/// ```
+/// use jozin_core::scan::{FailurePolicy, ScanConfig};
+///
+/// let config = ScanConfig {
+///     failure_policy: FailurePolicy { abort_on_internal: true, max_file_failures: Some(100) },
+///     ..ScanConfig::default()
+/// };
+/// assert_eq!(config.failure_policy.max_file_failures, Some(100));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FailurePolicy {
+    pub abort_on_internal: bool,
+    pub max_file_failures: Option<usize>,
+}
+
+impl Default for FailurePolicy {
+    fn default() -> Self {
+        Self {
+            abort_on_internal: true,
+            max_file_failures: None,
+        }
+    }
+}
+
+/// Configuration for [`scan_path()`].
+///
+/// Groups the scan options so new ones can be added without changing the
+/// `scan_path` signature. Use `ScanConfig::default()` and override fields as needed.
+///
+/// # Fields
+///
+/// - `recursive`: Traverse subdirectories (ignored for single files)
+/// - `include`: Glob patterns to include. If set, only matching files are scanned
+/// - `exclude`: Glob patterns to exclude. Applied before include patterns
+///   (both ignore case on case-insensitive filesystems; see
+///   [`crate::util::glob_path_case`])
+/// - `dry_run`: Compute metadata but don't write sidecars
+/// - `max_threads`: Number of threads scanning files of a directory in parallel
+/// - `hash_mode`: Hash computation strategy: "file", "pixel", or "both"
+///   (Phase 1: only "file" is supported)
+/// - `content_type_filter`: MIME types to scan (e.g., `["image/jpeg", "image/*"]`).
+///   When set, file contents are sniffed instead of trusting the extension
+/// - `include_raw_exif`: Store the full raw EXIF in `ImageInfo::exif_json`
+/// - `link_live_photos`: Record the paired `.MOV` of Live Photo stills in
+///   `SourceInfo::live_photo_path`
+/// - `failure_policy`: When a directory scan aborts (see [`FailurePolicy`])
+/// - `scan_archives`: Scan images inside `.zip` files (`archives` feature;
+///   see [`crate::archives`])
+/// - `extract_archives`: Extract archives to this directory and scan the
+///   extracted files instead of scanning in memory
+/// - `hash_algorithm`: Content hash to record (see [`HashAlgorithm`])
+/// - `sidecar_suffix`: Appended to the image file name (default `".json"`)
+/// - `sidecar_prefix`: Prepended to the image file name (e.g. `"."` for hidden sidecars)
+/// - `symlinks`: How symlinks are treated during traversal (see [`SymlinkStrategy`])
+/// - `assume_timezone`: Camera UTC offset for capture times without
+///   `OffsetTimeOriginal` (default UTC; see [`crate::util::timezone`])
+/// - `io_budget`: Maximum bytes per second read for hashing and EXIF, shared
+///   by all scan threads (see [`IoBudget`])
+/// - `journal`: Append each scanned file's hash to this checksum journal
+///   (see [`ChecksumJournal`]); not written in dry runs
+/// - `hash_cache`: Reuse BLAKE3 hashes of unchanged files, shared with other
+///   operations in the same process (see [`HashCache`])
+/// - `fsync_policy`: When sidecars are synced to disk before being renamed
+///   into place (default `Auto`: not on network shares; see [`FsyncPolicy`])
+/// - `force_local_io`: Keep these settings on network filesystems instead of
+///   adjusting them (see [`ScanConfig::adjusted_for_filesystem()`])
+/// - `output_sort_order`: Order of [`ScanResult::scanned_files`] (default
+///   `None`: by path, without extra work; see [`SortOrder`])
+/// - `memory_limit_mb`: Stop scanning once the process's resident memory
+///   exceeds this many MiB and return the partial result with
+///   [`ScanResult::truncated`] set (see [`MemoryGuard`])
+/// - `sidecar_index`: Sidecars of the library before the scan; a file without
+///   a sidecar whose hash matches one whose photo is gone is treated as
+///   renamed (see [`SidecarIndex`])
+/// - `traversal_order`: Order in which the walked files of each root are
+///   scanned (default depth-first, as walked; see [`TraversalOrder`])
+/// - `force_overwrite_newer`: Replace sidecars whose schema is newer than
+///   this build understands instead of reporting [`ScanAction::Conflict`]
+///   (their newer fields are lost)
+/// - `validate_encoding`: Check each file's structure before parsing it and
+///   fail files with a broken one (reads whole JPEGs; see
+///   [`validate_image_encoding()`])
+/// - `compress_sidecars`: Write sidecars gzipped as `<sidecar>.gz`; an
+///   uncompressed one and its backups become the `.gz` backups (see
+///   [`crate::util::compression`])
+///
+/// A non-default sidecar naming is saved to the library's
+/// `.jozin/config.json`; later scans with the default naming pick it up from
+/// there (see [`LibraryConfig`]).
+///
+/// # Example
+///
+/// ```
+/// use jozin_core::scan::ScanConfig;
+///
+/// let config = ScanConfig {
+///     recursive: true,
+///     exclude: Some(vec![String::from("**/.jozin/**")]),
+///     ..ScanConfig::default()
+/// };
+/// assert!(!config.dry_run);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ScanConfig {
+    pub recursive: bool,
+    pub include: Option<Vec<String>>,
+    pub exclude: Option<Vec<String>>,
+    pub dry_run: bool,
+    pub max_threads: u16,
+    pub hash_mode: Option<String>,
+    pub content_type_filter: Option<Vec<String>>,
+    pub include_raw_exif: bool,
+    pub link_live_photos: bool,
+    pub failure_policy: FailurePolicy,
+    pub scan_archives: bool,
+    pub extract_archives: Option<PathBuf>,
+    pub hash_algorithm: HashAlgorithm,
+    pub sidecar_suffix: String,
+    pub sidecar_prefix: Option<String>,
+    pub symlinks: SymlinkStrategy,
+    pub assume_timezone: UtcOffset,
+    pub io_budget: Option<IoBudget>,
+    pub journal: Option<PathBuf>,
+    pub hash_cache: Option<Arc<Mutex<HashCache>>>,
+    pub fsync_policy: FsyncPolicy,
+    pub force_local_io: bool,
+    pub output_sort_order: SortOrder,
+    pub memory_limit_mb: Option<u64>,
+    pub sidecar_index: Option<Arc<SidecarIndex>>,
+    pub traversal_order: TraversalOrder,
+    pub force_overwrite_newer: bool,
+    pub validate_encoding: bool,
+    pub compress_sidecars: bool,
+}
+
+impl Default for ScanConfig {
+    fn default() -> Self {
+        Self {
+            recursive: false,
+            include: None,
+            exclude: None,
+            dry_run: false,
+            max_threads: 4,
+            hash_mode: None,
+            content_type_filter: None,
+            include_raw_exif: false,
+            link_live_photos: false,
+            failure_policy: FailurePolicy::default(),
+            scan_archives: false,
+            extract_archives: None,
+            hash_algorithm: HashAlgorithm::Blake3,
+            sidecar_suffix: DEFAULT_SIDECAR_SUFFIX.to_string(),
+            sidecar_prefix: None,
+            symlinks: SymlinkStrategy::default(),
+            assume_timezone: UtcOffset::UTC,
+            io_budget: None,
+            journal: None,
+            hash_cache: None,
+            fsync_policy: FsyncPolicy::default(),
+            force_local_io: false,
+            output_sort_order: SortOrder::default(),
+            memory_limit_mb: None,
+            sidecar_index: None,
+            traversal_order: TraversalOrder::default(),
+            force_overwrite_newer: false,
+            validate_encoding: false,
+            compress_sidecars: false,
+        }
+    }
+}
+
+impl ScanConfig {
+    /// Adapts the config to the filesystem the library is on.
+    ///
+    /// On network filesystems (SMB, NFS), unless `force_local_io` is set:
+    /// - `max_threads` is capped at [`NETWORK_MAX_THREADS`] (bandwidth, not
+    ///   CPU, is the bottleneck)
+    /// - `io_budget` is capped at [`NETWORK_IO_BUDGET_BYTES_PER_SEC`]
+    ///
+    /// On every filesystem, `fsync_policy` is resolved to `Always` or `Never`
+    /// (see [`FsyncPolicy::resolve()`]); `Auto` turns syncing off on network
+    /// filesystems, where a sync per sidecar is very slow (sidecars are still
+    /// renamed into place atomically).
+    pub fn adjusted_for_filesystem(&self, filesystem: FilesystemType) -> ScanConfig {
+        if self.force_local_io || !filesystem.is_network() {
+            let treated_as = if self.force_local_io { FilesystemType::Local } else { filesystem };
+            return ScanConfig {
+                fsync_policy: self.fsync_policy.resolve(treated_as),
+                ..self.clone()
+            };
+        }
+        let network_budget = IoBudget::new(NETWORK_IO_BUDGET_BYTES_PER_SEC);
+        ScanConfig {
+            max_threads: self.max_threads.min(NETWORK_MAX_THREADS),
+            io_budget: Some(self.io_budget.map_or(network_budget, |budget| {
+                IoBudget::new(budget.max_bytes_per_sec.min(network_budget.max_bytes_per_sec))
+            })),
+            fsync_policy: self.fsync_policy.resolve(filesystem),
+            ..self.clone()
+        }
+    }
+
+    /// Sidecar naming of this scan as a [`LibraryConfig`].
+    pub fn library_config(&self) -> LibraryConfig {
+        LibraryConfig {
+            sidecar_prefix: self.sidecar_prefix.clone(),
+            sidecar_suffix: self.sidecar_suffix.clone(),
+        }
+    }
+}
+
+impl ExifCoverage {
+    /// Coverage of a single photo with this `image`.
+    pub fn of(image: Option<&ImageInfo>) -> Self {
+        let mut coverage = Self::default();
+        coverage.add(image);
+        coverage
+    }
+
+    /// Counts one more photo with this `image`.
+    pub fn add(&mut self, image: Option<&ImageInfo>) {
+        self.total += 1;
+        let Some(image) = image else {
+            self.missing_all += 1;
+            return;
+        };
+        let datetime = image.datetime_original.is_some();
+        let gps = image.gps_latitude.is_some() && image.gps_longitude.is_some();
+        let (make, model) = (image.camera_make.is_some(), image.camera_model.is_some());
+
+        self.has_datetime += usize::from(datetime);
+        self.has_gps += usize::from(gps);
+        self.has_camera += usize::from(make || model);
+        self.has_all += usize::from(datetime && gps && make && model);
+        self.missing_all += usize::from(!datetime && !gps && !make && !model);
+    }
+
+    /// Adds the counts of `other`.
+    pub fn merge(&mut self, other: &ExifCoverage) {
+        self.has_datetime += other.has_datetime;
+        self.has_gps += other.has_gps;
+        self.has_camera += other.has_camera;
+        self.has_all += other.has_all;
+        self.missing_all += other.missing_all;
+        self.total += other.total;
+    }
+
+    /// `count` as a whole percentage of `total` (0 when nothing was counted).
+    pub fn percent(&self, count: usize) -> usize {
+        if self.total == 0 {
+            return 0;
+        }
+        (count * 100 + self.total / 2) / self.total
+    }
 }
 
 // ============================================================================
@@ -168,21 +594,12 @@ pub enum ScanAction {
 ///
 /// This is the main entry point for the scan module, handling both single files
 /// and directory trees. It supports recursive traversal, glob pattern filtering,
-/// and dry-run mode.
+/// content-type filtering, and dry-run mode.
 ///
 /// # Arguments
 ///
 /// * `path` - File or directory to scan
-/// * `recursive` - Enable recursive directory traversal (ignored for files)
-/// * `include` - Glob patterns to include (e.g., `["*.jpg", "*.png"]`). If specified,
-///               only files matching at least one pattern are scanned.
-/// * `exclude` - Glob patterns to exclude (e.g., `["**/.jozin/**"]`). Files matching
-///               any exclude pattern are skipped. Applied before include patterns.
-/// * `dry_run` - Preview mode: compute metadata but don't write sidecars
-/// * `max_threads` - Parallelism limit (Phase 1: unused, processes sequentially.
-///                   Phase 2: will use rayon for parallel processing)
-/// * `hash_mode` - Hash computation strategy: "file", "pixel", or "both"
-///                 (Phase 1: only "file" is supported)
+/// * `config` - Scan options (see [`ScanConfig`])
 /// * `progress_callback` - Optional callback for real-time progress reporting
 ///
 /// # Returns
@@ -202,38 +619,29 @@ pub enum ScanAction {
 /// # Examples
 ///
 /// ```no_run
-/// use jozin_core::scan::scan_path;
+/// use jozin_core::scan::{scan_path, ScanConfig};
 /// use std::path::Path;
 ///
 /// // Scan a single file
-/// let result = scan_path(
-///     Path::new("/photos/IMG_1234.JPG"),
-///     false, None, None, false, 4, Some("file"), None
-/// )?;
+/// let result = scan_path(Path::new("/photos/IMG_1234.JPG"), &ScanConfig::default(), None)?;
 /// assert_eq!(result.total_files, 1);
 ///
 /// // Scan directory with filtering
-/// let result = scan_path(
-///     Path::new("/photos"),
-///     true,
-///     Some(&[String::from("*.jpg")]),
-///     Some(&[String::from("**/.jozin/**")]),
-///     false,
-///     8,
-///     Some("file"),
-///     None
-/// )?;
+/// let config = ScanConfig {
+///     recursive: true,
+///     include: Some(vec![String::from("*.jpg")]),
+///     exclude: Some(vec![String::from("**/.jozin/**")]),
+///     max_threads: 8,
+///     hash_mode: Some(String::from("file")),
+///     ..ScanConfig::default()
+/// };
+/// let result = scan_path(Path::new("/photos"), &config, None)?;
 /// println!("Scanned {} files", result.successful);
 /// # Ok::<(), jozin_core::JozinError>(())
 /// ```
 pub fn scan_path(
     path: &Path,
-    recursive: bool,
-    include: Option<&[String]>,
-    exclude: Option<&[String]>,
-    dry_run: bool,
-    _max_threads: u16, // Unused in Phase 1, will be used in Phase 2 for rayon
-    _hash_mode: Option<&str>, // Phase 1: only "file" mode is supported
+    config: &ScanConfig,
     progress_callback: Option<&dyn Fn(crate::ProgressEvent)>,
 ) -> Result<ScanResult> {
     // Validate path exists
@@ -243,42 +651,74 @@ pub fn scan_path(
         });
     }
 
+    #[cfg(not(feature = "archives"))]
+    if config.scan_archives {
+        return Err(JozinError::NotSupported {
+            feature: "archive scanning (rebuild with --features archives)".to_string(),
+        });
+    }
+
+    let config = &root_config(path, config)?;
+
     // Handle single file
     if path.is_file() {
+        #[cfg(feature = "archives")]
+        if config.scan_archives && crate::archives::is_zip_file(path) {
+            let mut result = ScanResult {
+                scanned_files: Vec::new(),
+                total_files: 0,
+                successful: 0,
+                failed: 0,
+                skipped: 0,
+                conflicts: 0,
+                truncated: false,
+                cross_root_duplicates: Vec::new(),
+                orphaned_sidecars: Vec::new(),
+                exif_coverage: ExifCoverage::default(),
+            };
+            let mut candidates = Vec::new();
+            crate::archives::queue_archive(path, config, &mut result, &mut candidates);
+            return scan_files_parallel(
+                candidates,
+                config,
+                &|path| scan_file_with_config(path, config),
+                progress_callback,
+                result,
+            );
+        }
+
         // Validate it's an image file
-        if !is_image_file(path) {
+        if !is_scannable_file(path, config.content_type_filter.as_deref()) {
             return Err(JozinError::ValidationError {
                 message: format!("Not an image file: {}", path.display()),
             });
         }
 
+        let dry_run = config.dry_run;
+
         // Scan the file
-        match scan_file(path, dry_run) {
-            Ok(sidecar) => {
-                let action = if dry_run {
-                    ScanAction::Skipped
-                } else {
-                    ScanAction::Written
-                };
-                let scanned_file = ScannedFile {
-                    path: path.display().to_string(),
-                    action,
-                    sidecar_path: if dry_run {
-                        None
-                    } else {
-                        Some(get_sidecar_path(path).display().to_string())
-                    },
-                    error: None,
-                    hash: Some(sidecar.source.file_hash_b3),
-                    size_bytes: Some(sidecar.source.file_size_bytes),
-                };
+        match scan_file_with_config(path, config) {
+            Ok(written) => {
+                let orphan = config.sidecar_index.as_deref().and_then(|index| index.take_rename(path));
+                let action = scan_action(orphan.is_some(), &written);
+                let conflict = matches!(action, ScanAction::Conflict { .. });
+                let scanned_file = scanned_file(path.display().to_string(), action, dry_run, &written);
 
                 Ok(ScanResult {
                     scanned_files: vec![scanned_file],
                     total_files: 1,
-                    successful: if dry_run { 0 } else { 1 },
+                    successful: usize::from(!dry_run && !conflict),
                     failed: 0,
-                    skipped: if dry_run { 1 } else { 0 },
+                    skipped: usize::from(dry_run && !conflict),
+                    conflicts: usize::from(conflict),
+                    truncated: false,
+                    cross_root_duplicates: Vec::new(),
+                    orphaned_sidecars: orphan
+                        .filter(|_| !dry_run)
+                        .map(|path| path.display().to_string())
+                        .into_iter()
+                        .collect(),
+                    exif_coverage: ExifCoverage::of(written.sidecar.image.as_ref()),
                 })
             }
             Err(e) => {
@@ -289,6 +729,7 @@ pub fn scan_path(
                     error: Some(e.to_string()),
                     hash: None,
                     size_bytes: None,
+                    backup_path: None,
                 };
 
                 Ok(ScanResult {
@@ -297,13 +738,18 @@ pub fn scan_path(
                     successful: 0,
                     failed: 1,
                     skipped: 0,
+                    conflicts: 0,
+                    truncated: false,
+                    cross_root_duplicates: Vec::new(),
+                    orphaned_sidecars: Vec::new(),
+                    exif_coverage: ExifCoverage::default(),
                 })
             }
         }
     }
     // Handle directory
     else if path.is_dir() {
-        scan_directory(path, recursive, include, exclude, dry_run, progress_callback)
+        scan_directory(path, config, progress_callback)
     }
     // Path exists but is neither file nor directory (e.g., socket, pipe)
     else {
@@ -313,13 +759,118 @@ pub fn scan_path(
     }
 }
 
+/// Scans several root paths (files or directories) as one operation.
+///
+/// Each root is prepared like in [`scan_path()`] (its own library settings
+/// and filesystem adjustments), then all roots are walked before any file is
+/// scanned, so a single `ProgressEvent::BatchStarted` reports the total. A
+/// file reachable from several roots (nested or repeated roots) is scanned
+/// and reported once, under the first root that contains it.
+///
+/// Files with the same content hash under different roots are listed in
+/// [`ScanResult::cross_root_duplicates`]; duplicates within one root are not.
+///
+/// # Errors
+///
+/// - `JozinError::UserError` if `paths` is empty
+/// - `JozinError::IoError` if a root doesn't exist
+/// - `JozinError::ValidationError` if a file root is not an image, or a root
+///   is neither a file nor a directory
+/// - Otherwise as [`scan_path()`]
+///
+/// # Example
+///
+/// ```no_run
+/// use jozin_core::scan::{scan_paths, ScanConfig};
+/// use std::path::Path;
+///
+/// let config = ScanConfig { recursive: true, ..ScanConfig::default() };
+/// let result = scan_paths(&[Path::new("/photos"), Path::new("/mnt/nas/photos")], &config, None)?;
+/// for group in &result.cross_root_duplicates {
+///     println!("{} copies: {:?}", group.paths.len(), group.paths);
+/// }
+/// # Ok::<(), jozin_core::JozinError>(())
+/// ```
+pub fn scan_paths(
+    paths: &[&Path],
+    config: &ScanConfig,
+    progress_callback: Option<&dyn Fn(crate::ProgressEvent)>,
+) -> Result<ScanResult> {
+    if paths.is_empty() {
+        return Err(JozinError::UserError {
+            message: "No paths to scan".to_string(),
+        });
+    }
+    if let Some(missing) = paths.iter().find(|path| !path.exists()) {
+        return Err(JozinError::IoError {
+            message: format!("Path not found: {}", missing.display()),
+        });
+    }
+    #[cfg(not(feature = "archives"))]
+    if config.scan_archives {
+        return Err(JozinError::NotSupported {
+            feature: "archive scanning (rebuild with --features archives)".to_string(),
+        });
+    }
+
+    // Walk every root first: the batch total spans all of them
+    let mut seen = HashSet::new();
+    let mut roots = Vec::with_capacity(paths.len());
+    for path in paths {
+        let root_config = root_config(path, config)?;
+        let (mut partial, mut candidates) = walk_root(path, &root_config)?;
+        partial.scanned_files.retain(|file| seen.insert(dedup_key(Path::new(&file.path))));
+        candidates.retain(|candidate| seen.insert(dedup_key(candidate)));
+        recount(&mut partial, candidates.len());
+        roots.push((root_config, partial, candidates));
+    }
+    let all_candidates: Vec<PathBuf> = roots.iter().flat_map(|(_, _, candidates)| candidates.clone()).collect();
+    emit_batch_started(&all_candidates, config, progress_callback);
+
+    let mut result = ScanResult {
+        scanned_files: Vec::new(),
+        total_files: 0,
+        successful: 0,
+        failed: 0,
+        skipped: 0,
+        conflicts: 0,
+        truncated: false,
+        cross_root_duplicates: Vec::new(),
+        orphaned_sidecars: Vec::new(),
+        exif_coverage: ExifCoverage::default(),
+    };
+    // Root index of each scanned file, for cross-root duplicates
+    let mut file_roots = Vec::new();
+    for (index, (root_config, partial, candidates)) in roots.into_iter().enumerate() {
+        if result.truncated {
+            break;
+        }
+        let root_result = scan_candidates(candidates, &root_config, progress_callback, partial)?;
+        result.total_files += root_result.total_files;
+        result.successful += root_result.successful;
+        result.failed += root_result.failed;
+        result.skipped += root_result.skipped;
+        result.conflicts += root_result.conflicts;
+        result.truncated |= root_result.truncated;
+        result.exif_coverage.merge(&root_result.exif_coverage);
+        result.orphaned_sidecars.extend(root_result.orphaned_sidecars);
+        file_roots.extend(std::iter::repeat_n(index, root_result.scanned_files.len()));
+        result.scanned_files.extend(root_result.scanned_files);
+    }
+
+    result.cross_root_duplicates = cross_root_duplicates(&result.scanned_files, &file_roots);
+    sort_scanned_files(&mut result.scanned_files, config.output_sort_order);
+    emit_batch_completed(&result, progress_callback);
+    Ok(result)
+}
+
 /// Scans a single file and generates its sidecar metadata.
 ///
 /// This function:
 /// 1. Reads file metadata (size, modification time)
 /// 2. Computes BLAKE3 hash of file contents
 /// 3. Creates a Sidecar struct with current pipeline signature
-/// 4. Writes sidecar atomically to `<file_path>.json`
+/// 4. Writes sidecar atomically to `<file_path>.json` (or the library's configured naming)
 ///
 /// # Arguments
 ///
@@ -328,7 +879,8 @@ pub fn scan_path(
 ///
 /// # Returns
 ///
-/// Returns the generated `Sidecar` structure.
+/// Returns the generated `Sidecar` along with what happened on disk (see
+/// [`SidecarWriteResult`]).
 ///
 /// # Errors
 ///
@@ -338,14 +890,40 @@ pub fn scan_path(
 /// # Example
 ///
 /// ```no_run
-/// use jozin_core::scan::scan_file;
+/// use jozin_core::scan::{scan_file, WriteAction};
 /// use std::path::Path;
 ///
-/// let sidecar = scan_file(Path::new("/photos/IMG_1234.JPG"), false)?;
-/// println!("Hash: {}", sidecar.source.file_hash_b3);
+/// let written = scan_file(Path::new("/photos/IMG_1234.JPG"), false)?;
+/// println!("Hash: {}", written.sidecar.source.file_hash_b3);
+/// if written.action == WriteAction::Updated {
+///     println!("Previous sidecar kept at {}", written.backup_path.unwrap().display());
+/// }
 /// # Ok::<(), jozin_core::JozinError>(())
 /// ```
-pub fn scan_file(file_path: &Path, dry_run: bool) -> Result<Sidecar> {
+pub fn scan_file(file_path: &Path, dry_run: bool) -> Result<SidecarWriteResult> {
+    let naming = LibraryConfig::discover(file_path);
+    let config = ScanConfig {
+        dry_run,
+        sidecar_prefix: naming.sidecar_prefix,
+        sidecar_suffix: naming.sidecar_suffix,
+        ..ScanConfig::default()
+    };
+    scan_file_with_config(file_path, &config)
+}
+
+/// Scans a single file using the per-file options from a [`ScanConfig`].
+///
+/// Same as [`scan_file()`], but honours options that affect sidecar content
+/// (e.g., `include_raw_exif`). Directory-level options such as `recursive`
+/// and glob patterns are ignored.
+///
+/// # Errors
+///
+/// - `JozinError::IoError` if file cannot be read or sidecar cannot be written
+/// - `JozinError::ValidationError` if file path is invalid
+pub fn scan_file_with_config(file_path: &Path, config: &ScanConfig) -> Result<SidecarWriteResult> {
+    let dry_run = config.dry_run;
+
     // Validate path
     if !file_path.exists() {
         return Err(JozinError::IoError {
@@ -359,6 +937,29 @@ pub fn scan_file(file_path: &Path, dry_run: bool) -> Result<Sidecar> {
         });
     }
 
+    // Leave sidecars from a newer Jožin alone (reported as a conflict)
+    let naming = config.library_config();
+    let existing_path = existing_sidecar_path(&naming.sidecar_path(file_path));
+    let sidecar_path = match config.compress_sidecars {
+        true => compressed_path(&existing_path),
+        false => existing_path.clone(),
+    };
+    if !config.force_overwrite_newer {
+        if let Some(existing) = newer_schema_sidecar(&existing_path) {
+            return existing.map(|sidecar| SidecarWriteResult {
+                previous_schema_version: Some(sidecar.schema_version.clone()),
+                sidecar,
+                action: WriteAction::Unchanged,
+                sidecar_path: existing_path,
+                backup_path: None,
+            });
+        }
+    }
+
+    if config.validate_encoding {
+        check_encoding(file_path)?;
+    }
+
     // Read file metadata
     let metadata = fs::metadata(file_path)?;
     let file_size_bytes = metadata.len();
@@ -372,309 +973,975 @@ pub fn scan_file(file_path: &Path, dry_run: bool) -> Result<Sidecar> {
             message: format!("Failed to format modification time: {}", e),
         })?;
 
-    // Compute BLAKE3 hash
-    let file_hash_b3 = compute_blake3_hash(file_path)?;
+    // Compute content hash
+    // "pixel" mode is not implemented yet and still hashes the file sequentially.
+    // Rate-limited reads are hashed sequentially too; chunking can't outpace the budget
+    let compute_b3 = || match config.io_budget {
+        Some(budget) => compute_blake3_hash_limited(file_path, budget),
+        None if config.hash_mode.as_deref() != Some("pixel") => {
+            compute_hash_parallel(file_path, DEFAULT_PARALLEL_CHUNK_BYTES)
+        }
+        None => compute_blake3_hash(file_path),
+    };
+    let (file_hash_b3, file_hash_sha256) = match (config.hash_algorithm, config.io_budget) {
+        (HashAlgorithm::Sha256, None) => (String::new(), Some(compute_sha256_hash(file_path)?)),
+        (HashAlgorithm::Sha256, Some(budget)) => (String::new(), Some(compute_sha256_hash_limited(file_path, budget)?)),
+        (HashAlgorithm::Blake3, _) => (
+            cached_hash(config.hash_cache.as_deref(), file_path, file_size_bytes, modified_offset, compute_b3)?,
+            None,
+        ),
+    };
 
-    // Create pipeline signature
-    let now = OffsetDateTime::now_utc();
-    let created_at = now
-        .format(&time::format_description::well_known::Rfc3339)
-        .map_err(|e| JozinError::InternalError {
-            message: format!("Failed to format timestamp: {}", e),
-        })?;
+    // Named EXIF fields (capture time, GPS), plus the raw appendix when opted in
+    let mut image = crate::exif::read_image_info(file_path, config.assume_timezone, config.io_budget)?;
+    // EXIF only names sRGB and Adobe RGB; other color spaces are named by the ICC profile
+    let exif_color_space = image.as_ref().and_then(|info| info.color_space.as_deref());
+    if exif_color_space.is_none_or(|name| name == "Uncalibrated") {
+        if let Ok(Some(profile)) = extract_icc_profile(file_path) {
+            image.get_or_insert_with(ImageInfo::default).color_space = Some(profile.description);
+        }
+    }
+    if config.include_raw_exif {
+        if let Some(raw) = crate::exif::read_raw_exif(file_path)? {
+            image.get_or_insert_with(ImageInfo::default).exif_json = Some(raw);
+        }
+    }
 
-    let pipeline_signature = PipelineSignature {
-        schema_version: "1.0.0".to_string(),
-        producer_version: env!("CARGO_PKG_VERSION").to_string(),
-        hash_algorithm: "blake3".to_string(),
-        face_model: None,
-        tag_model: None,
-        created_at: created_at.clone(),
+    let live_photo_path = if config.link_live_photos {
+        find_motion_companion(file_path).map(|p| p.display().to_string())
+    } else {
+        None
     };
 
-    // Build sidecar
-    let sidecar = Sidecar {
-        schema_version: "1.0.0".to_string(),
-        producer_version: env!("CARGO_PKG_VERSION").to_string(),
-        created_at: created_at.clone(),
-        updated_at: created_at,
-        pipeline_signature,
-        source: SourceInfo {
-            file_path: file_path.display().to_string(),
-            file_size_bytes,
-            file_hash_b3,
-            file_modified_at,
-        },
-        image: None, // EXIF parsing to be added in Phase 1+
-        faces: Vec::new(),
-        tags: Vec::new(),
-        thumbnails: Vec::new(),
+    let base_dir = std::env::current_dir().unwrap_or_default();
+    let source = SourceInfo {
+        file_path: normalise_sidecar_path(&file_path.display().to_string(), &base_dir),
+        file_size_bytes,
+        file_hash_b3,
+        file_hash_sha256,
+        file_modified_at,
+        ingest_timestamp: String::new(),
+        live_photo_path,
+        previous_paths: Vec::new(),
+        hardlink_of: None,
+    };
+    let sidecar = match renamed_sidecar(file_path, &source, &naming, config) {
+        Some(sidecar) => sidecar?,
+        None => build_sidecar(file_path, source, image, &naming)?,
     };
 
-    // Write sidecar atomically (unless dry_run)
+    // Write sidecar atomically (unless dry_run); an existing one is rotated to .bak1
+    let existed = existing_path.exists();
+    let previous_schema_version = if existed { sidecar_schema_version(&existing_path) } else { None };
+    let (action, backup_path) = match (dry_run, existed) {
+        (true, _) => (WriteAction::DryRunPreview, None),
+        (false, false) => (WriteAction::Created, None),
+        (false, true) => (WriteAction::Updated, Some(append_to_file_name(&sidecar_path, ".bak1"))),
+    };
     if !dry_run {
-        write_sidecar(file_path, &sidecar)?;
+        // Compressing converts the uncompressed sidecar and its backups first,
+        // so it is rotated into the `.gz` backup chain like any other update
+        if existed && existing_path != sidecar_path {
+            convert_sidecar_file(&existing_path, true)?;
+        }
+        write_sidecar_json(&sidecar_path, &serde_json::to_string_pretty(&sidecar)?, config.fsync_policy)?;
+        if let Some(journal) = &config.journal {
+            let hash = sidecar.source.file_hash_sha256.as_deref().unwrap_or(&sidecar.source.file_hash_b3);
+            ChecksumJournal::new(journal).append(&JournalEntry::now(file_path, hash, file_size_bytes)?)?;
+        }
     }
 
-    Ok(sidecar)
+    Ok(SidecarWriteResult {
+        sidecar,
+        action,
+        sidecar_path,
+        backup_path,
+        previous_schema_version,
+    })
 }
 
-// ============================================================================
-// Internal Helpers
-// ============================================================================
-
-/// Scans a directory and generates sidecar metadata for all image files.
-///
-/// This function orchestrates directory traversal with filtering. In Phase 1,
-/// it processes files sequentially. Phase 2 will add parallel processing using rayon.
+/// Reads and parses the sidecar of an image file (`<file_path>.json`, or the
+/// library's configured naming; compressed `.json.gz` if only that exists).
 ///
-/// The filtering logic is applied in this order for performance:
-/// 1. Skip directories (only process files)
-/// 2. Apply exclude patterns (early rejection)
-/// 3. Apply include patterns (if specified)
-/// 4. Check image file extension
-/// 5. Scan the file
+/// # Errors
 ///
-/// # Arguments
+/// - `JozinError::IoError` if the sidecar does not exist or cannot be read
+/// - `JozinError::ValidationError` if the sidecar is not valid JSON
+pub fn read_sidecar(file_path: &Path) -> Result<Sidecar> {
+    let sidecar_path = LibraryConfig::discover(file_path).sidecar_path(file_path);
+    parse_sidecar(&read_sidecar_file(&existing_sidecar_path(&sidecar_path))?)
+}
+
+/// Loads all sidecars under a path.
 ///
-/// * `dir_path` - Directory to scan
-/// * `recursive` - Traverse subdirectories
-/// * `include` - Include glob patterns (e.g., `["*.jpg", "*.png"]`)
-/// * `exclude` - Exclude glob patterns (e.g., `["**/.jozin/**"]`)
-/// * `dry_run` - Preview mode without writing sidecars
+/// `path` may be an image file (its sidecar is loaded), a sidecar file, or a
+/// directory. In directories, only sidecars named after a supported image
+/// (`<image>.json`, or the library's configured naming) are considered. Unparsable sidecars are reported as
+/// warnings and skipped.
 ///
-/// # Returns
+/// # Arguments
 ///
-/// Returns a [`ScanResult`] with all files processed, including successes,
-/// failures, and skipped files.
+/// * `path` - Image, sidecar, or directory to load from
+/// * `recursive` - Traverse subdirectories (ignored for files)
 ///
-/// # Error Handling
+/// # Errors
 ///
-/// Individual file scan failures do not stop the entire operation. Errors are
-/// collected in the result structure rather than failing fast. This ensures
-/// that one corrupted or unreadable file doesn't prevent scanning the rest.
-fn scan_directory(
-    dir_path: &Path,
-    recursive: bool,
-    include: Option<&[String]>,
-    exclude: Option<&[String]>,
-    dry_run: bool,
-    progress_callback: Option<&dyn Fn(crate::ProgressEvent)>,
-) -> Result<ScanResult> {
-    // Initialize result
-    let mut result = ScanResult {
-        scanned_files: Vec::new(),
-        total_files: 0,
-        successful: 0,
-        failed: 0,
-        skipped: 0,
-    };
-
-    // Build glob matchers
-    let exclude_matcher = if let Some(patterns) = exclude {
-        Some(build_glob_matcher(patterns)?)
-    } else {
-        None
-    };
+/// Returns `JozinError::IoError` if `path` does not exist.
+pub fn load_sidecars(path: &Path, recursive: bool) -> Result<Vec<Sidecar>> {
+    if !path.exists() {
+        return Err(JozinError::IoError {
+            message: format!("Path not found: {}", path.display()),
+        });
+    }
 
-    let include_matcher = if let Some(patterns) = include {
-        Some(build_glob_matcher(patterns)?)
-    } else {
-        None
-    };
+    let naming = LibraryConfig::discover(path);
+    if path.is_file() {
+        return if naming.is_sidecar(path) {
+            Ok(vec![parse_sidecar(&read_sidecar_file(path)?)?])
+        } else {
+            read_sidecar(path).map(|sidecar| vec![sidecar])
+        };
+    }
 
-    // Configure directory walker
     let walker = if recursive {
-        WalkDir::new(dir_path)
+        WalkDir::new(path)
     } else {
-        WalkDir::new(dir_path).max_depth(1)
+        WalkDir::new(path).max_depth(1)
     };
 
-    // Iterate through directory entries
-    for entry in walker {
-        // Handle walkdir errors (permission denied, etc.)
-        let entry = match entry {
-            Ok(e) => e,
-            Err(e) => {
-                // Log error but continue scanning
-                eprintln!("Warning: Failed to access entry: {}", e);
-                continue;
-            }
-        };
-
-        let path = entry.path();
-
-        // Skip directories, only process files
-        if !path.is_file() {
+    let mut sidecars = Vec::new();
+    for entry in walker.into_iter().filter_map(|e| e.ok()) {
+        let entry_path = entry.path();
+        if !entry_path.is_file() || !naming.is_sidecar(entry_path) {
             continue;
         }
 
-        // Apply exclude patterns first (early rejection for performance)
-        if let Some(ref matcher) = exclude_matcher {
-            if matcher.is_match(path) {
-                result.total_files += 1;
-                result.skipped += 1;
-                result.scanned_files.push(ScannedFile {
-                    path: path.display().to_string(),
-                    action: ScanAction::Skipped,
-                    sidecar_path: None,
-                    error: Some("Excluded by pattern".to_string()),
-                    hash: None,
-                    size_bytes: None,
-                });
-                continue;
-            }
+        match read_sidecar_file(entry_path).and_then(|json| parse_sidecar(&json))
+        {
+            Ok(sidecar) => sidecars.push(sidecar),
+            Err(e) => eprintln!("Warning: Skipping sidecar {}: {}", entry_path.display(), e),
         }
+    }
 
-        // Apply include patterns (if specified, file must match at least one)
-        if let Some(ref matcher) = include_matcher {
-            if !matcher.is_match(path) {
-                result.total_files += 1;
-                result.skipped += 1;
-                result.scanned_files.push(ScannedFile {
-                    path: path.display().to_string(),
-                    action: ScanAction::Skipped,
-                    sidecar_path: None,
-                    error: Some("Not included by pattern".to_string()),
-                    hash: None,
+    Ok(sidecars)
+}
+
+/// Writes a sidecar file atomically with backup rotation.
+///
+/// Atomic write strategy:
+/// 1. Write to temporary file: `<file_path>.json.tmp`
+/// 2. Fsync to ensure data is on disk
+/// 3. Rename to final location: `<file_path>.json`
+///
+/// Backup rotation strategy:
+/// - If `<file_path>.json` exists, rotate to `.bak1`
+/// - If `.bak1` exists, rotate to `.bak2`
+/// - If `.bak2` exists, rotate to `.bak3`
+/// - `.bak3` is overwritten (oldest backup is lost)
+///
+/// The sidecar is named after the library's configured naming (see
+/// [`LibraryConfig::discover()`]). If only a compressed sidecar exists, it is
+/// replaced by a compressed one (see [`crate::util::compression`]).
+///
+/// # Errors
+///
+/// Returns `JozinError::IoError` if writing or renaming fails.
+pub fn write_sidecar(file_path: &Path, sidecar: &Sidecar) -> Result<()> {
+    write_sidecar_with(file_path, sidecar, &LibraryConfig::discover(file_path))
+}
+
+/// [`write_sidecar()`] with a known sidecar naming.
+pub(crate) fn write_sidecar_with(file_path: &Path, sidecar: &Sidecar, naming: &LibraryConfig) -> Result<()> {
+    write_sidecar_synced(file_path, sidecar, naming, FsyncPolicy::Always)
+}
+
+/// [`write_sidecar_with()`], syncing the file before the rename as
+/// `fsync_policy` says (see `ScanConfig::fsync_policy`).
+pub(crate) fn write_sidecar_synced(file_path: &Path, sidecar: &Sidecar, naming: &LibraryConfig, fsync_policy: FsyncPolicy) -> Result<()> {
+    // Serialize to JSON
+    let json = serde_json::to_string_pretty(sidecar)?;
+    write_sidecar_json(&existing_sidecar_path(&naming.sidecar_path(file_path)), &json, fsync_policy)
+}
+
+/// Writes already-serialized sidecar JSON to `sidecar_path` with the same
+/// atomic write and backup rotation as [`write_sidecar()`], gzipped if
+/// `sidecar_path` ends with `.gz`.
+pub(crate) fn write_sidecar_json(sidecar_path: &Path, json: &str, fsync_policy: FsyncPolicy) -> Result<()> {
+    let tmp_path = get_tmp_sidecar_path(sidecar_path);
+
+    // Rotate backups if sidecar already exists
+    if sidecar_path.exists() {
+        rotate_backups(sidecar_path)?;
+    }
+
+    // Write to temporary file
+    let mut tmp_file = File::create(&tmp_path)?;
+    tmp_file.write_all(&encode_sidecar(json, is_compressed_path(sidecar_path))?)?;
+    if fsync_policy.should_sync() {
+        tmp_file.sync_all()?; // Ensure data is on disk
+    }
+
+    // Atomic rename
+    atomic_rename(&tmp_path, sidecar_path)?;
+
+    // Spotlight tag (macOS); best effort, e.g. exFAT has no extended attributes
+    platform::mark_jozin_generated(sidecar_path).ok();
+
+    Ok(())
+}
+
+// ============================================================================
+// Internal Helpers
+// ============================================================================
+
+/// Scans a directory and generates sidecar metadata for all image files.
+///
+/// This function orchestrates directory traversal with filtering. With
+/// `config.max_threads > 1` entries are filtered on a [`ParallelWalker`] so
+/// directory reads overlap the per-entry checks (results are sorted by path
+/// afterwards); otherwise traversal is sequential. The remaining files are
+/// scanned in parallel by [`scan_files_parallel()`].
+///
+/// The filtering logic is applied in this order for performance:
+/// 1. Skip directories (only process files); directories excluded from backups
+///    on macOS are not descended into
+/// 2. Apply exclude patterns (early rejection)
+/// 3. Apply include patterns (if specified)
+/// 4. Check image file extension (or sniffed MIME type when a content-type filter is set)
+/// 5. Scan the file (in parallel, up to `config.max_threads` threads)
+///
+/// Before scanning starts, a `ProgressEvent::BatchStarted` event carries the
+/// file count and a duration estimate from [`estimate_scan_duration()`].
+///
+/// # Arguments
+///
+/// * `dir_path` - Directory to scan
+/// * `config` - Scan options (recursion, include/exclude patterns, content types, dry run)
+///
+/// # Returns
+///
+/// Returns a [`ScanResult`] with all files processed, including successes,
+/// failures, and skipped files.
+///
+/// # Error Handling
+///
+/// Individual file scan failures do not stop the entire operation. Errors are
+/// collected in the result structure rather than failing fast. This ensures
+/// that one corrupted or unreadable file doesn't prevent scanning the rest.
+/// `config.failure_policy` decides when internal errors or too many file
+/// failures abort the scan instead.
+fn scan_directory(
+    dir_path: &Path,
+    config: &ScanConfig,
+    progress_callback: Option<&dyn Fn(crate::ProgressEvent)>,
+) -> Result<ScanResult> {
+    let (result, candidates) = walk_directory(dir_path, config)?;
+    emit_batch_started(&candidates, config, progress_callback);
+    let mut result = scan_candidates(candidates, config, progress_callback, result)?;
+    sort_scanned_files(&mut result.scanned_files, config.output_sort_order);
+    emit_batch_completed(&result, progress_callback);
+    Ok(result)
+}
+
+/// Applies the per-library settings of a scan root to `config`.
+///
+/// Explicit sidecar naming options win and are saved for the library,
+/// otherwise the library's saved naming applies. The filesystem type is
+/// detected once for the whole root.
+fn root_config(path: &Path, config: &ScanConfig) -> Result<ScanConfig> {
+    let naming = config.library_config();
+    naming.validate()?;
+    let library_root = if path.is_file() { path.parent().unwrap_or(path) } else { path };
+    let naming = if naming.is_default_naming() {
+        LibraryConfig::discover(library_root)
+    } else {
+        if !config.dry_run {
+            naming.save(library_root)?;
+        }
+        naming
+    };
+    Ok(ScanConfig {
+        sidecar_prefix: naming.sidecar_prefix,
+        sidecar_suffix: naming.sidecar_suffix,
+        ..config.adjusted_for_filesystem(detect_filesystem_type(library_root))
+    })
+}
+
+/// Walks a directory, returning the filtered-out files (as a partial
+/// [`ScanResult`]) and the files to scan, in `config.traversal_order`.
+fn walk_directory(dir_path: &Path, config: &ScanConfig) -> Result<(ScanResult, Vec<PathBuf>)> {
+    // Initialize result
+    let mut result = ScanResult {
+        scanned_files: Vec::new(),
+        total_files: 0,
+        successful: 0,
+        failed: 0,
+        skipped: 0,
+        conflicts: 0,
+        truncated: false,
+        cross_root_duplicates: Vec::new(),
+        orphaned_sidecars: Vec::new(),
+        exif_coverage: ExifCoverage::default(),
+    };
+
+    let content_type_filter = config.content_type_filter.as_deref();
+
+    // Build glob matchers (case-insensitive if the scanned filesystem is)
+    let exclude_matcher = if let Some(patterns) = &config.exclude {
+        Some(build_case_aware_glob_matcher(patterns, dir_path)?)
+    } else {
+        None
+    };
+
+    let include_matcher = if let Some(patterns) = &config.include {
+        Some(build_case_aware_glob_matcher(patterns, dir_path)?)
+    } else {
+        None
+    };
+
+    // Classify directory entries, collecting files to scan. With several
+    // threads the walk overlaps the per-entry stat and content sniffing.
+    let filters = EntryFilters {
+        exclude: exclude_matcher.as_ref(),
+        include: include_matcher.as_ref(),
+        content_type_filter,
+        symlinks: config.symlinks,
+        #[cfg(feature = "archives")]
+        scan_archives: config.scan_archives,
+    };
+    let entries: Box<dyn Iterator<Item = Result<WalkEntry>>> = if config.max_threads > 1 {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(usize::from(config.max_threads))
+            .build()
+            .map_err(|e| JozinError::InternalError {
+                message: format!("Failed to start walk thread pool: {}", e),
+            })?;
+        let walk = ParallelWalker::new(dir_path, config.recursive)
+            .symlinks(config.symlinks)
+            .skip_backup_excluded(true)
+            .max_threads(config.max_threads);
+        let mut entries = pool.install(|| {
+            use rayon::iter::ParallelIterator;
+            walk.par_iter()
+                .map(|entry| classify_entry(entry, &filters))
+                .collect::<Result<Vec<WalkEntry>>>()
+        })?;
+        // Report in a stable order regardless of thread timing
+        entries.sort_by(|a, b| a.path().cmp(&b.path()));
+        Box::new(entries.into_iter().map(Ok))
+    } else {
+        let walk = walker(dir_path, config.recursive, config.symlinks).into_iter();
+        Box::new(walk.filter_entry(|entry| !is_backup_excluded_dir(entry)).map(|entry| {
+            let entry = entry.map_err(|e| JozinError::IoError { message: e.to_string() });
+            classify_entry(entry, &filters)
+        }))
+    };
+
+    let mut candidates = Vec::new();
+    #[cfg(feature = "archives")]
+    let mut archives = Vec::new();
+    for entry in entries {
+        match entry? {
+            WalkEntry::Ignored => {}
+            WalkEntry::Skipped { path, reason } => {
+                result.total_files += 1;
+                result.skipped += 1;
+                result.scanned_files.push(ScannedFile {
+                    path: path.display().to_string(),
+                    action: ScanAction::Skipped,
+                    sidecar_path: None,
+                    error: Some(reason.to_string()),
+                    hash: None,
                     size_bytes: None,
+                    backup_path: None,
                 });
-                continue;
+            }
+            // Archives are expanded after the walk (include patterns target images)
+            #[cfg(feature = "archives")]
+            WalkEntry::Archive(path) => archives.push(path),
+            // Queue the file for parallel scanning
+            WalkEntry::Candidate(path) => {
+                result.total_files += 1;
+                candidates.push(path);
             }
         }
+    }
 
-        // Check if file is an image by extension
-        if !is_image_file(path) {
-            result.total_files += 1;
-            result.skipped += 1;
-            result.scanned_files.push(ScannedFile {
-                path: path.display().to_string(),
-                action: ScanAction::Skipped,
-                sidecar_path: None,
-                error: Some("Not an image file (unsupported extension)".to_string()),
-                hash: None,
-                size_bytes: None,
-            });
+    #[cfg(feature = "archives")]
+    for archive in archives {
+        crate::archives::queue_archive(&archive, config, &mut result, &mut candidates);
+    }
+
+    order_files(&mut candidates, config.traversal_order);
+    Ok((result, candidates))
+}
+
+/// Walks one root of [`scan_paths()`]: a directory, or a single file.
+fn walk_root(path: &Path, config: &ScanConfig) -> Result<(ScanResult, Vec<PathBuf>)> {
+    if path.is_dir() {
+        return walk_directory(path, config);
+    }
+    if !path.is_file() {
+        return Err(JozinError::ValidationError {
+            message: format!("Path is neither a file nor a directory: {}", path.display()),
+        });
+    }
+
+    let mut result = ScanResult {
+        scanned_files: Vec::new(),
+        total_files: 0,
+        successful: 0,
+        failed: 0,
+        skipped: 0,
+        conflicts: 0,
+        truncated: false,
+        cross_root_duplicates: Vec::new(),
+        orphaned_sidecars: Vec::new(),
+        exif_coverage: ExifCoverage::default(),
+    };
+    let mut candidates = Vec::new();
+    #[cfg(feature = "archives")]
+    if config.scan_archives && crate::archives::is_zip_file(path) {
+        crate::archives::queue_archive(path, config, &mut result, &mut candidates);
+        return Ok((result, candidates));
+    }
+    if !is_scannable_file(path, config.content_type_filter.as_deref()) {
+        return Err(JozinError::ValidationError {
+            message: format!("Not an image file: {}", path.display()),
+        });
+    }
+    candidates.push(path.to_path_buf());
+    result.total_files = 1;
+    Ok((result, candidates))
+}
+
+/// Recomputes the counts of a walk result after files were removed from it;
+/// `pending` files are still to be scanned.
+fn recount(result: &mut ScanResult, pending: usize) {
+    result.total_files = result.scanned_files.len() + pending;
+    (result.successful, result.failed, result.skipped, result.conflicts) = (0, 0, 0, 0);
+    for file in &result.scanned_files {
+        match file.action {
+            ScanAction::Written | ScanAction::Updated | ScanAction::Renamed { .. } => result.successful += 1,
+            ScanAction::Failed => result.failed += 1,
+            ScanAction::Skipped => result.skipped += 1,
+            ScanAction::Conflict { .. } => result.conflicts += 1,
+        }
+    }
+}
+
+/// Identity of a file across roots (its canonical path, when it exists).
+fn dedup_key(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Groups files by hash, keeping groups that span more than one root.
+///
+/// `file_roots[i]` is the root index of `files[i]`.
+fn cross_root_duplicates(files: &[ScannedFile], file_roots: &[usize]) -> Vec<DuplicateGroup> {
+    let mut groups: Vec<(DuplicateGroup, HashSet<usize>)> = Vec::new();
+    let mut by_hash: HashMap<&str, usize> = HashMap::new();
+    for (file, root) in files.iter().zip(file_roots) {
+        let Some(hash) = file.hash.as_deref() else {
             continue;
+        };
+        let index = *by_hash.entry(hash).or_insert_with(|| {
+            groups.push((DuplicateGroup { hash: hash.to_string(), paths: Vec::new() }, HashSet::new()));
+            groups.len() - 1
+        });
+        groups[index].0.paths.push(file.path.clone());
+        groups[index].1.insert(*root);
+    }
+    groups.into_iter().filter(|(_, roots)| roots.len() > 1).map(|(group, _)| group).collect()
+}
+
+/// Emits `ProgressEvent::BatchStarted` for the files about to be scanned.
+fn emit_batch_started(
+    candidates: &[PathBuf],
+    config: &ScanConfig,
+    progress_callback: Option<&dyn Fn(crate::ProgressEvent)>,
+) {
+    if let Some(callback) = progress_callback {
+        let estimate = estimate_scan_duration(candidates, config.max_threads);
+        callback(crate::ProgressEvent::BatchStarted {
+            total_files: candidates.len(),
+            total_bytes: estimate.total_bytes,
+            estimated_duration_ms: Some(estimate.estimated_ms),
+            traversal_order: config.traversal_order.to_string(),
+        });
+    }
+}
+
+/// Emits `ProgressEvent::BatchCompleted` with the counts of a finished scan.
+fn emit_batch_completed(result: &ScanResult, progress_callback: Option<&dyn Fn(crate::ProgressEvent)>) {
+    if let Some(callback) = progress_callback {
+        callback(crate::ProgressEvent::BatchCompleted {
+            successful: result.successful,
+            failed: result.failed,
+            skipped: result.skipped,
+        });
+    }
+}
+
+/// Scans walked files with [`scan_files_parallel()`], adding them to `result`.
+fn scan_candidates(
+    candidates: Vec<PathBuf>,
+    config: &ScanConfig,
+    progress_callback: Option<&dyn Fn(crate::ProgressEvent)>,
+    result: ScanResult,
+) -> Result<ScanResult> {
+    // Each thread gets its share of the I/O budget
+    let file_config = ScanConfig {
+        io_budget: config.io_budget.map(|budget| budget.per_thread(config.max_threads)),
+        ..config.clone()
+    };
+    scan_files_parallel(
+        candidates,
+        config,
+        &|path| scan_file_with_config(path, &file_config),
+        progress_callback,
+        result,
+    )
+}
+
+/// What the directory walk does with one entry.
+enum WalkEntry {
+    /// Directories and unreadable entries
+    Ignored,
+    /// Reported as [`ScanAction::Skipped`] with `reason`
+    Skipped { path: PathBuf, reason: &'static str },
+    /// A `.zip` archive to expand after the walk
+    #[cfg(feature = "archives")]
+    Archive(PathBuf),
+    /// A file to scan
+    Candidate(PathBuf),
+}
+
+impl WalkEntry {
+    fn path(&self) -> Option<&Path> {
+        match self {
+            WalkEntry::Ignored => None,
+            #[cfg(feature = "archives")]
+            WalkEntry::Archive(path) => Some(path),
+            WalkEntry::Skipped { path, .. } | WalkEntry::Candidate(path) => Some(path),
         }
+    }
+}
 
-        // Scan the file
-        result.total_files += 1;
+/// Entry filters of a directory scan, shared by the walker threads.
+struct EntryFilters<'a> {
+    exclude: Option<&'a CaseAwareGlobMatcher>,
+    include: Option<&'a CaseAwareGlobMatcher>,
+    content_type_filter: Option<&'a [String]>,
+    symlinks: SymlinkStrategy,
+    #[cfg(feature = "archives")]
+    scan_archives: bool,
+}
 
-        // Emit FileStarted event
-        if let Some(callback) = progress_callback {
-            callback(crate::ProgressEvent::FileStarted {
-                path: path.display().to_string(),
-            });
+/// Classifies one walker entry.
+///
+/// Walk errors (permission denied, symlink loops, etc.) are reported as
+/// warnings and the entry is ignored, so the scan continues.
+///
+/// # Errors
+///
+/// Returns `JozinError::UserError` for a symlink under [`SymlinkStrategy::Error`].
+fn classify_entry(entry: Result<walkdir::DirEntry>, filters: &EntryFilters<'_>) -> Result<WalkEntry> {
+    let entry = match entry {
+        Ok(e) => e,
+        Err(e) => {
+            eprintln!("Warning: Failed to access entry: {}", e);
+            return Ok(WalkEntry::Ignored);
         }
+    };
+    let path = entry.path();
+    let skipped = |reason| WalkEntry::Skipped {
+        path: path.to_path_buf(),
+        reason,
+    };
 
-        match scan_file(path, dry_run) {
-            Ok(sidecar) => {
-                let action = if dry_run {
-                    ScanAction::Skipped
-                } else {
-                    ScanAction::Written
-                };
-                result.scanned_files.push(ScannedFile {
-                    path: path.display().to_string(),
-                    action,
-                    sidecar_path: if dry_run {
-                        None
-                    } else {
-                        Some(get_sidecar_path(path).display().to_string())
-                    },
-                    error: None,
-                    hash: Some(sidecar.source.file_hash_b3),
-                    size_bytes: Some(sidecar.source.file_size_bytes),
-                });
-                if dry_run {
-                    result.skipped += 1;
-                } else {
-                    result.successful += 1;
-                }
+    // Symlinks: skipped, or abort the scan (SymlinkStrategy::Error)
+    if check_symlink(&entry, filters.symlinks)? {
+        return Ok(if path.is_file() { skipped("Symlink") } else { WalkEntry::Ignored });
+    }
 
-                // Emit FileCompleted event for success
-                if let Some(callback) = progress_callback {
-                    callback(crate::ProgressEvent::FileCompleted {
-                        path: path.display().to_string(),
-                        success: true,
-                        error: None,
-                        size_bytes: Some(sidecar.source.file_size_bytes),
-                    });
+    // Skip directories, only process files
+    if !path.is_file() {
+        return Ok(WalkEntry::Ignored);
+    }
+
+    // Apply exclude patterns first (early rejection for performance)
+    if filters.exclude.is_some_and(|matcher| matcher.is_match(path)) {
+        return Ok(skipped("Excluded by pattern"));
+    }
+
+    #[cfg(feature = "archives")]
+    if filters.scan_archives && crate::archives::is_zip_file(path) {
+        return Ok(WalkEntry::Archive(path.to_path_buf()));
+    }
+
+    // Apply include patterns (if specified, file must match at least one)
+    if filters.include.is_some_and(|matcher| !matcher.is_match(path)) {
+        return Ok(skipped("Not included by pattern"));
+    }
+
+    // Check if file is an image by extension (or by content when filtering by MIME type)
+    if !is_scannable_file(path, filters.content_type_filter) {
+        return Ok(skipped(if filters.content_type_filter.is_some() {
+            "Content type not matched by filter"
+        } else {
+            "Not an image file (unsupported extension)"
+        }));
+    }
+
+    Ok(WalkEntry::Candidate(path.to_path_buf()))
+}
+
+/// Whether a walked entry is a subdirectory excluded from backups (macOS),
+/// which scans don't descend into.
+fn is_backup_excluded_dir(entry: &walkdir::DirEntry) -> bool {
+    entry.depth() > 0 && entry.file_type().is_dir() && platform::is_backup_excluded(entry.path())
+}
+
+/// Scans files on a rayon thread pool of `config.max_threads` threads.
+///
+/// Each file is a task spawned in a `rayon` scope. Tasks record per-file
+/// outcomes in a shared `Mutex<ScanResult>` and fatal errors in a shared
+/// `Mutex<Vec<JozinError>>`:
+///
+/// - `JozinError::InternalError` and panics (converted to `InternalError`) are
+///   fatal when `failure_policy.abort_on_internal` is set, and the first one is
+///   returned once all tasks have finished. Otherwise they count as file failures.
+/// - Every other error is a per-file failure (`ScanAction::Failed`).
+/// - Once `failure_policy.max_file_failures` is exceeded, remaining files are
+///   not scanned and an `IoError` is returned.
+/// - With `memory_limit_mb`, the RSS is checked every
+///   [`MEMORY_CHECK_INTERVAL`] files; once it is exceeded, remaining files
+///   are not scanned and the partial result is returned as `truncated`.
+///
+/// Progress events are forwarded over a channel and delivered on the calling
+/// thread, so `progress_callback` does not need to be thread-safe.
+///
+/// `process` performs the per-file scan (tests substitute their own).
+fn scan_files_parallel(
+    files: Vec<PathBuf>,
+    config: &ScanConfig,
+    process: &(dyn Fn(&Path) -> Result<SidecarWriteResult> + Sync),
+    progress_callback: Option<&dyn Fn(crate::ProgressEvent)>,
+    result: ScanResult,
+) -> Result<ScanResult> {
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(usize::from(config.max_threads.max(1)))
+        .build()
+        .map_err(|e| JozinError::InternalError {
+            message: format!("Failed to start scan thread pool: {}", e),
+        })?;
+
+    let policy = &config.failure_policy;
+    let dry_run = config.dry_run;
+    let result = Mutex::new(result);
+    let fatal_errors: Mutex<Vec<JozinError>> = Mutex::new(Vec::new());
+    let file_failures = AtomicUsize::new(0);
+    let aborted = AtomicBool::new(false);
+    let memory_guard = config.memory_limit_mb.map(MemoryGuard::from_megabytes);
+    let started = AtomicUsize::new(0);
+    let truncated = AtomicBool::new(false);
+    let (events, received) = mpsc::channel::<crate::ProgressEvent>();
+
+    pool.in_place_scope(|scope| {
+        for path in &files {
+            let events = events.clone();
+            let (result, fatal_errors, file_failures, aborted) =
+                (&result, &fatal_errors, &file_failures, &aborted);
+            let (memory_guard, started, truncated) = (&memory_guard, &started, &truncated);
+
+            scope.spawn(move |_| {
+                if let Some(guard) = memory_guard {
+                    if started.fetch_add(1, Ordering::SeqCst) % MEMORY_CHECK_INTERVAL == 0 {
+                        if let Err(e) = guard.check() {
+                            eprintln!("Warning: {}; returning partial results", e);
+                            truncated.store(true, Ordering::SeqCst);
+                        }
+                    }
                 }
-            }
-            Err(e) => {
-                result.scanned_files.push(ScannedFile {
-                    path: path.display().to_string(),
-                    action: ScanAction::Failed,
-                    sidecar_path: None,
-                    error: Some(e.to_string()),
-                    hash: None,
-                    size_bytes: None,
-                });
-                result.failed += 1;
-
-                // Emit FileCompleted event for failure
-                if let Some(callback) = progress_callback {
-                    callback(crate::ProgressEvent::FileCompleted {
-                        path: path.display().to_string(),
-                        success: false,
-                        error: Some(e.to_string()),
-                        size_bytes: None,
+                if aborted.load(Ordering::SeqCst) || truncated.load(Ordering::SeqCst) {
+                    return;
+                }
+                let display = path.display().to_string();
+                let _ = events.send(crate::ProgressEvent::FileStarted { path: display.clone() });
+
+                let outcome = panic::catch_unwind(AssertUnwindSafe(|| process(path)))
+                    .unwrap_or_else(|payload| {
+                        Err(JozinError::InternalError {
+                            message: format!("Panic while scanning {}: {}", display, panic_message(&*payload)),
+                        })
                     });
+
+                let mut orphan = None;
+                let mut coverage = ExifCoverage::default();
+                let scanned_file = match outcome {
+                    Ok(written) => {
+                        let _ = events.send(crate::ProgressEvent::FileCompleted {
+                            path: display.clone(),
+                            success: true,
+                            error: None,
+                            size_bytes: Some(written.sidecar.source.file_size_bytes),
+                        });
+                        orphan = config.sidecar_index.as_deref().and_then(|index| index.take_rename(path));
+                        let action = scan_action(orphan.is_some(), &written);
+                        coverage = ExifCoverage::of(written.sidecar.image.as_ref());
+                        scanned_file(display, action, dry_run, &written)
+                    }
+                    Err(e @ JozinError::InternalError { .. }) if policy.abort_on_internal => {
+                        let _ = events.send(crate::ProgressEvent::FileCompleted {
+                            path: display,
+                            success: false,
+                            error: Some(e.to_string()),
+                            size_bytes: None,
+                        });
+                        aborted.store(true, Ordering::SeqCst);
+                        lock(fatal_errors).push(e);
+                        return;
+                    }
+                    Err(e) => {
+                        let _ = events.send(crate::ProgressEvent::FileCompleted {
+                            path: display.clone(),
+                            success: false,
+                            error: Some(e.to_string()),
+                            size_bytes: None,
+                        });
+                        let failures = file_failures.fetch_add(1, Ordering::SeqCst) + 1;
+                        if policy.max_file_failures.is_some_and(|max| failures > max) {
+                            aborted.store(true, Ordering::SeqCst);
+                        }
+                        ScannedFile {
+                            path: display,
+                            action: ScanAction::Failed,
+                            sidecar_path: None,
+                            error: Some(e.to_string()),
+                            hash: None,
+                            size_bytes: None,
+                            backup_path: None,
+                        }
+                    }
+                };
+
+                let mut result = lock(result);
+                result.exif_coverage.merge(&coverage);
+                match scanned_file.action {
+                    ScanAction::Written | ScanAction::Updated | ScanAction::Renamed { .. } => result.successful += 1,
+                    ScanAction::Skipped => result.skipped += 1,
+                    ScanAction::Failed => result.failed += 1,
+                    ScanAction::Conflict { .. } => result.conflicts += 1,
+                }
+                result.scanned_files.push(scanned_file);
+                if !dry_run {
+                    result.orphaned_sidecars.extend(orphan.map(|path| path.display().to_string()));
                 }
+            });
+        }
+
+        // Deliver progress events on this thread until every task has finished
+        drop(events);
+        if let Some(callback) = progress_callback {
+            for event in received {
+                callback(event);
             }
         }
+    });
+
+    if let Some(error) = fatal_errors.into_inner().unwrap_or_else(|e| e.into_inner()).into_iter().next() {
+        return Err(error);
+    }
+
+    let failures = file_failures.load(Ordering::SeqCst);
+    if let Some(max) = policy.max_file_failures.filter(|max| failures > *max) {
+        return Err(JozinError::IoError {
+            message: format!("Scan aborted: {} files failed (limit {})", failures, max),
+        });
     }
 
+    let mut result = result.into_inner().unwrap_or_else(|e| e.into_inner());
+    result.truncated |= truncated.load(Ordering::SeqCst);
+    result.scanned_files.sort_by(|a, b| a.path.cmp(&b.path));
+    result.orphaned_sidecars.sort();
     Ok(result)
 }
 
-/// Builds a GlobSet matcher from a list of glob patterns.
+/// Action of a successfully scanned file.
 ///
-/// This function compiles multiple glob patterns into a single efficient
-/// matcher for use with include/exclude filtering. Invalid patterns are
-/// logged as warnings and treated as non-matching.
+/// A sidecar left `Unchanged` is the existing one with a newer schema (see
+/// [`newer_schema_sidecar()`]).
+fn scan_action(renamed: bool, written: &SidecarWriteResult) -> ScanAction {
+    match (written.action, written.sidecar.source.previous_paths.last()) {
+        (WriteAction::Unchanged, _) => ScanAction::Conflict {
+            existing_schema: written.sidecar.schema_version.clone(),
+            scanner_schema: CURRENT_SCHEMA_VERSION.to_string(),
+        },
+        (WriteAction::DryRunPreview, _) => ScanAction::Skipped,
+        (_, Some(previous_path)) if renamed => ScanAction::Renamed { previous_path: previous_path.clone() },
+        (WriteAction::Updated, _) => ScanAction::Updated,
+        (WriteAction::Created, _) => ScanAction::Written,
+    }
+}
+
+/// Scan result entry of a successfully scanned file.
+fn scanned_file(path: String, action: ScanAction, dry_run: bool, written: &SidecarWriteResult) -> ScannedFile {
+    ScannedFile {
+        path,
+        action,
+        sidecar_path: if dry_run { None } else { Some(written.sidecar_path.display().to_string()) },
+        error: None,
+        hash: Some(written.sidecar.source.file_hash().to_string()),
+        size_bytes: Some(written.sidecar.source.file_size_bytes),
+        backup_path: written.backup_path.as_ref().map(|path| path.display().to_string()),
+    }
+}
+
+/// Looks up a hash in `cache`, computing and caching it on a miss.
+///
+/// The lock is not held while hashing, so parallel scan threads don't wait on
+/// each other.
+fn cached_hash(
+    cache: Option<&Mutex<HashCache>>,
+    file_path: &Path,
+    size_bytes: u64,
+    modified_at: OffsetDateTime,
+    compute: impl FnOnce() -> Result<String>,
+) -> Result<String> {
+    let Some(cache) = cache else {
+        return compute();
+    };
+    if let Some(hash) = lock(cache).get(file_path, size_bytes, modified_at) {
+        return Ok(hash);
+    }
+
+    let hash = compute()?;
+    lock(cache).insert(
+        file_path.to_path_buf(),
+        HashCacheEntry {
+            size_bytes,
+            modified_at,
+            hash: hash.clone(),
+        },
+    );
+    Ok(hash)
+}
+
+/// Locks a mutex, recovering the data if another task panicked while holding it.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Extracts the message from a panic payload.
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".to_string())
+}
+
+/// Collects sidecar JSON paths under a path, sorted for stable output.
 ///
-/// # Glob Syntax
+/// `path` may be a sidecar, an image (its sidecar is returned if present), or a
+/// directory. Sidecars follow the library's configured naming.
 ///
-/// Supports standard glob syntax per TASK+PHASE_PLAN.md line 18:
-/// - `*`: Matches any characters except path separators
-/// - `**`: Matches any characters including path separators (recursive)
-/// - `?`: Matches a single character
-/// - `[abc]`: Matches any character in the set
+/// # Errors
 ///
-/// # Arguments
+/// Returns `JozinError::IoError` if `path` does not exist.
+pub(crate) fn find_sidecar_files(path: &Path, recursive: bool) -> Result<Vec<PathBuf>> {
+    find_sidecar_files_with(path, recursive, &LibraryConfig::discover(path), SymlinkStrategy::default())
+}
+
+/// [`find_sidecar_files()`] with a given sidecar naming and symlink strategy.
 ///
-/// * `patterns` - List of glob pattern strings (e.g., `["*.jpg", "**/.jozin/**"]`)
+/// # Errors
 ///
-/// # Returns
+/// - `JozinError::IoError` if `path` does not exist
+/// - `JozinError::UserError` if a symlink is found with [`SymlinkStrategy::Error`]
+pub(crate) fn find_sidecar_files_with(
+    path: &Path,
+    recursive: bool,
+    naming: &LibraryConfig,
+    symlinks: SymlinkStrategy,
+) -> Result<Vec<PathBuf>> {
+    if !path.exists() {
+        return Err(JozinError::IoError {
+            message: format!("Path not found: {}", path.display()),
+        });
+    }
+
+    if path.is_file() {
+        let sidecar_path = if naming.is_sidecar(path) {
+            path.to_path_buf()
+        } else {
+            existing_sidecar_path(&naming.sidecar_path(path))
+        };
+        return Ok(if sidecar_path.is_file() { vec![sidecar_path] } else { Vec::new() });
+    }
+
+    let mut paths = Vec::new();
+    for entry in walker(path, recursive, symlinks).into_iter().filter_map(|e| e.ok()) {
+        if check_symlink(&entry, symlinks)? {
+            continue;
+        }
+        if entry.path().is_file() && naming.is_sidecar(entry.path()) {
+            paths.push(entry.into_path());
+        }
+    }
+    paths.sort();
+    Ok(paths)
+}
+
+/// Decides whether a file should be scanned.
 ///
-/// Returns a compiled `GlobSet` that can efficiently match paths against
-/// all patterns.
+/// Without a content-type filter this is the extension check from
+/// [`is_image_file()`]. With a filter, the MIME type is detected from the file's
+/// magic bytes and matched against the filter; files whose type cannot be
+/// detected fall back to the extension check.
+fn is_scannable_file(file_path: &Path, content_type_filter: Option<&[String]>) -> bool {
+    let Some(filter) = content_type_filter else {
+        return is_image_file(file_path);
+    };
+
+    match detect_mime_type(file_path) {
+        Some(mime) => filter.iter().any(|pattern| mime_matches(pattern, &mime)),
+        None => is_image_file(file_path),
+    }
+}
+
+/// Detects a file's MIME type from its magic bytes.
 ///
-/// # Errors
+/// Supported image formats are identified by
+/// [`detect_format_from_magic_bytes()`]; anything else (PDFs, videos, ...)
+/// falls back to the `infer` crate.
 ///
-/// Returns `JozinError::ValidationError` if any pattern is invalid.
-fn build_glob_matcher(patterns: &[String]) -> Result<globset::GlobSet> {
-    let mut builder = GlobSetBuilder::new();
-
-    for pattern in patterns {
-        match Glob::new(pattern) {
-            Ok(glob) => {
-                builder.add(glob);
-            }
-            Err(e) => {
-                return Err(JozinError::ValidationError {
-                    message: format!("Invalid glob pattern '{}': {}", pattern, e),
-                });
-            }
-        }
+/// Returns `None` if the file cannot be read or its type is not recognised.
+fn detect_mime_type(file_path: &Path) -> Option<String> {
+    if let Ok(Some(format)) = detect_format_from_magic_bytes(file_path) {
+        return Some(format.to_mime_type().to_string());
+    }
+    match infer::get_from_path(file_path) {
+        Ok(Some(kind)) => Some(kind.mime_type().to_string()),
+        _ => None,
     }
+}
 
-    builder.build().map_err(|e| JozinError::InternalError {
-        message: format!("Failed to build glob matcher: {}", e),
-    })
+/// Matches a MIME type against a filter pattern.
+///
+/// Patterns are either exact (`image/jpeg`) or a type wildcard (`image/*`).
+/// Comparison is case-insensitive.
+fn mime_matches(pattern: &str, mime: &str) -> bool {
+    let pattern = pattern.trim().to_ascii_lowercase();
+    let mime = mime.to_ascii_lowercase();
+
+    match pattern.strip_suffix("/*") {
+        Some(top_level) => mime
+            .split_once('/')
+            .is_some_and(|(mime_top, _)| mime_top == top_level),
+        None => pattern == "*/*" || pattern == mime,
+    }
 }
 
 /// Checks if a file is an image based on its extension.
@@ -698,7 +1965,7 @@ fn build_glob_matcher(patterns: &[String]) -> Result<globset::GlobSet> {
 ///
 /// Returns `true` if the file has a supported image extension (case-insensitive),
 /// `false` otherwise.
-fn is_image_file(file_path: &Path) -> bool {
+pub(crate) fn is_image_file(file_path: &Path) -> bool {
     if let Some(extension) = file_path.extension() {
         let ext_lower = extension.to_string_lossy().to_lowercase();
         SUPPORTED_EXTENSIONS.contains(&ext_lower.as_str())
@@ -707,89 +1974,197 @@ fn is_image_file(file_path: &Path) -> bool {
     }
 }
 
-/// Computes BLAKE3 hash of a file.
+/// Assembles a fresh sidecar for `source`.
 ///
-/// Reads the entire file and computes its hash using the BLAKE3 algorithm.
-/// Returns the hash as a lowercase hexadecimal string.
-fn compute_blake3_hash(file_path: &Path) -> Result<String> {
-    let mut file = File::open(file_path)?;
-    let mut hasher = blake3::Hasher::new();
-    let mut buffer = [0u8; 8192];
+/// `sidecar_key` is the path whose sidecar (named by `naming`) holds the
+/// sidecar (the image itself, or a virtual path for archive entries). What
+/// must survive a rescan is carried over from an existing sidecar there (see
+/// [`read_existing_sidecar()`]); `source.ingest_timestamp` is filled in.
+pub(crate) fn build_sidecar(
+    sidecar_key: &Path,
+    mut source: SourceInfo,
+    image: Option<ImageInfo>,
+    naming: &LibraryConfig,
+) -> Result<Sidecar> {
+    // Create pipeline signature
+    let now = OffsetDateTime::now_utc();
+    let created_at = now
+        .format(&time::format_description::well_known::Rfc3339)
+        .map_err(|e| JozinError::InternalError {
+            message: format!("Failed to format timestamp: {}", e),
+        })?;
 
-    loop {
-        let bytes_read = file.read(&mut buffer)?;
-        if bytes_read == 0 {
-            break;
-        }
-        hasher.update(&buffer[..bytes_read]);
+    let pipeline_signature = PipelineSignature {
+        schema_version: SIDECAR_SCHEMA_VERSION.to_string(),
+        producer_version: env!("CARGO_PKG_VERSION").to_string(),
+        hash_algorithm: source.hash_algorithm().as_str().to_string(),
+        face_model: None,
+        tag_model: None,
+        created_at: created_at.clone(),
+    };
+
+    // Carry over what must survive a rescan
+    let existing = read_existing_sidecar(&existing_sidecar_path(&naming.sidecar_path(sidecar_key)));
+    source.ingest_timestamp = existing
+        .as_ref()
+        .map(|s| s.source.ingest_timestamp.clone())
+        .unwrap_or_else(|| created_at.clone());
+    if let Some(existing) = &existing {
+        source.previous_paths = existing.source.previous_paths.clone();
+        source.hardlink_of = existing.source.hardlink_of.clone();
     }
+    let extensions = existing.map(|s| s.extensions).unwrap_or_default();
 
-    let hash = hasher.finalize();
-    Ok(hash.to_hex().to_string())
+    Ok(Sidecar {
+        schema_version: SIDECAR_SCHEMA_VERSION.to_string(),
+        producer_version: env!("CARGO_PKG_VERSION").to_string(),
+        created_at: created_at.clone(),
+        updated_at: created_at,
+        pipeline_signature,
+        source,
+        image,
+        faces: Vec::new(),
+        tags: Vec::new(),
+        thumbnails: Vec::new(),
+        extensions,
+    })
 }
 
-/// Writes a sidecar file atomically with backup rotation.
-///
-/// Atomic write strategy:
-/// 1. Write to temporary file: `<file_path>.json.tmp`
-/// 2. Fsync to ensure data is on disk
-/// 3. Rename to final location: `<file_path>.json`
-///
-/// Backup rotation strategy:
-/// - If `<file_path>.json` exists, rotate to `.bak1`
-/// - If `.bak1` exists, rotate to `.bak2`
-/// - If `.bak2` exists, rotate to `.bak3`
-/// - `.bak3` is overwritten (oldest backup is lost)
+/// Returns the existing sidecar at `sidecar_path`, if there is a readable one.
 ///
-/// # Errors
-///
-/// Returns `JozinError::IoError` if writing or renaming fails.
-fn write_sidecar(file_path: &Path, sidecar: &Sidecar) -> Result<()> {
-    let sidecar_path = get_sidecar_path(file_path);
-    let tmp_path = get_tmp_sidecar_path(file_path);
+/// A rescan carries over what the previous sidecar recorded and the new scan
+/// cannot reproduce: third-party extensions (they belong to other tools),
+/// `source.ingest_timestamp`, `source.previous_paths` and `source.hardlink_of`.
+fn read_existing_sidecar(sidecar_path: &Path) -> Option<Sidecar> {
+    read_sidecar_file(sidecar_path)
+        .ok()
+        .and_then(|json| parse_sidecar(&json).ok())
+}
 
-    // Rotate backups if sidecar already exists
-    if sidecar_path.exists() {
-        rotate_backups(&sidecar_path)?;
+/// Reads `schema_version` of an existing sidecar without parsing the rest
+/// (`None` if it is missing or unreadable).
+fn sidecar_schema_version(sidecar_path: &Path) -> Option<String> {
+    let value: serde_json::Value = serde_json::from_str(&read_sidecar_file(sidecar_path).ok()?).ok()?;
+    Some(value.get("schema_version")?.as_str()?.to_string())
+}
+
+/// Returns the sidecar at `sidecar_path` if its schema is newer than this
+/// build understands ([`CURRENT_SCHEMA_VERSION`]).
+///
+/// Only `schema_version` needs to parse for the check; a newer sidecar this
+/// build cannot read is an error rather than being overwritten.
+fn newer_schema_sidecar(sidecar_path: &Path) -> Option<Result<Sidecar>> {
+    let json = read_sidecar_file(sidecar_path).ok()?;
+    let value: serde_json::Value = serde_json::from_str(&json).ok()?;
+    let version = value.get("schema_version")?.as_str()?;
+    if !is_newer_schema(version) {
+        return None;
     }
+    Some(parse_sidecar(&json).map_err(|e| JozinError::ValidationError {
+        message: format!(
+            "Sidecar {} has schema {} (newer than {}) and cannot be read; upgrade Jožin: {}",
+            sidecar_path.display(),
+            version,
+            CURRENT_SCHEMA_VERSION,
+            e
+        ),
+    }))
+}
 
-    // Serialize to JSON
-    let json = serde_json::to_string_pretty(sidecar)?;
+/// Fails files whose structure is broken (see [`validate_image_encoding()`]);
+/// files of unknown format pass.
+fn check_encoding(file_path: &Path) -> Result<()> {
+    let Some(format) = expected_image_format(file_path)? else {
+        return Ok(());
+    };
+    let result = validate_image_encoding(file_path, format)?;
+    match result.first_error {
+        Some(error) => Err(JozinError::ValidationError {
+            message: format!("Invalid {} encoding in {}: {}", format, file_path.display(), error),
+        }),
+        None => Ok(()),
+    }
+}
 
-    // Write to temporary file
-    let mut tmp_file = File::create(&tmp_path)?;
-    tmp_file.write_all(json.as_bytes())?;
-    tmp_file.sync_all()?; // Ensure data is on disk
+/// Whether `version` is newer than [`CURRENT_SCHEMA_VERSION`] (unparsable
+/// versions are not).
+fn is_newer_schema(version: &str) -> bool {
+    matches!(
+        (parse_schema_version(version), parse_schema_version(CURRENT_SCHEMA_VERSION)),
+        (Some(version), Some(current)) if version > current
+    )
+}
 
-    // Atomic rename
-    fs::rename(&tmp_path, &sidecar_path)?;
+/// Builds the sidecar of a renamed file from its old sidecar, if
+/// `config.sidecar_index` has one (see [`SidecarIndex::claim_rename()`]).
+///
+/// Everything recorded so far (faces, tags, thumbnails, EXIF) is kept; the
+/// source describes the file at its new path, with the old path appended to
+/// `previous_paths`. Files that already have a sidecar are not renames.
+fn renamed_sidecar(
+    file_path: &Path,
+    source: &SourceInfo,
+    naming: &LibraryConfig,
+    config: &ScanConfig,
+) -> Option<Result<Sidecar>> {
+    let index = config.sidecar_index.as_deref()?;
+    if existing_sidecar_path(&naming.sidecar_path(file_path)).exists() {
+        return None;
+    }
+    let old_sidecar_path = index.claim_rename(source.file_hash(), file_path)?;
+    let old = read_sidecar_file(&old_sidecar_path).and_then(|json| parse_sidecar(&json));
+    Some(old.and_then(|old| {
+        let updated_at = OffsetDateTime::now_utc()
+            .format(&time::format_description::well_known::Rfc3339)
+            .map_err(|e| JozinError::InternalError {
+                message: format!("Failed to format timestamp: {}", e),
+            })?;
+        let mut previous_paths = old.source.previous_paths.clone();
+        previous_paths.push(old.source.file_path.clone());
+        Ok(Sidecar {
+            schema_version: SIDECAR_SCHEMA_VERSION.to_string(),
+            producer_version: env!("CARGO_PKG_VERSION").to_string(),
+            updated_at,
+            source: SourceInfo {
+                ingest_timestamp: old.source.ingest_timestamp.clone(),
+                previous_paths,
+                ..source.clone()
+            },
+            ..old
+        })
+    }))
+}
 
-    Ok(())
+/// Parses sidecar JSON, filling fields that older sidecars lack.
+///
+/// - `source.ingest_timestamp` missing: falls back to `created_at`
+pub(crate) fn parse_sidecar(json: &str) -> Result<Sidecar> {
+    let mut sidecar: Sidecar = serde_json::from_str(json)?;
+    if sidecar.source.ingest_timestamp.is_empty() {
+        sidecar.source.ingest_timestamp = sidecar.created_at.clone();
+    }
+    Ok(sidecar)
 }
 
-/// Returns the sidecar path for a given file: `<file_path>.json`
-fn get_sidecar_path(file_path: &Path) -> PathBuf {
-    let mut path = file_path.to_path_buf();
-    let current_name = path.file_name().unwrap().to_string_lossy().to_string();
-    path.set_file_name(format!("{}.json", current_name));
-    path
+/// Returns the temporary sidecar path: `<sidecar_path>.tmp`
+fn get_tmp_sidecar_path(sidecar_path: &Path) -> PathBuf {
+    append_to_file_name(sidecar_path, ".tmp")
 }
 
-/// Returns the temporary sidecar path: `<file_path>.json.tmp`
-fn get_tmp_sidecar_path(file_path: &Path) -> PathBuf {
-    let mut path = get_sidecar_path(file_path);
-    let current_name = path.file_name().unwrap().to_string_lossy().to_string();
-    path.set_file_name(format!("{}.tmp", current_name));
-    path
+/// Appends `suffix` to the file name of `path` (e.g. `a.json` → `a.json.bak1`).
+pub(crate) fn append_to_file_name(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(suffix);
+    path.with_file_name(name)
 }
 
 /// Rotates backup files: .json → .bak1 → .bak2 → .bak3
 ///
 /// This ensures we keep up to 3 backups of the sidecar file.
 fn rotate_backups(sidecar_path: &Path) -> Result<()> {
-    let bak3 = sidecar_path.with_extension("json.bak3");
-    let bak2 = sidecar_path.with_extension("json.bak2");
-    let bak1 = sidecar_path.with_extension("json.bak1");
+    let bak3 = append_to_file_name(sidecar_path, ".bak3");
+    let bak2 = append_to_file_name(sidecar_path, ".bak2");
+    let bak1 = append_to_file_name(sidecar_path, ".bak1");
 
     // Rotate .bak2 → .bak3 (overwrite .bak3 if exists)
     if bak2.exists() {
@@ -851,12 +2226,58 @@ mod tests {
         assert!(!is_image_file(Path::new("test")));
     }
 
+    #[test]
+    fn test_rescan_leaves_newer_schema_sidecar_unchanged() {
+        let temp_dir = TempDir::new().unwrap();
+        let image_path = create_test_image(temp_dir.path(), "test.jpg", b"fake image data");
+        let other_path = create_test_image(temp_dir.path(), "other.jpg", b"other image data");
+        let sidecar = scan_file(&image_path, false).unwrap().sidecar;
+        let sidecar_path = LibraryConfig::default().sidecar_path(&image_path);
+        let mut json: serde_json::Value = serde_json::to_value(&sidecar).unwrap();
+        json["schema_version"] = "9.0.0".into();
+        json["future_field"] = true.into();
+        let newer = serde_json::to_string_pretty(&json).unwrap();
+        fs::write(&sidecar_path, &newer).unwrap();
+
+        let result = scan_path(temp_dir.path(), &ScanConfig::default(), None).unwrap();
+        assert_eq!((result.successful, result.conflicts), (1, 1));
+        let conflict = result.scanned_files.iter().find(|file| file.path == image_path.display().to_string()).unwrap();
+        assert!(matches!(
+            &conflict.action,
+            ScanAction::Conflict { existing_schema, scanner_schema }
+                if existing_schema == "9.0.0" && scanner_schema == CURRENT_SCHEMA_VERSION
+        ));
+        assert_eq!(fs::read_to_string(&sidecar_path).unwrap(), newer);
+        assert!(LibraryConfig::default().sidecar_path(&other_path).exists());
+
+        // Single files too, unless forced
+        assert_eq!(scan_path(&image_path, &ScanConfig::default(), None).unwrap().conflicts, 1);
+        let forced = ScanConfig { force_overwrite_newer: true, ..ScanConfig::default() };
+        let result = scan_path(&image_path, &forced, None).unwrap();
+        assert_eq!((result.successful, result.conflicts), (1, 0));
+        assert_eq!(read_sidecar(&image_path).unwrap().schema_version, SIDECAR_SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn test_scan_validate_encoding() {
+        let temp_dir = TempDir::new().unwrap();
+        let truncated = create_test_image(temp_dir.path(), "truncated.jpg", &[0xFF, 0xD8, 0xFF, 0xFE, 0x00, 0x04, b'h', b'i']);
+        let unknown = create_test_image(temp_dir.path(), "unknown.raw", b"not checked");
+
+        let config = ScanConfig { validate_encoding: true, ..ScanConfig::default() };
+        let error = scan_file_with_config(&truncated, &config).unwrap_err();
+        assert!(matches!(&error, JozinError::ValidationError { message } if message.contains("Invalid JPEG encoding")));
+        assert!(!LibraryConfig::default().sidecar_path(&truncated).exists());
+        assert!(scan_file_with_config(&unknown, &config).is_ok());
+        assert!(scan_file(&truncated, true).is_ok());
+    }
+
     #[test]
     fn test_scan_path_single_file() {
         let temp_dir = TempDir::new().unwrap();
         let image_path = create_test_image(temp_dir.path(), "test.jpg", b"fake image data");
 
-        let result = scan_path(&image_path, false, None, None, false, 4, Some("file"), None).unwrap();
+        let result = scan_path(&image_path, &ScanConfig::default(), None).unwrap();
 
         assert_eq!(result.total_files, 1);
         assert_eq!(result.successful, 1);
@@ -871,7 +2292,7 @@ mod tests {
         assert!(scanned.size_bytes.is_some());
 
         // Verify sidecar was created
-        let sidecar_path = get_sidecar_path(&image_path);
+        let sidecar_path = LibraryConfig::default().sidecar_path(&image_path);
         assert!(sidecar_path.exists());
     }
 
@@ -880,7 +2301,7 @@ mod tests {
         let temp_dir = TempDir::new().unwrap();
         let image_path = create_test_image(temp_dir.path(), "test.jpg", b"fake image data");
 
-        let result = scan_path(&image_path, false, None, None, true, 4, Some("file"), None).unwrap();
+        let result = scan_path(&image_path, &ScanConfig { dry_run: true, ..ScanConfig::default() }, None).unwrap();
 
         assert_eq!(result.total_files, 1);
         assert_eq!(result.successful, 0);
@@ -892,7 +2313,7 @@ mod tests {
         assert!(scanned.hash.is_some());
 
         // Verify sidecar was NOT created
-        let sidecar_path = get_sidecar_path(&image_path);
+        let sidecar_path = LibraryConfig::default().sidecar_path(&image_path);
         assert!(!sidecar_path.exists());
     }
 
@@ -910,7 +2331,7 @@ mod tests {
         fs::create_dir(&subdir).unwrap();
         create_test_image(&subdir, "image3.jpg", b"image 3");
 
-        let result = scan_path(root, true, None, None, false, 4, Some("file"), None).unwrap();
+        let result = scan_path(root, &ScanConfig { recursive: true, ..ScanConfig::default() }, None).unwrap();
 
         assert_eq!(result.total_files, 3);
         assert_eq!(result.successful, 3);
@@ -932,7 +2353,7 @@ mod tests {
         fs::create_dir(&subdir).unwrap();
         create_test_image(&subdir, "image3.jpg", b"image 3");
 
-        let result = scan_path(root, false, None, None, false, 4, Some("file"), None).unwrap();
+        let result = scan_path(root, &ScanConfig::default(), None).unwrap();
 
         assert_eq!(result.total_files, 2);
         assert_eq!(result.successful, 2);
@@ -950,7 +2371,7 @@ mod tests {
         create_test_image(root, "image3.jpg", b"image 3");
 
         let include = vec![String::from("*.jpg")];
-        let result = scan_path(root, false, Some(&include), None, false, 4, Some("file"), None).unwrap();
+        let result = scan_path(root, &ScanConfig { include: Some(include), ..ScanConfig::default() }, None).unwrap();
 
         assert_eq!(result.total_files, 3);
         assert_eq!(result.successful, 2); // Only .jpg files
@@ -970,7 +2391,7 @@ mod tests {
         create_test_image(&excluded_dir, "image2.jpg", b"image 2");
 
         let exclude = vec![String::from("**/.jozin/**")];
-        let result = scan_path(root, true, None, Some(&exclude), false, 4, Some("file"), None).unwrap();
+        let result = scan_path(root, &ScanConfig { recursive: true, exclude: Some(exclude), ..ScanConfig::default() }, None).unwrap();
 
         assert_eq!(result.total_files, 2);
         assert_eq!(result.successful, 1); // Only root image
@@ -985,7 +2406,7 @@ mod tests {
         create_test_image(root, "image.jpg", b"image");
         create_test_image(root, "document.txt", b"not an image");
 
-        let result = scan_path(root, false, None, None, false, 4, Some("file"), None).unwrap();
+        let result = scan_path(root, &ScanConfig::default(), None).unwrap();
 
         assert_eq!(result.total_files, 2);
         assert_eq!(result.successful, 1); // Only .jpg
@@ -994,7 +2415,7 @@ mod tests {
 
     #[test]
     fn test_scan_path_nonexistent_path() {
-        let result = scan_path(Path::new("/nonexistent/path"), false, None, None, false, 4, Some("file"), None);
+        let result = scan_path(Path::new("/nonexistent/path"), &ScanConfig::default(), None);
         assert!(result.is_err());
         assert!(matches!(result.unwrap_err(), JozinError::IoError { .. }));
     }
@@ -1004,26 +2425,645 @@ mod tests {
         let temp_dir = TempDir::new().unwrap();
         let text_file = create_test_image(temp_dir.path(), "test.txt", b"not an image");
 
-        let result = scan_path(&text_file, false, None, None, false, 4, Some("file"), None);
+        let result = scan_path(&text_file, &ScanConfig::default(), None);
         assert!(result.is_err());
         assert!(matches!(result.unwrap_err(), JozinError::ValidationError { .. }));
     }
 
     #[test]
-    fn test_build_glob_matcher_valid_patterns() {
-        let patterns = vec![String::from("*.jpg"), String::from("**/*.png")];
-        let matcher = build_glob_matcher(&patterns).unwrap();
+    fn test_mime_matches_exact_and_wildcard() {
+        assert!(mime_matches("image/jpeg", "image/jpeg"));
+        assert!(mime_matches("IMAGE/JPEG", "image/jpeg"));
+        assert!(mime_matches("image/*", "image/png"));
+        assert!(!mime_matches("image/*", "application/pdf"));
+        assert!(!mime_matches("image/png", "image/jpeg"));
+    }
 
-        assert!(matcher.is_match("test.jpg"));
-        assert!(matcher.is_match("dir/subdir/test.png"));
-        assert!(!matcher.is_match("test.txt"));
+    #[test]
+    fn test_supported_mime_types_are_detectable() {
+        for mime in SUPPORTED_MIME_TYPES {
+            assert!(infer::is_mime_supported(mime), "{} not detectable", mime);
+        }
     }
 
     #[test]
-    fn test_build_glob_matcher_invalid_pattern() {
-        let patterns = vec![String::from("[invalid")];
-        let result = build_glob_matcher(&patterns);
-        assert!(result.is_err());
+    fn test_scan_path_content_type_filter() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+
+        // A real PNG signature with a misleading .jpg extension
+        create_test_image(root, "real_png.jpg", b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR");
+        // A PDF pretending to be a JPEG
+        create_test_image(root, "receipt.jpg", b"%PDF-1.4\n%fake");
+        // Undetectable content falls back to the extension check
+        create_test_image(root, "unknown.jpg", b"no magic here");
+
+        let config = ScanConfig {
+            content_type_filter: Some(vec![String::from("image/png")]),
+            ..ScanConfig::default()
+        };
+        let result = scan_path(root, &config, None).unwrap();
+
+        assert_eq!(result.total_files, 3);
+        assert_eq!(result.successful, 2); // real_png.jpg + unknown.jpg (fallback)
+        let receipt = result
+            .scanned_files
+            .iter()
+            .find(|f| f.path.ends_with("receipt.jpg"))
+            .unwrap();
+        assert!(matches!(receipt.action, ScanAction::Skipped));
+
+        // Wildcard matches any image subtype; the PDF is still rejected
+        let config = ScanConfig {
+            content_type_filter: Some(vec![String::from("image/*")]),
+            dry_run: true,
+            ..ScanConfig::default()
+        };
+        let result = scan_path(&root.join("receipt.jpg"), &config, None);
         assert!(matches!(result.unwrap_err(), JozinError::ValidationError { .. }));
     }
+
+    #[test]
+    fn test_scan_file_include_raw_exif_without_exif() {
+        let temp_dir = TempDir::new().unwrap();
+        let image_path = create_test_image(temp_dir.path(), "plain.jpg", b"no exif here");
+
+        let config = ScanConfig {
+            include_raw_exif: true,
+            dry_run: true,
+            ..ScanConfig::default()
+        };
+        let sidecar = scan_file_with_config(&image_path, &config).unwrap().sidecar;
+
+        // No EXIF block means no image section, and nothing is serialized
+        assert!(sidecar.image.is_none());
+        let json = serde_json::to_string(&sidecar).unwrap();
+        assert!(!json.contains("exif_json"));
+    }
+
+    #[test]
+    fn test_rescan_preserves_extensions() {
+        let temp_dir = TempDir::new().unwrap();
+        let image_path = create_test_image(temp_dir.path(), "ext.jpg", b"image data");
+
+        let mut sidecar = scan_file(&image_path, false).unwrap().sidecar;
+        assert!(sidecar.extensions.is_empty());
+        sidecar.set_extension("com.example.app", "rating", serde_json::json!(4));
+        write_sidecar(&image_path, &sidecar).unwrap();
+
+        let rescanned = scan_file(&image_path, false).unwrap().sidecar;
+        assert_eq!(
+            rescanned.get_extension("com.example.app", "rating"),
+            Some(&serde_json::json!(4))
+        );
+        assert_eq!(rescanned.get_extension("com.example.app", "missing"), None);
+    }
+
+    #[test]
+    fn test_load_sidecars_from_directory() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+
+        create_test_image(root, "a.jpg", b"image a");
+        create_test_image(root, "b.png", b"image b");
+        scan_path(root, &ScanConfig::default(), None).unwrap();
+        // Not a sidecar (no image extension before .json)
+        create_test_image(root, "settings.json", b"{}");
+
+        let sidecars = load_sidecars(root, false).unwrap();
+        assert_eq!(sidecars.len(), 2);
+
+        let single = load_sidecars(&root.join("a.jpg"), false).unwrap();
+        assert_eq!(single.len(), 1);
+        assert!(single[0].source.file_path.ends_with("a.jpg"));
+    }
+
+    #[test]
+    fn test_scan_links_live_photo() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let still = create_test_image(root, "IMG_0001.HEIC", b"still");
+        create_test_image(root, "IMG_0001.MOV", b"motion");
+
+        let config = ScanConfig {
+            link_live_photos: true,
+            dry_run: true,
+            ..ScanConfig::default()
+        };
+        let sidecar = scan_file_with_config(&still, &config).unwrap().sidecar;
+        assert_eq!(
+            sidecar.source.live_photo_path,
+            Some(root.join("IMG_0001.MOV").display().to_string())
+        );
+
+        // Off by default
+        let sidecar = scan_file(&still, true).unwrap().sidecar;
+        assert!(sidecar.source.live_photo_path.is_none());
+    }
+
+    /// Files `a.jpg` .. `e.jpg` with a processor that fails or panics on some.
+    fn parallel_fixture(dir: &Path) -> Vec<PathBuf> {
+        ["a.jpg", "b.jpg", "c.jpg", "d.jpg", "e.jpg"]
+            .iter()
+            .map(|name| create_test_image(dir, name, name.as_bytes()))
+            .collect()
+    }
+
+    fn empty_result(total: usize) -> ScanResult {
+        ScanResult {
+            scanned_files: Vec::new(),
+            total_files: total,
+            successful: 0,
+            failed: 0,
+            skipped: 0,
+            conflicts: 0,
+            truncated: false,
+            cross_root_duplicates: Vec::new(),
+            orphaned_sidecars: Vec::new(),
+            exif_coverage: ExifCoverage::default(),
+        }
+    }
+
+    fn faulty_processor(path: &Path) -> Result<SidecarWriteResult> {
+        match path.file_name().unwrap().to_str().unwrap() {
+            "b.jpg" | "d.jpg" => Err(JozinError::IoError {
+                message: "permission denied".to_string(),
+            }),
+            "c.jpg" => panic!("simulated bug"),
+            _ => scan_file(path, true),
+        }
+    }
+
+    #[test]
+    fn test_scan_files_parallel_reraises_panic_as_internal_error() {
+        let temp_dir = TempDir::new().unwrap();
+        let files = parallel_fixture(temp_dir.path());
+
+        let result = scan_files_parallel(files, &ScanConfig::default(), &faulty_processor, None, empty_result(5));
+        match result {
+            Err(JozinError::InternalError { message }) => {
+                assert!(message.contains("c.jpg"));
+                assert!(message.contains("simulated bug"));
+            }
+            other => panic!("expected InternalError, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_scan_files_parallel_demotes_errors_to_file_failures() {
+        let temp_dir = TempDir::new().unwrap();
+        let files = parallel_fixture(temp_dir.path());
+        let config = ScanConfig {
+            dry_run: true,
+            failure_policy: FailurePolicy {
+                abort_on_internal: false,
+                max_file_failures: None,
+            },
+            ..ScanConfig::default()
+        };
+
+        let events = Mutex::new(0usize);
+        let callback = |_event: crate::ProgressEvent| *events.lock().unwrap() += 1;
+        let result =
+            scan_files_parallel(files, &config, &faulty_processor, Some(&callback), empty_result(5)).unwrap();
+
+        assert_eq!(result.failed, 3);
+        assert_eq!(result.skipped, 2);
+        let paths: Vec<&str> = result.scanned_files.iter().map(|f| f.path.as_str()).collect();
+        assert!(paths.windows(2).all(|w| w[0] <= w[1]), "results sorted by path");
+        // FileStarted + FileCompleted per file
+        assert_eq!(*events.lock().unwrap(), 10);
+    }
+
+    #[test]
+    fn test_scan_files_parallel_max_file_failures() {
+        let temp_dir = TempDir::new().unwrap();
+        let files = parallel_fixture(temp_dir.path());
+        let config = ScanConfig {
+            max_threads: 1,
+            failure_policy: FailurePolicy {
+                abort_on_internal: false,
+                max_file_failures: Some(1),
+            },
+            ..ScanConfig::default()
+        };
+
+        let result = scan_files_parallel(files, &config, &faulty_processor, None, empty_result(5));
+        assert!(matches!(result, Err(JozinError::IoError { message }) if message.contains("limit 1")));
+    }
+
+    #[test]
+    fn test_scan_directory_emits_batch_started() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_image(temp_dir.path(), "a.jpg", b"image a");
+        create_test_image(temp_dir.path(), "b.jpg", b"image b!");
+        create_test_image(temp_dir.path(), "notes.txt", b"not an image");
+
+        let batches = Mutex::new(Vec::new());
+        let callback = |event: crate::ProgressEvent| {
+            if let crate::ProgressEvent::BatchStarted { total_files, total_bytes, estimated_duration_ms, .. } = event {
+                batches.lock().unwrap().push((total_files, total_bytes, estimated_duration_ms));
+            }
+        };
+        let config = ScanConfig { dry_run: true, ..ScanConfig::default() };
+        scan_path(temp_dir.path(), &config, Some(&callback)).unwrap();
+
+        assert_eq!(*batches.lock().unwrap(), vec![(2, 15, Some(1))]);
+    }
+
+    #[test]
+    fn test_scan_directory_breadth_first_order() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir_all(temp_dir.path().join("2024/summer")).unwrap();
+        create_test_image(&temp_dir.path().join("2024/summer"), "a.jpg", b"image a");
+        create_test_image(&temp_dir.path().join("2024"), "b.jpg", b"image b");
+        create_test_image(temp_dir.path(), "c.jpg", b"image c");
+
+        let events = Mutex::new(Vec::new());
+        let callback = |event: crate::ProgressEvent| match event {
+            crate::ProgressEvent::BatchStarted { traversal_order, .. } => events.lock().unwrap().push(traversal_order),
+            crate::ProgressEvent::FileStarted { path } => events.lock().unwrap().push(path),
+            crate::ProgressEvent::FileCompleted { .. } | crate::ProgressEvent::BatchCompleted { .. } => {}
+        };
+        let config = ScanConfig {
+            recursive: true,
+            dry_run: true,
+            max_threads: 1,
+            traversal_order: TraversalOrder::BreadthFirst,
+            ..ScanConfig::default()
+        };
+        scan_path(temp_dir.path(), &config, Some(&callback)).unwrap();
+
+        let expected: Vec<String> = std::iter::once("breadth-first".to_string())
+            .chain(["c.jpg", "2024/b.jpg", "2024/summer/a.jpg"].map(|file| temp_dir.path().join(file).display().to_string()))
+            .collect();
+        assert_eq!(*events.lock().unwrap(), expected);
+    }
+
+    #[test]
+    fn test_scan_paths_merges_roots() {
+        let temp_dir = TempDir::new().unwrap();
+        let (root_a, root_b) = (temp_dir.path().join("a"), temp_dir.path().join("b"));
+        fs::create_dir_all(root_a.join("sub")).unwrap();
+        fs::create_dir(&root_b).unwrap();
+        create_test_image(&root_a, "one.jpg", b"same content");
+        create_test_image(&root_a.join("sub"), "two.jpg", b"image two");
+        create_test_image(&root_b, "copy.jpg", b"same content");
+        create_test_image(&root_b, "notes.txt", b"not an image");
+
+        let batches = Mutex::new(Vec::new());
+        let callback = |event: crate::ProgressEvent| {
+            if let crate::ProgressEvent::BatchStarted { total_files, .. } = event {
+                batches.lock().unwrap().push(total_files);
+            }
+        };
+        let config = ScanConfig { recursive: true, ..ScanConfig::default() };
+        // The nested root adds nothing new
+        let roots = [root_a.as_path(), root_b.as_path(), &root_a.join("sub")];
+        let result = scan_paths(&roots, &config, Some(&callback)).unwrap();
+
+        assert_eq!(*batches.lock().unwrap(), vec![3]);
+        assert_eq!((result.total_files, result.successful, result.skipped), (4, 3, 1));
+        let mut paths: Vec<&str> = result.scanned_files.iter().map(|f| f.path.as_str()).collect();
+        paths.sort();
+        paths.dedup();
+        assert_eq!(paths.len(), 4);
+
+        // Only scanned files count; test files have no EXIF
+        assert_eq!((result.exif_coverage.total, result.exif_coverage.missing_all), (3, 3));
+
+        assert_eq!(result.cross_root_duplicates.len(), 1);
+        let mut group = result.cross_root_duplicates[0].paths.clone();
+        group.sort();
+        assert!(group[0].ends_with("one.jpg") && group[1].ends_with("copy.jpg"));
+
+        assert!(matches!(scan_paths(&[], &config, None), Err(JozinError::UserError { .. })));
+        let missing = temp_dir.path().join("missing");
+        assert!(matches!(scan_paths(&[&root_a, &missing], &config, None), Err(JozinError::IoError { .. })));
+    }
+
+    #[test]
+    fn test_rescan_preserves_ingest_timestamp() {
+        let temp_dir = TempDir::new().unwrap();
+        let photo = create_test_image(temp_dir.path(), "a.jpg", b"image a");
+
+        let first = scan_file(&photo, false).unwrap().sidecar;
+        assert_eq!(first.source.ingest_timestamp, first.created_at);
+
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        let second = scan_file(&photo, false).unwrap().sidecar;
+        assert_ne!(second.created_at, first.created_at);
+        assert_eq!(second.source.ingest_timestamp, first.source.ingest_timestamp);
+    }
+
+    #[test]
+    fn test_scan_file_reports_write_action() {
+        let temp_dir = TempDir::new().unwrap();
+        let photo = create_test_image(temp_dir.path(), "a.jpg", b"image a");
+        let sidecar_path = LibraryConfig::default().sidecar_path(&photo);
+
+        let preview = scan_file(&photo, true).unwrap();
+        assert_eq!(preview.action, WriteAction::DryRunPreview);
+        assert_eq!(preview.sidecar_path, sidecar_path);
+        assert!(!sidecar_path.exists());
+
+        let created = scan_file(&photo, false).unwrap();
+        assert_eq!(created.action, WriteAction::Created);
+        assert_eq!((created.backup_path, created.previous_schema_version), (None, None));
+
+        let updated = scan_file(&photo, false).unwrap();
+        assert_eq!(updated.action, WriteAction::Updated);
+        assert_eq!(updated.previous_schema_version.as_deref(), Some(created.sidecar.schema_version.as_str()));
+        let backup_path = updated.backup_path.unwrap();
+        assert_eq!(backup_path, append_to_file_name(&sidecar_path, ".bak1"));
+        assert_eq!(parse_sidecar(&fs::read_to_string(&backup_path).unwrap()).unwrap().created_at, created.sidecar.created_at);
+
+        // A rescan through the directory scan reports the update and its backup
+        let result = scan_path(temp_dir.path(), &ScanConfig::default(), None).unwrap();
+        let scanned = &result.scanned_files[0];
+        assert!(matches!(scanned.action, ScanAction::Updated));
+        assert_eq!(scanned.backup_path.as_deref(), Some(backup_path.display().to_string().as_str()));
+        assert_eq!(result.successful, 1);
+    }
+
+    #[test]
+    fn test_scan_with_compressed_sidecars() {
+        let temp_dir = TempDir::new().unwrap();
+        let photo = create_test_image(temp_dir.path(), "a.jpg", b"image a");
+        let sidecar_path = LibraryConfig::default().sidecar_path(&photo);
+        scan_file(&photo, false).unwrap();
+        let uncompressed = scan_file(&photo, false).unwrap().sidecar;
+        let plain_backup = fs::read_to_string(append_to_file_name(&sidecar_path, ".bak1")).unwrap();
+
+        let config = ScanConfig { compress_sidecars: true, ..ScanConfig::default() };
+        let written = scan_file_with_config(&photo, &config).unwrap();
+        assert_eq!(written.action, WriteAction::Updated);
+        assert_eq!(written.sidecar_path, append_to_file_name(&sidecar_path, ".gz"));
+        assert!(!sidecar_path.exists());
+
+        // The plain sidecar and its backup continue the compressed backup chain
+        let gz_backup = |k: u8| append_to_file_name(&written.sidecar_path, &format!(".bak{}", k));
+        assert_eq!(written.backup_path, Some(gz_backup(1)));
+        assert_eq!(parse_sidecar(&read_sidecar_file(&gz_backup(1)).unwrap()).unwrap().created_at, uncompressed.created_at);
+        assert_eq!(read_sidecar_file(&gz_backup(2)).unwrap(), plain_backup);
+        assert!(!append_to_file_name(&sidecar_path, ".bak1").exists());
+
+        // Read back through gzip with identical content
+        assert_eq!(
+            serde_json::to_string_pretty(&read_sidecar(&photo).unwrap()).unwrap(),
+            serde_json::to_string_pretty(&written.sidecar).unwrap()
+        );
+        assert_eq!(find_sidecar_files(temp_dir.path(), false).unwrap(), vec![written.sidecar_path.clone()]);
+        assert_eq!(load_sidecars(temp_dir.path(), false).unwrap().len(), 1);
+
+        // Later plain scans keep the compressed form
+        let rescanned = scan_file(&photo, false).unwrap();
+        assert_eq!(rescanned.sidecar_path, written.sidecar_path);
+        assert!(rescanned.backup_path.unwrap().ends_with("a.jpg.json.gz.bak1"));
+        assert_eq!(rescanned.sidecar.source.ingest_timestamp, uncompressed.source.ingest_timestamp);
+        assert!(!sidecar_path.exists());
+    }
+
+    #[test]
+    fn test_scan_path_compressed_without_existing_sidecar() {
+        let temp_dir = TempDir::new().unwrap();
+        let photo = create_test_image(temp_dir.path(), "a.jpg", b"image a");
+        let sidecar_path = LibraryConfig::default().sidecar_path(&photo);
+
+        let config = ScanConfig { compress_sidecars: true, ..ScanConfig::default() };
+        let result = scan_path(&photo, &config, None).unwrap();
+        assert_eq!((result.successful, result.failed), (1, 0));
+        assert!(matches!(result.scanned_files[0].action, ScanAction::Written));
+        assert!(!sidecar_path.exists());
+        assert_eq!(
+            find_sidecar_files(temp_dir.path(), false).unwrap(),
+            vec![append_to_file_name(&sidecar_path, ".gz")]
+        );
+    }
+
+    #[test]
+    fn test_old_sidecar_ingest_timestamp_falls_back_to_created_at() {
+        let temp_dir = TempDir::new().unwrap();
+        let photo = create_test_image(temp_dir.path(), "a.jpg", b"image a");
+        let sidecar = scan_file(&photo, false).unwrap().sidecar;
+
+        // Simulate a sidecar written before ingest_timestamp existed
+        let sidecar_path = LibraryConfig::default().sidecar_path(&photo);
+        let mut json: serde_json::Value = serde_json::from_str(&fs::read_to_string(&sidecar_path).unwrap()).unwrap();
+        json["source"].as_object_mut().unwrap().remove("ingest_timestamp");
+        json["created_at"] = serde_json::json!("2020-01-01T00:00:00Z");
+        fs::write(&sidecar_path, json.to_string()).unwrap();
+
+        assert_eq!(read_sidecar(&photo).unwrap().source.ingest_timestamp, "2020-01-01T00:00:00Z");
+        let rescanned = scan_file(&photo, false).unwrap().sidecar;
+        assert_eq!(rescanned.source.ingest_timestamp, "2020-01-01T00:00:00Z");
+        assert_ne!(rescanned.source.ingest_timestamp, sidecar.source.ingest_timestamp);
+    }
+
+    #[test]
+    fn test_scan_custom_sidecar_naming_is_saved_for_library() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        create_test_image(root, "IMG_0001.jpg", b"image 1");
+
+        let config = ScanConfig { sidecar_suffix: ".jozin.json".to_string(), ..ScanConfig::default() };
+        let result = scan_path(root, &config, None).unwrap();
+        assert_eq!(result.successful, 1);
+        assert!(root.join("IMG_0001.jpg.jozin.json").exists());
+        assert!(!root.join("IMG_0001.jpg.json").exists());
+
+        // Later scans and reads without options use the saved naming
+        create_test_image(root, "IMG_0002.jpg", b"image 2");
+        scan_path(root, &ScanConfig::default(), None).unwrap();
+        assert!(root.join("IMG_0002.jpg.jozin.json").exists());
+        assert!(read_sidecar(&root.join("IMG_0002.jpg")).is_ok());
+        assert_eq!(find_sidecar_files(root, false).unwrap().len(), 2);
+        assert_eq!(load_sidecars(root, false).unwrap().len(), 2);
+
+        let empty = ScanConfig { sidecar_suffix: String::new(), ..ScanConfig::default() };
+        assert!(matches!(scan_path(root, &empty, None), Err(JozinError::ValidationError { .. })));
+    }
+
+    #[test]
+    fn test_adjusted_for_network_filesystem() {
+        let config = ScanConfig { max_threads: 8, ..ScanConfig::default() };
+
+        let nas = config.adjusted_for_filesystem(FilesystemType::Smb);
+        assert_eq!(nas.max_threads, NETWORK_MAX_THREADS);
+        assert_eq!(nas.io_budget, Some(IoBudget::new(NETWORK_IO_BUDGET_BYTES_PER_SEC)));
+        assert_eq!(nas.fsync_policy, FsyncPolicy::Never);
+
+        // A stricter user limit is kept
+        let limited = ScanConfig { io_budget: Some(IoBudget::new(1_000_000)), ..config.clone() };
+        let nfs = limited.adjusted_for_filesystem(FilesystemType::Nfs);
+        assert_eq!(nfs.io_budget, Some(IoBudget::new(1_000_000)));
+
+        let local = config.adjusted_for_filesystem(FilesystemType::Local);
+        assert_eq!(local.max_threads, 8);
+        assert_eq!(local.fsync_policy, FsyncPolicy::Always);
+        let forced = ScanConfig { force_local_io: true, ..config }.adjusted_for_filesystem(FilesystemType::Smb);
+        assert_eq!(forced.max_threads, 8);
+        assert_eq!(forced.io_budget, None);
+    }
+
+    #[test]
+    fn test_scan_uses_shared_hash_cache() {
+        let temp_dir = TempDir::new().unwrap();
+        let photo = create_test_image(temp_dir.path(), "IMG_0001.jpg", b"image 1");
+        let cache = Arc::new(Mutex::new(HashCache::default()));
+        let config = ScanConfig { dry_run: true, hash_cache: Some(Arc::clone(&cache)), ..ScanConfig::default() };
+
+        let sidecar = scan_file_with_config(&photo, &config).unwrap().sidecar;
+        assert_eq!(sidecar.source.file_hash_b3, compute_blake3_hash(&photo).unwrap());
+        assert_eq!(lock(&cache).len(), 1);
+
+        // A cached hash for the unchanged file is used as is
+        let (size_bytes, modified_at) = crate::util::hash_cache::file_state(&photo).unwrap();
+        let entry = HashCacheEntry { size_bytes, modified_at, hash: "cached".to_string() };
+        lock(&cache).insert(photo.clone(), entry);
+        assert_eq!(scan_file_with_config(&photo, &config).unwrap().sidecar.source.file_hash_b3, "cached");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_scan_symlink_strategies() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        create_test_image(root, "IMG_0001.jpg", b"image 1");
+        std::os::unix::fs::symlink(root.join("IMG_0001.jpg"), root.join("link.jpg")).unwrap();
+
+        let skip = ScanConfig { dry_run: true, symlinks: SymlinkStrategy::Skip, ..ScanConfig::default() };
+        let result = scan_path(root, &skip, None).unwrap();
+        let link = result.scanned_files.iter().find(|f| f.path.ends_with("link.jpg")).unwrap();
+        assert!(matches!(link.action, ScanAction::Skipped));
+        assert_eq!(link.error.as_deref(), Some("Symlink"));
+
+        let error = ScanConfig { dry_run: true, symlinks: SymlinkStrategy::Error, ..ScanConfig::default() };
+        assert!(matches!(scan_path(root, &error, None), Err(JozinError::UserError { .. })));
+
+        let follow = ScanConfig { dry_run: true, ..ScanConfig::default() };
+        assert_eq!(scan_path(root, &follow, None).unwrap().total_files, 2);
+    }
+
+    #[test]
+    fn test_parallel_walk_matches_sequential_walk() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir(root.join("album")).unwrap();
+        for i in 0..20 {
+            create_test_image(root, &format!("IMG_{:04}.jpg", i), b"image");
+            create_test_image(&root.join("album"), &format!("IMG_{:04}.jpg", i), b"image");
+        }
+        create_test_image(root, "notes.txt", b"not an image");
+
+        let skipped = |threads| {
+            let config = ScanConfig { dry_run: true, recursive: true, max_threads: threads, ..ScanConfig::default() };
+            let result = scan_path(root, &config, None).unwrap();
+            assert_eq!(result.total_files, 41);
+            // Walk-time skips carry a reason; dry-run skips don't
+            result.scanned_files.iter().filter(|f| f.error.is_some()).map(|f| f.path.clone()).collect::<Vec<_>>()
+        };
+        assert_eq!(skipped(1), skipped(8));
+        assert_eq!(skipped(8).len(), 1);
+    }
+
+    #[test]
+    fn test_scan_path_output_sort_order() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        create_test_image(root, "a.jpg", b"1");
+        create_test_image(root, "b.jpg", b"123");
+        create_test_image(root, "c.jpg", b"12");
+
+        let config = ScanConfig { output_sort_order: SortOrder::SizeLargestFirst, ..ScanConfig::default() };
+        let result = scan_path(root, &config, None).unwrap();
+        let names: Vec<&str> = result.scanned_files.iter().map(|f| f.path.rsplit('/').next().unwrap()).collect();
+        assert_eq!(names, ["b.jpg", "c.jpg", "a.jpg"]);
+    }
+
+    #[test]
+    fn test_scan_path_memory_limit_truncates() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        for i in 0..5 {
+            create_test_image(root, &format!("IMG_{}.jpg", i), b"image");
+        }
+
+        let unlimited = scan_path(root, &ScanConfig { dry_run: true, ..ScanConfig::default() }, None).unwrap();
+        assert!(!unlimited.truncated);
+        assert_eq!(unlimited.scanned_files.len(), 5);
+
+        if crate::util::memory_limit::current_rss_bytes().is_some() {
+            let config = ScanConfig { dry_run: true, memory_limit_mb: Some(0), ..ScanConfig::default() };
+            let result = scan_path(root, &config, None).unwrap();
+            assert!(result.truncated);
+            assert_eq!(result.total_files, 5);
+            assert!(result.scanned_files.len() < 5);
+        }
+    }
+
+    #[test]
+    fn test_exif_coverage_counts() {
+        let complete = ImageInfo {
+            datetime_original: Some("2024-06-01T12:00:00Z".to_string()),
+            gps_latitude: Some(50.08),
+            gps_longitude: Some(14.42),
+            camera_make: Some("Apple".to_string()),
+            camera_model: Some("iPhone 12".to_string()),
+            ..ImageInfo::default()
+        };
+        let camera_only = ImageInfo { camera_make: Some("Canon".to_string()), ..ImageInfo::default() };
+        // Latitude alone is not a GPS location
+        let half_gps = ImageInfo { gps_latitude: Some(50.08), width: Some(640), ..ImageInfo::default() };
+
+        let mut coverage = ExifCoverage::of(Some(&complete));
+        coverage.add(Some(&camera_only));
+        coverage.add(Some(&half_gps));
+        coverage.add(None);
+        assert_eq!(
+            coverage,
+            ExifCoverage { has_datetime: 1, has_gps: 1, has_camera: 2, has_all: 1, missing_all: 2, total: 4 }
+        );
+        assert_eq!(coverage.percent(coverage.has_camera), 50);
+
+        coverage.merge(&ExifCoverage::of(Some(&complete)));
+        assert_eq!((coverage.has_all, coverage.total), (2, 5));
+        assert_eq!(coverage.percent(coverage.has_datetime), 40);
+        assert_eq!(ExifCoverage::default().percent(0), 0);
+    }
+
+    #[test]
+    fn test_scan_path_detects_renamed_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let old_path = create_test_image(root, "old.jpg", b"renamed photo");
+        create_test_image(root, "other.jpg", b"other photo");
+        let mut sidecar = scan_file(&old_path, false).unwrap().sidecar;
+        sidecar.tags = vec![crate::Tag::from_label("beach")];
+        write_sidecar(&old_path, &sidecar).unwrap();
+        scan_file(&root.join("other.jpg"), false).unwrap();
+        let new_path = root.join("new.jpg");
+        fs::rename(&old_path, &new_path).unwrap();
+
+        let index = SidecarIndex::build(root, false, &LibraryConfig::default()).unwrap();
+        let config = ScanConfig { sidecar_index: Some(Arc::new(index)), ..ScanConfig::default() };
+        let result = scan_path(root, &config, None).unwrap();
+
+        let renamed = result.scanned_files.iter().find(|f| f.path.ends_with("new.jpg")).unwrap();
+        assert!(matches!(&renamed.action, ScanAction::Renamed { previous_path } if previous_path.ends_with("old.jpg")));
+        assert_eq!(result.successful, 2);
+        assert_eq!(result.orphaned_sidecars, vec![root.join("old.jpg.json").display().to_string()]);
+
+        let carried = read_sidecar(&new_path).unwrap();
+        assert_eq!(carried.tags[0].label, "beach");
+        assert_eq!(carried.created_at, sidecar.created_at);
+        assert!(carried.source.file_path.ends_with("new.jpg"));
+        assert_eq!(carried.source.previous_paths, vec![sidecar.source.file_path.clone()]);
+
+        // A plain rescan keeps the rename history and is not a rename again
+        let rescan = scan_path(root, &ScanConfig::default(), None).unwrap();
+        assert!(rescan.scanned_files.iter().all(|f| !matches!(f.action, ScanAction::Renamed { .. })));
+        assert!(rescan.orphaned_sidecars.is_empty());
+        assert_eq!(read_sidecar(&new_path).unwrap().source.previous_paths.len(), 1);
+    }
 }
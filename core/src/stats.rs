@@ -0,0 +1,259 @@
+//! Stats module - Library statistics snapshots
+//!
+//! This module is responsible for:
+//! - Summarising a library from its sidecars ([`LibraryStats::collect()`])
+//! - Saving and loading stats snapshots as JSON files
+//! - Comparing two snapshots ([`LibraryStats::delta()`])
+//!
+//! Snapshots are plain JSON, so `jozin stats --save stats_2025-01.json` one
+//! month and `jozin stats --compare stats_2025-01.json` the next gives a
+//! library health report of what was added and removed in between.
+//!
+//! Only sidecars are read; photos without a sidecar are not counted.
+
+use crate::scan::{load_sidecars, ExifCoverage};
+use crate::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+
+// ============================================================================
+// Public Types
+// ============================================================================
+
+/// Summary of a library at one point in time.
+///
+/// # Fields
+///
+/// - `total_files`: Photos with a sidecar
+/// - `total_bytes`: Sum of `source.file_size_bytes`
+/// - `cameras`: Photos per camera (`"<make> <model>"`)
+/// - `tag_frequency`: Occurrences per tag label
+/// - `faces`: Detected faces over all photos
+/// - `geotagged`: Photos with GPS coordinates
+/// - `exif_coverage`: How complete the photos' EXIF data is
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LibraryStats {
+    pub total_files: u64,
+    pub total_bytes: u64,
+    #[serde(default)]
+    pub cameras: BTreeMap<String, u64>,
+    #[serde(default)]
+    pub tag_frequency: BTreeMap<String, u64>,
+    #[serde(default)]
+    pub faces: u64,
+    #[serde(default)]
+    pub geotagged: u64,
+    #[serde(default)]
+    pub exif_coverage: ExifCoverage,
+}
+
+/// Difference between two [`LibraryStats`] snapshots (`new - old`).
+///
+/// # Fields
+///
+/// - `new_files`: Photos added (negative if removed)
+/// - `bytes_delta`: Change in total size
+/// - `new_cameras`: Cameras only in the new snapshot (sorted)
+/// - `removed_cameras`: Cameras only in the old snapshot (sorted)
+/// - `tag_frequency_delta`: Change in occurrences per tag; unchanged tags are omitted
+/// - `face_delta`: Change in detected faces
+/// - `geotagged_delta`: Change in geotagged photos
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LibraryStatsDelta {
+    pub new_files: i64,
+    pub bytes_delta: i64,
+    pub new_cameras: Vec<String>,
+    pub removed_cameras: Vec<String>,
+    pub tag_frequency_delta: HashMap<String, i64>,
+    pub face_delta: i64,
+    pub geotagged_delta: i64,
+}
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+impl LibraryStats {
+    /// Collects stats from all sidecars under `path`.
+    ///
+    /// Unparsable sidecars are skipped (see [`load_sidecars()`]).
+    ///
+    /// # Errors
+    ///
+    /// Returns `JozinError::IoError` if `path` does not exist.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use jozin_core::stats::LibraryStats;
+    /// use std::path::Path;
+    ///
+    /// let stats = LibraryStats::collect(Path::new("/photos"), true)?;
+    /// println!("{} photos, {} bytes", stats.total_files, stats.total_bytes);
+    /// # Ok::<(), jozin_core::JozinError>(())
+    /// ```
+    pub fn collect(path: &Path, recursive: bool) -> Result<Self> {
+        let mut stats = Self::default();
+        for sidecar in load_sidecars(path, recursive)? {
+            stats.total_files += 1;
+            stats.total_bytes += sidecar.source.file_size_bytes;
+            stats.faces += sidecar.faces.len() as u64;
+            stats.exif_coverage.add(sidecar.image.as_ref());
+            for tag in &sidecar.tags {
+                *stats.tag_frequency.entry(tag.label.clone()).or_default() += 1;
+            }
+            if let Some(image) = &sidecar.image {
+                if image.gps_latitude.is_some() && image.gps_longitude.is_some() {
+                    stats.geotagged += 1;
+                }
+                if let Some(camera) = camera_name(image.camera_make.as_deref(), image.camera_model.as_deref()) {
+                    *stats.cameras.entry(camera).or_default() += 1;
+                }
+            }
+        }
+        Ok(stats)
+    }
+
+    /// Loads a snapshot saved with [`LibraryStats::save()`].
+    ///
+    /// # Errors
+    ///
+    /// - `JozinError::IoError` if the file cannot be read
+    /// - `JozinError::ValidationError` if it is not a valid stats snapshot
+    pub fn load(path: &Path) -> Result<Self> {
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    /// Saves the snapshot as pretty-printed JSON.
+    ///
+    /// # Errors
+    ///
+    /// Returns `JozinError::IoError` if the file cannot be written.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Computes what changed from `old` to `new`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use jozin_core::stats::LibraryStats;
+    /// use std::path::Path;
+    ///
+    /// let old = LibraryStats::load(Path::new("stats_2025-01.json"))?;
+    /// let new = LibraryStats::collect(Path::new("/photos"), true)?;
+    /// let delta = LibraryStats::delta(&old, &new);
+    /// println!("{:+} photos, new cameras: {:?}", delta.new_files, delta.new_cameras);
+    /// # Ok::<(), jozin_core::JozinError>(())
+    /// ```
+    pub fn delta(old: &LibraryStats, new: &LibraryStats) -> LibraryStatsDelta {
+        let mut tag_frequency_delta = HashMap::new();
+        for label in old.tag_frequency.keys().chain(new.tag_frequency.keys()) {
+            let change = signed_diff(
+                old.tag_frequency.get(label).copied().unwrap_or(0),
+                new.tag_frequency.get(label).copied().unwrap_or(0),
+            );
+            if change != 0 {
+                tag_frequency_delta.insert(label.clone(), change);
+            }
+        }
+
+        LibraryStatsDelta {
+            new_files: signed_diff(old.total_files, new.total_files),
+            bytes_delta: signed_diff(old.total_bytes, new.total_bytes),
+            new_cameras: new.cameras.keys().filter(|c| !old.cameras.contains_key(*c)).cloned().collect(),
+            removed_cameras: old.cameras.keys().filter(|c| !new.cameras.contains_key(*c)).cloned().collect(),
+            tag_frequency_delta,
+            face_delta: signed_diff(old.faces, new.faces),
+            geotagged_delta: signed_diff(old.geotagged, new.geotagged),
+        }
+    }
+}
+
+// ============================================================================
+// Internal Helpers
+// ============================================================================
+
+/// `"<make> <model>"`, or whichever of the two is present.
+fn camera_name(make: Option<&str>, model: Option<&str>) -> Option<String> {
+    let name = [make, model]
+        .into_iter()
+        .flatten()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+    (!name.is_empty()).then_some(name)
+}
+
+/// `new - old` as a signed count.
+fn signed_diff(old: u64, new: u64) -> i64 {
+    new as i64 - old as i64
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scan::scan_file;
+    use tempfile::TempDir;
+
+    fn snapshot(files: u64, cameras: &[&str], tags: &[(&str, u64)]) -> LibraryStats {
+        LibraryStats {
+            total_files: files,
+            total_bytes: files * 1000,
+            cameras: cameras.iter().map(|c| (c.to_string(), 1)).collect(),
+            tag_frequency: tags.iter().map(|(t, n)| (t.to_string(), *n)).collect(),
+            faces: files * 2,
+            geotagged: files / 2,
+            exif_coverage: ExifCoverage::default(),
+        }
+    }
+
+    #[test]
+    fn test_delta_added_and_removed() {
+        let old = snapshot(10, &["Canon EOS R5", "Apple iPhone 12"], &[("beach", 3), ("cat", 2)]);
+        let new = snapshot(8, &["Canon EOS R5", "Sony A7 IV"], &[("beach", 5), ("cat", 2), ("snow", 1)]);
+
+        let delta = LibraryStats::delta(&old, &new);
+        assert_eq!(delta.new_files, -2);
+        assert_eq!(delta.bytes_delta, -2000);
+        assert_eq!(delta.new_cameras, vec!["Sony A7 IV"]);
+        assert_eq!(delta.removed_cameras, vec!["Apple iPhone 12"]);
+        assert_eq!(delta.tag_frequency_delta.len(), 2);
+        assert_eq!(delta.tag_frequency_delta["beach"], 2);
+        assert_eq!(delta.tag_frequency_delta["snow"], 1);
+        assert_eq!(delta.face_delta, -4);
+        assert_eq!(delta.geotagged_delta, -1);
+
+        assert_eq!(LibraryStats::delta(&new, &new), LibraryStatsDelta::default());
+    }
+
+    #[test]
+    fn test_collect_save_and_load() {
+        let temp_dir = TempDir::new().unwrap();
+        for name in ["a.jpg", "b.jpg"] {
+            let photo = temp_dir.path().join(name);
+            fs::write(&photo, b"image bytes").unwrap();
+            scan_file(&photo, false).unwrap();
+        }
+
+        let stats = LibraryStats::collect(temp_dir.path(), false).unwrap();
+        assert_eq!(stats.total_files, 2);
+        assert_eq!(stats.total_bytes, 22);
+        assert_eq!((stats.exif_coverage.total, stats.exif_coverage.missing_all), (2, 2));
+
+        let saved = temp_dir.path().join("stats.json");
+        stats.save(&saved).unwrap();
+        assert_eq!(LibraryStats::load(&saved).unwrap(), stats);
+        assert_eq!(camera_name(Some("Canon "), Some("EOS R5")).as_deref(), Some("Canon EOS R5"));
+        assert_eq!(camera_name(None, Some("")), None);
+    }
+}
@@ -8,9 +8,40 @@
 //!
 //! ## Status
 //!
-//! This is a **Phase 2+ stub module**. The actual implementation is planned
-//! but not yet available. This file exists to satisfy the feature flag
-//! structure and prevent compilation warnings.
+//! Rule-based tagging is available through [`suggest_tags()`], which previews
+//! tags without touching the sidecar. ML tagging needs a local model runtime
+//! that is not bundled yet: [`TagMode::Ml`] returns `JozinError::NotSupported`
+//! and [`TagMode::Both`] currently yields rule tags only.
+//!
+//! ## Zero-Shot Tagging (CLIP)
+//!
+//! [`ml_tag_batch()`] scores images against free-form candidate labels
+//! ("beach", "dog", "birthday party") with a CLIP model: images are
+//! preprocessed to 224×224 (see [`preprocess_clip()`]) and embedded in
+//! batches, labels are embedded once per [`ClipTagger`] (cached), and each
+//! label's score is the softmax of the cosine similarities scaled by CLIP's
+//! logit scale (see [`zero_shot_scores()`]). Running the encoders needs an
+//! ONNX runtime that is not bundled yet: the tagger validates its model and
+//! preprocesses the images, then returns `JozinError::NotSupported`.
+//!
+//! ## Rules
+//!
+//! | Sidecar data                         | Tag                        |
+//! |--------------------------------------|----------------------------|
+//! | `image.camera_make` "Apple"          | `camera:apple`             |
+//! | `image.datetime_original` in 2020    | `year:2020`                |
+//! | width > height / height > width      | `landscape` / `portrait`   |
+//! | `image.gps_latitude` and longitude   | `geotagged`                |
+//! | at least one face                    | `people`                   |
+//! | `source.live_photo_path`             | `live-photo`               |
+//!
+//! ## Hierarchical Tags
+//!
+//! Labels may be nested with `/`, e.g. `scene/outdoor/beach`. A rule that
+//! emits a nested label also emits its ancestors (`scene`, `scene/outdoor`),
+//! so collapsing a tree view never loses photos. [`TagHierarchy`] builds the
+//! label tree with counts for a set of sidecars, and [`matches_tag_pattern()`]
+//! matches `scene/**` against all descendants.
 //!
 //! ## Future Implementation
 //!
@@ -19,6 +50,774 @@
 //! - `apply_rules()` - Apply rule-based heuristics
 //! - `merge_tags()` - Merge ML and rule-based tags
 
-// Placeholder to prevent "unused module" warnings
-#[allow(dead_code)]
-const _PLACEHOLDER: &str = "Phase 2+ implementation pending";
+use crate::{JozinError, Result, Sidecar, Tag, TagSource};
+use image::imageops::FilterType;
+use image::DynamicImage;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+/// Separator between the levels of a hierarchical tag label.
+pub const TAG_HIERARCHY_SEPARATOR: char = '/';
+
+/// Edge length (pixels) of the square CLIP input image.
+pub const CLIP_INPUT_SIZE: u32 = 224;
+
+/// Images embedded per CLIP vision encoder run by default.
+pub const DEFAULT_TAG_BATCH_SIZE: u32 = 32;
+
+/// Default minimum zero-shot score for an ML tag.
+pub const DEFAULT_MIN_TAG_SCORE: f32 = 0.2;
+
+/// CLIP's learned logit scale (the cosine similarities are multiplied by it
+/// before the softmax).
+const CLIP_LOGIT_SCALE: f32 = 100.0;
+
+/// Per-channel (RGB) mean and standard deviation CLIP was trained with.
+const CLIP_MEAN: [f32; 3] = [0.481_454_66, 0.457_827_5, 0.408_210_73];
+const CLIP_STD: [f32; 3] = [0.268_629_54, 0.261_302_6, 0.275_777_1];
+
+// ============================================================================
+// Public Types
+// ============================================================================
+
+/// Which tagging pipelines to run.
+///
+/// # Variants
+///
+/// - `Ml`: ML models only
+/// - `Rules`: Rule-based heuristics only
+/// - `Both`: ML models and rules
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TagMode {
+    Ml,
+    Rules,
+    Both,
+}
+
+/// Tree of hierarchical tag labels with usage counts.
+///
+/// # Fields
+///
+/// - `root`: Unnamed root node; its `count` is the total number of tags
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TagHierarchy {
+    pub root: HierarchyNode,
+}
+
+/// One level of a [`TagHierarchy`].
+///
+/// # Fields
+///
+/// - `label`: Full label of the node (e.g. `scene/outdoor`); empty for the root
+/// - `children`: Child nodes, sorted by label
+/// - `count`: Tags with this label or a descendant label
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HierarchyNode {
+    pub label: String,
+    pub children: Vec<HierarchyNode>,
+    pub count: usize,
+}
+
+impl HierarchyNode {
+    /// Last level of the label (`beach` for `scene/outdoor/beach`).
+    pub fn name(&self) -> &str {
+        self.label.rsplit(TAG_HIERARCHY_SEPARATOR).next().unwrap_or_default()
+    }
+
+    /// Child node for `label`, created if missing.
+    fn child(&mut self, label: &str) -> &mut HierarchyNode {
+        let index = match self.children.iter().position(|c| c.label == label) {
+            Some(index) => index,
+            None => {
+                self.children.push(HierarchyNode {
+                    label: label.to_string(),
+                    ..HierarchyNode::default()
+                });
+                self.children.len() - 1
+            }
+        };
+        &mut self.children[index]
+    }
+
+    fn sort(&mut self) {
+        self.children.sort_by(|a, b| a.label.cmp(&b.label));
+        self.children.iter_mut().for_each(HierarchyNode::sort);
+    }
+}
+
+impl TagHierarchy {
+    /// Builds the label tree of all tags in `sidecars`.
+    ///
+    /// Every tag counts once towards its own node and each ancestor; empty
+    /// levels (`scene//beach`) are ignored.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use jozin_core::scan::load_sidecars;
+    /// use jozin_core::tags::TagHierarchy;
+    /// use std::path::Path;
+    ///
+    /// let hierarchy = TagHierarchy::build(&load_sidecars(Path::new("/photos"), true)?);
+    /// for node in &hierarchy.root.children {
+    ///     println!("{} ({})", node.name(), node.count);
+    /// }
+    /// # Ok::<(), jozin_core::JozinError>(())
+    /// ```
+    pub fn build(sidecars: &[Sidecar]) -> TagHierarchy {
+        let mut root = HierarchyNode::default();
+        for tag in sidecars.iter().flat_map(|s| &s.tags) {
+            let levels = Self::ancestors(&tag.label).into_iter().chain(std::iter::once(normalise_label(&tag.label)));
+            let mut node = &mut root;
+            node.count += 1;
+            for label in levels.filter(|l| !l.is_empty()) {
+                node = node.child(&label);
+                node.count += 1;
+            }
+        }
+        root.sort();
+        TagHierarchy { root }
+    }
+
+    /// Ancestor labels of `label`, outermost first.
+    ///
+    /// `"scene/outdoor/beach"` yields `["scene", "scene/outdoor"]`; a flat
+    /// label has no ancestors.
+    pub fn ancestors(label: &str) -> Vec<String> {
+        let label = normalise_label(label);
+        let levels: Vec<&str> = label.split(TAG_HIERARCHY_SEPARATOR).collect();
+        (1..levels.len()).map(|depth| levels[..depth].join("/")).collect()
+    }
+}
+
+/// Model and labels for zero-shot tagging with [`ClipTagger`].
+///
+/// # Fields
+///
+/// - `model_path`: CLIP model (ONNX, vision and text encoders)
+/// - `candidate_labels`: Labels each image is scored against
+/// - `batch_size`: Images per vision encoder run
+/// - `min_score`: Drop tags scoring below this (0-1)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TagModelConfig {
+    pub model_path: PathBuf,
+    pub candidate_labels: Vec<String>,
+    pub batch_size: u32,
+    pub min_score: f32,
+}
+
+impl TagModelConfig {
+    /// Config for the given model and labels with the default batch size (32)
+    /// and minimum score (0.2).
+    pub fn new(model_path: impl Into<PathBuf>, candidate_labels: Vec<String>) -> Self {
+        Self {
+            model_path: model_path.into(),
+            candidate_labels,
+            batch_size: DEFAULT_TAG_BATCH_SIZE,
+            min_score: DEFAULT_MIN_TAG_SCORE,
+        }
+    }
+}
+
+/// A CLIP model loaded once, reused across batches.
+///
+/// The text embeddings of the candidate labels are computed on the first
+/// batch and cached for the lifetime of the tagger.
+#[derive(Debug)]
+pub struct ClipTagger {
+    config: TagModelConfig,
+    session: ModelSession,
+    text_embeddings: OnceLock<HashMap<String, Vec<f32>>>,
+}
+
+impl ClipTagger {
+    /// Validates `config` and loads its model.
+    ///
+    /// # Errors
+    ///
+    /// - `JozinError::UserError` if there are no candidate labels, the batch
+    ///   size is 0 or `min_score` is outside 0-1
+    /// - `JozinError::IoError` if the model file cannot be read
+    pub fn new(config: &TagModelConfig) -> Result<ClipTagger> {
+        if config.candidate_labels.iter().all(|label| label.trim().is_empty()) {
+            return Err(JozinError::UserError {
+                message: "at least one candidate label is required".to_string(),
+            });
+        }
+        if config.batch_size == 0 {
+            return Err(JozinError::UserError {
+                message: "batch_size must be at least 1".to_string(),
+            });
+        }
+        if !(0.0..=1.0).contains(&config.min_score) {
+            return Err(JozinError::UserError {
+                message: format!("min_score must be between 0 and 1, got {}", config.min_score),
+            });
+        }
+        Ok(ClipTagger {
+            config: config.clone(),
+            session: ModelSession::load(&config.model_path)?,
+            text_embeddings: OnceLock::new(),
+        })
+    }
+
+    /// Tags each image with the candidate labels scoring at least `min_score`.
+    ///
+    /// Images are decoded and embedded `batch_size` at a time. The result has
+    /// one entry per image, in input order; each entry holds
+    /// [`TagSource::Ml`] tags sorted by score (highest first).
+    ///
+    /// # Errors
+    ///
+    /// - `JozinError::IoError` if an image cannot be decoded
+    /// - `JozinError::NotSupported` until an ONNX runtime is available
+    pub fn tag_batch(&self, images: &[&Path]) -> Result<Vec<Vec<Tag>>> {
+        let mut tags = Vec::with_capacity(images.len());
+        for batch in images.chunks(self.config.batch_size as usize) {
+            let pixels = batch
+                .iter()
+                .map(|path| {
+                    image::open(path).map(|image| preprocess_clip(&image)).map_err(|e| JozinError::IoError {
+                        message: format!("Failed to decode {}: {}", path.display(), e),
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            let labels = self.label_embeddings()?;
+            for embedding in self.session.encode_images(&pixels)? {
+                let scores = zero_shot_scores(&embedding, &self.config.candidate_labels, labels);
+                tags.push(tags_from_scores(scores, self.config.min_score));
+            }
+        }
+        Ok(tags)
+    }
+
+    /// Text embeddings of the candidate labels, encoded on first use.
+    fn label_embeddings(&self) -> Result<&HashMap<String, Vec<f32>>> {
+        if let Some(cached) = self.text_embeddings.get() {
+            return Ok(cached);
+        }
+        let labels = &self.config.candidate_labels;
+        let embeddings = self.session.encode_text(labels)?;
+        Ok(self
+            .text_embeddings
+            .get_or_init(|| labels.iter().cloned().zip(embeddings).collect()))
+    }
+}
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Tags images by zero-shot classification against `candidate_labels` with
+/// a CLIP model.
+///
+/// Loads a [`ClipTagger`] with the default batch size; use it directly to
+/// tag several batches with the same model and labels.
+///
+/// # Arguments
+///
+/// * `images` - Images to tag
+/// * `candidate_labels` - Labels to score each image against
+/// * `model_path` - CLIP model (ONNX)
+/// * `min_score` - Minimum zero-shot score (0-1) of a returned tag
+///
+/// # Returns
+///
+/// One list of tags per image, in input order, sorted by score.
+///
+/// # Errors
+///
+/// - `JozinError::UserError` if there are no labels or `min_score` is outside 0-1
+/// - `JozinError::IoError` if the model or an image cannot be read
+/// - `JozinError::NotSupported` until an ONNX runtime is available
+///
+/// # Example
+///
+/// ```no_run
+/// use jozin_core::tags::ml_tag_batch;
+/// use std::path::Path;
+///
+/// let images = [Path::new("/photos/IMG_1234.JPG"), Path::new("/photos/IMG_1235.JPG")];
+/// let tags = ml_tag_batch(&images, &["beach", "mountains", "city"], Path::new("/models/clip.onnx"), 0.3)?;
+/// for (image, tags) in images.iter().zip(tags) {
+///     println!("{}: {:?}", image.display(), tags.iter().map(|t| &t.label).collect::<Vec<_>>());
+/// }
+/// # Ok::<(), jozin_core::JozinError>(())
+/// ```
+pub fn ml_tag_batch(
+    images: &[&Path],
+    candidate_labels: &[&str],
+    model_path: &Path,
+    min_score: f32,
+) -> Result<Vec<Vec<Tag>>> {
+    let config = TagModelConfig {
+        min_score,
+        ..TagModelConfig::new(model_path, candidate_labels.iter().map(|l| l.to_string()).collect())
+    };
+    ClipTagger::new(&config)?.tag_batch(images)
+}
+
+/// Converts an image to CLIP input: the shorter edge is resized to
+/// [`CLIP_INPUT_SIZE`], the centre is cropped square, and the RGB channels
+/// are normalised with CLIP's mean and standard deviation.
+///
+/// # Returns
+///
+/// `3 × 224 × 224` values in channel-major (CHW) order.
+pub fn preprocess_clip(image: &DynamicImage) -> Vec<f32> {
+    let size = CLIP_INPUT_SIZE;
+    let (width, height) = (image.width().max(1), image.height().max(1));
+    let scale = size as f32 / width.min(height) as f32;
+    let (scaled_width, scaled_height) =
+        (((width as f32 * scale).round() as u32).max(size), ((height as f32 * scale).round() as u32).max(size));
+    let resized = image.resize_exact(scaled_width, scaled_height, FilterType::CatmullRom);
+    let cropped = resized
+        .crop_imm((scaled_width - size) / 2, (scaled_height - size) / 2, size, size)
+        .to_rgb8();
+
+    let plane = (size * size) as usize;
+    let mut pixels = vec![0.0; 3 * plane];
+    for (index, pixel) in cropped.pixels().enumerate() {
+        for channel in 0..3 {
+            let value = f32::from(pixel.0[channel]) / 255.0;
+            pixels[channel * plane + index] = (value - CLIP_MEAN[channel]) / CLIP_STD[channel];
+        }
+    }
+    pixels
+}
+
+/// Zero-shot scores of an image embedding against labelled text embeddings.
+///
+/// Scores are the softmax over `labels` of the cosine similarities times
+/// CLIP's logit scale (100), so they sum to 1. Labels without an embedding
+/// (or one of a different length) are left out.
+pub fn zero_shot_scores(
+    image_embedding: &[f32],
+    labels: &[String],
+    text_embeddings: &HashMap<String, Vec<f32>>,
+) -> Vec<(String, f32)> {
+    let norm = |v: &[f32]| v.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let image_norm = norm(image_embedding);
+    let logits: Vec<(String, f32)> = labels
+        .iter()
+        .filter_map(|label| {
+            let text = text_embeddings.get(label).filter(|t| t.len() == image_embedding.len())?;
+            let denominator = image_norm * norm(text);
+            let dot: f32 = image_embedding.iter().zip(text).map(|(a, b)| a * b).sum();
+            let cosine = if denominator == 0.0 { 0.0 } else { dot / denominator };
+            Some((label.clone(), cosine * CLIP_LOGIT_SCALE))
+        })
+        .collect();
+
+    let max = logits.iter().map(|(_, logit)| *logit).fold(f32::NEG_INFINITY, f32::max);
+    let total: f32 = logits.iter().map(|(_, logit)| (logit - max).exp()).sum();
+    logits
+        .into_iter()
+        .map(|(label, logit)| (label, (logit - max).exp() / total))
+        .collect()
+}
+
+/// Whether `label` matches a tag pattern.
+///
+/// A pattern ending in `/**` matches the label before it and all its
+/// descendants (`scene/**` matches `scene`, `scene/outdoor/beach`, but not
+/// `scenery`); any other pattern must equal the label. Case-insensitive.
+pub fn matches_tag_pattern(pattern: &str, label: &str) -> bool {
+    let label = normalise_label(label);
+    match pattern.strip_suffix("/**") {
+        Some(prefix) => {
+            let prefix = normalise_label(prefix);
+            label.eq_ignore_ascii_case(&prefix)
+                || (label.len() > prefix.len()
+                    && label.is_char_boundary(prefix.len())
+                    && label[..prefix.len()].eq_ignore_ascii_case(&prefix)
+                    && label[prefix.len()..].starts_with(TAG_HIERARCHY_SEPARATOR))
+        }
+        None => label.eq_ignore_ascii_case(&normalise_label(pattern)),
+    }
+}
+
+/// Suggests tags for a sidecar without modifying it or writing any files.
+///
+/// Runs the pipelines selected by `mode` and returns tags the sidecar does not
+/// already have (labels compared case-insensitively).
+///
+/// # Arguments
+///
+/// * `sidecar` - Sidecar to suggest tags for (not modified)
+/// * `mode` - Pipelines to run
+/// * `min_score` - Minimum confidence for ML tags (rule tags have no score)
+///
+/// # Returns
+///
+/// ML tags sorted by score (highest first), followed by rule tags sorted
+/// alphabetically.
+///
+/// # Errors
+///
+/// Returns `JozinError::NotSupported` for [`TagMode::Ml`] (no model runtime yet).
+///
+/// # Example
+///
+/// ```no_run
+/// use jozin_core::scan::read_sidecar;
+/// use jozin_core::tags::{suggest_tags, TagMode};
+/// use std::path::Path;
+///
+/// let sidecar = read_sidecar(Path::new("/photos/IMG_1234.JPG"))?;
+/// for tag in suggest_tags(&sidecar, TagMode::Rules, 0.6)? {
+///     println!("{}", tag.label);
+/// }
+/// # Ok::<(), jozin_core::JozinError>(())
+/// ```
+pub fn suggest_tags(sidecar: &Sidecar, mode: TagMode, min_score: f32) -> Result<Vec<Tag>> {
+    let mut ml = match mode {
+        TagMode::Ml => {
+            return Err(JozinError::NotSupported {
+                feature: "ML tagging (no model runtime available yet)".to_string(),
+            })
+        }
+        TagMode::Rules => Vec::new(),
+        // No model runtime is bundled yet, so ML contributes no tags
+        TagMode::Both => Vec::new(),
+    };
+    ml.retain(|tag: &Tag| tag.score.unwrap_or(0.0) >= min_score);
+    ml.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| a.label.cmp(&b.label))
+    });
+
+    let mut rules = rule_tags(sidecar);
+    rules.sort_by(|a, b| a.label.cmp(&b.label));
+
+    let mut suggestions: Vec<Tag> = Vec::new();
+    for tag in ml.into_iter().chain(rules) {
+        let known = sidecar
+            .tags
+            .iter()
+            .chain(suggestions.iter())
+            .any(|t| t.label.eq_ignore_ascii_case(&tag.label));
+        if !known {
+            suggestions.push(tag);
+        }
+    }
+
+    Ok(suggestions)
+}
+
+// ============================================================================
+// Internal Helpers
+// ============================================================================
+
+/// Derives rule-based tags from sidecar metadata (see the module table).
+fn rule_tags(sidecar: &Sidecar) -> Vec<Tag> {
+    let mut labels = Vec::new();
+
+    if let Some(image) = &sidecar.image {
+        if let Some(make) = image.camera_make.as_deref().map(str::trim).filter(|m| !m.is_empty()) {
+            labels.push(format!("camera:{}", make.to_lowercase()));
+        }
+        if let Some(year) = image.datetime_original.as_deref().and_then(|dt| dt.get(..4)) {
+            if year.chars().all(|c| c.is_ascii_digit()) {
+                labels.push(format!("year:{}", year));
+            }
+        }
+        if let (Some(width), Some(height)) = (image.width, image.height) {
+            // EXIF orientations 5-8 rotate the image by 90 degrees
+            let rotated = matches!(image.orientation, Some(5..=8));
+            let (width, height) = if rotated { (height, width) } else { (width, height) };
+            match width.cmp(&height) {
+                Ordering::Greater => labels.push("landscape".to_string()),
+                Ordering::Less => labels.push("portrait".to_string()),
+                Ordering::Equal => {}
+            }
+        }
+        if image.gps_latitude.is_some() && image.gps_longitude.is_some() {
+            labels.push("geotagged".to_string());
+        }
+    }
+
+    if !sidecar.faces.is_empty() {
+        labels.push("people".to_string());
+    }
+    if sidecar.source.live_photo_path.is_some() {
+        labels.push("live-photo".to_string());
+    }
+
+    with_ancestors(labels)
+        .into_iter()
+        .map(|label| Tag {
+            label,
+            score: None,
+            source: TagSource::Rules,
+        })
+        .collect()
+}
+
+/// ML tags of the labels scoring at least `min_score`, highest score first.
+fn tags_from_scores(scores: Vec<(String, f32)>, min_score: f32) -> Vec<Tag> {
+    let mut tags: Vec<Tag> = scores
+        .into_iter()
+        .filter(|(_, score)| *score >= min_score)
+        .map(|(label, score)| Tag {
+            label,
+            score: Some(score),
+            source: TagSource::Ml,
+        })
+        .collect();
+    tags.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| a.label.cmp(&b.label))
+    });
+    tags
+}
+
+/// A loaded CLIP model (ONNX).
+///
+/// Only the model file is checked for now; inference returns
+/// `JozinError::NotSupported` until an ONNX runtime is bundled.
+#[derive(Debug)]
+struct ModelSession {
+    model_path: PathBuf,
+}
+
+impl ModelSession {
+    fn load(model_path: &Path) -> Result<Self> {
+        let metadata = fs::metadata(model_path).map_err(|e| JozinError::IoError {
+            message: format!("Failed to load model {}: {}", model_path.display(), e),
+        })?;
+        if !metadata.is_file() {
+            return Err(JozinError::IoError {
+                message: format!("Failed to load model {}: not a file", model_path.display()),
+            });
+        }
+        Ok(Self { model_path: model_path.to_path_buf() })
+    }
+
+    /// Runs the vision encoder on a batch of preprocessed images.
+    fn encode_images(&self, pixels: &[Vec<f32>]) -> Result<Vec<Vec<f32>>> {
+        let _ = pixels;
+        Err(self.not_supported("CLIP image encoding"))
+    }
+
+    /// Runs the text encoder on the labels.
+    fn encode_text(&self, labels: &[String]) -> Result<Vec<Vec<f32>>> {
+        let _ = labels;
+        Err(self.not_supported("CLIP text encoding"))
+    }
+
+    fn not_supported(&self, task: &str) -> JozinError {
+        JozinError::NotSupported {
+            feature: format!("{} with {} (no ONNX runtime available yet)", task, self.model_path.display()),
+        }
+    }
+}
+
+/// Adds the ancestors of every hierarchical label, each before its first
+/// descendant, without duplicates.
+fn with_ancestors(labels: Vec<String>) -> Vec<String> {
+    let mut expanded: Vec<String> = Vec::with_capacity(labels.len());
+    for label in labels {
+        for level in TagHierarchy::ancestors(&label).into_iter().chain(std::iter::once(label)) {
+            if !expanded.contains(&level) {
+                expanded.push(level);
+            }
+        }
+    }
+    expanded
+}
+
+/// Trims a label and drops empty levels (`/scene//beach/` → `scene/beach`).
+fn normalise_label(label: &str) -> String {
+    label
+        .trim()
+        .split(TAG_HIERARCHY_SEPARATOR)
+        .map(str::trim)
+        .filter(|level| !level.is_empty())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scan::{read_sidecar, scan_file};
+    use crate::ImageInfo;
+    use tempfile::TempDir;
+
+    fn sample_sidecar(dir: &TempDir) -> Sidecar {
+        let photo = dir.path().join("IMG_0001.jpg");
+        std::fs::write(&photo, b"image").unwrap();
+        scan_file(&photo, false).unwrap();
+
+        let mut sidecar = read_sidecar(&photo).unwrap();
+        sidecar.image = Some(ImageInfo {
+            width: Some(3024),
+            height: Some(4032),
+            camera_make: Some("Apple".to_string()),
+            datetime_original: Some("2020-06-15T10:30:00Z".to_string()),
+            gps_latitude: Some(37.77),
+            gps_longitude: Some(-122.42),
+            ..ImageInfo::default()
+        });
+        sidecar.tags.push(Tag {
+            label: "Geotagged".to_string(),
+            score: None,
+            source: TagSource::User,
+        });
+        sidecar
+    }
+
+    #[test]
+    fn test_suggest_tags_rules_sorted_and_deduplicated() {
+        let temp_dir = TempDir::new().unwrap();
+        let sidecar = sample_sidecar(&temp_dir);
+
+        let tags = suggest_tags(&sidecar, TagMode::Rules, 0.6).unwrap();
+        let labels: Vec<&str> = tags.iter().map(|t| t.label.as_str()).collect();
+        // "geotagged" is already a user tag
+        assert_eq!(labels, vec!["camera:apple", "portrait", "year:2020"]);
+        assert!(tags.iter().all(|t| t.source == TagSource::Rules));
+
+        assert!(matches!(
+            suggest_tags(&sidecar, TagMode::Ml, 0.6),
+            Err(JozinError::NotSupported { .. })
+        ));
+    }
+
+    #[test]
+    fn test_suggest_tags_has_no_side_effects() {
+        let temp_dir = TempDir::new().unwrap();
+        let sidecar = sample_sidecar(&temp_dir);
+        let sidecar_path = temp_dir.path().join("IMG_0001.jpg.json");
+        let on_disk = std::fs::read_to_string(&sidecar_path).unwrap();
+        let before = serde_json::to_value(&sidecar).unwrap();
+
+        let first = serde_json::to_value(suggest_tags(&sidecar, TagMode::Both, 0.6).unwrap()).unwrap();
+        let second = serde_json::to_value(suggest_tags(&sidecar, TagMode::Both, 0.6).unwrap()).unwrap();
+
+        assert_eq!(first, second);
+        assert_eq!(serde_json::to_value(&sidecar).unwrap(), before);
+        assert_eq!(std::fs::read_to_string(&sidecar_path).unwrap(), on_disk);
+    }
+
+    #[test]
+    fn test_preprocess_clip_crops_and_normalises() {
+        // A wide image: left half black, right half white; the centre crop keeps both
+        let image = DynamicImage::ImageRgb8(image::RgbImage::from_fn(448, 224, |x, _| {
+            image::Rgb(if x < 224 { [0, 0, 0] } else { [255, 255, 255] })
+        }));
+        let pixels = preprocess_clip(&image);
+        let plane = (CLIP_INPUT_SIZE * CLIP_INPUT_SIZE) as usize;
+        assert_eq!(pixels.len(), 3 * plane);
+
+        let red_black = pixels[0];
+        let red_white = pixels[CLIP_INPUT_SIZE as usize - 1];
+        assert!((red_black - (-CLIP_MEAN[0] / CLIP_STD[0])).abs() < 1e-4);
+        assert!((red_white - (1.0 - CLIP_MEAN[0]) / CLIP_STD[0]).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_zero_shot_scores_and_tags() {
+        let labels = vec!["beach".to_string(), "city".to_string(), "dog".to_string()];
+        let embeddings: HashMap<String, Vec<f32>> = [
+            ("beach".to_string(), vec![1.0, 0.0]),
+            ("city".to_string(), vec![0.6, 0.8]),
+            ("dog".to_string(), vec![0.0, 1.0, 0.0]),
+        ]
+        .into_iter()
+        .collect();
+
+        // "dog" has a mismatched embedding length and is left out
+        let scores = zero_shot_scores(&[2.0, 0.0], &labels, &embeddings);
+        assert_eq!(scores.len(), 2);
+        assert!((scores.iter().map(|(_, s)| s).sum::<f32>() - 1.0).abs() < 1e-5);
+
+        let tags = tags_from_scores(scores, 0.2);
+        assert_eq!(tags.len(), 1);
+        assert_eq!(tags[0].label, "beach");
+        assert_eq!(tags[0].source, TagSource::Ml);
+        assert!(tags[0].score.unwrap() > 0.99);
+    }
+
+    #[test]
+    fn test_ml_tag_batch_validates_model_and_labels() {
+        let temp_dir = TempDir::new().unwrap();
+        let photo = temp_dir.path().join("photo.png");
+        DynamicImage::new_rgb8(8, 8).save(&photo).unwrap();
+        let model = temp_dir.path().join("clip.onnx");
+        std::fs::write(&model, b"onnx").unwrap();
+        let images = [photo.as_path()];
+
+        assert!(matches!(
+            ml_tag_batch(&images, &["beach"], &temp_dir.path().join("missing.onnx"), 0.2),
+            Err(JozinError::IoError { .. })
+        ));
+        assert!(matches!(ml_tag_batch(&images, &[" "], &model, 0.2), Err(JozinError::UserError { .. })));
+        assert!(matches!(ml_tag_batch(&images, &["beach"], &model, 1.5), Err(JozinError::UserError { .. })));
+        assert!(matches!(
+            ml_tag_batch(&[temp_dir.path().join("missing.png").as_path()], &["beach"], &model, 0.2),
+            Err(JozinError::IoError { .. })
+        ));
+        assert!(matches!(ml_tag_batch(&images, &["beach"], &model, 0.2), Err(JozinError::NotSupported { .. })));
+        assert!(ml_tag_batch(&[], &["beach"], &model, 0.2).unwrap().is_empty());
+    }
+
+    fn user_tag(label: &str) -> Tag {
+        Tag {
+            label: label.to_string(),
+            score: None,
+            source: TagSource::User,
+        }
+    }
+
+    #[test]
+    fn test_tag_hierarchy_build_and_ancestors() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut a = sample_sidecar(&temp_dir);
+        a.tags = vec![user_tag("scene/outdoor/beach"), user_tag("scene/indoor")];
+        let mut b = a.clone();
+        b.tags = vec![user_tag("scene/outdoor/beach"), user_tag("vacation")];
+
+        let hierarchy = TagHierarchy::build(&[a, b]);
+        assert_eq!(hierarchy.root.count, 4);
+        let labels: Vec<&str> = hierarchy.root.children.iter().map(|n| n.label.as_str()).collect();
+        assert_eq!(labels, ["scene", "vacation"]);
+        let scene = &hierarchy.root.children[0];
+        assert_eq!(scene.count, 3);
+        assert_eq!(scene.children[1].label, "scene/outdoor");
+        assert_eq!(scene.children[1].children[0].name(), "beach");
+        assert_eq!(scene.children[1].children[0].count, 2);
+
+        assert_eq!(TagHierarchy::ancestors("scene/outdoor/beach"), ["scene", "scene/outdoor"]);
+        assert!(TagHierarchy::ancestors("beach").is_empty());
+        assert_eq!(with_ancestors(vec!["a/b".to_string(), "a/c".to_string()]), ["a", "a/b", "a/c"]);
+    }
+
+    #[test]
+    fn test_matches_tag_pattern() {
+        assert!(matches_tag_pattern("scene/**", "scene"));
+        assert!(matches_tag_pattern("scene/**", "Scene/Outdoor/beach"));
+        assert!(!matches_tag_pattern("scene/**", "scenery"));
+        assert!(matches_tag_pattern("scene/outdoor", "scene/outdoor"));
+        assert!(!matches_tag_pattern("scene/outdoor", "scene/outdoor/beach"));
+    }
+}
@@ -8,17 +8,1349 @@
 //!
 //! ## Status
 //!
-//! This is a **Phase 2+ stub module**. The actual implementation is planned
-//! but not yet available. This file exists to satisfy the feature flag
-//! structure and prevent compilation warnings.
+//! Still images are processed by [`generate_thumbnails()`]. Video keyframe
+//! thumbnails are available through [`generate_video_thumbnail()`] when the
+//! `videos` feature is enabled.
+//!
+//! ## Embedded EXIF Thumbnails
+//!
+//! Most camera JPEGs carry a ~160×120 JPEG in their EXIF (IFD1,
+//! `JPEGInterchangeFormat`/`JPEGInterchangeFormatLength`).
+//! [`extract_exif_thumbnail()`] returns its bytes, and
+//! [`generate_thumbnails()`] uses it for sizes it covers, so small previews
+//! don't decode the full image. An embedded thumbnail of exactly the
+//! requested size is copied as is; such thumbnails are marked with
+//! [`ThumbnailInfo::from_exif`].
+//!
+//! ## JPEG XL
+//!
+//! [`ThumbnailFormat::Jxl`] and [`ThumbnailFormat::JxlFromJpeg`] are part of
+//! the format list so sidecars and the CLI can name them, but no JPEG XL
+//! encoder is bundled yet: [`generate_thumbnail_jxl()`] and
+//! [`transcode_jpeg_to_jxl()`] return `JozinError::NotSupported`.
+//!
+//! ## Dimensions
+//!
+//! [`thumbnail_dimensions()`] computes the size of a thumbnail (longest edge
+//! fitted, aspect ratio kept, never upscaled) without decoding anything, so
+//! a gallery can lay out its grid before the thumbnails exist. The writers
+//! in this module resize to exactly these dimensions.
+//!
+//! ## Dominant Colour
+//!
+//! With [`ThumbnailWriteOptions::dominant_color`], each thumbnail records the
+//! centroid of its RGB pixels (k-means with k = 1, i.e. the mean colour) as
+//! a CSS hex colour in [`ThumbnailInfo::dominant_color`], for palettes and
+//! colour-based search (see [`ThumbnailInfo::dominant_color_distance()`]).
+//! It is computed from the resized pixels, so it costs one pass over the
+//! thumbnail.
+//!
+//! ## HDR Tone Mapping
+//!
+//! HDR captures (gain map JPEGs/HEICs, see [`crate::exif::detect_hdr()`])
+//! look washed out when their pixels are resized as is. For them,
+//! [`generate_thumbnails()`] runs the [`HdrTonemap`] operator over the
+//! decoded image first (Reinhard by default; `jozin thumbs --hdr-tonemap`)
+//! and records it in [`ThumbnailInfo::tonemap`]. Tone mapped thumbnails are
+//! always made from the full image, never copied from the embedded EXIF
+//! thumbnail. `verify` warns about HDR thumbnails without tone mapping.
+//!
+//! ## Animated Thumbnails
+//!
+//! Multi-frame images get a small looping GIF preview for gallery hover
+//! effects (`jozin thumbs --animated`). [`generate_animated_gif_thumbnail()`]
+//! samples at most `max_frames` frames evenly (every
+//! `ceil(total / max_frames)`-th frame) from an animated GIF, quantises each
+//! to its own 256-colour palette and plays them at 100 ms per frame. With a
+//! byte budget ([`generate_animated_gif_thumbnail_within()`], `--max-gif-size`)
+//! frames are dropped and then the size shrunk until the GIF fits. HEIC
+//! bursts (Live Photos) need the `heic` feature, which has no HEIF decoder
+//! bundled yet, so they return `JozinError::NotSupported` for now.
+//!
+//! ## Naming
+//!
+//! Thumbnails are written as `<stem>_<size>.<ext>` (e.g., `IMG_1234_256.jpg`),
+//! which is the pattern the cleanup module recognises as a thumbnail.
 //!
 //! ## Future Implementation
 //!
 //! When implemented, this module will expose:
-//! - `generate_thumbnails()` - Generate thumbnails for an image
-//! - `resize_image()` - Resize with quality preservation
 //! - `convert_format()` - Convert between JPEG and WebP
 
-// Placeholder to prevent "unused module" warnings
-#[allow(dead_code)]
-const _PLACEHOLDER: &str = "Phase 2+ implementation pending";
+use crate::exif::detect_hdr;
+use crate::util::atomic_rename::atomic_rename;
+use crate::util::image_format::{detect_format_from_magic_bytes, ImageFormat};
+use crate::util::mime::mime_to_extensions;
+use crate::util::orientation::apply_orientation;
+use crate::{JozinError, Result, ThumbnailInfo};
+use exif::{In, Tag};
+use image::{AnimationDecoder, DynamicImage, RgbaImage};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+// ============================================================================
+// Public Types
+// ============================================================================
+
+/// Options for [`write_thumbnails_with()`].
+///
+/// # Fields
+///
+/// - `dominant_color`: Record each thumbnail's dominant colour (see
+///   [`dominant_color()`])
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ThumbnailWriteOptions {
+    pub dominant_color: bool,
+}
+
+/// Output format for generated thumbnails.
+///
+/// Serializes to the lowercase file extension ("jpg", "webp", "jxl"), which is
+/// also the value stored in [`ThumbnailInfo::format`]; `JxlFromJpeg`
+/// serializes as "jxl-from-jpeg" but its thumbnails are recorded as "jxl".
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ThumbnailFormat {
+    /// JPEG (lossy, honours the quality setting)
+    Jpg,
+    /// WebP (lossless; the quality setting is ignored)
+    Webp,
+    /// JPEG XL (lossy, honours the quality setting); no encoder bundled yet
+    Jxl,
+    /// JPEG XL, losslessly transcoded when the source is a JPEG kept at its
+    /// original size (decodes bit-identical to the source); no encoder
+    /// bundled yet
+    #[serde(rename = "jxl-from-jpeg")]
+    JxlFromJpeg,
+}
+
+impl ThumbnailFormat {
+    /// Returns the file extension (without dot) used for this format.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ThumbnailFormat::Jpg => "jpg",
+            ThumbnailFormat::Webp => "webp",
+            ThumbnailFormat::Jxl | ThumbnailFormat::JxlFromJpeg => "jxl",
+        }
+    }
+
+    /// Returns the MIME type stored in [`ThumbnailInfo::format`] from schema
+    /// `2.0.0` on.
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            ThumbnailFormat::Jpg => "image/jpeg",
+            ThumbnailFormat::Webp => "image/webp",
+            ThumbnailFormat::Jxl | ThumbnailFormat::JxlFromJpeg => "image/jxl",
+        }
+    }
+
+    /// Parses a format from a file extension or [`ThumbnailInfo::format`] value
+    /// (case-insensitive; `"jpeg"` is accepted as JPEG, and MIME types as
+    /// written by schema `2.0.0` are accepted too).
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        let extension = match mime_to_extensions(extension).first() {
+            Some(preferred) => preferred,
+            None => extension,
+        };
+        match extension.to_ascii_lowercase().as_str() {
+            "jpg" | "jpeg" => Some(ThumbnailFormat::Jpg),
+            "webp" => Some(ThumbnailFormat::Webp),
+            "jxl" => Some(ThumbnailFormat::Jxl),
+            _ => None,
+        }
+    }
+}
+
+/// Tone mapping operator applied to HDR sources before resizing.
+///
+/// Serializes to lowercase ("none", "reinhard", "filmic"), the value of
+/// `jozin thumbs --hdr-tonemap` and of [`ThumbnailInfo::tonemap`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HdrTonemap {
+    /// No tone mapping (HDR thumbnails may look washed out)
+    None,
+    /// Reinhard `x / (1 + x)`, rescaled so white stays white
+    #[default]
+    Reinhard,
+    /// Hable's filmic curve (Uncharted 2): softer shoulder, deeper shadows
+    Filmic,
+}
+
+impl HdrTonemap {
+    /// Returns the name stored in [`ThumbnailInfo::tonemap`].
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HdrTonemap::None => "none",
+            HdrTonemap::Reinhard => "reinhard",
+            HdrTonemap::Filmic => "filmic",
+        }
+    }
+
+    /// Parses an operator name (case-insensitive).
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "none" => Some(HdrTonemap::None),
+            "reinhard" => Some(HdrTonemap::Reinhard),
+            "filmic" => Some(HdrTonemap::Filmic),
+            _ => None,
+        }
+    }
+}
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+/// JPEG quality used when regenerating thumbnails (matches the CLI default).
+pub const DEFAULT_JPEG_QUALITY: u8 = 85;
+
+/// Exposure applied before the Reinhard curve; the SDR base of an HDR capture
+/// is brightened by this factor in linear light before highlights are rolled off.
+const REINHARD_EXPOSURE: f32 = 2.0;
+
+/// Linear white point of Hable's filmic curve; the 0-1 input range is
+/// stretched to it so white stays white.
+const FILMIC_WHITE: f32 = 11.2;
+
+/// Default number of frames in an animated thumbnail.
+pub const DEFAULT_ANIMATED_GIF_FRAMES: u32 = 10;
+
+/// Delay between animated thumbnail frames, in GIF units of 10 ms (100 ms).
+pub const ANIMATED_GIF_FRAME_DELAY: u16 = 10;
+
+/// Smallest long edge an animated thumbnail is shrunk to for a byte budget.
+const MIN_ANIMATED_GIF_SIZE: u32 = 32;
+
+/// NeuQuant sampling speed for palette quantisation (1 = best, 30 = fastest).
+const GIF_QUANTIZE_SPEED: i32 = 10;
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Default point in the video (seconds) to sample the thumbnail frame from.
+#[cfg(feature = "videos")]
+pub const DEFAULT_VIDEO_THUMBNAIL_TIME_SEC: f32 = 1.0;
+
+/// Extracts a keyframe from a video and writes it as thumbnails in each requested size.
+///
+/// Samples the frame at [`DEFAULT_VIDEO_THUMBNAIL_TIME_SEC`]. Use
+/// [`generate_video_thumbnail_at()`] to choose a different point in the video.
+///
+/// # Errors
+///
+/// - `JozinError::NotSupported` if `ffmpeg` is not in PATH
+/// - `JozinError::IoError` if the video cannot be read or thumbnails cannot be written
+/// - `JozinError::ValidationError` if no frame could be decoded from the video
+///
+/// # Example
+///
+/// ```no_run
+/// use jozin_core::thumbs::{generate_video_thumbnail, ThumbnailFormat};
+/// use std::path::Path;
+///
+/// let thumbs = generate_video_thumbnail(
+///     Path::new("/videos/IMG_1234.MOV"),
+///     &[256, 512],
+///     ThumbnailFormat::Jpg,
+///     85,
+///     None, // write next to the video
+/// )?;
+/// println!("Generated {} thumbnails", thumbs.len());
+/// # Ok::<(), jozin_core::JozinError>(())
+/// ```
+#[cfg(feature = "videos")]
+pub fn generate_video_thumbnail(
+    video_path: &Path,
+    sizes: &[u32],
+    format: ThumbnailFormat,
+    quality: u8,
+    output_dir: Option<&Path>,
+) -> Result<Vec<ThumbnailInfo>> {
+    generate_video_thumbnail_at(
+        video_path,
+        DEFAULT_VIDEO_THUMBNAIL_TIME_SEC,
+        sizes,
+        format,
+        quality,
+        output_dir,
+    )
+}
+
+/// Extracts the frame at `time_sec` from a video and writes it as thumbnails.
+///
+/// `ffmpeg` is invoked as a subprocess (`-ss <time> -vframes 1`) and the frame is
+/// piped back as JPEG, then resized with the `image` crate. If the video has no
+/// frame at `time_sec` (e.g., it is shorter than that), the first frame (0s) is
+/// used instead; the actual timestamp is recorded in
+/// [`ThumbnailInfo::source_timestamp_sec`].
+///
+/// # Arguments
+///
+/// * `video_path` - Video file to sample
+/// * `time_sec` - Point in the video to sample (seconds, >= 0)
+/// * `sizes` - Longest-edge sizes in pixels (e.g., `[256, 512]`)
+/// * `format` - Output format
+/// * `quality` - JPEG quality 1-100 (ignored for WebP)
+/// * `output_dir` - Directory for thumbnails (default: next to the video)
+#[cfg(feature = "videos")]
+pub fn generate_video_thumbnail_at(
+    video_path: &Path,
+    time_sec: f32,
+    sizes: &[u32],
+    format: ThumbnailFormat,
+    quality: u8,
+    output_dir: Option<&Path>,
+) -> Result<Vec<ThumbnailInfo>> {
+    if !video_path.is_file() {
+        return Err(JozinError::IoError {
+            message: format!("File not found: {}", video_path.display()),
+        });
+    }
+    if !time_sec.is_finite() || time_sec < 0.0 {
+        return Err(JozinError::UserError {
+            message: format!("video thumbnail time must be >= 0, got {}", time_sec),
+        });
+    }
+
+    ensure_ffmpeg_available()?;
+
+    // Try the requested timestamp first, then fall back to the very first frame
+    let (frame, sampled_at) = match extract_frame(video_path, time_sec)? {
+        Some(frame) => (frame, time_sec),
+        None if time_sec > 0.0 => match extract_frame(video_path, 0.0)? {
+            Some(frame) => (frame, 0.0),
+            None => return Err(no_frame_error(video_path)),
+        },
+        None => return Err(no_frame_error(video_path)),
+    };
+
+    let image = image::load_from_memory(&frame).map_err(|e| JozinError::ValidationError {
+        message: format!("Failed to decode frame from {}: {}", video_path.display(), e),
+    })?;
+
+    let out_dir = match output_dir {
+        Some(dir) => dir.to_path_buf(),
+        None => video_path.parent().map(Path::to_path_buf).unwrap_or_default(),
+    };
+    let stem = video_path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| "video".to_string());
+
+    let mut thumbnails = write_thumbnails(&image, &stem, sizes, format, quality, &out_dir)?;
+    for thumb in &mut thumbnails {
+        thumb.source_timestamp_sec = Some(sampled_at);
+    }
+
+    Ok(thumbnails)
+}
+
+/// Writes thumbnails of a still image in each requested size.
+///
+/// Sizes covered by the thumbnail embedded in the image's EXIF (see
+/// [`extract_exif_thumbnail()`]) are made from it; the full image is only
+/// decoded, once, for larger sizes or when there is no embedded thumbnail.
+/// An embedded JPEG whose longest edge is exactly `size` is copied without
+/// re-encoding if `format` is JPEG and no rotation is needed.
+///
+/// HDR images (see [`crate::exif::detect_hdr()`]) are tone mapped with
+/// `tonemap` first (see [`tonemap_image()`]); unless it is
+/// [`HdrTonemap::None`], all their thumbnails come from the full image.
+///
+/// # Arguments
+///
+/// * `image_path` - Image to make thumbnails of
+/// * `sizes` - Longest-edge sizes in pixels (e.g., `[128, 512]`)
+/// * `format` - Output format
+/// * `quality` - JPEG quality 1-100 (ignored for WebP)
+/// * `output_dir` - Directory for thumbnails (default: next to the image)
+/// * `orientation` - EXIF orientation of the image, applied before resizing
+/// * `tonemap` - Operator for HDR images (ignored for SDR images)
+///
+/// # Errors
+///
+/// - `JozinError::IoError` if the image cannot be read or thumbnails cannot be written
+/// - `JozinError::ValidationError` if the image cannot be decoded
+/// - `JozinError::InternalError` if encoding fails
+///
+/// # Example
+///
+/// ```no_run
+/// use jozin_core::thumbs::{generate_thumbnails, HdrTonemap, ThumbnailFormat, DEFAULT_JPEG_QUALITY};
+/// use std::path::Path;
+///
+/// let thumbs = generate_thumbnails(
+///     Path::new("/photos/IMG_1234.JPG"),
+///     &[128, 512],
+///     ThumbnailFormat::Jpg,
+///     DEFAULT_JPEG_QUALITY,
+///     None, // write next to the image
+///     Some(1),
+///     HdrTonemap::Reinhard,
+/// )?;
+/// println!("{} of {} from EXIF", thumbs.iter().filter(|t| t.from_exif).count(), thumbs.len());
+/// # Ok::<(), jozin_core::JozinError>(())
+/// ```
+pub fn generate_thumbnails(
+    image_path: &Path,
+    sizes: &[u32],
+    format: ThumbnailFormat,
+    quality: u8,
+    output_dir: Option<&Path>,
+    orientation: Option<u8>,
+    tonemap: HdrTonemap,
+) -> Result<Vec<ThumbnailInfo>> {
+    if !image_path.is_file() {
+        return Err(JozinError::IoError {
+            message: format!("File not found: {}", image_path.display()),
+        });
+    }
+
+    let tonemap = match detect_hdr(image_path)? {
+        Some(true) if tonemap != HdrTonemap::None => Some(tonemap),
+        _ => None,
+    };
+
+    // An embedded thumbnail that doesn't decode is treated as absent
+    let embedded = match tonemap {
+        Some(_) => None,
+        None => extract_exif_thumbnail(image_path)?
+            .and_then(|jpeg| image::load_from_memory(&jpeg).ok().map(|image| (jpeg, image))),
+    };
+    let mut full_image = None;
+
+    let out_dir = match output_dir {
+        Some(dir) => dir.to_path_buf(),
+        None => image_path.parent().map(Path::to_path_buf).unwrap_or_default(),
+    };
+    let stem = image_path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| "image".to_string());
+
+    let mut thumbnails = Vec::with_capacity(sizes.len());
+    for &size in sizes {
+        let path = out_dir.join(format!("{}_{}.{}", stem, size, format.extension()));
+        let from_exif = match &embedded {
+            Some((jpeg, thumb)) if thumb.width().max(thumb.height()) >= size => {
+                let upright = orientation.is_none_or(|o| o == 1);
+                if format == ThumbnailFormat::Jpg && upright && thumb.width().max(thumb.height()) == size {
+                    write_bytes_atomic(jpeg, &path)?;
+                } else {
+                    let oriented = apply_orientation(thumb.clone(), orientation);
+                    write_image_atomic(&resize_to(&oriented, size), &path, format, quality)?;
+                }
+                true
+            }
+            _ => {
+                if full_image.is_none() {
+                    let image = image::open(image_path).map_err(|e| JozinError::ValidationError {
+                        message: format!("Failed to decode {}: {}", image_path.display(), e),
+                    })?;
+                    let image = match tonemap {
+                        Some(operator) => tonemap_image(&image, operator),
+                        None => image,
+                    };
+                    full_image = Some(apply_orientation(image, orientation));
+                }
+                let image = full_image.as_ref().expect("decoded above");
+                write_image_atomic(&resize_to(image, size), &path, format, quality)?;
+                false
+            }
+        };
+
+        thumbnails.push(ThumbnailInfo {
+            path: path.display().to_string(),
+            size,
+            format: format.extension().to_string(),
+            source_timestamp_sec: None,
+            dominant_color: None,
+            from_exif,
+            tonemap: tonemap.map(|operator| operator.as_str().to_string()),
+        });
+    }
+
+    Ok(thumbnails)
+}
+
+/// Writes a looping GIF preview of a multi-frame image to `output_path`.
+///
+/// See [`generate_animated_gif_thumbnail_within()`]; no byte budget.
+///
+/// # Example
+///
+/// ```no_run
+/// use jozin_core::thumbs::{generate_animated_gif_thumbnail, DEFAULT_ANIMATED_GIF_FRAMES};
+/// use std::path::Path;
+///
+/// let thumb = generate_animated_gif_thumbnail(
+///     Path::new("/photos/party.gif"),
+///     DEFAULT_ANIMATED_GIF_FRAMES,
+///     256,
+///     Path::new("/photos/party_256.gif"),
+/// )?;
+/// assert_eq!(thumb.format, "gif");
+/// # Ok::<(), jozin_core::JozinError>(())
+/// ```
+pub fn generate_animated_gif_thumbnail(
+    image_path: &Path,
+    max_frames: u32,
+    size: u32,
+    output_path: &Path,
+) -> Result<ThumbnailInfo> {
+    generate_animated_gif_thumbnail_within(image_path, max_frames, size, None, output_path)
+}
+
+/// Writes a looping GIF preview of a multi-frame image to `output_path`,
+/// at most `max_bytes` large.
+///
+/// At most `max_frames` frames are sampled evenly and resized to `size`
+/// (never upscaled). While the GIF exceeds `max_bytes`, every other frame is
+/// dropped down to two frames, then the size is shrunk by a quarter down to
+/// 32 px. [`ThumbnailInfo::size`] is the size finally used.
+///
+/// # Errors
+///
+/// - `JozinError::ValidationError` if `max_frames` or `size` is 0, or the
+///   image cannot be decoded
+/// - `JozinError::NotSupported` if the image is not an animated GIF or HEIC
+///   burst, or HEIC support is missing (see the module docs)
+/// - `JozinError::UserError` if no GIF fits in `max_bytes`
+/// - `JozinError::IoError` if the image cannot be read or the GIF written
+pub fn generate_animated_gif_thumbnail_within(
+    image_path: &Path,
+    max_frames: u32,
+    size: u32,
+    max_bytes: Option<u64>,
+    output_path: &Path,
+) -> Result<ThumbnailInfo> {
+    if max_frames == 0 || size == 0 {
+        return Err(JozinError::ValidationError {
+            message: "Animated thumbnails need at least one frame and a size above 0".to_string(),
+        });
+    }
+    if !image_path.is_file() {
+        return Err(JozinError::IoError {
+            message: format!("File not found: {}", image_path.display()),
+        });
+    }
+
+    let frames = match detect_format_from_magic_bytes(image_path)? {
+        Some(ImageFormat::Gif) => sample_gif_frames(image_path, max_frames)?,
+        Some(ImageFormat::Heic) => heic_burst_frames(image_path, max_frames)?,
+        _ => {
+            return Err(JozinError::NotSupported {
+                feature: format!("animated thumbnails of {} (only GIF and HEIC bursts)", image_path.display()),
+            })
+        }
+    };
+    let Some(first) = frames.first() else {
+        return Err(JozinError::ValidationError {
+            message: format!("No frames in {}", image_path.display()),
+        });
+    };
+
+    let mut frames: Vec<&RgbaImage> = frames.iter().collect();
+    let mut size = size.min(first.width().max(first.height()));
+    let bytes = loop {
+        let bytes = encode_animated_gif(&frames, size)?;
+        match max_bytes {
+            Some(max_bytes) if bytes.len() as u64 > max_bytes => {}
+            _ => break bytes,
+        }
+        if frames.len() > 2 {
+            frames = frames.into_iter().step_by(2).collect();
+        } else if size > MIN_ANIMATED_GIF_SIZE {
+            size = (size * 3 / 4).max(MIN_ANIMATED_GIF_SIZE);
+        } else {
+            return Err(JozinError::UserError {
+                message: format!(
+                    "Animated thumbnail of {} does not fit in {} bytes; raise --max-gif-size",
+                    image_path.display(),
+                    max_bytes.unwrap_or_default()
+                ),
+            });
+        }
+    };
+    write_bytes_atomic(&bytes, output_path)?;
+
+    Ok(ThumbnailInfo {
+        path: output_path.display().to_string(),
+        size,
+        format: "gif".to_string(),
+        source_timestamp_sec: None,
+        dominant_color: None,
+        from_exif: false,
+        tonemap: None,
+    })
+}
+
+/// Returns the JPEG thumbnail embedded in an image's EXIF (IFD1), as stored.
+///
+/// Reads the `JPEGInterchangeFormat` (offset) and
+/// `JPEGInterchangeFormatLength` fields; nothing is decoded.
+///
+/// # Returns
+///
+/// `Ok(None)` if the image has no EXIF, no embedded thumbnail, or the
+/// fields point outside the EXIF data or at something that is not a JPEG.
+///
+/// # Errors
+///
+/// Returns `JozinError::IoError` if the image cannot be opened.
+pub fn extract_exif_thumbnail(image_path: &Path) -> Result<Option<Vec<u8>>> {
+    let file = File::open(image_path)?;
+    let Ok(exif) = exif::Reader::new().read_from_container(&mut BufReader::new(file)) else {
+        return Ok(None);
+    };
+    let field = |tag| exif.get_field(tag, In::THUMBNAIL).and_then(|f| f.value.get_uint(0));
+    let (Some(offset), Some(length)) = (field(Tag::JPEGInterchangeFormat), field(Tag::JPEGInterchangeFormatLength)) else {
+        return Ok(None);
+    };
+
+    // The offset is relative to the TIFF header, i.e. the start of `buf()`
+    let (offset, length) = (offset as usize, length as usize);
+    let jpeg = exif.buf().get(offset..offset.saturating_add(length));
+    Ok(jpeg.filter(|bytes| bytes.starts_with(&[0xFF, 0xD8])).map(<[u8]>::to_vec))
+}
+
+/// Returns the path a thumbnail of `source` at `size` is written to when no
+/// output directory is given (`<dir>/<stem>_<size>.<ext>`).
+pub fn thumbnail_path(source: &Path, size: u32, format: ThumbnailFormat) -> PathBuf {
+    let stem = source
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    source.with_file_name(format!("{}_{}.{}", stem, size, format.extension()))
+}
+
+/// Returns the dimensions of a thumbnail of an image at `size`: the longest
+/// edge becomes `size` and the aspect ratio is kept (the shorter edge is
+/// rounded, and at least 1).
+///
+/// Images whose longest edge already fits in `size` keep their dimensions
+/// (never upscaled). Without known dimensions (`0`), `(size, size)` is
+/// returned, e.g. to reserve a square cell in a gallery grid.
+///
+/// # Example
+///
+/// ```
+/// use jozin_core::thumbs::thumbnail_dimensions;
+///
+/// assert_eq!(thumbnail_dimensions(256, 4000, 3000), (256, 192));
+/// assert_eq!(thumbnail_dimensions(256, 3000, 4000), (192, 256));
+/// assert_eq!(thumbnail_dimensions(256, 100, 50), (100, 50));
+/// ```
+pub fn thumbnail_dimensions(size: u32, image_width: u32, image_height: u32) -> (u32, u32) {
+    if image_width == 0 || image_height == 0 {
+        return (size, size);
+    }
+    let (long, short) = (image_width.max(image_height), image_width.min(image_height));
+    if long <= size {
+        return (image_width, image_height);
+    }
+    let scaled_short = ((u64::from(short) * u64::from(size) + u64::from(long) / 2) / u64::from(long)).max(1) as u32;
+    if image_width >= image_height {
+        (size, scaled_short)
+    } else {
+        (scaled_short, size)
+    }
+}
+
+/// Resizes a decoded image to each size and writes `<stem>_<size>.<ext>` into `out_dir`.
+///
+/// Images are never upscaled: a size larger than the source keeps the source
+/// dimensions (see [`thumbnail_dimensions()`]). Each file is written
+/// atomically (`.tmp` → fsync → rename).
+///
+/// # Errors
+///
+/// - `JozinError::IoError` if a thumbnail cannot be written
+/// - `JozinError::InternalError` if encoding fails
+pub fn write_thumbnails(
+    image: &DynamicImage,
+    stem: &str,
+    sizes: &[u32],
+    format: ThumbnailFormat,
+    quality: u8,
+    out_dir: &Path,
+) -> Result<Vec<ThumbnailInfo>> {
+    write_thumbnails_with(image, stem, sizes, format, quality, out_dir, &ThumbnailWriteOptions::default())
+}
+
+/// Like [`write_thumbnails()`], with the extras selected in `options`.
+///
+/// # Errors
+///
+/// Same as [`write_thumbnails()`].
+pub fn write_thumbnails_with(
+    image: &DynamicImage,
+    stem: &str,
+    sizes: &[u32],
+    format: ThumbnailFormat,
+    quality: u8,
+    out_dir: &Path,
+    options: &ThumbnailWriteOptions,
+) -> Result<Vec<ThumbnailInfo>> {
+    let mut thumbnails = Vec::with_capacity(sizes.len());
+
+    for &size in sizes {
+        let resized = resize_to(image, size);
+
+        let path = out_dir.join(format!("{}_{}.{}", stem, size, format.extension()));
+        write_image_atomic(&resized, &path, format, quality)?;
+
+        thumbnails.push(ThumbnailInfo {
+            path: path.display().to_string(),
+            size,
+            format: format.extension().to_string(),
+            source_timestamp_sec: None,
+            dominant_color: options.dominant_color.then(|| dominant_color(&resized)),
+            from_exif: false,
+            tonemap: None,
+        });
+    }
+
+    Ok(thumbnails)
+}
+
+/// Dominant colour of an image as `"#RRGGBB"`: the centroid of its RGB
+/// pixels (k-means with k = 1), rounded per channel. Alpha is ignored.
+///
+/// # Example
+///
+/// ```
+/// use image::{DynamicImage, Rgb, RgbImage};
+/// use jozin_core::thumbs::dominant_color;
+///
+/// let red = DynamicImage::ImageRgb8(RgbImage::from_pixel(4, 4, Rgb([255, 0, 0])));
+/// assert_eq!(dominant_color(&red), "#FF0000");
+/// ```
+pub fn dominant_color(image: &DynamicImage) -> String {
+    let rgb = image.to_rgb8();
+    let mut sums = [0u64; 3];
+    for pixel in rgb.pixels() {
+        for (sum, channel) in sums.iter_mut().zip(pixel.0) {
+            *sum += u64::from(channel);
+        }
+    }
+    let count = (u64::from(rgb.width()) * u64::from(rgb.height())).max(1);
+    let [r, g, b] = sums.map(|sum| ((sum + count / 2) / count) as u8);
+    format!("#{:02X}{:02X}{:02X}", r, g, b)
+}
+
+/// Tone maps an HDR image for SDR display.
+///
+/// Pixels are converted to linear light, passed through the operator per
+/// channel and converted back to sRGB; both curves map black to black and
+/// white to white. [`HdrTonemap::None`] returns the image unchanged. Alpha
+/// is dropped (the result is 8-bit RGB).
+///
+/// # Example
+///
+/// ```
+/// use image::{DynamicImage, Rgb, RgbImage};
+/// use jozin_core::thumbs::{tonemap_image, HdrTonemap};
+///
+/// let grey = DynamicImage::ImageRgb8(RgbImage::from_pixel(2, 2, Rgb([64, 64, 64])));
+/// let mapped = tonemap_image(&grey, HdrTonemap::Reinhard).to_rgb8();
+/// assert!(mapped.get_pixel(0, 0)[0] > 64);
+/// ```
+pub fn tonemap_image(image: &DynamicImage, operator: HdrTonemap) -> DynamicImage {
+    let curve: fn(f32) -> f32 = match operator {
+        HdrTonemap::None => return image.clone(),
+        HdrTonemap::Reinhard => reinhard,
+        HdrTonemap::Filmic => |x| hable(x * FILMIC_WHITE) / hable(FILMIC_WHITE),
+    };
+
+    let mut rgb = image.to_rgb8();
+    // 8-bit input: one lookup table covers every pixel
+    let table: Vec<u8> = (0..=255u8)
+        .map(|value| {
+            let mapped = curve(srgb_to_linear(f32::from(value) / 255.0)).clamp(0.0, 1.0);
+            (linear_to_srgb(mapped) * 255.0).round() as u8
+        })
+        .collect();
+    for pixel in rgb.pixels_mut() {
+        for channel in pixel.0.iter_mut() {
+            *channel = table[usize::from(*channel)];
+        }
+    }
+    DynamicImage::ImageRgb8(rgb)
+}
+
+/// Recreates one thumbnail recorded in a sidecar from its source image.
+///
+/// Used by `verify --check-thumbnails --fix` for missing or corrupt thumbnails.
+/// The source is decoded, rotated per `orientation`, resized to fit
+/// `thumbnail.size` (never upscaled) and written atomically to `output_path`.
+/// The tone mapping recorded in `thumbnail.tonemap` is applied again.
+///
+/// # Errors
+///
+/// - `JozinError::ValidationError` if the source cannot be decoded or
+///   `thumbnail.format` is unknown
+/// - `JozinError::IoError` if the thumbnail cannot be written
+pub fn regenerate_thumbnail(
+    source: &Path,
+    thumbnail: &ThumbnailInfo,
+    output_path: &Path,
+    orientation: Option<u8>,
+) -> Result<()> {
+    if thumbnail.format == "gif" {
+        let max_frames = DEFAULT_ANIMATED_GIF_FRAMES;
+        return generate_animated_gif_thumbnail(source, max_frames, thumbnail.size, output_path).map(|_| ());
+    }
+    let format = ThumbnailFormat::from_extension(&thumbnail.format).ok_or_else(|| {
+        JozinError::ValidationError {
+            message: format!("Unknown thumbnail format: {}", thumbnail.format),
+        }
+    })?;
+
+    let image = image::open(source).map_err(|e| JozinError::ValidationError {
+        message: format!("Failed to decode {}: {}", source.display(), e),
+    })?;
+    let image = match thumbnail.tonemap.as_deref().and_then(HdrTonemap::from_name) {
+        Some(operator) => tonemap_image(&image, operator),
+        None => image,
+    };
+    let image = apply_orientation(image, orientation);
+
+    let resized = resize_to(&image, thumbnail.size);
+
+    write_image_atomic(&resized, output_path, format, DEFAULT_JPEG_QUALITY)
+}
+
+/// Encodes an image as JPEG XL.
+///
+/// # Arguments
+///
+/// * `image` - Decoded (and already resized) image
+/// * `quality` - 1-100; ignored when `lossless` is set
+/// * `lossless` - Encode losslessly
+///
+/// # Errors
+///
+/// Returns `JozinError::NotSupported`: no JPEG XL encoder is bundled yet.
+pub fn generate_thumbnail_jxl(_image: &DynamicImage, _quality: u8, _lossless: bool) -> Result<Vec<u8>> {
+    Err(JozinError::NotSupported {
+        feature: "JPEG XL encoding (no encoder bundled yet)".to_string(),
+    })
+}
+
+/// Losslessly transcodes a JPEG file's bytes to JPEG XL; decoding the result
+/// reproduces the original JPEG bit for bit.
+///
+/// # Errors
+///
+/// Returns `JozinError::NotSupported`: no JPEG XL encoder is bundled yet.
+pub fn transcode_jpeg_to_jxl(_jpeg: &[u8]) -> Result<Vec<u8>> {
+    Err(JozinError::NotSupported {
+        feature: "JPEG XL transcoding (no encoder bundled yet)".to_string(),
+    })
+}
+
+// ============================================================================
+// Internal Helpers
+// ============================================================================
+
+/// Reinhard `x / (1 + x)` after [`REINHARD_EXPOSURE`], rescaled so 1.0 maps to 1.0.
+fn reinhard(x: f32) -> f32 {
+    let k = REINHARD_EXPOSURE;
+    x * (1.0 + k) / (1.0 + k * x)
+}
+
+/// Hable's filmic curve (before white-point normalisation).
+fn hable(x: f32) -> f32 {
+    const A: f32 = 0.15;
+    const B: f32 = 0.50;
+    const C: f32 = 0.10;
+    const D: f32 = 0.20;
+    const E: f32 = 0.02;
+    const F: f32 = 0.30;
+    ((x * (A * x + C * B) + D * E) / (x * (A * x + B) + D * F)) - E / F
+}
+
+/// Converts an sRGB-encoded value in 0-1 to linear light.
+fn srgb_to_linear(value: f32) -> f32 {
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Converts a linear value in 0-1 to sRGB encoding.
+fn linear_to_srgb(value: f32) -> f32 {
+    if value <= 0.003_130_8 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// Resizes an image to [`thumbnail_dimensions()`] at `size` (a copy if it
+/// already fits).
+fn resize_to(image: &DynamicImage, size: u32) -> DynamicImage {
+    let (width, height) = thumbnail_dimensions(size, image.width(), image.height());
+    if (width, height) == (image.width(), image.height()) {
+        image.clone()
+    } else {
+        image.resize_exact(width, height, image::imageops::FilterType::Lanczos3)
+    }
+}
+
+/// Encodes `image` in `format` and writes it atomically to `path`.
+fn write_image_atomic(
+    image: &DynamicImage,
+    path: &Path,
+    format: ThumbnailFormat,
+    quality: u8,
+) -> Result<()> {
+    let mut tmp_path = path.to_path_buf();
+    let tmp_name = format!("{}.tmp", path.file_name().unwrap().to_string_lossy());
+    tmp_path.set_file_name(tmp_name);
+
+    // Encoded up front so an unsupported encoder leaves no temporary file
+    let jxl = match format {
+        // Resized thumbnails are never bit-identical to the source, so they
+        // are encoded from pixels like `Jxl`
+        ThumbnailFormat::Jxl | ThumbnailFormat::JxlFromJpeg => Some(generate_thumbnail_jxl(image, quality, false)?),
+        ThumbnailFormat::Jpg | ThumbnailFormat::Webp => None,
+    };
+
+    let file = File::create(&tmp_path)?;
+    let mut writer = BufWriter::new(file);
+
+    let encoded = match format {
+        ThumbnailFormat::Jpg => {
+            let encoder =
+                image::codecs::jpeg::JpegEncoder::new_with_quality(&mut writer, quality);
+            // JPEG has no alpha channel
+            DynamicImage::ImageRgb8(image.to_rgb8()).write_with_encoder(encoder)
+        }
+        ThumbnailFormat::Webp => {
+            let encoder = image::codecs::webp::WebPEncoder::new_lossless(&mut writer);
+            DynamicImage::ImageRgba8(image.to_rgba8()).write_with_encoder(encoder)
+        }
+        ThumbnailFormat::Jxl | ThumbnailFormat::JxlFromJpeg => {
+            writer.write_all(jxl.as_deref().unwrap_or_default()).map_err(image::ImageError::IoError)
+        }
+    };
+    encoded.map_err(|e| JozinError::InternalError {
+        message: format!("Failed to encode thumbnail {}: {}", path.display(), e),
+    })?;
+
+    let file = writer.into_inner().map_err(|e| JozinError::IoError {
+        message: e.to_string(),
+    })?;
+    file.sync_all()?;
+    atomic_rename(&tmp_path, path)?;
+
+    Ok(())
+}
+
+/// Writes already-encoded thumbnail bytes atomically to `path`.
+fn write_bytes_atomic(bytes: &[u8], path: &Path) -> Result<()> {
+    let mut tmp_path = path.to_path_buf();
+    tmp_path.set_file_name(format!("{}.tmp", path.file_name().unwrap().to_string_lossy()));
+
+    let mut file = File::create(&tmp_path)?;
+    file.write_all(bytes)?;
+    file.sync_all()?;
+    atomic_rename(&tmp_path, path)?;
+
+    Ok(())
+}
+
+/// Decodes every `ceil(total / max_frames)`-th frame of an animated GIF
+/// (fully composited), at most `max_frames` of them.
+fn sample_gif_frames(image_path: &Path, max_frames: u32) -> Result<Vec<RgbaImage>> {
+    let decode_error = |e: &dyn std::fmt::Display| JozinError::ValidationError {
+        message: format!("Failed to decode {}: {}", image_path.display(), e),
+    };
+
+    // Counting reads only the frame headers
+    let mut counter = gif::DecodeOptions::new()
+        .read_info(BufReader::new(File::open(image_path)?))
+        .map_err(|e| decode_error(&e))?;
+    let mut total = 0u32;
+    while counter.next_frame_info().map_err(|e| decode_error(&e))?.is_some() {
+        total += 1;
+    }
+    let step = total.div_ceil(max_frames).max(1) as usize;
+
+    let decoder = image::codecs::gif::GifDecoder::new(BufReader::new(File::open(image_path)?))
+        .map_err(|e| decode_error(&e))?;
+    decoder
+        .into_frames()
+        .step_by(step)
+        .take(max_frames as usize)
+        .map(|frame| frame.map(image::Frame::into_buffer).map_err(|e| decode_error(&e)))
+        .collect()
+}
+
+/// Frames of a HEIC burst (Live Photo), at most `max_frames`.
+#[cfg(feature = "heic")]
+fn heic_burst_frames(_image_path: &Path, _max_frames: u32) -> Result<Vec<RgbaImage>> {
+    Err(JozinError::NotSupported {
+        feature: "HEIC burst frames (no HEIF decoder bundled yet)".to_string(),
+    })
+}
+
+#[cfg(not(feature = "heic"))]
+fn heic_burst_frames(_image_path: &Path, _max_frames: u32) -> Result<Vec<RgbaImage>> {
+    Err(JozinError::NotSupported {
+        feature: "HEIC burst frames (rebuild with --features heic)".to_string(),
+    })
+}
+
+/// Encodes `frames`, resized to `size`, as a looping GIF with a palette per
+/// frame.
+fn encode_animated_gif(frames: &[&RgbaImage], size: u32) -> Result<Vec<u8>> {
+    let encode_error = |e: gif::EncodingError| JozinError::InternalError {
+        message: format!("Failed to encode animated thumbnail: {}", e),
+    };
+    let Some(first) = frames.first() else {
+        return Ok(Vec::new());
+    };
+    let (width, height) = thumbnail_dimensions(size, first.width(), first.height());
+    let dimension = |value: u32| {
+        u16::try_from(value).map_err(|_| JozinError::ValidationError {
+            message: format!("Animated thumbnails are limited to {} px, got {}", u16::MAX, value),
+        })
+    };
+    let (gif_width, gif_height) = (dimension(width)?, dimension(height)?);
+
+    let mut bytes = Vec::new();
+    let mut encoder = gif::Encoder::new(&mut bytes, gif_width, gif_height, &[]).map_err(encode_error)?;
+    encoder.set_repeat(gif::Repeat::Infinite).map_err(encode_error)?;
+    for frame in frames {
+        let mut pixels =
+            image::imageops::resize(*frame, width, height, image::imageops::FilterType::Lanczos3).into_raw();
+        let mut gif_frame = gif::Frame::from_rgba_speed(gif_width, gif_height, &mut pixels, GIF_QUANTIZE_SPEED);
+        gif_frame.delay = ANIMATED_GIF_FRAME_DELAY;
+        encoder.write_frame(&gif_frame).map_err(encode_error)?;
+    }
+    // Writes the trailer
+    encoder.into_inner().map_err(encode_error)?;
+    Ok(bytes)
+}
+
+/// Checks that `ffmpeg` is installed before any frame is extracted.
+#[cfg(feature = "videos")]
+fn ensure_ffmpeg_available() -> Result<()> {
+    crate::util::capability_check::CapabilityChecker::new().require("ffmpeg").map(|_| ())
+}
+
+/// Runs `ffmpeg` to extract a single JPEG frame at `time_sec`.
+///
+/// Returns `Ok(None)` when ffmpeg produced no output, which happens when the
+/// timestamp lies beyond the end of the video.
+#[cfg(feature = "videos")]
+fn extract_frame(video_path: &Path, time_sec: f32) -> Result<Option<Vec<u8>>> {
+    use std::process::{Command, Stdio};
+
+    let output = Command::new("ffmpeg")
+        .args(["-v", "error", "-ss"])
+        .arg(format_ffmpeg_timestamp(time_sec))
+        .arg("-i")
+        .arg(video_path)
+        .args(["-vframes", "1", "-f", "image2pipe", "-vcodec", "mjpeg", "pipe:1"])
+        .stdin(Stdio::null())
+        .output()?;
+
+    if !output.status.success() && output.stdout.is_empty() {
+        return Err(JozinError::ValidationError {
+            message: format!(
+                "ffmpeg failed on {}: {}",
+                video_path.display(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            ),
+        });
+    }
+
+    if output.stdout.is_empty() {
+        Ok(None)
+    } else {
+        Ok(Some(output.stdout))
+    }
+}
+
+#[cfg(feature = "videos")]
+fn no_frame_error(video_path: &Path) -> JozinError {
+    JozinError::ValidationError {
+        message: format!("No video frame could be extracted from {}", video_path.display()),
+    }
+}
+
+/// Formats seconds as an ffmpeg `-ss` timestamp: `HH:MM:SS.mmm`.
+#[cfg(feature = "videos")]
+fn format_ffmpeg_timestamp(time_sec: f32) -> String {
+    let total_ms = (time_sec.max(0.0) as f64 * 1000.0).round() as u64;
+    let hours = total_ms / 3_600_000;
+    let minutes = (total_ms / 60_000) % 60;
+    let seconds = (total_ms / 1000) % 60;
+    let millis = total_ms % 1000;
+    format!("{:02}:{:02}:{:02}.{:03}", hours, minutes, seconds, millis)
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::test_helpers::{create_synthetic_jpeg, SyntheticExif};
+    use proptest::prelude::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_thumbnail_format_extension() {
+        assert_eq!(ThumbnailFormat::Jpg.extension(), "jpg");
+        assert_eq!(ThumbnailFormat::Webp.extension(), "webp");
+        assert_eq!(serde_json::to_string(&ThumbnailFormat::Webp).unwrap(), "\"webp\"");
+        assert_eq!(ThumbnailFormat::from_extension("image/webp"), Some(ThumbnailFormat::Webp));
+        assert_eq!(ThumbnailFormat::from_extension("JPEG"), Some(ThumbnailFormat::Jpg));
+        assert_eq!(ThumbnailFormat::from_extension(ThumbnailFormat::Jpg.mime_type()), Some(ThumbnailFormat::Jpg));
+        assert_eq!(ThumbnailFormat::from_extension("image/png"), None);
+        assert_eq!(ThumbnailFormat::from_extension("image/jxl"), Some(ThumbnailFormat::Jxl));
+        assert_eq!(ThumbnailFormat::JxlFromJpeg.extension(), "jxl");
+        assert_eq!(serde_json::to_string(&ThumbnailFormat::JxlFromJpeg).unwrap(), "\"jxl-from-jpeg\"");
+    }
+
+    #[test]
+    fn test_write_thumbnails_sizes_and_names() {
+        let temp_dir = TempDir::new().unwrap();
+        let image = DynamicImage::new_rgb8(400, 200);
+
+        let thumbs =
+            write_thumbnails(&image, "clip", &[128, 1024], ThumbnailFormat::Jpg, 85, temp_dir.path())
+                .unwrap();
+
+        assert_eq!(thumbs.len(), 2);
+        let small = image::open(temp_dir.path().join("clip_128.jpg")).unwrap();
+        assert_eq!((small.width(), small.height()), (128, 64));
+
+        // Never upscale beyond the source frame
+        let large = image::open(temp_dir.path().join("clip_1024.jpg")).unwrap();
+        assert_eq!((large.width(), large.height()), (400, 200));
+        assert_eq!(
+            thumbnail_path(Path::new("/v/clip.mov"), 128, ThumbnailFormat::Jpg),
+            PathBuf::from("/v/clip_128.jpg")
+        );
+    }
+
+    #[test]
+    fn test_dominant_color() {
+        let temp_dir = TempDir::new().unwrap();
+        let red = DynamicImage::ImageRgb8(image::RgbImage::from_pixel(300, 200, image::Rgb([255, 0, 0])));
+        let options = ThumbnailWriteOptions { dominant_color: true };
+        let thumbs = write_thumbnails_with(&red, "red", &[64], ThumbnailFormat::Webp, 85, temp_dir.path(), &options).unwrap();
+        assert_eq!(thumbs[0].dominant_color.as_deref(), Some("#FF0000"));
+        assert_eq!(thumbs[0].dominant_color_distance("#f50000"), Some(10.0));
+
+        // Half black, half white: the centroid is grey
+        let halves = image::RgbImage::from_fn(10, 10, |x, _| image::Rgb(if x < 5 { [0; 3] } else { [255; 3] }));
+        assert_eq!(dominant_color(&DynamicImage::ImageRgb8(halves)), "#808080");
+
+        let plain = write_thumbnails(&red, "plain", &[64], ThumbnailFormat::Jpg, 85, temp_dir.path()).unwrap();
+        assert_eq!(plain[0].dominant_color, None);
+        assert_eq!(plain[0].dominant_color_distance("#FF0000"), None);
+    }
+
+    #[test]
+    fn test_generate_thumbnails_uses_exif_thumbnail() {
+        let temp_dir = TempDir::new().unwrap();
+        let exif = SyntheticExif { width: 640, height: 480, thumbnail: Some((160, 120)), ..SyntheticExif::default() };
+        let photo = create_synthetic_jpeg(temp_dir.path(), "IMG_0001.jpg", exif);
+
+        let embedded = extract_exif_thumbnail(&photo).unwrap().unwrap();
+        let decoded = image::load_from_memory(&embedded).unwrap();
+        assert_eq!((decoded.width(), decoded.height()), (160, 120));
+
+        let thumbs =
+            generate_thumbnails(&photo, &[160, 128, 512], ThumbnailFormat::Jpg, DEFAULT_JPEG_QUALITY, None, None, HdrTonemap::default()).unwrap();
+        assert_eq!(thumbs.iter().map(|t| t.from_exif).collect::<Vec<_>>(), vec![true, true, false]);
+
+        // Exactly the embedded size: copied as is
+        assert_eq!(std::fs::read(&thumbs[0].path).unwrap(), embedded);
+        // Smaller: resized from the (white) embedded thumbnail, not the grey image
+        let small = image::open(&thumbs[1].path).unwrap();
+        assert_eq!((small.width(), small.height()), (128, 96));
+        assert!(small.to_rgb8().get_pixel(64, 48).0[0] > 250);
+        // Larger than embedded: decoded from the full image
+        let large = image::open(&thumbs[2].path).unwrap();
+        assert_eq!((large.width(), large.height()), (512, 384));
+        assert!(large.to_rgb8().get_pixel(256, 192).0[0] < 140);
+        assert!(!serde_json::to_string(&thumbs[2]).unwrap().contains("from_exif"));
+    }
+
+    #[test]
+    fn test_generate_thumbnails_without_exif_thumbnail() {
+        let temp_dir = TempDir::new().unwrap();
+        let exif = SyntheticExif { width: 64, height: 32, ..SyntheticExif::default() };
+        let photo = create_synthetic_jpeg(temp_dir.path(), "IMG_0002.jpg", exif);
+        assert_eq!(extract_exif_thumbnail(&photo).unwrap(), None);
+
+        let out_dir = temp_dir.path().join("thumbs");
+        std::fs::create_dir(&out_dir).unwrap();
+        let thumbs = generate_thumbnails(&photo, &[16], ThumbnailFormat::Webp, 85, Some(&out_dir), Some(6), HdrTonemap::default()).unwrap();
+        assert!(!thumbs[0].from_exif);
+        // Rotated 90° before resizing
+        let thumb = image::open(out_dir.join("IMG_0002_16.webp")).unwrap();
+        assert_eq!((thumb.width(), thumb.height()), (8, 16));
+    }
+
+    #[test]
+    fn test_generate_thumbnails_tonemaps_hdr() {
+        let temp_dir = TempDir::new().unwrap();
+        let exif = SyntheticExif { width: 32, height: 32, thumbnail: Some((16, 16)), ..SyntheticExif::default() };
+        let photo = create_synthetic_jpeg(temp_dir.path(), "IMG_0003.jpg", exif);
+        let mut bytes = std::fs::read(&photo).unwrap();
+        bytes.extend_from_slice(b"urn:com:apple:photo:2020:aux:hdrgainmap");
+        std::fs::write(&photo, bytes).unwrap();
+
+        // The embedded thumbnail is skipped and the grey image is brightened
+        let thumbs = generate_thumbnails(&photo, &[16], ThumbnailFormat::Jpg, 85, None, None, HdrTonemap::Reinhard).unwrap();
+        assert!(!thumbs[0].from_exif);
+        assert_eq!(thumbs[0].tonemap.as_deref(), Some("reinhard"));
+        let level = image::open(&thumbs[0].path).unwrap().to_rgb8().get_pixel(8, 8).0[0];
+        assert!((150..250).contains(&level), "level {}", level);
+
+        let plain = generate_thumbnails(&photo, &[16], ThumbnailFormat::Jpg, 85, None, None, HdrTonemap::None).unwrap();
+        assert!(plain[0].from_exif);
+        assert_eq!(plain[0].tonemap, None);
+    }
+
+    #[test]
+    fn test_tonemap_curves_keep_black_and_white() {
+        for operator in [HdrTonemap::Reinhard, HdrTonemap::Filmic] {
+            let image = DynamicImage::ImageRgb8(image::RgbImage::from_fn(3, 1, |x, _| {
+                image::Rgb([[0, 128, 255][x as usize]; 3])
+            }));
+            let mapped = tonemap_image(&image, operator).to_rgb8();
+            assert_eq!(mapped.get_pixel(0, 0).0[0], 0, "{:?}", operator);
+            assert!(mapped.get_pixel(2, 0).0[0] >= 254, "{:?}", operator);
+        }
+        assert_eq!(HdrTonemap::from_name("Filmic"), Some(HdrTonemap::Filmic));
+        assert_eq!(serde_json::to_string(&HdrTonemap::Reinhard).unwrap(), "\"reinhard\"");
+    }
+
+    #[test]
+    fn test_thumbnail_dimensions_edge_cases() {
+        assert_eq!(thumbnail_dimensions(256, 0, 300), (256, 256));
+        assert_eq!(thumbnail_dimensions(256, 300, 0), (256, 256));
+        assert_eq!(thumbnail_dimensions(256, 256, 256), (256, 256));
+        assert_eq!(thumbnail_dimensions(128, 400, 200), (128, 64));
+        assert_eq!(thumbnail_dimensions(128, 200, 400), (64, 128));
+        assert_eq!(thumbnail_dimensions(100, 10_000, 1), (100, 1));
+        assert_eq!(thumbnail_dimensions(300, 256, 200), (256, 200));
+    }
+
+    proptest! {
+        #[test]
+        fn test_thumbnail_dimensions_fit_size(size in 1u32..4096, width in 0u32..100_000, height in 0u32..100_000) {
+            let (w, h) = thumbnail_dimensions(size, width, height);
+            prop_assert!(w.max(h) <= size);
+            prop_assert!(w >= 1 && h >= 1);
+            if width > 0 && height > 0 {
+                prop_assert!(w <= width && h <= height);
+            }
+        }
+    }
+
+    #[cfg(feature = "videos")]
+    #[test]
+    fn test_format_ffmpeg_timestamp() {
+        assert_eq!(format_ffmpeg_timestamp(0.0), "00:00:00.000");
+        assert_eq!(format_ffmpeg_timestamp(1.0), "00:00:01.000");
+        assert_eq!(format_ffmpeg_timestamp(3725.5), "01:02:05.500");
+    }
+
+    #[cfg(feature = "videos")]
+    #[test]
+    fn test_generate_video_thumbnail_missing_file() {
+        let result = generate_video_thumbnail(
+            Path::new("/nonexistent/clip.mov"),
+            &[256],
+            ThumbnailFormat::Jpg,
+            85,
+            None,
+        );
+        assert!(matches!(result, Err(JozinError::IoError { .. })));
+    }
+
+    /// An animated GIF with `count` frames of distinct grey levels.
+    fn write_animated_gif(path: &Path, count: u8, width: u16, height: u16) {
+        let mut encoder = gif::Encoder::new(File::create(path).unwrap(), width, height, &[]).unwrap();
+        for i in 0..count {
+            let level = i.wrapping_mul(37);
+            let mut pixels = [level, level, level, 255].repeat(usize::from(width) * usize::from(height));
+            encoder.write_frame(&gif::Frame::from_rgba(width, height, &mut pixels)).unwrap();
+        }
+    }
+
+    /// Dimensions and frame delays of a GIF file.
+    fn gif_frames(path: &Path) -> (u16, u16, Vec<u16>) {
+        let mut decoder = gif::DecodeOptions::new().read_info(File::open(path).unwrap()).unwrap();
+        let (width, height) = (decoder.width(), decoder.height());
+        let mut delays = Vec::new();
+        while let Some(frame) = decoder.read_next_frame().unwrap() {
+            delays.push(frame.delay);
+        }
+        (width, height, delays)
+    }
+
+    #[test]
+    fn test_animated_gif_thumbnail_samples_frames() {
+        let temp_dir = TempDir::new().unwrap();
+        let source = temp_dir.path().join("burst.gif");
+        write_animated_gif(&source, 25, 200, 100);
+        let output = temp_dir.path().join("burst_64.gif");
+
+        // ceil(25 / 10) = 3: frames 0, 3, ..., 24
+        let thumb = generate_animated_gif_thumbnail(&source, 10, 64, &output).unwrap();
+        assert_eq!((thumb.format.as_str(), thumb.size), ("gif", 64));
+        let (width, height, delays) = gif_frames(&output);
+        assert_eq!((width, height), (64, 32));
+        assert_eq!(delays, vec![ANIMATED_GIF_FRAME_DELAY; 9]);
+
+        // Never upscaled
+        let thumb = generate_animated_gif_thumbnail(&source, 3, 1024, &output).unwrap();
+        assert_eq!(thumb.size, 200);
+        assert_eq!(gif_frames(&output).2.len(), 3);
+
+        let png = temp_dir.path().join("still.png");
+        DynamicImage::new_rgb8(8, 8).save(&png).unwrap();
+        assert!(matches!(
+            generate_animated_gif_thumbnail(&png, 10, 64, &output),
+            Err(JozinError::NotSupported { .. })
+        ));
+    }
+
+    #[test]
+    fn test_animated_gif_thumbnail_byte_budget() {
+        let temp_dir = TempDir::new().unwrap();
+        let source = temp_dir.path().join("burst.gif");
+        write_animated_gif(&source, 8, 256, 256);
+        let output = temp_dir.path().join("burst_256.gif");
+
+        let unbounded = generate_animated_gif_thumbnail(&source, 8, 256, &output).unwrap();
+        let full_size = std::fs::metadata(&output).unwrap().len();
+        assert_eq!((unbounded.size, gif_frames(&output).2.len()), (256, 8));
+
+        let budget = full_size / 3;
+        let bounded = generate_animated_gif_thumbnail_within(&source, 8, 256, Some(budget), &output).unwrap();
+        assert!(std::fs::metadata(&output).unwrap().len() <= budget);
+        assert!(gif_frames(&output).2.len() < 8 || bounded.size < 256);
+
+        let err = generate_animated_gif_thumbnail_within(&source, 8, 256, Some(10), &output).unwrap_err();
+        assert!(matches!(err, JozinError::UserError { .. }));
+    }
+}
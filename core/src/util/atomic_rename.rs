@@ -0,0 +1,174 @@
+//! Atomic file replacement across platforms
+//!
+//! Sidecars and thumbnails are written to a `.tmp` file first and then renamed
+//! over the target, so readers never see a half-written file. `fs::rename`
+//! gives that guarantee on Unix, but on Windows it can fail with
+//! `ERROR_SHARING_VIOLATION` on network drives and refuses to move files
+//! between drives.
+//!
+//! ## Platform Behavior
+//!
+//! | Platform | Implementation                                                       |
+//! |----------|----------------------------------------------------------------------|
+//! | Unix     | `fs::rename` (atomic within one filesystem)                          |
+//! | Windows  | `MoveFileExW` with `MOVEFILE_REPLACE_EXISTING \| MOVEFILE_WRITE_THROUGH` |
+//!
+//! On Windows, a cross-drive move (`ERROR_NOT_SAME_DEVICE`) falls back to
+//! copying the file, comparing BLAKE3 hashes of source and copy, and only then
+//! deleting the source. That fallback is not atomic: a crash mid-copy can
+//! leave a partial target, but never loses the source.
+
+use crate::Result;
+use std::path::Path;
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Renames `src` to `dst`, replacing `dst` if it exists.
+///
+/// # Arguments
+///
+/// * `src` - File to move (usually a `.tmp` file)
+/// * `dst` - Final location
+///
+/// # Errors
+///
+/// Returns `JozinError::IoError` if the rename fails, and
+/// `JozinError::InternalError` if a Windows cross-drive copy does not match
+/// its source (the source is kept in that case).
+///
+/// # Example
+///
+/// ```no_run
+/// use jozin_core::util::atomic_rename::atomic_rename;
+/// use std::path::Path;
+///
+/// atomic_rename(Path::new("/photos/IMG_1234.JPG.json.tmp"), Path::new("/photos/IMG_1234.JPG.json"))?;
+/// # Ok::<(), jozin_core::JozinError>(())
+/// ```
+#[cfg(not(windows))]
+pub fn atomic_rename(src: &Path, dst: &Path) -> Result<()> {
+    std::fs::rename(src, dst)?;
+    Ok(())
+}
+
+/// Renames `src` to `dst`, replacing `dst` if it exists.
+///
+/// Uses `MoveFileExW` and falls back to copy-verify-delete across drives
+/// (see the module docs).
+#[cfg(windows)]
+pub fn atomic_rename(src: &Path, dst: &Path) -> Result<()> {
+    use std::io;
+    use windows_sys::Win32::Foundation::ERROR_NOT_SAME_DEVICE;
+    use windows_sys::Win32::Storage::FileSystem::{
+        MoveFileExW, MOVEFILE_REPLACE_EXISTING, MOVEFILE_WRITE_THROUGH,
+    };
+
+    let src_wide = to_wide(src);
+    let dst_wide = to_wide(dst);
+
+    // SAFETY: both pointers reference NUL-terminated UTF-16 buffers that
+    // outlive the call.
+    let moved = unsafe {
+        MoveFileExW(
+            src_wide.as_ptr(),
+            dst_wide.as_ptr(),
+            MOVEFILE_REPLACE_EXISTING | MOVEFILE_WRITE_THROUGH,
+        )
+    };
+    if moved != 0 {
+        return Ok(());
+    }
+
+    let error = io::Error::last_os_error();
+    if error.raw_os_error() == Some(ERROR_NOT_SAME_DEVICE as i32) {
+        return copy_verify_delete(src, dst);
+    }
+    Err(error.into())
+}
+
+// ============================================================================
+// Internal Helpers
+// ============================================================================
+
+/// Encodes a path as a NUL-terminated UTF-16 string for Win32 calls.
+#[cfg(windows)]
+fn to_wide(path: &Path) -> Vec<u16> {
+    use std::os::windows::ffi::OsStrExt;
+
+    path.as_os_str().encode_wide().chain(std::iter::once(0)).collect()
+}
+
+/// Cross-drive fallback: copy, compare hashes, then delete the source.
+#[cfg(windows)]
+fn copy_verify_delete(src: &Path, dst: &Path) -> Result<()> {
+    use crate::hash::compute_blake3_hash;
+    use crate::JozinError;
+    use std::fs;
+
+    fs::copy(src, dst)?;
+    fs::File::open(dst)?.sync_all()?;
+
+    let src_hash = compute_blake3_hash(src)?;
+    let dst_hash = compute_blake3_hash(dst)?;
+    if src_hash != dst_hash {
+        return Err(JozinError::InternalError {
+            message: format!(
+                "Copy of {} to {} does not match the source (kept source)",
+                src.display(),
+                dst.display()
+            ),
+        });
+    }
+
+    fs::remove_file(src)?;
+    Ok(())
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_atomic_rename_replaces_existing_target() {
+        let temp_dir = TempDir::new().unwrap();
+        let tmp = temp_dir.path().join("IMG_0001.jpg.json.tmp");
+        let json = temp_dir.path().join("IMG_0001.jpg.json");
+        fs::write(&json, "old").unwrap();
+        fs::write(&tmp, "new").unwrap();
+
+        atomic_rename(&tmp, &json).unwrap();
+
+        assert_eq!(fs::read_to_string(&json).unwrap(), "new");
+        assert!(!tmp.exists(), ".tmp must not be left behind");
+    }
+
+    #[test]
+    fn test_atomic_rename_missing_source() {
+        let temp_dir = TempDir::new().unwrap();
+        let result = atomic_rename(&temp_dir.path().join("missing.tmp"), &temp_dir.path().join("a.json"));
+        assert!(matches!(result, Err(crate::JozinError::IoError { .. })));
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn test_copy_verify_delete_replaces_target() {
+        let temp_dir = TempDir::new().unwrap();
+        let tmp = temp_dir.path().join("a.json.tmp");
+        let json = temp_dir.path().join("a.json");
+        fs::write(&json, "old").unwrap();
+        fs::write(&tmp, "new").unwrap();
+
+        copy_verify_delete(&tmp, &json).unwrap();
+
+        assert_eq!(fs::read_to_string(&json).unwrap(), "new");
+        assert!(!tmp.exists());
+    }
+}
@@ -0,0 +1,171 @@
+//! Restoring sidecars from their rotated backups
+//!
+//! Every sidecar write keeps the previous versions as `.bak1` (newest) to
+//! `.bak3` (oldest). When a sidecar is damaged anyway (a bit flip, a write
+//! that bypassed the atomic rename), [`restore_from_backup()`] puts back the
+//! newest backup that is still a valid sidecar.
+//!
+//! The backups themselves are left in place, so a restore can be repeated
+//! or redone from an older backup.
+//!
+//! ## Usage
+//!
+//! ```no_run
+//! use jozin_core::util::backup_restore::{restore_from_backup, MAX_BACKUP_DEPTH};
+//! use std::path::Path;
+//!
+//! let restored = restore_from_backup(Path::new("/photos/IMG_1234.JPG.json"), MAX_BACKUP_DEPTH)?;
+//! println!("Restored from {}", restored.backup_path.display());
+//! # Ok::<(), jozin_core::JozinError>(())
+//! ```
+
+use crate::scan::{append_to_file_name, parse_sidecar};
+use crate::util::atomic_rename::atomic_rename;
+use crate::util::compression::read_sidecar_file;
+use crate::{JozinError, Result, Sidecar, Timestamp};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use time::OffsetDateTime;
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+/// Number of backups kept per sidecar (`.bak1` to `.bak3`).
+pub const MAX_BACKUP_DEPTH: u8 = 3;
+
+// ============================================================================
+// Public Types
+// ============================================================================
+
+/// Backup a sidecar was restored from.
+///
+/// # Fields
+///
+/// - `backup_path`: The `.bakN` file whose content was restored
+/// - `schema_version`: Schema version of the restored sidecar
+/// - `restored_at`: When the sidecar was replaced (RFC3339)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RestoredFrom {
+    pub backup_path: PathBuf,
+    pub schema_version: String,
+    pub restored_at: Timestamp,
+}
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Replaces a sidecar with its newest valid backup.
+///
+/// Tries `.bak1` up to `.bak<backup_depth>` (at most [`MAX_BACKUP_DEPTH`]) and
+/// takes the first that parses as a sidecar. Its content is written to a
+/// temporary file and renamed over `sidecar_path`; the backups are kept.
+///
+/// # Errors
+///
+/// Returns `JozinError::IoError` if no backup up to `backup_depth` exists and
+/// is valid, or if writing the sidecar fails.
+pub fn restore_from_backup(sidecar_path: &Path, backup_depth: u8) -> Result<RestoredFrom> {
+    let (backup_path, sidecar) = find_valid_backup(sidecar_path, backup_depth)?;
+
+    let tmp_path = append_to_file_name(sidecar_path, ".restore.tmp");
+    let mut tmp_file = fs::File::create(&tmp_path)?;
+    tmp_file.write_all(&fs::read(&backup_path)?)?;
+    tmp_file.sync_all()?;
+    atomic_rename(&tmp_path, sidecar_path)?;
+
+    Ok(RestoredFrom {
+        backup_path,
+        schema_version: sidecar.schema_version,
+        restored_at: OffsetDateTime::now_utc()
+            .format(&time::format_description::well_known::Rfc3339)
+            .unwrap_or_default(),
+    })
+}
+
+/// Finds the backup [`restore_from_backup()`] would restore, without
+/// changing anything.
+///
+/// # Errors
+///
+/// Returns `JozinError::IoError` if no backup up to `backup_depth` exists and
+/// is valid.
+pub fn find_valid_backup(sidecar_path: &Path, backup_depth: u8) -> Result<(PathBuf, Sidecar)> {
+    for depth in 1..=backup_depth.min(MAX_BACKUP_DEPTH) {
+        let backup_path = append_to_file_name(sidecar_path, &format!(".bak{}", depth));
+        let Ok(json) = read_sidecar_file(&backup_path) else {
+            continue;
+        };
+        if let Ok(sidecar) = parse_sidecar(&json) {
+            return Ok((backup_path, sidecar));
+        }
+    }
+    Err(JozinError::IoError {
+        message: format!(
+            "No valid backup of {} (tried .bak1 to .bak{})",
+            sidecar_path.display(),
+            backup_depth.min(MAX_BACKUP_DEPTH)
+        ),
+    })
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scan::{read_sidecar, scan_file, write_sidecar};
+    use tempfile::TempDir;
+
+    /// Scans a photo and rewrites its sidecar twice, so `.bak1` and `.bak2` exist.
+    fn sidecar_with_backups(dir: &Path) -> (PathBuf, PathBuf) {
+        let photo = dir.join("a.jpg");
+        fs::write(&photo, b"image").unwrap();
+        let mut sidecar = scan_file(&photo, false).unwrap().sidecar;
+        for make in ["Canon", "Nikon"] {
+            sidecar.tags = vec![crate::Tag::from_label(make)];
+            write_sidecar(&photo, &sidecar).unwrap();
+        }
+        (photo, dir.join("a.jpg.json"))
+    }
+
+    #[test]
+    fn test_restore_skips_corrupt_backups() {
+        let temp_dir = TempDir::new().unwrap();
+        let (photo, sidecar_path) = sidecar_with_backups(temp_dir.path());
+        fs::write(&sidecar_path, b"{ \"schema_version\": ").unwrap();
+
+        // .bak1 is valid
+        let restored = restore_from_backup(&sidecar_path, MAX_BACKUP_DEPTH).unwrap();
+        assert_eq!(restored.backup_path, temp_dir.path().join("a.jpg.json.bak1"));
+        assert_eq!(read_sidecar(&photo).unwrap().tags[0].label, "canon");
+        assert!(restored.backup_path.exists());
+
+        // Corrupt .bak1: .bak2 (no tags yet) is used
+        fs::write(&sidecar_path, b"garbage").unwrap();
+        fs::write(temp_dir.path().join("a.jpg.json.bak1"), b"{}").unwrap();
+        let restored = restore_from_backup(&sidecar_path, MAX_BACKUP_DEPTH).unwrap();
+        assert_eq!(restored.backup_path, temp_dir.path().join("a.jpg.json.bak2"));
+        assert!(read_sidecar(&photo).unwrap().tags.is_empty());
+        assert!(!temp_dir.path().join("a.jpg.json.restore.tmp").exists());
+    }
+
+    #[test]
+    fn test_restore_without_valid_backup() {
+        let temp_dir = TempDir::new().unwrap();
+        let (_, sidecar_path) = sidecar_with_backups(temp_dir.path());
+        fs::write(&sidecar_path, b"garbage").unwrap();
+        fs::write(temp_dir.path().join("a.jpg.json.bak1"), b"garbage").unwrap();
+
+        // .bak2 is beyond the requested depth
+        let err = restore_from_backup(&sidecar_path, 1).unwrap_err();
+        assert!(matches!(err, JozinError::IoError { ref message } if message.contains("No valid backup")));
+        assert_eq!(fs::read(&sidecar_path).unwrap(), b"garbage");
+        assert!(find_valid_backup(&sidecar_path, 2).is_ok());
+    }
+}
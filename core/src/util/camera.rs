@@ -0,0 +1,234 @@
+//! Canonical camera make and model names
+//!
+//! Cameras write their manufacturer in many spellings: `"CANON"`,
+//! `"Canon Inc."`, `"NIKON CORPORATION"`, `"FUJI PHOTO FILM CO., LTD."`.
+//! Grouping photos by camera (stats, `camera:` tags) needs one name per
+//! manufacturer, so scans store the output of [`normalise_camera_make()`]
+//! and [`normalise_camera_model()`] in `ImageInfo::camera_make` and
+//! `ImageInfo::camera_model`, and the EXIF values in the `_raw` fields when
+//! they differ.
+//!
+//! | EXIF Make               | EXIF Model             | Make                | Model            |
+//! |-------------------------|------------------------|---------------------|------------------|
+//! | `Canon`                 | `Canon EOS 5D Mark IV` | `Canon`             | `EOS 5D Mark IV` |
+//! | `NIKON CORPORATION`     | `NIKON D850`           | `Nikon`             | `D850`           |
+//! | `FUJIFILM`              | `X-T4`                 | `Fujifilm`          | `X-T4`           |
+//! | `OLYMPUS IMAGING CORP.` | `E-M5MarkII`           | `Olympus`           | `E-M5MarkII`     |
+//! | `Unknown Maker Ltd`     | `Box  Camera`          | `Unknown Maker Ltd` | `Box Camera`     |
+//!
+//! Makes that are not in the compiled-in table keep their spelling (with
+//! whitespace collapsed). The functions are pure (no I/O);
+//! [`normalise_image_camera()`] applies both to an `ImageInfo`.
+
+use crate::ImageInfo;
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+/// Words dropped from the end of a make before the table lookup
+/// (lowercase, punctuation removed).
+const MAKE_SUFFIXES: &[&str] = &[
+    "ag", "camera", "co", "company", "computer", "corp", "corporation", "electronics", "gmbh", "imaging",
+    "inc", "limited", "ltd", "optical", "techwin",
+];
+
+/// Known manufacturers: lookup key (lowercase, suffixes dropped) → canonical name.
+const KNOWN_MAKES: &[(&str, &str)] = &[
+    ("apple", "Apple"),
+    ("asahi", "Pentax"),
+    ("canon", "Canon"),
+    ("casio", "Casio"),
+    ("dji", "DJI"),
+    ("eastman kodak", "Kodak"),
+    ("fuji photo film", "Fujifilm"),
+    ("fujifilm", "Fujifilm"),
+    ("google", "Google"),
+    ("gopro", "GoPro"),
+    ("hasselblad", "Hasselblad"),
+    ("htc", "HTC"),
+    ("huawei", "Huawei"),
+    ("kodak", "Kodak"),
+    ("konica minolta", "Konica Minolta"),
+    ("leica", "Leica"),
+    ("lg", "LG"),
+    ("minolta", "Minolta"),
+    ("motorola", "Motorola"),
+    ("nikon", "Nikon"),
+    ("nokia", "Nokia"),
+    ("olympus", "Olympus"),
+    ("om digital solutions", "OM Digital Solutions"),
+    ("oneplus", "OnePlus"),
+    ("panasonic", "Panasonic"),
+    ("pentax", "Pentax"),
+    ("ricoh", "Ricoh"),
+    ("samsung", "Samsung"),
+    ("sigma", "Sigma"),
+    ("sony", "Sony"),
+    ("xiaomi", "Xiaomi"),
+];
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Canonical name of a camera manufacturer.
+///
+/// Known makes are matched case-insensitively, ignoring punctuation and
+/// corporate suffixes (`"Canon Inc."` → `"Canon"`); others are returned
+/// trimmed, with runs of whitespace collapsed.
+///
+/// # Example
+///
+/// ```
+/// use jozin_core::util::camera::normalise_camera_make;
+///
+/// assert_eq!(normalise_camera_make("NIKON CORPORATION"), "Nikon");
+/// assert_eq!(normalise_camera_make(" Canon Inc "), "Canon");
+/// ```
+pub fn normalise_camera_make(raw: &str) -> String {
+    let key = make_key(raw);
+    match KNOWN_MAKES.binary_search_by(|(known, _)| known.cmp(&key.as_str())) {
+        Ok(index) => KNOWN_MAKES[index].1.to_string(),
+        Err(_) => collapse_whitespace(raw),
+    }
+}
+
+/// Camera model without a leading manufacturer name.
+///
+/// `make` may be the raw or the canonical make; the model is returned
+/// trimmed, with runs of whitespace collapsed and the make (in either
+/// form, case-insensitively) removed from its start. A model that consists
+/// of the make only is kept.
+///
+/// # Example
+///
+/// ```
+/// use jozin_core::util::camera::normalise_camera_model;
+///
+/// assert_eq!(normalise_camera_model("Canon EOS 5D Mark IV", "Canon"), "EOS 5D Mark IV");
+/// assert_eq!(normalise_camera_model("NIKON D850", "NIKON CORPORATION"), "D850");
+/// assert_eq!(normalise_camera_model("iPhone 12", "Apple"), "iPhone 12");
+/// ```
+pub fn normalise_camera_model(raw: &str, make: &str) -> String {
+    let model = collapse_whitespace(raw);
+    let prefixes = [collapse_whitespace(make), normalise_camera_make(make)];
+    for prefix in prefixes.iter().filter(|prefix| !prefix.is_empty()) {
+        let Some(head) = model.get(..prefix.len()) else {
+            continue;
+        };
+        let rest = &model[prefix.len()..];
+        if head.eq_ignore_ascii_case(prefix) && rest.starts_with(' ') {
+            return rest.trim_start().to_string();
+        }
+    }
+    model
+}
+
+/// Normalises `camera_make` and `camera_model` of `image` in place.
+///
+/// Values that change are kept in `camera_make_raw` / `camera_model_raw`
+/// (unless those are already set, e.g. on a second call).
+pub fn normalise_image_camera(image: &mut ImageInfo) {
+    let raw_make = image.camera_make_raw.clone().or_else(|| image.camera_make.clone());
+    if let Some(make) = image.camera_make.take() {
+        let normalised = normalise_camera_make(&make);
+        if normalised != make && image.camera_make_raw.is_none() {
+            image.camera_make_raw = Some(make);
+        }
+        image.camera_make = Some(normalised);
+    }
+    if let Some(model) = image.camera_model.take() {
+        let normalised = normalise_camera_model(&model, raw_make.as_deref().unwrap_or_default());
+        if normalised != model && image.camera_model_raw.is_none() {
+            image.camera_model_raw = Some(model);
+        }
+        image.camera_model = Some(normalised);
+    }
+}
+
+// ============================================================================
+// Internal Helpers
+// ============================================================================
+
+/// Lowercase words of `raw` without punctuation and trailing corporate
+/// suffixes (`"FUJI PHOTO FILM CO., LTD."` → `"fuji photo film"`).
+fn make_key(raw: &str) -> String {
+    let lower = raw.to_lowercase().replace(['.', ','], " ");
+    let mut words: Vec<&str> = lower.split_whitespace().collect();
+    while words.len() > 1 && words.last().is_some_and(|word| MAKE_SUFFIXES.contains(word)) {
+        words.pop();
+    }
+    words.join(" ")
+}
+
+fn collapse_whitespace(value: &str) -> String {
+    value.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_known_makes() {
+        assert!(KNOWN_MAKES.windows(2).all(|pair| pair[0].0 < pair[1].0), "table must stay sorted");
+        for (raw, canonical) in [
+            ("CANON", "Canon"),
+            ("Canon Inc.", "Canon"),
+            ("Canon Inc", "Canon"),
+            ("NIKON", "Nikon"),
+            ("NIKON CORPORATION", "Nikon"),
+            ("FUJI PHOTO FILM CO., LTD.", "Fujifilm"),
+            ("OLYMPUS IMAGING CORP.", "Olympus"),
+            ("OM Digital Solutions", "OM Digital Solutions"),
+            ("EASTMAN KODAK COMPANY", "Kodak"),
+            ("SAMSUNG TECHWIN", "Samsung"),
+            ("LG Electronics", "LG"),
+            ("LEICA CAMERA AG", "Leica"),
+            ("ASAHI OPTICAL CO.,LTD", "Pentax"),
+        ] {
+            assert_eq!(normalise_camera_make(raw), canonical, "{}", raw);
+        }
+        assert_eq!(normalise_camera_make("  Unknown   Maker Ltd "), "Unknown Maker Ltd");
+        assert_eq!(normalise_camera_make(""), "");
+    }
+
+    #[test]
+    fn test_models() {
+        assert_eq!(normalise_camera_model("Canon EOS R5", "Canon Inc."), "EOS R5");
+        assert_eq!(normalise_camera_model("canon  EOS R5 ", "Canon"), "EOS R5");
+        assert_eq!(normalise_camera_model("Canon", "Canon"), "Canon");
+        assert_eq!(normalise_camera_model("Canonet QL17", "Canon"), "Canonet QL17");
+        assert_eq!(normalise_camera_model("X-T4", "FUJIFILM"), "X-T4");
+        assert_eq!(normalise_camera_model("Pixel 8", ""), "Pixel 8");
+        // Multi-byte characters before the cut
+        assert_eq!(normalise_camera_model("Ž1 Camera", "Ž"), "Ž1 Camera");
+    }
+
+    #[test]
+    fn test_normalise_image_camera_keeps_raw_values() {
+        let mut image = ImageInfo {
+            camera_make: Some("NIKON CORPORATION".to_string()),
+            camera_model: Some("NIKON D850".to_string()),
+            ..ImageInfo::default()
+        };
+        normalise_image_camera(&mut image);
+        normalise_image_camera(&mut image);
+        assert_eq!((image.camera_make.as_deref(), image.camera_model.as_deref()), (Some("Nikon"), Some("D850")));
+        assert_eq!(image.camera_make_raw.as_deref(), Some("NIKON CORPORATION"));
+        assert_eq!(image.camera_model_raw.as_deref(), Some("NIKON D850"));
+
+        let mut apple = ImageInfo {
+            camera_make: Some("Apple".to_string()),
+            camera_model: Some("iPhone 12".to_string()),
+            ..ImageInfo::default()
+        };
+        normalise_image_camera(&mut apple);
+        assert_eq!((apple.camera_make_raw, apple.camera_model_raw), (None, None));
+    }
+}
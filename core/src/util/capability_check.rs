@@ -0,0 +1,248 @@
+//! Detecting optional external tools
+//!
+//! Some operations call external programs: video thumbnails need `ffmpeg`,
+//! video metadata `ffprobe`, and IPTC keyword export `exiftool`. Rather than
+//! failing halfway through a library, operations check for their tool first
+//! with [`CapabilityChecker::require()`], which returns
+//! `JozinError::NotSupported` with an install hint when it is missing.
+//! `jozin capabilities` reports all tools at once
+//! ([`CapabilityChecker::check_all()`]).
+//!
+//! | Tool       | Version probe  | Used by                       |
+//! |------------|----------------|-------------------------------|
+//! | `ffprobe`  | `-version`     | Video metadata                |
+//! | `exiftool` | `-ver`         | IPTC keywords (`interop::iptc`) |
+//! | `ffmpeg`   | `-version`     | Video thumbnails (`thumbs`)   |
+//!
+//! Tools are looked up in `PATH` (with `PATHEXT` extensions on Windows).
+
+use crate::{JozinError, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+/// Known tools: name, version probe argument, install hint.
+const TOOLS: [(&str, &str, &str); 3] = [
+    ("ffprobe", "-version", "install ffmpeg, which includes ffprobe"),
+    ("exiftool", "-ver", "install exiftool from https://exiftool.org"),
+    ("ffmpeg", "-version", "install ffmpeg from https://ffmpeg.org"),
+];
+
+// ============================================================================
+// Public Types
+// ============================================================================
+
+/// Availability of one external tool.
+///
+/// # Fields
+///
+/// - `available`: The tool was found
+/// - `version`: Version reported by the tool (e.g. "6.1.1", "12.76")
+/// - `path`: Where the tool was found
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CapabilityResult {
+    pub available: bool,
+    pub version: Option<String>,
+    pub path: Option<PathBuf>,
+}
+
+/// Looks up external tools in a search path (`PATH` by default).
+#[derive(Debug, Clone, Default)]
+pub struct CapabilityChecker {
+    search_path: Option<OsString>,
+}
+
+impl CapabilityChecker {
+    /// Checker searching `PATH`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Checker searching `search_path` (same syntax as `PATH`) instead.
+    pub fn with_search_path(search_path: impl Into<OsString>) -> Self {
+        Self { search_path: Some(search_path.into()) }
+    }
+
+    /// Checks for `ffprobe`.
+    pub fn check_ffprobe(&self) -> CapabilityResult {
+        self.check("ffprobe")
+    }
+
+    /// Checks for `exiftool`.
+    pub fn check_exiftool(&self) -> CapabilityResult {
+        self.check("exiftool")
+    }
+
+    /// Checks for `ffmpeg`.
+    pub fn check_ffmpeg(&self) -> CapabilityResult {
+        self.check("ffmpeg")
+    }
+
+    /// Checks all known tools concurrently, keyed by tool name.
+    pub fn check_all(&self) -> HashMap<&'static str, CapabilityResult> {
+        std::thread::scope(|scope| {
+            let handles: Vec<_> = TOOLS
+                .iter()
+                .map(|(name, _, _)| (*name, scope.spawn(move || self.check(name))))
+                .collect();
+            handles
+                .into_iter()
+                .map(|(name, handle)| (name, handle.join().unwrap_or_default()))
+                .collect()
+        })
+    }
+
+    /// Returns the path of `tool`, for operations that cannot run without it.
+    ///
+    /// Only the search path is consulted; the tool is not started.
+    ///
+    /// # Errors
+    ///
+    /// - `JozinError::NotSupported` with an install hint if the tool is missing
+    /// - `JozinError::InternalError` if `tool` is not a known tool
+    pub fn require(&self, tool: &str) -> Result<PathBuf> {
+        let (name, _, hint) = tool_spec(tool)?;
+        self.find_executable(name).ok_or_else(|| JozinError::NotSupported {
+            feature: format!("{} ({} and make sure it is in PATH)", name, hint),
+        })
+    }
+
+    /// Looks up a known tool and asks it for its version.
+    fn check(&self, tool: &str) -> CapabilityResult {
+        let Ok((name, version_arg, _)) = tool_spec(tool) else {
+            return CapabilityResult::default();
+        };
+        match self.find_executable(name) {
+            Some(path) => CapabilityResult {
+                available: true,
+                version: probe_version(&path, version_arg),
+                path: Some(path),
+            },
+            None => CapabilityResult::default(),
+        }
+    }
+
+    /// First executable named `name` in the search path.
+    fn find_executable(&self, name: &str) -> Option<PathBuf> {
+        let search_path = self.search_path.clone().or_else(|| std::env::var_os("PATH"))?;
+        std::env::split_paths(&search_path)
+            .flat_map(|dir| executable_names(name).into_iter().map(move |file| dir.join(file)))
+            .find(|candidate| is_executable(candidate))
+    }
+}
+
+// ============================================================================
+// Internal Helpers
+// ============================================================================
+
+fn tool_spec(tool: &str) -> Result<(&'static str, &'static str, &'static str)> {
+    TOOLS.iter().copied().find(|(name, _, _)| *name == tool).ok_or_else(|| JozinError::InternalError {
+        message: format!("Unknown external tool '{}'", tool),
+    })
+}
+
+/// File names `name` may have on this platform.
+fn executable_names(name: &str) -> Vec<String> {
+    if cfg!(windows) {
+        let extensions = std::env::var("PATHEXT").unwrap_or_else(|_| ".EXE;.BAT;.CMD".to_string());
+        extensions.split(';').filter(|ext| !ext.is_empty()).map(|ext| format!("{}{}", name, ext)).collect()
+    } else {
+        vec![name.to_string()]
+    }
+}
+
+fn is_executable(path: &Path) -> bool {
+    let Ok(metadata) = path.metadata() else {
+        return false;
+    };
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        metadata.is_file() && metadata.permissions().mode() & 0o111 != 0
+    }
+    #[cfg(not(unix))]
+    {
+        metadata.is_file()
+    }
+}
+
+/// Runs `path version_arg` and extracts the version from its first line
+/// (`ffmpeg version 6.1.1 Copyright ...` → `6.1.1`, `12.76` → `12.76`).
+fn probe_version(path: &Path, version_arg: &str) -> Option<String> {
+    let output = Command::new(path)
+        .arg(version_arg)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    parse_version(stdout.lines().next()?)
+}
+
+fn parse_version(line: &str) -> Option<String> {
+    let mut words = line.split_whitespace();
+    let version = match line.split_once(" version ") {
+        Some((_, rest)) => rest.split_whitespace().next(),
+        None => words.next(),
+    }?;
+    version.starts_with(|c: char| c.is_ascii_digit() || c == 'n').then(|| version.to_string())
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!(parse_version("ffmpeg version 6.1.1 Copyright (c) 2000-2023").as_deref(), Some("6.1.1"));
+        assert_eq!(parse_version("ffprobe version n7.0 Copyright").as_deref(), Some("n7.0"));
+        assert_eq!(parse_version("12.76").as_deref(), Some("12.76"));
+        assert_eq!(parse_version("usage: exiftool"), None);
+    }
+
+    #[test]
+    fn test_missing_tool_is_not_supported() {
+        let temp_dir = TempDir::new().unwrap();
+        let checker = CapabilityChecker::with_search_path(temp_dir.path());
+
+        assert_eq!(checker.check_exiftool(), CapabilityResult::default());
+        let all = checker.check_all();
+        assert_eq!(all.len(), 3);
+        assert!(all.values().all(|result| !result.available));
+        match checker.require("exiftool") {
+            Err(JozinError::NotSupported { feature }) => assert!(feature.starts_with("exiftool (install")),
+            other => panic!("expected NotSupported, got {:?}", other),
+        }
+        assert!(matches!(checker.require("gimp"), Err(JozinError::InternalError { .. })));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_finds_tool_and_version() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = TempDir::new().unwrap();
+        let exiftool = temp_dir.path().join("exiftool");
+        std::fs::write(&exiftool, "#!/bin/sh\necho 12.76\n").unwrap();
+        std::fs::set_permissions(&exiftool, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let checker = CapabilityChecker::with_search_path(temp_dir.path());
+
+        let result = checker.check_exiftool();
+        assert!(result.available);
+        assert_eq!(result.version.as_deref(), Some("12.76"));
+        assert_eq!(result.path.as_deref(), Some(exiftool.as_path()));
+        assert_eq!(checker.require("exiftool").unwrap(), exiftool);
+        assert!(!checker.check_ffmpeg().available);
+    }
+}
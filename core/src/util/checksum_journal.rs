@@ -0,0 +1,192 @@
+//! Append-only checksum journal
+//!
+//! Sidecars can be deleted, overwritten or lost with a library move. The
+//! checksum journal is an audit trail kept outside the library: every scanned
+//! file appends one JSON line recording what was hashed, and when.
+//!
+//! ```text
+//! {"ts":"2025-06-15T14:30:00Z","path":"/photos/IMG_1234.JPG","hash":"af13...","size":2048576}
+//! ```
+//!
+//! The default location is `~/.jozin/checksums.jsonl` (`jozin scan --journal`).
+//! Each entry is a single append followed by a data sync, so a crash loses at
+//! most the entry being written. The journal is not indexed;
+//! [`ChecksumJournal::query()`] reads it linearly.
+//!
+//! ## Usage
+//!
+//! ```no_run
+//! use jozin_core::util::checksum_journal::ChecksumJournal;
+//! use std::path::Path;
+//!
+//! let journal = ChecksumJournal::new(ChecksumJournal::default_path().unwrap());
+//! for entry in journal.query(Path::new("/photos/IMG_1234.JPG"))? {
+//!     println!("{} {}", entry.ts, entry.hash);
+//! }
+//! # Ok::<(), jozin_core::JozinError>(())
+//! ```
+
+use crate::{JozinError, Result};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use time::OffsetDateTime;
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+/// Journal location relative to the user's home directory.
+pub const DEFAULT_JOURNAL_FILE: &str = ".jozin/checksums.jsonl";
+
+// ============================================================================
+// Public Types
+// ============================================================================
+
+/// One hashed file.
+///
+/// # Fields
+///
+/// - `ts`: When the file was hashed (RFC3339, UTC)
+/// - `path`: File path as scanned
+/// - `hash`: Content hash (BLAKE3 or SHA-256 hex, as recorded in the sidecar)
+/// - `size`: File size in bytes
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub ts: String,
+    pub path: String,
+    pub hash: String,
+    pub size: u64,
+}
+
+impl JournalEntry {
+    /// Creates an entry timestamped now.
+    pub fn now(path: &Path, hash: &str, size: u64) -> Result<Self> {
+        let ts = OffsetDateTime::now_utc()
+            .format(&time::format_description::well_known::Rfc3339)
+            .map_err(|e| JozinError::InternalError {
+                message: format!("Failed to format journal timestamp: {}", e),
+            })?;
+        Ok(Self {
+            ts,
+            path: path.display().to_string(),
+            hash: hash.to_string(),
+            size,
+        })
+    }
+}
+
+/// A JSON-lines checksum journal file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChecksumJournal {
+    path: PathBuf,
+}
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+impl ChecksumJournal {
+    /// Journal at `path`; the file is created on the first append.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// `~/.jozin/checksums.jsonl`, or `None` if the home directory is unknown.
+    pub fn default_path() -> Option<PathBuf> {
+        std::env::var_os("HOME")
+            .or_else(|| std::env::var_os("USERPROFILE"))
+            .filter(|home| !home.is_empty())
+            .map(|home| PathBuf::from(home).join(DEFAULT_JOURNAL_FILE))
+    }
+
+    /// Path of the journal file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Appends an entry and syncs it to disk.
+    ///
+    /// The line is written with a single append, so concurrent scan threads
+    /// don't interleave entries.
+    ///
+    /// # Errors
+    ///
+    /// Returns `JozinError::IoError` if the journal (or its directory) cannot
+    /// be created or written.
+    pub fn append(&self, entry: &JournalEntry) -> Result<()> {
+        if let Some(parent) = self.path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut line = serde_json::to_string(entry)?;
+        line.push('\n');
+
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        file.write_all(line.as_bytes())?;
+        file.sync_data()?;
+        Ok(())
+    }
+
+    /// Returns all entries for `path`, oldest first.
+    ///
+    /// Lines that are not valid entries (e.g. one cut short by a crash) are
+    /// skipped. A journal that doesn't exist yet has no entries.
+    ///
+    /// # Errors
+    ///
+    /// Returns `JozinError::IoError` if the journal cannot be read.
+    pub fn query(&self, path: &Path) -> Result<Vec<JournalEntry>> {
+        let file = match fs::File::open(&self.path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+
+        let wanted = path.display().to_string();
+        let mut entries = Vec::new();
+        for line in BufReader::new(file).lines() {
+            if let Ok(entry) = serde_json::from_str::<JournalEntry>(&line?) {
+                if entry.path == wanted {
+                    entries.push(entry);
+                }
+            }
+        }
+        Ok(entries)
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_append_and_query() {
+        let temp_dir = TempDir::new().unwrap();
+        let journal = ChecksumJournal::new(temp_dir.path().join("nested/checksums.jsonl"));
+        let photo = Path::new("/photos/IMG_1234.JPG");
+
+        assert!(journal.query(photo).unwrap().is_empty());
+
+        journal.append(&JournalEntry::now(photo, "aaaa", 10).unwrap()).unwrap();
+        journal.append(&JournalEntry::now(Path::new("/photos/other.jpg"), "bbbb", 20).unwrap()).unwrap();
+        journal.append(&JournalEntry::now(photo, "cccc", 11).unwrap()).unwrap();
+
+        // A torn line from a crash is skipped
+        let mut file = OpenOptions::new().append(true).open(journal.path()).unwrap();
+        file.write_all(b"{\"ts\":\"2025-").unwrap();
+
+        let entries = journal.query(photo).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].hash, "aaaa");
+        assert_eq!(entries[1].hash, "cccc");
+        assert_eq!(entries[1].size, 11);
+        assert!(entries[0].ts.ends_with('Z'));
+    }
+}
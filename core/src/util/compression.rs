@@ -0,0 +1,330 @@
+//! Gzip-compressed sidecars for space-constrained storage
+//!
+//! Pretty-printed sidecars of a 200 000-photo library add up to gigabytes of
+//! JSON. With `ScanConfig::compress_sidecars` (`scan --compress-sidecars`),
+//! sidecars are written as `<sidecar>.gz` (e.g. `IMG_1234.JPG.json.gz`),
+//! gzip at level [`COMPRESSION_LEVEL`]; JSON compresses to roughly a fifth.
+//!
+//! Both forms are sidecars everywhere: discovery finds either, readers
+//! detect gzip by its magic bytes, and a rewrite keeps the form of the file
+//! it replaces (including its `.bakN` backups). If both exist, the
+//! uncompressed one wins. A compressing scan of a photo with an uncompressed
+//! sidecar converts it and its backups first, so the old version becomes
+//! `.gz.bak1`. [`convert_sidecars()`] switches an existing library between
+//! the forms (`jozin migrate --compress` / `--decompress`).
+//!
+//! | Uncompressed             | Compressed                  |
+//! |--------------------------|-----------------------------|
+//! | `IMG_1234.JPG.json`      | `IMG_1234.JPG.json.gz`      |
+//! | `IMG_1234.JPG.json.bak1` | `IMG_1234.JPG.json.gz.bak1` |
+
+use crate::scan::{append_to_file_name, find_sidecar_files};
+use crate::util::atomic_rename::atomic_rename;
+use crate::util::backup_restore::MAX_BACKUP_DEPTH;
+use crate::{JozinError, Result};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+/// Suffix appended to the name of a compressed sidecar.
+pub const COMPRESSED_SIDECAR_SUFFIX: &str = ".gz";
+
+/// Gzip level of compressed sidecars (flate2's default trade-off).
+pub const COMPRESSION_LEVEL: u32 = 6;
+
+/// First bytes of every gzip stream.
+const GZIP_MAGIC: [u8; 2] = [0x1F, 0x8B];
+
+// ============================================================================
+// Public Types
+// ============================================================================
+
+/// Result of [`convert_sidecars()`].
+///
+/// # Fields
+///
+/// - `converted`: Sidecars converted (or with `dry_run`, that would be)
+/// - `already_converted`: Sidecars already in the requested form
+/// - `skipped`: Sidecars left alone because the other form exists as well
+/// - `bytes_before`: Size of the converted sidecars before conversion
+/// - `bytes_after`: Size of the converted sidecars after conversion
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompressionResult {
+    pub converted: usize,
+    pub already_converted: usize,
+    pub skipped: usize,
+    pub bytes_before: u64,
+    pub bytes_after: u64,
+}
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Whether `path` names a compressed sidecar (ends with `.gz`).
+pub fn is_compressed_path(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.ends_with(COMPRESSED_SIDECAR_SUFFIX))
+}
+
+/// Returns the compressed form of a sidecar path (`a.jpg.json` → `a.jpg.json.gz`).
+pub fn compressed_path(sidecar_path: &Path) -> PathBuf {
+    if is_compressed_path(sidecar_path) {
+        return sidecar_path.to_path_buf();
+    }
+    append_to_file_name(sidecar_path, COMPRESSED_SIDECAR_SUFFIX)
+}
+
+/// Returns the uncompressed form of a sidecar path (`a.jpg.json.gz` → `a.jpg.json`).
+pub fn uncompressed_path(sidecar_path: &Path) -> PathBuf {
+    match sidecar_path.file_name().and_then(|name| name.to_str()) {
+        Some(name) => match name.strip_suffix(COMPRESSED_SIDECAR_SUFFIX) {
+            Some(stem) => sidecar_path.with_file_name(stem),
+            None => sidecar_path.to_path_buf(),
+        },
+        None => sidecar_path.to_path_buf(),
+    }
+}
+
+/// Returns the sidecar that exists for an uncompressed sidecar path: the
+/// path itself, or its `.gz` form if only that exists. Falls back to the
+/// path itself if neither exists.
+///
+/// # Example
+///
+/// ```no_run
+/// use jozin_core::util::compression::existing_sidecar_path;
+/// use std::path::Path;
+///
+/// let path = existing_sidecar_path(Path::new("/photos/IMG_1234.JPG.json"));
+/// println!("Reading {}", path.display());
+/// ```
+pub fn existing_sidecar_path(sidecar_path: &Path) -> PathBuf {
+    let compressed = compressed_path(sidecar_path);
+    if !sidecar_path.exists() && compressed.exists() {
+        compressed
+    } else {
+        sidecar_path.to_path_buf()
+    }
+}
+
+/// Gzips sidecar JSON at [`COMPRESSION_LEVEL`].
+///
+/// # Errors
+///
+/// Returns `JozinError::IoError` if compression fails.
+pub fn compress(json: &str) -> Result<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::new(COMPRESSION_LEVEL));
+    encoder.write_all(json.as_bytes())?;
+    Ok(encoder.finish()?)
+}
+
+/// Decompresses gzipped sidecar JSON.
+///
+/// # Errors
+///
+/// Returns `JozinError::ValidationError` if `bytes` is not a gzip stream of
+/// UTF-8 text.
+pub fn decompress(bytes: &[u8]) -> Result<String> {
+    let mut json = String::new();
+    GzDecoder::new(bytes)
+        .read_to_string(&mut json)
+        .map_err(|e| JozinError::ValidationError {
+            message: format!("Invalid compressed sidecar: {}", e),
+        })?;
+    Ok(json)
+}
+
+/// Encodes sidecar JSON for writing: gzipped if `compressed`, else as is.
+///
+/// # Errors
+///
+/// Returns `JozinError::IoError` if compression fails.
+pub fn encode_sidecar(json: &str, compressed: bool) -> Result<Vec<u8>> {
+    if compressed {
+        compress(json)
+    } else {
+        Ok(json.as_bytes().to_vec())
+    }
+}
+
+/// Reads a sidecar (or backup) as JSON text, decompressing it if it starts
+/// with the gzip magic bytes.
+///
+/// # Errors
+///
+/// - `JozinError::IoError` if the file cannot be read
+/// - `JozinError::ValidationError` if it is neither UTF-8 text nor valid gzip
+pub fn read_sidecar_file(path: &Path) -> Result<String> {
+    let bytes = fs::read(path)?;
+    if bytes.starts_with(&GZIP_MAGIC) {
+        return decompress(&bytes);
+    }
+    String::from_utf8(bytes).map_err(|e| JozinError::ValidationError {
+        message: format!("Sidecar {} is not UTF-8: {}", path.display(), e),
+    })
+}
+
+/// Compresses (`compress == true`) or decompresses every sidecar under
+/// `root` in place, together with its `.bakN` backups. The other form is
+/// written atomically before the original is removed.
+///
+/// # Arguments
+///
+/// * `root` - Sidecar, image file or directory
+/// * `recursive` - Descend into subdirectories
+/// * `compress` - Convert to `.gz` (`true`) or back to plain JSON (`false`)
+/// * `dry_run` - Count and measure without writing
+///
+/// # Errors
+///
+/// - `JozinError::IoError` if `root` does not exist or a file cannot be written
+/// - `JozinError::ValidationError` if a compressed sidecar is corrupt
+///
+/// # Example
+///
+/// ```no_run
+/// use jozin_core::util::compression::convert_sidecars;
+/// use std::path::Path;
+///
+/// let result = convert_sidecars(Path::new("/photos"), true, true, false)?;
+/// println!("{} → {} bytes", result.bytes_before, result.bytes_after);
+/// # Ok::<(), jozin_core::JozinError>(())
+/// ```
+pub fn convert_sidecars(root: &Path, recursive: bool, compress: bool, dry_run: bool) -> Result<CompressionResult> {
+    let mut result = CompressionResult::default();
+    for sidecar_path in find_sidecar_files(root, recursive)? {
+        if is_compressed_path(&sidecar_path) == compress {
+            result.already_converted += 1;
+            continue;
+        }
+        let target = if compress { compressed_path(&sidecar_path) } else { uncompressed_path(&sidecar_path) };
+        if target.exists() {
+            result.skipped += 1;
+            continue;
+        }
+
+        let json = read_sidecar_file(&sidecar_path)?;
+        result.converted += 1;
+        result.bytes_before += fs::metadata(&sidecar_path)?.len();
+        result.bytes_after += encode_sidecar(&json, compress)?.len() as u64;
+        if dry_run {
+            continue;
+        }
+
+        convert_sidecar_file(&sidecar_path, compress)?;
+    }
+    Ok(result)
+}
+
+/// Converts one sidecar and its `.bakN` backups to the compressed
+/// (`compress == true`) or uncompressed form, returning the new path.
+///
+/// Files of the other form at the target paths are replaced.
+///
+/// # Errors
+///
+/// - `JozinError::IoError` if a file cannot be read or written
+/// - `JozinError::ValidationError` if a compressed file is corrupt
+pub(crate) fn convert_sidecar_file(sidecar_path: &Path, compress: bool) -> Result<PathBuf> {
+    let target = if compress { compressed_path(sidecar_path) } else { uncompressed_path(sidecar_path) };
+    for k in 1..=MAX_BACKUP_DEPTH {
+        let backup = append_to_file_name(sidecar_path, &format!(".bak{}", k));
+        if backup.is_file() {
+            let converted = append_to_file_name(&target, &format!(".bak{}", k));
+            write_atomic(&converted, &encode_sidecar(&read_sidecar_file(&backup)?, compress)?)?;
+            fs::remove_file(&backup)?;
+        }
+    }
+    write_atomic(&target, &encode_sidecar(&read_sidecar_file(sidecar_path)?, compress)?)?;
+    fs::remove_file(sidecar_path)?;
+    Ok(target)
+}
+
+// ============================================================================
+// Internal Helpers
+// ============================================================================
+
+/// Writes `bytes` to `<path>.tmp`, syncs it and renames it over `path`.
+fn write_atomic(path: &Path, bytes: &[u8]) -> Result<()> {
+    let tmp_path = append_to_file_name(path, ".tmp");
+    let mut tmp_file = File::create(&tmp_path)?;
+    tmp_file.write_all(bytes)?;
+    tmp_file.sync_all()?;
+    atomic_rename(&tmp_path, path)
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scan::{read_sidecar, scan_file, write_sidecar};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_compress_round_trip() {
+        let json = serde_json::to_string_pretty(&serde_json::json!({ "schema_version": "2.0.0", "tags": ["beach"] })).unwrap();
+        let compressed = compress(&json).unwrap();
+        assert!(compressed.starts_with(&GZIP_MAGIC));
+        assert_eq!(decompress(&compressed).unwrap(), json);
+        assert!(decompress(b"not gzip").is_err());
+    }
+
+    #[test]
+    fn test_paths() {
+        let plain = Path::new("/photos/a.jpg.json");
+        let gz = Path::new("/photos/a.jpg.json.gz");
+        assert_eq!(compressed_path(plain), gz);
+        assert_eq!(compressed_path(gz), gz);
+        assert_eq!(uncompressed_path(gz), plain);
+        assert!(is_compressed_path(gz) && !is_compressed_path(plain));
+    }
+
+    #[test]
+    fn test_convert_sidecars_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let photo = temp_dir.path().join("a.jpg");
+        fs::write(&photo, b"fake image data").unwrap();
+        let mut sidecar = scan_file(&photo, false).unwrap().sidecar;
+        sidecar.tags.push(crate::Tag { label: "beach".to_string(), score: None, source: crate::TagSource::User });
+        write_sidecar(&photo, &sidecar).unwrap();
+        let plain = temp_dir.path().join("a.jpg.json");
+        let original = fs::read_to_string(&plain).unwrap();
+
+        let dry = convert_sidecars(temp_dir.path(), false, true, true).unwrap();
+        assert_eq!(dry.converted, 1);
+        assert!(dry.bytes_after < dry.bytes_before);
+        assert!(plain.exists());
+
+        convert_sidecars(temp_dir.path(), false, true, false).unwrap();
+        let gz = temp_dir.path().join("a.jpg.json.gz");
+        assert!(!plain.exists() && gz.exists());
+        assert!(temp_dir.path().join("a.jpg.json.gz.bak1").exists());
+        assert!(!temp_dir.path().join("a.jpg.json.bak1").exists());
+        assert_eq!(read_sidecar_file(&gz).unwrap(), original);
+        assert_eq!(read_sidecar(&photo).unwrap().tags[0].label, "beach");
+
+        // Rewrites keep the compressed form
+        write_sidecar(&photo, &sidecar).unwrap();
+        assert!(!plain.exists());
+        assert!(fs::read(&gz).unwrap().starts_with(&GZIP_MAGIC));
+
+        let result = convert_sidecars(temp_dir.path(), false, false, false).unwrap();
+        assert_eq!((result.converted, result.already_converted), (1, 0));
+        assert!(!gz.exists());
+        assert_eq!(fs::read_to_string(&plain).unwrap(), original);
+        assert_eq!(convert_sidecars(temp_dir.path(), false, false, false).unwrap().already_converted, 1);
+    }
+}
@@ -0,0 +1,237 @@
+//! Batched sidecar updates
+//!
+//! Steps that each update a sidecar (tags, faces, thumbnails) would each
+//! read, parse, serialize and write it, and rotate its backups. A
+//! [`DeferredSidecarWriter`] reads the sidecar once, applies any number of
+//! modifications in memory, and writes the result once on
+//! [`commit()`](DeferredSidecarWriter::commit), atomically and with the
+//! usual backup rotation.
+//!
+//! Modifications that are never committed are discarded with a warning
+//! when the writer is dropped, e.g. when a step fails halfway: a sidecar is
+//! either fully updated or left as it was.
+//!
+//! ## Usage
+//!
+//! ```no_run
+//! use jozin_core::util::deferred_write::DeferredSidecarWriter;
+//! use jozin_core::Tag;
+//! use std::path::Path;
+//!
+//! let photo = Path::new("/photos/IMG_1234.JPG");
+//! let mut writer = DeferredSidecarWriter::load(photo)?;
+//! println!("{} tags so far", writer.tags.len());
+//! writer.modify(|sidecar| {
+//!     sidecar.tags.push(Tag::from_label("beach"));
+//!     Ok(())
+//! })?;
+//! writer.commit()?;
+//!
+//! // The same in one call
+//! DeferredSidecarWriter::transaction(photo, |sidecar| {
+//!     sidecar.tags.retain(|tag| tag.label != "beach");
+//!     Ok(())
+//! })?;
+//! # Ok::<(), jozin_core::JozinError>(())
+//! ```
+
+use crate::scan::{parse_sidecar, write_sidecar_with};
+use crate::util::compression::{existing_sidecar_path, read_sidecar_file};
+use crate::util::library_config::LibraryConfig;
+use crate::{Result, Sidecar};
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+
+// ============================================================================
+// Public Types
+// ============================================================================
+
+/// An in-memory copy of a sidecar, written back on
+/// [`commit()`](DeferredSidecarWriter::commit).
+///
+/// Dereferences to the [`Sidecar`] for reading.
+#[derive(Debug)]
+pub struct DeferredSidecarWriter {
+    image_path: PathBuf,
+    naming: LibraryConfig,
+    sidecar: Sidecar,
+    /// Number of `modify` calls not yet committed
+    pending: usize,
+}
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+impl DeferredSidecarWriter {
+    /// Reads the sidecar of `image_path` (with the library's naming).
+    ///
+    /// # Errors
+    ///
+    /// - `JozinError::IoError` if the sidecar does not exist or cannot be read
+    /// - `JozinError::ValidationError` if it is not a valid sidecar
+    pub fn load(image_path: &Path) -> Result<Self> {
+        let naming = LibraryConfig::discover(image_path);
+        let sidecar = parse_sidecar(&read_sidecar_file(&existing_sidecar_path(&naming.sidecar_path(image_path)))?)?;
+        Ok(DeferredSidecarWriter {
+            image_path: image_path.to_path_buf(),
+            naming,
+            sidecar,
+            pending: 0,
+        })
+    }
+
+    /// Applies `f` to the in-memory sidecar. Nothing is written.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of `f`; changes `f` made before failing are kept in
+    /// memory (and discarded unless committed).
+    pub fn modify<F>(&mut self, f: F) -> Result<()>
+    where
+        F: FnOnce(&mut Sidecar) -> Result<()>,
+    {
+        self.pending += 1;
+        f(&mut self.sidecar)
+    }
+
+    /// Whether there are modifications that have not been committed.
+    pub fn is_dirty(&self) -> bool {
+        self.pending > 0
+    }
+
+    /// Writes the sidecar once, atomically and with backup rotation, if it
+    /// was modified. `updated_at` and the face/tag counts are refreshed.
+    ///
+    /// # Errors
+    ///
+    /// Returns `JozinError::IoError` if writing fails; the modifications are
+    /// then discarded.
+    pub fn commit(mut self) -> Result<()> {
+        if !self.is_dirty() {
+            return Ok(());
+        }
+        // Not retried on failure: dropping must not warn a second time
+        self.pending = 0;
+        self.sidecar.update_summary_counts();
+        if let Ok(now) = OffsetDateTime::now_utc().format(&Rfc3339) {
+            self.sidecar.updated_at = now;
+        }
+        write_sidecar_with(&self.image_path, &self.sidecar, &self.naming)
+    }
+
+    /// Loads the sidecar of `image_path`, applies `f` and commits.
+    ///
+    /// # Errors
+    ///
+    /// As [`load()`](Self::load), the error of `f` (nothing is written), or
+    /// as [`commit()`](Self::commit).
+    pub fn transaction<F>(image_path: &Path, f: F) -> Result<()>
+    where
+        F: FnOnce(&mut Sidecar) -> Result<()>,
+    {
+        let mut writer = DeferredSidecarWriter::load(image_path)?;
+        if let Err(e) = writer.modify(f) {
+            // Failed transactions are expected to discard, without a warning
+            writer.pending = 0;
+            return Err(e);
+        }
+        writer.commit()
+    }
+}
+
+impl Deref for DeferredSidecarWriter {
+    type Target = Sidecar;
+
+    fn deref(&self) -> &Sidecar {
+        &self.sidecar
+    }
+}
+
+impl Drop for DeferredSidecarWriter {
+    fn drop(&mut self) {
+        if self.is_dirty() {
+            eprintln!(
+                "Warning: Discarding {} uncommitted modification(s) of the sidecar of {}",
+                self.pending,
+                self.image_path.display()
+            );
+        }
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scan::{read_sidecar, scan_file};
+    use crate::{JozinError, Tag};
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn scanned_photo(dir: &Path) -> PathBuf {
+        let photo = dir.join("a.jpg");
+        fs::write(&photo, b"image").unwrap();
+        scan_file(&photo, false).unwrap();
+        photo
+    }
+
+    #[test]
+    fn test_modifications_are_written_once_on_commit() {
+        let temp_dir = TempDir::new().unwrap();
+        let photo = scanned_photo(temp_dir.path());
+
+        let mut writer = DeferredSidecarWriter::load(&photo).unwrap();
+        for label in ["beach", "sunset"] {
+            writer.modify(|sidecar| {
+                sidecar.tags.push(Tag::from_label(label));
+                Ok(())
+            })
+            .unwrap();
+        }
+        assert_eq!(writer.tags.len(), 2);
+        assert!(read_sidecar(&photo).unwrap().tags.is_empty());
+        writer.commit().unwrap();
+
+        assert_eq!(read_sidecar(&photo).unwrap().tags.len(), 2);
+        // One write: the scanned version is the only backup
+        assert!(temp_dir.path().join("a.jpg.json.bak1").exists());
+        assert!(!temp_dir.path().join("a.jpg.json.bak2").exists());
+    }
+
+    #[test]
+    fn test_uncommitted_and_failed_changes_are_discarded() {
+        let temp_dir = TempDir::new().unwrap();
+        let photo = scanned_photo(temp_dir.path());
+
+        let mut writer = DeferredSidecarWriter::load(&photo).unwrap();
+        writer.modify(|sidecar| {
+            sidecar.tags.push(Tag::from_label("lost"));
+            Ok(())
+        })
+        .unwrap();
+        drop(writer);
+        assert!(read_sidecar(&photo).unwrap().tags.is_empty());
+
+        let err = DeferredSidecarWriter::transaction(&photo, |sidecar| {
+            sidecar.tags.push(Tag::from_label("half"));
+            Err(JozinError::UserError { message: "step failed".to_string() })
+        })
+        .unwrap_err();
+        assert!(matches!(err, JozinError::UserError { .. }));
+        assert!(read_sidecar(&photo).unwrap().tags.is_empty());
+
+        DeferredSidecarWriter::transaction(&photo, |sidecar| {
+            sidecar.tags.push(Tag::from_label("kept"));
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(read_sidecar(&photo).unwrap().tags[0].label, "kept");
+        assert!(DeferredSidecarWriter::load(&temp_dir.path().join("missing.jpg")).is_err());
+    }
+}
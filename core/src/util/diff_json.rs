@@ -0,0 +1,357 @@
+//! Unified diffs of JSON values, for showing sidecar changes on a terminal
+//!
+//! [`Sidecar::diff()`](crate::Sidecar::diff) lists changed fields for
+//! programs; [`json_unified_diff()`] renders the same comparison the way
+//! `git diff` shows text:
+//!
+//! ```text
+//! --- old
+//! +++ new
+//! @@ -1,6 +1,6 @@ image.camera_make
+//!  {
+//!    "image": {
+//! -    "camera_make": "Canon Inc."
+//! +    "camera_make": "Canon"
+//!      "width": 8192
+//!    }
+//! ```
+//!
+//! Both values are laid out like pretty-printed JSON with sorted keys but
+//! without trailing commas, so adding a field or array element changes
+//! exactly one line. The hunk header names the JSON path of its first
+//! change (`faces[2].person`); unchanged lines around each change are kept
+//! as context. [`colorize_diff()`] adds ANSI colours for terminals.
+
+use crate::FieldChange;
+use serde_json::Value;
+use std::fmt::Write;
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+/// Unchanged lines shown around each change by default (as `git diff`).
+pub const DEFAULT_CONTEXT_LINES: usize = 3;
+
+const GREEN: &str = "\x1b[32m";
+const RED: &str = "\x1b[31m";
+const CYAN: &str = "\x1b[36m";
+const BOLD: &str = "\x1b[1m";
+const RESET: &str = "\x1b[0m";
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Unified diff of two JSON values, with `--- old` / `+++ new` headers.
+///
+/// Returns an empty string if the values are equal. See the module docs for
+/// the layout.
+///
+/// # Example
+///
+/// ```
+/// use jozin_core::util::diff_json::json_unified_diff;
+/// use serde_json::json;
+///
+/// let diff = json_unified_diff(&json!({"a": 1, "b": 2}), &json!({"a": 1, "b": 3}), 1);
+/// assert_eq!(diff, "--- old\n+++ new\n@@ -2,3 +2,3 @@ b\n   \"a\": 1\n-  \"b\": 2\n+  \"b\": 3\n }\n");
+/// ```
+pub fn json_unified_diff(old: &Value, new: &Value, context_lines: usize) -> String {
+    json_unified_diff_labeled(old, new, "old", "new", context_lines)
+}
+
+/// Like [`json_unified_diff()`], with the names shown in the `---`/`+++`
+/// headers (e.g. file paths).
+pub fn json_unified_diff_labeled(
+    old: &Value,
+    new: &Value,
+    old_label: &str,
+    new_label: &str,
+    context_lines: usize,
+) -> String {
+    let (old_lines, new_lines) = (render_lines(old), render_lines(new));
+    let ops = diff_lines(&old_lines, &new_lines);
+    if ops.iter().all(|op| matches!(op, Op::Equal(..))) {
+        return String::new();
+    }
+
+    let mut out = String::new();
+    let _ = writeln!(out, "--- {}", old_label);
+    let _ = writeln!(out, "+++ {}", new_label);
+    for (start, end) in hunk_ranges(&ops, context_lines) {
+        let hunk = &ops[start..end];
+        let (old_start, new_start) = hunk_start(&ops, start);
+        let old_len = hunk.iter().filter(|op| !matches!(op, Op::Insert(_))).count();
+        let new_len = hunk.iter().filter(|op| !matches!(op, Op::Delete(_))).count();
+        let path = hunk.iter().find_map(|op| match op {
+            Op::Delete(i) => Some(&old_lines[*i].path),
+            Op::Insert(j) => Some(&new_lines[*j].path),
+            Op::Equal(..) => None,
+        });
+        let _ = writeln!(
+            out,
+            "@@ -{} +{} @@ {}",
+            range(old_start, old_len),
+            range(new_start, new_len),
+            path.map(String::as_str).unwrap_or_default()
+        );
+        for op in hunk {
+            let _ = match op {
+                Op::Equal(i, _) => writeln!(out, " {}", old_lines[*i].text),
+                Op::Delete(i) => writeln!(out, "-{}", old_lines[*i].text),
+                Op::Insert(j) => writeln!(out, "+{}", new_lines[*j].text),
+            };
+        }
+    }
+    out
+}
+
+/// Field-level changes between two JSON values (see
+/// [`Sidecar::diff()`](crate::Sidecar::diff)), sorted by path.
+pub fn json_field_changes(old: &Value, new: &Value) -> Vec<FieldChange> {
+    let mut changes = Vec::new();
+    crate::diff_values("", Some(old), Some(new), &mut changes);
+    changes
+}
+
+/// Colours a unified diff for terminals: additions green, removals red,
+/// hunk headers cyan and file headers bold.
+pub fn colorize_diff(diff: &str) -> String {
+    let mut out = String::with_capacity(diff.len() + diff.len() / 4);
+    for line in diff.lines() {
+        let color = if line.starts_with("+++") || line.starts_with("---") {
+            Some(BOLD)
+        } else if line.starts_with('+') {
+            Some(GREEN)
+        } else if line.starts_with('-') {
+            Some(RED)
+        } else if line.starts_with("@@") {
+            Some(CYAN)
+        } else {
+            None
+        };
+        match color {
+            Some(color) => {
+                let _ = writeln!(out, "{}{}{}", color, line, RESET);
+            }
+            None => {
+                let _ = writeln!(out, "{}", line);
+            }
+        }
+    }
+    out
+}
+
+// ============================================================================
+// Internal Helpers
+// ============================================================================
+
+/// One rendered line and the JSON path it belongs to.
+struct Line {
+    text: String,
+    path: String,
+}
+
+/// An edit step: a line kept (old and new index), removed or added.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Equal(usize, usize),
+    Delete(usize),
+    Insert(usize),
+}
+
+/// Lays out a value as indented lines (see the module docs).
+fn render_lines(value: &Value) -> Vec<Line> {
+    let mut lines = Vec::new();
+    render(value, None, "", 0, &mut lines);
+    lines
+}
+
+fn render(value: &Value, key: Option<&str>, path: &str, depth: usize, lines: &mut Vec<Line>) {
+    let indent = "  ".repeat(depth);
+    let prefix = match key {
+        Some(key) => format!("{}{}: ", indent, Value::from(key)),
+        None => indent.clone(),
+    };
+    let line = |text: String| Line { text, path: path.to_string() };
+
+    match value {
+        Value::Object(map) if !map.is_empty() => {
+            lines.push(line(format!("{}{{", prefix)));
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort();
+            for key in keys {
+                let child = if path.is_empty() { key.clone() } else { format!("{}.{}", path, key) };
+                render(&map[key], Some(key), &child, depth + 1, lines);
+            }
+            lines.push(line(format!("{}}}", indent)));
+        }
+        Value::Array(items) if !items.is_empty() => {
+            lines.push(line(format!("{}[", prefix)));
+            for (index, item) in items.iter().enumerate() {
+                render(item, None, &format!("{}[{}]", path, index), depth + 1, lines);
+            }
+            lines.push(line(format!("{}]", indent)));
+        }
+        scalar => lines.push(line(format!("{}{}", prefix, scalar))),
+    }
+}
+
+/// Longest-common-subsequence edit script between two line lists.
+///
+/// The common prefix and suffix are matched directly; only the lines in
+/// between go through the quadratic table.
+fn diff_lines(old: &[Line], new: &[Line]) -> Vec<Op> {
+    let same = |i: usize, j: usize| old[i].text == new[j].text;
+    let prefix = (0..old.len().min(new.len())).take_while(|&i| same(i, i)).count();
+    let suffix = (0..old.len().min(new.len()) - prefix)
+        .take_while(|&k| same(old.len() - 1 - k, new.len() - 1 - k))
+        .count();
+    let (old_mid, new_mid) = (old.len() - prefix - suffix, new.len() - prefix - suffix);
+
+    // lcs[i][j]: common lines of old[prefix + i..] and new[prefix + j..] (middle part)
+    let width = new_mid + 1;
+    let mut lcs = vec![0u32; (old_mid + 1) * width];
+    for i in (0..old_mid).rev() {
+        for j in (0..new_mid).rev() {
+            lcs[i * width + j] = if same(prefix + i, prefix + j) {
+                lcs[(i + 1) * width + j + 1] + 1
+            } else {
+                lcs[(i + 1) * width + j].max(lcs[i * width + j + 1])
+            };
+        }
+    }
+
+    let mut ops: Vec<Op> = (0..prefix).map(|i| Op::Equal(i, i)).collect();
+    let (mut i, mut j) = (0, 0);
+    while i < old_mid || j < new_mid {
+        if i < old_mid && j < new_mid && same(prefix + i, prefix + j) {
+            ops.push(Op::Equal(prefix + i, prefix + j));
+            i += 1;
+            j += 1;
+        } else if j < new_mid && (i == old_mid || lcs[i * width + j + 1] >= lcs[(i + 1) * width + j]) {
+            ops.push(Op::Insert(prefix + j));
+            j += 1;
+        } else {
+            ops.push(Op::Delete(prefix + i));
+            i += 1;
+        }
+    }
+    // Removals before additions within a block of changes, as `git diff` shows them
+    for block in ops[prefix..].split_mut(|op| matches!(op, Op::Equal(..))) {
+        block.sort_by_key(|op| matches!(op, Op::Insert(_)));
+    }
+    ops.extend((0..suffix).map(|k| Op::Equal(old_mid + prefix + k, new_mid + prefix + k)));
+    ops
+}
+
+/// Ranges of `ops` shown as hunks: each change with up to `context` equal
+/// lines on either side, merged where they touch.
+fn hunk_ranges(ops: &[Op], context: usize) -> Vec<(usize, usize)> {
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+    for (index, _) in ops.iter().enumerate().filter(|(_, op)| !matches!(op, Op::Equal(..))) {
+        let (start, end) = (index.saturating_sub(context), (index + context + 1).min(ops.len()));
+        match ranges.last_mut() {
+            Some(last) if start <= last.1 => last.1 = end,
+            _ => ranges.push((start, end)),
+        }
+    }
+    ranges
+}
+
+/// 1-based old and new line numbers of the first line of a hunk starting at `ops[start]`.
+fn hunk_start(ops: &[Op], start: usize) -> (usize, usize) {
+    let before = &ops[..start];
+    let old = before.iter().filter(|op| !matches!(op, Op::Insert(_))).count();
+    let new = before.iter().filter(|op| !matches!(op, Op::Delete(_))).count();
+    (old + 1, new + 1)
+}
+
+/// `start,len` of a hunk side; an empty side names the line before it.
+fn range(start: usize, len: usize) -> String {
+    if len == 0 {
+        format!("{},0", start - 1)
+    } else {
+        format!("{},{}", start, len)
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_unified_diff_of_objects() {
+        let old = json!({
+            "schema_version": "1.0.0",
+            "image": { "camera_make": "Canon Inc.", "width": 8192 },
+            "tags": ["beach", "sunset"]
+        });
+        let new = json!({
+            "schema_version": "2.0.0",
+            "image": { "camera_make": "Canon", "width": 8192 },
+            "tags": ["beach", "family", "sunset"]
+        });
+
+        let expected = "\
+--- old
++++ new
+@@ -1,11 +1,12 @@ image.camera_make
+ {
+   \"image\": {
+-    \"camera_make\": \"Canon Inc.\"
++    \"camera_make\": \"Canon\"
+     \"width\": 8192
+   }
+-  \"schema_version\": \"1.0.0\"
++  \"schema_version\": \"2.0.0\"
+   \"tags\": [
+     \"beach\"
++    \"family\"
+     \"sunset\"
+   ]
+ }
+";
+        assert_eq!(json_unified_diff(&old, &new, 3), expected);
+    }
+
+    #[test]
+    fn test_unified_diff_splits_distant_hunks() {
+        let old = json!({ "a": 1, "b": 0, "c": 0, "d": 0, "e": 0, "f": 0, "g": 1 });
+        let new = json!({ "a": 2, "b": 0, "c": 0, "d": 0, "e": 0, "f": 0 });
+
+        let diff = json_unified_diff_labeled(&old, &new, "a.json", "b.json", 1);
+        assert_eq!(
+            diff,
+            "--- a.json\n+++ b.json\n\
+             @@ -1,3 +1,3 @@ a\n {\n-  \"a\": 1\n+  \"a\": 2\n   \"b\": 0\n\
+             @@ -7,3 +7,2 @@ g\n   \"f\": 0\n-  \"g\": 1\n }\n"
+        );
+    }
+
+    #[test]
+    fn test_unified_diff_equal_and_array_removal() {
+        assert_eq!(json_unified_diff(&json!({"a": [1, 2]}), &json!({"a": [1, 2]}), 3), "");
+
+        let diff = json_unified_diff(&json!([{"person": "Alice"}, 2]), &json!([2]), 0);
+        assert_eq!(diff, "--- old\n+++ new\n@@ -2,3 +1,0 @@ [0]\n-  {\n-    \"person\": \"Alice\"\n-  }\n");
+    }
+
+    #[test]
+    fn test_colorize_and_field_changes() {
+        let diff = json_unified_diff(&json!({"a": 1}), &json!({"a": 2}), 0);
+        let colored = colorize_diff(&diff);
+        assert!(colored.contains("\x1b[31m-  \"a\": 1\x1b[0m"));
+        assert!(colored.contains("\x1b[32m+  \"a\": 2\x1b[0m"));
+
+        let changes = json_field_changes(&json!({"a": {"b": 1}}), &json!({"a": {"b": 2, "c": 3}}));
+        let paths: Vec<&str> = changes.iter().map(|c| c.path.as_str()).collect();
+        assert_eq!(paths, vec!["a.b", "a.c"]);
+    }
+}
@@ -0,0 +1,178 @@
+//! File digests with a selectable algorithm
+//!
+//! BLAKE3 is Jožin's native hash (fast, parallelisable). Some tools compare
+//! files by SHA-256 instead (e.g., exiftool, Apple Photos exports), so a
+//! library can be scanned with SHA-256 to make its hashes directly comparable.
+//!
+//! ## Sidecar Keys
+//!
+//! | Algorithm                  | `source` key        | `pipeline_signature.hash_algorithm` |
+//! |----------------------------|---------------------|-------------------------------------|
+//! | [`HashAlgorithm::Blake3`]  | `file_hash_b3`      | `"blake3"`                          |
+//! | [`HashAlgorithm::Sha256`]  | `file_hash_sha256`  | `"sha256"`                          |
+//!
+//! ## Usage
+//!
+//! ```no_run
+//! use jozin_core::util::digest::{compute_hash, HashAlgorithm};
+//! use std::path::Path;
+//!
+//! let hash = compute_hash(Path::new("/photos/IMG_1234.JPG"), HashAlgorithm::Sha256)?;
+//! println!("sha256:{}", hash);
+//! # Ok::<(), jozin_core::JozinError>(())
+//! ```
+
+use crate::hash::{compute_hash_parallel, DEFAULT_PARALLEL_CHUNK_BYTES};
+use crate::util::io_budget::{IoBudget, RateLimitedReader};
+use crate::{JozinError, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use std::str::FromStr;
+
+// ============================================================================
+// Public Types
+// ============================================================================
+
+/// Content hash algorithm recorded in a sidecar.
+///
+/// # Variants
+///
+/// - `Blake3`: BLAKE3 (default)
+/// - `Sha256`: SHA-256, for comparison with external tools
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HashAlgorithm {
+    #[default]
+    Blake3,
+    Sha256,
+}
+
+impl HashAlgorithm {
+    /// Name stored in `pipeline_signature.hash_algorithm`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HashAlgorithm::Blake3 => "blake3",
+            HashAlgorithm::Sha256 => "sha256",
+        }
+    }
+}
+
+impl fmt::Display for HashAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for HashAlgorithm {
+    type Err = JozinError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "blake3" => Ok(HashAlgorithm::Blake3),
+            "sha256" | "sha-256" => Ok(HashAlgorithm::Sha256),
+            other => Err(JozinError::ValidationError {
+                message: format!("Unknown hash algorithm '{}' (expected blake3 or sha256)", other),
+            }),
+        }
+    }
+}
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Computes the SHA-256 hash of a file as a lowercase hex string.
+///
+/// # Errors
+///
+/// Returns `JozinError::IoError` if the file cannot be read.
+pub fn compute_sha256_hash(file_path: &Path) -> Result<String> {
+    sha256_from_reader(File::open(file_path)?)
+}
+
+/// Computes the SHA-256 hash of a file, reading at most `budget` bytes per second.
+///
+/// # Errors
+///
+/// Returns `JozinError::IoError` if the file cannot be read.
+pub fn compute_sha256_hash_limited(file_path: &Path, budget: IoBudget) -> Result<String> {
+    sha256_from_reader(RateLimitedReader::new(File::open(file_path)?, budget))
+}
+
+/// Computes a file hash with the given algorithm.
+///
+/// BLAKE3 uses [`compute_hash_parallel()`] (chunked for large files).
+///
+/// # Errors
+///
+/// Returns `JozinError::IoError` if the file cannot be read.
+pub fn compute_hash(file_path: &Path, algorithm: HashAlgorithm) -> Result<String> {
+    match algorithm {
+        HashAlgorithm::Blake3 => compute_hash_parallel(file_path, DEFAULT_PARALLEL_CHUNK_BYTES),
+        HashAlgorithm::Sha256 => compute_sha256_hash(file_path),
+    }
+}
+
+// ============================================================================
+// Internal Helpers
+// ============================================================================
+
+/// Hashes everything `reader` yields with SHA-256, as a lowercase hex string.
+fn sha256_from_reader<R: Read>(mut reader: R) -> Result<String> {
+    let mut hasher = Sha256::new();
+    let mut buffer = [0u8; 8192];
+
+    loop {
+        let bytes_read = reader.read(&mut buffer)?;
+        if bytes_read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..bytes_read]);
+    }
+
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect())
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hash::compute_blake3_hash;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_compute_sha256_hash_known_vector() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("abc.jpg");
+        std::fs::write(&path, b"abc").unwrap();
+
+        assert_eq!(
+            compute_sha256_hash(&path).unwrap(),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_eq!(
+            compute_hash(&path, HashAlgorithm::Blake3).unwrap(),
+            compute_blake3_hash(&path).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_hash_algorithm_parse_and_display() {
+        assert_eq!("SHA256".parse::<HashAlgorithm>().unwrap(), HashAlgorithm::Sha256);
+        assert_eq!("blake3".parse::<HashAlgorithm>().unwrap(), HashAlgorithm::Blake3);
+        assert!("md5".parse::<HashAlgorithm>().is_err());
+        assert_eq!(HashAlgorithm::Sha256.to_string(), "sha256");
+        assert_eq!(HashAlgorithm::default(), HashAlgorithm::Blake3);
+    }
+}
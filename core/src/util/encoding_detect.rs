@@ -0,0 +1,388 @@
+//! Structural checks of image files without decoding them
+//!
+//! A truncated download or a file recovered from a damaged card often has a
+//! valid signature but broken structure. [`validate_image_encoding()`] finds
+//! the common cases from the byte stream alone, so `scan --validate-encoding`
+//! can fail such files before parsing their EXIF, and
+//! `verify --validate-encoding` can report them without an image decoder:
+//!
+//! | Format | Checks                                                          | Bytes read   |
+//! |--------|-----------------------------------------------------------------|--------------|
+//! | JPEG   | `FF D8` SOI, segment lengths up to the scan data, a JFIF, EXIF, COM or DQT segment, `FF D9` EOI at the end | whole file |
+//! | PNG    | IHDR is the first chunk and its CRC matches                     | 33           |
+//! | TIFF   | IFD chain, EXIF/GPS/sub-IFDs and tag values within the file     | IFDs only    |
+//! | Others | Signature only                                                  | 12           |
+//!
+//! A file whose signature is another format than expected is invalid.
+//! Trailing zero padding after the JPEG EOI marker is accepted. These checks
+//! don't replace a full decode (`verify --check-encoding`): corrupt
+//! entropy-coded data behind valid structure is not found.
+
+use crate::util::image_format::{ImageFormat, MAGIC_BYTES_LEN};
+use crate::Result;
+use flate2::Crc;
+use serde::{Deserialize, Serialize};
+use std::cell::Cell;
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+/// PNG signature, IHDR length, type, 13 data bytes and CRC.
+const PNG_IHDR_END: usize = 33;
+
+/// IFDs followed at most, against offset loops.
+const MAX_TIFF_IFDS: usize = 256;
+
+/// TIFF tags whose value is the offset of another IFD (SubIFDs, EXIF, GPS,
+/// interoperability).
+const TIFF_IFD_POINTER_TAGS: &[u16] = &[0x014A, 0x8769, 0x8825, 0xA005];
+
+// ============================================================================
+// Public Types
+// ============================================================================
+
+/// Outcome of [`validate_image_encoding()`].
+///
+/// # Fields
+///
+/// - `format_detected`: Format of the file's signature (the expected format
+///   if the signature is not recognised)
+/// - `encoding_valid`: All checks for the format passed
+/// - `first_error`: The first problem found
+/// - `checked_bytes`: Bytes read from the file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EncodingValidationResult {
+    pub format_detected: ImageFormat,
+    pub encoding_valid: bool,
+    pub first_error: Option<String>,
+    pub checked_bytes: u64,
+}
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Checks the structure of the image at `path`, which should be a `format`
+/// file (see the module documentation for the checks per format).
+///
+/// # Errors
+///
+/// - `JozinError::IoError` if the file cannot be read (structural problems
+///   are reported in the result, not as errors)
+///
+/// # Example
+///
+/// ```no_run
+/// use jozin_core::util::encoding_detect::validate_image_encoding;
+/// use jozin_core::util::image_format::ImageFormat;
+/// use std::path::Path;
+///
+/// let result = validate_image_encoding(Path::new("/photos/IMG_1234.JPG"), ImageFormat::Jpeg)?;
+/// if !result.encoding_valid {
+///     println!("Broken: {}", result.first_error.unwrap_or_default());
+/// }
+/// # Ok::<(), jozin_core::JozinError>(())
+/// ```
+pub fn validate_image_encoding(path: &Path, format: ImageFormat) -> Result<EncodingValidationResult> {
+    let mut file = File::open(path)?;
+    let mut header = Vec::with_capacity(MAGIC_BYTES_LEN);
+    file.by_ref().take(MAGIC_BYTES_LEN as u64).read_to_end(&mut header)?;
+
+    let invalid = |format_detected, error: String, checked_bytes| EncodingValidationResult {
+        format_detected,
+        encoding_valid: false,
+        first_error: Some(error),
+        checked_bytes,
+    };
+    let detected = match ImageFormat::from_magic_bytes(&header) {
+        Some(detected) if detected == format => detected,
+        Some(detected) => {
+            return Ok(invalid(detected, format!("File is {}, not {}", detected, format), header.len() as u64));
+        }
+        None => {
+            return Ok(invalid(format, format!("No {} signature", format), header.len() as u64));
+        }
+    };
+
+    let (checked_bytes, error) = match format {
+        ImageFormat::Jpeg => {
+            let data = fs::read(path)?;
+            (data.len() as u64, check_jpeg(&data).err())
+        }
+        ImageFormat::Png => {
+            let mut data = Vec::with_capacity(PNG_IHDR_END);
+            File::open(path)?.take(PNG_IHDR_END as u64).read_to_end(&mut data)?;
+            (data.len() as u64, check_png(&data).err())
+        }
+        ImageFormat::Tiff => check_tiff(&mut file)?,
+        ImageFormat::WebP | ImageFormat::Heic | ImageFormat::Gif => (header.len() as u64, None),
+    };
+    Ok(EncodingValidationResult {
+        format_detected: detected,
+        encoding_valid: error.is_none(),
+        first_error: error,
+        checked_bytes,
+    })
+}
+
+/// Format a file at `path` is expected to have: from its extension, or
+/// from its signature for extensions without a single format (RAW files,
+/// which are mostly TIFF-based). `None` if neither is known.
+///
+/// # Errors
+///
+/// - `JozinError::IoError` if the signature cannot be read
+pub fn expected_image_format(path: &Path) -> Result<Option<ImageFormat>> {
+    let from_extension = path.extension().and_then(|ext| ext.to_str()).and_then(ImageFormat::from_name);
+    match from_extension {
+        Some(format) => Ok(Some(format)),
+        None => crate::util::image_format::detect_format_from_magic_bytes(path),
+    }
+}
+
+// ============================================================================
+// Internal Helpers
+// ============================================================================
+
+/// Walks the JPEG segments up to the start of scan (SOS) and checks the
+/// end-of-image marker.
+fn check_jpeg(data: &[u8]) -> std::result::Result<(), String> {
+    let mut pos = 2;
+    let mut has_known_segment = false;
+    while pos + 1 < data.len() {
+        if data[pos] != 0xFF {
+            return Err(format!("Expected a marker at byte {}, found 0x{:02X}", pos, data[pos]));
+        }
+        let marker = data[pos + 1];
+        match marker {
+            // Fill byte before a marker
+            0xFF => {
+                pos += 1;
+                continue;
+            }
+            // Standalone markers have no length
+            0x01 | 0xD0..=0xD7 => {
+                pos += 2;
+                continue;
+            }
+            0xD9 => break,
+            _ => {}
+        }
+        let length = match data.get(pos + 2..pos + 4) {
+            Some(bytes) => usize::from(u16::from_be_bytes([bytes[0], bytes[1]])),
+            None => return Err(format!("Segment 0xFF{:02X} at byte {} is truncated", marker, pos)),
+        };
+        let end = pos + 2 + length;
+        if length < 2 || end > data.len() {
+            return Err(format!("Segment 0xFF{:02X} at byte {} extends past the end of the file", marker, pos));
+        }
+        let payload = &data[pos + 4..end];
+        has_known_segment |= match marker {
+            0xE0 => payload.starts_with(b"JFIF\0"),
+            0xE1 => payload.starts_with(b"Exif\0\0"),
+            0xFE | 0xDB => true,
+            _ => false,
+        };
+        if marker == 0xDA {
+            break;
+        }
+        pos = end;
+    }
+    if !has_known_segment {
+        return Err("No JFIF, EXIF, COM or DQT segment".to_string());
+    }
+
+    let end = data.iter().rposition(|byte| *byte != 0).map_or(0, |last| last + 1);
+    if !data[..end].ends_with(&[0xFF, 0xD9]) {
+        return Err("Missing end-of-image marker (FF D9); the file may be truncated".to_string());
+    }
+    Ok(())
+}
+
+/// Checks that the first chunk is a 13-byte IHDR with a matching CRC.
+fn check_png(data: &[u8]) -> std::result::Result<(), String> {
+    if data.len() < PNG_IHDR_END {
+        return Err("File ends inside the IHDR chunk".to_string());
+    }
+    let length = u32::from_be_bytes([data[8], data[9], data[10], data[11]]);
+    if &data[12..16] != b"IHDR" || length != 13 {
+        return Err("First chunk is not a 13-byte IHDR".to_string());
+    }
+    let mut crc = Crc::new();
+    crc.update(&data[12..29]);
+    let stored = u32::from_be_bytes([data[29], data[30], data[31], data[32]]);
+    if crc.sum() != stored {
+        return Err(format!("IHDR CRC mismatch (stored {:08X}, computed {:08X})", stored, crc.sum()));
+    }
+    Ok(())
+}
+
+/// Follows the IFD chain (and IFD pointer tags) from the TIFF header,
+/// checking that every IFD and out-of-line tag value lies within the file.
+///
+/// Returns the bytes read and the first problem.
+fn check_tiff(file: &mut File) -> Result<(u64, Option<String>)> {
+    let file_len = file.metadata()?.len();
+    let checked = Cell::new(0);
+    let mut read_at = |offset: u64, len: usize| -> Result<Vec<u8>> {
+        let mut buf = vec![0; len];
+        file.seek(SeekFrom::Start(offset))?;
+        file.read_exact(&mut buf)?;
+        checked.set(checked.get() + len as u64);
+        Ok(buf)
+    };
+
+    let header = match file_len {
+        8.. => read_at(0, 8)?,
+        _ => return Ok((file_len, Some("File ends inside the TIFF header".to_string()))),
+    };
+    let big_endian = header.starts_with(b"MM");
+    let u16_at = |bytes: &[u8], at: usize| {
+        let pair = [bytes[at], bytes[at + 1]];
+        if big_endian { u16::from_be_bytes(pair) } else { u16::from_le_bytes(pair) }
+    };
+    let u32_at = |bytes: &[u8], at: usize| {
+        let quad = [bytes[at], bytes[at + 1], bytes[at + 2], bytes[at + 3]];
+        u64::from(if big_endian { u32::from_be_bytes(quad) } else { u32::from_le_bytes(quad) })
+    };
+
+    let mut pending = vec![u32_at(&header, 4)];
+    let mut visited = HashSet::new();
+    while let Some(offset) = pending.pop() {
+        if offset == 0 || !visited.insert(offset) {
+            continue;
+        }
+        if visited.len() > MAX_TIFF_IFDS {
+            return Ok((checked.get(), Some(format!("More than {} IFDs", MAX_TIFF_IFDS))));
+        }
+        if offset + 2 > file_len {
+            return Ok((checked.get(), Some(format!("IFD offset {} is beyond the end of the file ({} bytes)", offset, file_len))));
+        }
+        let count = u64::from(u16_at(&read_at(offset, 2)?, 0));
+        let end = offset + 2 + 12 * count + 4;
+        if end > file_len {
+            return Ok((checked.get(), Some(format!("IFD at byte {} extends past the end of the file", offset))));
+        }
+        let entries = read_at(offset + 2, (12 * count + 4) as usize)?;
+        for entry in entries[..12 * count as usize].chunks_exact(12) {
+            let (tag, kind, values) = (u16_at(entry, 0), u16_at(entry, 2), u32_at(entry, 4));
+            let size = tiff_type_size(kind) * values;
+            let value_offset = u32_at(entry, 8);
+            if size > 4 && value_offset + size > file_len {
+                return Ok((checked.get(), Some(format!("Value of tag 0x{:04X} is beyond the end of the file", tag))));
+            }
+            if TIFF_IFD_POINTER_TAGS.contains(&tag) && values == 1 {
+                pending.push(value_offset);
+            }
+        }
+        pending.push(u32_at(&entries, entries.len() - 4));
+    }
+    Ok((checked.get(), None))
+}
+
+/// Size in bytes of one value of a TIFF field type (0 for unknown types).
+fn tiff_type_size(kind: u16) -> u64 {
+    match kind {
+        1 | 2 | 6 | 7 => 1,
+        3 | 8 => 2,
+        4 | 9 | 11 | 13 => 4,
+        5 | 10 | 12 => 8,
+        _ => 0,
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::test_helpers::{create_synthetic_jpeg, create_synthetic_png, SyntheticExif};
+    use tempfile::TempDir;
+
+    fn validate(path: &Path, format: ImageFormat) -> EncodingValidationResult {
+        validate_image_encoding(path, format).unwrap()
+    }
+
+    #[test]
+    fn test_jpeg_structure() {
+        let temp_dir = TempDir::new().unwrap();
+        let jpeg = create_synthetic_jpeg(temp_dir.path(), "ok.jpg", SyntheticExif::default());
+        let result = validate(&jpeg, ImageFormat::Jpeg);
+        assert!(result.encoding_valid, "{:?}", result.first_error);
+        assert_eq!(result.checked_bytes, fs::metadata(&jpeg).unwrap().len());
+
+        // Zero padding after EOI is fine, a missing EOI is not
+        let mut data = fs::read(&jpeg).unwrap();
+        data.extend([0; 16]);
+        fs::write(&jpeg, &data).unwrap();
+        assert!(validate(&jpeg, ImageFormat::Jpeg).encoding_valid);
+        data.truncate(data.len() - 18);
+        fs::write(&jpeg, &data).unwrap();
+        let result = validate(&jpeg, ImageFormat::Jpeg);
+        assert!(result.first_error.unwrap().contains("end-of-image"));
+
+        // Segment length past the end of the file
+        fs::write(&jpeg, [0xFF, 0xD8, 0xFF, 0xE1, 0x40, 0x00, b'E']).unwrap();
+        assert!(validate(&jpeg, ImageFormat::Jpeg).first_error.unwrap().contains("extends past"));
+        // No known segment
+        fs::write(&jpeg, [0xFF, 0xD8, 0xFF, 0xE2, 0x00, 0x02, 0xFF, 0xD9]).unwrap();
+        assert!(validate(&jpeg, ImageFormat::Jpeg).first_error.unwrap().contains("No JFIF"));
+    }
+
+    #[test]
+    fn test_png_ihdr_crc() {
+        let temp_dir = TempDir::new().unwrap();
+        let png = create_synthetic_png(temp_dir.path(), "ok.png", SyntheticExif::default());
+        let result = validate(&png, ImageFormat::Png);
+        assert!(result.encoding_valid, "{:?}", result.first_error);
+        assert_eq!(result.checked_bytes, PNG_IHDR_END as u64);
+
+        let mut data = fs::read(&png).unwrap();
+        data[20] ^= 0xFF;
+        fs::write(&png, &data).unwrap();
+        assert!(validate(&png, ImageFormat::Png).first_error.unwrap().contains("CRC mismatch"));
+    }
+
+    #[test]
+    fn test_tiff_offsets() {
+        let temp_dir = TempDir::new().unwrap();
+        let tiff = temp_dir.path().join("a.tif");
+        // One IFD at 8 with an ASCII value of 20 bytes at offset 26
+        let mut data = b"II*\0\x08\0\0\0".to_vec();
+        data.extend([1, 0]);
+        data.extend([0x0E, 0x01, 2, 0, 20, 0, 0, 0, 26, 0, 0, 0]);
+        data.extend([0, 0, 0, 0]);
+        data.extend([b'x'; 20]);
+        fs::write(&tiff, &data).unwrap();
+        let result = validate(&tiff, ImageFormat::Tiff);
+        assert!(result.encoding_valid, "{:?}", result.first_error);
+
+        data.truncate(40);
+        fs::write(&tiff, &data).unwrap();
+        assert!(validate(&tiff, ImageFormat::Tiff).first_error.unwrap().contains("tag 0x010E"));
+
+        fs::write(&tiff, b"II*\0\xFF\0\0\0").unwrap();
+        assert!(validate(&tiff, ImageFormat::Tiff).first_error.unwrap().contains("IFD offset 255"));
+    }
+
+    #[test]
+    fn test_signature_mismatch() {
+        let temp_dir = TempDir::new().unwrap();
+        let png = create_synthetic_png(temp_dir.path(), "photo.jpg", SyntheticExif::default());
+        let result = validate(&png, ImageFormat::Jpeg);
+        assert_eq!((result.format_detected, result.encoding_valid), (ImageFormat::Png, false));
+        assert_eq!(expected_image_format(&png).unwrap(), Some(ImageFormat::Jpeg));
+
+        let text = temp_dir.path().join("notes.cr2");
+        fs::write(&text, b"hello").unwrap();
+        assert_eq!(expected_image_format(&text).unwrap(), None);
+        assert!(validate(&text, ImageFormat::Tiff).first_error.unwrap().contains("No TIFF signature"));
+    }
+}
@@ -0,0 +1,386 @@
+//! Writing EXIF fields back into photos
+//!
+//! When a camera clock was wrong (a forgotten DST switch, a trip across
+//! timezones) or GPS is missing, the fix belongs in the photo too, not only
+//! in its sidecar. [`write_exif_fields()`] writes the capture time, GPS
+//! position and camera name with [`exiftool`](https://exiftool.org) as a
+//! subprocess (it must be in `PATH`).
+//!
+//! **This modifies the original file**, which Jožin otherwise never does;
+//! the CLI requires `--allow-modify-originals`. exiftool rewrites the file
+//! in place (`-overwrite_original`), without keeping an `_original` copy.
+//!
+//! | Field               | exiftool tags                                     |
+//! |---------------------|---------------------------------------------------|
+//! | `datetime_original` | `DateTimeOriginal`, `OffsetTimeOriginal`          |
+//! | `gps`               | `GPSLatitude(Ref)`, `GPSLongitude(Ref)`, `GPSAltitude(Ref)` |
+//! | `camera_make`       | `Make`                                            |
+//! | `camera_model`      | `Model`                                           |
+//!
+//! ## Clock correction
+//!
+//! `jozin correct --datetime-offset +01:00:00` shifts the capture time of
+//! every photo by the offset: [`plan_datetime_corrections()`] computes the
+//! corrected times from the sidecars (a preview), and
+//! [`apply_datetime_correction()`] writes one into the photo and updates
+//! `image.datetime_original` of its sidecar to match.
+//!
+//! ## Usage
+//!
+//! ```no_run
+//! use jozin_core::util::exif_write::{write_exif_fields, ExifWriteFields};
+//! use std::path::Path;
+//!
+//! let fields = ExifWriteFields {
+//!     datetime_original: Some("2024-06-01T13:00:00+02:00"),
+//!     gps: Some((50.087, 14.421, Some(235.0))),
+//!     ..ExifWriteFields::default()
+//! };
+//! let result = write_exif_fields(Path::new("/photos/IMG_1234.JPG"), &fields)?;
+//! println!("{}", result.stdout);
+//! # Ok::<(), jozin_core::JozinError>(())
+//! ```
+
+use crate::scan::{load_sidecars, read_sidecar, write_sidecar};
+use crate::util::capability_check::CapabilityChecker;
+use crate::{JozinError, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use time::format_description::well_known::Rfc3339;
+use time::{Duration, OffsetDateTime};
+
+// ============================================================================
+// Public Types
+// ============================================================================
+
+/// EXIF fields to write; `None` leaves a field untouched.
+///
+/// # Fields
+///
+/// - `datetime_original`: Capture time as RFC3339, the format of
+///   `ImageInfo::datetime_original` (`2024-06-01T13:00:00+02:00`)
+/// - `gps`: Latitude and longitude in decimal degrees, altitude in metres
+///   (negative below sea level)
+/// - `camera_make`: Camera manufacturer
+/// - `camera_model`: Camera model
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ExifWriteFields<'a> {
+    pub datetime_original: Option<&'a str>,
+    pub gps: Option<(f64, f64, Option<f64>)>,
+    pub camera_make: Option<&'a str>,
+    pub camera_model: Option<&'a str>,
+}
+
+/// Result of writing the EXIF fields of one photo.
+///
+/// # Fields
+///
+/// - `image_path`: The photo that was modified
+/// - `arguments`: The tag assignments passed to exiftool
+/// - `stdout`: exiftool's output (e.g. `"1 image files updated"`)
+/// - `stderr`: exiftool's warnings
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExifWriteResult {
+    pub image_path: PathBuf,
+    pub arguments: Vec<String>,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+/// A planned capture time correction of one photo.
+///
+/// # Fields
+///
+/// - `image_path`: The photo
+/// - `original`: Capture time recorded in its sidecar (RFC3339)
+/// - `corrected`: Capture time after the offset (RFC3339, same UTC offset)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DatetimeCorrection {
+    pub image_path: PathBuf,
+    pub original: String,
+    pub corrected: String,
+}
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Writes `fields` into the EXIF block of `image_path`.
+///
+/// Nothing is run if no field is set.
+///
+/// # Errors
+///
+/// - `JozinError::ValidationError` if `datetime_original` is not RFC3339 or
+///   `gps` is outside ±90°/±180°
+/// - `JozinError::NotSupported` if exiftool is not installed
+/// - `JozinError::IoError` if exiftool cannot be run or fails (its error
+///   output is included), e.g. for a format it cannot write
+pub fn write_exif_fields(image_path: &Path, fields: &ExifWriteFields) -> Result<ExifWriteResult> {
+    let arguments = exiftool_assignments(fields)?;
+    if arguments.is_empty() {
+        return Ok(ExifWriteResult {
+            image_path: image_path.to_path_buf(),
+            arguments,
+            stdout: String::new(),
+            stderr: String::new(),
+        });
+    }
+
+    let exiftool = CapabilityChecker::new().require("exiftool")?;
+    let output = Command::new(exiftool)
+        .arg("-overwrite_original")
+        .args(&arguments)
+        .arg(image_path)
+        .stdin(Stdio::null())
+        .output()?;
+
+    let (stdout, stderr) = (
+        String::from_utf8_lossy(&output.stdout).trim().to_string(),
+        String::from_utf8_lossy(&output.stderr).trim().to_string(),
+    );
+    if !output.status.success() {
+        return Err(JozinError::IoError {
+            message: format!("exiftool failed on {}: {}", image_path.display(), stderr),
+        });
+    }
+
+    Ok(ExifWriteResult {
+        image_path: image_path.to_path_buf(),
+        arguments,
+        stdout,
+        stderr,
+    })
+}
+
+/// Parses a clock offset such as `+01:00:00`, `-00:30` or `+25:00:00`.
+///
+/// The format is `±H:MM[:SS]`; the sign is required so the direction of the
+/// correction is explicit.
+///
+/// # Errors
+///
+/// Returns `JozinError::ValidationError` if `s` is not such an offset.
+pub fn parse_datetime_offset(s: &str) -> Result<Duration> {
+    let invalid = || JozinError::ValidationError {
+        message: format!("Invalid datetime offset '{}' (expected ±H:MM[:SS], e.g. +01:00:00)", s),
+    };
+    let (negative, rest) = match s.trim().split_at_checked(1) {
+        Some(("+", rest)) => (false, rest),
+        Some(("-", rest)) => (true, rest),
+        _ => return Err(invalid()),
+    };
+    let parts: Vec<&str> = rest.split(':').collect();
+    if !(2..=3).contains(&parts.len()) || parts.iter().any(|p| p.is_empty() || !p.bytes().all(|b| b.is_ascii_digit())) {
+        return Err(invalid());
+    }
+    let number = |part: &str| part.parse::<i64>().map_err(|_| invalid());
+    let (hours, minutes) = (number(parts[0])?, number(parts[1])?);
+    let seconds = parts.get(2).map_or(Ok(0), |part| number(part))?;
+    if minutes >= 60 || seconds >= 60 {
+        return Err(invalid());
+    }
+    let offset = Duration::seconds(hours * 3600 + minutes * 60 + seconds);
+    Ok(if negative { -offset } else { offset })
+}
+
+/// Shifts an RFC3339 capture time by `offset`, keeping its UTC offset.
+///
+/// # Errors
+///
+/// Returns `JozinError::ValidationError` if `datetime` is not RFC3339.
+pub fn shift_datetime(datetime: &str, offset: Duration) -> Result<String> {
+    let shifted = parse_rfc3339(datetime)? + offset;
+    shifted.format(&Rfc3339).map_err(|e| JozinError::InternalError {
+        message: format!("Failed to format capture time: {}", e),
+    })
+}
+
+/// Computes the corrected capture time of every photo with a sidecar under
+/// `root` that records one.
+///
+/// Nothing is modified; see [`apply_datetime_correction()`].
+///
+/// # Errors
+///
+/// - `JozinError::IoError` if `root` does not exist
+/// - `JozinError::ValidationError` if a recorded capture time is not RFC3339
+pub fn plan_datetime_corrections(root: &Path, recursive: bool, offset: Duration) -> Result<Vec<DatetimeCorrection>> {
+    let mut corrections = Vec::new();
+    for sidecar in load_sidecars(root, recursive)? {
+        let Some(original) = sidecar.image.as_ref().and_then(|image| image.datetime_original.clone()) else {
+            continue;
+        };
+        corrections.push(DatetimeCorrection {
+            image_path: PathBuf::from(&sidecar.source.file_path),
+            corrected: shift_datetime(&original, offset)?,
+            original,
+        });
+    }
+    corrections.sort_by(|a, b| a.image_path.cmp(&b.image_path));
+    Ok(corrections)
+}
+
+/// Writes a planned capture time into the photo, then into
+/// `image.datetime_original` of its sidecar (with the usual backup rotation).
+///
+/// # Errors
+///
+/// - As [`write_exif_fields()`]; the sidecar is not touched then
+/// - `JozinError::IoError` if the sidecar cannot be read or written
+pub fn apply_datetime_correction(correction: &DatetimeCorrection) -> Result<ExifWriteResult> {
+    let mut sidecar = read_sidecar(&correction.image_path)?;
+    let fields = ExifWriteFields {
+        datetime_original: Some(&correction.corrected),
+        ..ExifWriteFields::default()
+    };
+    let result = write_exif_fields(&correction.image_path, &fields)?;
+    if let Some(image) = sidecar.image.as_mut() {
+        image.datetime_original = Some(correction.corrected.clone());
+    }
+    write_sidecar(&correction.image_path, &sidecar)?;
+    Ok(result)
+}
+
+// ============================================================================
+// Internal Helpers
+// ============================================================================
+
+fn parse_rfc3339(datetime: &str) -> Result<OffsetDateTime> {
+    OffsetDateTime::parse(datetime, &Rfc3339).map_err(|e| JozinError::ValidationError {
+        message: format!("Invalid capture time '{}' (expected RFC3339): {}", datetime, e),
+    })
+}
+
+/// exiftool `-TAG=VALUE` arguments writing `fields`.
+fn exiftool_assignments(fields: &ExifWriteFields) -> Result<Vec<String>> {
+    let mut args = Vec::new();
+    if let Some(datetime) = fields.datetime_original {
+        let datetime = parse_rfc3339(datetime)?;
+        let local = format!(
+            "{:04}:{:02}:{:02} {:02}:{:02}:{:02}",
+            datetime.year(),
+            u8::from(datetime.month()),
+            datetime.day(),
+            datetime.hour(),
+            datetime.minute(),
+            datetime.second()
+        );
+        let offset = datetime.offset();
+        let sign = if offset.is_negative() { '-' } else { '+' };
+        args.push(format!("-EXIF:DateTimeOriginal={}", local));
+        args.push(format!(
+            "-EXIF:OffsetTimeOriginal={}{:02}:{:02}",
+            sign,
+            offset.whole_hours().unsigned_abs(),
+            offset.minutes_past_hour().unsigned_abs()
+        ));
+    }
+    if let Some((latitude, longitude, altitude)) = fields.gps {
+        if !(-90.0..=90.0).contains(&latitude) || !(-180.0..=180.0).contains(&longitude) {
+            return Err(JozinError::ValidationError {
+                message: format!("GPS position {}, {} is out of range", latitude, longitude),
+            });
+        }
+        args.push(format!("-GPSLatitude={}", latitude.abs()));
+        args.push(format!("-GPSLatitudeRef={}", if latitude < 0.0 { 'S' } else { 'N' }));
+        args.push(format!("-GPSLongitude={}", longitude.abs()));
+        args.push(format!("-GPSLongitudeRef={}", if longitude < 0.0 { 'W' } else { 'E' }));
+        if let Some(altitude) = altitude {
+            args.push(format!("-GPSAltitude={}", altitude.abs()));
+            // 0 = above, 1 = below sea level
+            args.push(format!("-GPSAltitudeRef={}", u8::from(altitude < 0.0)));
+        }
+    }
+    if let Some(make) = fields.camera_make {
+        args.push(format!("-Make={}", make));
+    }
+    if let Some(model) = fields.camera_model {
+        args.push(format!("-Model={}", model));
+    }
+    Ok(args)
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scan::scan_file;
+    use crate::ImageInfo;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_offsets_and_shifting() {
+        assert_eq!(parse_datetime_offset("+01:00:00").unwrap(), Duration::hours(1));
+        assert_eq!(parse_datetime_offset("-00:30").unwrap(), Duration::minutes(-30));
+        assert_eq!(parse_datetime_offset("+25:00:05").unwrap(), Duration::seconds(25 * 3600 + 5));
+        for invalid in ["01:00:00", "+1", "+01:60", "+01:00:00:00", "+a:00", ""] {
+            assert!(parse_datetime_offset(invalid).is_err(), "{}", invalid);
+        }
+
+        assert_eq!(
+            shift_datetime("2024-12-31T23:30:00+02:00", Duration::hours(1)).unwrap(),
+            "2025-01-01T00:30:00+02:00"
+        );
+        assert!(matches!(
+            shift_datetime("2024:12:31 23:30:00", Duration::hours(1)),
+            Err(JozinError::ValidationError { .. })
+        ));
+    }
+
+    #[test]
+    fn test_exiftool_assignments() {
+        let fields = ExifWriteFields {
+            datetime_original: Some("2024-06-01T13:00:00-03:30"),
+            gps: Some((-33.86, 151.2, Some(-5.0))),
+            camera_make: Some("Canon"),
+            camera_model: None,
+        };
+        assert_eq!(
+            exiftool_assignments(&fields).unwrap(),
+            [
+                "-EXIF:DateTimeOriginal=2024:06:01 13:00:00",
+                "-EXIF:OffsetTimeOriginal=-03:30",
+                "-GPSLatitude=33.86",
+                "-GPSLatitudeRef=S",
+                "-GPSLongitude=151.2",
+                "-GPSLongitudeRef=E",
+                "-GPSAltitude=5",
+                "-GPSAltitudeRef=1",
+                "-Make=Canon",
+            ]
+        );
+
+        let out_of_range = ExifWriteFields { gps: Some((91.0, 0.0, None)), ..ExifWriteFields::default() };
+        assert!(exiftool_assignments(&out_of_range).is_err());
+
+        // Nothing to write: exiftool is not run
+        let result = write_exif_fields(Path::new("missing.jpg"), &ExifWriteFields::default()).unwrap();
+        assert!(result.arguments.is_empty() && result.stdout.is_empty());
+    }
+
+    #[test]
+    fn test_plan_datetime_corrections() {
+        let temp_dir = TempDir::new().unwrap();
+        for (name, datetime) in [("a.jpg", Some("2024-06-01T12:00:00Z")), ("b.jpg", None)] {
+            let photo = temp_dir.path().join(name);
+            std::fs::write(&photo, b"image").unwrap();
+            let mut sidecar = scan_file(&photo, true).unwrap().sidecar;
+            sidecar.image = datetime.map(|datetime| ImageInfo {
+                datetime_original: Some(datetime.to_string()),
+                ..ImageInfo::default()
+            });
+            write_sidecar(&photo, &sidecar).unwrap();
+        }
+
+        let corrections = plan_datetime_corrections(temp_dir.path(), false, Duration::hours(-1)).unwrap();
+        assert_eq!(corrections.len(), 1);
+        assert!(corrections[0].image_path.ends_with("a.jpg"));
+        assert_eq!(corrections[0].original, "2024-06-01T12:00:00Z");
+        assert_eq!(corrections[0].corrected, "2024-06-01T11:00:00Z");
+    }
+}
@@ -0,0 +1,259 @@
+//! When sidecar writes are synced to disk
+//!
+//! Syncing a sidecar (`sync_all`) before renaming it into place guarantees it
+//! survives a power loss, but every sync can take 50–500 ms on spinning disks
+//! and network shares. An [`FsyncPolicy`] decides per operation whether
+//! writes are synced:
+//!
+//! | Policy      | Syncs                                                  |
+//! |-------------|--------------------------------------------------------|
+//! | `Always`    | Every write                                            |
+//! | `Never`     | No write (files are still renamed into place atomically) |
+//! | `OnBattery` | Only while running on battery, when a power loss is most likely |
+//! | `Auto`      | On local filesystems, not on network shares            |
+//!
+//! `OnBattery` and `Auto` are resolved to `Always` or `Never` once per
+//! operation with [`FsyncPolicy::resolve()`].
+//!
+//! ## Power State
+//!
+//! | Platform | Source                                                  |
+//! |----------|---------------------------------------------------------|
+//! | Linux    | `/sys/class/power_supply/*/{type,online,status}`        |
+//! | macOS    | `pmset -g batt`                                         |
+//! | Other    | Unknown (treated as battery, i.e. writes are synced)    |
+
+use crate::util::network_drive::FilesystemType;
+use crate::JozinError;
+use std::fmt;
+use std::str::FromStr;
+
+// ============================================================================
+// Public Types
+// ============================================================================
+
+/// Whether sidecar writes are synced to disk (see the module docs).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FsyncPolicy {
+    /// Sync every write
+    Always,
+    /// Never sync
+    Never,
+    /// Sync on battery power, not on AC power
+    OnBattery,
+    /// Sync on local filesystems, not on network shares
+    #[default]
+    Auto,
+}
+
+/// Power source of the machine.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PowerSource {
+    /// Mains power
+    Ac,
+    /// Running on battery
+    Battery,
+}
+
+impl fmt::Display for FsyncPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            FsyncPolicy::Always => "always",
+            FsyncPolicy::Never => "never",
+            FsyncPolicy::OnBattery => "on-battery",
+            FsyncPolicy::Auto => "auto",
+        })
+    }
+}
+
+impl FromStr for FsyncPolicy {
+    type Err = JozinError;
+
+    fn from_str(s: &str) -> crate::Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "always" => Ok(FsyncPolicy::Always),
+            "never" => Ok(FsyncPolicy::Never),
+            "on-battery" => Ok(FsyncPolicy::OnBattery),
+            "auto" => Ok(FsyncPolicy::Auto),
+            other => Err(JozinError::ValidationError {
+                message: format!("Unknown fsync policy '{}' (expected always, never, on-battery or auto)", other),
+            }),
+        }
+    }
+}
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+impl FsyncPolicy {
+    /// Resolves `Auto` and `OnBattery` to `Always` or `Never` for an
+    /// operation on `filesystem`, reading the current power state if needed.
+    ///
+    /// `Always` and `Never` are returned unchanged.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jozin_core::util::fsync_policy::FsyncPolicy;
+    /// use jozin_core::util::network_drive::FilesystemType;
+    ///
+    /// assert_eq!(FsyncPolicy::Auto.resolve(FilesystemType::Smb), FsyncPolicy::Never);
+    /// assert_eq!(FsyncPolicy::Auto.resolve(FilesystemType::Local), FsyncPolicy::Always);
+    /// ```
+    pub fn resolve(self, filesystem: FilesystemType) -> FsyncPolicy {
+        match self {
+            FsyncPolicy::OnBattery => self.resolve_with_power(filesystem, current_power_source()),
+            _ => self.resolve_with_power(filesystem, None),
+        }
+    }
+
+    /// [`FsyncPolicy::resolve()`] with a known power state (`None` if unknown,
+    /// which counts as battery).
+    pub fn resolve_with_power(self, filesystem: FilesystemType, power: Option<PowerSource>) -> FsyncPolicy {
+        match self {
+            FsyncPolicy::Always | FsyncPolicy::Never => self,
+            FsyncPolicy::OnBattery if power == Some(PowerSource::Ac) => FsyncPolicy::Never,
+            FsyncPolicy::OnBattery => FsyncPolicy::Always,
+            FsyncPolicy::Auto if filesystem.is_network() => FsyncPolicy::Never,
+            FsyncPolicy::Auto => FsyncPolicy::Always,
+        }
+    }
+
+    /// Whether a write is synced under this policy.
+    ///
+    /// Unresolved `Auto` and `OnBattery` sync, so writes outside an
+    /// operation that resolved its policy stay durable.
+    pub fn should_sync(self) -> bool {
+        self != FsyncPolicy::Never
+    }
+}
+
+/// Returns the machine's power source, if the platform reports it.
+pub fn current_power_source() -> Option<PowerSource> {
+    #[cfg(target_os = "linux")]
+    {
+        linux_power_source(std::path::Path::new("/sys/class/power_supply"))
+    }
+    #[cfg(target_os = "macos")]
+    {
+        let output = std::process::Command::new("pmset").args(["-g", "batt"]).output().ok()?;
+        parse_pmset(&String::from_utf8_lossy(&output.stdout))
+    }
+    #[cfg(not(any(target_os = "linux", target_os = "macos")))]
+    {
+        None
+    }
+}
+
+// ============================================================================
+// Internal Helpers
+// ============================================================================
+
+/// Reads the power source from a `power_supply` sysfs directory.
+///
+/// An online mains supply means AC; otherwise a discharging battery means
+/// battery. Machines without a battery (desktops, servers) report AC.
+#[cfg(any(target_os = "linux", test))]
+fn linux_power_source(power_supply: &std::path::Path) -> Option<PowerSource> {
+    let read = |dir: &std::path::Path, name: &str| {
+        std::fs::read_to_string(dir.join(name)).map(|value| value.trim().to_string()).unwrap_or_default()
+    };
+    let mut has_battery = false;
+    let mut discharging = false;
+    for entry in std::fs::read_dir(power_supply).ok()?.flatten() {
+        let dir = entry.path();
+        match read(&dir, "type").as_str() {
+            "Mains" if read(&dir, "online") == "1" => return Some(PowerSource::Ac),
+            "Battery" => {
+                has_battery = true;
+                discharging |= read(&dir, "status") == "Discharging";
+            }
+            _ => {}
+        }
+    }
+    match (has_battery, discharging) {
+        (false, _) => Some(PowerSource::Ac),
+        (true, true) => Some(PowerSource::Battery),
+        (true, false) => None,
+    }
+}
+
+/// Parses the first line of `pmset -g batt` (`Now drawing from 'AC Power'`).
+#[cfg(any(target_os = "macos", test))]
+fn parse_pmset(output: &str) -> Option<PowerSource> {
+    let first_line = output.lines().next()?;
+    if first_line.contains("'AC Power'") {
+        Some(PowerSource::Ac)
+    } else if first_line.contains("'Battery Power'") {
+        Some(PowerSource::Battery)
+    } else {
+        None
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_resolve_each_policy() {
+        use FilesystemType::{Local, Nfs, Smb, Unknown};
+        use PowerSource::{Ac, Battery};
+
+        for filesystem in [Local, Smb] {
+            assert_eq!(FsyncPolicy::Always.resolve_with_power(filesystem, Some(Ac)), FsyncPolicy::Always);
+            assert_eq!(FsyncPolicy::Never.resolve_with_power(filesystem, Some(Battery)), FsyncPolicy::Never);
+        }
+        assert_eq!(FsyncPolicy::OnBattery.resolve_with_power(Local, Some(Ac)), FsyncPolicy::Never);
+        assert_eq!(FsyncPolicy::OnBattery.resolve_with_power(Local, Some(Battery)), FsyncPolicy::Always);
+        assert_eq!(FsyncPolicy::OnBattery.resolve_with_power(Local, None), FsyncPolicy::Always);
+        assert_eq!(FsyncPolicy::Auto.resolve_with_power(Local, Some(Ac)), FsyncPolicy::Always);
+        assert_eq!(FsyncPolicy::Auto.resolve_with_power(Unknown, None), FsyncPolicy::Always);
+        assert_eq!(FsyncPolicy::Auto.resolve_with_power(Nfs, None), FsyncPolicy::Never);
+
+        assert!(FsyncPolicy::Auto.should_sync());
+        assert!(!FsyncPolicy::Never.should_sync());
+        assert_eq!("on-battery".parse::<FsyncPolicy>().unwrap(), FsyncPolicy::OnBattery);
+        assert_eq!(FsyncPolicy::OnBattery.to_string(), "on-battery");
+    }
+
+    #[test]
+    fn test_linux_power_source() {
+        let temp_dir = TempDir::new().unwrap();
+        let supply = |name: &str, files: &[(&str, &str)]| {
+            let dir = temp_dir.path().join(name);
+            fs::create_dir_all(&dir).unwrap();
+            for (file, value) in files {
+                fs::write(dir.join(file), format!("{}\n", value)).unwrap();
+            }
+        };
+
+        // No battery at all (desktop)
+        assert_eq!(linux_power_source(temp_dir.path()), Some(PowerSource::Ac));
+
+        supply("BAT0", &[("type", "Battery"), ("status", "Discharging")]);
+        supply("AC", &[("type", "Mains"), ("online", "0")]);
+        assert_eq!(linux_power_source(temp_dir.path()), Some(PowerSource::Battery));
+
+        supply("AC", &[("type", "Mains"), ("online", "1")]);
+        assert_eq!(linux_power_source(temp_dir.path()), Some(PowerSource::Ac));
+
+        assert_eq!(linux_power_source(&temp_dir.path().join("missing")), None);
+    }
+
+    #[test]
+    fn test_parse_pmset() {
+        let ac = "Now drawing from 'AC Power'\n -InternalBattery-0 (id=1)\t100%; charged; 0:00 remaining\n";
+        let battery = "Now drawing from 'Battery Power'\n -InternalBattery-0 (id=1)\t87%; discharging\n";
+        assert_eq!(parse_pmset(ac), Some(PowerSource::Ac));
+        assert_eq!(parse_pmset(battery), Some(PowerSource::Battery));
+        assert_eq!(parse_pmset(""), None);
+    }
+}
@@ -0,0 +1,205 @@
+//! Glob matching with platform-aware case sensitivity
+//!
+//! Include/exclude patterns are written by users who rarely care about case:
+//! on Windows `IMG_1234.JPG` should match `--include "*.jpg"`, because the
+//! filesystem itself treats both spellings as the same file.
+//!
+//! ## Platform Behaviour
+//!
+//! - **Windows**: patterns and candidate paths are folded to lowercase before matching
+//! - **Unix (Linux, macOS)**: matching is case-sensitive (unchanged behaviour)
+//!
+//! Directory scans use [`crate::util::glob_path_case`] instead, which asks
+//! the filesystem being scanned rather than assuming one per platform.
+//!
+//! ## Usage
+//!
+//! ```
+//! use jozin_core::util::glob::build_glob_matcher_ci;
+//! use std::path::Path;
+//!
+//! let matcher = build_glob_matcher_ci(&[String::from("*.jpg")])?;
+//! assert!(matcher.is_match(Path::new("photo.jpg")));
+//! # Ok::<(), jozin_core::JozinError>(())
+//! ```
+
+use crate::{JozinError, Result};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use std::path::Path;
+
+// ============================================================================
+// Public Types
+// ============================================================================
+
+/// A compiled set of glob patterns that optionally ignores case.
+///
+/// Wraps [`globset::GlobSet`] and exposes the same `is_match` interface. When
+/// case-insensitive, both the patterns (at build time) and the candidate path
+/// (at match time) are lowercased.
+#[derive(Debug, Clone)]
+pub struct CiGlobSet {
+    set: GlobSet,
+    case_insensitive: bool,
+}
+
+impl CiGlobSet {
+    /// Returns true if `path` matches at least one pattern in the set.
+    pub fn is_match(&self, path: &Path) -> bool {
+        if self.case_insensitive {
+            let folded = path.to_string_lossy().to_lowercase();
+            self.set.is_match(folded)
+        } else {
+            self.set.is_match(path)
+        }
+    }
+
+    /// Returns true if this matcher folds case before matching.
+    pub fn is_case_insensitive(&self) -> bool {
+        self.case_insensitive
+    }
+}
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Builds a glob matcher using the current platform's case rules.
+///
+/// Case-insensitive on Windows, case-sensitive everywhere else.
+///
+/// # Arguments
+///
+/// * `patterns` - List of glob pattern strings (e.g., `["*.jpg", "**/.jozin/**"]`)
+///
+/// # Errors
+///
+/// Returns `JozinError::ValidationError` if any pattern is invalid.
+pub fn build_glob_matcher_ci(patterns: &[String]) -> Result<CiGlobSet> {
+    build_glob_matcher_with_case(patterns, cfg!(windows))
+}
+
+/// Builds a glob matcher with explicit case handling.
+///
+/// Used by [`build_glob_matcher_ci()`]; exposed so callers (and tests) can
+/// request case-insensitive matching regardless of platform.
+///
+/// # Errors
+///
+/// Returns `JozinError::ValidationError` if any pattern is invalid.
+pub fn build_glob_matcher_with_case(
+    patterns: &[String],
+    case_insensitive: bool,
+) -> Result<CiGlobSet> {
+    let set = if case_insensitive {
+        let folded: Vec<String> = patterns.iter().map(|p| p.to_lowercase()).collect();
+        build_glob_matcher(&folded)?
+    } else {
+        build_glob_matcher(patterns)?
+    };
+
+    Ok(CiGlobSet {
+        set,
+        case_insensitive,
+    })
+}
+
+/// Builds a GlobSet matcher from a list of glob patterns.
+///
+/// This function compiles multiple glob patterns into a single efficient
+/// matcher for use with include/exclude filtering.
+///
+/// # Glob Syntax
+///
+/// Supports standard glob syntax per TASK+PHASE_PLAN.md line 18:
+/// - `*`: Matches any characters except path separators
+/// - `**`: Matches any characters including path separators (recursive)
+/// - `?`: Matches a single character
+/// - `[abc]`: Matches any character in the set
+///
+/// # Arguments
+///
+/// * `patterns` - List of glob pattern strings (e.g., `["*.jpg", "**/.jozin/**"]`)
+///
+/// # Returns
+///
+/// Returns a compiled `GlobSet` that can efficiently match paths against
+/// all patterns.
+///
+/// # Errors
+///
+/// Returns `JozinError::ValidationError` if any pattern is invalid.
+pub(crate) fn build_glob_matcher(patterns: &[String]) -> Result<GlobSet> {
+    let mut builder = GlobSetBuilder::new();
+
+    for pattern in patterns {
+        match Glob::new(pattern) {
+            Ok(glob) => {
+                builder.add(glob);
+            }
+            Err(e) => {
+                return Err(JozinError::ValidationError {
+                    message: format!("Invalid glob pattern '{}': {}", pattern, e),
+                });
+            }
+        }
+    }
+
+    builder.build().map_err(|e| JozinError::InternalError {
+        message: format!("Failed to build glob matcher: {}", e),
+    })
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_glob_matcher_valid_patterns() {
+        let patterns = vec![String::from("*.jpg"), String::from("**/*.png")];
+        let matcher = build_glob_matcher(&patterns).unwrap();
+
+        assert!(matcher.is_match("test.jpg"));
+        assert!(matcher.is_match("dir/subdir/test.png"));
+        assert!(!matcher.is_match("test.txt"));
+    }
+
+    #[test]
+    fn test_build_glob_matcher_invalid_pattern() {
+        let patterns = vec![String::from("[invalid")];
+        let result = build_glob_matcher(&patterns);
+        assert!(result.is_err());
+        assert!(matches!(result.unwrap_err(), JozinError::ValidationError { .. }));
+    }
+
+    #[test]
+    fn test_case_insensitive_matches_uppercase_extension() {
+        // Simulates Windows behaviour on any platform
+        let matcher = build_glob_matcher_with_case(&[String::from("*.jpg")], true).unwrap();
+        assert!(matcher.is_case_insensitive());
+        assert!(matcher.is_match(Path::new("PHOTO.JPG")));
+        assert!(matcher.is_match(Path::new("photo.jpg")));
+
+        // Uppercase patterns are folded too
+        let matcher = build_glob_matcher_with_case(&[String::from("*.JPG")], true).unwrap();
+        assert!(matcher.is_match(Path::new("photo.jpg")));
+    }
+
+    #[test]
+    fn test_case_sensitive_rejects_uppercase_extension() {
+        let matcher = build_glob_matcher_with_case(&[String::from("*.jpg")], false).unwrap();
+        assert!(!matcher.is_case_insensitive());
+        assert!(!matcher.is_match(Path::new("PHOTO.JPG")));
+        assert!(matcher.is_match(Path::new("photo.jpg")));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_platform_default_is_case_sensitive_on_unix() {
+        let matcher = build_glob_matcher_ci(&[String::from("*.jpg")]).unwrap();
+        assert!(!matcher.is_match(Path::new("PHOTO.JPG")));
+    }
+}
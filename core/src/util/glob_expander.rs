@@ -0,0 +1,189 @@
+//! Shell-style brace expansion for include/exclude patterns
+//!
+//! Users familiar with shell globs write `--include "*.{jpg,jpeg,png}"`, but
+//! `globset` patterns are matched one by one and brace syntax is not
+//! expanded the way a shell would. [`expand_braces()`] turns such a pattern
+//! into the plain patterns it stands for before they are compiled.
+//!
+//! ## Syntax
+//!
+//! | Pattern               | Expansion                        |
+//! |-----------------------|----------------------------------|
+//! | `*.{jpg,jpeg}`        | `*.jpg`, `*.jpeg`                |
+//! | `*.{cr{2,3},nef}`     | `*.cr2`, `*.cr3`, `*.nef`        |
+//! | `IMG{,_edit}.jpg`     | `IMG.jpg`, `IMG_edit.jpg`        |
+//! | `\{literal\}.jpg`     | unchanged (escaped braces)       |
+//! | `*.jpg`, `{unclosed`  | unchanged                        |
+//!
+//! Escapes are kept in the output, so `globset` still sees `\{` as a literal
+//! brace.
+//!
+//! ## Usage
+//!
+//! ```
+//! use jozin_core::util::glob_expander::{expand_braces, split_pattern_list};
+//!
+//! assert_eq!(expand_braces("*.{jpg,png}"), ["*.jpg", "*.png"]);
+//! assert_eq!(split_pattern_list("{*.jpg,*.png}, raw/**"), ["{*.jpg,*.png}", "raw/**"]);
+//! ```
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Expands `{a,b,c}` alternatives (nested braces included) into the plain
+/// patterns they stand for, in order.
+///
+/// A pattern without (balanced, unescaped) braces is returned unchanged as
+/// the only element.
+///
+/// # Example
+///
+/// ```
+/// use jozin_core::util::glob_expander::expand_braces;
+///
+/// assert_eq!(expand_braces("*.{cr{2,3},nef}"), ["*.cr2", "*.cr3", "*.nef"]);
+/// assert_eq!(expand_braces("*.jpg"), ["*.jpg"]);
+/// ```
+pub fn expand_braces(pattern: &str) -> Vec<String> {
+    let Some((open, close, commas)) = find_group(pattern) else {
+        return vec![pattern.to_string()];
+    };
+
+    let prefix = &pattern[..open];
+    let suffixes = expand_braces(&pattern[close + 1..]);
+    let mut bounds = Vec::with_capacity(commas.len() + 2);
+    bounds.push(open);
+    bounds.extend(commas);
+    bounds.push(close);
+
+    let mut expanded = Vec::new();
+    for window in bounds.windows(2) {
+        for alternative in expand_braces(&pattern[window[0] + 1..window[1]]) {
+            for suffix in &suffixes {
+                expanded.push(format!("{}{}{}", prefix, alternative, suffix));
+            }
+        }
+    }
+    expanded
+}
+
+/// Splits a comma-separated pattern list on commas outside braces, trimming
+/// each pattern.
+///
+/// Lets `--include "{*.jpg,*.png},raw/**"` keep its brace group intact for
+/// [`expand_braces()`].
+pub fn split_pattern_list(list: &str) -> Vec<String> {
+    let bytes = list.as_bytes();
+    let mut patterns = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 1,
+            b'{' => depth += 1,
+            b'}' => depth = depth.saturating_sub(1),
+            b',' if depth == 0 => {
+                patterns.push(list[start..i].trim().to_string());
+                start = i + 1;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    patterns.push(list[start..].trim().to_string());
+    patterns
+}
+
+// ============================================================================
+// Internal Helpers
+// ============================================================================
+
+/// Finds the first balanced, unescaped brace group.
+///
+/// Returns the byte offsets of `{`, of the matching `}`, and of the commas
+/// directly inside the group. An unmatched `{` is skipped as a literal.
+fn find_group(pattern: &str) -> Option<(usize, usize, Vec<usize>)> {
+    let bytes = pattern.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            b'{' => {
+                if let Some((close, commas)) = find_close(bytes, i) {
+                    return Some((i, close, commas));
+                }
+                i += 1;
+            }
+            _ => i += 1,
+        }
+    }
+    None
+}
+
+/// Finds the `}` matching the `{` at `open`, and the top-level commas between.
+fn find_close(bytes: &[u8], open: usize) -> Option<(usize, Vec<usize>)> {
+    let mut depth = 0usize;
+    let mut commas = Vec::new();
+    let mut i = open + 1;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 1,
+            b'{' => depth += 1,
+            b'}' if depth == 0 => return Some((i, commas)),
+            b'}' => depth -= 1,
+            b',' if depth == 0 => commas.push(i),
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_single_expansion() {
+        assert_eq!(expand_braces("*.{jpg,jpeg}"), ["*.jpg", "*.jpeg"]);
+        assert_eq!(expand_braces("{a,b}/{c,d}"), ["a/c", "a/d", "b/c", "b/d"]);
+    }
+
+    #[test]
+    fn test_nested_expansion() {
+        assert_eq!(expand_braces("*.{cr{2,3},nef}"), ["*.cr2", "*.cr3", "*.nef"]);
+    }
+
+    #[test]
+    fn test_no_braces() {
+        assert_eq!(expand_braces("**/*.jpg"), ["**/*.jpg"]);
+        assert_eq!(expand_braces(""), [""]);
+        assert_eq!(expand_braces("{unclosed"), ["{unclosed"]);
+    }
+
+    #[test]
+    fn test_empty_alternative() {
+        assert_eq!(expand_braces("{,jpg}"), ["", "jpg"]);
+        assert_eq!(expand_braces("IMG{,_edit}.jpg"), ["IMG.jpg", "IMG_edit.jpg"]);
+    }
+
+    #[test]
+    fn test_escaped_braces() {
+        assert_eq!(expand_braces(r"\{a,b\}.jpg"), [r"\{a,b\}.jpg"]);
+        assert_eq!(expand_braces(r"\{x\}.{jpg,png}"), [r"\{x\}.jpg", r"\{x\}.png"]);
+        assert_eq!(expand_braces(r"*.{jpg,a\,b}"), ["*.jpg", r"*.a\,b"]);
+    }
+
+    #[test]
+    fn test_split_pattern_list() {
+        assert_eq!(split_pattern_list("*.jpg, *.png"), ["*.jpg", "*.png"]);
+        assert_eq!(split_pattern_list("{*.jpg,*.jpeg,*.png}"), ["{*.jpg,*.jpeg,*.png}"]);
+        assert_eq!(split_pattern_list(r"a\,b,c"), [r"a\,b", "c"]);
+    }
+}
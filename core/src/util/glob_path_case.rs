@@ -0,0 +1,210 @@
+//! Glob matching that follows the case sensitivity of the filesystem
+//!
+//! [`crate::util::glob`] decides case handling by platform, but the platform
+//! is not what matters: macOS volumes (APFS, HFS+) are usually
+//! case-insensitive, a Linux machine may mount an exFAT card or an SMB share
+//! that is, and a Windows machine may have case-sensitive directories.
+//! A [`CaseAwareGlobMatcher`] asks the filesystem of the scan root instead:
+//!
+//! | Filesystem of the root | `--include "*.JPG"` matches         |
+//! |------------------------|-------------------------------------|
+//! | Case-sensitive         | `photo.JPG`                         |
+//! | Case-insensitive       | `photo.JPG`, `photo.jpg`, `photo.Jpg` |
+//!
+//! ## Detection
+//!
+//! A probe file with a lowercase name is created in the root and looked up
+//! under its uppercase name, then removed. The result is cached per
+//! filesystem (device ID) for the lifetime of the process, so each
+//! filesystem is probed once. If the root is not writable (read-only media),
+//! the platform default is assumed: case-insensitive on Windows and macOS,
+//! case-sensitive elsewhere.
+//!
+//! ## Usage
+//!
+//! ```no_run
+//! use jozin_core::util::glob_path_case::build_case_aware_glob_matcher;
+//! use std::path::Path;
+//!
+//! let matcher = build_case_aware_glob_matcher(&[String::from("*.JPG")], Path::new("/photos"))?;
+//! println!("photo.jpg included: {}", matcher.is_match(Path::new("/photos/photo.jpg")));
+//! # Ok::<(), jozin_core::JozinError>(())
+//! ```
+
+use crate::util::glob::build_glob_matcher;
+use crate::Result;
+use globset::GlobSet;
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+/// Name prefix of the probe file (lowercase; looked up in uppercase).
+const PROBE_PREFIX: &str = ".jozin-case-probe";
+
+// ============================================================================
+// Public Types
+// ============================================================================
+
+/// Glob patterns matched with the case rules of a filesystem.
+///
+/// # Fields
+///
+/// - `matcher`: The compiled patterns (lowercased if not `case_sensitive`)
+/// - `case_sensitive`: Whether the filesystem distinguishes `A` from `a`
+#[derive(Debug, Clone)]
+pub struct CaseAwareGlobMatcher {
+    matcher: GlobSet,
+    case_sensitive: bool,
+}
+
+impl CaseAwareGlobMatcher {
+    /// Returns true if `path` matches at least one pattern. The path is
+    /// lowercased first on case-insensitive filesystems.
+    pub fn is_match(&self, path: &Path) -> bool {
+        if self.case_sensitive {
+            self.matcher.is_match(path)
+        } else {
+            self.matcher.is_match(path.to_string_lossy().to_lowercase())
+        }
+    }
+
+    /// Whether the filesystem (and so this matcher) is case-sensitive.
+    pub fn is_case_sensitive(&self) -> bool {
+        self.case_sensitive
+    }
+}
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Builds a matcher for `patterns` with the case rules of the filesystem
+/// `root` is on (see [`is_case_sensitive_fs()`]).
+///
+/// # Errors
+///
+/// Returns `JozinError::ValidationError` if any pattern is invalid.
+pub fn build_case_aware_glob_matcher(patterns: &[String], root: &Path) -> Result<CaseAwareGlobMatcher> {
+    let case_sensitive = is_case_sensitive_fs(root);
+    let matcher = if case_sensitive {
+        build_glob_matcher(patterns)?
+    } else {
+        build_glob_matcher(&patterns.iter().map(|p| p.to_lowercase()).collect::<Vec<_>>())?
+    };
+    Ok(CaseAwareGlobMatcher { matcher, case_sensitive })
+}
+
+/// Whether the filesystem of `root` (a directory, or a file whose directory
+/// is used) distinguishes upper- and lowercase names.
+///
+/// Probes once per filesystem and caches the answer; falls back to the
+/// platform default if the probe file cannot be created.
+pub fn is_case_sensitive_fs(root: &Path) -> bool {
+    let dir = if root.is_dir() { root } else { root.parent().unwrap_or(Path::new(".")) };
+    let device = device_id(dir);
+    let cache = PROBE_CACHE.get_or_init(Default::default);
+    if let Some(cached) = device.and_then(|device| lock(cache).get(&device).copied()) {
+        return cached;
+    }
+
+    let Some(case_sensitive) = probe(dir) else {
+        return !cfg!(any(windows, target_os = "macos"));
+    };
+    if let Some(device) = device {
+        lock(cache).insert(device, case_sensitive);
+    }
+    case_sensitive
+}
+
+// ============================================================================
+// Internal Helpers
+// ============================================================================
+
+/// Probe results by device ID.
+static PROBE_CACHE: OnceLock<Mutex<HashMap<u64, bool>>> = OnceLock::new();
+
+/// Distinguishes concurrent probes of one process.
+static PROBE_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Creates a lowercase probe file in `dir` and checks whether its uppercase
+/// name resolves to it. `None` if the file cannot be created.
+fn probe(dir: &Path) -> Option<bool> {
+    let name = format!(
+        "{}-{}-{}",
+        PROBE_PREFIX,
+        std::process::id(),
+        PROBE_COUNTER.fetch_add(1, Ordering::Relaxed)
+    );
+    let lower = dir.join(&name);
+    OpenOptions::new().write(true).create_new(true).open(&lower).ok()?;
+    let case_sensitive = !dir.join(name.to_uppercase()).exists();
+    let _ = fs::remove_file(&lower);
+    Some(case_sensitive)
+}
+
+/// Device ID of the filesystem `path` is on, where the platform has one.
+fn device_id(path: &Path) -> Option<u64> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        fs::metadata(path).ok().map(|metadata| metadata.dev())
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = path;
+        None
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_probe_matches_filesystem_and_cleans_up() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("lower.jpg"), b"x").unwrap();
+        let expected = !temp_dir.path().join("LOWER.JPG").exists();
+
+        assert_eq!(probe(temp_dir.path()), Some(expected));
+        assert_eq!(is_case_sensitive_fs(temp_dir.path()), expected);
+        // Cached: the same answer for a file on the same filesystem
+        assert_eq!(is_case_sensitive_fs(&temp_dir.path().join("lower.jpg")), expected);
+        assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 1);
+
+        assert_eq!(probe(&temp_dir.path().join("missing")), None);
+    }
+
+    #[test]
+    fn test_matcher_case_handling() {
+        let patterns = [String::from("*.JPG")];
+        let folded = build_glob_matcher(&[String::from("*.jpg")]).unwrap();
+        let insensitive = CaseAwareGlobMatcher { matcher: folded, case_sensitive: false };
+        assert!(insensitive.is_match(Path::new("/photos/a.jpg")));
+        assert!(insensitive.is_match(Path::new("/photos/a.JPG")));
+
+        let sensitive = CaseAwareGlobMatcher { matcher: build_glob_matcher(&patterns).unwrap(), case_sensitive: true };
+        assert!(sensitive.is_match(Path::new("/photos/a.JPG")));
+        assert!(!sensitive.is_match(Path::new("/photos/a.jpg")));
+
+        let temp_dir = TempDir::new().unwrap();
+        let built = build_case_aware_glob_matcher(&patterns, temp_dir.path()).unwrap();
+        assert_eq!(built.is_match(Path::new("a.jpg")), !built.is_case_sensitive());
+        assert!(build_case_aware_glob_matcher(&[String::from("[")], temp_dir.path()).is_err());
+    }
+}
@@ -0,0 +1,280 @@
+//! Replacing duplicate files with hardlinks
+//!
+//! Identical photos in several albums take their size once per copy.
+//! [`create_hardlink_dedup()`] replaces a duplicate with a hardlink to the
+//! canonical copy, after checking that this is safe:
+//!
+//! - Both paths are on the same filesystem (hardlinks cannot cross devices)
+//! - The canonical file is a regular file, not a symlink or directory
+//! - The duplicate, if it exists, is a regular file with the same size and
+//!   BLAKE3 hash as the canonical file, so no unique original is lost
+//!
+//! The duplicate is replaced atomically (the link is created next to it and
+//! renamed over it), and its sidecar records the canonical path in
+//! `source.hardlink_of`.
+//!
+//! Device IDs and inode numbers are only available on Unix; elsewhere the
+//! cross-device check is left to the filesystem and `inodes_match` is false.
+
+use crate::hash::compute_blake3_hash;
+use crate::scan::{append_to_file_name, parse_sidecar, write_sidecar_with};
+use crate::util::compression::{existing_sidecar_path, read_sidecar_file};
+use crate::util::atomic_rename::atomic_rename;
+use crate::util::library_config::LibraryConfig;
+use crate::util::path_normalise::normalise_sidecar_path;
+use crate::{JozinError, Result};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, Metadata};
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use time::OffsetDateTime;
+
+// ============================================================================
+// Public Types
+// ============================================================================
+
+/// Outcome of [`create_hardlink_dedup()`].
+///
+/// # Fields
+///
+/// - `src`: Canonical file
+/// - `dst`: The duplicate, now a hardlink of `src`
+/// - `inodes_match`: `src` and `dst` were confirmed to be the same inode
+/// - `bytes_saved`: Size of the replaced duplicate (0 if `dst` was already a
+///   hardlink of `src` or did not exist)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HardlinkResult {
+    pub src: PathBuf,
+    pub dst: PathBuf,
+    pub inodes_match: bool,
+    pub bytes_saved: u64,
+}
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Replaces `dst` with a hardlink to `src` and points the sidecar of `dst`
+/// (if it has one) at `src`.
+///
+/// # Arguments
+///
+/// * `src` - Canonical file to keep
+/// * `dst` - Duplicate to replace (created if missing)
+///
+/// # Returns
+///
+/// A [`HardlinkResult`]; linking a file that is already a hardlink of `src`
+/// only updates the sidecar.
+///
+/// # Errors
+///
+/// - `JozinError::UserError` if `src` and `dst` are on different filesystems,
+///   `src` is not a regular file, `dst` is a directory or symlink, or the
+///   contents of `dst` differ from `src` (nothing is changed)
+/// - `JozinError::IoError` if a file cannot be read, linked or renamed
+///   (e.g. missing permissions)
+/// - `JozinError::ValidationError` if the sidecar of `dst` is not valid JSON
+///
+/// # Example
+///
+/// ```no_run
+/// use jozin_core::util::hardlink::create_hardlink_dedup;
+/// use std::path::Path;
+///
+/// let result = create_hardlink_dedup(Path::new("/photos/2024/IMG_1.jpg"), Path::new("/photos/best/IMG_1.jpg"))?;
+/// println!("Saved {} bytes", result.bytes_saved);
+/// # Ok::<(), jozin_core::JozinError>(())
+/// ```
+pub fn create_hardlink_dedup(src: &Path, dst: &Path) -> Result<HardlinkResult> {
+    let src_metadata = fs::symlink_metadata(src)?;
+    if !src_metadata.file_type().is_file() {
+        return Err(JozinError::UserError {
+            message: format!("Cannot hardlink {}: not a regular file", src.display()),
+        });
+    }
+    let dst_metadata = match fs::symlink_metadata(dst) {
+        Ok(metadata) if !metadata.file_type().is_file() => {
+            return Err(JozinError::UserError {
+                message: format!("Cannot replace {} with a hardlink: not a regular file", dst.display()),
+            });
+        }
+        Ok(metadata) => Some(metadata),
+        Err(e) if e.kind() == ErrorKind::NotFound => None,
+        Err(e) => return Err(e.into()),
+    };
+
+    let dst_dir = match dst.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    if let (Some(src_device), Some(dst_device)) = (device_id(&src_metadata), device_id(&fs::metadata(dst_dir)?)) {
+        if src_device != dst_device {
+            return Err(JozinError::UserError {
+                message: format!(
+                    "Cannot hardlink {} to {}: different filesystems",
+                    dst.display(),
+                    src.display()
+                ),
+            });
+        }
+    }
+
+    let already_linked = dst_metadata.as_ref().is_some_and(|metadata| same_inode(metadata, &src_metadata));
+    if let Some(metadata) = dst_metadata.as_ref().filter(|_| !already_linked) {
+        ensure_same_contents(src, &src_metadata, dst, metadata)?;
+    }
+    let bytes_saved = match &dst_metadata {
+        Some(metadata) if !already_linked => metadata.len(),
+        _ => 0,
+    };
+    if !already_linked {
+        // Link next to the duplicate, then replace it in one step
+        let tmp_path = append_to_file_name(dst, ".link.tmp");
+        let _ = fs::remove_file(&tmp_path);
+        fs::hard_link(src, &tmp_path)?;
+        if let Err(e) = atomic_rename(&tmp_path, dst) {
+            let _ = fs::remove_file(&tmp_path);
+            return Err(e);
+        }
+    }
+    let inodes_match = same_inode(&fs::metadata(src)?, &fs::metadata(dst)?);
+
+    update_sidecar(src, dst)?;
+
+    Ok(HardlinkResult {
+        src: src.to_path_buf(),
+        dst: dst.to_path_buf(),
+        inodes_match,
+        bytes_saved,
+    })
+}
+
+// ============================================================================
+// Internal Helpers
+// ============================================================================
+
+/// Records `src` as `source.hardlink_of` in the sidecar of `dst`, if any.
+fn update_sidecar(src: &Path, dst: &Path) -> Result<()> {
+    let naming = LibraryConfig::discover(dst);
+    let sidecar_path = existing_sidecar_path(&naming.sidecar_path(dst));
+    if !sidecar_path.is_file() {
+        return Ok(());
+    }
+    let mut sidecar = parse_sidecar(&read_sidecar_file(&sidecar_path)?)?;
+    let base_dir = std::env::current_dir().unwrap_or_default();
+    sidecar.source.hardlink_of = Some(normalise_sidecar_path(&src.display().to_string(), &base_dir));
+    sidecar.updated_at = OffsetDateTime::now_utc()
+        .format(&time::format_description::well_known::Rfc3339)
+        .map_err(|e| JozinError::InternalError {
+            message: format!("Failed to format timestamp: {}", e),
+        })?;
+    write_sidecar_with(dst, &sidecar, &naming)
+}
+
+/// Fails unless `dst` has the same size and BLAKE3 hash as `src`.
+fn ensure_same_contents(src: &Path, src_metadata: &Metadata, dst: &Path, dst_metadata: &Metadata) -> Result<()> {
+    if src_metadata.len() != dst_metadata.len() || compute_blake3_hash(src)? != compute_blake3_hash(dst)? {
+        return Err(JozinError::UserError {
+            message: format!(
+                "Cannot replace {} with a hardlink to {}: contents differ",
+                dst.display(),
+                src.display()
+            ),
+        });
+    }
+    Ok(())
+}
+
+/// Device ID of the filesystem holding a file, where the platform has one.
+fn device_id(metadata: &Metadata) -> Option<u64> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        Some(metadata.dev())
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = metadata;
+        None
+    }
+}
+
+/// Whether two files are the same inode (always false without inode numbers).
+fn same_inode(a: &Metadata, b: &Metadata) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        (a.dev(), a.ino()) == (b.dev(), b.ino())
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = (a, b);
+        false
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scan::{read_sidecar, scan_file};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_hardlink_dedup_replaces_duplicate() {
+        let temp_dir = TempDir::new().unwrap();
+        let src = temp_dir.path().join("a.jpg");
+        let dst = temp_dir.path().join("b.jpg");
+        fs::write(&src, b"same photo").unwrap();
+        fs::write(&dst, b"same photo").unwrap();
+        scan_file(&dst, false).unwrap();
+
+        let result = create_hardlink_dedup(&src, &dst).unwrap();
+        assert_eq!(result.bytes_saved, 10);
+        assert_eq!(result.inodes_match, cfg!(unix));
+        assert_eq!(fs::read(&dst).unwrap(), b"same photo");
+        let hardlink_of = read_sidecar(&dst).unwrap().source.hardlink_of.unwrap();
+        assert!(hardlink_of.ends_with("/a.jpg"));
+
+        // Already linked: nothing more to save
+        assert_eq!(create_hardlink_dedup(&src, &dst).unwrap().bytes_saved, if cfg!(unix) { 0 } else { 10 });
+
+        // A rescan keeps the canonical path
+        scan_file(&dst, false).unwrap();
+        assert_eq!(read_sidecar(&dst).unwrap().source.hardlink_of, Some(hardlink_of));
+    }
+
+    #[test]
+    fn test_hardlink_dedup_rejects_directories() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path().join("album");
+        fs::create_dir(&dir).unwrap();
+        let file = temp_dir.path().join("a.jpg");
+        fs::write(&file, b"photo").unwrap();
+
+        assert!(matches!(create_hardlink_dedup(&dir, &file), Err(JozinError::UserError { .. })));
+        assert!(matches!(create_hardlink_dedup(&file, &dir), Err(JozinError::UserError { .. })));
+        assert!(dir.is_dir());
+    }
+
+    #[test]
+    fn test_hardlink_dedup_refuses_different_contents() {
+        let temp_dir = TempDir::new().unwrap();
+        let src = temp_dir.path().join("a.jpg");
+        let same_size = temp_dir.path().join("b.jpg");
+        let other_size = temp_dir.path().join("c.jpg");
+        fs::write(&src, b"photo one").unwrap();
+        fs::write(&same_size, b"photo two").unwrap();
+        fs::write(&other_size, b"a longer photo").unwrap();
+
+        for dst in [&same_size, &other_size] {
+            assert!(matches!(create_hardlink_dedup(&src, dst), Err(JozinError::UserError { .. })));
+        }
+        assert_eq!(fs::read(&same_size).unwrap(), b"photo two");
+        assert_eq!(fs::read(&other_size).unwrap(), b"a longer photo");
+    }
+}
@@ -0,0 +1,205 @@
+//! In-process cache of file hashes
+//!
+//! Hashing dominates scan and verify time on large libraries. When one
+//! process hashes the same files more than once (e.g. `verify --check-hashes`
+//! followed by `scan` from a GUI session), a [`HashCache`] returns the earlier
+//! result as long as the file is unchanged.
+//!
+//! Entries are keyed by path and validated against the file's current size
+//! and modification time; a file whose size or mtime changed is rehashed.
+//! The cache is bounded and evicts the least recently used entry.
+//!
+//! `scan` shares a cache through `ScanConfig::hash_cache`
+//! (`Arc<Mutex<HashCache>>`). It caches BLAKE3 hashes only; don't mix
+//! algorithms in one cache.
+//!
+//! ## Usage
+//!
+//! ```no_run
+//! use jozin_core::hash::compute_blake3_hash;
+//! use jozin_core::util::hash_cache::HashCache;
+//! use std::path::Path;
+//!
+//! let mut cache = HashCache::default();
+//! let first = cache.get_or_compute(Path::new("/photos/IMG_1234.JPG"), compute_blake3_hash)?;
+//! let again = cache.get_or_compute(Path::new("/photos/IMG_1234.JPG"), compute_blake3_hash)?; // cached
+//! assert_eq!(first, again);
+//! # Ok::<(), jozin_core::JozinError>(())
+//! ```
+
+use crate::Result;
+use hashlink::LruCache;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use time::OffsetDateTime;
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+/// Default number of cached hashes.
+pub const DEFAULT_HASH_CACHE_CAPACITY: usize = 10_000;
+
+// ============================================================================
+// Public Types
+// ============================================================================
+
+/// A cached hash and the file state it was computed for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HashCacheEntry {
+    pub size_bytes: u64,
+    pub modified_at: OffsetDateTime,
+    pub hash: String,
+}
+
+/// Bounded LRU cache of file hashes.
+pub struct HashCache(LruCache<PathBuf, HashCacheEntry>);
+
+impl fmt::Debug for HashCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HashCache")
+            .field("len", &self.0.len())
+            .field("capacity", &self.0.capacity())
+            .finish()
+    }
+}
+
+impl Default for HashCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_HASH_CACHE_CAPACITY)
+    }
+}
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+impl HashCache {
+    /// Creates a cache holding at most `capacity` hashes (at least one).
+    pub fn new(capacity: usize) -> Self {
+        Self(LruCache::new(capacity.max(1)))
+    }
+
+    /// Number of cached hashes.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Whether the cache is empty.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns the cached hash if the file still has `size_bytes` and
+    /// `modified_at`; a stale entry is dropped.
+    pub fn get(&mut self, path: &Path, size_bytes: u64, modified_at: OffsetDateTime) -> Option<String> {
+        let entry = self.0.get(path)?;
+        if entry.size_bytes == size_bytes && entry.modified_at == modified_at {
+            return Some(entry.hash.clone());
+        }
+        self.0.remove(path);
+        None
+    }
+
+    /// Caches a hash, evicting the least recently used entry when full.
+    pub fn insert(&mut self, path: PathBuf, entry: HashCacheEntry) {
+        self.0.insert(path, entry);
+    }
+
+    /// Returns the hash of `path`, computing it with `compute_fn` unless an
+    /// entry for the file's current size and mtime is cached.
+    ///
+    /// # Errors
+    ///
+    /// - `JozinError::IoError` if the file's metadata cannot be read
+    /// - Any error of `compute_fn` (nothing is cached then)
+    pub fn get_or_compute(&mut self, path: &Path, compute_fn: impl Fn(&Path) -> Result<String>) -> Result<String> {
+        let (size_bytes, modified_at) = file_state(path)?;
+        if let Some(hash) = self.get(path, size_bytes, modified_at) {
+            return Ok(hash);
+        }
+
+        let hash = compute_fn(path)?;
+        self.insert(
+            path.to_path_buf(),
+            HashCacheEntry {
+                size_bytes,
+                modified_at,
+                hash: hash.clone(),
+            },
+        );
+        Ok(hash)
+    }
+}
+
+/// Current size and modification time of a file, as compared by the cache.
+///
+/// # Errors
+///
+/// Returns `JozinError::IoError` if the metadata cannot be read.
+pub fn file_state(path: &Path) -> Result<(u64, OffsetDateTime)> {
+    let metadata = fs::metadata(path)?;
+    Ok((metadata.len(), OffsetDateTime::from(metadata.modified()?)))
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_cache_hit_and_staleness() {
+        let temp_dir = TempDir::new().unwrap();
+        let photo = temp_dir.path().join("a.jpg");
+        fs::write(&photo, b"first").unwrap();
+
+        let calls = Cell::new(0);
+        let compute = |path: &Path| {
+            calls.set(calls.get() + 1);
+            Ok(String::from_utf8(fs::read(path).unwrap()).unwrap())
+        };
+
+        let mut cache = HashCache::new(10);
+        assert_eq!(cache.get_or_compute(&photo, compute).unwrap(), "first");
+        assert_eq!(cache.get_or_compute(&photo, compute).unwrap(), "first");
+        assert_eq!(calls.get(), 1);
+
+        // A size change invalidates the entry
+        fs::write(&photo, b"second!").unwrap();
+        assert_eq!(cache.get_or_compute(&photo, compute).unwrap(), "second!");
+        assert_eq!(calls.get(), 2);
+
+        // So does an mtime change with the same size
+        let (size, modified_at) = file_state(&photo).unwrap();
+        assert!(cache.get(&photo, size, modified_at + time::Duration::seconds(1)).is_none());
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_least_recently_used_is_evicted() {
+        let temp_dir = TempDir::new().unwrap();
+        let paths: Vec<PathBuf> = (0..3).map(|i| temp_dir.path().join(format!("{}.jpg", i))).collect();
+        for path in &paths {
+            fs::write(path, b"x").unwrap();
+        }
+
+        let mut cache = HashCache::new(2);
+        let name = |path: &Path| Ok(path.file_name().unwrap().to_string_lossy().into_owned());
+        cache.get_or_compute(&paths[0], name).unwrap();
+        cache.get_or_compute(&paths[1], name).unwrap();
+        cache.get_or_compute(&paths[0], name).unwrap(); // 0 is now the most recent
+        cache.get_or_compute(&paths[2], name).unwrap();
+
+        let (size, modified_at) = file_state(&paths[1]).unwrap();
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get(&paths[1], size, modified_at).is_none());
+        let (size, modified_at) = file_state(&paths[0]).unwrap();
+        assert!(cache.get(&paths[0], size, modified_at).is_some());
+    }
+}
@@ -0,0 +1,572 @@
+//! Embedded ICC color profiles
+//!
+//! EXIF's `ColorSpace` only knows sRGB and Adobe RGB; wide-gamut photos
+//! (Display P3 from phones, ProPhoto from raw converters) are marked
+//! "Uncalibrated" and carry an ICC profile instead. [`extract_icc_profile()`]
+//! reads that profile and decodes its header and colorimetric tags:
+//!
+//! | Format | Where the profile is stored                               |
+//! |--------|-----------------------------------------------------------|
+//! | JPEG   | `APP2` segments starting with `ICC_PROFILE\0` (possibly split over several) |
+//! | PNG    | `iCCP` chunk (zlib-compressed)                            |
+//! | TIFF   | Tag 34675 (`InterColorProfile`) of the first IFD          |
+//!
+//! Other formats report no profile. `scan` records the profile's
+//! description in `ImageInfo::color_space` when EXIF names no color space
+//! (or "Uncalibrated").
+//!
+//! ## Decoded Fields
+//!
+//! | [`IccProfile`] field | ICC source                                    |
+//! |----------------------|-----------------------------------------------|
+//! | `description`        | `desc` tag (`desc` or `mluc` type, first record) |
+//! | `color_space`        | Header bytes 16..20                           |
+//! | `white_point`        | `wtpt` tag as xy chromaticity (PCS illuminant if absent) |
+//! | `primaries`          | `rXYZ`, `gXYZ`, `bXYZ` tags as xy chromaticity (zero if absent) |
+//! | `rendering_intent`   | Header bytes 64..68                           |
+//!
+//! Primaries are stored in profiles adapted to the D50 connection space, so
+//! they differ slightly from a standard's published values (sRGB red is
+//! `(0.648, 0.331)` instead of `(0.640, 0.330)`).
+//!
+//! ## Usage
+//!
+//! ```no_run
+//! use jozin_core::util::icc_profile::{extract_icc_profile, is_srgb};
+//! use std::path::Path;
+//!
+//! if let Some(profile) = extract_icc_profile(Path::new("/photos/IMG_1234.JPG"))? {
+//!     println!("{} (sRGB: {})", profile.description, is_srgb(&profile));
+//! }
+//! # Ok::<(), jozin_core::JozinError>(())
+//! ```
+
+use crate::util::image_format::{detect_format_from_magic_bytes, ImageFormat};
+use crate::{JozinError, Result};
+use flate2::read::ZlibDecoder;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+/// Largest profile read (real profiles are a few KiB, LUT-based ones < 1 MiB).
+const MAX_PROFILE_BYTES: usize = 16 * 1024 * 1024;
+
+/// Size of the ICC profile header.
+const HEADER_LEN: usize = 128;
+
+/// TIFF tag holding the ICC profile (`InterColorProfile`).
+const TIFF_ICC_TAG: u16 = 34675;
+
+/// Prefix of JPEG `APP2` segments carrying a profile chunk.
+const JPEG_ICC_MARKER: &[u8] = b"ICC_PROFILE\0";
+
+/// xy chromaticity of the sRGB reference white (D65).
+const SRGB_WHITE: [f32; 2] = [0.3127, 0.3290];
+
+/// xy chromaticity of the D50 profile connection space illuminant.
+const D50_WHITE: [f32; 2] = [0.3457, 0.3585];
+
+/// sRGB primaries (red, green, blue) as stored in profiles (D50-adapted).
+const SRGB_PRIMARIES: [[f32; 2]; 3] = [[0.6485, 0.3309], [0.3212, 0.5979], [0.1559, 0.0660]];
+
+/// Tolerance for chromaticity comparisons.
+const CHROMATICITY_TOLERANCE: f32 = 0.005;
+
+// ============================================================================
+// Public Types
+// ============================================================================
+
+/// Color space of the data a profile describes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorSpaceType {
+    Rgb,
+    Gray,
+    Cmyk,
+    Lab,
+    Xyz,
+    YCbCr,
+    Other,
+}
+
+/// Rendering intent recorded in a profile header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RenderingIntent {
+    Perceptual,
+    RelativeColorimetric,
+    Saturation,
+    AbsoluteColorimetric,
+}
+
+/// The decoded header and colorimetric tags of an ICC profile.
+///
+/// # Fields
+///
+/// - `description`: Profile name, e.g. `"sRGB IEC61966-2.1"` or `"Display P3"`
+/// - `color_space`: Color space of the image data
+/// - `white_point`: Media white point as xy chromaticity
+/// - `primaries`: Red, green and blue colorants as xy chromaticity (zero for
+///   profiles without colorant tags, e.g. gray or CMYK)
+/// - `rendering_intent`: Intent the profile was created for
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IccProfile {
+    pub description: String,
+    pub color_space: ColorSpaceType,
+    pub white_point: [f32; 2],
+    pub primaries: [[f32; 2]; 3],
+    pub rendering_intent: RenderingIntent,
+}
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Extracts and decodes the ICC profile embedded in a JPEG, PNG or TIFF.
+///
+/// Only the metadata part of the file is read (up to the image data).
+///
+/// # Returns
+///
+/// `Ok(None)` if the file has no profile or is in another format.
+///
+/// # Errors
+///
+/// - `JozinError::IoError` if the file cannot be read
+/// - `JozinError::ValidationError` if the embedded profile is malformed
+pub fn extract_icc_profile(image_path: &Path) -> Result<Option<IccProfile>> {
+    let raw = match detect_format_from_magic_bytes(image_path)? {
+        Some(ImageFormat::Jpeg) => read_jpeg_profile(image_path)?,
+        Some(ImageFormat::Png) => read_png_profile(image_path)?,
+        Some(ImageFormat::Tiff) => read_tiff_profile(image_path)?,
+        _ => None,
+    };
+    raw.map(|data| parse_icc_profile(&data)).transpose()
+}
+
+/// Decodes a raw ICC profile.
+///
+/// # Errors
+///
+/// Returns `JozinError::ValidationError` if the data is shorter than a
+/// profile header, lacks the `acsp` signature, or has a truncated tag table.
+pub fn parse_icc_profile(data: &[u8]) -> Result<IccProfile> {
+    if data.len() < HEADER_LEN + 4 || &data[36..40] != b"acsp" {
+        return Err(invalid("missing ICC header"));
+    }
+
+    let color_space = match &data[16..20] {
+        b"RGB " => ColorSpaceType::Rgb,
+        b"GRAY" => ColorSpaceType::Gray,
+        b"CMYK" => ColorSpaceType::Cmyk,
+        b"Lab " => ColorSpaceType::Lab,
+        b"XYZ " => ColorSpaceType::Xyz,
+        b"YCbr" => ColorSpaceType::YCbCr,
+        _ => ColorSpaceType::Other,
+    };
+    let rendering_intent = match be_u32(data, 64) & 0xFFFF {
+        1 => RenderingIntent::RelativeColorimetric,
+        2 => RenderingIntent::Saturation,
+        3 => RenderingIntent::AbsoluteColorimetric,
+        _ => RenderingIntent::Perceptual,
+    };
+    let pcs_illuminant = chromaticity(xyz_at(data, 68));
+
+    let tag_count = be_u32(data, HEADER_LEN) as usize;
+    if data.len() < HEADER_LEN + 4 + tag_count.saturating_mul(12) {
+        return Err(invalid("truncated tag table"));
+    }
+    let tag = |signature: &[u8; 4]| {
+        (0..tag_count).find_map(|index| {
+            let entry = HEADER_LEN + 4 + index * 12;
+            let (offset, size) = (be_u32(data, entry + 4) as usize, be_u32(data, entry + 8) as usize);
+            (&data[entry..entry + 4] == signature).then(|| data.get(offset..offset.checked_add(size)?)).flatten()
+        })
+    };
+    let colorant = |signature| tag(signature).and_then(xyz_tag).map(chromaticity).unwrap_or_default();
+
+    Ok(IccProfile {
+        description: tag(b"desc").and_then(description).unwrap_or_default(),
+        color_space,
+        white_point: tag(b"wtpt").and_then(xyz_tag).map(chromaticity).unwrap_or(pcs_illuminant),
+        primaries: [colorant(b"rXYZ"), colorant(b"gXYZ"), colorant(b"bXYZ")],
+        rendering_intent,
+    })
+}
+
+/// Whether two profiles are the same: equal description and color space.
+pub fn profiles_match(a: &IccProfile, b: &IccProfile) -> bool {
+    a.description == b.description && a.color_space == b.color_space
+}
+
+/// Whether a profile is sRGB: an RGB profile with the sRGB primaries whose
+/// white point is the sRGB reference white (D65).
+///
+/// ICC v4 profiles record the media white point as D50 by definition, so
+/// D50 is accepted too when the primaries match.
+///
+/// # Example
+///
+/// ```
+/// use jozin_core::util::icc_profile::{is_srgb, ColorSpaceType, IccProfile, RenderingIntent};
+///
+/// let profile = IccProfile {
+///     description: "sRGB IEC61966-2.1".to_string(),
+///     color_space: ColorSpaceType::Rgb,
+///     white_point: [0.3127, 0.3290],
+///     primaries: [[0.6485, 0.3309], [0.3212, 0.5979], [0.1559, 0.0660]],
+///     rendering_intent: RenderingIntent::Perceptual,
+/// };
+/// assert!(is_srgb(&profile));
+/// ```
+pub fn is_srgb(profile: &IccProfile) -> bool {
+    profile.color_space == ColorSpaceType::Rgb
+        && (near(profile.white_point, SRGB_WHITE) || near(profile.white_point, D50_WHITE))
+        && profile.primaries.iter().zip(SRGB_PRIMARIES).all(|(&primary, reference)| near(primary, reference))
+}
+
+// ============================================================================
+// Internal Helpers
+// ============================================================================
+
+fn invalid(reason: &str) -> JozinError {
+    JozinError::ValidationError {
+        message: format!("Invalid ICC profile: {}", reason),
+    }
+}
+
+fn near(a: [f32; 2], b: [f32; 2]) -> bool {
+    (a[0] - b[0]).abs() <= CHROMATICITY_TOLERANCE && (a[1] - b[1]).abs() <= CHROMATICITY_TOLERANCE
+}
+
+/// Big-endian `u32` at `offset` (callers check the length).
+fn be_u32(data: &[u8], offset: usize) -> u32 {
+    u32::from_be_bytes([data[offset], data[offset + 1], data[offset + 2], data[offset + 3]])
+}
+
+/// Three `s15Fixed16Number`s at `offset`.
+fn xyz_at(data: &[u8], offset: usize) -> [f32; 3] {
+    [0, 4, 8].map(|i| be_u32(data, offset + i) as i32 as f32 / 65536.0)
+}
+
+/// Value of an `XYZ ` type tag.
+fn xyz_tag(tag: &[u8]) -> Option<[f32; 3]> {
+    (tag.len() >= 20 && &tag[..4] == b"XYZ ").then(|| xyz_at(tag, 8))
+}
+
+/// xy chromaticity of an XYZ color.
+fn chromaticity([x, y, z]: [f32; 3]) -> [f32; 2] {
+    let sum = x + y + z;
+    if sum == 0.0 {
+        [0.0, 0.0]
+    } else {
+        [x / sum, y / sum]
+    }
+}
+
+/// Text of a `desc` tag: ASCII (`desc` type, ICC v2) or the first record of
+/// a `mluc` type (UTF-16BE, ICC v4).
+fn description(tag: &[u8]) -> Option<String> {
+    match tag.get(..4)? {
+        b"desc" => {
+            let count = be_u32(tag.get(..12)?, 8) as usize;
+            let text = tag.get(12..12 + count)?;
+            Some(String::from_utf8_lossy(text).trim_end_matches('\0').trim().to_string())
+        }
+        b"mluc" => {
+            let record = tag.get(16..28)?;
+            let (length, offset) = (be_u32(record, 4) as usize, be_u32(record, 8) as usize);
+            let units: Vec<u16> =
+                tag.get(offset..offset + length)?.chunks_exact(2).map(|pair| u16::from_be_bytes([pair[0], pair[1]])).collect();
+            Some(String::from_utf16_lossy(&units).trim_end_matches('\0').trim().to_string())
+        }
+        _ => None,
+    }
+}
+
+/// Concatenates the `ICC_PROFILE` `APP2` segments of a JPEG in sequence order.
+fn read_jpeg_profile(path: &Path) -> Result<Option<Vec<u8>>> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut soi = [0u8; 2];
+    reader.read_exact(&mut soi)?;
+
+    let mut chunks: Vec<(u8, Vec<u8>)> = Vec::new();
+    loop {
+        let mut byte = [0u8; 1];
+        if reader.read_exact(&mut byte).is_err() || byte[0] != 0xFF {
+            break;
+        }
+        // Fill bytes before a marker
+        let mut marker = 0xFF;
+        while marker == 0xFF {
+            reader.read_exact(&mut byte)?;
+            marker = byte[0];
+        }
+        match marker {
+            // Start of scan or end of image: no metadata follows
+            0xDA | 0xD9 => break,
+            0x01 | 0xD0..=0xD7 => continue,
+            _ => {}
+        }
+        let mut length = [0u8; 2];
+        reader.read_exact(&mut length)?;
+        let payload_len = usize::from(u16::from_be_bytes(length)).saturating_sub(2);
+        if marker == 0xE2 {
+            let mut payload = vec![0u8; payload_len];
+            reader.read_exact(&mut payload)?;
+            if payload.len() > JPEG_ICC_MARKER.len() + 2 && payload.starts_with(JPEG_ICC_MARKER) {
+                let sequence = payload[JPEG_ICC_MARKER.len()];
+                chunks.push((sequence, payload.split_off(JPEG_ICC_MARKER.len() + 2)));
+            }
+        } else {
+            reader.seek_relative(payload_len as i64)?;
+        }
+    }
+
+    if chunks.is_empty() {
+        return Ok(None);
+    }
+    chunks.sort_by_key(|(sequence, _)| *sequence);
+    Ok(Some(chunks.into_iter().flat_map(|(_, data)| data).collect()))
+}
+
+/// Decompresses the `iCCP` chunk of a PNG.
+fn read_png_profile(path: &Path) -> Result<Option<Vec<u8>>> {
+    let mut reader = BufReader::new(File::open(path)?);
+    reader.seek(SeekFrom::Start(8))?;
+    loop {
+        let mut header = [0u8; 8];
+        if reader.read_exact(&mut header).is_err() {
+            return Ok(None);
+        }
+        let length = be_u32(&header, 0) as usize;
+        match &header[4..8] {
+            // The profile must precede the image data
+            b"IDAT" | b"IEND" => return Ok(None),
+            b"iCCP" => {
+                let mut chunk = vec![0u8; length];
+                reader.read_exact(&mut chunk)?;
+                // Profile name, NUL, compression method (0 = zlib), data
+                let name_end = chunk.iter().position(|&b| b == 0).ok_or_else(|| invalid("iCCP chunk without name"))?;
+                let compressed = chunk.get(name_end + 2..).ok_or_else(|| invalid("truncated iCCP chunk"))?;
+                let mut profile = Vec::new();
+                ZlibDecoder::new(compressed)
+                    .take(MAX_PROFILE_BYTES as u64)
+                    .read_to_end(&mut profile)
+                    .map_err(|e| invalid(&format!("iCCP decompression failed: {}", e)))?;
+                return Ok(Some(profile));
+            }
+            // Skip the data and CRC
+            _ => reader.seek_relative(length as i64 + 4)?,
+        }
+    }
+}
+
+/// Reads tag 34675 of the first IFD of a TIFF.
+fn read_tiff_profile(path: &Path) -> Result<Option<Vec<u8>>> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut header = [0u8; 8];
+    reader.read_exact(&mut header)?;
+    let little_endian = &header[..2] == b"II";
+    let u16_at = |bytes: &[u8]| {
+        let pair = [bytes[0], bytes[1]];
+        if little_endian { u16::from_le_bytes(pair) } else { u16::from_be_bytes(pair) }
+    };
+    let u32_at = |bytes: &[u8]| {
+        let quad = [bytes[0], bytes[1], bytes[2], bytes[3]];
+        if little_endian { u32::from_le_bytes(quad) } else { u32::from_be_bytes(quad) }
+    };
+
+    reader.seek(SeekFrom::Start(u64::from(u32_at(&header[4..8]))))?;
+    let mut count = [0u8; 2];
+    reader.read_exact(&mut count)?;
+    for _ in 0..u16_at(&count) {
+        let mut entry = [0u8; 12];
+        reader.read_exact(&mut entry)?;
+        if u16_at(&entry[..2]) != TIFF_ICC_TAG {
+            continue;
+        }
+        // BYTE or UNDEFINED: one byte per element
+        let length = u32_at(&entry[4..8]) as usize;
+        if length > MAX_PROFILE_BYTES {
+            return Err(invalid("profile too large"));
+        }
+        if length <= 4 {
+            return Ok(Some(entry[8..8 + length].to_vec()));
+        }
+        reader.seek(SeekFrom::Start(u64::from(u32_at(&entry[8..12]))))?;
+        let mut profile = vec![0u8; length];
+        reader.read_exact(&mut profile)?;
+        return Ok(Some(profile));
+    }
+    Ok(None)
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::write::ZlibEncoder;
+    use flate2::Compression;
+    use std::fs;
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    const D65_XYZ: [f32; 3] = [0.9505, 1.0, 1.0891];
+    const SRGB_COLORANTS: [[f32; 3]; 3] = [[0.4361, 0.2225, 0.0139], [0.3851, 0.7169, 0.0971], [0.1431, 0.0606, 0.7141]];
+    const P3_COLORANTS: [[f32; 3]; 3] = [[0.5151, 0.2412, -0.0011], [0.2919, 0.6922, 0.0419], [0.1572, 0.0666, 0.7841]];
+
+    fn xyz_tag_bytes(xyz: [f32; 3]) -> Vec<u8> {
+        let mut tag = b"XYZ \0\0\0\0".to_vec();
+        for value in xyz {
+            tag.extend(((value * 65536.0).round() as i32).to_be_bytes());
+        }
+        tag
+    }
+
+    /// A minimal RGB profile; `mluc` selects the v4 description type.
+    fn profile_bytes(description: &str, mluc: bool, white: [f32; 3], colorants: [[f32; 3]; 3]) -> Vec<u8> {
+        let desc = if mluc {
+            let text: Vec<u8> = description.encode_utf16().flat_map(u16::to_be_bytes).collect();
+            let mut tag = b"mluc\0\0\0\0".to_vec();
+            tag.extend(1u32.to_be_bytes());
+            tag.extend(12u32.to_be_bytes());
+            tag.extend(b"enUS");
+            tag.extend((text.len() as u32).to_be_bytes());
+            tag.extend(28u32.to_be_bytes());
+            tag.extend(text);
+            tag
+        } else {
+            let mut tag = b"desc\0\0\0\0".to_vec();
+            tag.extend((description.len() as u32 + 1).to_be_bytes());
+            tag.extend(description.as_bytes());
+            tag.push(0);
+            tag
+        };
+        let tags: Vec<(&[u8; 4], Vec<u8>)> = vec![
+            (b"desc", desc),
+            (b"wtpt", xyz_tag_bytes(white)),
+            (b"rXYZ", xyz_tag_bytes(colorants[0])),
+            (b"gXYZ", xyz_tag_bytes(colorants[1])),
+            (b"bXYZ", xyz_tag_bytes(colorants[2])),
+        ];
+
+        let mut header = vec![0u8; HEADER_LEN];
+        header[16..20].copy_from_slice(b"RGB ");
+        header[36..40].copy_from_slice(b"acsp");
+        header[64..68].copy_from_slice(&1u32.to_be_bytes());
+        let mut table = (tags.len() as u32).to_be_bytes().to_vec();
+        let mut data = Vec::new();
+        let mut offset = HEADER_LEN + 4 + tags.len() * 12;
+        for (signature, bytes) in &tags {
+            table.extend(*signature);
+            table.extend((offset as u32).to_be_bytes());
+            table.extend((bytes.len() as u32).to_be_bytes());
+            data.extend(bytes);
+            offset += bytes.len();
+        }
+        [header, table, data].concat()
+    }
+
+    fn srgb_profile() -> Vec<u8> {
+        profile_bytes("sRGB IEC61966-2.1", false, D65_XYZ, SRGB_COLORANTS)
+    }
+
+    /// JPEG with the profile split over two `APP2` segments (in reverse order).
+    fn jpeg_with_profile(profile: &[u8]) -> Vec<u8> {
+        let (first, second) = profile.split_at(profile.len() / 2);
+        let mut jpeg = vec![0xFF, 0xD8];
+        for (sequence, part) in [(2u8, second), (1u8, first)] {
+            let mut payload = JPEG_ICC_MARKER.to_vec();
+            payload.extend([sequence, 2]);
+            payload.extend(part);
+            jpeg.extend([0xFF, 0xE2]);
+            jpeg.extend((payload.len() as u16 + 2).to_be_bytes());
+            jpeg.extend(payload);
+        }
+        jpeg.extend([0xFF, 0xDA, 0x00, 0x02, 0xFF, 0xD9]);
+        jpeg
+    }
+
+    #[test]
+    fn test_parse_profiles() {
+        let srgb = parse_icc_profile(&srgb_profile()).unwrap();
+        assert_eq!(srgb.description, "sRGB IEC61966-2.1");
+        assert_eq!((srgb.color_space, srgb.rendering_intent), (ColorSpaceType::Rgb, RenderingIntent::RelativeColorimetric));
+        assert!(near(srgb.white_point, SRGB_WHITE));
+        assert!(is_srgb(&srgb));
+
+        let p3 = parse_icc_profile(&profile_bytes("Display P3", true, [0.9642, 1.0, 0.8249], P3_COLORANTS)).unwrap();
+        assert_eq!(p3.description, "Display P3");
+        assert!(near(p3.white_point, D50_WHITE));
+        assert!(!is_srgb(&p3));
+        assert!(!profiles_match(&srgb, &p3));
+        assert!(profiles_match(&srgb, &srgb.clone()));
+
+        assert!(matches!(parse_icc_profile(b"not a profile"), Err(JozinError::ValidationError { .. })));
+    }
+
+    #[test]
+    fn test_extract_from_jpeg_png_tiff() {
+        let temp_dir = TempDir::new().unwrap();
+        let profile = srgb_profile();
+
+        let jpeg = temp_dir.path().join("a.jpg");
+        fs::write(&jpeg, jpeg_with_profile(&profile)).unwrap();
+
+        let png = temp_dir.path().join("a.png");
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&profile).unwrap();
+        let compressed = encoder.finish().unwrap();
+        let mut bytes = b"\x89PNG\r\n\x1a\n".to_vec();
+        bytes.extend(((b"ICC\0\0".len() + compressed.len()) as u32).to_be_bytes());
+        bytes.extend(b"iCCPICC\0\0");
+        bytes.extend(&compressed);
+        bytes.extend([0; 4]);
+        bytes.extend(0u32.to_be_bytes());
+        bytes.extend(b"IEND");
+        fs::write(&png, bytes).unwrap();
+
+        let tiff = temp_dir.path().join("a.tif");
+        let mut bytes = b"II*\0".to_vec();
+        bytes.extend(8u32.to_le_bytes());
+        bytes.extend(1u16.to_le_bytes());
+        bytes.extend(TIFF_ICC_TAG.to_le_bytes());
+        bytes.extend(7u16.to_le_bytes());
+        bytes.extend((profile.len() as u32).to_le_bytes());
+        bytes.extend(26u32.to_le_bytes());
+        bytes.extend(0u32.to_le_bytes());
+        bytes.extend(&profile);
+        fs::write(&tiff, bytes).unwrap();
+
+        for path in [&jpeg, &png, &tiff] {
+            let extracted = extract_icc_profile(path).unwrap().unwrap();
+            assert_eq!(extracted.description, "sRGB IEC61966-2.1", "{}", path.display());
+        }
+
+        let plain = temp_dir.path().join("plain.jpg");
+        fs::write(&plain, [0xFF, 0xD8, 0xFF, 0xDA, 0x00, 0x02, 0xFF, 0xD9]).unwrap();
+        assert_eq!(extract_icc_profile(&plain).unwrap(), None);
+        fs::write(temp_dir.path().join("b.txt"), b"text").unwrap();
+        assert_eq!(extract_icc_profile(&temp_dir.path().join("b.txt")).unwrap(), None);
+    }
+
+    #[test]
+    fn test_scan_records_profile_description() {
+        let temp_dir = TempDir::new().unwrap();
+        let photo = temp_dir.path().join("p3.jpg");
+        let profile = profile_bytes("Display P3", true, [0.9642, 1.0, 0.8249], P3_COLORANTS);
+        fs::write(&photo, jpeg_with_profile(&profile)).unwrap();
+
+        let sidecar = crate::scan::scan_file(&photo, true).unwrap().sidecar;
+        assert_eq!(sidecar.image.and_then(|image| image.color_space).as_deref(), Some("Display P3"));
+    }
+}
@@ -0,0 +1,205 @@
+//! Image format detection from magic bytes
+//!
+//! [`crate::scan`] decides what to scan by file extension. Extensions lie
+//! (a PDF renamed to `.jpg`, a PNG saved as `.JPG` by a phone app), so this
+//! module identifies the supported formats from their leading bytes instead.
+//! At most 12 bytes are read per file.
+//!
+//! ## Signatures
+//!
+//! | Format                  | Bytes | Signature                                   |
+//! |-------------------------|-------|---------------------------------------------|
+//! | [`ImageFormat::Jpeg`]   | 3     | `FF D8 FF`                                  |
+//! | [`ImageFormat::Png`]    | 8     | `89 50 4E 47 0D 0A 1A 0A`                   |
+//! | [`ImageFormat::Tiff`]   | 4     | `II*\0` or `MM\0*` (also most RAW formats)  |
+//! | [`ImageFormat::WebP`]   | 12    | `RIFF` ... `WEBP`                           |
+//! | [`ImageFormat::Heic`]   | 12    | `ftyp` box with a HEIC/HEIF brand           |
+//! | [`ImageFormat::Gif`]    | 6     | `GIF87a` or `GIF89a`                        |
+//!
+//! Used by `scan` for content-type filtering and by `verify` to check that
+//! `ImageInfo::format` matches the file.
+
+use crate::Result;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+/// Bytes needed to identify every supported format.
+pub const MAGIC_BYTES_LEN: usize = 12;
+
+/// `ftyp` brands of HEIC/HEIF still images.
+const HEIF_BRANDS: &[&[u8; 4]] = &[b"heic", b"heix", b"hevc", b"hevx", b"heim", b"heis", b"heif", b"mif1", b"msf1"];
+
+// ============================================================================
+// Public Types
+// ============================================================================
+
+/// An image format identified from its magic bytes.
+///
+/// Serialized upper-case (e.g. `"JPEG"`), matching `ImageInfo::format`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum ImageFormat {
+    Jpeg,
+    Png,
+    Tiff,
+    WebP,
+    Heic,
+    Gif,
+}
+
+impl ImageFormat {
+    /// MIME type of the format (e.g. `"image/jpeg"`).
+    pub fn to_mime_type(&self) -> &'static str {
+        match self {
+            ImageFormat::Jpeg => "image/jpeg",
+            ImageFormat::Png => "image/png",
+            ImageFormat::Tiff => "image/tiff",
+            ImageFormat::WebP => "image/webp",
+            ImageFormat::Heic => "image/heic",
+            ImageFormat::Gif => "image/gif",
+        }
+    }
+
+    /// Upper-case name as stored in `ImageInfo::format` (e.g. `"JPEG"`).
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ImageFormat::Jpeg => "JPEG",
+            ImageFormat::Png => "PNG",
+            ImageFormat::Tiff => "TIFF",
+            ImageFormat::WebP => "WEBP",
+            ImageFormat::Heic => "HEIC",
+            ImageFormat::Gif => "GIF",
+        }
+    }
+
+    /// Parses a format name or extension, case-insensitively (`"jpg"`,
+    /// `"JPEG"`, `"heif"`, ...). Returns `None` for names without a single
+    /// magic-byte format, such as `"RAW"`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "jpg" | "jpeg" => Some(ImageFormat::Jpeg),
+            "png" => Some(ImageFormat::Png),
+            "tif" | "tiff" => Some(ImageFormat::Tiff),
+            "webp" => Some(ImageFormat::WebP),
+            "heic" | "heif" => Some(ImageFormat::Heic),
+            "gif" => Some(ImageFormat::Gif),
+            _ => None,
+        }
+    }
+
+    /// Identifies a format from the first bytes of a file.
+    ///
+    /// `header` may be shorter than [`MAGIC_BYTES_LEN`]; formats whose
+    /// signature does not fit are not matched.
+    pub fn from_magic_bytes(header: &[u8]) -> Option<Self> {
+        if header.starts_with(&[0xFF, 0xD8, 0xFF]) {
+            Some(ImageFormat::Jpeg)
+        } else if header.starts_with(&[0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A]) {
+            Some(ImageFormat::Png)
+        } else if header.starts_with(b"II*\0") || header.starts_with(b"MM\0*") {
+            Some(ImageFormat::Tiff)
+        } else if header.starts_with(b"GIF87a") || header.starts_with(b"GIF89a") {
+            Some(ImageFormat::Gif)
+        } else if header.len() >= 12 && header.starts_with(b"RIFF") && &header[8..12] == b"WEBP" {
+            Some(ImageFormat::WebP)
+        } else if header.len() >= 12
+            && &header[4..8] == b"ftyp"
+            && HEIF_BRANDS.iter().any(|brand| &header[8..12] == *brand)
+        {
+            Some(ImageFormat::Heic)
+        } else {
+            None
+        }
+    }
+}
+
+impl fmt::Display for ImageFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Detects the image format of a file from its magic bytes.
+///
+/// Performs a single read of at most [`MAGIC_BYTES_LEN`] bytes.
+///
+/// # Returns
+///
+/// `None` if the file is not one of the supported image formats (or is
+/// shorter than its signature).
+///
+/// # Errors
+///
+/// Returns `JozinError::IoError` if the file cannot be opened or read.
+///
+/// # Example
+///
+/// ```no_run
+/// use jozin_core::util::image_format::{detect_format_from_magic_bytes, ImageFormat};
+/// use std::path::Path;
+///
+/// let format = detect_format_from_magic_bytes(Path::new("/photos/IMG_1234.JPG"))?;
+/// assert_eq!(format, Some(ImageFormat::Jpeg));
+/// # Ok::<(), jozin_core::JozinError>(())
+/// ```
+pub fn detect_format_from_magic_bytes(path: &Path) -> Result<Option<ImageFormat>> {
+    let mut reader = BufReader::with_capacity(MAGIC_BYTES_LEN, File::open(path)?);
+    Ok(ImageFormat::from_magic_bytes(reader.fill_buf()?))
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_from_magic_bytes_all_formats() {
+        let cases: &[(&[u8], Option<ImageFormat>)] = &[
+            (&[0xFF, 0xD8, 0xFF, 0xE1], Some(ImageFormat::Jpeg)),
+            (&[0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, 0, 0], Some(ImageFormat::Png)),
+            (b"II*\0\x08\0\0\0", Some(ImageFormat::Tiff)),
+            (b"MM\0*\0\0\0\x08", Some(ImageFormat::Tiff)),
+            (b"RIFF\x24\0\0\0WEBPVP8 ", Some(ImageFormat::WebP)),
+            (b"\0\0\0\x18ftypheic", Some(ImageFormat::Heic)),
+            (b"GIF89a\x01\0", Some(ImageFormat::Gif)),
+            (b"\0\0\0\x18ftypisom", None), // MP4
+            (b"RIFF\x24\0\0\0WAVE", None),
+            (b"%PDF-1.4", None),
+            (&[0xFF, 0xD8], None),
+        ];
+        for (header, expected) in cases {
+            assert_eq!(ImageFormat::from_magic_bytes(header), *expected, "{:?}", header);
+        }
+    }
+
+    #[test]
+    fn test_detect_format_from_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let png_as_jpg = temp_dir.path().join("photo.jpg");
+        std::fs::write(&png_as_jpg, [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, 0, 0, 0, 0x0D, b'I']).unwrap();
+        let empty = temp_dir.path().join("empty.jpg");
+        std::fs::write(&empty, b"").unwrap();
+
+        let format = detect_format_from_magic_bytes(&png_as_jpg).unwrap();
+        assert_eq!(format, Some(ImageFormat::Png));
+        assert_eq!(format.unwrap().to_mime_type(), "image/png");
+        assert_eq!(detect_format_from_magic_bytes(&empty).unwrap(), None);
+        assert_eq!(ImageFormat::from_name("jpg"), Some(ImageFormat::Jpeg));
+        assert_eq!(ImageFormat::from_name("RAW"), None);
+    }
+}
@@ -0,0 +1,192 @@
+//! Read throughput limiting
+//!
+//! Scanning a library on a shared NAS can saturate the drive and make it
+//! sluggish for everyone else. An [`IoBudget`] caps how many bytes per second
+//! are read; [`RateLimitedReader`] enforces it by sleeping whenever reads get
+//! ahead of the budget.
+//!
+//! Sleeps shorter than [`MIN_SLEEP`] are deferred until the reader is at least
+//! that far ahead, so small reads don't cause a flood of tiny sleeps. The
+//! budget is tracked per reader; [`IoBudget::per_thread()`] splits one budget
+//! across parallel workers.
+//!
+//! ## Usage
+//!
+//! ```no_run
+//! use jozin_core::util::io_budget::{IoBudget, RateLimitedReader};
+//! use std::fs::File;
+//! use std::io::Read;
+//!
+//! let budget: IoBudget = "50M".parse()?;
+//! let mut reader = RateLimitedReader::new(File::open("/photos/IMG_1234.CR2")?, budget);
+//! let mut data = Vec::new();
+//! reader.read_to_end(&mut data)?; // at most 50 MB/s
+//! # Ok::<(), jozin_core::JozinError>(())
+//! ```
+
+use crate::{JozinError, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+/// Shortest sleep [`RateLimitedReader`] performs.
+pub const MIN_SLEEP: Duration = Duration::from_millis(10);
+
+// ============================================================================
+// Public Types
+// ============================================================================
+
+/// Maximum read throughput.
+///
+/// Parses from plain byte counts or SI notation: `"1000"`, `"500k"`, `"50M"`,
+/// `"1.5G"` (powers of 1000, case-insensitive, optional trailing `B`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IoBudget {
+    pub max_bytes_per_sec: u64,
+}
+
+impl IoBudget {
+    /// Creates a budget; a zero rate is raised to 1 byte per second.
+    pub fn new(max_bytes_per_sec: u64) -> Self {
+        Self {
+            max_bytes_per_sec: max_bytes_per_sec.max(1),
+        }
+    }
+
+    /// Splits the budget evenly across `threads` concurrent readers.
+    pub fn per_thread(&self, threads: u16) -> Self {
+        Self::new(self.max_bytes_per_sec / u64::from(threads.max(1)))
+    }
+}
+
+impl fmt::Display for IoBudget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} B/s", self.max_bytes_per_sec)
+    }
+}
+
+impl FromStr for IoBudget {
+    type Err = JozinError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || JozinError::ValidationError {
+            message: format!("Invalid I/O limit '{}' (expected bytes per second, e.g. 500k, 50M, 1G)", s),
+        };
+
+        let trimmed = s.trim();
+        let number = trimmed.strip_suffix(['B', 'b']).unwrap_or(trimmed);
+        let (number, multiplier) = match number.chars().last() {
+            Some('k' | 'K') => (&number[..number.len() - 1], 1e3),
+            Some('m' | 'M') => (&number[..number.len() - 1], 1e6),
+            Some('g' | 'G') => (&number[..number.len() - 1], 1e9),
+            _ => (number, 1.0),
+        };
+        let value: f64 = number.trim().parse().map_err(|_| invalid())?;
+        let bytes = value * multiplier;
+        if !bytes.is_finite() || bytes < 1.0 {
+            return Err(invalid());
+        }
+        Ok(Self::new(bytes as u64))
+    }
+}
+
+/// A reader that sleeps to stay within an [`IoBudget`].
+///
+/// Throughput is measured from the first read, so the average rate over the
+/// whole stream never exceeds the budget by more than [`MIN_SLEEP`] worth of
+/// bytes.
+#[derive(Debug)]
+pub struct RateLimitedReader<R> {
+    inner: R,
+    budget: IoBudget,
+    started: Option<Instant>,
+    bytes_read: u64,
+}
+
+impl<R> RateLimitedReader<R> {
+    /// Wraps `inner`.
+    pub fn new(inner: R, budget: IoBudget) -> Self {
+        Self {
+            inner,
+            budget,
+            started: None,
+            bytes_read: 0,
+        }
+    }
+
+    /// Total bytes read so far.
+    pub fn bytes_read(&self) -> u64 {
+        self.bytes_read
+    }
+
+    /// Returns the wrapped reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Sleeps until the bytes read so far fit the budget.
+    fn throttle(&mut self, started: Instant) {
+        let allowed = Duration::from_secs_f64(self.bytes_read as f64 / self.budget.max_bytes_per_sec as f64);
+        let ahead = allowed.saturating_sub(started.elapsed());
+        if ahead >= MIN_SLEEP {
+            std::thread::sleep(ahead);
+        }
+    }
+}
+
+impl<R: Read> Read for RateLimitedReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let started = *self.started.get_or_insert_with(Instant::now);
+        let n = self.inner.read(buf)?;
+        self.bytes_read += n as u64;
+        self.throttle(started);
+        Ok(n)
+    }
+}
+
+impl<R: Seek> Seek for RateLimitedReader<R> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.inner.seek(pos)
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_parse_si_notation() {
+        assert_eq!("50M".parse::<IoBudget>().unwrap().max_bytes_per_sec, 50_000_000);
+        assert_eq!("500k".parse::<IoBudget>().unwrap().max_bytes_per_sec, 500_000);
+        assert_eq!("1.5GB".parse::<IoBudget>().unwrap().max_bytes_per_sec, 1_500_000_000);
+        assert_eq!("4096".parse::<IoBudget>().unwrap().max_bytes_per_sec, 4096);
+        assert!("0".parse::<IoBudget>().is_err());
+        assert!("fast".parse::<IoBudget>().is_err());
+        assert!("-5M".parse::<IoBudget>().is_err());
+        assert_eq!(IoBudget::new(8_000_000).per_thread(4).max_bytes_per_sec, 2_000_000);
+    }
+
+    #[test]
+    fn test_reading_10mb_at_1mb_per_sec_takes_10s() {
+        let data = vec![0u8; 10_000_000];
+        let mut reader = RateLimitedReader::new(Cursor::new(data), IoBudget::new(1_000_000));
+
+        let start = Instant::now();
+        let copied = io::copy(&mut reader, &mut io::sink()).unwrap();
+        let elapsed = start.elapsed().as_secs_f64();
+
+        assert_eq!(copied, 10_000_000);
+        assert!((8.0..=12.0).contains(&elapsed), "took {:.2}s", elapsed);
+    }
+}
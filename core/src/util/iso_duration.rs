@@ -0,0 +1,193 @@
+//! ISO 8601 durations
+//!
+//! `duration_ms` in [`OperationResponse`](crate::OperationResponse) leaves
+//! the unit to the reader. With the `iso-duration` feature, responses also
+//! carry `duration_iso`, the same duration as an ISO 8601 duration string
+//! that ISO-aware tools parse without further configuration.
+//!
+//! | Milliseconds  | ISO 8601           |
+//! |---------------|--------------------|
+//! | `0`           | `PT0S`             |
+//! | `5234`        | `PT5.234S`         |
+//! | `3_723_000`   | `PT1H2M3S`         |
+//! | `86_400_000`  | `P1D`              |
+//! | `90_061_500`  | `P1DT1H1M1.5S`     |
+//!
+//! Only days, hours, minutes and seconds are used: years and months have no
+//! fixed length, so elapsed times are never expressed in them.
+
+use crate::{JozinError, Result};
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+const MS_PER_SECOND: u64 = 1000;
+const MS_PER_MINUTE: u64 = 60 * MS_PER_SECOND;
+const MS_PER_HOUR: u64 = 60 * MS_PER_MINUTE;
+const MS_PER_DAY: u64 = 24 * MS_PER_HOUR;
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Formats milliseconds as an ISO 8601 duration.
+///
+/// Zero components are omitted; fractional seconds have no trailing zeros.
+///
+/// # Example
+///
+/// ```
+/// use jozin_core::util::iso_duration::format_iso_duration;
+///
+/// assert_eq!(format_iso_duration(5234), "PT5.234S");
+/// assert_eq!(format_iso_duration(90_000), "PT1M30S");
+/// ```
+pub fn format_iso_duration(ms: u64) -> String {
+    let days = ms / MS_PER_DAY;
+    let hours = ms % MS_PER_DAY / MS_PER_HOUR;
+    let minutes = ms % MS_PER_HOUR / MS_PER_MINUTE;
+    let seconds = ms % MS_PER_MINUTE / MS_PER_SECOND;
+    let millis = ms % MS_PER_SECOND;
+
+    let mut iso = String::from("P");
+    if days > 0 {
+        iso.push_str(&format!("{}D", days));
+    }
+    if hours == 0 && minutes == 0 && seconds == 0 && millis == 0 && days > 0 {
+        return iso;
+    }
+    iso.push('T');
+    if hours > 0 {
+        iso.push_str(&format!("{}H", hours));
+    }
+    if minutes > 0 {
+        iso.push_str(&format!("{}M", minutes));
+    }
+    if millis > 0 {
+        let fraction = format!("{:03}", millis);
+        iso.push_str(&format!("{}.{}S", seconds, fraction.trim_end_matches('0')));
+    } else if seconds > 0 || iso == "PT" {
+        iso.push_str(&format!("{}S", seconds));
+    }
+    iso
+}
+
+/// Parses an ISO 8601 duration of days, hours, minutes and seconds back to
+/// milliseconds (the inverse of [`format_iso_duration()`]).
+///
+/// Fractions are accepted on seconds only, and truncated to milliseconds.
+///
+/// # Errors
+///
+/// Returns `JozinError::UserError` if the string is not such a duration
+/// (including durations with years, months or weeks).
+///
+/// # Example
+///
+/// ```
+/// use jozin_core::util::iso_duration::parse_iso_duration;
+///
+/// assert_eq!(parse_iso_duration("P1DT1.5S")?, 86_401_500);
+/// # Ok::<(), jozin_core::JozinError>(())
+/// ```
+pub fn parse_iso_duration(iso: &str) -> Result<u64> {
+    let invalid = || JozinError::UserError {
+        message: format!("Invalid ISO 8601 duration: '{}'", iso),
+    };
+    let rest = iso.strip_prefix('P').ok_or_else(invalid)?;
+    let (date_part, time_part) = match rest.split_once('T') {
+        Some((_, "")) => return Err(invalid()),
+        Some((date, time)) => (date, time),
+        None => (rest, ""),
+    };
+    if date_part.is_empty() && time_part.is_empty() {
+        return Err(invalid());
+    }
+
+    let mut ms = 0u64;
+    if !date_part.is_empty() {
+        let days = date_part.strip_suffix('D').ok_or_else(invalid)?;
+        ms += parse_whole(days).ok_or_else(invalid)? * MS_PER_DAY;
+    }
+
+    // Designators must appear in order H, M, S, each at most once
+    let mut remaining = time_part;
+    for (designator, unit) in [('H', MS_PER_HOUR), ('M', MS_PER_MINUTE), ('S', MS_PER_SECOND)] {
+        let Some((value, rest)) = remaining.split_once(designator) else {
+            continue;
+        };
+        ms += if designator == 'S' {
+            parse_seconds(value).ok_or_else(invalid)?
+        } else {
+            parse_whole(value).ok_or_else(invalid)? * unit
+        };
+        remaining = rest;
+    }
+    if !remaining.is_empty() {
+        return Err(invalid());
+    }
+    Ok(ms)
+}
+
+// ============================================================================
+// Internal Helpers
+// ============================================================================
+
+/// Parses a non-empty string of ASCII digits.
+fn parse_whole(value: &str) -> Option<u64> {
+    if value.is_empty() || !value.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    value.parse().ok()
+}
+
+/// Parses `seconds[.fraction]` to milliseconds.
+fn parse_seconds(value: &str) -> Option<u64> {
+    let (whole, fraction) = value.split_once('.').unwrap_or((value, ""));
+    let mut ms = parse_whole(whole)? * MS_PER_SECOND;
+    if value.contains('.') {
+        parse_whole(fraction)?;
+        let digits: String = fraction.chars().chain("00".chars()).take(3).collect();
+        ms += digits.parse::<u64>().ok()?;
+    }
+    Some(ms)
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_format_iso_duration() {
+        assert_eq!(format_iso_duration(0), "PT0S");
+        assert_eq!(format_iso_duration(5), "PT0.005S");
+        assert_eq!(format_iso_duration(5234), "PT5.234S");
+        assert_eq!(format_iso_duration(5230), "PT5.23S");
+        assert_eq!(format_iso_duration(60_000), "PT1M");
+        assert_eq!(format_iso_duration(3_723_000), "PT1H2M3S");
+        assert_eq!(format_iso_duration(86_400_000), "P1D");
+        assert_eq!(format_iso_duration(90_061_500), "P1DT1H1M1.5S");
+        assert_eq!(format_iso_duration(172_800_001), "P2DT0.001S");
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid() {
+        for iso in ["", "P", "PT", "5S", "P1Y", "P1W", "PT1S1M", "PT1.S", "PT1H1H", "P1DT", "PT-1S", "PT1.5M"] {
+            assert!(matches!(parse_iso_duration(iso), Err(JozinError::UserError { .. })), "{}", iso);
+        }
+        assert_eq!(parse_iso_duration("PT0.1234S").unwrap(), 123);
+    }
+
+    proptest! {
+        #[test]
+        fn test_round_trip(ms in 0u64..10_000_000_000) {
+            prop_assert_eq!(parse_iso_duration(&format_iso_duration(ms)).unwrap(), ms);
+        }
+    }
+}
@@ -0,0 +1,225 @@
+//! Per-library settings stored in `.jozin/config.json`
+//!
+//! Sidecars are named `<image>.json` by default. Static site generators
+//! (Next.js, Gatsby) and other tools also keep `.json` files next to content,
+//! so a library can choose its own sidecar naming:
+//!
+//! | `sidecar_prefix` | `sidecar_suffix` | Sidecar of `IMG_1234.JPG`   |
+//! |------------------|------------------|-----------------------------|
+//! | none             | `.json`          | `IMG_1234.JPG.json`         |
+//! | none             | `.jozin.json`    | `IMG_1234.JPG.jozin.json`   |
+//! | `.`              | `.json`          | `.IMG_1234.JPG.json`        |
+//!
+//! `jozin scan` writes the config to the library root when a non-default
+//! naming is used. Every other module finds it with
+//! [`LibraryConfig::discover()`], which looks in the given directory and its
+//! ancestors, so sidecars are recognised without repeating the flags.
+//!
+//! ## Usage
+//!
+//! ```no_run
+//! use jozin_core::util::library_config::LibraryConfig;
+//! use std::path::Path;
+//!
+//! let config = LibraryConfig::discover(Path::new("/photos/2024"));
+//! let sidecar = config.sidecar_path(Path::new("/photos/2024/IMG_1234.JPG"));
+//! println!("{}", sidecar.display());
+//! ```
+
+use crate::scan::is_image_file;
+use crate::util::compression::COMPRESSED_SIDECAR_SUFFIX;
+use crate::{JozinError, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+/// Location of the library config, relative to the library root.
+pub const LIBRARY_CONFIG_FILE: &str = ".jozin/config.json";
+
+/// Sidecar suffix used when a library does not configure one.
+pub const DEFAULT_SIDECAR_SUFFIX: &str = ".json";
+
+// ============================================================================
+// Public Types
+// ============================================================================
+
+/// Settings shared by every command run on one library.
+///
+/// # Fields
+///
+/// - `sidecar_prefix`: Prepended to the image file name (e.g. `"."` for hidden sidecars)
+/// - `sidecar_suffix`: Appended to the image file name (default `".json"`)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LibraryConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sidecar_prefix: Option<String>,
+    #[serde(default = "default_sidecar_suffix")]
+    pub sidecar_suffix: String,
+}
+
+impl Default for LibraryConfig {
+    fn default() -> Self {
+        Self {
+            sidecar_prefix: None,
+            sidecar_suffix: default_sidecar_suffix(),
+        }
+    }
+}
+
+impl LibraryConfig {
+    /// Loads `<library_root>/.jozin/config.json`, or the defaults if it does not exist.
+    ///
+    /// # Errors
+    ///
+    /// - `JozinError::IoError` if the file exists but cannot be read
+    /// - `JozinError::ValidationError` if it is not valid JSON or the suffix is empty
+    pub fn load(library_root: &Path) -> Result<Self> {
+        let path = library_root.join(LIBRARY_CONFIG_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let config: Self = serde_json::from_str(&fs::read_to_string(&path)?)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Saves the config to `<library_root>/.jozin/config.json`.
+    ///
+    /// # Errors
+    ///
+    /// - `JozinError::ValidationError` if the suffix is empty
+    /// - `JozinError::IoError` if the file cannot be written
+    pub fn save(&self, library_root: &Path) -> Result<()> {
+        self.validate()?;
+        let path = library_root.join(LIBRARY_CONFIG_FILE);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Finds the config that applies to `path`.
+    ///
+    /// Checks `path` (or its parent, for files) and every ancestor for
+    /// `.jozin/config.json` and loads the nearest one. Falls back to the
+    /// defaults if none exists or the nearest one cannot be read.
+    pub fn discover(path: &Path) -> Self {
+        let start = if path.is_file() { path.parent().unwrap_or(path) } else { path };
+        start
+            .ancestors()
+            .find(|dir| dir.join(LIBRARY_CONFIG_FILE).is_file())
+            .and_then(|root| Self::load(root).ok())
+            .unwrap_or_default()
+    }
+
+    /// Whether this is the default `<image>.json` naming.
+    pub fn is_default_naming(&self) -> bool {
+        self.sidecar_prefix.as_deref().unwrap_or("").is_empty() && self.sidecar_suffix == DEFAULT_SIDECAR_SUFFIX
+    }
+
+    /// Returns the sidecar path of an image file.
+    pub fn sidecar_path(&self, file_path: &Path) -> PathBuf {
+        let file_name = file_path.file_name().unwrap_or_default().to_string_lossy();
+        file_path.with_file_name(format!(
+            "{}{}{}",
+            self.sidecar_prefix.as_deref().unwrap_or(""),
+            file_name,
+            self.sidecar_suffix
+        ))
+    }
+
+    /// Returns the image file name a sidecar file name belongs to, if it
+    /// follows this naming. Compressed sidecars (`<sidecar>.gz`, see
+    /// [`crate::util::compression`]) follow it too.
+    pub fn image_name<'a>(&self, sidecar_name: &'a str) -> Option<&'a str> {
+        let sidecar_name = sidecar_name.strip_suffix(COMPRESSED_SIDECAR_SUFFIX).unwrap_or(sidecar_name);
+        let name = sidecar_name.strip_prefix(self.sidecar_prefix.as_deref().unwrap_or(""))?;
+        name.strip_suffix(self.sidecar_suffix.as_str())
+            .filter(|image_name| !image_name.is_empty())
+    }
+
+    /// Returns the image path a sidecar belongs to, if it follows this naming.
+    pub fn image_path(&self, sidecar_path: &Path) -> Option<PathBuf> {
+        let sidecar_name = sidecar_path.file_name()?.to_str()?;
+        Some(sidecar_path.with_file_name(self.image_name(sidecar_name)?))
+    }
+
+    /// Checks if a path is a sidecar: this naming around a supported image name.
+    pub fn is_sidecar(&self, path: &Path) -> bool {
+        path.file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| self.image_name(name))
+            .is_some_and(|image_name| is_image_file(Path::new(image_name)))
+    }
+
+    /// Rejects an empty suffix (every image would look like its own sidecar).
+    ///
+    /// # Errors
+    ///
+    /// Returns `JozinError::ValidationError` if `sidecar_suffix` is empty.
+    pub fn validate(&self) -> Result<()> {
+        if self.sidecar_suffix.is_empty() {
+            return Err(JozinError::ValidationError {
+                message: "sidecar_suffix must not be empty".to_string(),
+            });
+        }
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Internal Helpers
+// ============================================================================
+
+fn default_sidecar_suffix() -> String {
+    DEFAULT_SIDECAR_SUFFIX.to_string()
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_sidecar_naming_round_trip() {
+        let hidden = LibraryConfig { sidecar_prefix: Some(".".to_string()), ..LibraryConfig::default() };
+        let suffixed = LibraryConfig { sidecar_suffix: ".jozin.json".to_string(), ..LibraryConfig::default() };
+
+        let photo = Path::new("/photos/IMG_1234.JPG");
+        assert_eq!(LibraryConfig::default().sidecar_path(photo), Path::new("/photos/IMG_1234.JPG.json"));
+        assert_eq!(hidden.sidecar_path(photo), Path::new("/photos/.IMG_1234.JPG.json"));
+        assert_eq!(suffixed.sidecar_path(photo), Path::new("/photos/IMG_1234.JPG.jozin.json"));
+
+        assert_eq!(suffixed.image_path(Path::new("/photos/IMG_1234.JPG.jozin.json")).unwrap(), photo);
+        assert!(suffixed.is_sidecar(Path::new("IMG_1234.JPG.jozin.json")));
+        assert!(suffixed.is_sidecar(Path::new("IMG_1234.JPG.jozin.json.gz")));
+        assert!(!suffixed.is_sidecar(Path::new("IMG_1234.JPG.jozin.json.gz.bak1")));
+        assert!(!suffixed.is_sidecar(Path::new("IMG_1234.JPG.json")));
+        assert!(!hidden.is_sidecar(Path::new("IMG_1234.JPG.json")));
+        assert!(!LibraryConfig::default().is_sidecar(Path::new("package.json")));
+    }
+
+    #[test]
+    fn test_discover_finds_config_in_ancestor() {
+        let temp_dir = TempDir::new().unwrap();
+        let nested = temp_dir.path().join("2024/06");
+        fs::create_dir_all(&nested).unwrap();
+        assert!(LibraryConfig::discover(&nested).is_default_naming());
+
+        let config = LibraryConfig { sidecar_suffix: ".jozin.json".to_string(), ..LibraryConfig::default() };
+        config.save(temp_dir.path()).unwrap();
+        assert_eq!(LibraryConfig::discover(&nested), config);
+
+        let empty = LibraryConfig { sidecar_suffix: String::new(), ..LibraryConfig::default() };
+        assert!(matches!(empty.save(temp_dir.path()), Err(JozinError::ValidationError { .. })));
+    }
+}
@@ -0,0 +1,374 @@
+//! One writing Jožin process per library
+//!
+//! Two `jozin scan` runs on the same directory rotate the same `.bakN`
+//! backups and replace the same sidecars at the same time, which can lose
+//! data. Mutating commands (`scan`, `migrate`, `verify --fix`) therefore
+//! hold a [`LibraryLock`] for their whole run: the file
+//! `<library root>/.jozin/.lock`, next to the library config, holding the
+//! PID of the locking process and when it took the lock:
+//!
+//! ```json
+//! { "pid": 4242, "acquired_at": "2025-06-01T12:00:00Z" }
+//! ```
+//!
+//! The library root is the nearest directory (the path itself or an
+//! ancestor) with a library config (`.jozin/config.json`), and otherwise the
+//! path itself (its parent for files). So in a configured library a scan of
+//! `/photos/2024` waits for one of `/photos` that is already running; the
+//! reverse (a library-wide run started while a subfolder is locked) is not
+//! detected. A lock alone does not make a library root: the lock of a
+//! single-file scan in `/tmp` does not hold up scans of `/tmp/<album>`.
+//!
+//! A lock whose process no longer runs (it crashed or was killed) is stale
+//! and taken over. Liveness is checked through `/proc/<pid>` on Linux and
+//! `kill(pid, 0)` on other Unix systems; elsewhere every lock counts as live
+//! and a stale one has to be deleted by hand. The lock file is removed when
+//! the [`LibraryLock`] is dropped, together with the `.jozin` directory if
+//! the lock created it.
+//!
+//! ## Usage
+//!
+//! ```no_run
+//! use jozin_core::util::library_lock::LibraryLock;
+//! use std::path::Path;
+//!
+//! let _lock = LibraryLock::acquire(Path::new("/photos"))?;
+//! // ... write sidecars; the lock is released at the end of the scope
+//! # Ok::<(), jozin_core::JozinError>(())
+//! ```
+
+use crate::util::library_config::LIBRARY_CONFIG_FILE;
+use crate::{JozinError, Result, Timestamp};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+/// Location of the lock file, relative to the library root.
+pub const LIBRARY_LOCK_FILE: &str = ".jozin/.lock";
+
+/// Attempts at creating the lock file after removing stale ones.
+const MAX_ACQUIRE_ATTEMPTS: u32 = 3;
+
+// ============================================================================
+// Public Types
+// ============================================================================
+
+/// Contents of a lock file.
+///
+/// # Fields
+///
+/// - `pid`: Process holding the lock
+/// - `acquired_at`: When it took the lock (RFC3339)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockInfo {
+    pub pid: u32,
+    pub acquired_at: Timestamp,
+}
+
+/// An exclusive lock on a library, released on drop.
+///
+/// Removal failures are reported on stderr; dropping never panics.
+#[derive(Debug)]
+pub struct LibraryLock {
+    path: PathBuf,
+    /// `.jozin` directory created for the lock, removed again on drop
+    created_dir: Option<PathBuf>,
+}
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+impl LibraryLock {
+    /// Locks the library containing `root` for this process.
+    ///
+    /// An existing lock of a process that is no longer running (or an
+    /// unreadable lock file) is replaced.
+    ///
+    /// # Errors
+    ///
+    /// - `JozinError::UserError` if another live process holds the lock
+    /// - `JozinError::IoError` if the lock file cannot be created (including
+    ///   when `root` does not exist)
+    pub fn acquire(root: &Path) -> Result<Self> {
+        let path = lock_path(root);
+        let mut created_dir = None;
+        if let Some(parent) = path.parent() {
+            match fs::create_dir(parent) {
+                Ok(()) => created_dir = Some(parent.to_path_buf()),
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+        let info = LockInfo {
+            pid: std::process::id(),
+            acquired_at: OffsetDateTime::now_utc().format(&Rfc3339).unwrap_or_default(),
+        };
+
+        for _ in 0..MAX_ACQUIRE_ATTEMPTS {
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(mut file) => {
+                    file.write_all(serde_json::to_string(&info)?.as_bytes())?;
+                    file.sync_all()?;
+                    return Ok(Self { path, created_dir });
+                }
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+                    let contents = match fs::read(&path) {
+                        Ok(contents) => contents,
+                        // Released meanwhile: try again
+                        Err(e) if e.kind() == ErrorKind::NotFound => continue,
+                        Err(e) => return Err(e.into()),
+                    };
+                    if let Some(holder) = parse_lock(&contents) {
+                        if is_process_alive(holder.pid) {
+                            return Err(JozinError::UserError {
+                                message: format!(
+                                    "Library is locked by another Jožin process (PID {}, since {}); \
+                                     wait for it to finish or delete {} if it is no longer running",
+                                    holder.pid,
+                                    holder.acquired_at,
+                                    path.display()
+                                ),
+                            });
+                        }
+                    }
+                    // Stale or unreadable: take it over (another process may win the retry)
+                    remove_stale_lock(&path, &contents)?;
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Err(JozinError::IoError {
+            message: format!("Failed to acquire library lock {}", path.display()),
+        })
+    }
+
+    /// Path of the lock file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The lock currently held on the library containing `root`, if any
+    /// (stale locks included).
+    pub fn holder(root: &Path) -> Option<LockInfo> {
+        read_lock(&lock_path(root))
+    }
+}
+
+impl Drop for LibraryLock {
+    fn drop(&mut self) {
+        if let Err(e) = fs::remove_file(&self.path) {
+            if e.kind() != ErrorKind::NotFound {
+                eprintln!("Warning: Failed to remove library lock {}: {}", self.path.display(), e);
+            }
+        }
+        if let Some(dir) = &self.created_dir {
+            // Fails (and is kept) if something else was stored there meanwhile
+            let _ = fs::remove_dir(dir);
+        }
+    }
+}
+
+/// Path of the lock file for the library containing `root` (see the module
+/// docs for how the library root is found).
+pub fn lock_path(root: &Path) -> PathBuf {
+    let start = if root.is_file() { root.parent().unwrap_or(root) } else { root };
+    let library_root = start
+        .ancestors()
+        .find(|dir| dir.join(LIBRARY_CONFIG_FILE).is_file())
+        .unwrap_or(start);
+    library_root.join(LIBRARY_LOCK_FILE)
+}
+
+/// Whether a process with this PID is running.
+///
+/// Always `true` on platforms without a liveness check (see the module docs).
+pub fn is_process_alive(pid: u32) -> bool {
+    if pid == std::process::id() {
+        return true;
+    }
+    process_exists(pid)
+}
+
+// ============================================================================
+// Internal Helpers
+// ============================================================================
+
+/// Parses a lock file; `None` if it is missing or not a lock.
+fn read_lock(path: &Path) -> Option<LockInfo> {
+    parse_lock(&fs::read(path).ok()?)
+}
+
+fn parse_lock(contents: &[u8]) -> Option<LockInfo> {
+    serde_json::from_slice(contents).ok()
+}
+
+/// Removes the lock file at `path` if it still holds the stale `contents`.
+///
+/// Two processes may find the same stale lock; once the first has replaced
+/// it with its own, the second must not delete that. The file is therefore
+/// first moved to a name unique to this process, and put back (without
+/// overwriting a lock created since) if it turns out not to be the stale one.
+fn remove_stale_lock(path: &Path, contents: &[u8]) -> Result<()> {
+    let nanos = OffsetDateTime::now_utc().unix_timestamp_nanos();
+    let moved = path.with_file_name(format!(".lock.stale-{}-{}", std::process::id(), nanos));
+    match fs::rename(path, &moved) {
+        Ok(()) => {}
+        // Taken over (or released) by another process meanwhile
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e.into()),
+    }
+    if fs::read(&moved).is_ok_and(|moved_contents| moved_contents == contents) {
+        fs::remove_file(&moved)?;
+        return Ok(());
+    }
+    // Another process's fresh lock: restore it
+    let restored = fs::hard_link(&moved, path);
+    fs::remove_file(&moved)?;
+    match restored {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == ErrorKind::AlreadyExists => Ok(()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn process_exists(pid: u32) -> bool {
+    Path::new(&format!("/proc/{}/status", pid)).exists()
+}
+
+#[cfg(target_os = "macos")]
+fn process_exists(pid: u32) -> bool {
+    let Ok(pid) = libc::pid_t::try_from(pid) else {
+        return false;
+    };
+    // EPERM: the process exists but belongs to another user
+    unsafe { libc::kill(pid, 0) == 0 } || std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+#[cfg(all(unix, not(any(target_os = "linux", target_os = "macos"))))]
+fn process_exists(pid: u32) -> bool {
+    std::process::Command::new("kill")
+        .args(["-0", &pid.to_string()])
+        .stderr(std::process::Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+#[cfg(not(unix))]
+fn process_exists(_pid: u32) -> bool {
+    true
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// A PID above the Linux maximum (2^22), so no process has it.
+    const DEAD_PID: u32 = 4_194_305;
+
+    #[test]
+    fn test_acquire_blocks_second_lock_and_releases_on_drop() {
+        let temp_dir = TempDir::new().unwrap();
+        let lock = LibraryLock::acquire(temp_dir.path()).unwrap();
+        assert_eq!(lock.path(), temp_dir.path().join(LIBRARY_LOCK_FILE));
+        assert_eq!(LibraryLock::holder(temp_dir.path()).unwrap().pid, std::process::id());
+        assert!(matches!(LibraryLock::acquire(temp_dir.path()), Err(JozinError::UserError { .. })));
+
+        drop(lock);
+        assert!(!temp_dir.path().join(".jozin").exists());
+
+        // Subfolders of a configured library belong to it
+        fs::create_dir(temp_dir.path().join(".jozin")).unwrap();
+        fs::write(temp_dir.path().join(LIBRARY_CONFIG_FILE), "{}").unwrap();
+        let album = temp_dir.path().join("2024");
+        fs::create_dir(&album).unwrap();
+        let lock = LibraryLock::acquire(temp_dir.path()).unwrap();
+        assert!(matches!(LibraryLock::acquire(&album), Err(JozinError::UserError { .. })));
+        drop(lock);
+        assert!(temp_dir.path().join(LIBRARY_CONFIG_FILE).is_file());
+        drop(LibraryLock::acquire(&album).unwrap());
+    }
+
+    #[test]
+    fn test_lock_without_config_is_not_a_library_root() {
+        let temp_dir = TempDir::new().unwrap();
+        let parent_lock = temp_dir.path().join(LIBRARY_LOCK_FILE);
+        fs::create_dir_all(parent_lock.parent().unwrap()).unwrap();
+        let stale = serde_json::to_string(&LockInfo { pid: DEAD_PID, acquired_at: "2025-01-01T00:00:00Z".to_string() });
+        fs::write(&parent_lock, stale.unwrap()).unwrap();
+        let (album_a, album_b) = (temp_dir.path().join("a"), temp_dir.path().join("b"));
+        fs::create_dir(&album_a).unwrap();
+        fs::create_dir(&album_b).unwrap();
+        fs::write(album_a.join("photo.jpg"), b"image a").unwrap();
+        fs::write(album_b.join("photo.jpg"), b"image b").unwrap();
+
+        // Sibling albums lock (and scan) independently of the parent's lock
+        let lock_a = LibraryLock::acquire(&album_a).unwrap();
+        let lock_b = LibraryLock::acquire(&album_b).unwrap();
+        assert_eq!(lock_a.path(), album_a.join(LIBRARY_LOCK_FILE));
+        assert_eq!(lock_b.path(), album_b.join(LIBRARY_LOCK_FILE));
+        for album in [&album_a, &album_b] {
+            let result = crate::scan_path(album, &crate::ScanConfig::default(), None).unwrap();
+            assert_eq!((result.successful, result.failed), (1, 0));
+        }
+        assert_eq!(LibraryLock::holder(temp_dir.path()).unwrap().pid, DEAD_PID);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_acquire_steals_stale_lock() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join(LIBRARY_LOCK_FILE);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        let stale = LockInfo { pid: DEAD_PID, acquired_at: "2025-01-01T00:00:00Z".to_string() };
+        fs::write(&path, serde_json::to_string(&stale).unwrap()).unwrap();
+        assert!(!is_process_alive(DEAD_PID));
+
+        let lock = LibraryLock::acquire(temp_dir.path()).unwrap();
+        let holder = LibraryLock::holder(temp_dir.path()).unwrap();
+        assert_eq!(holder.pid, std::process::id());
+        assert!(holder.acquired_at.len() >= 20);
+        drop(lock);
+
+        // An unreadable lock file is replaced too
+        fs::write(&path, b"garbage").unwrap();
+        assert!(LibraryLock::acquire(temp_dir.path()).is_ok());
+    }
+
+    #[test]
+    fn test_remove_stale_lock_keeps_a_lock_taken_over_meanwhile() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join(LIBRARY_LOCK_FILE);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        let stale = serde_json::to_vec(&LockInfo { pid: DEAD_PID, acquired_at: "2025-01-01T00:00:00Z".to_string() });
+        let stale = stale.unwrap();
+
+        fs::write(&path, &stale).unwrap();
+        remove_stale_lock(&path, &stale).unwrap();
+        assert!(!path.exists());
+
+        // Another process replaced the stale lock after it was read
+        let fresh = serde_json::to_vec(&LockInfo { pid: 1, acquired_at: "2025-06-01T12:00:00Z".to_string() }).unwrap();
+        fs::write(&path, &fresh).unwrap();
+        remove_stale_lock(&path, &stale).unwrap();
+        assert_eq!(fs::read(&path).unwrap(), fresh);
+        assert_eq!(fs::read_dir(path.parent().unwrap()).unwrap().count(), 1);
+
+        // Already gone
+        fs::remove_file(&path).unwrap();
+        remove_stale_lock(&path, &stale).unwrap();
+    }
+}
@@ -0,0 +1,135 @@
+//! Resident memory limit for long-running operations
+//!
+//! Scanning a library of hundreds of thousands of photos holds per-file
+//! results (and, for some operations, whole sidecars) in memory. On small
+//! machines such as a NAS that can exhaust RAM. A [`MemoryGuard`] compares
+//! the process's resident set size (RSS) with a limit; operations call
+//! [`MemoryGuard::check()`] every [`MEMORY_CHECK_INTERVAL`] items and stop
+//! early, returning partial results, once it fails.
+//!
+//! ## Platform Support
+//!
+//! RSS is read from `/proc/self/status` on Linux. On other platforms it is
+//! unknown and [`MemoryGuard::check()`] always passes.
+//!
+//! ## Usage
+//!
+//! ```
+//! use jozin_core::util::memory_limit::MemoryGuard;
+//!
+//! let guard = MemoryGuard::from_megabytes(2048);
+//! guard.check()?;
+//! # Ok::<(), jozin_core::JozinError>(())
+//! ```
+
+use crate::{JozinError, Result};
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+/// Number of processed items between two [`MemoryGuard::check()`] calls
+/// (reading RSS costs a file read on Linux).
+pub const MEMORY_CHECK_INTERVAL: usize = 1000;
+
+// ============================================================================
+// Public Types
+// ============================================================================
+
+/// Maximum resident memory of the process.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryGuard {
+    limit_bytes: u64,
+}
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+impl MemoryGuard {
+    /// Creates a guard for a limit in bytes.
+    pub fn new(limit_bytes: u64) -> Self {
+        Self { limit_bytes }
+    }
+
+    /// Creates a guard for a limit in MiB (`--memory-limit`).
+    pub fn from_megabytes(limit_mb: u64) -> Self {
+        Self::new(limit_mb.saturating_mul(1024 * 1024))
+    }
+
+    /// The limit in bytes.
+    pub fn limit_bytes(&self) -> u64 {
+        self.limit_bytes
+    }
+
+    /// Checks the current RSS against the limit.
+    ///
+    /// # Errors
+    ///
+    /// Returns `JozinError::InternalError` ("Memory limit exceeded") if the
+    /// RSS is above the limit. Passes if the RSS cannot be determined.
+    pub fn check(&self) -> Result<()> {
+        match current_rss_bytes() {
+            Some(rss) if rss > self.limit_bytes => Err(JozinError::InternalError {
+                message: format!(
+                    "Memory limit exceeded: {} MiB resident, limit {} MiB",
+                    rss / (1024 * 1024),
+                    self.limit_bytes / (1024 * 1024)
+                ),
+            }),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Returns the resident set size of the current process, if the platform
+/// reports it.
+pub fn current_rss_bytes() -> Option<u64> {
+    #[cfg(target_os = "linux")]
+    {
+        parse_vm_rss(&std::fs::read_to_string("/proc/self/status").ok()?)
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        None
+    }
+}
+
+// ============================================================================
+// Internal Helpers
+// ============================================================================
+
+/// Extracts `VmRSS` (reported in kB) from `/proc/<pid>/status` contents.
+#[cfg(any(target_os = "linux", test))]
+fn parse_vm_rss(status: &str) -> Option<u64> {
+    let line = status.lines().find(|line| line.starts_with("VmRSS:"))?;
+    let kilobytes: u64 = line["VmRSS:".len()..].trim().trim_end_matches("kB").trim().parse().ok()?;
+    Some(kilobytes * 1024)
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_vm_rss() {
+        let status = "Name:\tjozin\nVmPeak:\t  20480 kB\nVmRSS:\t   10240 kB\nThreads:\t4\n";
+        assert_eq!(parse_vm_rss(status), Some(10240 * 1024));
+        assert_eq!(parse_vm_rss("Name:\tjozin\n"), None);
+    }
+
+    #[test]
+    fn test_check_against_limit() {
+        assert!(MemoryGuard::new(u64::MAX).check().is_ok());
+        assert_eq!(MemoryGuard::from_megabytes(2).limit_bytes(), 2 * 1024 * 1024);
+
+        if current_rss_bytes().is_some() {
+            let err = MemoryGuard::new(1).check().unwrap_err();
+            assert!(matches!(err, JozinError::InternalError { ref message } if message.starts_with("Memory limit exceeded")));
+        }
+    }
+}
@@ -0,0 +1,117 @@
+//! MIME types of supported file extensions
+//!
+//! One lookup table shared by everything that has to name a format to the
+//! outside world: exported metadata, `Content-Type` values, MIME-type filters,
+//! and [`ThumbnailInfo::format`](crate::ThumbnailInfo::format) in schema
+//! `2.0.0` sidecars.
+//!
+//! | Extensions      | MIME type           |
+//! |-----------------|---------------------|
+//! | `jpg`, `jpeg`   | `image/jpeg`        |
+//! | `png`           | `image/png`         |
+//! | `heic`, `heif`  | `image/heif`        |
+//! | `webp`          | `image/webp`        |
+//! | `dng`           | `image/x-adobe-dng` |
+//! | `cr2`           | `image/x-canon-cr2` |
+//! | `nef`           | `image/x-nikon-nef` |
+//! | `arw`           | `image/x-sony-arw`  |
+//! | `tiff`, `tif`   | `image/tiff`        |
+//! | `avif`          | `image/avif`        |
+//! | `jxl`           | `image/jxl`         |
+//!
+//! Note that magic-byte detection ([`super::image_format`]) reports HEIC
+//! content as `image/heic`; this table uses the container type `image/heif`
+//! for both extensions.
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+/// `(MIME type, extensions)`; the first extension is the preferred one.
+const MIME_TABLE: &[(&str, &[&str])] = &[
+    ("image/jpeg", &["jpg", "jpeg"]),
+    ("image/png", &["png"]),
+    ("image/heif", &["heic", "heif"]),
+    ("image/webp", &["webp"]),
+    ("image/x-adobe-dng", &["dng"]),
+    ("image/x-canon-cr2", &["cr2"]),
+    ("image/x-nikon-nef", &["nef"]),
+    ("image/x-sony-arw", &["arw"]),
+    ("image/tiff", &["tiff", "tif"]),
+    ("image/avif", &["avif"]),
+    ("image/jxl", &["jxl"]),
+];
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Returns the MIME type for a file extension (case-insensitive, without dot).
+///
+/// # Example
+///
+/// ```
+/// use jozin_core::util::mime::extension_to_mime;
+///
+/// assert_eq!(extension_to_mime("JPG"), Some("image/jpeg"));
+/// assert_eq!(extension_to_mime("txt"), None);
+/// ```
+pub fn extension_to_mime(ext: &str) -> Option<&'static str> {
+    let ext = ext.to_ascii_lowercase();
+    MIME_TABLE
+        .iter()
+        .find(|(_, extensions)| extensions.contains(&ext.as_str()))
+        .map(|(mime, _)| *mime)
+}
+
+/// Returns the extensions of a MIME type, preferred first (case-insensitive;
+/// parameters such as `; charset=...` are ignored).
+///
+/// Unknown types yield an empty slice.
+///
+/// # Example
+///
+/// ```
+/// use jozin_core::util::mime::mime_to_extensions;
+///
+/// assert_eq!(mime_to_extensions("image/tiff"), &["tiff", "tif"]);
+/// assert!(mime_to_extensions("text/plain").is_empty());
+/// ```
+pub fn mime_to_extensions(mime: &str) -> &'static [&'static str] {
+    let mime = mime.split(';').next().unwrap_or_default().trim();
+    MIME_TABLE
+        .iter()
+        .find(|(known, _)| known.eq_ignore_ascii_case(mime))
+        .map_or(&[], |(_, extensions)| extensions)
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extension_to_mime() {
+        assert_eq!(extension_to_mime("jpeg"), Some("image/jpeg"));
+        assert_eq!(extension_to_mime("HEIC"), Some("image/heif"));
+        assert_eq!(extension_to_mime("nef"), Some("image/x-nikon-nef"));
+        assert_eq!(extension_to_mime("avif"), Some("image/avif"));
+        assert_eq!(extension_to_mime(""), None);
+        assert_eq!(extension_to_mime("raw"), None);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for (mime, extensions) in MIME_TABLE {
+            assert_eq!(mime_to_extensions(mime), *extensions);
+            for ext in *extensions {
+                assert_eq!(extension_to_mime(ext), Some(*mime));
+            }
+        }
+        assert_eq!(mime_to_extensions("Image/JPEG; q=0.9"), &["jpg", "jpeg"]);
+        assert!(mime_to_extensions("image/gif").is_empty());
+    }
+}
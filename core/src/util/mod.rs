@@ -0,0 +1,104 @@
+//! Util module - Shared helpers used across the core modules
+//!
+//! Small, self-contained building blocks that don't belong to a single
+//! pipeline module:
+//! - **atomic_rename** - Replacing files atomically, including on Windows
+//! - **backup_restore** - Restoring damaged sidecars from their `.bakN` backups (`verify --restore-from-backup`)
+//! - **capability_check** - Detecting optional external tools (`ffprobe`, `exiftool`, `ffmpeg`) before they are needed (`jozin capabilities`)
+//! - **camera** - Canonical camera make and model names (`"NIKON CORPORATION"` → `"Nikon"`)
+//! - **checksum_journal** - Append-only log of scanned file hashes (`scan --journal`)
+//! - **compression** - Gzip-compressed sidecars (`scan --compress-sidecars`, `migrate --compress`)
+//! - **deferred_write** - Batching several sidecar modifications into one atomic write
+//! - **diff_json** - `git diff`-style unified diffs of JSON values (`migrate --preview`, `jozin diff`)
+//! - **digest** - File hashes with a selectable algorithm (BLAKE3, SHA-256)
+//! - **encoding_detect** - Structural checks of JPEG, PNG and TIFF files without decoding them (`--validate-encoding`)
+//! - **exif_write** - Writing EXIF capture time, GPS and camera fields into photos with exiftool (`jozin correct`)
+//! - **fsync_policy** - When sidecar writes are synced to disk (`scan --no-fsync`)
+//! - **glob** - Glob pattern matching with platform-aware case folding
+//! - **glob_path_case** - Glob matching with the case sensitivity of the scanned filesystem (`scan --include/--exclude`)
+//! - **glob_expander** - Shell-style `{a,b}` brace expansion of include/exclude patterns
+//! - **hardlink** - Replacing duplicate files with hardlinks to a canonical copy
+//! - **hash_cache** - In-process LRU cache of file hashes, validated by size and mtime
+//! - **icc_profile** - Embedded ICC color profiles of JPEG, PNG and TIFF files
+//! - **image_format** - Identifying image formats from magic bytes
+//! - **io_budget** - Limiting read throughput (`scan --io-limit`)
+//! - **iso_duration** - ISO 8601 durations (`duration_iso` in responses)
+//! - **library_config** - Per-library settings (`.jozin/config.json`), e.g. sidecar naming
+//! - **library_lock** - One mutating Jožin process per library (`.jozin/.lock`)
+//! - **memory_limit** - Resident memory limit for long-running operations
+//! - **mime** - MIME types of supported file extensions
+//! - **network_check** - Blocking all outbound network access of the process (`core/tests/network_isolation_test.rs`)
+//! - **network_drive** - Detecting network filesystems (SMB, NFS) to adapt I/O
+//! - **orientation** - Applying EXIF orientation to decoded images (image features only)
+//! - **parallel_walker** - Directory traversal overlapped with per-entry work on rayon threads
+//! - **path_normalise** - Canonical `source.file_path` values (absolute, forward slashes)
+//! - **platform** - Platform integration of sidecars (macOS Spotlight tags, backup-excluded directories)
+//! - **process_pool** - Persistent helper processes (`ffprobe`, `exiftool`, ...) shared by worker threads
+//! - **progress_file** - JSONL logs of progress events for crash analysis (`scan --progress-log`)
+//! - **progress_tee** - Fan-out of progress events to several callbacks
+//! - **progress_throttle** - Rate limiting of progress events for UIs
+//! - **quarantine** - Moving corrupt photos and their sidecars to `~/.jozin/quarantine/` with an `index.json` manifest
+//! - **report_builder** - Multi-section operation reports for terminals or JSON (`verify`, `stats`, `faces --privacy-report`)
+//! - **sidecar_index** - Sidecars by content hash, to carry metadata over to renamed files
+//! - **sidecar_patch** - Partial sidecar updates addressed by JSON Pointer
+//! - **sidecar_sorter** - Deterministic ordering of scan results (path, capture date, size)
+//! - **size_estimate** - Pre-estimating operation duration from file sizes
+//! - **symlink** - Symlink handling strategy for directory traversal
+//! - **temp_dir** - Temporary directories removed on drop (`$JOZIN_TEMP_DIR`)
+//! - **traversal_order** - Order in which a scan processes directories (`scan --traversal-order`)
+//! - **test_corpus** - Synthetic photo libraries for integration tests (test builds and the `test-helpers` feature)
+//! - **test_helpers** - Realistic test fixtures such as JPEGs with EXIF (test builds and the `test-helpers` feature)
+//! - **unicode_normalise** - NFC, whitespace and case normalisation of tag labels and person names
+//! - **timezone** - EXIF capture times with UTC offsets, as RFC3339
+
+pub mod atomic_rename;
+pub mod backup_restore;
+pub mod camera;
+pub mod capability_check;
+pub mod checksum_journal;
+pub mod compression;
+pub mod deferred_write;
+pub mod diff_json;
+pub mod digest;
+pub mod encoding_detect;
+pub mod exif_write;
+pub mod fsync_policy;
+pub mod glob;
+pub mod glob_expander;
+pub mod glob_path_case;
+pub mod hardlink;
+pub mod hash_cache;
+pub mod icc_profile;
+pub mod image_format;
+pub mod io_budget;
+pub mod iso_duration;
+pub mod library_config;
+pub mod library_lock;
+pub mod memory_limit;
+pub mod mime;
+pub mod network_check;
+pub mod network_drive;
+pub mod parallel_walker;
+pub mod path_normalise;
+pub mod platform;
+pub mod process_pool;
+pub mod progress_file;
+pub mod progress_tee;
+pub mod progress_throttle;
+pub mod quarantine;
+pub mod report_builder;
+pub mod sidecar_index;
+pub mod sidecar_patch;
+pub mod sidecar_sorter;
+pub mod size_estimate;
+pub mod symlink;
+pub mod temp_dir;
+pub mod timezone;
+pub mod traversal_order;
+pub mod unicode_normalise;
+#[cfg(any(feature = "faces", feature = "thumbs"))]
+pub mod orientation;
+#[cfg(any(test, feature = "test-helpers"))]
+pub mod test_corpus;
+#[cfg(any(test, feature = "test-helpers"))]
+pub mod test_helpers;
@@ -0,0 +1,176 @@
+//! Blocking outbound network access for the whole process
+//!
+//! Jožin works offline: nothing it does should open a network connection.
+//! [`deny_outbound_network()`] makes the kernel enforce that, so a test can
+//! prove it, and a dependency update that quietly adds a telemetry ping or
+//! licence check fails `core/tests/network_isolation_test.rs` instead of
+//! shipping.
+//!
+//! | Platform                   | Mechanism                                  | Violation          |
+//! |----------------------------|--------------------------------------------|--------------------|
+//! | Linux (x86_64, aarch64)    | seccomp filter on `connect`, `sendto`, `sendmsg`, `sendmmsg` | [`NetworkViolation`] |
+//! | macOS                      | `sandbox_init` with the `no-network` profile | `EPERM` only     |
+//! | Others                     | -                                          | `NotSupported`     |
+//!
+//! The restriction covers every thread of the process, including threads
+//! started later and child processes, and cannot be lifted again. On Linux
+//! it also covers local (Unix domain) sockets, which Jožin does not use
+//! either.
+//!
+//! ## Usage
+//!
+//! ```no_run
+//! use jozin_core::util::network_check::{deny_outbound_network, NetworkViolation};
+//!
+//! // In a test binary: any connection attempt kills the process (SIGSYS)
+//! deny_outbound_network(NetworkViolation::Kill)?;
+//! # Ok::<(), jozin_core::JozinError>(())
+//! ```
+
+use crate::{JozinError, Result};
+
+// ============================================================================
+// Public Types
+// ============================================================================
+
+/// What happens when the process tries to reach the network.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NetworkViolation {
+    /// The call fails with `EACCES` (`EPERM` on macOS)
+    Deny,
+    /// The process is killed with `SIGSYS` (Linux only), so nothing can
+    /// swallow the error
+    Kill,
+}
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Whether [`deny_outbound_network()`] works on this platform.
+pub fn is_supported() -> bool {
+    cfg!(any(
+        all(target_os = "linux", any(target_arch = "x86_64", target_arch = "aarch64")),
+        target_os = "macos"
+    ))
+}
+
+/// Blocks all outbound network access of this process from now on.
+///
+/// # Errors
+///
+/// - `JozinError::NotSupported` on platforms without a mechanism (see the
+///   module docs), and for [`NetworkViolation::Kill`] on macOS
+/// - `JozinError::IoError` if the kernel rejects the filter or sandbox
+pub fn deny_outbound_network(on_violation: NetworkViolation) -> Result<()> {
+    install(on_violation)
+}
+
+// ============================================================================
+// Internal Helpers
+// ============================================================================
+
+#[cfg(all(target_os = "linux", any(target_arch = "x86_64", target_arch = "aarch64")))]
+fn install(on_violation: NetworkViolation) -> Result<()> {
+    use libc::{sock_filter, sock_fprog, BPF_ABS, BPF_JEQ, BPF_JMP, BPF_K, BPF_LD, BPF_RET, BPF_W};
+
+    /// `AUDIT_ARCH_*` of the running architecture (`<linux/audit.h>`)
+    #[cfg(target_arch = "x86_64")]
+    const AUDIT_ARCH: u32 = 0xC000_003E;
+    #[cfg(target_arch = "aarch64")]
+    const AUDIT_ARCH: u32 = 0xC000_00B7;
+    /// Offsets of `nr` and `arch` in `struct seccomp_data`
+    const NR_OFFSET: u32 = 0;
+    const ARCH_OFFSET: u32 = 4;
+    const BLOCKED: [libc::c_long; 4] = [libc::SYS_connect, libc::SYS_sendto, libc::SYS_sendmsg, libc::SYS_sendmmsg];
+
+    let statement = |code: u32, k: u32| sock_filter { code: code as u16, jt: 0, jf: 0, k };
+    let jump = |k: u32, jt: u8, jf: u8| sock_filter { code: (BPF_JMP | BPF_JEQ | BPF_K) as u16, jt, jf, k };
+    let action = match on_violation {
+        NetworkViolation::Deny => libc::SECCOMP_RET_ERRNO | libc::EACCES as u32,
+        NetworkViolation::Kill => libc::SECCOMP_RET_KILL_PROCESS,
+    };
+
+    // Syscall numbers differ between ABIs: other architectures are killed
+    let mut program = vec![
+        statement(BPF_LD | BPF_W | BPF_ABS, ARCH_OFFSET),
+        jump(AUDIT_ARCH, 1, 0),
+        statement(BPF_RET | BPF_K, libc::SECCOMP_RET_KILL_PROCESS),
+        statement(BPF_LD | BPF_W | BPF_ABS, NR_OFFSET),
+    ];
+    for syscall in BLOCKED {
+        program.push(jump(syscall as u32, 0, 1));
+        program.push(statement(BPF_RET | BPF_K, action));
+    }
+    program.push(statement(BPF_RET | BPF_K, libc::SECCOMP_RET_ALLOW));
+
+    let filter = sock_fprog {
+        len: program.len() as u16,
+        filter: program.as_mut_ptr(),
+    };
+    let os_error = |what: &str| JozinError::IoError {
+        message: format!("Failed to {}: {}", what, std::io::Error::last_os_error()),
+    };
+    // SAFETY: plain syscalls; `filter` points to `program`, which outlives them
+    unsafe {
+        // Required to install a filter without CAP_SYS_ADMIN
+        if libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0) != 0 {
+            return Err(os_error("set no_new_privs"));
+        }
+        // TSYNC: applies to the threads that already exist too
+        let flags = libc::SECCOMP_FILTER_FLAG_TSYNC;
+        let filter = &filter as *const sock_fprog;
+        if libc::syscall(libc::SYS_seccomp, libc::SECCOMP_SET_MODE_FILTER, flags, filter) != 0 {
+            return Err(os_error("install the seccomp network filter"));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+fn install(on_violation: NetworkViolation) -> Result<()> {
+    use std::ffi::{c_char, c_int, c_ulonglong, CStr};
+
+    /// `SANDBOX_NAMED` (`<sandbox.h>`): `profile` names a built-in profile
+    const SANDBOX_NAMED: c_ulonglong = 1;
+
+    extern "C" {
+        fn sandbox_init(profile: *const c_char, flags: c_ulonglong, errorbuf: *mut *mut c_char) -> c_int;
+        fn sandbox_free_error(errorbuf: *mut c_char);
+    }
+
+    if on_violation == NetworkViolation::Kill {
+        return Err(JozinError::NotSupported {
+            feature: "killing on network access on macOS (use NetworkViolation::Deny)".to_string(),
+        });
+    }
+    // `kSBXProfileNoNetwork`
+    let profile = c"no-network";
+    let mut error: *mut c_char = std::ptr::null_mut();
+    // SAFETY: `profile` is NUL-terminated; `error` is freed by the sandbox library
+    unsafe {
+        if sandbox_init(profile.as_ptr(), SANDBOX_NAMED, &mut error) != 0 {
+            let message = if error.is_null() {
+                "unknown error".to_string()
+            } else {
+                let message = CStr::from_ptr(error).to_string_lossy().into_owned();
+                sandbox_free_error(error);
+                message
+            };
+            return Err(JozinError::IoError {
+                message: format!("Failed to enter the no-network sandbox: {}", message),
+            });
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(any(
+    all(target_os = "linux", any(target_arch = "x86_64", target_arch = "aarch64")),
+    target_os = "macos"
+)))]
+fn install(_on_violation: NetworkViolation) -> Result<()> {
+    Err(JozinError::NotSupported {
+        feature: "network isolation on this platform (Linux seccomp or macOS sandbox only)".to_string(),
+    })
+}
@@ -0,0 +1,224 @@
+//! Network filesystem detection
+//!
+//! Libraries on a NAS behave differently from local disks: `fsync` over SMB
+//! can take longer than hashing the file, network bandwidth rather than CPU
+//! limits throughput, and change notifications are not delivered from remote
+//! filesystems. [`detect_filesystem_type()`] tells the operations which kind
+//! of filesystem a library is on so they can adapt (see
+//! `ScanConfig::adjusted_for_filesystem()`).
+//!
+//! ## Platforms
+//!
+//! | Platform | Source                                               |
+//! |----------|------------------------------------------------------|
+//! | Linux    | `/proc/self/mountinfo` (longest matching mount point) |
+//! | Windows  | `GetDriveTypeW` on the volume root (remote drives are reported as [`FilesystemType::Smb`]) |
+//! | Other    | Always [`FilesystemType::Unknown`]                   |
+//!
+//! Detection is a single read (or call) per operation; callers detect once
+//! for the operation's root and reuse the result.
+
+use std::fmt;
+use std::path::Path;
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+/// Scan threads used on network filesystems.
+pub const NETWORK_MAX_THREADS: u16 = 2;
+
+/// Read throughput used on network filesystems when no lower limit is set
+/// (20 MB/s).
+pub const NETWORK_IO_BUDGET_BYTES_PER_SEC: u64 = 20_000_000;
+
+// ============================================================================
+// Public Types
+// ============================================================================
+
+/// Kind of filesystem a path lives on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FilesystemType {
+    /// A local disk filesystem (ext4, APFS, NTFS, ...)
+    Local,
+    /// SMB/CIFS network share
+    Smb,
+    /// NFS network share
+    Nfs,
+    /// In-memory filesystem
+    Tmpfs,
+    /// Could not be determined
+    Unknown,
+}
+
+impl FilesystemType {
+    /// Whether the filesystem is a network share.
+    pub fn is_network(&self) -> bool {
+        matches!(self, FilesystemType::Smb | FilesystemType::Nfs)
+    }
+
+    /// Maps a Linux filesystem type name (as in `/proc/mounts`).
+    pub fn from_linux_fs_name(name: &str) -> Self {
+        match name {
+            "cifs" | "smb3" | "smbfs" => FilesystemType::Smb,
+            "nfs" | "nfs4" => FilesystemType::Nfs,
+            "tmpfs" | "ramfs" => FilesystemType::Tmpfs,
+            "ext2" | "ext3" | "ext4" | "xfs" | "btrfs" | "zfs" | "f2fs" | "jfs" | "reiserfs" | "vfat" | "exfat"
+            | "ntfs" | "ntfs3" | "hfsplus" | "apfs" | "overlay" | "bcachefs" => FilesystemType::Local,
+            _ => FilesystemType::Unknown,
+        }
+    }
+}
+
+impl fmt::Display for FilesystemType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            FilesystemType::Local => "local",
+            FilesystemType::Smb => "smb",
+            FilesystemType::Nfs => "nfs",
+            FilesystemType::Tmpfs => "tmpfs",
+            FilesystemType::Unknown => "unknown",
+        })
+    }
+}
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Detects the filesystem type of `path`.
+///
+/// Never fails; anything that cannot be determined is
+/// [`FilesystemType::Unknown`].
+///
+/// # Example
+///
+/// ```no_run
+/// use jozin_core::util::network_drive::detect_filesystem_type;
+/// use std::path::Path;
+///
+/// if detect_filesystem_type(Path::new("/mnt/nas/photos")).is_network() {
+///     println!("Library is on a network share");
+/// }
+/// ```
+#[cfg(target_os = "linux")]
+pub fn detect_filesystem_type(path: &Path) -> FilesystemType {
+    let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    match std::fs::read_to_string("/proc/self/mountinfo") {
+        Ok(mountinfo) => filesystem_from_mountinfo(&mountinfo, &path),
+        Err(_) => FilesystemType::Unknown,
+    }
+}
+
+/// Detects the filesystem type of `path` (see the module docs).
+#[cfg(windows)]
+pub fn detect_filesystem_type(path: &Path) -> FilesystemType {
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::Storage::FileSystem::{GetDriveTypeW, GetVolumePathNameW};
+
+    // GetDriveTypeW return values
+    const DRIVE_FIXED: u32 = 3;
+    const DRIVE_REMOTE: u32 = 4;
+    const DRIVE_RAMDISK: u32 = 6;
+
+    let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    let wide: Vec<u16> = path.as_os_str().encode_wide().chain(std::iter::once(0)).collect();
+    let mut root = [0u16; 1024];
+
+    // SAFETY: `wide` is NUL-terminated and `root` is a writable buffer of the
+    // given length; both outlive the calls.
+    let drive_type = unsafe {
+        if GetVolumePathNameW(wide.as_ptr(), root.as_mut_ptr(), root.len() as u32) == 0 {
+            return FilesystemType::Unknown;
+        }
+        GetDriveTypeW(root.as_ptr())
+    };
+    match drive_type {
+        DRIVE_FIXED => FilesystemType::Local,
+        DRIVE_REMOTE => FilesystemType::Smb,
+        DRIVE_RAMDISK => FilesystemType::Tmpfs,
+        _ => FilesystemType::Unknown,
+    }
+}
+
+/// Detects the filesystem type of `path` (see the module docs).
+#[cfg(not(any(target_os = "linux", windows)))]
+pub fn detect_filesystem_type(_path: &Path) -> FilesystemType {
+    FilesystemType::Unknown
+}
+
+// ============================================================================
+// Internal Helpers
+// ============================================================================
+
+/// Finds the filesystem of the longest mount point containing `path`.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn filesystem_from_mountinfo(mountinfo: &str, path: &Path) -> FilesystemType {
+    let mut best: Option<(usize, &str)> = None;
+    for line in mountinfo.lines() {
+        // <id> <parent> <major:minor> <root> <mount point> <options> [optional...] - <fstype> <source> <super options>
+        let mut fields = line.split(' ');
+        let Some(mount_point) = fields.nth(4).map(unescape_mount_field) else {
+            continue;
+        };
+        let Some(fs_type) = fields.skip_while(|f| *f != "-").nth(1) else {
+            continue;
+        };
+        let mount_point = Path::new(&mount_point);
+        if path.starts_with(mount_point) {
+            let depth = mount_point.components().count();
+            if best.is_none_or(|(best_depth, _)| depth >= best_depth) {
+                best = Some((depth, fs_type));
+            }
+        }
+    }
+    best.map_or(FilesystemType::Unknown, |(_, fs_type)| FilesystemType::from_linux_fs_name(fs_type))
+}
+
+/// Decodes the octal escapes (`\040` for space) used in mountinfo fields.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn unescape_mount_field(field: &str) -> String {
+    let bytes = field.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'\\' && i + 4 <= bytes.len() && bytes[i + 1..i + 4].iter().all(|b| (b'0'..=b'7').contains(b)) {
+            let code = bytes[i + 1..i + 4].iter().fold(0u32, |acc, b| acc * 8 + u32::from(b - b'0'));
+            out.push(code as u8);
+            i += 4;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MOUNTINFO: &str = "\
+1 0 0:1 / / rw - ext4 /dev/sda1 rw
+2 1 0:2 / /mnt/nas rw,relatime shared:1 - cifs //nas/photos rw
+3 1 0:3 / /mnt/nas\\040backup rw - nfs4 nas:/backup rw
+4 1 0:4 / /tmp rw - tmpfs tmpfs rw
+5 1 0:5 / /media/usb rw - fuseblk /dev/sdb1 rw";
+
+    #[test]
+    fn test_filesystem_from_mountinfo() {
+        let detect = |path: &str| filesystem_from_mountinfo(MOUNTINFO, Path::new(path));
+        assert_eq!(detect("/home/user/photos"), FilesystemType::Local);
+        assert_eq!(detect("/mnt/nas/2024/IMG_0001.jpg"), FilesystemType::Smb);
+        assert_eq!(detect("/mnt/nas backup/2019"), FilesystemType::Nfs);
+        assert_eq!(detect("/mnt/nasty"), FilesystemType::Local);
+        assert_eq!(detect("/tmp/scratch"), FilesystemType::Tmpfs);
+        assert_eq!(detect("/media/usb/DCIM"), FilesystemType::Unknown);
+        assert!(FilesystemType::Nfs.is_network());
+        assert!(!FilesystemType::Tmpfs.is_network());
+    }
+}
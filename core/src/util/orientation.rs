@@ -0,0 +1,55 @@
+//! EXIF orientation handling for decoded images
+//!
+//! Cameras store pixels in sensor order and record how to display them in the
+//! EXIF `Orientation` tag (1-8). Anything that works in display coordinates,
+//! such as face bounding boxes or thumbnails, must apply it first.
+
+use image::DynamicImage;
+
+/// Rotates/flips a decoded image so it appears as intended for display.
+///
+/// # Arguments
+///
+/// * `image` - Decoded image in stored (sensor) orientation
+/// * `orientation` - EXIF orientation value (1-8). `None` or out-of-range values
+///   leave the image unchanged.
+///
+/// | Value | Transform                       |
+/// |-------|---------------------------------|
+/// | 1     | none                            |
+/// | 2     | flip horizontal                 |
+/// | 3     | rotate 180°                     |
+/// | 4     | flip vertical                   |
+/// | 5     | rotate 90° CW + flip horizontal |
+/// | 6     | rotate 90° CW                   |
+/// | 7     | rotate 270° CW + flip horizontal|
+/// | 8     | rotate 270° CW                  |
+pub fn apply_orientation(image: DynamicImage, orientation: Option<u8>) -> DynamicImage {
+    match orientation {
+        Some(2) => image.fliph(),
+        Some(3) => image.rotate180(),
+        Some(4) => image.flipv(),
+        Some(5) => image.rotate90().fliph(),
+        Some(6) => image.rotate90(),
+        Some(7) => image.rotate270().fliph(),
+        Some(8) => image.rotate270(),
+        _ => image,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_orientation_swaps_dimensions_for_rotations() {
+        let image = DynamicImage::new_rgb8(40, 20);
+
+        assert_eq!(apply_orientation(image.clone(), None).width(), 40);
+        assert_eq!(apply_orientation(image.clone(), Some(3)).width(), 40);
+        for o in 5..=8 {
+            let rotated = apply_orientation(image.clone(), Some(o));
+            assert_eq!((rotated.width(), rotated.height()), (20, 40), "orientation {}", o);
+        }
+    }
+}
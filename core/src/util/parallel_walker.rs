@@ -0,0 +1,182 @@
+//! Directory traversal overlapped with per-entry work
+//!
+//! `walkdir` yields one entry at a time: a sequential loop stats and filters
+//! an entry, then asks for the next, so reading directories and processing
+//! entries never overlap. On fast SSDs with deep queues that leaves most of
+//! the device idle.
+//!
+//! [`ParallelWalker`] walks on a dedicated producer thread that keeps a
+//! bounded buffer of `min(max_threads * 2, 32)` entries filled, while a rayon
+//! parallel iterator hands the buffered entries to worker threads.
+//!
+//! ## Ordering and Errors
+//!
+//! Entries are processed in no particular order; callers that report results
+//! sort them afterwards. Walk errors (permission denied, symlink loops) are
+//! yielded in the stream as `JozinError::IoError` items, exactly where the
+//! sequential walker yields them, and the walk continues.
+//!
+//! ## Usage
+//!
+//! ```no_run
+//! use jozin_core::util::parallel_walker::ParallelWalker;
+//! use rayon::prelude::*;
+//! use std::path::Path;
+//!
+//! let files = ParallelWalker::new(Path::new("/photos"), true)
+//!     .max_threads(8)
+//!     .par_iter()
+//!     .filter_map(|entry| entry.ok())
+//!     .filter(|entry| entry.file_type().is_file())
+//!     .count();
+//! println!("{} files", files);
+//! ```
+
+use crate::util::platform::is_backup_excluded;
+use crate::util::symlink::{walker, SymlinkStrategy};
+use crate::{JozinError, Result};
+use rayon::iter::{ParallelBridge, ParallelIterator};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::thread;
+use walkdir::DirEntry;
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+/// Upper bound of the prefetch buffer, whatever the thread count.
+pub const MAX_PREFETCH_ENTRIES: usize = 32;
+
+// ============================================================================
+// Public Types
+// ============================================================================
+
+/// A directory walk whose entries are consumed by a rayon parallel iterator.
+#[derive(Debug, Clone)]
+pub struct ParallelWalker {
+    root: PathBuf,
+    recursive: bool,
+    symlinks: SymlinkStrategy,
+    max_threads: u16,
+    skip_backup_excluded: bool,
+}
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+impl ParallelWalker {
+    /// Creates a walker over `root` (depth 1 unless `recursive`), following
+    /// symlinks and prefetching for one thread until configured otherwise.
+    pub fn new(root: &Path, recursive: bool) -> Self {
+        Self {
+            root: root.to_path_buf(),
+            recursive,
+            symlinks: SymlinkStrategy::default(),
+            max_threads: 1,
+            skip_backup_excluded: false,
+        }
+    }
+
+    /// Sets the symlink strategy (see [`crate::util::symlink::walker()`]).
+    pub fn symlinks(mut self, symlinks: SymlinkStrategy) -> Self {
+        self.symlinks = symlinks;
+        self
+    }
+
+    /// Sets the number of consuming threads the buffer is sized for.
+    pub fn max_threads(mut self, max_threads: u16) -> Self {
+        self.max_threads = max_threads.max(1);
+        self
+    }
+
+    /// Doesn't descend into subdirectories excluded from backups (see
+    /// [`crate::util::platform::is_backup_excluded()`]).
+    pub fn skip_backup_excluded(mut self, skip: bool) -> Self {
+        self.skip_backup_excluded = skip;
+        self
+    }
+
+    /// Number of entries read ahead of the consumers.
+    pub fn prefetch(&self) -> usize {
+        (usize::from(self.max_threads) * 2).min(MAX_PREFETCH_ENTRIES)
+    }
+
+    /// Starts the walk and returns its entries as a parallel iterator.
+    ///
+    /// The iterator runs on the current rayon pool (use
+    /// `ThreadPool::install` to bound it). The producer is a plain thread
+    /// rather than a pool task, so a single-threaded pool cannot deadlock
+    /// waiting on itself; it stops early once the iterator is dropped.
+    pub fn par_iter(self) -> impl ParallelIterator<Item = Result<DirEntry>> {
+        let (sender, receiver) = mpsc::sync_channel(self.prefetch());
+        let skip_backup_excluded = self.skip_backup_excluded;
+        let walk = walker(&self.root, self.recursive, self.symlinks).into_iter().filter_entry(move |entry| {
+            !(skip_backup_excluded && entry.depth() > 0 && entry.file_type().is_dir() && is_backup_excluded(entry.path()))
+        });
+
+        thread::spawn(move || {
+            for entry in walk {
+                let entry = entry.map_err(|e| JozinError::IoError {
+                    message: e.to_string(),
+                });
+                if sender.send(entry).is_err() {
+                    break;
+                }
+            }
+        });
+
+        receiver.into_iter().par_bridge()
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_par_iter_matches_sequential_walk() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::create_dir(temp_dir.path().join("sub")).unwrap();
+        for i in 0..50 {
+            std::fs::write(temp_dir.path().join(format!("{}.jpg", i)), b"x").unwrap();
+            std::fs::write(temp_dir.path().join(format!("sub/{}.jpg", i)), b"x").unwrap();
+        }
+
+        let sorted = |mut paths: Vec<PathBuf>| {
+            paths.sort();
+            paths
+        };
+        let sequential: Vec<PathBuf> = walker(temp_dir.path(), true, SymlinkStrategy::Follow)
+            .into_iter()
+            .map(|e| e.unwrap().into_path())
+            .collect();
+        let parallel: Vec<PathBuf> = ParallelWalker::new(temp_dir.path(), true)
+            .max_threads(4)
+            .par_iter()
+            .map(|e| e.unwrap().into_path())
+            .collect();
+        assert_eq!(sorted(parallel), sorted(sequential));
+
+        let shallow = ParallelWalker::new(temp_dir.path(), false).par_iter().count();
+        assert_eq!(shallow, 52); // root, 50 files, sub
+    }
+
+    #[test]
+    fn test_walk_errors_are_yielded() {
+        let temp_dir = TempDir::new().unwrap();
+        let entries: Vec<Result<DirEntry>> =
+            ParallelWalker::new(&temp_dir.path().join("missing"), true).par_iter().collect();
+        assert_eq!(entries.len(), 1);
+        assert!(matches!(entries[0], Err(JozinError::IoError { .. })));
+
+        assert_eq!(ParallelWalker::new(temp_dir.path(), true).max_threads(64).prefetch(), MAX_PREFETCH_ENTRIES);
+        assert_eq!(ParallelWalker::new(temp_dir.path(), true).prefetch(), 2);
+    }
+}
@@ -0,0 +1,160 @@
+//! Canonical `source.file_path` values
+//!
+//! The same photo can be scanned as `./photos/img.jpg`, `photos/img.jpg` or
+//! `/home/user/photos/img.jpg`, and on Windows as `C:\Photos\img.jpg`.
+//! Sidecars that record those spellings verbatim can't be compared or moved
+//! between machines reliably. [`normalise_sidecar_path()`] turns all of them
+//! into one canonical form:
+//!
+//! - Relative paths are resolved against a base directory
+//! - The containing directory is canonicalised (`.`/`..` and directory
+//!   symlinks resolved); the file name itself is kept, so a symlinked photo
+//!   keeps its own name
+//! - Paths that don't exist are normalised lexically instead
+//! - Separators are forward slashes and trailing slashes are removed
+//!
+//! `scan` normalises paths before writing them; `verify --fix
+//! --normalise-paths` rewrites sidecars recorded with other spellings.
+
+use std::path::{Path, PathBuf};
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Returns the canonical form of a photo path as stored in
+/// `SourceInfo::file_path`.
+///
+/// # Arguments
+///
+/// * `file_path` - Path as given (relative, absolute, or Windows-style)
+/// * `base_dir` - Directory relative paths are resolved against (usually
+///   the current directory)
+///
+/// # Example
+///
+/// ```
+/// use jozin_core::util::path_normalise::normalise_sidecar_path;
+/// use std::path::Path;
+///
+/// let path = normalise_sidecar_path("albums/../photos/img.jpg/", Path::new("/nonexistent/library"));
+/// assert_eq!(path, "/nonexistent/library/photos/img.jpg");
+/// ```
+pub fn normalise_sidecar_path(file_path: &str, base_dir: &Path) -> String {
+    let unified = file_path.replace('\\', "/");
+    let joined = if is_absolute(&unified) {
+        PathBuf::from(&unified)
+    } else {
+        base_dir.join(&unified)
+    };
+
+    // Only paths that are absolute on this platform can exist here
+    if joined.is_absolute() {
+        if let (Some(parent), Some(name)) = (joined.parent(), joined.file_name()) {
+            if let Ok(parent) = parent.canonicalize() {
+                return to_forward_slashes(&parent.join(name));
+            }
+        }
+    }
+    lexically_normalise(&to_forward_slashes(&joined))
+}
+
+// ============================================================================
+// Internal Helpers
+// ============================================================================
+
+/// Whether a (forward-slash) path is absolute on any platform: `/...`,
+/// `C:/...`, or a `//server/share` UNC path.
+fn is_absolute(path: &str) -> bool {
+    drive_prefix(path).is_some() || path.starts_with('/')
+}
+
+/// The `C:/` prefix of a Windows path, if any.
+fn drive_prefix(path: &str) -> Option<&str> {
+    let bytes = path.as_bytes();
+    (bytes.len() >= 3 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':' && bytes[2] == b'/').then(|| &path[..3])
+}
+
+/// Resolves `.`, `..` and repeated or trailing slashes of a forward-slash
+/// path without touching the filesystem.
+///
+/// Works on the string rather than [`Path`] so Windows drive and UNC
+/// prefixes survive on every platform.
+fn lexically_normalise(path: &str) -> String {
+    let prefix = if path.starts_with("//") {
+        "//"
+    } else if path.starts_with('/') {
+        "/"
+    } else {
+        drive_prefix(path).unwrap_or("")
+    };
+
+    let mut segments: Vec<&str> = Vec::new();
+    for segment in path[prefix.len()..].split('/') {
+        match segment {
+            "" | "." => {}
+            ".." if segments.last().is_some_and(|last| *last != "..") => {
+                segments.pop();
+            }
+            // `..` above the root is dropped; above a relative path it's kept
+            ".." if !prefix.is_empty() => {}
+            other => segments.push(other),
+        }
+    }
+    format!("{}{}", prefix, segments.join("/"))
+}
+
+/// Renders a path with `/` separators and no Windows verbatim prefix.
+fn to_forward_slashes(path: &Path) -> String {
+    let rendered = path.to_string_lossy().replace('\\', "/");
+    if let Some(rest) = rendered.strip_prefix("//?/UNC/") {
+        return format!("//{}", rest);
+    }
+    rendered.strip_prefix("//?/").map(str::to_string).unwrap_or(rendered)
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_relative_paths() {
+        let temp_dir = TempDir::new().unwrap();
+        let base = temp_dir.path().canonicalize().unwrap();
+        std::fs::create_dir(base.join("photos")).unwrap();
+        std::fs::write(base.join("photos/img.jpg"), b"image").unwrap();
+        let expected = format!("{}/photos/img.jpg", to_forward_slashes(&base));
+
+        assert_eq!(normalise_sidecar_path("./photos/img.jpg", &base), expected);
+        assert_eq!(normalise_sidecar_path("photos/img.jpg", &base), expected);
+        assert_eq!(normalise_sidecar_path("photos/../photos/./img.jpg", &base), expected);
+        // Missing files are still made absolute
+        assert_eq!(
+            normalise_sidecar_path("gone/../new.jpg", &base),
+            format!("{}/new.jpg", to_forward_slashes(&base))
+        );
+    }
+
+    #[test]
+    fn test_absolute_paths() {
+        assert_eq!(
+            normalise_sidecar_path("/nonexistent/photos/img.jpg", Path::new("/elsewhere")),
+            "/nonexistent/photos/img.jpg"
+        );
+        assert_eq!(normalise_sidecar_path("/nonexistent/photos//", Path::new("/")), "/nonexistent/photos");
+        assert_eq!(normalise_sidecar_path("/", Path::new("/elsewhere")), "/");
+    }
+
+    #[test]
+    fn test_windows_paths() {
+        assert_eq!(normalise_sidecar_path(r"C:\Photos\2024\img.jpg", Path::new("/base")), "C:/Photos/2024/img.jpg");
+        assert_eq!(normalise_sidecar_path(r"\\nas\photos\img.jpg", Path::new("/base")), "//nas/photos/img.jpg");
+        assert_eq!(normalise_sidecar_path(r"D:\a\..\b\img.jpg\", Path::new("/base")), "D:/b/img.jpg");
+        assert_eq!(to_forward_slashes(Path::new(r"\\?\C:\Photos\img.jpg")), "C:/Photos/img.jpg");
+    }
+}
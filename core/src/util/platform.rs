@@ -0,0 +1,262 @@
+//! Platform integration of sidecar files (macOS Spotlight)
+//!
+//! On macOS, Spotlight indexes extended attributes of every file. Jožin uses
+//! two of them:
+//!
+//! | Attribute                                          | Use                                    |
+//! |----------------------------------------------------|----------------------------------------|
+//! | `com.apple.metadata:_kMDItemUserTags`              | Finder tags written on sidecars ([`mark_jozin_generated()`], [`add_spotlight_metadata()`]) |
+//! | `com.apple.metadata:com_apple_backup_excludeItem`  | Directories carrying it are skipped by scans ([`is_backup_excluded()`]) |
+//!
+//! Tagging every sidecar `jozin-generated` lets users find (or exclude) Jožin
+//! files with a Spotlight search such as `tag:jozin-generated`. Tag values
+//! are binary property lists, encoded by [`encode_user_tags()`].
+//!
+//! ## Platforms
+//!
+//! | Platform | Behaviour                                       |
+//! |----------|-------------------------------------------------|
+//! | macOS    | `setxattr`/`getxattr`                           |
+//! | Other    | Tagging is a no-op, nothing is backup-excluded  |
+
+use crate::Result;
+use std::path::Path;
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+/// Extended attribute holding Finder/Spotlight tags.
+pub const USER_TAGS_XATTR: &str = "com.apple.metadata:_kMDItemUserTags";
+
+/// Extended attribute marking items excluded from Time Machine backups.
+pub const BACKUP_EXCLUDE_XATTR: &str = "com.apple.metadata:com_apple_backup_excludeItem";
+
+/// Tag written on every sidecar by [`mark_jozin_generated()`].
+pub const JOZIN_GENERATED_TAG: &str = "jozin-generated";
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Sets the Spotlight tags of a sidecar file, replacing existing tags.
+///
+/// No-op on platforms other than macOS.
+///
+/// # Errors
+///
+/// Returns `JozinError::IoError` if the attribute cannot be written (e.g.
+/// the filesystem has no extended attributes).
+///
+/// # Example
+///
+/// ```no_run
+/// use jozin_core::util::platform::add_spotlight_metadata;
+/// use std::path::Path;
+///
+/// add_spotlight_metadata(Path::new("/photos/IMG_1234.JPG.json"), &["beach".to_string()])?;
+/// # Ok::<(), jozin_core::JozinError>(())
+/// ```
+#[cfg(target_os = "macos")]
+pub fn add_spotlight_metadata(sidecar_path: &Path, tags: &[String]) -> Result<()> {
+    macos::set_xattr(sidecar_path, USER_TAGS_XATTR, &encode_user_tags(tags))
+}
+
+/// Sets the Spotlight tags of a sidecar file (no-op on this platform).
+#[cfg(not(target_os = "macos"))]
+pub fn add_spotlight_metadata(_sidecar_path: &Path, _tags: &[String]) -> Result<()> {
+    Ok(())
+}
+
+/// Tags a file `jozin-generated` in Spotlight.
+///
+/// Called after every sidecar write. No-op on platforms other than macOS.
+///
+/// # Errors
+///
+/// See [`add_spotlight_metadata()`].
+pub fn mark_jozin_generated(path: &Path) -> Result<()> {
+    add_spotlight_metadata(path, &[JOZIN_GENERATED_TAG.to_string()])
+}
+
+/// Whether a path carries the backup-exclude attribute (always `false`
+/// on platforms other than macOS).
+#[cfg(target_os = "macos")]
+pub fn is_backup_excluded(path: &Path) -> bool {
+    macos::has_xattr(path, BACKUP_EXCLUDE_XATTR)
+}
+
+/// Whether a path carries the backup-exclude attribute (always `false`
+/// on this platform).
+#[cfg(not(target_os = "macos"))]
+pub fn is_backup_excluded(_path: &Path) -> bool {
+    false
+}
+
+/// Encodes tag names as the binary property list (`bplist00`) array of
+/// strings that `_kMDItemUserTags` holds.
+///
+/// Platform-independent so the encoding can be tested everywhere.
+///
+/// # Example
+///
+/// ```
+/// use jozin_core::util::platform::encode_user_tags;
+///
+/// let plist = encode_user_tags(&["jozin-generated".to_string()]);
+/// assert!(plist.starts_with(b"bplist00"));
+/// ```
+pub fn encode_user_tags(tags: &[String]) -> Vec<u8> {
+    let mut plist = b"bplist00".to_vec();
+    let object_count = tags.len() + 1;
+    let ref_size: u8 = if object_count <= usize::from(u8::MAX) { 1 } else { 2 };
+    let mut offsets = Vec::with_capacity(object_count);
+
+    // Object 0: the array, referencing objects 1..=n
+    offsets.push(plist.len());
+    push_marker(&mut plist, 0xA0, tags.len());
+    for index in 1..object_count {
+        push_sized_int(&mut plist, index as u64, ref_size);
+    }
+
+    // Objects 1..=n: the strings (ASCII, or UTF-16BE otherwise)
+    for tag in tags {
+        offsets.push(plist.len());
+        if tag.is_ascii() {
+            push_marker(&mut plist, 0x50, tag.len());
+            plist.extend_from_slice(tag.as_bytes());
+        } else {
+            let units: Vec<u16> = tag.encode_utf16().collect();
+            push_marker(&mut plist, 0x60, units.len());
+            for unit in units {
+                plist.extend_from_slice(&unit.to_be_bytes());
+            }
+        }
+    }
+
+    let offset_table_offset = plist.len();
+    let offset_size = int_size(offset_table_offset as u64);
+    for offset in offsets {
+        push_sized_int(&mut plist, offset as u64, offset_size);
+    }
+
+    // Trailer: 6 unused bytes, offset and reference sizes, object count,
+    // top object, offset table position
+    plist.extend_from_slice(&[0; 6]);
+    plist.push(offset_size);
+    plist.push(ref_size);
+    plist.extend_from_slice(&(object_count as u64).to_be_bytes());
+    plist.extend_from_slice(&0u64.to_be_bytes());
+    plist.extend_from_slice(&(offset_table_offset as u64).to_be_bytes());
+    plist
+}
+
+// ============================================================================
+// Internal Helpers
+// ============================================================================
+
+/// Pushes an object marker with its length (inline up to 14, otherwise
+/// followed by an integer object).
+fn push_marker(plist: &mut Vec<u8>, marker: u8, len: usize) {
+    if len < 0x0F {
+        plist.push(marker | len as u8);
+    } else {
+        plist.push(marker | 0x0F);
+        let size = int_size(len as u64);
+        plist.push(0x10 | size.trailing_zeros() as u8);
+        push_sized_int(plist, len as u64, size);
+    }
+}
+
+/// Smallest of 1, 2, 4 or 8 bytes that holds `value`.
+fn int_size(value: u64) -> u8 {
+    match value {
+        0..=0xFF => 1,
+        0x100..=0xFFFF => 2,
+        0x1_0000..=0xFFFF_FFFF => 4,
+        _ => 8,
+    }
+}
+
+/// Pushes the low `size` bytes of `value`, big-endian.
+fn push_sized_int(plist: &mut Vec<u8>, value: u64, size: u8) {
+    plist.extend_from_slice(&value.to_be_bytes()[8 - usize::from(size)..]);
+}
+
+#[cfg(target_os = "macos")]
+mod macos {
+    use crate::{JozinError, Result};
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+    use std::path::Path;
+
+    fn c_string(value: &[u8]) -> Result<CString> {
+        CString::new(value).map_err(|e| JozinError::IoError { message: e.to_string() })
+    }
+
+    /// Writes an extended attribute (following symlinks).
+    pub(super) fn set_xattr(path: &Path, name: &str, value: &[u8]) -> Result<()> {
+        let c_path = c_string(path.as_os_str().as_bytes())?;
+        let c_name = c_string(name.as_bytes())?;
+        // SAFETY: both strings are NUL-terminated and `value` is valid for
+        // `value.len()` bytes for the duration of the call.
+        let status = unsafe { libc::setxattr(c_path.as_ptr(), c_name.as_ptr(), value.as_ptr().cast(), value.len(), 0, 0) };
+        if status == 0 {
+            Ok(())
+        } else {
+            Err(std::io::Error::last_os_error().into())
+        }
+    }
+
+    /// Whether an extended attribute exists (querying its size only).
+    pub(super) fn has_xattr(path: &Path, name: &str) -> bool {
+        let (Ok(c_path), Ok(c_name)) = (c_string(path.as_os_str().as_bytes()), c_string(name.as_bytes())) else {
+            return false;
+        };
+        // SAFETY: both strings are NUL-terminated; a null buffer of size 0
+        // only queries the attribute's size.
+        unsafe { libc::getxattr(c_path.as_ptr(), c_name.as_ptr(), std::ptr::null_mut(), 0, 0, 0) >= 0 }
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_user_tags() {
+        let plist = encode_user_tags(&[JOZIN_GENERATED_TAG.to_string()]);
+        let expected: Vec<u8> = [
+            b"bplist00".as_slice(),
+            &[0xA1, 0x01],                // array of 1, ref to object 1
+            &[0x5F, 0x10, 0x0F],          // ASCII string, length 15 as int object
+            b"jozin-generated",
+            &[0x08, 0x0A],                // offset table
+            &[0, 0, 0, 0, 0, 0, 0x01, 0x01],
+            &2u64.to_be_bytes(),
+            &0u64.to_be_bytes(),
+            &28u64.to_be_bytes(),
+        ]
+        .concat();
+        assert_eq!(plist, expected);
+
+        // Non-ASCII tags are UTF-16BE
+        let plist = encode_user_tags(&["Jožin".to_string()]);
+        assert_eq!(&plist[10..13], &[0x65, 0x00, b'J']);
+    }
+
+    #[test]
+    fn test_noop_without_spotlight() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("a.jpg.json");
+        std::fs::write(&path, b"{}").unwrap();
+        if cfg!(not(target_os = "macos")) {
+            assert!(mark_jozin_generated(&path).is_ok());
+            assert!(!is_backup_excluded(temp_dir.path()));
+        }
+    }
+}
@@ -0,0 +1,386 @@
+//! Pool of long-lived helper processes
+//!
+//! Spawning an external tool per file (`ffprobe`, `exiftool`, an ONNX
+//! server, ...) costs ~100ms of startup each time. A [`ProcessPool`] keeps a
+//! fixed number of worker threads, each owning one persistent child process,
+//! and hands requests to whichever worker is free.
+//!
+//! The pool is tool-agnostic. It is built from two closures:
+//! - `spawn` starts a child process with piped stdin/stdout (e.g.
+//!   `exiftool -stay_open True -@ -`)
+//! - `exchange` writes one request to the child's stdin and reads its
+//!   response from stdout
+//!
+//! If a child has exited (crashed) by the time a worker picks up a request, it
+//! is restarted transparently. If a child fails mid-exchange, the request
+//! fails and the child is restarted for the next one.
+//!
+//! ## Usage
+//!
+//! ```no_run
+//! use jozin_core::util::process_pool::{default_pool_size, ProcessPool};
+//! use jozin_core::JozinError;
+//! use std::io::{BufRead, BufReader, Write};
+//! use std::process::{Command, Stdio};
+//!
+//! let pool = ProcessPool::new(
+//!     default_pool_size(),
+//!     || Command::new("cat").stdin(Stdio::piped()).stdout(Stdio::piped()).spawn(),
+//!     |child, request: String| {
+//!         writeln!(child.stdin.as_mut().unwrap(), "{}", request)?;
+//!         let mut line = String::new();
+//!         BufReader::new(child.stdout.as_mut().unwrap()).read_line(&mut line)?;
+//!         Ok(line)
+//!     },
+//! );
+//! let response = pool.submit("hello".to_string()).wait()?;
+//! pool.shutdown();
+//! # Ok::<(), JozinError>(())
+//! ```
+//!
+//! [`PendingResponse`] is a `Future`, so it can also be `.await`ed from an
+//! async caller; `wait()` blocks the current thread instead.
+
+use crate::{JozinError, Result};
+use std::future::Future;
+use std::io;
+use std::pin::Pin;
+use std::process::Child;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Condvar, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+/// Upper bound of [`default_pool_size()`].
+pub const MAX_DEFAULT_POOL_SIZE: usize = 4;
+
+/// How long a child may take to exit after its stdin is closed on shutdown
+/// before it is killed.
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(1);
+
+// ============================================================================
+// Public Types
+// ============================================================================
+
+/// Starts one child process.
+pub type SpawnFn = dyn Fn() -> io::Result<Child> + Send + Sync;
+
+/// Sends one request to a running child and reads the response.
+pub type ExchangeFn<Req, Resp> = dyn Fn(&mut Child, Req) -> Result<Resp> + Send + Sync;
+
+/// A fixed-size pool of worker threads with one persistent child process each.
+///
+/// Dropping the pool shuts it down (see [`ProcessPool::shutdown()`]).
+pub struct ProcessPool<Req, Resp> {
+    jobs: Option<Sender<Job<Req, Resp>>>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+/// The response to a [`ProcessPool::submit()`] request.
+///
+/// Resolves once a worker has processed the request, either by `.await` or by
+/// [`PendingResponse::wait()`].
+pub struct PendingResponse<Resp> {
+    slot: Arc<ResponseSlot<Resp>>,
+}
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Default number of workers: half the CPUs, at most
+/// [`MAX_DEFAULT_POOL_SIZE`], at least one.
+pub fn default_pool_size() -> usize {
+    let cpus = thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+    (cpus / 2).clamp(1, MAX_DEFAULT_POOL_SIZE)
+}
+
+impl<Req: Send + 'static, Resp: Send + 'static> ProcessPool<Req, Resp> {
+    /// Starts `size` workers (at least one).
+    ///
+    /// Children are spawned lazily, on each worker's first request, so a
+    /// missing tool surfaces as a request error rather than here.
+    ///
+    /// # Arguments
+    ///
+    /// * `size` - Number of workers and child processes
+    /// * `spawn` - Starts a child; it should pipe stdin and stdout
+    /// * `exchange` - Performs one request/response round trip with a child
+    pub fn new<S, E>(size: usize, spawn: S, exchange: E) -> Self
+    where
+        S: Fn() -> io::Result<Child> + Send + Sync + 'static,
+        E: Fn(&mut Child, Req) -> Result<Resp> + Send + Sync + 'static,
+    {
+        let spawn: Arc<SpawnFn> = Arc::new(spawn);
+        let exchange: Arc<ExchangeFn<Req, Resp>> = Arc::new(exchange);
+        let (jobs, received) = mpsc::channel::<Job<Req, Resp>>();
+        let received = Arc::new(Mutex::new(received));
+
+        let workers = (0..size.max(1))
+            .map(|_| {
+                let (spawn, exchange, received) = (Arc::clone(&spawn), Arc::clone(&exchange), Arc::clone(&received));
+                thread::spawn(move || run_worker(&*spawn, &*exchange, &received))
+            })
+            .collect();
+
+        Self {
+            jobs: Some(jobs),
+            workers,
+        }
+    }
+
+    /// Queues a request for the next free worker.
+    ///
+    /// If the workers have stopped, the response is an `InternalError`.
+    pub fn submit(&self, request: Req) -> PendingResponse<Resp> {
+        let slot = Arc::new(ResponseSlot::default());
+        let job = Job {
+            request,
+            slot: Arc::clone(&slot),
+        };
+        let unsent = match &self.jobs {
+            Some(jobs) => jobs.send(job).err().map(|mpsc::SendError(job)| job),
+            None => Some(job),
+        };
+        if let Some(job) = unsent {
+            job.slot.fulfil(Err(pool_closed()));
+        }
+        PendingResponse { slot }
+    }
+
+    /// Stops the pool after queued requests are done.
+    ///
+    /// Each child's stdin is closed, which ends well-behaved servers; children
+    /// still running after a short grace period are killed.
+    pub fn shutdown(mut self) {
+        self.stop();
+    }
+}
+
+impl<Req, Resp> ProcessPool<Req, Resp> {
+    fn stop(&mut self) {
+        self.jobs = None;
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
+
+impl<Req, Resp> Drop for ProcessPool<Req, Resp> {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+impl<Resp> PendingResponse<Resp> {
+    /// Blocks until the response is available.
+    ///
+    /// # Errors
+    ///
+    /// Returns whatever the pool's `exchange` returned, `IoError` if the child
+    /// could not be spawned, or `InternalError` if the pool was shut down.
+    pub fn wait(self) -> Result<Resp> {
+        let mut state = lock(&self.slot.state);
+        loop {
+            if let Some(result) = state.result.take() {
+                return result;
+            }
+            state = self.slot.ready.wait(state).unwrap_or_else(|e| e.into_inner());
+        }
+    }
+}
+
+impl<Resp> Future for PendingResponse<Resp> {
+    type Output = Result<Resp>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = lock(&self.slot.state);
+        match state.result.take() {
+            Some(result) => Poll::Ready(result),
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+// ============================================================================
+// Internal Helpers
+// ============================================================================
+
+struct Job<Req, Resp> {
+    request: Req,
+    slot: Arc<ResponseSlot<Resp>>,
+}
+
+struct ResponseSlot<Resp> {
+    state: Mutex<SlotState<Resp>>,
+    ready: Condvar,
+}
+
+struct SlotState<Resp> {
+    result: Option<Result<Resp>>,
+    waker: Option<Waker>,
+}
+
+impl<Resp> Default for ResponseSlot<Resp> {
+    fn default() -> Self {
+        Self {
+            state: Mutex::new(SlotState { result: None, waker: None }),
+            ready: Condvar::new(),
+        }
+    }
+}
+
+impl<Resp> ResponseSlot<Resp> {
+    fn fulfil(&self, result: Result<Resp>) {
+        let mut state = lock(&self.state);
+        state.result = Some(result);
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+        self.ready.notify_all();
+    }
+}
+
+/// Worker thread: serves jobs until the pool's sender is dropped.
+fn run_worker<Req, Resp>(
+    spawn: &SpawnFn,
+    exchange: &ExchangeFn<Req, Resp>,
+    received: &Mutex<Receiver<Job<Req, Resp>>>,
+) {
+    let mut child: Option<Child> = None;
+    loop {
+        // Hold the lock only while waiting for the next job
+        let job = match lock(received).recv() {
+            Ok(job) => job,
+            Err(_) => break,
+        };
+
+        // Restart a child that has exited since the last request
+        if let Some(running) = child.as_mut() {
+            if !matches!(running.try_wait(), Ok(None)) {
+                stop_child(child.take());
+            }
+        }
+        if child.is_none() {
+            match spawn() {
+                Ok(spawned) => child = Some(spawned),
+                Err(e) => {
+                    job.slot.fulfil(Err(JozinError::IoError {
+                        message: format!("Failed to start pooled process: {}", e),
+                    }));
+                    continue;
+                }
+            }
+        }
+
+        let result = match child.as_mut() {
+            Some(running) => exchange(running, job.request),
+            None => Err(pool_closed()),
+        };
+        if result.is_err() {
+            // The child may be left mid-response; start afresh next time
+            stop_child(child.take());
+        }
+        job.slot.fulfil(result);
+    }
+    stop_child(child.take());
+}
+
+/// Closes the child's stdin, waits up to [`SHUTDOWN_GRACE`], then kills it.
+fn stop_child(child: Option<Child>) {
+    let Some(mut child) = child else {
+        return;
+    };
+    drop(child.stdin.take());
+    let deadline = Instant::now() + SHUTDOWN_GRACE;
+    while Instant::now() < deadline {
+        if !matches!(child.try_wait(), Ok(None)) {
+            return;
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+fn pool_closed() -> JozinError {
+    JozinError::InternalError {
+        message: "Process pool has been shut down".to_string(),
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Write};
+    use std::process::{Command, Stdio};
+
+    /// Echoes lines back; exits on `crash`.
+    fn echo_pool(size: usize) -> ProcessPool<String, String> {
+        ProcessPool::new(
+            size,
+            || {
+                Command::new("sh")
+                    .args(["-c", "while read l; do [ \"$l\" = crash ] && exit 1; echo \"$l\"; done"])
+                    .stdin(Stdio::piped())
+                    .stdout(Stdio::piped())
+                    .spawn()
+            },
+            |child, request: String| {
+                writeln!(child.stdin.as_mut().unwrap(), "{}", request)?;
+                let mut line = String::new();
+                BufReader::new(child.stdout.as_mut().unwrap()).read_line(&mut line)?;
+                if line.is_empty() {
+                    return Err(JozinError::IoError {
+                        message: "process exited".to_string(),
+                    });
+                }
+                Ok(line.trim_end().to_string())
+            },
+        )
+    }
+
+    #[test]
+    fn test_requests_are_served_by_pool() {
+        let pool = echo_pool(2);
+        let pending: Vec<_> = (0..8).map(|i| pool.submit(format!("req {}", i))).collect();
+        let responses: Vec<String> = pending.into_iter().map(|p| p.wait().unwrap()).collect();
+        assert_eq!(responses, (0..8).map(|i| format!("req {}", i)).collect::<Vec<_>>());
+        pool.shutdown();
+        assert!((1..=MAX_DEFAULT_POOL_SIZE).contains(&default_pool_size()));
+    }
+
+    #[test]
+    fn test_crashed_process_is_restarted() {
+        let pool = echo_pool(1);
+        assert!(pool.submit("crash".to_string()).wait().is_err());
+        assert_eq!(pool.submit("after".to_string()).wait().unwrap(), "after");
+
+        // Also usable as a future
+        let mut pending = pool.submit("polled".to_string());
+        let mut cx = Context::from_waker(Waker::noop());
+        let response = loop {
+            if let Poll::Ready(result) = Pin::new(&mut pending).poll(&mut cx) {
+                break result;
+            }
+            thread::sleep(Duration::from_millis(5));
+        };
+        assert_eq!(response.unwrap(), "polled");
+    }
+}
@@ -0,0 +1,227 @@
+//! Progress logs for crash analysis
+//!
+//! When a long scan dies, the question is how far it got. A
+//! [`ProgressFileWriter`] appends every [`ProgressEvent`] as one JSON line to
+//! a log file (`scan --progress-log`):
+//!
+//! ```text
+//! {"event":"batch_started","total_files":2,"total_bytes":5120,...}
+//! {"event":"file_started","path":"/photos/IMG_0001.jpg"}
+//! {"event":"file_completed","path":"/photos/IMG_0001.jpg","success":true,...}
+//! ```
+//!
+//! Lines are flushed to the OS after each event but not synced, so the log
+//! survives a crash of Jožin (not necessarily of the machine) without
+//! slowing the scan down. [`ProgressFileWriter::summarise()`] reads a log
+//! back: a batch without `batch_completed` was interrupted, and
+//! `in_progress` lists the files it was working on.
+
+use crate::{ProgressEvent, Result};
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+
+// ============================================================================
+// Public Types
+// ============================================================================
+
+/// Appends progress events to a JSONL file.
+#[derive(Debug)]
+pub struct ProgressFileWriter {
+    file: File,
+}
+
+/// Event counts of a progress log.
+///
+/// # Fields
+///
+/// - `batches_started`, `batches_completed`: Batch events
+/// - `files_started`: `file_started` events
+/// - `files_completed`: `file_completed` events (successful or not)
+/// - `files_failed`: `file_completed` events with `success: false`
+/// - `in_progress`: Files started but not completed, in log order
+/// - `invalid_lines`: Lines that are not events (e.g. cut off by a crash)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProgressSummary {
+    pub batches_started: usize,
+    pub batches_completed: usize,
+    pub files_started: usize,
+    pub files_completed: usize,
+    pub files_failed: usize,
+    pub in_progress: Vec<String>,
+    pub invalid_lines: usize,
+}
+
+impl ProgressSummary {
+    /// Whether every batch in the log completed.
+    pub fn is_complete(&self) -> bool {
+        self.batches_started == self.batches_completed
+    }
+}
+
+impl ProgressFileWriter {
+    /// Opens `path` for appending, creating it if needed.
+    ///
+    /// # Errors
+    ///
+    /// Returns `JozinError::IoError` if the file cannot be opened.
+    pub fn new(path: &Path) -> Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self { file })
+    }
+
+    /// Appends `event` as one JSON line and flushes it to the OS.
+    ///
+    /// # Errors
+    ///
+    /// Returns `JozinError::IoError` if the line cannot be written.
+    pub fn write_event(&self, event: &ProgressEvent) -> Result<()> {
+        let mut line = serde_json::to_string(event)?;
+        line.push('\n');
+        // One write per line, so events from worker threads don't interleave
+        let mut file = &self.file;
+        file.write_all(line.as_bytes())?;
+        file.flush()?;
+        Ok(())
+    }
+
+    /// Turns the writer into a progress callback. Write errors are reported
+    /// as warnings; they don't interrupt the operation.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use jozin_core::util::progress_file::ProgressFileWriter;
+    /// use jozin_core::{scan_path, ScanConfig};
+    /// use std::path::Path;
+    ///
+    /// let log = ProgressFileWriter::new(Path::new("scan.jsonl"))?.into_callback();
+    /// scan_path(Path::new("/photos"), &ScanConfig::default(), Some(&log))?;
+    /// # Ok::<(), jozin_core::JozinError>(())
+    /// ```
+    pub fn into_callback(self) -> impl Fn(ProgressEvent) + Send + Sync {
+        move |event: ProgressEvent| {
+            if let Err(e) = self.write_event(&event) {
+                eprintln!("Warning: Failed to write progress log: {}", e);
+            }
+        }
+    }
+
+    /// Counts the events of a progress log.
+    ///
+    /// # Errors
+    ///
+    /// Returns `JozinError::IoError` if the log cannot be read.
+    pub fn summarise(path: &Path) -> Result<ProgressSummary> {
+        let mut summary = ProgressSummary::default();
+        for line in BufReader::new(File::open(path)?).lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str::<ProgressEvent>(&line) {
+                Ok(ProgressEvent::BatchStarted { .. }) => summary.batches_started += 1,
+                Ok(ProgressEvent::BatchCompleted { .. }) => summary.batches_completed += 1,
+                Ok(ProgressEvent::FileStarted { path }) => {
+                    summary.files_started += 1;
+                    summary.in_progress.push(path);
+                }
+                Ok(ProgressEvent::FileCompleted { path, success, .. }) => {
+                    summary.files_completed += 1;
+                    if !success {
+                        summary.files_failed += 1;
+                    }
+                    if let Some(index) = summary.in_progress.iter().position(|p| *p == path) {
+                        summary.in_progress.remove(index);
+                    }
+                }
+                Err(_) => summary.invalid_lines += 1,
+            }
+        }
+        Ok(summary)
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{scan_path, ScanConfig};
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_progress_log_of_scan() {
+        let temp_dir = TempDir::new().unwrap();
+        let photos = temp_dir.path().join("photos");
+        fs::create_dir(&photos).unwrap();
+        for name in ["a.jpg", "b.jpg", "c.jpg"] {
+            fs::write(photos.join(name), name).unwrap();
+        }
+        let log_path = temp_dir.path().join("scan.jsonl");
+
+        let log = ProgressFileWriter::new(&log_path).unwrap().into_callback();
+        scan_path(&photos, &ScanConfig::default(), Some(&log)).unwrap();
+
+        let lines: Vec<serde_json::Value> = fs::read_to_string(&log_path)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.first().unwrap()["event"], "batch_started");
+        assert_eq!(lines.last().unwrap()["event"], "batch_completed");
+        assert_eq!(lines.last().unwrap()["successful"], 3);
+
+        let summary = ProgressFileWriter::summarise(&log_path).unwrap();
+        assert_eq!(
+            summary,
+            ProgressSummary {
+                batches_started: 1,
+                batches_completed: 1,
+                files_started: 3,
+                files_completed: 3,
+                ..ProgressSummary::default()
+            }
+        );
+        assert!(summary.is_complete());
+    }
+
+    #[test]
+    fn test_summarise_interrupted_log() {
+        let temp_dir = TempDir::new().unwrap();
+        let log_path = temp_dir.path().join("scan.jsonl");
+        let writer = ProgressFileWriter::new(&log_path).unwrap();
+        writer
+            .write_event(&ProgressEvent::BatchStarted {
+                total_files: 2,
+                total_bytes: 0,
+                estimated_duration_ms: None,
+                traversal_order: "depth-first".to_string(),
+            })
+            .unwrap();
+        for path in ["a.jpg", "b.jpg"] {
+            writer.write_event(&ProgressEvent::FileStarted { path: path.to_string() }).unwrap();
+        }
+        writer
+            .write_event(&ProgressEvent::FileCompleted {
+                path: "a.jpg".to_string(),
+                success: false,
+                error: Some("unreadable".to_string()),
+                size_bytes: None,
+            })
+            .unwrap();
+        // A crash cut off the last line
+        drop(writer);
+        fs::write(&log_path, fs::read_to_string(&log_path).unwrap() + "{\"event\":\"file_comp").unwrap();
+
+        let summary = ProgressFileWriter::summarise(&log_path).unwrap();
+        assert_eq!((summary.files_started, summary.files_completed, summary.files_failed), (2, 1, 1));
+        assert_eq!(summary.in_progress, vec!["b.jpg".to_string()]);
+        assert_eq!(summary.invalid_lines, 1);
+        assert!(!summary.is_complete());
+    }
+}
@@ -0,0 +1,86 @@
+//! Fan-out of progress events to several consumers
+//!
+//! Operations take a single `progress_callback`. When events must go to more
+//! than one place (e.g. the Tauri frontend and a stderr log),
+//! [`tee_progress()`] combines two callbacks into one that forwards every
+//! event to both, in order. Tees nest, so any number of consumers can be
+//! combined:
+//!
+//! ```
+//! use jozin_core::util::progress_tee::tee_progress;
+//! use jozin_core::ProgressEvent;
+//!
+//! let log = |event: ProgressEvent| eprintln!("{:?}", event);
+//! let ui = |_event: ProgressEvent| { /* forward to the frontend */ };
+//! let audit = |_event: ProgressEvent| { /* append to a journal */ };
+//! let progress = tee_progress(tee_progress(log, ui), audit);
+//! progress(ProgressEvent::FileStarted { path: "IMG_0001.jpg".to_string() });
+//! ```
+
+use crate::ProgressEvent;
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Combines two progress callbacks into one that calls `a`, then `b`, with
+/// each event.
+///
+/// `a` receives a clone of the event and `b` the original. The result is
+/// `Send + Sync` so it can be shared with worker threads.
+pub fn tee_progress<A, B>(a: A, b: B) -> impl Fn(ProgressEvent) + Send + Sync
+where
+    A: Fn(ProgressEvent) + Send + Sync,
+    B: Fn(ProgressEvent) + Send + Sync,
+{
+    move |event: ProgressEvent| {
+        a(event.clone());
+        b(event);
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    fn started(path: &str) -> ProgressEvent {
+        ProgressEvent::FileStarted { path: path.to_string() }
+    }
+
+    fn path(event: &ProgressEvent) -> String {
+        match event {
+            ProgressEvent::FileStarted { path } | ProgressEvent::FileCompleted { path, .. } => path.clone(),
+            ProgressEvent::BatchStarted { total_files, .. } => format!("batch:{}", total_files),
+            ProgressEvent::BatchCompleted { successful, .. } => format!("done:{}", successful),
+        }
+    }
+
+    #[test]
+    fn test_every_consumer_receives_events_in_order() {
+        let received = Mutex::new(Vec::new());
+        let consumer = |name: &'static str| {
+            let received = &received;
+            move |event: ProgressEvent| received.lock().unwrap().push((name, path(&event)))
+        };
+        let progress = tee_progress(tee_progress(consumer("log"), consumer("ui")), consumer("audit"));
+
+        progress(ProgressEvent::BatchStarted { total_files: 2, total_bytes: 0, estimated_duration_ms: None, traversal_order: String::new() });
+        progress(started("a.jpg"));
+        progress(started("b.jpg"));
+
+        drop(progress);
+        let received = received.into_inner().unwrap();
+        for name in ["log", "ui", "audit"] {
+            let events: Vec<&str> =
+                received.iter().filter(|(consumer, _)| *consumer == name).map(|(_, path)| path.as_str()).collect();
+            assert_eq!(events, ["batch:2", "a.jpg", "b.jpg"], "{}", name);
+        }
+        // Consumers are called in tee order for each event
+        assert_eq!(received[..3].iter().map(|(name, _)| *name).collect::<Vec<_>>(), ["log", "ui", "audit"]);
+    }
+}
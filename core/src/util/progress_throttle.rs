@@ -0,0 +1,143 @@
+//! Rate limiting of progress events
+//!
+//! On a fast SSD a scan can report a thousand files per second, more than a
+//! UI that redraws on every event can keep up with. [`throttled_progress()`]
+//! wraps a callback so that at most `max_hz` `FileStarted` events per second
+//! get through; the rest are dropped.
+//!
+//! Events that carry counts or results are never dropped: `BatchStarted`
+//! (the total) and `FileCompleted` (each outcome) always reach the callback,
+//! so progress bars computed from them stay exact.
+//!
+//! ```
+//! use jozin_core::util::progress_tee::tee_progress;
+//! use jozin_core::util::progress_throttle::throttled_progress;
+//! use jozin_core::ProgressEvent;
+//!
+//! let ui = |_event: ProgressEvent| { /* redraw */ };
+//! let log = |event: ProgressEvent| eprintln!("{:?}", event);
+//! // The UI is throttled, the log sees every event
+//! let progress = tee_progress(throttled_progress(ui, 30.0), log);
+//! progress(ProgressEvent::FileStarted { path: "IMG_0001.jpg".to_string() });
+//! ```
+
+use crate::ProgressEvent;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+/// Default event rate for UIs (one per frame at 60 fps).
+pub const DEFAULT_MAX_HZ: f32 = 60.0;
+
+/// Window over which the rate is measured.
+const WINDOW: Duration = Duration::from_secs(1);
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Wraps `callback` so it receives at most `max_hz` `FileStarted` events per
+/// second (rounded, at least one); all other events are always forwarded.
+///
+/// The timestamps of the last `max_hz` forwarded events are kept in a ring
+/// buffer; an event is dropped while the oldest of them is less than a
+/// second old. The result is `Send + Sync` like [`tee_progress()`].
+///
+/// [`tee_progress()`]: crate::util::progress_tee::tee_progress
+pub fn throttled_progress<F>(callback: F, max_hz: f32) -> impl Fn(ProgressEvent) + Send + Sync
+where
+    F: Fn(ProgressEvent) + Send + Sync,
+{
+    let capacity = capacity(max_hz);
+    let sent = Mutex::new(VecDeque::with_capacity(capacity));
+    move |event: ProgressEvent| {
+        if let ProgressEvent::FileStarted { .. } = event {
+            let mut sent = sent.lock().unwrap_or_else(|e| e.into_inner());
+            if !admit(&mut sent, capacity, Instant::now()) {
+                return;
+            }
+        }
+        callback(event);
+    }
+}
+
+// ============================================================================
+// Internal Helpers
+// ============================================================================
+
+/// Events per window for a rate.
+fn capacity(max_hz: f32) -> usize {
+    if max_hz.is_finite() && max_hz >= 1.0 {
+        max_hz.round() as usize
+    } else {
+        1
+    }
+}
+
+/// Records an event at `now` if fewer than `capacity` were recorded within
+/// the last [`WINDOW`].
+fn admit(sent: &mut VecDeque<Instant>, capacity: usize, now: Instant) -> bool {
+    if sent.len() == capacity {
+        match sent.front() {
+            Some(oldest) if now.duration_since(*oldest) < WINDOW => return false,
+            _ => {
+                sent.pop_front();
+            }
+        }
+    }
+    sent.push_back(now);
+    true
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_admit_window() {
+        let start = Instant::now();
+        let mut sent = VecDeque::new();
+        assert!(admit(&mut sent, 2, start));
+        assert!(admit(&mut sent, 2, start + Duration::from_millis(100)));
+        assert!(!admit(&mut sent, 2, start + Duration::from_millis(900)));
+        // The first event left the window
+        assert!(admit(&mut sent, 2, start + Duration::from_millis(1000)));
+        assert!(!admit(&mut sent, 2, start + Duration::from_millis(1050)));
+        assert_eq!(sent.len(), 2);
+
+        assert_eq!((capacity(DEFAULT_MAX_HZ), capacity(29.6), capacity(0.0), capacity(f32::NAN)), (60, 30, 1, 1));
+    }
+
+    #[test]
+    fn test_only_file_started_is_dropped() {
+        let received = Mutex::new(Vec::new());
+        let progress = throttled_progress(|event| received.lock().unwrap().push(event), 5.0);
+
+        progress(ProgressEvent::BatchStarted {
+            total_files: 100,
+            total_bytes: 0,
+            estimated_duration_ms: None,
+            traversal_order: String::new(),
+        });
+        for i in 0..100 {
+            let path = format!("{}.jpg", i);
+            progress(ProgressEvent::FileStarted { path: path.clone() });
+            progress(ProgressEvent::FileCompleted { path, success: true, error: None, size_bytes: None });
+        }
+        drop(progress);
+
+        let received = received.into_inner().unwrap();
+        let count = |matches: fn(&ProgressEvent) -> bool| received.iter().filter(|event| matches(event)).count();
+        assert_eq!(count(|event| matches!(event, ProgressEvent::BatchStarted { .. })), 1);
+        assert_eq!(count(|event| matches!(event, ProgressEvent::FileCompleted { .. })), 100);
+        assert_eq!(count(|event| matches!(event, ProgressEvent::FileStarted { .. })), 5);
+    }
+}
@@ -0,0 +1,234 @@
+//! Moving corrupt photos out of the library
+//!
+//! A photo whose image data no longer decodes (see `verify --check-encoding`)
+//! can't be repaired by Jožin, but leaving it in place keeps failing every
+//! scan. [`Quarantine::quarantine()`] moves it, together with its sidecar,
+//! to a quarantine directory (`~/.jozin/quarantine/` by default) and records
+//! it in the directory's `index.json` manifest:
+//!
+//! ```json
+//! [
+//!   {
+//!     "original_path": "/photos/IMG_1234.JPG",
+//!     "quarantined_path": "/home/me/.jozin/quarantine/IMG_1234.JPG",
+//!     "sidecar_original_path": "/photos/IMG_1234.JPG.json",
+//!     "sidecar_quarantined_path": "/home/me/.jozin/quarantine/IMG_1234.JPG.json",
+//!     "error": "Format error decoding Jpeg: ...",
+//!     "quarantined_at": "2025-01-01T12:00:00Z"
+//!   }
+//! ]
+//! ```
+//!
+//! Files with the same name from different directories are kept apart with
+//! a numeric prefix (`1-IMG_1234.JPG`). Moves across filesystems fall back to
+//! copy and delete.
+
+use crate::util::atomic_rename::atomic_rename;
+use crate::{JozinError, Result, Timestamp};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use time::OffsetDateTime;
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+/// Quarantine location relative to the user's home directory.
+pub const DEFAULT_QUARANTINE_DIR: &str = ".jozin/quarantine";
+
+/// Manifest of the quarantined files, inside the quarantine directory.
+pub const QUARANTINE_INDEX_FILE: &str = "index.json";
+
+// ============================================================================
+// Public Types
+// ============================================================================
+
+/// One quarantined photo.
+///
+/// # Fields
+///
+/// - `original_path`: Where the photo was
+/// - `quarantined_path`: Where it is now
+/// - `sidecar_original_path`: Where its sidecar was (if it had one)
+/// - `sidecar_quarantined_path`: Where the sidecar is now
+/// - `error`: Why the photo was quarantined
+/// - `quarantined_at`: When it was moved (RFC3339)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuarantineEntry {
+    pub original_path: PathBuf,
+    pub quarantined_path: PathBuf,
+    pub sidecar_original_path: Option<PathBuf>,
+    pub sidecar_quarantined_path: Option<PathBuf>,
+    pub error: String,
+    pub quarantined_at: Timestamp,
+}
+
+/// A quarantine directory; created on the first quarantined photo.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Quarantine {
+    dir: PathBuf,
+}
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+impl Quarantine {
+    /// Quarantine at `dir`.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// `~/.jozin/quarantine`, or `None` if the home directory is unknown.
+    pub fn default_dir() -> Option<PathBuf> {
+        std::env::var_os("HOME")
+            .or_else(|| std::env::var_os("USERPROFILE"))
+            .filter(|home| !home.is_empty())
+            .map(|home| PathBuf::from(home).join(DEFAULT_QUARANTINE_DIR))
+    }
+
+    /// Path of the quarantine directory.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// The entries of `index.json`, oldest first (empty if nothing was
+    /// quarantined yet).
+    ///
+    /// # Errors
+    ///
+    /// - `JozinError::IoError` if the index cannot be read
+    /// - `JozinError::ValidationError` if it is not a valid manifest
+    pub fn entries(&self) -> Result<Vec<QuarantineEntry>> {
+        let index_path = self.dir.join(QUARANTINE_INDEX_FILE);
+        if !index_path.exists() {
+            return Ok(Vec::new());
+        }
+        serde_json::from_str(&fs::read_to_string(&index_path)?).map_err(|e| JozinError::ValidationError {
+            message: format!("Invalid quarantine index {}: {}", index_path.display(), e),
+        })
+    }
+
+    /// Moves `image_path` (and `sidecar_path`, if given and present) into
+    /// the quarantine and appends them to `index.json`.
+    ///
+    /// # Errors
+    ///
+    /// - `JozinError::IoError` if the directory cannot be created or a file
+    ///   cannot be moved (a photo whose sidecar fails to move stays
+    ///   quarantined and is recorded without it)
+    /// - As [`entries()`](Self::entries) for an existing index
+    pub fn quarantine(&self, image_path: &Path, sidecar_path: Option<&Path>, error: &str) -> Result<QuarantineEntry> {
+        fs::create_dir_all(&self.dir)?;
+        let mut entries = self.entries()?;
+
+        let quarantined_path = self.free_path(image_path)?;
+        move_file(image_path, &quarantined_path)?;
+        let sidecar = sidecar_path.filter(|path| path.is_file()).and_then(|path| {
+            let moved = self.free_path(path).and_then(|target| move_file(path, &target).map(|()| target));
+            match moved {
+                Ok(target) => Some((path.to_path_buf(), target)),
+                Err(e) => {
+                    eprintln!("Warning: Failed to quarantine sidecar {}: {}", path.display(), e);
+                    None
+                }
+            }
+        });
+
+        let (sidecar_original_path, sidecar_quarantined_path) = sidecar.unzip();
+        let entry = QuarantineEntry {
+            original_path: image_path.to_path_buf(),
+            quarantined_path,
+            sidecar_original_path,
+            sidecar_quarantined_path,
+            error: error.to_string(),
+            quarantined_at: OffsetDateTime::now_utc()
+                .format(&time::format_description::well_known::Rfc3339)
+                .unwrap_or_default(),
+        };
+        entries.push(entry.clone());
+        self.write_index(&entries)?;
+        Ok(entry)
+    }
+}
+
+// ============================================================================
+// Internal Helpers
+// ============================================================================
+
+impl Quarantine {
+    /// A path in the quarantine for the file name of `path` that is not
+    /// taken yet (`name`, `1-name`, `2-name`, ...).
+    fn free_path(&self, path: &Path) -> Result<PathBuf> {
+        let name = path.file_name().ok_or_else(|| JozinError::UserError {
+            message: format!("Not a file: {}", path.display()),
+        })?;
+        let mut candidate = self.dir.join(name);
+        let mut n = 0;
+        while candidate.exists() || candidate.file_name() == Some(QUARANTINE_INDEX_FILE.as_ref()) {
+            n += 1;
+            candidate = self.dir.join(format!("{}-{}", n, name.to_string_lossy()));
+        }
+        Ok(candidate)
+    }
+
+    /// Replaces `index.json` atomically.
+    fn write_index(&self, entries: &[QuarantineEntry]) -> Result<()> {
+        let index_path = self.dir.join(QUARANTINE_INDEX_FILE);
+        let tmp_path = self.dir.join(format!("{}.tmp", QUARANTINE_INDEX_FILE));
+        fs::write(&tmp_path, serde_json::to_string_pretty(entries)?)?;
+        atomic_rename(&tmp_path, &index_path)
+    }
+}
+
+/// Renames `from` to `to`, copying and deleting when they are on different
+/// filesystems.
+fn move_file(from: &Path, to: &Path) -> Result<()> {
+    if fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    fs::copy(from, to)?;
+    fs::remove_file(from)?;
+    Ok(())
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_quarantine_moves_files_and_records_them() {
+        let temp_dir = TempDir::new().unwrap();
+        let quarantine = Quarantine::new(temp_dir.path().join("quarantine"));
+        assert!(quarantine.entries().unwrap().is_empty());
+
+        for album in ["a", "b"] {
+            let dir = temp_dir.path().join(album);
+            fs::create_dir(&dir).unwrap();
+            fs::write(dir.join("IMG_1.jpg"), b"broken").unwrap();
+            fs::write(dir.join("IMG_1.jpg.json"), b"{}").unwrap();
+        }
+        let a = temp_dir.path().join("a/IMG_1.jpg");
+        let entry = quarantine.quarantine(&a, Some(&temp_dir.path().join("a/IMG_1.jpg.json")), "bad huffman code").unwrap();
+        assert!(!a.exists());
+        assert_eq!(fs::read(&entry.quarantined_path).unwrap(), b"broken");
+        assert!(entry.sidecar_quarantined_path.unwrap().exists());
+
+        // Same name from another album, without a sidecar
+        let b = temp_dir.path().join("b/IMG_1.jpg");
+        let entry = quarantine.quarantine(&b, Some(&temp_dir.path().join("b/missing.json")), "truncated").unwrap();
+        assert!(entry.quarantined_path.ends_with("1-IMG_1.jpg"));
+        assert_eq!(entry.sidecar_original_path, None);
+
+        let entries = quarantine.entries().unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!((entries[0].original_path.as_path(), entries[0].error.as_str()), (a.as_path(), "bad huffman code"));
+        assert_eq!(entries[1].original_path, b);
+    }
+}
@@ -0,0 +1,237 @@
+//! Multi-section operation reports
+//!
+//! Reports such as `verify`, `stats` and `faces --privacy-report` share one
+//! shape: a title, named sections with a count and optional detail lines,
+//! warnings, and how long the operation took. [`ReportBuilder`] assembles
+//! them and renders either for a terminal:
+//!
+//! ```text
+//! ┌─ Verify
+//! │ Sidecars      12
+//! │ With issues    1
+//! │   • a.jpg.json: corrupt sidecar
+//! ├─ Warnings
+//! │ ⚠ 2 files skipped
+//! └─ Done in 35 ms
+//! ```
+//!
+//! (with coloured counts), or as JSON:
+//!
+//! ```json
+//! {
+//!   "title": "Verify",
+//!   "sections": [{ "name": "Sidecars", "count": 12, "details": [] }, ...],
+//!   "warnings": ["2 files skipped"],
+//!   "duration_ms": 35
+//! }
+//! ```
+//!
+//! ## Usage
+//!
+//! ```
+//! use jozin_core::util::report_builder::{ReportBuilder, ReportFormat};
+//!
+//! let report = ReportBuilder::new("Library statistics", ReportFormat::Json)
+//!     .section("Photos", 120)
+//!     .section("Cameras", 2)
+//!     .detail(&["Canon".to_string(), "Nikon".to_string()])
+//!     .timing(35)
+//!     .build();
+//! assert!(report.contains("\"duration_ms\": 35"));
+//! ```
+
+use serde::Serialize;
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+const ANSI_COUNT: &str = "\x1b[1;36m";
+const ANSI_WARNING: &str = "\x1b[33m";
+const ANSI_RESET: &str = "\x1b[0m";
+
+// ============================================================================
+// Public Types
+// ============================================================================
+
+/// How [`ReportBuilder::build`] renders a report.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    /// Box-drawn text with coloured counts, for terminals
+    Human,
+    /// Pretty-printed JSON object
+    Json,
+}
+
+/// One section of a report.
+///
+/// # Fields
+///
+/// - `name`: Section heading
+/// - `count`: Number shown next to the heading
+/// - `details`: Lines listed under the section
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ReportSection {
+    pub name: String,
+    pub count: usize,
+    pub details: Vec<String>,
+}
+
+/// Fluent builder of a multi-section report (see the module docs).
+#[derive(Debug, Clone, Serialize)]
+pub struct ReportBuilder {
+    title: String,
+    #[serde(skip)]
+    format: ReportFormat,
+    sections: Vec<ReportSection>,
+    warnings: Vec<String>,
+    duration_ms: Option<u64>,
+}
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+impl ReportBuilder {
+    /// Starts an empty report.
+    pub fn new(title: &str, format: ReportFormat) -> Self {
+        Self {
+            title: title.to_string(),
+            format,
+            sections: Vec::new(),
+            warnings: Vec::new(),
+            duration_ms: None,
+        }
+    }
+
+    /// Adds a section with a count.
+    pub fn section(mut self, name: &str, count: usize) -> Self {
+        self.sections.push(ReportSection { name: name.to_string(), count, details: Vec::new() });
+        self
+    }
+
+    /// Lists `items` under the last section.
+    ///
+    /// Before the first section they get an untitled section of their own,
+    /// counting them.
+    pub fn detail(mut self, items: &[String]) -> Self {
+        match self.sections.last_mut() {
+            Some(section) => section.details.extend_from_slice(items),
+            None => self.sections.push(ReportSection {
+                name: String::new(),
+                count: items.len(),
+                details: items.to_vec(),
+            }),
+        }
+        self
+    }
+
+    /// Adds a warning, shown after the sections.
+    pub fn warning(mut self, msg: &str) -> Self {
+        self.warnings.push(msg.to_string());
+        self
+    }
+
+    /// Records how long the operation took.
+    pub fn timing(mut self, duration_ms: u64) -> Self {
+        self.duration_ms = Some(duration_ms);
+        self
+    }
+
+    /// Sections added so far.
+    pub fn sections(&self) -> &[ReportSection] {
+        &self.sections
+    }
+
+    /// Renders the report in the builder's format.
+    pub fn build(&self) -> String {
+        match self.format {
+            ReportFormat::Human => self.render_human(),
+            // Only strings and numbers: serialization cannot fail
+            ReportFormat::Json => serde_json::to_string_pretty(self).unwrap_or_default(),
+        }
+    }
+}
+
+// ============================================================================
+// Internal Helpers
+// ============================================================================
+
+impl ReportBuilder {
+    fn render_human(&self) -> String {
+        let name_width = self.sections.iter().map(|s| s.name.chars().count()).max().unwrap_or(0);
+        let count_width = self.sections.iter().map(|s| s.count.to_string().len()).max().unwrap_or(0);
+
+        let mut lines = vec![format!("┌─ {}", self.title)];
+        for section in &self.sections {
+            let padding = " ".repeat(name_width - section.name.chars().count());
+            lines.push(format!(
+                "│ {}{}  {}{:>count_width$}{}",
+                section.name, padding, ANSI_COUNT, section.count, ANSI_RESET
+            ));
+            lines.extend(section.details.iter().map(|detail| format!("│   • {}", detail)));
+        }
+        if !self.warnings.is_empty() {
+            lines.push("├─ Warnings".to_string());
+            lines.extend(self.warnings.iter().map(|w| format!("│ {}⚠{} {}", ANSI_WARNING, ANSI_RESET, w)));
+        }
+        lines.push(match self.duration_ms {
+            Some(ms) => format!("└─ Done in {} ms", ms),
+            None => "└─".to_string(),
+        });
+        lines.join("\n")
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(format: ReportFormat) -> ReportBuilder {
+        ReportBuilder::new("Verify", format)
+            .section("Sidecars", 12)
+            .section("With issues", 1)
+            .detail(&["a.jpg.json: corrupt sidecar".to_string()])
+            .warning("2 files skipped")
+            .timing(35)
+    }
+
+    #[test]
+    fn test_json_report_shape() {
+        let value: serde_json::Value = serde_json::from_str(&sample(ReportFormat::Json).build()).unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "title": "Verify",
+                "sections": [
+                    { "name": "Sidecars", "count": 12, "details": [] },
+                    { "name": "With issues", "count": 1, "details": ["a.jpg.json: corrupt sidecar"] },
+                ],
+                "warnings": ["2 files skipped"],
+                "duration_ms": 35,
+            })
+        );
+
+        let untimed = ReportBuilder::new("Empty", ReportFormat::Json).detail(&["x".to_string()]).build();
+        let value: serde_json::Value = serde_json::from_str(&untimed).unwrap();
+        assert_eq!(value["duration_ms"], serde_json::Value::Null);
+        assert_eq!(value["sections"][0]["count"], 1);
+    }
+
+    #[test]
+    fn test_human_report_aligns_counts() {
+        let text = sample(ReportFormat::Human).build();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines[0], "┌─ Verify");
+        assert_eq!(lines[1], format!("│ Sidecars     {}12{}", ANSI_COUNT, ANSI_RESET));
+        assert_eq!(lines[2], format!("│ With issues  {} 1{}", ANSI_COUNT, ANSI_RESET));
+        assert_eq!(lines[3], "│   • a.jpg.json: corrupt sidecar");
+        assert_eq!(lines[4], "├─ Warnings");
+        assert!(lines[5].ends_with("2 files skipped"));
+        assert_eq!(lines[6], "└─ Done in 35 ms");
+    }
+}
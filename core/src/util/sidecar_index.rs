@@ -0,0 +1,181 @@
+//! Index of a library's sidecars by content hash
+//!
+//! Renaming a photo outside Jožin leaves its sidecar behind under the old
+//! name, and the next scan creates a fresh sidecar without the faces, tags
+//! and thumbnails recorded so far. A [`SidecarIndex`] built before the scan
+//! and passed in `ScanConfig::sidecar_index` lets the scan recognise the
+//! renamed file by its hash: it finds the sidecar whose photo no longer
+//! exists and carries its metadata over to the new name (reported as
+//! `ScanAction::Renamed`).
+//!
+//! Each indexed sidecar is claimed by at most one file, so two copies of a
+//! renamed photo don't both inherit its metadata. The old sidecar is left in
+//! place and listed in `ScanResult::orphaned_sidecars`.
+//!
+//! ## Usage
+//!
+//! ```no_run
+//! use jozin_core::scan::{scan_path, ScanConfig};
+//! use jozin_core::util::library_config::LibraryConfig;
+//! use jozin_core::util::sidecar_index::SidecarIndex;
+//! use std::path::Path;
+//! use std::sync::Arc;
+//!
+//! let root = Path::new("/photos");
+//! let index = SidecarIndex::build(root, true, &LibraryConfig::discover(root))?;
+//! let config = ScanConfig { recursive: true, sidecar_index: Some(Arc::new(index)), ..ScanConfig::default() };
+//! let result = scan_path(root, &config, None)?;
+//! println!("{} sidecars of renamed files left behind", result.orphaned_sidecars.len());
+//! # Ok::<(), jozin_core::JozinError>(())
+//! ```
+
+use crate::scan::{find_sidecar_files_with, parse_sidecar};
+use crate::util::compression::read_sidecar_file;
+use crate::util::library_config::LibraryConfig;
+use crate::util::symlink::SymlinkStrategy;
+use crate::{Result, Sidecar};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+
+// ============================================================================
+// Public Types
+// ============================================================================
+
+/// An indexed sidecar.
+///
+/// # Fields
+///
+/// - `sidecar_path`: The sidecar file
+/// - `image_path`: The photo the sidecar belongs to (by its name)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexedSidecar {
+    pub sidecar_path: PathBuf,
+    pub image_path: PathBuf,
+}
+
+/// Sidecars by content hash, plus the renames claimed during a scan.
+#[derive(Debug, Default)]
+pub struct SidecarIndex {
+    by_hash: Mutex<HashMap<String, Vec<IndexedSidecar>>>,
+    renames: Mutex<HashMap<PathBuf, PathBuf>>,
+}
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+impl SidecarIndex {
+    /// Indexes the readable sidecars under `root`. Unreadable sidecars are
+    /// left out.
+    ///
+    /// # Errors
+    ///
+    /// Returns `JozinError::IoError` if `root` does not exist.
+    pub fn build(root: &Path, recursive: bool, naming: &LibraryConfig) -> Result<Self> {
+        let index = SidecarIndex::default();
+        index.add_root(root, recursive, naming)?;
+        Ok(index)
+    }
+
+    /// Adds the readable sidecars under another root (for scans of several
+    /// roots).
+    ///
+    /// # Errors
+    ///
+    /// Returns `JozinError::IoError` if `root` does not exist.
+    pub fn add_root(&self, root: &Path, recursive: bool, naming: &LibraryConfig) -> Result<()> {
+        for sidecar_path in find_sidecar_files_with(root, recursive, naming, SymlinkStrategy::default())? {
+            let Some(image_path) = naming.image_path(&sidecar_path) else {
+                continue;
+            };
+            let parsed = read_sidecar_file(&sidecar_path).and_then(|json| parse_sidecar(&json));
+            if let Ok(sidecar) = parsed {
+                self.insert(IndexedSidecar { sidecar_path, image_path }, &sidecar);
+            }
+        }
+        Ok(())
+    }
+
+    /// Adds a sidecar under its recorded content hash.
+    pub fn insert(&self, entry: IndexedSidecar, sidecar: &Sidecar) {
+        lock(&self.by_hash).entry(sidecar.source.file_hash().to_string()).or_default().push(entry);
+    }
+
+    /// Number of indexed sidecars that have not been claimed by a rename.
+    pub fn len(&self) -> usize {
+        lock(&self.by_hash).values().map(Vec::len).sum()
+    }
+
+    /// Whether no unclaimed sidecars are indexed.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Claims the sidecar of a renamed file: one with `hash` whose photo no
+    /// longer exists. The claim is recorded for [`SidecarIndex::take_rename()`].
+    ///
+    /// # Returns
+    ///
+    /// The old sidecar's path, or `None` if no unclaimed sidecar matches.
+    pub fn claim_rename(&self, hash: &str, new_image_path: &Path) -> Option<PathBuf> {
+        let mut by_hash = lock(&self.by_hash);
+        let candidates = by_hash.get_mut(hash)?;
+        let position = candidates
+            .iter()
+            .position(|entry| entry.image_path != new_image_path && !entry.image_path.exists())?;
+        let claimed = candidates.remove(position).sidecar_path;
+        lock(&self.renames).insert(new_image_path.to_path_buf(), claimed.clone());
+        Some(claimed)
+    }
+
+    /// Returns (and forgets) the old sidecar claimed for `new_image_path`.
+    pub fn take_rename(&self, new_image_path: &Path) -> Option<PathBuf> {
+        lock(&self.renames).remove(new_image_path)
+    }
+}
+
+// ============================================================================
+// Internal Helpers
+// ============================================================================
+
+/// Locks a mutex, recovering the data if another thread panicked.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scan::scan_file;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_claim_rename_only_for_missing_photos() {
+        let temp_dir = TempDir::new().unwrap();
+        let (kept, renamed) = (temp_dir.path().join("kept.jpg"), temp_dir.path().join("old.jpg"));
+        fs::write(&kept, b"same bytes").unwrap();
+        fs::write(&renamed, b"same bytes").unwrap();
+        let hash = scan_file(&kept, false).unwrap().sidecar.source.file_hash_b3;
+        scan_file(&renamed, false).unwrap();
+        let new_path = temp_dir.path().join("new.jpg");
+        fs::rename(&renamed, &new_path).unwrap();
+
+        let index = SidecarIndex::build(temp_dir.path(), false, &LibraryConfig::default()).unwrap();
+        assert_eq!(index.len(), 2);
+
+        assert_eq!(index.claim_rename(&hash, &new_path), Some(temp_dir.path().join("old.jpg.json")));
+        // Claimed once only; kept.jpg still exists
+        assert_eq!(index.claim_rename(&hash, &temp_dir.path().join("copy.jpg")), None);
+        assert_eq!(index.claim_rename("unknown", &new_path), None);
+
+        assert_eq!(index.take_rename(&new_path), Some(temp_dir.path().join("old.jpg.json")));
+        assert_eq!(index.take_rename(&new_path), None);
+        assert_eq!(index.len(), 1);
+    }
+}
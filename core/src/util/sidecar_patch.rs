@@ -0,0 +1,299 @@
+//! Partial sidecar updates addressed by JSON Pointer
+//!
+//! Tools that only want to add a tag or rename a face shouldn't have to model
+//! the whole sidecar. [`patch_sidecar()`] edits the sidecar JSON in place:
+//! each [`SidecarPatch`] names a location with a JSON Pointer
+//! ([RFC 6901](https://www.rfc-editor.org/rfc/rfc6901), e.g.
+//! `/image/camera_make` or `/tags/0/label`) and an operation.
+//!
+//! | [`PatchOp`]          | Effect                                                  |
+//! |----------------------|---------------------------------------------------------|
+//! | `Set`                | Replace the value, or add it to its parent object; `-` as last array index appends |
+//! | `Remove`             | Delete an object member or array element                |
+//! | `ArrayAppend`        | Push `value` onto the array at the pointer              |
+//! | `ArrayRemoveByValue` | Delete every element equal to `value` from the array    |
+//!
+//! Fields this build doesn't know (e.g. written by a newer Jožin) are kept,
+//! since the JSON is never round-tripped through [`Sidecar`]. The result must
+//! still parse as a [`Sidecar`] before it is written, atomically and with the
+//! usual backup rotation.
+//!
+//! ## Usage
+//!
+//! ```no_run
+//! use jozin_core::util::sidecar_patch::{patch_sidecar, PatchOp, SidecarPatch};
+//! use serde_json::json;
+//! use std::path::Path;
+//!
+//! patch_sidecar(
+//!     Path::new("/photos/IMG_1234.JPG.json"),
+//!     &[SidecarPatch::new("/faces/0/person", PatchOp::Set, json!("Alice"))],
+//! )?;
+//! # Ok::<(), jozin_core::JozinError>(())
+//! ```
+
+use crate::scan::write_sidecar_json;
+use crate::util::compression::read_sidecar_file;
+use crate::util::fsync_policy::FsyncPolicy;
+use crate::{JozinError, Result, Sidecar};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::Path;
+
+// ============================================================================
+// Public Types
+// ============================================================================
+
+/// Operation of a [`SidecarPatch`].
+///
+/// # Variants
+///
+/// - `Set`: Replace or add the value at the pointer
+/// - `Remove`: Delete the value at the pointer (`value` is ignored)
+/// - `ArrayAppend`: Push `value` onto the array at the pointer
+/// - `ArrayRemoveByValue`: Delete elements equal to `value` from the array at the pointer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PatchOp {
+    Set,
+    Remove,
+    ArrayAppend,
+    ArrayRemoveByValue,
+}
+
+/// One edit of a sidecar's JSON.
+///
+/// # Fields
+///
+/// - `pointer`: JSON Pointer to the edited location (`""` is the whole sidecar)
+/// - `op`: What to do there
+/// - `value`: Value to set, append or remove (`Null` for `Remove`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SidecarPatch {
+    pub pointer: String,
+    pub op: PatchOp,
+    pub value: Value,
+}
+
+impl SidecarPatch {
+    /// Creates a patch.
+    pub fn new(pointer: impl Into<String>, op: PatchOp, value: Value) -> Self {
+        Self {
+            pointer: pointer.into(),
+            op,
+            value,
+        }
+    }
+}
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Applies patches to a sidecar file and writes it back atomically.
+///
+/// Patches are applied in order; if any of them fails, or the result is not
+/// a valid sidecar, the file is left untouched.
+///
+/// # Errors
+///
+/// - `JozinError::IoError` if the sidecar cannot be read or written
+/// - `JozinError::ValidationError` if the file is not JSON, or the patched
+///   JSON is not a valid sidecar
+/// - `JozinError::UserError` if a pointer is malformed or doesn't fit the
+///   operation (see [`apply_patches()`])
+pub fn patch_sidecar(sidecar_path: &Path, patches: &[SidecarPatch]) -> Result<()> {
+    let mut value: Value = serde_json::from_str(&read_sidecar_file(sidecar_path)?)?;
+    apply_patches(&mut value, patches)?;
+
+    serde_json::from_value::<Sidecar>(value.clone()).map_err(|e| JozinError::ValidationError {
+        message: format!("Patched sidecar is not valid: {}", e),
+    })?;
+    write_sidecar_json(sidecar_path, &serde_json::to_string_pretty(&value)?, FsyncPolicy::Always)
+}
+
+/// Applies patches to a JSON value in memory, in order.
+///
+/// `value` is only modified if every patch applies.
+///
+/// # Errors
+///
+/// Returns `JozinError::UserError` if a pointer doesn't start with `/`, its
+/// parent doesn't exist, `Remove` targets a missing value, or the array
+/// operations target something other than an array.
+///
+/// # Example
+///
+/// ```
+/// use jozin_core::util::sidecar_patch::{apply_patches, PatchOp, SidecarPatch};
+/// use serde_json::json;
+///
+/// let mut value = json!({ "tags": [{ "label": "beach" }] });
+/// apply_patches(&mut value, &[
+///     SidecarPatch::new("/tags", PatchOp::ArrayAppend, json!({ "label": "sunset" })),
+///     SidecarPatch::new("/tags/0/label", PatchOp::Set, json!("sea")),
+/// ])?;
+/// assert_eq!(value, json!({ "tags": [{ "label": "sea" }, { "label": "sunset" }] }));
+/// # Ok::<(), jozin_core::JozinError>(())
+/// ```
+pub fn apply_patches(value: &mut Value, patches: &[SidecarPatch]) -> Result<()> {
+    let mut patched = value.clone();
+    for patch in patches {
+        apply_patch(&mut patched, patch)?;
+    }
+    *value = patched;
+    Ok(())
+}
+
+// ============================================================================
+// Internal Helpers
+// ============================================================================
+
+/// Applies one patch.
+fn apply_patch(root: &mut Value, patch: &SidecarPatch) -> Result<()> {
+    let pointer = patch.pointer.as_str();
+    if !pointer.is_empty() && !pointer.starts_with('/') {
+        return Err(patch_error(pointer, "a JSON Pointer must be empty or start with '/'"));
+    }
+
+    match patch.op {
+        PatchOp::Set if pointer.is_empty() => *root = patch.value.clone(),
+        PatchOp::Set => {
+            let (parent, token) = split_last(pointer);
+            match root.pointer_mut(parent) {
+                Some(Value::Object(map)) => {
+                    map.insert(token, patch.value.clone());
+                }
+                Some(Value::Array(items)) if token == "-" => items.push(patch.value.clone()),
+                Some(Value::Array(items)) => {
+                    let slot = array_index(&token, items.len())
+                        .and_then(|index| items.get_mut(index))
+                        .ok_or_else(|| patch_error(pointer, "array index out of range"))?;
+                    *slot = patch.value.clone();
+                }
+                _ => return Err(patch_error(pointer, "parent is not an object or array")),
+            }
+        }
+        PatchOp::Remove => {
+            let (parent, token) = split_last(pointer);
+            let removed = match root.pointer_mut(parent) {
+                Some(Value::Object(map)) if !pointer.is_empty() => map.remove(&token).is_some(),
+                Some(Value::Array(items)) => match array_index(&token, items.len()) {
+                    Some(index) if index < items.len() => {
+                        items.remove(index);
+                        true
+                    }
+                    _ => false,
+                },
+                _ => false,
+            };
+            if !removed {
+                return Err(patch_error(pointer, "nothing to remove"));
+            }
+        }
+        PatchOp::ArrayAppend => match root.pointer_mut(pointer) {
+            Some(Value::Array(items)) => items.push(patch.value.clone()),
+            _ => return Err(patch_error(pointer, "not an array")),
+        },
+        PatchOp::ArrayRemoveByValue => match root.pointer_mut(pointer) {
+            Some(Value::Array(items)) => items.retain(|item| *item != patch.value),
+            _ => return Err(patch_error(pointer, "not an array")),
+        },
+    }
+    Ok(())
+}
+
+/// Splits a pointer into its parent pointer and unescaped last token.
+fn split_last(pointer: &str) -> (&str, String) {
+    let (parent, token) = pointer.rsplit_once('/').unwrap_or(("", pointer));
+    (parent, token.replace("~1", "/").replace("~0", "~"))
+}
+
+/// Parses an array index token (no leading zeros, as RFC 6901 requires).
+fn array_index(token: &str, len: usize) -> Option<usize> {
+    if token.len() > 1 && token.starts_with('0') {
+        return None;
+    }
+    token.parse().ok().filter(|index| *index <= len)
+}
+
+/// Error for a patch that doesn't apply.
+fn patch_error(pointer: &str, reason: &str) -> JozinError {
+    JozinError::UserError {
+        message: format!("Cannot patch '{}': {}", pointer, reason),
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scan::{read_sidecar, scan_file};
+    use serde_json::json;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_apply_patches_ops() {
+        let mut value = json!({ "a": { "b/c": 1 }, "list": [1, 2, 1, 3] });
+        apply_patches(
+            &mut value,
+            &[
+                SidecarPatch::new("/a/b~1c", PatchOp::Set, json!(2)),
+                SidecarPatch::new("/a/new", PatchOp::Set, json!("x")),
+                SidecarPatch::new("/list/-", PatchOp::Set, json!(4)),
+                SidecarPatch::new("/list/1", PatchOp::Remove, Value::Null),
+                SidecarPatch::new("/list", PatchOp::ArrayRemoveByValue, json!(1)),
+                SidecarPatch::new("/list", PatchOp::ArrayAppend, json!(5)),
+            ],
+        )
+        .unwrap();
+        assert_eq!(value, json!({ "a": { "b/c": 2, "new": "x" }, "list": [3, 4, 5] }));
+    }
+
+    #[test]
+    fn test_failed_patch_leaves_value_unchanged() {
+        let mut value = json!({ "list": [1], "name": "a" });
+        let original = value.clone();
+        for patch in [
+            SidecarPatch::new("/name", PatchOp::ArrayAppend, json!(1)),
+            SidecarPatch::new("/missing/key", PatchOp::Set, json!(1)),
+            SidecarPatch::new("/list/5", PatchOp::Set, json!(1)),
+            SidecarPatch::new("/list/01", PatchOp::Remove, Value::Null),
+            SidecarPatch::new("/absent", PatchOp::Remove, Value::Null),
+            SidecarPatch::new("name", PatchOp::Set, json!(1)),
+        ] {
+            let patches = [SidecarPatch::new("/name", PatchOp::Set, json!("b")), patch];
+            assert!(matches!(apply_patches(&mut value, &patches), Err(JozinError::UserError { .. })));
+            assert_eq!(value, original);
+        }
+    }
+
+    #[test]
+    fn test_patch_sidecar_validates_and_keeps_unknown_fields() {
+        let temp_dir = TempDir::new().unwrap();
+        let photo = temp_dir.path().join("a.jpg");
+        fs::write(&photo, b"image").unwrap();
+        scan_file(&photo, false).unwrap();
+        let sidecar_path = temp_dir.path().join("a.jpg.json");
+
+        patch_sidecar(&sidecar_path, &[SidecarPatch::new("/future_field", PatchOp::Set, json!(true))]).unwrap();
+        patch_sidecar(
+            &sidecar_path,
+            &[SidecarPatch::new("/tags", PatchOp::ArrayAppend, json!({ "label": "beach", "source": "user" }))],
+        )
+        .unwrap();
+        assert_eq!(read_sidecar(&photo).unwrap().tags[0].label, "beach");
+        let raw: Value = serde_json::from_str(&fs::read_to_string(&sidecar_path).unwrap()).unwrap();
+        assert_eq!(raw["future_field"], json!(true));
+        assert!(temp_dir.path().join("a.jpg.json.bak1").exists());
+
+        // Results that are no longer sidecars are rejected
+        let invalid = patch_sidecar(&sidecar_path, &[SidecarPatch::new("/source", PatchOp::Remove, Value::Null)]);
+        assert!(matches!(invalid, Err(JozinError::ValidationError { .. })));
+        assert!(read_sidecar(&photo).is_ok());
+    }
+}
@@ -0,0 +1,220 @@
+//! Deterministic ordering of scan results
+//!
+//! Files come out of a scan in whatever order the directory walk and the
+//! worker threads produce them, which differs between operating systems and
+//! filesystems. [`sort_scanned_files()`] puts a result list into a stable
+//! [`SortOrder`], so output and test assertions don't depend on the platform.
+//!
+//! | [`SortOrder`]        | Key                                              |
+//! |----------------------|--------------------------------------------------|
+//! | `PathAlphabetical`   | `path` (byte order)                              |
+//! | `DateOldestFirst`    | `image.datetime_original` from the file's sidecar |
+//! | `DateNewestFirst`    | same, newest first                               |
+//! | `SizeSmallestFirst`  | `size_bytes`                                     |
+//! | `SizeLargestFirst`   | same, largest first                              |
+//! | `None`               | unchanged (no extra work)                        |
+//!
+//! Files without a key (no sidecar, no capture time, or no size) sort after
+//! all files that have one; ties are broken by path.
+//!
+//! ## Usage
+//!
+//! ```no_run
+//! use jozin_core::scan::{scan_path, ScanConfig};
+//! use jozin_core::util::sidecar_sorter::SortOrder;
+//! use std::path::Path;
+//!
+//! let config = ScanConfig { output_sort_order: SortOrder::DateOldestFirst, ..ScanConfig::default() };
+//! let result = scan_path(Path::new("/photos"), &config, None)?;
+//! # Ok::<(), jozin_core::JozinError>(())
+//! ```
+
+use crate::scan::{read_sidecar, ScannedFile};
+use serde::{Deserialize, Serialize};
+use std::cmp::{Ordering, Reverse};
+use std::path::Path;
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+
+// ============================================================================
+// Public Types
+// ============================================================================
+
+/// Order of files in a scan result.
+///
+/// # Variants
+///
+/// - `PathAlphabetical`: By path
+/// - `DateOldestFirst`: By capture time, oldest first
+/// - `DateNewestFirst`: By capture time, newest first
+/// - `SizeSmallestFirst`: By file size, smallest first
+/// - `SizeLargestFirst`: By file size, largest first
+/// - `None`: Keep the order the operation produced (default)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SortOrder {
+    PathAlphabetical,
+    DateOldestFirst,
+    DateNewestFirst,
+    SizeSmallestFirst,
+    SizeLargestFirst,
+    #[default]
+    None,
+}
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Sorts scan results in place.
+///
+/// The date orders read each file's sidecar for `image.datetime_original`;
+/// files whose sidecar is missing (dry runs, failures) or has no capture
+/// time sort last.
+///
+/// # Example
+///
+/// ```
+/// use jozin_core::scan::{ScanAction, ScannedFile};
+/// use jozin_core::util::sidecar_sorter::{sort_scanned_files, SortOrder};
+///
+/// let file = |path: &str, size| ScannedFile {
+///     path: path.to_string(),
+///     action: ScanAction::Written,
+///     sidecar_path: None,
+///     error: None,
+///     hash: None,
+///     size_bytes: Some(size),
+///     backup_path: None,
+/// };
+/// let mut files = vec![file("b.jpg", 10), file("a.jpg", 20)];
+/// sort_scanned_files(&mut files, SortOrder::SizeLargestFirst);
+/// assert_eq!(files[0].path, "a.jpg");
+/// ```
+pub fn sort_scanned_files(files: &mut [ScannedFile], order: SortOrder) {
+    match order {
+        SortOrder::None => {}
+        SortOrder::PathAlphabetical => files.sort_by(|a, b| a.path.cmp(&b.path)),
+        SortOrder::SizeSmallestFirst => sort_by_key_last_missing(files, |file| file.size_bytes),
+        SortOrder::SizeLargestFirst => sort_by_key_last_missing(files, |file| file.size_bytes.map(Reverse)),
+        SortOrder::DateOldestFirst | SortOrder::DateNewestFirst => {
+            // Read each sidecar once, not once per comparison
+            let mut keyed: Vec<(Option<OffsetDateTime>, ScannedFile)> =
+                files.iter().map(|file| (capture_time(file), file.clone())).collect();
+            let newest_first = order == SortOrder::DateNewestFirst;
+            keyed.sort_by(|(a_time, a), (b_time, b)| {
+                let by_time = match (a_time, b_time) {
+                    (Some(a), Some(b)) if newest_first => b.cmp(a),
+                    (a, b) => compare_missing_last(a, b),
+                };
+                by_time.then_with(|| a.path.cmp(&b.path))
+            });
+            for (slot, (_, file)) in files.iter_mut().zip(keyed) {
+                *slot = file;
+            }
+        }
+    }
+}
+
+// ============================================================================
+// Internal Helpers
+// ============================================================================
+
+/// Sorts by an optional key, files without one last, ties by path.
+fn sort_by_key_last_missing<K: Ord>(files: &mut [ScannedFile], key: impl Fn(&ScannedFile) -> Option<K>) {
+    files.sort_by(|a, b| compare_missing_last(&key(a), &key(b)).then_with(|| a.path.cmp(&b.path)));
+}
+
+/// Orders `Some` values ascending, before any `None`.
+fn compare_missing_last<K: Ord>(a: &Option<K>, b: &Option<K>) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => a.cmp(b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+/// Capture time recorded in the file's sidecar, if any.
+fn capture_time(file: &ScannedFile) -> Option<OffsetDateTime> {
+    let sidecar = read_sidecar(Path::new(&file.path)).ok()?;
+    let datetime = sidecar.image?.datetime_original?;
+    OffsetDateTime::parse(&datetime, &Rfc3339).ok()
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scan::{scan_file, write_sidecar, ScanAction};
+    use crate::ImageInfo;
+    use tempfile::TempDir;
+
+    fn file(path: &str, size_bytes: Option<u64>) -> ScannedFile {
+        ScannedFile {
+            path: path.to_string(),
+            action: ScanAction::Written,
+            sidecar_path: None,
+            error: None,
+            hash: None,
+            size_bytes,
+            backup_path: None,
+        }
+    }
+
+    fn paths(files: &[ScannedFile]) -> Vec<&str> {
+        files.iter().map(|f| f.path.as_str()).collect()
+    }
+
+    #[test]
+    fn test_sort_by_path_and_size() {
+        let mut files = vec![file("c.jpg", Some(5)), file("a.jpg", None), file("b.jpg", Some(5)), file("d.jpg", Some(1))];
+
+        sort_scanned_files(&mut files, SortOrder::None);
+        assert_eq!(paths(&files), ["c.jpg", "a.jpg", "b.jpg", "d.jpg"]);
+
+        sort_scanned_files(&mut files, SortOrder::PathAlphabetical);
+        assert_eq!(paths(&files), ["a.jpg", "b.jpg", "c.jpg", "d.jpg"]);
+
+        sort_scanned_files(&mut files, SortOrder::SizeSmallestFirst);
+        assert_eq!(paths(&files), ["d.jpg", "b.jpg", "c.jpg", "a.jpg"]);
+
+        sort_scanned_files(&mut files, SortOrder::SizeLargestFirst);
+        assert_eq!(paths(&files), ["b.jpg", "c.jpg", "d.jpg", "a.jpg"]);
+    }
+
+    #[test]
+    fn test_sort_by_capture_time() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut files = Vec::new();
+        for (name, datetime) in [
+            ("late.jpg", Some("2024-06-01T12:00:00+00:00")),
+            ("undated.jpg", None),
+            // Earlier than late.jpg once the offset is applied
+            ("early.jpg", Some("2024-06-01T13:00:00+02:00")),
+        ] {
+            let photo = temp_dir.path().join(name);
+            std::fs::write(&photo, name).unwrap();
+            let mut sidecar = scan_file(&photo, true).unwrap().sidecar;
+            sidecar.image = Some(ImageInfo {
+                datetime_original: datetime.map(String::from),
+                ..ImageInfo::default()
+            });
+            write_sidecar(&photo, &sidecar).unwrap();
+            files.push(file(&photo.display().to_string(), None));
+        }
+        files.push(file(&temp_dir.path().join("no_sidecar.jpg").display().to_string(), None));
+
+        let names = |files: &[ScannedFile]| -> Vec<String> {
+            files.iter().map(|f| Path::new(&f.path).file_name().unwrap().to_string_lossy().into_owned()).collect()
+        };
+        sort_scanned_files(&mut files, SortOrder::DateOldestFirst);
+        assert_eq!(names(&files), ["early.jpg", "late.jpg", "no_sidecar.jpg", "undated.jpg"]);
+
+        sort_scanned_files(&mut files, SortOrder::DateNewestFirst);
+        assert_eq!(names(&files), ["late.jpg", "early.jpg", "no_sidecar.jpg", "undated.jpg"]);
+    }
+}
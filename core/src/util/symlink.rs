@@ -0,0 +1,195 @@
+//! Symlink handling during directory traversal
+//!
+//! Photo libraries often contain symlinks: shared folders linked into several
+//! albums, or links back to a parent directory. Following them can count the
+//! same photo twice; a link to an ancestor would loop forever without
+//! detection.
+//!
+//! ## Strategies
+//!
+//! | Strategy                     | Symlinked directories | Symlinked files          |
+//! |------------------------------|-----------------------|--------------------------|
+//! | [`SymlinkStrategy::Follow`]  | Descended into        | Processed                |
+//! | [`SymlinkStrategy::Skip`]    | Ignored               | Reported as skipped      |
+//! | [`SymlinkStrategy::Error`]   | Abort with `UserError`| Abort with `UserError`   |
+//!
+//! With `Follow`, walkdir detects symlink loops: the looping entry is yielded
+//! as an error (see `walkdir::Error::loop_ancestor()`) and the walk continues
+//! with the next entry, so traversal always terminates.
+//!
+//! ## Usage
+//!
+//! ```no_run
+//! use jozin_core::util::symlink::{check_symlink, walker, SymlinkStrategy};
+//! use std::path::Path;
+//!
+//! for entry in walker(Path::new("/photos"), true, SymlinkStrategy::Skip) {
+//!     let entry = entry?;
+//!     if check_symlink(&entry, SymlinkStrategy::Skip)? {
+//!         continue; // symlink, skipped
+//!     }
+//!     println!("{}", entry.path().display());
+//! }
+//! # Ok::<(), jozin_core::JozinError>(())
+//! ```
+
+use crate::{JozinError, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+use walkdir::{DirEntry, WalkDir};
+
+// ============================================================================
+// Public Types
+// ============================================================================
+
+/// How directory traversal treats symbolic links.
+///
+/// # Variants
+///
+/// - `Follow`: Follow symlinks (default); loops are detected and reported
+/// - `Skip`: Don't follow symlinks; symlinked files are skipped
+/// - `Error`: Fail on the first symlink
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SymlinkStrategy {
+    #[default]
+    Follow,
+    Skip,
+    Error,
+}
+
+impl SymlinkStrategy {
+    /// Lowercase name (`"follow"`, `"skip"`, `"error"`).
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SymlinkStrategy::Follow => "follow",
+            SymlinkStrategy::Skip => "skip",
+            SymlinkStrategy::Error => "error",
+        }
+    }
+}
+
+impl fmt::Display for SymlinkStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for SymlinkStrategy {
+    type Err = JozinError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "follow" => Ok(SymlinkStrategy::Follow),
+            "skip" => Ok(SymlinkStrategy::Skip),
+            "error" => Ok(SymlinkStrategy::Error),
+            other => Err(JozinError::ValidationError {
+                message: format!("Unknown symlink strategy '{}' (expected follow, skip or error)", other),
+            }),
+        }
+    }
+}
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Creates a directory walker configured for a symlink strategy.
+///
+/// # Arguments
+///
+/// * `root` - Directory to walk (followed even if it is itself a symlink)
+/// * `recursive` - Descend into subdirectories (otherwise depth 1)
+/// * `strategy` - Only `Follow` descends into symlinked directories
+pub fn walker(root: &Path, recursive: bool, strategy: SymlinkStrategy) -> WalkDir {
+    let walker = WalkDir::new(root).follow_links(strategy == SymlinkStrategy::Follow);
+    if recursive {
+        walker
+    } else {
+        walker.max_depth(1)
+    }
+}
+
+/// Applies a symlink strategy to one walker entry.
+///
+/// # Returns
+///
+/// `true` if the entry is a symlink that should be skipped (`Skip`),
+/// `false` if it should be processed.
+///
+/// # Errors
+///
+/// Returns `JozinError::UserError` if the entry is a symlink and the strategy
+/// is `Error`.
+pub fn check_symlink(entry: &DirEntry, strategy: SymlinkStrategy) -> Result<bool> {
+    if !entry.path_is_symlink() || entry.depth() == 0 {
+        return Ok(false);
+    }
+    match strategy {
+        SymlinkStrategy::Follow => Ok(false),
+        SymlinkStrategy::Skip => Ok(true),
+        SymlinkStrategy::Error => Err(JozinError::UserError {
+            message: format!(
+                "Symlink found: {} (use --symlinks follow or skip)",
+                entry.path().display()
+            ),
+        }),
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::os::unix::fs::symlink;
+    use tempfile::TempDir;
+
+    /// `root/a/photo.jpg` plus `root/a/loop -> root`
+    fn library_with_loop() -> TempDir {
+        let temp_dir = TempDir::new().unwrap();
+        let album = temp_dir.path().join("a");
+        fs::create_dir(&album).unwrap();
+        fs::write(album.join("photo.jpg"), b"image").unwrap();
+        symlink(temp_dir.path(), album.join("loop")).unwrap();
+        temp_dir
+    }
+
+    #[test]
+    fn test_follow_terminates_with_loop_error() {
+        let temp_dir = library_with_loop();
+
+        let entries: Vec<_> = walker(temp_dir.path(), true, SymlinkStrategy::Follow).into_iter().collect();
+        let loop_error = entries
+            .iter()
+            .filter_map(|e| e.as_ref().err())
+            .find(|e| e.loop_ancestor().is_some());
+        assert!(loop_error.is_some(), "walkdir must report the symlink loop");
+        assert_eq!(entries.iter().filter(|e| e.is_ok()).count(), 3); // root, a, photo.jpg
+    }
+
+    #[test]
+    fn test_skip_and_error_strategies() {
+        let temp_dir = library_with_loop();
+
+        let mut skipped = 0;
+        for entry in walker(temp_dir.path(), true, SymlinkStrategy::Skip) {
+            if check_symlink(&entry.unwrap(), SymlinkStrategy::Skip).unwrap() {
+                skipped += 1;
+            }
+        }
+        assert_eq!(skipped, 1);
+
+        let result: Result<Vec<bool>> = walker(temp_dir.path(), true, SymlinkStrategy::Error)
+            .into_iter()
+            .map(|e| check_symlink(&e.unwrap(), SymlinkStrategy::Error))
+            .collect();
+        assert!(matches!(result, Err(JozinError::UserError { .. })));
+        assert_eq!("SKIP".parse::<SymlinkStrategy>().unwrap(), SymlinkStrategy::Skip);
+    }
+}
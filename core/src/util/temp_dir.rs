@@ -0,0 +1,178 @@
+//! Scoped temporary directories
+//!
+//! Intermediate files (encoder buffers, temporarily extracted archive
+//! entries, format conversions) must not outlive the operation that created
+//! them, including when it fails or panics. A [`ScopedTempDir`] creates a
+//! uniquely named directory and removes it, with everything inside, when it
+//! is dropped.
+//!
+//! Directories are created under `$JOZIN_TEMP_DIR` if set (e.g. to keep
+//! large intermediates off a small `/tmp`), and under
+//! [`std::env::temp_dir()`] otherwise.
+//!
+//! ## Usage
+//!
+//! ```no_run
+//! use jozin_core::util::temp_dir::ScopedTempDir;
+//! use std::io::Write;
+//!
+//! let scratch = ScopedTempDir::new()?;
+//! let (path, mut file) = scratch.create_file("frame.jpg")?;
+//! file.write_all(b"...")?;
+//! // `path` is deleted together with `scratch`
+//! # Ok::<(), jozin_core::JozinError>(())
+//! ```
+
+use crate::{JozinError, Result};
+use std::fs::{self, File};
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+/// Environment variable overriding the base directory.
+pub const TEMP_DIR_ENV: &str = "JOZIN_TEMP_DIR";
+
+/// Attempts at finding an unused directory name before giving up.
+const MAX_CREATE_ATTEMPTS: u32 = 16;
+
+/// Distinguishes directories created by the same process.
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+// ============================================================================
+// Public Types
+// ============================================================================
+
+/// A temporary directory deleted on drop.
+///
+/// Removal failures are reported on stderr; dropping never panics.
+#[derive(Debug)]
+pub struct ScopedTempDir {
+    path: PathBuf,
+}
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+impl ScopedTempDir {
+    /// Creates a new, empty directory (`jozin-<pid>-<n>-<nanos>`).
+    ///
+    /// # Errors
+    ///
+    /// Returns `JozinError::IoError` if the base directory is not writable.
+    pub fn new() -> Result<Self> {
+        let base = std::env::var_os(TEMP_DIR_ENV)
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .unwrap_or_else(std::env::temp_dir);
+        Self::new_in(&base)
+    }
+
+    /// Creates a new, empty directory under `base` (created if missing).
+    ///
+    /// # Errors
+    ///
+    /// Returns `JozinError::IoError` if `base` is not writable.
+    pub fn new_in(base: &Path) -> Result<Self> {
+        fs::create_dir_all(base)?;
+        for _ in 0..MAX_CREATE_ATTEMPTS {
+            let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.subsec_nanos()).unwrap_or(0);
+            let name = format!("jozin-{}-{}-{}", std::process::id(), NEXT_ID.fetch_add(1, Ordering::Relaxed), nanos);
+            let path = base.join(name);
+            match fs::create_dir(&path) {
+                Ok(()) => return Ok(Self { path }),
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Err(JozinError::IoError {
+            message: format!("Failed to create a unique temporary directory in {}", base.display()),
+        })
+    }
+
+    /// Path of the directory.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Creates (or truncates) a file directly inside the directory.
+    ///
+    /// # Errors
+    ///
+    /// - `JozinError::ValidationError` if `name` is not a plain file name
+    ///   (contains a separator or `..`)
+    /// - `JozinError::IoError` if the file cannot be created
+    pub fn create_file(&self, name: &str) -> Result<(PathBuf, File)> {
+        let mut components = Path::new(name).components();
+        if !matches!((components.next(), components.next()), (Some(Component::Normal(_)), None)) {
+            return Err(JozinError::ValidationError {
+                message: format!("Invalid temporary file name '{}'", name),
+            });
+        }
+        let path = self.path.join(name);
+        let file = File::create(&path)?;
+        Ok((path, file))
+    }
+}
+
+impl Drop for ScopedTempDir {
+    fn drop(&mut self) {
+        if let Err(e) = fs::remove_dir_all(&self.path) {
+            if e.kind() != io::ErrorKind::NotFound {
+                eprintln!("Warning: Failed to remove temporary directory {}: {}", self.path.display(), e);
+            }
+        }
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use std::panic;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_removed_on_drop_and_panic() {
+        let base = TempDir::new().unwrap();
+
+        let scratch = ScopedTempDir::new_in(base.path()).unwrap();
+        let other = ScopedTempDir::new_in(base.path()).unwrap();
+        assert_ne!(scratch.path(), other.path());
+        let (file_path, mut file) = scratch.create_file("frame.jpg").unwrap();
+        file.write_all(b"data").unwrap();
+        assert!(file_path.starts_with(scratch.path()));
+        let dir = scratch.path().to_path_buf();
+        drop(scratch);
+        assert!(!dir.exists());
+
+        let panicked_dir = panic::catch_unwind(|| {
+            let scratch = ScopedTempDir::new_in(base.path()).unwrap();
+            scratch.create_file("partial.tmp").unwrap();
+            let dir = scratch.path().to_path_buf();
+            panic::panic_any(dir);
+        })
+        .unwrap_err()
+        .downcast::<PathBuf>()
+        .unwrap();
+        assert!(!panicked_dir.exists());
+    }
+
+    #[test]
+    fn test_create_file_rejects_paths() {
+        let base = TempDir::new().unwrap();
+        let scratch = ScopedTempDir::new_in(base.path()).unwrap();
+        assert!(scratch.create_file("../escape.txt").is_err());
+        assert!(scratch.create_file("sub/file.txt").is_err());
+        assert!(scratch.create_file("").is_err());
+    }
+}
@@ -0,0 +1,259 @@
+//! Synthetic photo libraries (test builds and the `test-helpers` feature)
+//!
+//! Integration tests and benchmarks need libraries of many photos with
+//! varied metadata. [`generate_test_corpus()`] writes one from a
+//! [`CorpusConfig`], using the fixtures of [`crate::util::test_helpers`]:
+//!
+//! - Cameras are assigned round-robin, capture times are spread evenly over
+//!   `date_range`
+//! - GPS positions (for `include_gps_fraction` of the photos) are spread over
+//!   `geo_coverage`
+//! - Photos are written to one directory per capture year, cycling through
+//!   `formats` (`"jpg"` and `"png"`)
+//! - Every photo is scanned; `include_faces_fraction` of them get one to
+//!   three synthetic faces in their sidecar
+//!
+//! The corpus is deterministic: the same config produces the same files.
+//!
+//! ```ignore
+//! let photos = generate_test_corpus(dir, CorpusConfig { file_count: 500, ..CorpusConfig::default() })?;
+//! let stats = LibraryStats::collect(dir, true)?;
+//! assert_eq!(stats.total_files, 500);
+//! ```
+
+use crate::scan::{scan_file, write_sidecar};
+use crate::util::test_helpers::{create_synthetic_jpeg, create_synthetic_png, SyntheticExif};
+use crate::{FaceDetection, JozinError, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use time::{Date, Month, OffsetDateTime};
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+/// Photo formats the generator can write.
+pub const CORPUS_FORMATS: &[&str] = &["jpg", "png"];
+
+// ============================================================================
+// Public Types
+// ============================================================================
+
+/// Latitude/longitude bounding box in decimal degrees.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GeoBbox {
+    pub min_latitude: f64,
+    pub max_latitude: f64,
+    pub min_longitude: f64,
+    pub max_longitude: f64,
+}
+
+impl Default for GeoBbox {
+    /// Central Europe.
+    fn default() -> Self {
+        Self {
+            min_latitude: 45.0,
+            max_latitude: 55.0,
+            min_longitude: 5.0,
+            max_longitude: 25.0,
+        }
+    }
+}
+
+/// What [`generate_test_corpus()`] writes.
+///
+/// # Fields
+///
+/// - `file_count`: Number of photos
+/// - `cameras`: `"<make> <model>"` strings (the first word is the make)
+/// - `date_range`: First and last capture time
+/// - `geo_coverage`: Area the GPS positions are spread over
+/// - `include_gps_fraction`: Share of photos with GPS (0-1)
+/// - `include_faces_fraction`: Share of photos with faces (0-1)
+/// - `formats`: File formats to cycle through (see [`CORPUS_FORMATS`])
+#[derive(Debug, Clone)]
+pub struct CorpusConfig<'a> {
+    pub file_count: usize,
+    pub cameras: Vec<&'a str>,
+    pub date_range: (OffsetDateTime, OffsetDateTime),
+    pub geo_coverage: GeoBbox,
+    pub include_gps_fraction: f32,
+    pub include_faces_fraction: f32,
+    pub formats: Vec<&'a str>,
+}
+
+impl Default for CorpusConfig<'_> {
+    fn default() -> Self {
+        let day = |year, month| Date::from_calendar_date(year, month, 1).unwrap_or(Date::MIN).midnight().assume_utc();
+        Self {
+            file_count: 100,
+            cameras: vec!["Canon EOS R5", "Nikon Z 6II", "Apple iPhone 15 Pro"],
+            date_range: (day(2020, Month::January), day(2024, Month::December)),
+            geo_coverage: GeoBbox::default(),
+            include_gps_fraction: 0.5,
+            include_faces_fraction: 0.3,
+            formats: vec!["jpg"],
+        }
+    }
+}
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Writes a synthetic library under `root` and scans it.
+///
+/// # Returns
+///
+/// The photo paths, in generation order.
+///
+/// # Errors
+///
+/// - `JozinError::UserError` if `cameras` or `formats` is empty, a format is
+///   not in [`CORPUS_FORMATS`], a fraction is outside 0-1, or the date
+///   range ends before it starts
+/// - `JozinError::IoError` if directories or sidecars cannot be written
+pub fn generate_test_corpus(root: &Path, config: CorpusConfig) -> Result<Vec<PathBuf>> {
+    validate(&config)?;
+
+    let (first, last) = config.date_range;
+    let span = last - first;
+    let mut photos = Vec::with_capacity(config.file_count);
+    for index in 0..config.file_count {
+        let camera = config.cameras[index % config.cameras.len()];
+        let (make, model) = match camera.split_once(' ') {
+            Some((make, model)) => (make, Some(model)),
+            None => (camera, None),
+        };
+        let position = if config.file_count > 1 { index as f64 / (config.file_count - 1) as f64 } else { 0.0 };
+        let taken = first + span * position;
+        let datetime = format!(
+            "{:04}:{:02}:{:02} {:02}:{:02}:{:02}",
+            taken.year(),
+            u8::from(taken.month()),
+            taken.day(),
+            taken.hour(),
+            taken.minute(),
+            taken.second()
+        );
+
+        let exif = SyntheticExif {
+            camera_make: Some(make),
+            camera_model: model,
+            datetime_original: Some(&datetime),
+            gps: selected(index, config.include_gps_fraction).then(|| spread(index, &config.geo_coverage)),
+            ..SyntheticExif::default()
+        };
+        let dir = root.join(taken.year().to_string());
+        fs::create_dir_all(&dir)?;
+        let format = config.formats[index % config.formats.len()];
+        let filename = format!("IMG_{:05}.{}", index, format);
+        let photo = match format {
+            "png" => create_synthetic_png(&dir, &filename, exif),
+            _ => create_synthetic_jpeg(&dir, &filename, exif),
+        };
+
+        // Scan in memory, so the sidecar is written once (without a backup)
+        let mut sidecar = scan_file(&photo, true)?.sidecar;
+        if selected(index, config.include_faces_fraction) {
+            sidecar.faces = (0..index % 3 + 1).map(synthetic_face).collect();
+            sidecar.update_summary_counts();
+        }
+        write_sidecar(&photo, &sidecar)?;
+        photos.push(photo);
+    }
+    Ok(photos)
+}
+
+// ============================================================================
+// Internal Helpers
+// ============================================================================
+
+fn validate(config: &CorpusConfig) -> Result<()> {
+    let error = |message: &str| Err(JozinError::UserError { message: message.to_string() });
+    if config.cameras.is_empty() || config.formats.is_empty() {
+        return error("Corpus needs at least one camera and one format");
+    }
+    if let Some(format) = config.formats.iter().find(|format| !CORPUS_FORMATS.contains(format)) {
+        return error(&format!("Unsupported corpus format '{}' (jpg, png)", format));
+    }
+    let fractions = [config.include_gps_fraction, config.include_faces_fraction];
+    if fractions.iter().any(|fraction| !(0.0..=1.0).contains(fraction)) {
+        return error("Corpus fractions must be between 0 and 1");
+    }
+    if config.date_range.1 < config.date_range.0 {
+        return error("Corpus date range ends before it starts");
+    }
+    Ok(())
+}
+
+/// Whether photo `index` is in the `fraction` of selected photos; exactly
+/// `floor(n * fraction)` of `n` photos are, evenly spaced.
+fn selected(index: usize, fraction: f32) -> bool {
+    let fraction = f64::from(fraction);
+    ((index + 1) as f64 * fraction).floor() > (index as f64 * fraction).floor()
+}
+
+/// A position inside `bbox`, spread evenly over the photos (additive
+/// recurrence with irrational steps).
+fn spread(index: usize, bbox: &GeoBbox) -> (f64, f64) {
+    let u = (index as f64 * 0.618_033_988_75).fract();
+    let v = (index as f64 * 0.754_877_666_25).fract();
+    (
+        bbox.min_latitude + u * (bbox.max_latitude - bbox.min_latitude),
+        bbox.min_longitude + v * (bbox.max_longitude - bbox.min_longitude),
+    )
+}
+
+/// The `n`-th face of a photo: side by side in the upper half.
+fn synthetic_face(n: usize) -> FaceDetection {
+    FaceDetection {
+        bbox: [0.05 + n as f32 * 0.3, 0.1, 0.25, 0.3],
+        score: 0.9,
+        embedding_hash: None,
+        person: None,
+        landmarks: None,
+        age_estimate: None,
+        cluster_id: None,
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scan::read_sidecar;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_generate_small_corpus() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = CorpusConfig { file_count: 10, formats: vec!["jpg", "png"], ..CorpusConfig::default() };
+        let photos = generate_test_corpus(temp_dir.path(), config).unwrap();
+        assert_eq!(photos.len(), 10);
+        assert!(photos[0].ends_with("2020/IMG_00000.jpg"));
+        assert!(photos[9].ends_with("2024/IMG_00009.png"));
+
+        let sidecars: Vec<_> = photos.iter().map(|photo| read_sidecar(photo).unwrap()).collect();
+        let count = |matches: fn(&crate::Sidecar) -> bool| sidecars.iter().filter(|s| matches(s)).count();
+        assert_eq!(count(|s| s.image.as_ref().is_some_and(|image| image.gps_latitude.is_some())), 5);
+        assert_eq!(count(|s| !s.faces.is_empty()), 3);
+        assert_eq!(count(|s| s.image.as_ref().and_then(|image| image.camera_make.as_deref()) == Some("Nikon")), 3);
+        assert!(!temp_dir.path().join("2020/IMG_00000.jpg.json.bak1").exists());
+    }
+
+    #[test]
+    fn test_invalid_configs() {
+        let temp_dir = TempDir::new().unwrap();
+        for config in [
+            CorpusConfig { cameras: Vec::new(), ..CorpusConfig::default() },
+            CorpusConfig { formats: vec!["gif"], ..CorpusConfig::default() },
+            CorpusConfig { include_gps_fraction: 1.5, ..CorpusConfig::default() },
+        ] {
+            assert!(matches!(generate_test_corpus(temp_dir.path(), config), Err(JozinError::UserError { .. })));
+        }
+    }
+}
@@ -0,0 +1,254 @@
+//! Realistic test fixtures (test builds and the `test-helpers` feature)
+//!
+//! Most tests write placeholder bytes such as `b"fake image data"` to a
+//! `.jpg` file, which is enough for hashing but carries no EXIF, so nothing
+//! that reads `image.*` can be tested that way. [`create_synthetic_jpeg()`]
+//! writes a real single-colour JPEG with the EXIF fields of a
+//! [`SyntheticExif`]; [`create_synthetic_png()`] does the same for PNG (EXIF
+//! in an `eXIf` chunk).
+//!
+//! ```ignore
+//! let path = create_synthetic_jpeg(dir, "test.jpg", SyntheticExif {
+//!     camera_make: Some("Canon"),
+//!     ..SyntheticExif::default()
+//! });
+//! let sidecar = scan_file(&path, false)?.sidecar;
+//! assert_eq!(sidecar.image.unwrap().camera_make.as_deref(), Some("Canon"));
+//! ```
+
+use exif::experimental::Writer;
+use exif::{Field, In, Rational, Tag, Value};
+use flate2::write::ZlibEncoder;
+use flate2::{Compression, Crc};
+use image::codecs::jpeg::JpegEncoder;
+use image::ExtendedColorType;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+// ============================================================================
+// Public Types
+// ============================================================================
+
+/// EXIF fields of a synthetic JPEG.
+///
+/// # Fields
+///
+/// - `camera_make`, `camera_model`: `Make` and `Model`
+/// - `datetime_original`: `DateTimeOriginal` in EXIF format (`"2024:06:01 12:00:00"`)
+/// - `gps`: Latitude and longitude in decimal degrees (negative is South/West)
+/// - `width`, `height`: Pixel size of the image (also written as
+///   `PixelXDimension`/`PixelYDimension`)
+/// - `thumbnail`: Width and height of a white JPEG thumbnail embedded in IFD1
+///   (as cameras do)
+#[derive(Debug, Clone, Copy)]
+pub struct SyntheticExif<'a> {
+    pub camera_make: Option<&'a str>,
+    pub camera_model: Option<&'a str>,
+    pub datetime_original: Option<&'a str>,
+    pub gps: Option<(f64, f64)>,
+    pub width: u32,
+    pub height: u32,
+    pub thumbnail: Option<(u32, u32)>,
+}
+
+impl Default for SyntheticExif<'_> {
+    fn default() -> Self {
+        Self {
+            camera_make: None,
+            camera_model: None,
+            datetime_original: None,
+            gps: None,
+            width: 8,
+            height: 8,
+            thumbnail: None,
+        }
+    }
+}
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Writes `dir/filename` as a grey JPEG carrying the given EXIF fields and
+/// returns its path.
+///
+/// # Panics
+///
+/// Panics if encoding or writing fails (test fixture).
+pub fn create_synthetic_jpeg(dir: &Path, filename: &str, exif: SyntheticExif) -> PathBuf {
+    let encoded = grey_jpeg(exif.width, exif.height, 128);
+
+    // APP1 goes right after SOI
+    let tiff = exif_tiff(&exif);
+    let mut jpeg = encoded[..2].to_vec();
+    jpeg.extend_from_slice(&[0xFF, 0xE1]);
+    jpeg.extend_from_slice(&((tiff.len() + 8) as u16).to_be_bytes());
+    jpeg.extend_from_slice(b"Exif\0\0");
+    jpeg.extend_from_slice(&tiff);
+    jpeg.extend_from_slice(&encoded[2..]);
+
+    let path = dir.join(filename);
+    std::fs::write(&path, jpeg).unwrap();
+    path
+}
+
+/// Writes `dir/filename` as a grey PNG carrying the given EXIF fields (in
+/// an `eXIf` chunk) and returns its path.
+///
+/// # Panics
+///
+/// Panics if writing fails (test fixture).
+pub fn create_synthetic_png(dir: &Path, filename: &str, exif: SyntheticExif) -> PathBuf {
+    // 8-bit RGB rows, each with filter type 0
+    let row = [&[0u8][..], &vec![128u8; exif.width as usize * 3]].concat();
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::fast());
+    for _ in 0..exif.height {
+        encoder.write_all(&row).unwrap();
+    }
+
+    let mut header = exif.width.to_be_bytes().to_vec();
+    header.extend(exif.height.to_be_bytes());
+    header.extend([8, 2, 0, 0, 0]);
+    let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+    for (kind, data) in [
+        (b"IHDR", header),
+        (b"eXIf", exif_tiff(&exif)),
+        (b"IDAT", encoder.finish().unwrap()),
+        (b"IEND", Vec::new()),
+    ] {
+        png.extend((data.len() as u32).to_be_bytes());
+        let mut crc = Crc::new();
+        crc.update(kind);
+        crc.update(&data);
+        png.extend(kind);
+        png.extend(data);
+        png.extend(crc.sum().to_be_bytes());
+    }
+
+    let path = dir.join(filename);
+    std::fs::write(&path, png).unwrap();
+    path
+}
+
+// ============================================================================
+// Internal Helpers
+// ============================================================================
+
+/// Encodes a JPEG of one grey `level`.
+fn grey_jpeg(width: u32, height: u32, level: u8) -> Vec<u8> {
+    let pixels = vec![level; width as usize * height as usize * 3];
+    let mut encoded = Vec::new();
+    JpegEncoder::new(&mut encoded).encode(&pixels, width, height, ExtendedColorType::Rgb8).unwrap();
+    encoded
+}
+
+/// Encodes the EXIF fields as a TIFF structure.
+fn exif_tiff(exif: &SyntheticExif) -> Vec<u8> {
+    let ascii = |value: &str| Value::Ascii(vec![value.as_bytes().to_vec()]);
+    let mut values = vec![
+        (Tag::PixelXDimension, Value::Long(vec![exif.width])),
+        (Tag::PixelYDimension, Value::Long(vec![exif.height])),
+    ];
+    let text = [
+        (Tag::Make, exif.camera_make),
+        (Tag::Model, exif.camera_model),
+        (Tag::DateTimeOriginal, exif.datetime_original),
+    ];
+    values.extend(text.into_iter().filter_map(|(tag, value)| Some((tag, ascii(value?)))));
+    if let Some((latitude, longitude)) = exif.gps {
+        values.extend([
+            (Tag::GPSLatitudeRef, ascii(if latitude < 0.0 { "S" } else { "N" })),
+            (Tag::GPSLatitude, degrees_to_dms(latitude)),
+            (Tag::GPSLongitudeRef, ascii(if longitude < 0.0 { "W" } else { "E" })),
+            (Tag::GPSLongitude, degrees_to_dms(longitude)),
+        ]);
+    }
+
+    let fields: Vec<Field> = values
+        .into_iter()
+        .map(|(tag, value)| Field { tag, ifd_num: In::PRIMARY, value })
+        .collect();
+    let thumbnail = exif.thumbnail.map(|(width, height)| grey_jpeg(width, height, 255));
+    let mut writer = Writer::new();
+    for field in &fields {
+        writer.push_field(field);
+    }
+    if let Some(jpeg) = &thumbnail {
+        writer.set_jpeg(jpeg, In::THUMBNAIL);
+    }
+    let mut tiff = std::io::Cursor::new(Vec::new());
+    writer.write(&mut tiff, false).unwrap();
+    tiff.into_inner()
+}
+
+/// Converts decimal degrees to EXIF degrees/minutes/seconds (the sign goes
+/// into the reference tag). Seconds keep four decimal places.
+fn degrees_to_dms(degrees: f64) -> Value {
+    let total_seconds = (degrees.abs() * 3600.0 * 10_000.0).round() as u64;
+    let (whole_seconds, fraction) = (total_seconds / 10_000, total_seconds % 10_000);
+    Value::Rational(vec![
+        Rational { num: (whole_seconds / 3600) as u32, denom: 1 },
+        Rational { num: (whole_seconds % 3600 / 60) as u32, denom: 1 },
+        Rational { num: ((whole_seconds % 60) * 10_000 + fraction) as u32, denom: 10_000 },
+    ])
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scan::scan_file;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_synthetic_jpeg_is_scanned_with_exif() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = create_synthetic_jpeg(
+            temp_dir.path(),
+            "test.jpg",
+            SyntheticExif {
+                camera_make: Some("Canon"),
+                camera_model: Some("EOS R5"),
+                datetime_original: Some("2024:06:01 12:00:00"),
+                gps: Some((-33.8568, 151.2153)),
+                width: 16,
+                height: 8,
+                thumbnail: None,
+            },
+        );
+
+        let decoded = image::open(&path).unwrap();
+        assert_eq!((decoded.width(), decoded.height()), (16, 8));
+
+        let image = scan_file(&path, true).unwrap().sidecar.image.unwrap();
+        assert_eq!(image.camera_make.as_deref(), Some("Canon"));
+        assert_eq!(image.camera_model.as_deref(), Some("EOS R5"));
+        assert!(image.datetime_original.unwrap().starts_with("2024-06-01T12:00:00"));
+        assert!((image.gps_latitude.unwrap() + 33.8568).abs() < 1e-6);
+        assert!((image.gps_longitude.unwrap() - 151.2153).abs() < 1e-6);
+        assert_eq!((image.width, image.height), (Some(16), Some(8)));
+    }
+
+    #[test]
+    fn test_synthetic_png_is_scanned_with_exif() {
+        let temp_dir = TempDir::new().unwrap();
+        let exif = SyntheticExif { camera_make: Some("Nikon"), width: 4, height: 3, ..SyntheticExif::default() };
+        let path = create_synthetic_png(temp_dir.path(), "test.png", exif);
+
+        let image = scan_file(&path, true).unwrap().sidecar.image.unwrap();
+        assert_eq!(image.camera_make.as_deref(), Some("Nikon"));
+        assert_eq!((image.width, image.height), (Some(4), Some(3)));
+    }
+
+    #[test]
+    fn test_synthetic_jpeg_without_exif_fields() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = create_synthetic_jpeg(temp_dir.path(), "plain.jpg", SyntheticExif::default());
+        let image = scan_file(&path, true).unwrap().sidecar.image.unwrap();
+        assert_eq!(image.camera_make, None);
+        assert_eq!((image.width, image.height), (Some(8), Some(8)));
+    }
+}
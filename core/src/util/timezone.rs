@@ -0,0 +1,176 @@
+//! EXIF capture times with timezone offsets
+//!
+//! EXIF `DateTimeOriginal` is local camera time without an offset
+//! (`2025:06:15 14:30:00`), so two photos taken at the same moment in
+//! different timezones cannot be compared. Newer cameras also write
+//! `OffsetTimeOriginal` (`+05:30`); for older ones the user supplies the
+//! camera's timezone (`jozin scan --assume-timezone +05:30`).
+//!
+//! [`resolve_capture_time()`] prefers the recorded offset and falls back to
+//! the assumed one. The result is RFC3339 (`2025-06-15T14:30:00+05:30`), as
+//! stored in `ImageInfo::datetime_original`.
+//!
+//! ## Usage
+//!
+//! ```
+//! use jozin_core::util::timezone::{exif_datetime_to_rfc3339, parse_utc_offset};
+//!
+//! let offset = parse_utc_offset("+05:30")?;
+//! let rfc3339 = exif_datetime_to_rfc3339("2025:06:15 14:30:00", offset)?;
+//! assert_eq!(rfc3339, "2025-06-15T14:30:00+05:30");
+//! # Ok::<(), jozin_core::JozinError>(())
+//! ```
+
+use crate::{JozinError, Result};
+use time::format_description::well_known::Rfc3339;
+use time::{Date, Month, PrimitiveDateTime, Time, UtcOffset};
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Converts an EXIF datetime (`YYYY:MM:DD HH:MM:SS`) in local time to RFC3339.
+///
+/// # Arguments
+///
+/// * `exif_str` - EXIF `DateTime`/`DateTimeOriginal` value; surrounding
+///   whitespace and NUL padding are ignored
+/// * `tz_offset` - UTC offset of the camera clock
+///
+/// # Errors
+///
+/// Returns `JozinError::ValidationError` if `exif_str` is not a valid EXIF
+/// datetime (including the all-blank `"    :  :     :  :  "` placeholder).
+pub fn exif_datetime_to_rfc3339(exif_str: &str, tz_offset: UtcOffset) -> Result<String> {
+    let datetime = parse_exif_datetime(exif_str).ok_or_else(|| JozinError::ValidationError {
+        message: format!("Invalid EXIF datetime '{}' (expected YYYY:MM:DD HH:MM:SS)", exif_str.trim()),
+    })?;
+    datetime.assume_offset(tz_offset).format(&Rfc3339).map_err(|e| JozinError::InternalError {
+        message: format!("Failed to format capture time: {}", e),
+    })
+}
+
+/// Parses a UTC offset such as `+05:30`, `-08:00` or `Z`.
+///
+/// Accepts the EXIF `OffsetTime*` format (`±HH:MM`) and the `--assume-timezone`
+/// flag value.
+///
+/// # Errors
+///
+/// Returns `JozinError::ValidationError` if `s` is not a valid offset.
+pub fn parse_utc_offset(s: &str) -> Result<UtcOffset> {
+    let s = s.trim_matches(|c: char| c.is_whitespace() || c == '\0');
+    if s.eq_ignore_ascii_case("z") {
+        return Ok(UtcOffset::UTC);
+    }
+    parse_offset_parts(s).ok_or_else(|| JozinError::ValidationError {
+        message: format!("Invalid UTC offset '{}' (expected +HH:MM or -HH:MM)", s),
+    })
+}
+
+/// Resolves the capture time of a photo.
+///
+/// Uses `offset_time_original` (EXIF `OffsetTimeOriginal`) if it is present
+/// and valid, and `assumed` otherwise.
+///
+/// # Errors
+///
+/// Returns `JozinError::ValidationError` if `datetime_original` is invalid.
+pub fn resolve_capture_time(
+    datetime_original: &str,
+    offset_time_original: Option<&str>,
+    assumed: UtcOffset,
+) -> Result<String> {
+    let offset = offset_time_original
+        .and_then(|s| parse_utc_offset(s).ok())
+        .unwrap_or(assumed);
+    exif_datetime_to_rfc3339(datetime_original, offset)
+}
+
+// ============================================================================
+// Internal Helpers
+// ============================================================================
+
+/// Parses `YYYY:MM:DD HH:MM:SS`.
+fn parse_exif_datetime(s: &str) -> Option<PrimitiveDateTime> {
+    let s = s.trim_matches(|c: char| c.is_whitespace() || c == '\0');
+    let (date, time) = s.split_once(' ')?;
+
+    let mut date_parts = date.split(':').map(|p| p.parse::<u32>().ok());
+    let (year, month, day) = (date_parts.next()??, date_parts.next()??, date_parts.next()??);
+    let mut time_parts = time.split(':').map(|p| p.parse::<u8>().ok());
+    let (hour, minute, second) = (time_parts.next()??, time_parts.next()??, time_parts.next()??);
+    if date_parts.next().is_some() || time_parts.next().is_some() {
+        return None;
+    }
+
+    let date = Date::from_calendar_date(
+        i32::try_from(year).ok()?,
+        Month::try_from(u8::try_from(month).ok()?).ok()?,
+        u8::try_from(day).ok()?,
+    )
+    .ok()?;
+    Some(PrimitiveDateTime::new(date, Time::from_hms(hour, minute, second).ok()?))
+}
+
+/// Parses `±HH:MM`.
+fn parse_offset_parts(s: &str) -> Option<UtcOffset> {
+    let sign: i8 = match s.chars().next()? {
+        '+' => 1,
+        '-' => -1,
+        _ => return None,
+    };
+    let (hours, minutes) = s[1..].split_once(':')?;
+    if hours.len() != 2 || minutes.len() != 2 {
+        return None;
+    }
+    let hours: i8 = hours.parse().ok()?;
+    let minutes: i8 = minutes.parse().ok()?;
+    UtcOffset::from_hms(sign * hours, sign * minutes, 0).ok()
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exif_datetime_with_offset() {
+        let offset = parse_utc_offset("+05:30").unwrap();
+        assert_eq!(
+            exif_datetime_to_rfc3339("2025:06:15 14:30:00", offset).unwrap(),
+            "2025-06-15T14:30:00+05:30"
+        );
+        assert_eq!(
+            exif_datetime_to_rfc3339("2025:06:15 14:30:00\0", UtcOffset::UTC).unwrap(),
+            "2025-06-15T14:30:00Z"
+        );
+        assert_eq!(parse_utc_offset("-03:30").unwrap(), UtcOffset::from_hms(-3, -30, 0).unwrap());
+
+        assert!(exif_datetime_to_rfc3339("    :  :     :  :  ", offset).is_err());
+        assert!(exif_datetime_to_rfc3339("2025:02:30 10:00:00", offset).is_err());
+        assert!(parse_utc_offset("05:30").is_err());
+        assert!(parse_utc_offset("+5:30").is_err());
+    }
+
+    #[test]
+    fn test_recorded_offset_preferred() {
+        let assumed = parse_utc_offset("+01:00").unwrap();
+        assert_eq!(
+            resolve_capture_time("2025:06:15 14:30:00", Some("-08:00"), assumed).unwrap(),
+            "2025-06-15T14:30:00-08:00"
+        );
+        // Blank or malformed OffsetTimeOriginal falls back to the assumed offset
+        assert_eq!(
+            resolve_capture_time("2025:06:15 14:30:00", Some("   :  "), assumed).unwrap(),
+            "2025-06-15T14:30:00+01:00"
+        );
+        assert_eq!(
+            resolve_capture_time("2025:06:15 14:30:00", None, assumed).unwrap(),
+            "2025-06-15T14:30:00+01:00"
+        );
+    }
+}
@@ -0,0 +1,181 @@
+//! Order in which a scan processes the files of a directory tree
+//!
+//! The directory walk yields files depth-first, so in a deep library the
+//! progress output jumps between albums. A [`TraversalOrder`] rearranges the
+//! walked files before scanning starts:
+//!
+//! | [`TraversalOrder`] | Files are processed                                   |
+//! |--------------------|-------------------------------------------------------|
+//! | `DepthFirst`       | In walk order (default)                               |
+//! | `BreadthFirst`     | Directory by directory, shallower directories first   |
+//! | `LargestFirst`     | Directory by directory, largest total size first      |
+//!
+//! Files of one directory always stay together and in walk order. Scanning
+//! runs on several threads, so neighbouring files may still complete out of
+//! order; `max_threads = 1` makes the order exact.
+//!
+//! This is the processing order; the order of `ScanResult::scanned_files` is
+//! set by `ScanConfig::output_sort_order` (see [`crate::util::sidecar_sorter`]).
+//!
+//! ## Usage
+//!
+//! ```no_run
+//! use jozin_core::scan::{scan_path, ScanConfig};
+//! use jozin_core::util::traversal_order::TraversalOrder;
+//! use std::path::Path;
+//!
+//! let config = ScanConfig {
+//!     recursive: true,
+//!     traversal_order: TraversalOrder::BreadthFirst,
+//!     ..ScanConfig::default()
+//! };
+//! let result = scan_path(Path::new("/photos"), &config, None)?;
+//! # Ok::<(), jozin_core::JozinError>(())
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// ============================================================================
+// Public Types
+// ============================================================================
+
+/// Order in which walked files are scanned.
+///
+/// # Variants
+///
+/// - `DepthFirst`: As the directory walk yields them (default)
+/// - `BreadthFirst`: All files of a directory before any deeper directory
+/// - `LargestFirst`: Directories with the most bytes to scan first
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TraversalOrder {
+    #[default]
+    DepthFirst,
+    BreadthFirst,
+    LargestFirst,
+}
+
+impl TraversalOrder {
+    /// Kebab-case name, as in JSON and `ProgressEvent::BatchStarted`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TraversalOrder::DepthFirst => "depth-first",
+            TraversalOrder::BreadthFirst => "breadth-first",
+            TraversalOrder::LargestFirst => "largest-first",
+        }
+    }
+}
+
+impl fmt::Display for TraversalOrder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Rearranges walked files into `order`, in place.
+///
+/// Files are grouped by parent directory, keeping their relative order
+/// within a group. `LargestFirst` reads each file's size; files whose size
+/// cannot be read count as empty.
+///
+/// # Example
+///
+/// ```
+/// use jozin_core::util::traversal_order::{order_files, TraversalOrder};
+/// use std::path::PathBuf;
+///
+/// let mut files: Vec<PathBuf> = ["a/b/1.jpg", "a/2.jpg", "3.jpg"].iter().map(PathBuf::from).collect();
+/// order_files(&mut files, TraversalOrder::BreadthFirst);
+/// assert_eq!(files, ["3.jpg", "a/2.jpg", "a/b/1.jpg"].map(PathBuf::from));
+/// ```
+pub fn order_files(files: &mut [PathBuf], order: TraversalOrder) {
+    match order {
+        TraversalOrder::DepthFirst => {}
+        TraversalOrder::BreadthFirst => {
+            // Stable: files of a directory keep their walk order
+            files.sort_by(|a, b| {
+                let (a_dir, b_dir) = (parent(a), parent(b));
+                a_dir.components().count().cmp(&b_dir.components().count()).then_with(|| a_dir.cmp(b_dir))
+            });
+        }
+        TraversalOrder::LargestFirst => {
+            let mut dir_sizes: HashMap<PathBuf, u64> = HashMap::new();
+            for file in files.iter() {
+                let size = fs::metadata(file).map(|metadata| metadata.len()).unwrap_or(0);
+                *dir_sizes.entry(parent(file).to_path_buf()).or_default() += size;
+            }
+            files.sort_by(|a, b| {
+                let (a_dir, b_dir) = (parent(a), parent(b));
+                dir_sizes[b_dir].cmp(&dir_sizes[a_dir]).then_with(|| a_dir.cmp(b_dir))
+            });
+        }
+    }
+}
+
+// ============================================================================
+// Internal Helpers
+// ============================================================================
+
+/// Directory of a walked file (empty for bare file names).
+fn parent(path: &Path) -> &Path {
+    path.parent().unwrap_or(Path::new(""))
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_order_files() {
+        let walked = || -> Vec<PathBuf> {
+            ["r/2024/summer/a.jpg", "r/2024/summer/b.jpg", "r/2024/x.jpg", "r/top.jpg", "r/2023/y.jpg"]
+                .iter()
+                .map(PathBuf::from)
+                .collect()
+        };
+
+        let mut files = walked();
+        order_files(&mut files, TraversalOrder::DepthFirst);
+        assert_eq!(files, walked());
+
+        order_files(&mut files, TraversalOrder::BreadthFirst);
+        let expected = ["r/top.jpg", "r/2023/y.jpg", "r/2024/x.jpg", "r/2024/summer/a.jpg", "r/2024/summer/b.jpg"];
+        assert_eq!(files, expected.map(PathBuf::from));
+    }
+
+    #[test]
+    fn test_largest_first_groups_by_directory_size() {
+        let temp_dir = TempDir::new().unwrap();
+        let (small, large) = (temp_dir.path().join("small"), temp_dir.path().join("large"));
+        fs::create_dir_all(&small).unwrap();
+        fs::create_dir_all(&large).unwrap();
+        // Two medium files outweigh one bigger file
+        fs::write(small.join("a.jpg"), vec![0; 300]).unwrap();
+        fs::write(large.join("b.jpg"), vec![0; 200]).unwrap();
+        fs::write(large.join("c.jpg"), vec![0; 200]).unwrap();
+
+        let mut files = vec![large.join("b.jpg"), small.join("a.jpg"), large.join("c.jpg")];
+        order_files(&mut files, TraversalOrder::LargestFirst);
+        assert_eq!(files, [large.join("b.jpg"), large.join("c.jpg"), small.join("a.jpg")]);
+    }
+
+    #[test]
+    fn test_display_matches_serde() {
+        for order in [TraversalOrder::DepthFirst, TraversalOrder::BreadthFirst, TraversalOrder::LargestFirst] {
+            assert_eq!(serde_json::to_value(order).unwrap(), order.to_string());
+        }
+    }
+}
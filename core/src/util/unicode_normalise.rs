@@ -0,0 +1,87 @@
+//! Normalisation of tag labels and person names
+//!
+//! The same label typed on different systems can differ invisibly: macOS
+//! input methods often produce decomposed characters (`"cafe\u{301}"`, NFD)
+//! where others produce precomposed ones (`"café"`, NFC), and pasted text
+//! brings stray whitespace or control characters. Labels are therefore
+//! stored normalised:
+//!
+//! | Step                                  | `normalise_label` | `normalise_person_name` |
+//! |---------------------------------------|-------------------|-------------------------|
+//! | Unicode NFC                           | yes               | yes                     |
+//! | Control characters removed            | yes               | yes                     |
+//! | Whitespace trimmed and collapsed      | yes               | yes                     |
+//! | Lowercased                            | yes               | no (case as entered)    |
+//!
+//! Tabs and newlines count as whitespace, not as control characters.
+
+use unicode_normalization::UnicodeNormalization;
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Normalises a tag label (see the module docs).
+///
+/// # Example
+///
+/// ```
+/// use jozin_core::util::unicode_normalise::normalise_label;
+///
+/// assert_eq!(normalise_label("  Cafe\u{301}\u{7}  Paris "), "café paris");
+/// assert_eq!(normalise_label("café"), normalise_label("cafe\u{301}"));
+/// ```
+pub fn normalise_label(s: &str) -> String {
+    // Lowercasing can decompose (e.g. 'İ'), so compose afterwards
+    clean(&s.to_lowercase())
+}
+
+/// Normalises a person name like [`normalise_label()`], but keeps its case
+/// (`"Anna-Lena McDonald"` stays as it is).
+///
+/// # Example
+///
+/// ```
+/// use jozin_core::util::unicode_normalise::normalise_person_name;
+///
+/// assert_eq!(normalise_person_name(" Zoe\u{308}  Smith\n"), "Zoë Smith");
+/// ```
+pub fn normalise_person_name(s: &str) -> String {
+    clean(s)
+}
+
+// ============================================================================
+// Internal Helpers
+// ============================================================================
+
+/// NFC, control characters removed, whitespace trimmed and collapsed.
+fn clean(s: &str) -> String {
+    let composed: String = s.nfc().filter(|c| !c.is_control() || c.is_whitespace()).collect();
+    composed.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalise_label() {
+        assert_eq!(normalise_label("cafe\u{301}"), "café");
+        assert_eq!(normalise_label("CAFÉ"), "café");
+        assert_eq!(normalise_label("\tSan\u{0}  Francisco\r\n"), "san francisco");
+        assert_eq!(normalise_label("İstanbul"), "i\u{307}stanbul".nfc().collect::<String>());
+        assert_eq!(normalise_label(" \u{1b} "), "");
+        let once = normalise_label("  Sunset\u{200b} Beach ");
+        assert_eq!(normalise_label(&once), once);
+    }
+
+    #[test]
+    fn test_normalise_person_name_keeps_case() {
+        assert_eq!(normalise_person_name("Anna-Lena  McDonald"), "Anna-Lena McDonald");
+        assert_eq!(normalise_person_name("Jos\u{65}\u{301} "), "José");
+    }
+}
@@ -5,5 +5,1734 @@
 //! - Checking schema versions
 //! - Detecting stale sidecars that need rescanning
 //! - Verifying file integrity via hash comparison
+//! - Checking that thumbnails referenced by sidecars exist and are intact
 //!
-//! Phase 1 stub - full implementation to be added in subsequent phases.
+//! ## Checks
+//!
+//! | Check                         | Status on failure                      | Enabled by          |
+//! |-------------------------------|----------------------------------------|---------------------|
+//! | Sidecar parses as JSON        | [`VerifyStatus::CorruptJson`]          | always              |
+//! | Thumbnail file exists         | [`VerifyStatus::MissingThumbnail`]     | `check_thumbnails`  |
+//! | Thumbnail is a readable image | [`VerifyStatus::CorruptThumbnail`]     | `check_thumbnails`  |
+//! | Thumbnail dimensions (±1 px)  | [`VerifyStatus::CorruptThumbnail`]     | `check_thumbnails`  |
+//! | HDR thumbnails tone mapped    | [`VerifyStatus::HdrWithoutTonemap`]    | `check_thumbnails`  |
+//! | Face landmarks within 0-1     | [`VerifyStatus::InvalidLandmarks`]     | always              |
+//! | Content hash still matches    | [`VerifyStatus::HashMismatch`]         | `check_hashes`      |
+//! | `image.format` matches file   | [`VerifyStatus::FormatMismatch`]       | `check_formats`     |
+//! | Schema not newer than build   | [`VerifyStatus::NewerSchema`]          | unless `allow_newer_schema` |
+//! | Producer not newer than build | [`VerifyStatus::NewerProducer`]        | unless `allow_newer_schema` |
+//! | No personal data (face ages)  | [`VerifyStatus::PrivacySensitiveData`] | `check_privacy`     |
+//! | Pipeline matches reference    | [`VerifyStatus::StaleSignature`]       | `reference_signature` |
+//! | Image data decodes            | [`VerifyStatus::CorruptImageData`]     | `check_encoding`    |
+//! | File structure is intact      | [`VerifyStatus::InvalidEncoding`]      | `validate_encoding` |
+//!
+//! Thumbnail dimensions are read from the image header when Jožin is built
+//! with an image feature (`thumbs` or `faces`); otherwise only the file type is
+//! checked. `check_encoding` decodes every source image in full, which
+//! catches corrupt entropy-coded data behind valid headers (common after
+//! disk recovery); it is CPU-intensive and needs an image feature too.
+//! `validate_encoding` only checks the file structure (markers, chunk CRCs,
+//! IFD offsets; see [`crate::util::encoding_detect`]) and works in every
+//! build, but reads whole JPEGs.
+//! Hashes are recomputed with the algorithm the sidecar was written
+//! with (`file_hash_b3` or `file_hash_sha256`). Staleness checks are not
+//! implemented yet.
+//!
+//! Out-of-range GPS coordinates (latitude beyond ±90°, longitude beyond
+//! ±180°) are reported as a built-in [`ValidationWarning`]; they do not count
+//! as issues.
+//! Tag labels that are not normalised (e.g. decomposed `"cafe\u{301}"` or
+//! `"Beach"`, see [`crate::util::unicode_normalise`]) are reported the same
+//! way; `fix` rewrites them.
+//!
+//! ## Severity
+//!
+//! A sidecar written by a newer Jožin may contain fields this build ignores.
+//! [`VerifyStatus::NewerSchema`] and [`VerifyStatus::NewerProducer`] are
+//! [`WarningSeverity::Warning`]s (the sidecar still counts as ok) unless
+//! `strict` is set. A rescan leaves such sidecars unchanged and reports
+//! them as [`crate::ScanAction::Conflict`].
+//! [`VerifyStatus::PrivacySensitiveData`] is always a
+//! warning: the data is valid, the user just asked to be told about it.
+//! Every other problem is a [`WarningSeverity::Error`].
+//!
+//! ## Library Warnings
+//!
+//! Some problems concern the library as a whole rather than one sidecar and
+//! are reported in [`VerifyResult::warnings`] with a [`SuggestedAction`]:
+//!
+//! - Sidecars hashed with different algorithms (BLAKE3 and SHA-256) cannot be
+//!   compared for duplicates; rescan with one `--hash-algorithm`.
+//! - A directory holds both default `<image>.json` sidecars and sidecars in the
+//!   library's configured naming (see [`crate::util::library_config`]).
+//! - Face clusters whose faces have no person name yet; name them with
+//!   [`crate::faces::assign_cluster()`].
+//!
+//! ## Validation Plugins
+//!
+//! Custom rules (e.g. "all photos must have a GPS location") can be added
+//! without changing Jožin by implementing [`ValidationPlugin`] and passing it
+//! to [`verify_path_with_plugins()`]. Plugin findings are reported per sidecar
+//! in [`VerifiedSidecar::warnings`] tagged with [`ValidationSource::Plugin`];
+//! they do not count as issues.
+//!
+//! With the `plugins` feature, [`PluginLoader`] loads plugins from shared
+//! libraries (`.so`/`.dylib`/`.dll`) that export `jozin_plugin_init`. See
+//! `examples/custom_validator/` for a skeleton.
+//!
+//! ## Fixing
+//!
+//! With `fix`, broken thumbnails are queued in
+//! [`VerifyResult::regeneration_queue`]. With the `thumbs` feature the queue is
+//! processed immediately via [`crate::thumbs::regenerate_thumbnail()`];
+//! otherwise it is returned for `jozin thumbs` to handle. Sidecars whose
+//! hash algorithm differs from `reference_signature` are queued in
+//! [`VerifyResult::rescan_queue`].
+//!
+//! With `fix` and `normalise_paths`, sidecars whose `source.file_path` is not
+//! in canonical form (relative, backslashes, `..`) are rewritten in place.
+//!
+//! With `restore_from_backup`, sidecars reported as
+//! [`VerifyStatus::CorruptJson`] are replaced by their newest valid `.bakN`
+//! backup (see [`crate::util::backup_restore`]); with `dry_run` as well, the
+//! backups are only looked up.
+//!
+//! With `fix` and `quarantine_corrupt`, photos reported as
+//! [`VerifyStatus::CorruptImageData`] are moved with their sidecar to the
+//! quarantine directory (see [`crate::util::quarantine`]) and listed in
+//! [`VerifyResult::quarantined`].
+
+use crate::exif::is_valid_coordinate;
+use crate::migrate::{parse_schema_version, CURRENT_SCHEMA_VERSION};
+use crate::scan::{find_sidecar_files_with, parse_sidecar, write_sidecar_with};
+use crate::util::compression::read_sidecar_file;
+use crate::util::encoding_detect::{expected_image_format, validate_image_encoding};
+use crate::util::image_format::{detect_format_from_magic_bytes, ImageFormat};
+use crate::util::library_config::LibraryConfig;
+use crate::util::backup_restore::{find_valid_backup, restore_from_backup, RestoredFrom, MAX_BACKUP_DEPTH};
+use crate::util::path_normalise::normalise_sidecar_path;
+use crate::util::quarantine::{Quarantine, QuarantineEntry};
+use crate::util::symlink::SymlinkStrategy;
+use crate::util::unicode_normalise::normalise_label;
+use crate::util::digest::{compute_hash, HashAlgorithm};
+use crate::{IncompatibleField, PipelineSignature, Result, Sidecar, ThumbnailInfo};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+// ============================================================================
+// Public Types
+// ============================================================================
+
+/// Options for [`verify_path()`].
+///
+/// # Fields
+///
+/// - `check_thumbnails`: Open every thumbnail referenced by a sidecar (extra I/O)
+/// - `check_hashes`: Re-hash every source image and compare (reads all images)
+/// - `check_formats`: Compare `image.format` with the source's magic bytes (reads 12 bytes per image)
+/// - `fix`: Queue (and with the `thumbs` feature, regenerate) broken thumbnails
+/// - `normalise_paths`: With `fix`, rewrite `source.file_path` values that
+///   aren't in canonical form (see [`normalise_sidecar_path()`])
+/// - `symlinks`: How symlinks are treated when looking for sidecars
+/// - `check_privacy`: Report sidecars holding personal data (estimated face ages)
+/// - `reference_signature`: Report sidecars whose pipeline signature is
+///   incompatible with this one (e.g. [`PipelineSignature::current()`])
+/// - `allow_newer_schema`: Don't report sidecars written by a newer Jožin
+/// - `strict`: Treat warnings (newer schema or producer) as errors
+/// - `restore_from_backup`: Replace corrupt sidecars with their newest valid
+///   backup (see [`restore_from_backup()`])
+/// - `dry_run`: With `restore_from_backup`, only find the backups that would
+///   be restored
+/// - `check_encoding`: Decode every source image in full (CPU-intensive;
+///   requires the `thumbs` or `faces` feature)
+/// - `quarantine_corrupt`: With `fix` and `check_encoding`, move photos with
+///   corrupt image data and their sidecars to the quarantine
+/// - `quarantine_dir`: Quarantine directory (default `~/.jozin/quarantine`)
+/// - `validate_encoding`: Check the structure of every source image without
+///   decoding it (reads whole JPEGs)
+#[derive(Debug, Clone, Default)]
+pub struct VerifyOptions {
+    pub check_thumbnails: bool,
+    pub check_hashes: bool,
+    pub check_formats: bool,
+    pub check_privacy: bool,
+    pub reference_signature: Option<PipelineSignature>,
+    pub fix: bool,
+    pub normalise_paths: bool,
+    pub symlinks: SymlinkStrategy,
+    pub allow_newer_schema: bool,
+    pub strict: bool,
+    pub restore_from_backup: bool,
+    pub dry_run: bool,
+    pub check_encoding: bool,
+    pub quarantine_corrupt: bool,
+    pub quarantine_dir: Option<PathBuf>,
+    pub validate_encoding: bool,
+}
+
+/// What the user should do about a verify finding.
+///
+/// # Variants
+///
+/// - `Noop`: Nothing to do
+/// - `Rescan`: Run `jozin scan` again
+/// - `Migrate`: Run `jozin migrate`
+/// - `UpgradeJozin`: Install a newer Jožin that understands the sidecar
+/// - `AssignClusterName`: Name the face cluster (`jozin faces --assign-cluster`)
+/// - `Quarantine`: Move the photo out of the library (`jozin verify --fix --quarantine-corrupt`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SuggestedAction {
+    Noop,
+    Rescan,
+    Migrate,
+    #[serde(rename = "upgrade_jozin")]
+    UpgradeJozin,
+    #[serde(rename = "assign_cluster_name")]
+    AssignClusterName,
+    Quarantine,
+}
+
+/// How serious a [`VerifyStatus`] is.
+///
+/// # Variants
+///
+/// - `Warning`: Reported, but the sidecar still counts as ok
+/// - `Error`: The sidecar counts as having issues
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WarningSeverity {
+    Warning,
+    Error,
+}
+
+/// A library-wide finding (not tied to one sidecar).
+///
+/// # Fields
+///
+/// - `message`: Human-readable description
+/// - `suggested_action`: How to resolve it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VerifyWarning {
+    pub message: String,
+    pub suggested_action: SuggestedAction,
+}
+
+/// Where a [`ValidationWarning`] came from.
+///
+/// # Variants
+///
+/// - `Builtin`: Jožin's own checks
+/// - `Plugin`: A [`ValidationPlugin`], identified by its name
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ValidationSource {
+    #[default]
+    Builtin,
+    Plugin { name: String },
+}
+
+/// A rule violation reported for one sidecar.
+///
+/// Plugins may leave `source` at its default; [`verify_path_with_plugins()`]
+/// overwrites it with [`ValidationSource::Plugin`] and the plugin's name.
+///
+/// # Fields
+///
+/// - `message`: Human-readable description
+/// - `source`: Which check produced the warning
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ValidationWarning {
+    pub message: String,
+    #[serde(default)]
+    pub source: ValidationSource,
+}
+
+impl ValidationWarning {
+    /// Creates a warning with the default (`Builtin`) source.
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            source: ValidationSource::default(),
+        }
+    }
+}
+
+/// A custom validation rule run against every parsed sidecar.
+///
+/// # Example
+///
+/// ```
+/// use jozin_core::verify::{ValidationPlugin, ValidationWarning};
+/// use jozin_core::Sidecar;
+///
+/// struct RequireGps;
+///
+/// impl ValidationPlugin for RequireGps {
+///     fn name(&self) -> &str {
+///         "require-gps"
+///     }
+///
+///     fn validate(&self, sidecar: &Sidecar) -> Vec<ValidationWarning> {
+///         if sidecar.image.as_ref().is_some_and(|i| i.gps_latitude.is_some()) {
+///             Vec::new()
+///         } else {
+///             vec![ValidationWarning::new("photo has no GPS location")]
+///         }
+///     }
+/// }
+/// ```
+pub trait ValidationPlugin: Send + Sync {
+    /// Short identifier shown next to the plugin's warnings.
+    fn name(&self) -> &str;
+
+    /// Returns one warning per rule the sidecar violates (empty if none).
+    fn validate(&self, sidecar: &Sidecar) -> Vec<ValidationWarning>;
+}
+
+/// Outcome of a single verify check.
+///
+/// Serialized with a `"status"` tag, e.g.
+/// `{"status": "missing_thumbnail", "expected_path": "...", "size": 256}`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum VerifyStatus {
+    /// No problems found
+    Ok,
+    /// The sidecar file is not valid sidecar JSON
+    CorruptJson { error: String },
+    /// A thumbnail listed in the sidecar does not exist
+    MissingThumbnail { expected_path: String, size: u32 },
+    /// A thumbnail exists but is unreadable or has the wrong dimensions
+    CorruptThumbnail { path: String, error: String },
+    /// A face has landmark coordinates outside the normalized 0-1 range
+    InvalidLandmarks { face_index: usize },
+    /// The source image no longer matches the recorded content hash
+    HashMismatch {
+        algorithm: HashAlgorithm,
+        expected: String,
+        actual: String,
+    },
+    /// `image.format` disagrees with the format detected from the file's magic bytes
+    FormatMismatch { recorded: String, detected: ImageFormat },
+    /// The sidecar's schema is newer than this build understands (a rescan
+    /// reports it as a [`crate::ScanAction::Conflict`])
+    NewerSchema {
+        sidecar_version: String,
+        supported_version: String,
+    },
+    /// The sidecar was written by a newer Jožin than the one running
+    NewerProducer {
+        producer_version: String,
+        running_version: String,
+    },
+    /// The sidecar holds personal data (e.g. `faces[].age_estimate`)
+    PrivacySensitiveData { fields: Vec<String> },
+    /// The sidecar's pipeline signature is incompatible with the reference
+    StaleSignature {
+        stored: Box<PipelineSignature>,
+        reference: Box<PipelineSignature>,
+        fields: Vec<IncompatibleField>,
+    },
+    /// The source image's headers are readable but its pixel data does not decode
+    CorruptImageData { error: String },
+    /// The source image's structure is broken (e.g. a JPEG without its end
+    /// marker, a PNG header with a bad CRC)
+    InvalidEncoding { format: ImageFormat, error: String },
+    /// A thumbnail of an HDR image (`image.hdr`) was made without tone
+    /// mapping and likely looks washed out (warning)
+    HdrWithoutTonemap { path: String },
+}
+
+impl VerifyStatus {
+    /// Severity of the status; `strict` turns warnings into errors.
+    pub fn severity(&self, strict: bool) -> WarningSeverity {
+        match self {
+            VerifyStatus::Ok | VerifyStatus::PrivacySensitiveData { .. } | VerifyStatus::HdrWithoutTonemap { .. } => {
+                WarningSeverity::Warning
+            }
+            VerifyStatus::NewerSchema { .. } | VerifyStatus::NewerProducer { .. } if !strict => {
+                WarningSeverity::Warning
+            }
+            _ => WarningSeverity::Error,
+        }
+    }
+
+    /// How the user can resolve the status.
+    pub fn suggested_action(&self) -> SuggestedAction {
+        match self {
+            VerifyStatus::Ok | VerifyStatus::PrivacySensitiveData { .. } => SuggestedAction::Noop,
+            VerifyStatus::NewerSchema { .. } | VerifyStatus::NewerProducer { .. } => SuggestedAction::UpgradeJozin,
+            VerifyStatus::CorruptImageData { .. } | VerifyStatus::InvalidEncoding { .. } => SuggestedAction::Quarantine,
+            _ => SuggestedAction::Rescan,
+        }
+    }
+}
+
+/// Verification outcome for one sidecar.
+///
+/// # Fields
+///
+/// - `sidecar_path`: Path to the sidecar JSON file
+/// - `statuses`: `[Ok]` if all checks passed, otherwise one entry per problem
+///   (the sidecar still counts as ok if all of them are warnings)
+/// - `warnings`: Findings from [`ValidationPlugin`]s (not counted as issues)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerifiedSidecar {
+    pub sidecar_path: String,
+    pub statuses: Vec<VerifyStatus>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<ValidationWarning>,
+}
+
+/// A thumbnail that should be recreated from its source image.
+///
+/// # Fields
+///
+/// - `source_path`: Original image the thumbnail is generated from
+/// - `thumbnail_path`: Where the thumbnail belongs (resolved against the sidecar)
+/// - `thumbnail`: The sidecar's record of the thumbnail (size, format)
+/// - `orientation`: EXIF orientation of the source, applied before resizing
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThumbnailRegeneration {
+    pub source_path: String,
+    pub thumbnail_path: String,
+    pub thumbnail: ThumbnailInfo,
+    pub orientation: Option<u8>,
+}
+
+/// Result of [`verify_path()`].
+///
+/// # Fields
+///
+/// - `total_sidecars`: Sidecars checked
+/// - `ok`: Sidecars without problems
+/// - `with_issues`: Sidecars with at least one problem
+/// - `sidecars`: Per-sidecar outcomes
+/// - `regeneration_queue`: Thumbnails to recreate (only with `fix`)
+/// - `regenerated`: Thumbnails recreated during this run (`thumbs` feature)
+/// - `paths_normalised`: Sidecars whose `source.file_path` was rewritten
+/// - `labels_normalised`: Sidecars whose tag labels were normalised (only
+///   with `fix`)
+/// - `rescan_queue`: Source images to rescan because their hash algorithm
+///   differs from the reference signature (only with `fix`)
+/// - `restored`: Corrupt sidecars replaced by a backup (only with
+///   `restore_from_backup`)
+/// - `restore_candidates`: Backups that would replace corrupt sidecars
+///   (only with `restore_from_backup` and `dry_run`)
+/// - `quarantined`: Photos with corrupt image data moved to the quarantine
+///   (only with `fix` and `quarantine_corrupt`)
+/// - `warnings`: Library-wide findings (see [`VerifyWarning`])
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct VerifyResult {
+    pub total_sidecars: usize,
+    pub ok: usize,
+    pub with_issues: usize,
+    pub sidecars: Vec<VerifiedSidecar>,
+    pub regeneration_queue: Vec<ThumbnailRegeneration>,
+    pub regenerated: usize,
+    #[serde(default)]
+    pub paths_normalised: usize,
+    #[serde(default)]
+    pub labels_normalised: usize,
+    #[serde(default)]
+    pub rescan_queue: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub restored: Vec<RestoredFrom>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub restore_candidates: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub quarantined: Vec<QuarantineEntry>,
+    pub warnings: Vec<VerifyWarning>,
+}
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Verifies the sidecars under a path.
+///
+/// # Arguments
+///
+/// * `path` - Sidecar, image, or directory
+/// * `recursive` - Descend into subdirectories
+/// * `options` - Which checks to run (see [`VerifyOptions`])
+///
+/// # Errors
+///
+/// - `JozinError::IoError` if `path` does not exist
+/// - `JozinError::UserError` if a symlink is found with [`SymlinkStrategy::Error`],
+///   or `quarantine_corrupt` is set without a `quarantine_dir` and the home
+///   directory is unknown
+/// - `JozinError::NotSupported` if `check_encoding` is set without an image
+///   feature (`thumbs` or `faces`)
+///
+/// Problems with individual sidecars are reported in the result, not as errors.
+///
+/// # Example
+///
+/// ```no_run
+/// use jozin_core::verify::{verify_path, VerifyOptions};
+/// use std::path::Path;
+///
+/// let options = VerifyOptions { check_thumbnails: true, ..VerifyOptions::default() };
+/// let result = verify_path(Path::new("/photos"), true, &options)?;
+/// println!("{} of {} sidecars have issues", result.with_issues, result.total_sidecars);
+/// # Ok::<(), jozin_core::JozinError>(())
+/// ```
+pub fn verify_path(path: &Path, recursive: bool, options: &VerifyOptions) -> Result<VerifyResult> {
+    verify_path_with_plugins(path, recursive, options, &[])
+}
+
+/// Verifies the sidecars under a path and runs custom validation plugins.
+///
+/// Every plugin sees every sidecar that parses; their findings are collected
+/// in [`VerifiedSidecar::warnings`] with `source` set to
+/// [`ValidationSource::Plugin`].
+///
+/// # Arguments
+///
+/// * `path` - Sidecar, image, or directory
+/// * `recursive` - Descend into subdirectories
+/// * `options` - Which built-in checks to run
+/// * `plugins` - Custom rules (see [`ValidationPlugin`])
+///
+/// # Errors
+///
+/// Same as [`verify_path()`].
+pub fn verify_path_with_plugins(
+    path: &Path,
+    recursive: bool,
+    options: &VerifyOptions,
+    plugins: &[Box<dyn ValidationPlugin>],
+) -> Result<VerifyResult> {
+    if options.check_encoding && !cfg!(any(feature = "faces", feature = "thumbs")) {
+        return Err(crate::JozinError::NotSupported {
+            feature: "verify --check-encoding (build with the thumbs or faces feature)".to_string(),
+        });
+    }
+    let quarantine = if options.fix && options.check_encoding && options.quarantine_corrupt {
+        let dir = options.quarantine_dir.clone().or_else(Quarantine::default_dir).ok_or_else(|| {
+            crate::JozinError::UserError {
+                message: "Cannot locate the home directory for ~/.jozin/quarantine".to_string(),
+            }
+        })?;
+        Some(Quarantine::new(dir))
+    } else {
+        None
+    };
+
+    let mut result = VerifyResult::default();
+    let mut algorithms = BTreeSet::new();
+    let mut unnamed_clusters: BTreeMap<String, usize> = BTreeMap::new();
+    let naming = LibraryConfig::discover(path);
+    let sidecar_paths = find_sidecar_files_with(path, recursive, &naming, options.symlinks)?;
+
+    for sidecar_path in &sidecar_paths {
+        let mut statuses = Vec::new();
+        let mut warnings = Vec::new();
+
+        match read_sidecar_file(sidecar_path).and_then(|json| parse_sidecar(&json)) {
+            Ok(mut sidecar) => {
+                if options.fix && options.normalise_paths && normalise_source_path(&mut sidecar, sidecar_path, &naming) {
+                    result.paths_normalised += 1;
+                }
+                if options.fix && normalise_tag_labels(&mut sidecar, sidecar_path, &naming) {
+                    result.labels_normalised += 1;
+                }
+                algorithms.insert(sidecar.source.hash_algorithm().as_str());
+                for face in sidecar.faces.iter().filter(|face| face.person.is_none()) {
+                    if let Some(cluster_id) = &face.cluster_id {
+                        *unnamed_clusters.entry(cluster_id.clone()).or_default() += 1;
+                    }
+                }
+                warnings.extend(check_gps(&sidecar));
+                warnings.extend(check_summary_counts(&sidecar));
+                warnings.extend(check_tag_labels(&sidecar));
+                warnings.extend(run_plugins(plugins, &sidecar));
+                if !options.allow_newer_schema {
+                    statuses.extend(check_newer_versions(&sidecar));
+                }
+                statuses.extend(check_landmarks(&sidecar));
+                if options.check_privacy {
+                    statuses.extend(check_privacy(&sidecar));
+                }
+                if let Some(reference) = &options.reference_signature {
+                    if let Some(status) = check_signature(&sidecar, reference) {
+                        let stale_hash = matches!(&status, VerifyStatus::StaleSignature { fields, .. }
+                            if fields.contains(&IncompatibleField::HashAlgorithm));
+                        if options.fix && stale_hash {
+                            result.rescan_queue.push(sidecar.source.file_path.clone());
+                        }
+                        statuses.push(status);
+                    }
+                }
+                if options.check_hashes {
+                    statuses.extend(check_hash(&sidecar, sidecar_path, &naming));
+                }
+                if options.check_formats {
+                    statuses.extend(check_format(&sidecar, sidecar_path, &naming));
+                }
+                if options.check_encoding {
+                    if let Some((image_path, error)) = check_encoding(sidecar_path, &naming) {
+                        if let Some(quarantine) = &quarantine {
+                            match quarantine.quarantine(&image_path, Some(sidecar_path), &error) {
+                                Ok(entry) => result.quarantined.push(entry),
+                                Err(e) => eprintln!("Warning: Failed to quarantine {}: {}", image_path.display(), e),
+                            }
+                        }
+                        statuses.push(VerifyStatus::CorruptImageData { error });
+                    }
+                }
+                if options.validate_encoding {
+                    statuses.extend(check_structure(sidecar_path, &naming));
+                }
+                if options.check_thumbnails {
+                    let base_dir = sidecar_path.parent().unwrap_or(Path::new(""));
+                    for (thumbnail, status) in check_thumbnails_in(&sidecar, base_dir) {
+                        if options.fix {
+                            result.regeneration_queue.push(ThumbnailRegeneration {
+                                source_path: sidecar.source.file_path.clone(),
+                                thumbnail_path: resolve(base_dir, &thumbnail.path).display().to_string(),
+                                thumbnail: thumbnail.clone(),
+                                orientation: sidecar.image.as_ref().and_then(|i| i.orientation),
+                            });
+                        }
+                        statuses.push(status);
+                    }
+                    statuses.extend(check_hdr_tonemap(&sidecar));
+                }
+            }
+            Err(e) => {
+                statuses.push(VerifyStatus::CorruptJson { error: e.to_string() });
+                if options.restore_from_backup {
+                    restore_corrupt(sidecar_path, options.dry_run, &mut result);
+                }
+            }
+        }
+
+        result.total_sidecars += 1;
+        if statuses.iter().all(|s| s.severity(options.strict) == WarningSeverity::Warning) {
+            result.ok += 1;
+        } else {
+            result.with_issues += 1;
+        }
+        if statuses.is_empty() {
+            statuses.push(VerifyStatus::Ok);
+        }
+        result.sidecars.push(VerifiedSidecar {
+            sidecar_path: sidecar_path.display().to_string(),
+            statuses,
+            warnings,
+        });
+    }
+
+    if options.fix {
+        result.regenerated = regenerate_queued(&result.regeneration_queue);
+    }
+
+    if algorithms.len() > 1 {
+        result.warnings.push(VerifyWarning {
+            message: format!(
+                "Sidecars use different hash algorithms ({}); rescan with a single --hash-algorithm",
+                algorithms.into_iter().collect::<Vec<_>>().join(", ")
+            ),
+            suggested_action: SuggestedAction::Rescan,
+        });
+    }
+
+    for (cluster_id, faces) in unnamed_clusters {
+        result.warnings.push(VerifyWarning {
+            message: format!(
+                "Face cluster {} has {} face{} without a person name; name it with jozin faces --assign-cluster {} --name <NAME>",
+                cluster_id,
+                faces,
+                if faces == 1 { "" } else { "s" },
+                cluster_id
+            ),
+            suggested_action: SuggestedAction::AssignClusterName,
+        });
+    }
+
+    if let Some(warning) = check_mixed_naming(path, recursive, &naming, options.symlinks, &sidecar_paths)? {
+        result.warnings.push(warning);
+    }
+
+    Ok(result)
+}
+
+/// Checks every thumbnail referenced by a sidecar.
+///
+/// Relative thumbnail paths are resolved against the directory of the
+/// sidecar's source file.
+///
+/// # Returns
+///
+/// One [`VerifyStatus::MissingThumbnail`] or [`VerifyStatus::CorruptThumbnail`]
+/// per broken thumbnail; empty if all thumbnails are intact.
+pub fn check_thumbnails(sidecar: &Sidecar) -> Vec<VerifyStatus> {
+    let base_dir = Path::new(&sidecar.source.file_path)
+        .parent()
+        .unwrap_or(Path::new(""))
+        .to_path_buf();
+    check_thumbnails_in(sidecar, &base_dir)
+        .into_iter()
+        .map(|(_, status)| status)
+        .collect()
+}
+
+/// Checks that all face landmarks are normalized coordinates in 0-1.
+///
+/// # Returns
+///
+/// One [`VerifyStatus::InvalidLandmarks`] per offending face.
+pub fn check_landmarks(sidecar: &Sidecar) -> Vec<VerifyStatus> {
+    sidecar
+        .faces
+        .iter()
+        .enumerate()
+        .filter(|(_, face)| {
+            face.landmarks
+                .is_some_and(|points| points.iter().flatten().any(|v| !(0.0..=1.0).contains(v)))
+        })
+        .map(|(face_index, _)| VerifyStatus::InvalidLandmarks { face_index })
+        .collect()
+}
+
+/// Compares a sidecar's pipeline signature with a reference signature.
+///
+/// # Returns
+///
+/// A [`VerifyStatus::StaleSignature`] naming the incompatible fields, or
+/// `None` if the signatures are compatible.
+pub fn check_signature(sidecar: &Sidecar, reference: &PipelineSignature) -> Option<VerifyStatus> {
+    let stored = &sidecar.pipeline_signature;
+    let fields = stored.incompatible_fields(reference);
+    (!fields.is_empty()).then(|| VerifyStatus::StaleSignature {
+        stored: Box::new(stored.clone()),
+        reference: Box::new(reference.clone()),
+        fields,
+    })
+}
+
+/// Checks a sidecar for personal data a GDPR-conscious user may not want to
+/// keep or share.
+///
+/// # Returns
+///
+/// A [`VerifyStatus::PrivacySensitiveData`] listing the affected fields, or
+/// `None` if there are none.
+pub fn check_privacy(sidecar: &Sidecar) -> Option<VerifyStatus> {
+    let mut fields = Vec::new();
+    if sidecar.faces.iter().any(|face| face.age_estimate.is_some()) {
+        fields.push("faces[].age_estimate".to_string());
+    }
+    (!fields.is_empty()).then_some(VerifyStatus::PrivacySensitiveData { fields })
+}
+
+// ============================================================================
+// Plugin Loading
+// ============================================================================
+
+/// Name of the symbol a plugin library must export.
+#[cfg(feature = "plugins")]
+pub const PLUGIN_INIT_SYMBOL: &str = "jozin_plugin_init";
+
+/// Signature of `jozin_plugin_init`.
+///
+/// A trait object pointer is not FFI-safe, so the plugin returns a thin
+/// pointer to a boxed trait object, created with
+/// `Box::into_raw(Box::new(Box::new(plugin) as Box<dyn ValidationPlugin>))`.
+#[cfg(feature = "plugins")]
+pub type PluginInitFn = unsafe extern "C" fn() -> *mut Box<dyn ValidationPlugin>;
+
+/// Loads [`ValidationPlugin`]s from shared libraries.
+///
+/// Rust has no stable ABI: a plugin must be built with the same compiler
+/// version and the same `jozin-core` version as the host binary.
+#[cfg(feature = "plugins")]
+pub struct PluginLoader;
+
+#[cfg(feature = "plugins")]
+impl PluginLoader {
+    /// Loads the plugin exported by the shared library at `path`.
+    ///
+    /// The library stays loaded for as long as the returned plugin lives.
+    ///
+    /// # Errors
+    ///
+    /// - `JozinError::UserError` if the library cannot be opened or does not
+    ///   export `jozin_plugin_init`
+    /// - `JozinError::InternalError` if `jozin_plugin_init` returns null
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use jozin_core::verify::{verify_path_with_plugins, PluginLoader, VerifyOptions};
+    /// use std::path::Path;
+    ///
+    /// let plugin = PluginLoader::load(Path::new("./libcustom_validator.so"))?;
+    /// let result = verify_path_with_plugins(Path::new("/photos"), true, &VerifyOptions::default(), &[plugin])?;
+    /// # Ok::<(), jozin_core::JozinError>(())
+    /// ```
+    pub fn load(path: &Path) -> Result<Box<dyn ValidationPlugin>> {
+        use crate::JozinError;
+        use libloading::{Library, Symbol};
+
+        // SAFETY: loading a library runs its initialisers; plugins are
+        // trusted code chosen explicitly by the user.
+        let library = unsafe { Library::new(path) }.map_err(|e| JozinError::UserError {
+            message: format!("Cannot load plugin {}: {}", path.display(), e),
+        })?;
+
+        // SAFETY: the symbol is declared with the documented PluginInitFn
+        // signature; calling it transfers ownership of the boxed plugin.
+        let raw = unsafe {
+            let init: Symbol<PluginInitFn> = library.get(PLUGIN_INIT_SYMBOL.as_bytes()).map_err(|e| {
+                JozinError::UserError {
+                    message: format!("{} does not export {}: {}", path.display(), PLUGIN_INIT_SYMBOL, e),
+                }
+            })?;
+            init()
+        };
+        if raw.is_null() {
+            return Err(JozinError::InternalError {
+                message: format!("{} returned no plugin from {}", path.display(), PLUGIN_INIT_SYMBOL),
+            });
+        }
+
+        // SAFETY: non-null pointer produced by Box::into_raw in the plugin.
+        let plugin = unsafe { Box::from_raw(raw) };
+        Ok(Box::new(LoadedPlugin {
+            plugin: *plugin,
+            _library: library,
+        }))
+    }
+}
+
+/// A plugin together with the library its code lives in.
+///
+/// Fields drop in declaration order: the plugin (whose vtable and drop glue
+/// live in the library) is dropped before the library is unloaded.
+#[cfg(feature = "plugins")]
+struct LoadedPlugin {
+    plugin: Box<dyn ValidationPlugin>,
+    _library: libloading::Library,
+}
+
+#[cfg(feature = "plugins")]
+impl ValidationPlugin for LoadedPlugin {
+    fn name(&self) -> &str {
+        self.plugin.name()
+    }
+
+    fn validate(&self, sidecar: &Sidecar) -> Vec<ValidationWarning> {
+        self.plugin.validate(sidecar)
+    }
+}
+
+// ============================================================================
+// Internal Helpers
+// ============================================================================
+
+/// Restores a corrupt sidecar from its newest valid backup (or, in a dry
+/// run, records which backup that would be). Sidecars without a valid backup
+/// get a library warning.
+fn restore_corrupt(sidecar_path: &Path, dry_run: bool, result: &mut VerifyResult) {
+    let outcome = if dry_run {
+        find_valid_backup(sidecar_path, MAX_BACKUP_DEPTH)
+            .map(|(backup_path, _)| result.restore_candidates.push(backup_path.display().to_string()))
+    } else {
+        restore_from_backup(sidecar_path, MAX_BACKUP_DEPTH).map(|restored| result.restored.push(restored))
+    };
+    if let Err(e) = outcome {
+        result.warnings.push(VerifyWarning {
+            message: e.to_string(),
+            suggested_action: SuggestedAction::Rescan,
+        });
+    }
+}
+
+/// Runs every plugin on a sidecar and tags the warnings with the plugin name.
+fn run_plugins(plugins: &[Box<dyn ValidationPlugin>], sidecar: &Sidecar) -> Vec<ValidationWarning> {
+    plugins
+        .iter()
+        .flat_map(|plugin| {
+            let name = plugin.name().to_string();
+            plugin.validate(sidecar).into_iter().map(move |mut warning| {
+                warning.source = ValidationSource::Plugin { name: name.clone() };
+                warning
+            })
+        })
+        .collect()
+}
+
+/// Re-hashes the image next to `sidecar_path` with the sidecar's algorithm.
+///
+/// Images that cannot be read (moved, or entries inside archives) are skipped.
+fn check_hash(sidecar: &Sidecar, sidecar_path: &Path, naming: &LibraryConfig) -> Option<VerifyStatus> {
+    let image_path = naming.image_path(sidecar_path)?;
+    let algorithm = sidecar.source.hash_algorithm();
+    let actual = compute_hash(&image_path, algorithm).ok()?;
+    let expected = sidecar.source.file_hash();
+
+    (actual != expected).then(|| VerifyStatus::HashMismatch {
+        algorithm,
+        expected: expected.to_string(),
+        actual,
+    })
+}
+
+/// Rewrites a non-canonical `source.file_path` to the canonical path of the
+/// image next to `sidecar_path` and saves the sidecar.
+///
+/// Returns whether the sidecar was rewritten; write failures are reported as
+/// warnings.
+fn normalise_source_path(sidecar: &mut Sidecar, sidecar_path: &Path, naming: &LibraryConfig) -> bool {
+    let Some(image_path) = naming.image_path(sidecar_path) else {
+        return false;
+    };
+    let base_dir = std::env::current_dir().unwrap_or_default();
+    let canonical = normalise_sidecar_path(&image_path.display().to_string(), &base_dir);
+    if sidecar.source.file_path == canonical {
+        return false;
+    }
+
+    let original = std::mem::replace(&mut sidecar.source.file_path, canonical);
+    match write_sidecar_with(&image_path, sidecar, naming) {
+        Ok(()) => true,
+        Err(e) => {
+            eprintln!("Warning: Failed to normalise path in {}: {}", sidecar_path.display(), e);
+            sidecar.source.file_path = original;
+            false
+        }
+    }
+}
+
+/// Rewrites tag labels that are not normalised (see
+/// [`crate::util::unicode_normalise`]); tags that become duplicates are
+/// dropped. Returns whether the sidecar was rewritten.
+fn normalise_tag_labels(sidecar: &mut Sidecar, sidecar_path: &Path, naming: &LibraryConfig) -> bool {
+    if sidecar.tags.iter().all(|tag| normalise_label(&tag.label) == tag.label) {
+        return false;
+    }
+    let Some(image_path) = naming.image_path(sidecar_path) else {
+        return false;
+    };
+
+    let original = std::mem::take(&mut sidecar.tags);
+    sidecar.merge_tags(original.clone(), true);
+    match write_sidecar_with(&image_path, sidecar, naming) {
+        Ok(()) => true,
+        Err(e) => {
+            eprintln!("Warning: Failed to normalise tag labels in {}: {}", sidecar_path.display(), e);
+            sidecar.tags = original;
+            sidecar.update_summary_counts();
+            false
+        }
+    }
+}
+
+/// Compares `image.format` with the magic bytes of the image next to `sidecar_path`.
+///
+/// Skipped when no format is recorded, the recorded name has no single
+/// magic-byte format (e.g. `"RAW"`), or the image cannot be read or identified.
+fn check_format(sidecar: &Sidecar, sidecar_path: &Path, naming: &LibraryConfig) -> Option<VerifyStatus> {
+    let recorded = sidecar.image.as_ref()?.format.as_deref()?;
+    let expected = ImageFormat::from_name(recorded)?;
+    let detected = detect_format_from_magic_bytes(&naming.image_path(sidecar_path)?).ok()??;
+
+    (detected != expected).then(|| VerifyStatus::FormatMismatch {
+        recorded: recorded.to_string(),
+        detected,
+    })
+}
+
+/// Decodes the source image of `sidecar_path` in full.
+///
+/// Returns the image path and the decoder's error if the pixel data is
+/// corrupt; missing files and formats without a decoder are not reported.
+#[cfg(any(feature = "faces", feature = "thumbs"))]
+fn check_encoding(sidecar_path: &Path, naming: &LibraryConfig) -> Option<(PathBuf, String)> {
+    let image_path = naming.image_path(sidecar_path)?;
+    let decoded = image::ImageReader::open(&image_path).ok()?.with_guessed_format().ok()?.decode();
+    match decoded {
+        Err(image::ImageError::Decoding(e)) => Some((image_path, e.to_string())),
+        _ => None,
+    }
+}
+
+/// Without an image decoder, nothing is decoded (rejected in
+/// [`verify_path_with_plugins()`]).
+#[cfg(not(any(feature = "faces", feature = "thumbs")))]
+fn check_encoding(_sidecar_path: &Path, _naming: &LibraryConfig) -> Option<(PathBuf, String)> {
+    None
+}
+
+/// Checks the structure of the source image of `sidecar_path`; missing
+/// files and formats that can't be told are not reported.
+fn check_structure(sidecar_path: &Path, naming: &LibraryConfig) -> Option<VerifyStatus> {
+    let image_path = naming.image_path(sidecar_path)?;
+    let format = expected_image_format(&image_path).ok()??;
+    let result = validate_image_encoding(&image_path, format).ok()?;
+    result.first_error.map(|error| VerifyStatus::InvalidEncoding {
+        format: result.format_detected,
+        error,
+    })
+}
+
+/// Warns about GPS coordinates outside ±90° latitude / ±180° longitude.
+fn check_gps(sidecar: &Sidecar) -> Option<ValidationWarning> {
+    let image = sidecar.image.as_ref()?;
+    let (latitude, longitude) = (image.gps_latitude?, image.gps_longitude?);
+    (!is_valid_coordinate(latitude, longitude)).then(|| {
+        ValidationWarning::new(format!("GPS coordinates out of range: {}, {}", latitude, longitude))
+    })
+}
+
+/// Warns about tag labels that are not normalised (NFC, lowercase, single
+/// spaces); `--fix` rewrites them.
+fn check_tag_labels(sidecar: &Sidecar) -> Vec<ValidationWarning> {
+    sidecar
+        .tags
+        .iter()
+        .filter(|tag| normalise_label(&tag.label) != tag.label)
+        .map(|tag| {
+            ValidationWarning::new(format!(
+                "Tag label {:?} is not normalised (expected {:?})",
+                tag.label,
+                normalise_label(&tag.label)
+            ))
+        })
+        .collect()
+}
+
+/// Warns when `faces_count`/`tag_count` disagree with the `faces`/`tags`
+/// arrays (e.g. after a manual edit).
+fn check_summary_counts(sidecar: &Sidecar) -> Vec<ValidationWarning> {
+    let Some(image) = sidecar.image.as_ref() else {
+        return Vec::new();
+    };
+    [
+        ("faces_count", image.faces_count, sidecar.faces.len()),
+        ("tag_count", image.tag_count, sidecar.tags.len()),
+    ]
+    .into_iter()
+    .filter_map(|(field, count, actual)| {
+        let count = count?;
+        (count as usize != actual).then(|| ValidationWarning::new(format!("{} is {} but sidecar has {}", field, count, actual)))
+    })
+    .collect()
+}
+
+/// Reports a schema or producer version newer than this build.
+///
+/// Unparsable versions are not reported (they are not comparable).
+fn check_newer_versions(sidecar: &Sidecar) -> Vec<VerifyStatus> {
+    let is_newer = |version: &str, running: &str| {
+        matches!(
+            (parse_schema_version(version), parse_schema_version(running)),
+            (Some(version), Some(running)) if version > running
+        )
+    };
+
+    let mut statuses = Vec::new();
+    if is_newer(&sidecar.schema_version, CURRENT_SCHEMA_VERSION) {
+        statuses.push(VerifyStatus::NewerSchema {
+            sidecar_version: sidecar.schema_version.clone(),
+            supported_version: CURRENT_SCHEMA_VERSION.to_string(),
+        });
+    }
+    let producer_version = &sidecar.pipeline_signature.producer_version;
+    if is_newer(producer_version, env!("CARGO_PKG_VERSION")) {
+        statuses.push(VerifyStatus::NewerProducer {
+            producer_version: producer_version.clone(),
+            running_version: env!("CARGO_PKG_VERSION").to_string(),
+        });
+    }
+    statuses
+}
+
+/// Warns about directories holding both default `<image>.json` sidecars and
+/// sidecars in the library's configured naming.
+///
+/// Only applies to libraries with a non-default naming.
+fn check_mixed_naming(
+    path: &Path,
+    recursive: bool,
+    naming: &LibraryConfig,
+    symlinks: SymlinkStrategy,
+    sidecar_paths: &[PathBuf],
+) -> Result<Option<VerifyWarning>> {
+    if naming.is_default_naming() {
+        return Ok(None);
+    }
+
+    let configured_dirs: BTreeSet<&Path> = sidecar_paths.iter().filter_map(|p| p.parent()).collect();
+    let mixed_dirs: BTreeSet<PathBuf> = find_sidecar_files_with(path, recursive, &LibraryConfig::default(), symlinks)?
+        .into_iter()
+        .filter(|p| !naming.is_sidecar(p))
+        .filter_map(|p| p.parent().map(Path::to_path_buf))
+        .filter(|dir| configured_dirs.contains(dir.as_path()))
+        .collect();
+
+    if mixed_dirs.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(VerifyWarning {
+        message: format!(
+            "{} director{} mix {} sidecars with default .json sidecars (e.g. {}); rescan and remove the old ones",
+            mixed_dirs.len(),
+            if mixed_dirs.len() == 1 { "y" } else { "ies" },
+            naming.sidecar_path(Path::new("<image>")).display(),
+            mixed_dirs.iter().next().map(|d| d.display().to_string()).unwrap_or_default()
+        ),
+        suggested_action: SuggestedAction::Rescan,
+    }))
+}
+
+/// Checks thumbnails, resolving relative paths against `base_dir`.
+fn check_thumbnails_in<'a>(sidecar: &'a Sidecar, base_dir: &Path) -> Vec<(&'a ThumbnailInfo, VerifyStatus)> {
+    let source_long_edge = sidecar
+        .image
+        .as_ref()
+        .and_then(|image| Some(image.width?.max(image.height?)));
+
+    sidecar
+        .thumbnails
+        .iter()
+        .filter_map(|thumbnail| {
+            let path = resolve(base_dir, &thumbnail.path);
+            if !path.is_file() {
+                return Some((
+                    thumbnail,
+                    VerifyStatus::MissingThumbnail {
+                        expected_path: path.display().to_string(),
+                        size: thumbnail.size,
+                    },
+                ));
+            }
+
+            // Thumbnails are never upscaled beyond the source
+            let expected = source_long_edge.map_or(thumbnail.size, |edge| edge.min(thumbnail.size));
+            check_thumbnail_file(&path, expected).err().map(|error| {
+                (
+                    thumbnail,
+                    VerifyStatus::CorruptThumbnail {
+                        path: path.display().to_string(),
+                        error,
+                    },
+                )
+            })
+        })
+        .collect()
+}
+
+/// One [`VerifyStatus::HdrWithoutTonemap`] per still-image thumbnail of an
+/// HDR image that records no tone mapping.
+fn check_hdr_tonemap(sidecar: &Sidecar) -> Vec<VerifyStatus> {
+    if sidecar.image.as_ref().and_then(|image| image.hdr) != Some(true) {
+        return Vec::new();
+    }
+    sidecar
+        .thumbnails
+        .iter()
+        .filter(|thumbnail| thumbnail.source_timestamp_sec.is_none() && thumbnail.tonemap.is_none())
+        .map(|thumbnail| VerifyStatus::HdrWithoutTonemap { path: thumbnail.path.clone() })
+        .collect()
+}
+
+/// Reads the thumbnail header and compares its long edge to `expected_size` (±1 px).
+#[cfg(any(feature = "faces", feature = "thumbs"))]
+fn check_thumbnail_file(path: &Path, expected_size: u32) -> std::result::Result<(), String> {
+    let (width, height) = image::ImageReader::open(path)
+        .map_err(|e| e.to_string())?
+        .with_guessed_format()
+        .map_err(|e| e.to_string())?
+        .into_dimensions()
+        .map_err(|e| e.to_string())?;
+
+    if width.max(height).abs_diff(expected_size) > 1 {
+        return Err(format!(
+            "dimensions {}x{} do not match thumbnail size {}",
+            width, height, expected_size
+        ));
+    }
+    Ok(())
+}
+
+/// Without an image decoder, only checks that the file is an image by magic bytes.
+#[cfg(not(any(feature = "faces", feature = "thumbs")))]
+fn check_thumbnail_file(path: &Path, _expected_size: u32) -> std::result::Result<(), String> {
+    match infer::get_from_path(path).map_err(|e| e.to_string())? {
+        Some(kind) if kind.matcher_type() == infer::MatcherType::Image => Ok(()),
+        _ => Err("not an image file".to_string()),
+    }
+}
+
+fn resolve(base_dir: &Path, path: &str) -> PathBuf {
+    let path = Path::new(path);
+    if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        base_dir.join(path)
+    }
+}
+
+/// Regenerates queued thumbnails, returning how many succeeded.
+#[cfg(feature = "thumbs")]
+fn regenerate_queued(queue: &[ThumbnailRegeneration]) -> usize {
+    queue
+        .iter()
+        .filter(|item| {
+            match crate::thumbs::regenerate_thumbnail(
+                Path::new(&item.source_path),
+                &item.thumbnail,
+                Path::new(&item.thumbnail_path),
+                item.orientation,
+            ) {
+                Ok(()) => true,
+                Err(e) => {
+                    eprintln!("Warning: Failed to regenerate {}: {}", item.thumbnail_path, e);
+                    false
+                }
+            }
+        })
+        .count()
+}
+
+/// Without the `thumbs` feature the queue is returned to the caller untouched.
+#[cfg(not(feature = "thumbs"))]
+fn regenerate_queued(_queue: &[ThumbnailRegeneration]) -> usize {
+    0
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scan::{read_sidecar, scan_file, write_sidecar};
+    use std::fs;
+    use tempfile::TempDir;
+
+    /// 1x1 PNG
+    const PNG_1X1: &[u8] = &[
+        0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A, 0x00, 0x00, 0x00, 0x0D, 0x49, 0x48, 0x44,
+        0x52, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x01, 0x08, 0x06, 0x00, 0x00, 0x00, 0x1F,
+        0x15, 0xC4, 0x89, 0x00, 0x00, 0x00, 0x0D, 0x49, 0x44, 0x41, 0x54, 0x78, 0x9C, 0x63, 0x00,
+        0x01, 0x00, 0x00, 0x05, 0x00, 0x01, 0x0D, 0x0A, 0x2D, 0xB4, 0x00, 0x00, 0x00, 0x00, 0x49,
+        0x45, 0x4E, 0x44, 0xAE, 0x42, 0x60, 0x82,
+    ];
+
+    fn thumb(path: &str, size: u32) -> ThumbnailInfo {
+        ThumbnailInfo {
+            path: path.to_string(),
+            size,
+            format: "png".to_string(),
+            source_timestamp_sec: None,
+            dominant_color: None,
+            from_exif: false,
+            tonemap: None,
+        }
+    }
+
+    #[test]
+    fn test_verify_thumbnails() {
+        let temp_dir = TempDir::new().unwrap();
+        let photo = temp_dir.path().join("a.jpg");
+        fs::write(&photo, b"image").unwrap();
+        let mut sidecar = scan_file(&photo, true).unwrap().sidecar;
+
+        fs::write(temp_dir.path().join("a_1.png"), PNG_1X1).unwrap();
+        fs::write(temp_dir.path().join("a_2.png"), b"garbage").unwrap();
+        sidecar.thumbnails = vec![thumb("a_1.png", 1), thumb("a_2.png", 2), thumb("a_3.png", 3)];
+        write_sidecar(&photo, &sidecar).unwrap();
+
+        // Thumbnails are only opened on request
+        let quick = verify_path(temp_dir.path(), false, &VerifyOptions::default()).unwrap();
+        assert_eq!(quick.ok, 1);
+
+        let options = VerifyOptions { check_thumbnails: true, fix: true, ..VerifyOptions::default() };
+        let result = verify_path(temp_dir.path(), false, &options).unwrap();
+        assert_eq!(result.with_issues, 1);
+
+        let statuses = &result.sidecars[0].statuses;
+        assert_eq!(statuses.len(), 2);
+        assert!(matches!(&statuses[0], VerifyStatus::CorruptThumbnail { path, .. } if path.ends_with("a_2.png")));
+        assert!(matches!(&statuses[1], VerifyStatus::MissingThumbnail { size: 3, .. }));
+        assert_eq!(result.regeneration_queue.len(), 2);
+
+        assert_eq!(check_thumbnails(&sidecar).len(), 2);
+    }
+
+    #[test]
+    fn test_verify_hdr_thumbnail_without_tonemap() {
+        let temp_dir = TempDir::new().unwrap();
+        let photo = temp_dir.path().join("a.jpg");
+        fs::write(&photo, b"image").unwrap();
+        let mut sidecar = scan_file(&photo, true).unwrap().sidecar;
+
+        fs::write(temp_dir.path().join("a_1.png"), PNG_1X1).unwrap();
+        fs::write(temp_dir.path().join("b_1.png"), PNG_1X1).unwrap();
+        let mapped = ThumbnailInfo { tonemap: Some("reinhard".to_string()), ..thumb("b_1.png", 1) };
+        sidecar.thumbnails = vec![thumb("a_1.png", 1), mapped];
+        sidecar.image.get_or_insert_with(Default::default).hdr = Some(true);
+        write_sidecar(&photo, &sidecar).unwrap();
+
+        let options = VerifyOptions { check_thumbnails: true, ..VerifyOptions::default() };
+        let result = verify_path(temp_dir.path(), false, &options).unwrap();
+        // A warning: the sidecar still counts as ok
+        assert_eq!(result.ok, 1);
+        assert_eq!(
+            result.sidecars[0].statuses,
+            vec![VerifyStatus::HdrWithoutTonemap { path: "a_1.png".to_string() }]
+        );
+    }
+
+    #[test]
+    fn test_verify_reports_corrupt_json() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("a.jpg.json"), b"{ not json").unwrap();
+
+        let result = verify_path(temp_dir.path(), false, &VerifyOptions::default()).unwrap();
+        assert_eq!(result.with_issues, 1);
+        assert!(matches!(result.sidecars[0].statuses[0], VerifyStatus::CorruptJson { .. }));
+    }
+
+    #[test]
+    fn test_verify_restores_corrupt_sidecars_from_backup() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut schema_version = String::new();
+        for name in ["a.jpg", "b.jpg"] {
+            let photo = temp_dir.path().join(name);
+            fs::write(&photo, b"image").unwrap();
+            let sidecar = scan_file(&photo, false).unwrap().sidecar;
+            write_sidecar(&photo, &sidecar).unwrap();
+            schema_version = sidecar.schema_version;
+            fs::write(temp_dir.path().join(format!("{}.json", name)), b"{ not json").unwrap();
+        }
+        // b.jpg has no usable backup
+        fs::write(temp_dir.path().join("b.jpg.json.bak1"), b"{}").unwrap();
+
+        let dry_run = VerifyOptions { restore_from_backup: true, dry_run: true, ..VerifyOptions::default() };
+        let result = verify_path(temp_dir.path(), false, &dry_run).unwrap();
+        assert!(result.restored.is_empty());
+        assert_eq!(result.restore_candidates.len(), 1);
+        assert!(result.restore_candidates[0].ends_with("a.jpg.json.bak1"));
+        assert!(result.warnings.iter().any(|w| w.message.contains("No valid backup") && w.message.contains("b.jpg.json")));
+
+        let options = VerifyOptions { restore_from_backup: true, ..VerifyOptions::default() };
+        let result = verify_path(temp_dir.path(), false, &options).unwrap();
+        assert_eq!(result.restored.len(), 1);
+        assert_eq!(result.restored[0].schema_version, schema_version);
+
+        let after = verify_path(temp_dir.path(), false, &VerifyOptions::default()).unwrap();
+        assert_eq!((after.ok, after.with_issues), (1, 1));
+    }
+
+    #[test]
+    fn test_verify_reports_out_of_range_landmarks() {
+        let temp_dir = TempDir::new().unwrap();
+        let photo = temp_dir.path().join("a.jpg");
+        fs::write(&photo, b"image").unwrap();
+        let mut sidecar = scan_file(&photo, true).unwrap().sidecar;
+
+        let face = |landmarks| crate::FaceDetection {
+            bbox: [0.1, 0.1, 0.2, 0.2],
+            score: 0.9,
+            embedding_hash: None,
+            person: None,
+            landmarks,
+            age_estimate: None,
+            cluster_id: None,
+        };
+        sidecar.faces = vec![
+            face(None),
+            face(Some([[0.2, 0.2], [0.3, 0.2], [0.25, 0.25], [0.2, 0.3], [0.3, 0.3]])),
+            face(Some([[0.2, 0.2], [1.3, 0.2], [0.25, 0.25], [0.2, 0.3], [0.3, 0.3]])),
+        ];
+        write_sidecar(&photo, &sidecar).unwrap();
+
+        let result = verify_path(temp_dir.path(), false, &VerifyOptions::default()).unwrap();
+        assert_eq!(result.sidecars[0].statuses, vec![VerifyStatus::InvalidLandmarks { face_index: 2 }]);
+    }
+
+    #[test]
+    fn test_verify_warns_about_unnamed_face_clusters() {
+        let temp_dir = TempDir::new().unwrap();
+        let photo = temp_dir.path().join("a.jpg");
+        fs::write(&photo, b"image").unwrap();
+        let mut sidecar = scan_file(&photo, true).unwrap().sidecar;
+
+        let face = |cluster_id: Option<&str>, person: Option<&str>| crate::FaceDetection {
+            bbox: [0.1, 0.1, 0.2, 0.2],
+            score: 0.9,
+            embedding_hash: None,
+            person: person.map(String::from),
+            landmarks: None,
+            age_estimate: None,
+            cluster_id: cluster_id.map(String::from),
+        };
+        sidecar.faces = vec![
+            face(Some("c1"), None),
+            face(Some("c1"), None),
+            face(Some("c2"), Some("Alice")),
+            face(None, None),
+        ];
+        write_sidecar(&photo, &sidecar).unwrap();
+
+        let result = verify_path(temp_dir.path(), false, &VerifyOptions::default()).unwrap();
+        assert_eq!(result.ok, 1);
+        assert_eq!(result.warnings.len(), 1);
+        assert!(result.warnings[0].message.contains("Face cluster c1 has 2 faces"));
+        assert_eq!(result.warnings[0].suggested_action, SuggestedAction::AssignClusterName);
+        assert_eq!(
+            serde_json::to_string(&SuggestedAction::AssignClusterName).unwrap(),
+            "\"assign_cluster_name\""
+        );
+    }
+
+    #[test]
+    fn test_verify_check_privacy_flags_age_estimates() {
+        let temp_dir = TempDir::new().unwrap();
+        let photo = temp_dir.path().join("a.jpg");
+        fs::write(&photo, b"image").unwrap();
+        let mut sidecar = scan_file(&photo, true).unwrap().sidecar;
+        sidecar.faces = vec![crate::FaceDetection {
+            bbox: [0.1, 0.1, 0.2, 0.2],
+            score: 0.9,
+            embedding_hash: None,
+            person: None,
+            landmarks: None,
+            age_estimate: Some(27.5),
+            cluster_id: None,
+        }];
+        write_sidecar(&photo, &sidecar).unwrap();
+
+        let unchecked = verify_path(temp_dir.path(), false, &VerifyOptions::default()).unwrap();
+        assert_eq!(unchecked.sidecars[0].statuses, vec![VerifyStatus::Ok]);
+
+        let options = VerifyOptions { check_privacy: true, ..VerifyOptions::default() };
+        let result = verify_path(temp_dir.path(), false, &options).unwrap();
+        assert_eq!(
+            result.sidecars[0].statuses,
+            vec![VerifyStatus::PrivacySensitiveData { fields: vec!["faces[].age_estimate".to_string()] }]
+        );
+        // A warning: the sidecar still counts as ok
+        assert_eq!((result.ok, result.with_issues), (1, 0));
+    }
+
+    #[test]
+    fn test_verify_reference_signature() {
+        let temp_dir = TempDir::new().unwrap();
+        let photo = temp_dir.path().join("a.jpg");
+        fs::write(&photo, b"image").unwrap();
+        let sidecar = scan_file(&photo, false).unwrap().sidecar;
+
+        // A fresh scan matches the running binary
+        let current = VerifyOptions { reference_signature: Some(PipelineSignature::current()), ..VerifyOptions::default() };
+        assert_eq!(verify_path(temp_dir.path(), false, &current).unwrap().sidecars[0].statuses, vec![VerifyStatus::Ok]);
+
+        let reference = PipelineSignature {
+            schema_version: "2.0.0".to_string(),
+            hash_algorithm: "sha256".to_string(),
+            ..sidecar.pipeline_signature.clone()
+        };
+        let options = VerifyOptions { reference_signature: Some(reference.clone()), fix: true, ..VerifyOptions::default() };
+        let result = verify_path(temp_dir.path(), false, &options).unwrap();
+        assert_eq!(
+            result.sidecars[0].statuses,
+            vec![VerifyStatus::StaleSignature {
+                stored: Box::new(sidecar.pipeline_signature.clone()),
+                reference: Box::new(reference),
+                fields: vec![IncompatibleField::SchemaVersion, IncompatibleField::HashAlgorithm],
+            }]
+        );
+        assert_eq!(result.with_issues, 1);
+        assert_eq!(result.rescan_queue, vec![sidecar.source.file_path.clone()]);
+    }
+
+    #[test]
+    fn test_verify_fix_normalises_paths() {
+        let temp_dir = TempDir::new().unwrap();
+        let photo = temp_dir.path().join("a.jpg");
+        fs::write(&photo, b"image").unwrap();
+        let mut sidecar = scan_file(&photo, true).unwrap().sidecar;
+        let canonical = sidecar.source.file_path.clone();
+        sidecar.source.file_path = format!("{}/./sub/../a.jpg/", temp_dir.path().display());
+        write_sidecar(&photo, &sidecar).unwrap();
+
+        // Without --fix nothing is rewritten
+        let report_only = VerifyOptions { normalise_paths: true, ..VerifyOptions::default() };
+        assert_eq!(verify_path(temp_dir.path(), false, &report_only).unwrap().paths_normalised, 0);
+
+        let options = VerifyOptions { fix: true, normalise_paths: true, ..VerifyOptions::default() };
+        assert_eq!(verify_path(temp_dir.path(), false, &options).unwrap().paths_normalised, 1);
+        assert_eq!(crate::scan::read_sidecar(&photo).unwrap().source.file_path, canonical);
+        assert_eq!(verify_path(temp_dir.path(), false, &options).unwrap().paths_normalised, 0);
+    }
+
+    #[test]
+    fn test_verify_hashes_with_recorded_algorithm() {
+        use crate::scan::{scan_path, ScanConfig};
+
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("a.jpg"), b"image a").unwrap();
+        fs::write(temp_dir.path().join("b.jpg"), b"image b").unwrap();
+        scan_path(&temp_dir.path().join("a.jpg"), &ScanConfig::default(), None).unwrap();
+        let sha256 = ScanConfig { hash_algorithm: HashAlgorithm::Sha256, ..ScanConfig::default() };
+        scan_path(&temp_dir.path().join("b.jpg"), &sha256, None).unwrap();
+
+        let options = VerifyOptions { check_hashes: true, ..VerifyOptions::default() };
+        let result = verify_path(temp_dir.path(), false, &options).unwrap();
+        assert_eq!(result.ok, 2);
+        assert_eq!(result.warnings.len(), 1);
+        assert_eq!(result.warnings[0].suggested_action, SuggestedAction::Rescan);
+
+        fs::write(temp_dir.path().join("b.jpg"), b"edited").unwrap();
+        let result = verify_path(temp_dir.path(), false, &options).unwrap();
+        assert!(matches!(
+            &result.sidecars[1].statuses[0],
+            VerifyStatus::HashMismatch { algorithm: HashAlgorithm::Sha256, .. }
+        ));
+    }
+
+    #[test]
+    fn test_verify_runs_plugins() {
+        struct RequireGps;
+
+        impl ValidationPlugin for RequireGps {
+            fn name(&self) -> &str {
+                "require-gps"
+            }
+
+            fn validate(&self, sidecar: &Sidecar) -> Vec<ValidationWarning> {
+                if sidecar.image.as_ref().is_some_and(|i| i.gps_latitude.is_some()) {
+                    Vec::new()
+                } else {
+                    vec![ValidationWarning::new("photo has no GPS location")]
+                }
+            }
+        }
+
+        let temp_dir = TempDir::new().unwrap();
+        let photo = temp_dir.path().join("a.jpg");
+        fs::write(&photo, b"image").unwrap();
+        scan_file(&photo, false).unwrap();
+
+        let plugins: Vec<Box<dyn ValidationPlugin>> = vec![Box::new(RequireGps)];
+        let result = verify_path_with_plugins(temp_dir.path(), false, &VerifyOptions::default(), &plugins).unwrap();
+
+        // Plugin findings are warnings, not issues
+        assert_eq!(result.ok, 1);
+        let warnings = &result.sidecars[0].warnings;
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].source, ValidationSource::Plugin { name: "require-gps".to_string() });
+
+        let json = serde_json::to_value(&result.sidecars[0]).unwrap();
+        assert_eq!(json["warnings"][0]["source"]["type"], "plugin");
+    }
+
+    #[test]
+    fn test_verify_warns_about_mixed_sidecar_naming() {
+        use crate::scan::{scan_path, ScanConfig};
+
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("a.jpg"), b"image a").unwrap();
+        fs::write(temp_dir.path().join("b.jpg"), b"image b").unwrap();
+        scan_file(&temp_dir.path().join("a.jpg"), false).unwrap();
+
+        let config = ScanConfig { sidecar_suffix: ".jozin.json".to_string(), ..ScanConfig::default() };
+        scan_path(&temp_dir.path().join("b.jpg"), &config, None).unwrap();
+
+        let options = VerifyOptions { check_hashes: true, ..VerifyOptions::default() };
+        let result = verify_path(temp_dir.path(), false, &options).unwrap();
+        assert_eq!(result.total_sidecars, 1);
+        assert_eq!(result.ok, 1);
+        assert_eq!(result.warnings.len(), 1);
+        assert!(result.warnings[0].message.contains(".jozin.json"));
+    }
+
+    #[test]
+    fn test_verify_format_mismatch() {
+        let temp_dir = TempDir::new().unwrap();
+        let photo = temp_dir.path().join("a.jpg");
+        fs::write(&photo, PNG_1X1).unwrap();
+        let mut sidecar = scan_file(&photo, true).unwrap().sidecar;
+        sidecar.image = Some(crate::ImageInfo { format: Some("JPEG".to_string()), ..crate::ImageInfo::default() });
+        write_sidecar(&photo, &sidecar).unwrap();
+
+        let options = VerifyOptions { check_formats: true, ..VerifyOptions::default() };
+        let result = verify_path(temp_dir.path(), false, &options).unwrap();
+        assert_eq!(
+            result.sidecars[0].statuses,
+            vec![VerifyStatus::FormatMismatch { recorded: "JPEG".to_string(), detected: ImageFormat::Png }]
+        );
+    }
+
+    #[test]
+    fn test_verify_newer_schema_and_producer() {
+        let temp_dir = TempDir::new().unwrap();
+        let photo = temp_dir.path().join("a.jpg");
+        fs::write(&photo, b"image").unwrap();
+        let mut sidecar = scan_file(&photo, true).unwrap().sidecar;
+        sidecar.schema_version = "9.0.0".to_string();
+        sidecar.pipeline_signature.producer_version = "99.0.0".to_string();
+        write_sidecar(&photo, &sidecar).unwrap();
+
+        let result = verify_path(temp_dir.path(), false, &VerifyOptions::default()).unwrap();
+        let statuses = &result.sidecars[0].statuses;
+        assert_eq!(
+            statuses[0],
+            VerifyStatus::NewerSchema { sidecar_version: "9.0.0".to_string(), supported_version: CURRENT_SCHEMA_VERSION.to_string() }
+        );
+        assert!(matches!(&statuses[1], VerifyStatus::NewerProducer { producer_version, .. } if producer_version == "99.0.0"));
+        assert_eq!(statuses[0].suggested_action(), SuggestedAction::UpgradeJozin);
+        assert_eq!(result.ok, 1, "warnings only: still ok");
+
+        let strict = VerifyOptions { strict: true, ..VerifyOptions::default() };
+        assert_eq!(verify_path(temp_dir.path(), false, &strict).unwrap().with_issues, 1);
+
+        let allowed = VerifyOptions { allow_newer_schema: true, strict: true, ..VerifyOptions::default() };
+        let result = verify_path(temp_dir.path(), false, &allowed).unwrap();
+        assert_eq!(result.sidecars[0].statuses, vec![VerifyStatus::Ok]);
+    }
+
+    #[test]
+    fn test_verify_out_of_range_gps_is_warning() {
+        let temp_dir = TempDir::new().unwrap();
+        let photo = temp_dir.path().join("a.jpg");
+        fs::write(&photo, b"image").unwrap();
+        let mut sidecar = scan_file(&photo, true).unwrap().sidecar;
+        sidecar.image = Some(crate::ImageInfo {
+            gps_latitude: Some(91.5),
+            gps_longitude: Some(14.42),
+            ..crate::ImageInfo::default()
+        });
+        write_sidecar(&photo, &sidecar).unwrap();
+
+        let result = verify_path(temp_dir.path(), false, &VerifyOptions::default()).unwrap();
+        assert_eq!(result.ok, 1);
+        assert_eq!(result.sidecars[0].warnings.len(), 1);
+        assert_eq!(result.sidecars[0].warnings[0].source, ValidationSource::Builtin);
+        assert!(result.sidecars[0].warnings[0].message.contains("91.5"));
+    }
+
+    #[test]
+    fn test_verify_stale_summary_counts_is_warning() {
+        let temp_dir = TempDir::new().unwrap();
+        let photo = temp_dir.path().join("a.jpg");
+        fs::write(&photo, b"image").unwrap();
+        let mut sidecar = scan_file(&photo, true).unwrap().sidecar;
+        sidecar.image = Some(crate::ImageInfo::default());
+        sidecar.merge_tags(vec![crate::Tag { label: "beach".to_string(), score: None, source: crate::TagSource::User }], true);
+        assert_eq!(sidecar.image.as_ref().unwrap().tag_count, Some(1));
+        assert_eq!(sidecar.image.as_ref().unwrap().faces_count, Some(0));
+        write_sidecar(&photo, &sidecar).unwrap();
+
+        let result = verify_path(temp_dir.path(), false, &VerifyOptions::default()).unwrap();
+        assert!(result.sidecars[0].warnings.is_empty());
+
+        sidecar.tags.clear();
+        write_sidecar(&photo, &sidecar).unwrap();
+        let result = verify_path(temp_dir.path(), false, &VerifyOptions::default()).unwrap();
+        assert_eq!(result.sidecars[0].warnings.len(), 1);
+        assert!(result.sidecars[0].warnings[0].message.contains("tag_count is 1"));
+    }
+
+    #[test]
+    fn test_verify_fix_normalises_tag_labels() {
+        let temp_dir = TempDir::new().unwrap();
+        let photo = temp_dir.path().join("a.jpg");
+        fs::write(&photo, b"image").unwrap();
+        let mut sidecar = scan_file(&photo, true).unwrap().sidecar;
+        let user_tag = |label: &str| crate::Tag { label: label.to_string(), score: None, source: crate::TagSource::User };
+        sidecar.tags = vec![user_tag("cafe\u{301}"), user_tag("café"), user_tag(" Beach")];
+        write_sidecar(&photo, &sidecar).unwrap();
+
+        let result = verify_path(temp_dir.path(), false, &VerifyOptions::default()).unwrap();
+        assert_eq!(result.sidecars[0].warnings.len(), 2);
+        assert_eq!(result.ok, 1);
+        assert_eq!(result.labels_normalised, 0);
+
+        let options = VerifyOptions { fix: true, ..VerifyOptions::default() };
+        let result = verify_path(temp_dir.path(), false, &options).unwrap();
+        assert_eq!(result.labels_normalised, 1);
+        assert!(result.sidecars[0].warnings.is_empty());
+        let labels: Vec<String> = read_sidecar(&photo).unwrap().tags.into_iter().map(|t| t.label).collect();
+        assert_eq!(labels, ["café", "beach"]);
+    }
+
+    #[test]
+    fn test_verify_validate_encoding() {
+        let temp_dir = TempDir::new().unwrap();
+        let good = temp_dir.path().join("good.png");
+        let truncated = temp_dir.path().join("truncated.jpg");
+        fs::write(&good, PNG_1X1).unwrap();
+        fs::write(&truncated, [0xFF, 0xD8, 0xFF, 0xFE, 0x00, 0x04, b'h', b'i']).unwrap();
+        scan_file(&good, false).unwrap();
+        scan_file(&truncated, false).unwrap();
+
+        let options = VerifyOptions { validate_encoding: true, ..VerifyOptions::default() };
+        let result = verify_path(temp_dir.path(), false, &options).unwrap();
+        assert_eq!((result.ok, result.with_issues), (1, 1));
+        let status = result.sidecars.iter().flat_map(|s| &s.statuses).find(|s| matches!(s, VerifyStatus::InvalidEncoding { .. }));
+        assert!(matches!(status, Some(VerifyStatus::InvalidEncoding { format: ImageFormat::Jpeg, error }) if error.contains("end-of-image")));
+    }
+
+    #[test]
+    #[cfg(any(feature = "faces", feature = "thumbs"))]
+    fn test_verify_check_encoding_quarantines_corrupt_images() {
+        use crate::util::test_helpers::{create_synthetic_jpeg, SyntheticExif};
+        let temp_dir = TempDir::new().unwrap();
+        let library = temp_dir.path().join("library");
+        fs::create_dir(&library).unwrap();
+        let exif = || SyntheticExif { width: 64, height: 64, ..SyntheticExif::default() };
+        let good = create_synthetic_jpeg(&library, "good.jpg", exif());
+        let bad = create_synthetic_jpeg(&library, "bad.jpg", exif());
+        scan_file(&good, false).unwrap();
+        scan_file(&bad, false).unwrap();
+        // Valid headers, a broken Huffman table marker inside the entropy-coded data
+        let mut jpeg = fs::read(&bad).unwrap();
+        let sos = jpeg.windows(2).position(|w| w == [0xFF, 0xDA]).unwrap();
+        let data_start = sos + 2 + usize::from(u16::from_be_bytes([jpeg[sos + 2], jpeg[sos + 3]]));
+        jpeg[data_start + 5..data_start + 7].copy_from_slice(&[0xFF, 0xC4]);
+        fs::write(&bad, jpeg).unwrap();
+
+        let options = VerifyOptions { check_encoding: true, ..VerifyOptions::default() };
+        let result = verify_path(&library, false, &options).unwrap();
+        assert_eq!((result.ok, result.with_issues), (1, 1));
+        let status = result.sidecars.iter().flat_map(|s| &s.statuses).find(|s| matches!(s, VerifyStatus::CorruptImageData { .. }));
+        assert_eq!(status.unwrap().suggested_action(), SuggestedAction::Quarantine);
+        assert!(result.quarantined.is_empty() && bad.exists());
+
+        let quarantine_dir = temp_dir.path().join("quarantine");
+        let options = VerifyOptions {
+            check_encoding: true,
+            fix: true,
+            quarantine_corrupt: true,
+            quarantine_dir: Some(quarantine_dir.clone()),
+            ..VerifyOptions::default()
+        };
+        let result = verify_path(&library, false, &options).unwrap();
+        assert_eq!(result.quarantined.len(), 1);
+        assert!(!bad.exists() && !library.join("bad.jpg.json").exists());
+        assert!(quarantine_dir.join("bad.jpg").exists() && quarantine_dir.join("bad.jpg.json").exists());
+        let index = Quarantine::new(&quarantine_dir).entries().unwrap();
+        assert_eq!(index[0].original_path, bad);
+        assert!(good.exists());
+    }
+
+    #[test]
+    #[cfg(not(any(feature = "faces", feature = "thumbs")))]
+    fn test_verify_check_encoding_needs_image_feature() {
+        let temp_dir = TempDir::new().unwrap();
+        let options = VerifyOptions { check_encoding: true, ..VerifyOptions::default() };
+        let err = verify_path(temp_dir.path(), false, &options).unwrap_err();
+        assert!(matches!(err, crate::JozinError::NotSupported { .. }));
+    }
+}
@@ -0,0 +1,71 @@
+//! End-to-end checks on a generated 500-photo library
+//!
+//! Requires the `test-helpers` feature:
+//!
+//! ```text
+//! cargo test -p jozin-core --features test-helpers --test corpus
+//! ```
+
+use jozin_core::scan::{load_sidecars, scan_path, ScanConfig};
+use jozin_core::stats::LibraryStats;
+use jozin_core::util::test_corpus::{generate_test_corpus, CorpusConfig};
+use jozin_core::verify::{verify_path, VerifyOptions};
+use std::time::{Duration, Instant};
+use tempfile::TempDir;
+
+const FILE_COUNT: usize = 500;
+
+/// Generation, scan, verify and search together.
+const TIME_LIMIT: Duration = Duration::from_secs(30);
+
+#[test]
+fn test_corpus_scan_verify_search() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    let start = Instant::now();
+
+    let config = CorpusConfig {
+        file_count: FILE_COUNT,
+        formats: vec!["jpg", "png"],
+        ..CorpusConfig::default()
+    };
+    let photos = generate_test_corpus(root, config).unwrap();
+    assert_eq!(photos.len(), FILE_COUNT);
+
+    // Scan (dry run: a rescan would replace the synthetic faces)
+    let scan_config = ScanConfig {
+        recursive: true,
+        dry_run: true,
+        ..ScanConfig::default()
+    };
+    let scan = scan_path(root, &scan_config, None).unwrap();
+    // Sidecars are walked too, and skipped with a reason
+    assert_eq!(scan.scanned_files.iter().filter(|file| file.error.is_none()).count(), FILE_COUNT);
+    assert_eq!(scan.failed, 0);
+
+    let verify = verify_path(root, true, &VerifyOptions::default()).unwrap();
+    assert_eq!(verify.total_sidecars, FILE_COUNT);
+    assert_eq!(verify.with_issues, 0);
+
+    let stats = LibraryStats::collect(root, true).unwrap();
+    assert_eq!(stats.total_files, FILE_COUNT as u64);
+    assert_eq!(stats.geotagged, FILE_COUNT as u64 / 2);
+    assert_eq!(stats.cameras.len(), 3);
+    assert_eq!(stats.cameras["Canon EOS R5"], 167);
+    assert!(stats.faces >= 150);
+
+    // Search: Nikon photos from 2022 with faces
+    let sidecars = load_sidecars(root, true).unwrap();
+    let hits = sidecars
+        .iter()
+        .filter(|sidecar| {
+            sidecar.image.as_ref().is_some_and(|image| {
+                image.camera_make.as_deref() == Some("Nikon")
+                    && image.datetime_original.as_deref().is_some_and(|date| date.starts_with("2022"))
+            }) && !sidecar.faces.is_empty()
+        })
+        .count();
+    assert!(hits > 0 && hits < FILE_COUNT / 3);
+
+    assert!(start.elapsed() < TIME_LIMIT, "corpus round trip took {:?}", start.elapsed());
+}
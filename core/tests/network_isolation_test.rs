@@ -0,0 +1,69 @@
+//! Jožin makes no network connections
+//!
+//! Blocks outbound network access for this whole test binary with a seccomp
+//! filter (see `util::network_check`) and runs every mutating pipeline over
+//! a small library. Any `connect`/`sendto`/`sendmsg` kills the process with
+//! `SIGSYS`, failing the run, so a dependency that starts phoning home is
+//! caught here. Runs with `cargo test --workspace`.
+//!
+//! Linux (x86_64, aarch64) only: macOS can deny network access but not
+//! kill on it, and other platforms have no mechanism.
+
+#![cfg(all(target_os = "linux", any(target_arch = "x86_64", target_arch = "aarch64")))]
+
+use jozin_core::migrate::{migrate_path, MigrateOptions, SourcePreference};
+use jozin_core::util::network_check::{deny_outbound_network, NetworkViolation};
+use jozin_core::verify::{verify_path, VerifyOptions};
+use jozin_core::{cleanup_path, scan_path, CleanupOptions, ScanConfig};
+use std::os::unix::process::ExitStatusExt;
+use std::process::{Command, Stdio};
+use std::sync::Once;
+use tempfile::TempDir;
+
+static ISOLATE: Once = Once::new();
+
+/// Blocks the network for the whole process (once; it cannot be undone).
+fn isolate() {
+    ISOLATE.call_once(|| deny_outbound_network(NetworkViolation::Kill).unwrap());
+}
+
+#[test]
+fn test_pipelines_make_no_network_connections() {
+    isolate();
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    std::fs::create_dir(root.join("2024")).unwrap();
+    for name in ["a.jpg", "b.png", "2024/c.jpg"] {
+        std::fs::write(root.join(name), format!("image bytes of {}", name)).unwrap();
+    }
+
+    let config = ScanConfig { recursive: true, ..ScanConfig::default() };
+    assert_eq!(scan_path(root, &config, None).unwrap().total_files, 3);
+    // Rescanning reads the existing sidecars
+    scan_path(root, &config, None).unwrap();
+
+    let options = VerifyOptions { check_hashes: true, check_formats: true, ..VerifyOptions::default() };
+    assert_eq!(verify_path(root, true, &options).unwrap().total_sidecars, 3);
+    migrate_path(root, "2.0.0", true, SourcePreference::Backup1, &MigrateOptions::default()).unwrap();
+    assert!(cleanup_path(root, true, CleanupOptions::all(), false, None).unwrap().total_files >= 3);
+}
+
+/// Run by [`test_network_attempts_kill_the_process`] in a child process.
+#[test]
+#[ignore = "connects on purpose; run in a child process by test_network_attempts_kill_the_process"]
+fn network_probe() {
+    isolate();
+    let result = std::net::TcpStream::connect("127.0.0.1:9");
+    panic!("connect was not blocked: {:?}", result);
+}
+
+#[test]
+fn test_network_attempts_kill_the_process() {
+    let status = Command::new(std::env::current_exe().unwrap())
+        .args(["network_probe", "--exact", "--ignored", "--test-threads", "1"])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .unwrap();
+    assert_eq!(status.signal(), Some(libc::SIGSYS));
+}
@@ -0,0 +1,44 @@
+//! Example validation plugin for `jozin verify --plugin`
+//!
+//! Rules:
+//! - Every photo must have a GPS location
+//! - Every photo tagged `portfolio` must contain at least one face
+//!
+//! A plugin exports `jozin_plugin_init`, which returns a thin pointer to a
+//! boxed [`ValidationPlugin`] (see `jozin_core::verify::PluginInitFn`).
+
+use jozin_core::verify::{ValidationPlugin, ValidationWarning};
+use jozin_core::Sidecar;
+
+struct CustomValidator;
+
+impl ValidationPlugin for CustomValidator {
+    fn name(&self) -> &str {
+        "custom-validator"
+    }
+
+    fn validate(&self, sidecar: &Sidecar) -> Vec<ValidationWarning> {
+        let mut warnings = Vec::new();
+
+        let has_gps = sidecar
+            .image
+            .as_ref()
+            .is_some_and(|image| image.gps_latitude.is_some() && image.gps_longitude.is_some());
+        if !has_gps {
+            warnings.push(ValidationWarning::new("photo has no GPS location"));
+        }
+
+        let is_portfolio = sidecar.tags.iter().any(|tag| tag.label == "portfolio");
+        if is_portfolio && sidecar.faces.is_empty() {
+            warnings.push(ValidationWarning::new("portfolio photo has no detected faces"));
+        }
+
+        warnings
+    }
+}
+
+/// Entry point called by `PluginLoader::load`.
+#[no_mangle]
+pub extern "C" fn jozin_plugin_init() -> *mut Box<dyn ValidationPlugin> {
+    Box::into_raw(Box::new(Box::new(CustomValidator)))
+}